@@ -81,10 +81,17 @@ pub fn generate_archive_files(jormungandr_database: &Path, output_dir: &Path) ->
                     });
 
                 let choice = match certificate.payload() {
-                    chain_impl_mockchain::vote::Payload::Public { choice } => choice.as_byte(),
-                    chain_impl_mockchain::vote::Payload::Private { .. } => {
+                    chain_impl_mockchain::vote::Payload::Public { choice }
+                    | chain_impl_mockchain::vote::Payload::Quadratic { choice, .. } => {
+                        choice.as_byte()
+                    }
+                    chain_impl_mockchain::vote::Payload::Private { .. }
+                    | chain_impl_mockchain::vote::Payload::RankedChoice { .. }
+                    | chain_impl_mockchain::vote::Payload::Approval { .. }
+                    | chain_impl_mockchain::vote::Payload::PrivateQuadratic { .. } => {
                         // zeroing data to enable private voting support
                         // (at least everying exception choice, since it is disabled by desing in private vote)
+                        // the multiple choices ballots do not fit in a single choice either
                         0u8
                     }
                 };
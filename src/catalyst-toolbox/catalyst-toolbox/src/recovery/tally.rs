@@ -765,8 +765,11 @@ mod test {
                         assert_eq!(*results.get(1).unwrap(), funds.into());
                         assert_eq!(*results.get(2).unwrap(), 0.into());
                     }
-                    Tally::Private { .. } => {
-                        unimplemented!("Private tally testing is not implemented")
+                    Tally::Private { .. }
+                    | Tally::RankedChoice { .. }
+                    | Tally::Approval { .. }
+                    | Tally::Quadratic { .. } => {
+                        unimplemented!("Only public tally testing is implemented")
                     }
                 }
             }
@@ -203,8 +203,12 @@ fn extract_yes_no_votes(proposal: &Proposal, voteplan: &VoteProposalStatus) -> R
         .ok_or(eyre!("missing `{NO}` field"))?;
 
     let tally = match &voteplan.tally {
-        Tally::Public { result } => result,
+        // the quadratic tallies count the weight of the ballots like the public ones
+        Tally::Public { result } | Tally::Quadratic { result } => result,
         Tally::Private { .. } => bail!("private vote tally"),
+        Tally::RankedChoice { .. } | Tally::Approval { .. } => {
+            bail!("multiple choices vote tally")
+        }
     };
 
     let yes_result = tally.results()[*yes_index as usize];
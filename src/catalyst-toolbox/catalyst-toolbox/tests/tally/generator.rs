@@ -199,7 +199,7 @@ impl VoteRoundGenerator {
                 let vote_end = manager.plan().vote_end();
 
                 match manager.plan().payload_type() {
-                    PayloadType::Private => {
+                    PayloadType::Private | PayloadType::PrivateQuadratic => {
                         let mut results = Vec::new();
                         let mut shares = Vec::new();
                         for proposal in manager.statuses().proposals {
@@ -228,6 +228,7 @@ impl VoteRoundGenerator {
                                 .zip(shares.into_iter())
                                 .map(|(tally_result, decrypt_shares)| {
                                     DecryptedPrivateTallyProposal {
+                                        member_indices: (0..decrypt_shares.len() as u8).collect(),
                                         decrypt_shares,
                                         tally_result,
                                     }
@@ -256,7 +257,10 @@ impl VoteRoundGenerator {
 
                         (id, manager)
                     }
-                    PayloadType::Public => {
+                    PayloadType::Public
+                    | PayloadType::RankedChoice
+                    | PayloadType::Approval
+                    | PayloadType::Quadratic => {
                         let manager = manager
                             .public_tally(
                                 vote_end,
@@ -235,6 +235,7 @@ fn tally_benchmark(
         .into_iter()
         .zip(decrypt_shares.into_iter())
         .map(|(tally, decrypt_shares)| DecryptedPrivateTallyProposal {
+            member_indices: (0..decrypt_shares.len() as u8).collect(),
             decrypt_shares: decrypt_shares.into_boxed_slice(),
             tally_result: tally.votes.into_boxed_slice(),
         })
@@ -1,6 +1,7 @@
 use super::Block;
 use crate::fragment::Contents;
 use crate::header::{BlockVersion, Header, HeaderBuilderNew};
+use std::future::Future;
 
 /// Create a block from a block version, content and a header builder closure
 ///
@@ -12,3 +13,21 @@ where
     hdr_builder(HeaderBuilderNew::new(version, &contents))
         .map(|header| Block::new_unchecked(header, contents))
 }
+
+/// Create a block from a block version, content and an asynchronous header builder
+/// closure, for when the header signature is obtained asynchronously
+///
+/// If the header builder returns an error, it is returned as is
+pub async fn builder_async<E, F, Fut>(
+    version: BlockVersion,
+    contents: Contents,
+    hdr_builder: F,
+) -> Result<Block, E>
+where
+    F: FnOnce(HeaderBuilderNew) -> Fut,
+    Fut: Future<Output = Result<Header, E>>,
+{
+    hdr_builder(HeaderBuilderNew::new(version, &contents))
+        .await
+        .map(|header| Block::new_unchecked(header, contents))
+}
@@ -20,7 +20,7 @@ pub use crate::header::{
     BftProof, BftSignature, Common, GenesisPraosProof, Header, HeaderId, KesSignature, Proof,
 };
 
-pub use builder::builder;
+pub use builder::{builder, builder_async};
 
 pub use crate::header::{BlockVersion, ChainLength};
 
@@ -1,6 +1,7 @@
 mod delegation;
 mod evm_mapping;
 mod mint_token;
+mod multisig_registration;
 mod pool;
 mod update_proposal;
 mod update_vote;
@@ -25,6 +26,7 @@ pub use self::vote_tally::{
 pub use delegation::{OwnerStakeDelegation, StakeDelegation};
 pub use evm_mapping::EvmMapping;
 pub use mint_token::MintToken;
+pub use multisig_registration::MultisigRegistration;
 pub use pool::{
     GenesisPraosLeaderHash, IndexSignatures, ManagementThreshold, PoolId, PoolOwnersSigned,
     PoolPermissions, PoolRegistration, PoolRegistrationHash, PoolRetirement, PoolSignature,
@@ -46,6 +48,7 @@ pub enum CertificateSlice<'a> {
     UpdateVote(PayloadSlice<'a, UpdateVote>),
     MintToken(PayloadSlice<'a, MintToken>),
     EvmMapping(PayloadSlice<'a, EvmMapping>),
+    MultisigRegistration(PayloadSlice<'a, MultisigRegistration>),
 }
 
 impl<'a> From<PayloadSlice<'a, StakeDelegation>> for CertificateSlice<'a> {
@@ -119,6 +122,12 @@ impl<'a> From<PayloadSlice<'a, EvmMapping>> for CertificateSlice<'a> {
     }
 }
 
+impl<'a> From<PayloadSlice<'a, MultisigRegistration>> for CertificateSlice<'a> {
+    fn from(payload: PayloadSlice<'a, MultisigRegistration>) -> CertificateSlice<'a> {
+        CertificateSlice::MultisigRegistration(payload)
+    }
+}
+
 impl<'a> CertificateSlice<'a> {
     pub fn into_owned(self) -> Certificate {
         match self {
@@ -138,6 +147,9 @@ impl<'a> CertificateSlice<'a> {
             CertificateSlice::UpdateVote(c) => Certificate::UpdateVote(c.into_payload()),
             CertificateSlice::MintToken(c) => Certificate::MintToken(c.into_payload()),
             CertificateSlice::EvmMapping(c) => Certificate::EvmMapping(c.into_payload()),
+            CertificateSlice::MultisigRegistration(c) => {
+                Certificate::MultisigRegistration(c.into_payload())
+            }
         }
     }
 }
@@ -156,6 +168,7 @@ pub enum CertificatePayload {
     UpdateVote(PayloadData<UpdateVote>),
     MintToken(PayloadData<MintToken>),
     EvmMapping(PayloadData<EvmMapping>),
+    MultisigRegistration(PayloadData<MultisigRegistration>),
 }
 
 impl CertificatePayload {
@@ -173,6 +186,7 @@ impl CertificatePayload {
             CertificatePayload::UpdateVote(payload) => payload.borrow().into(),
             CertificatePayload::MintToken(payload) => payload.borrow().into(),
             CertificatePayload::EvmMapping(payload) => payload.borrow().into(),
+            CertificatePayload::MultisigRegistration(payload) => payload.borrow().into(),
         }
     }
 }
@@ -212,6 +226,9 @@ impl<'a> From<&'a Certificate> for CertificatePayload {
             Certificate::EvmMapping(payload) => {
                 CertificatePayload::EvmMapping(payload.payload_data())
             }
+            Certificate::MultisigRegistration(payload) => {
+                CertificatePayload::MultisigRegistration(payload.payload_data())
+            }
         }
     }
 }
@@ -231,6 +248,7 @@ pub enum Certificate {
     UpdateVote(UpdateVote),
     MintToken(MintToken),
     EvmMapping(EvmMapping),
+    MultisigRegistration(MultisigRegistration),
 }
 
 impl From<StakeDelegation> for Certificate {
@@ -305,6 +323,12 @@ impl From<EvmMapping> for Certificate {
     }
 }
 
+impl From<MultisigRegistration> for Certificate {
+    fn from(multisig_registration: MultisigRegistration) -> Self {
+        Self::MultisigRegistration(multisig_registration)
+    }
+}
+
 impl Certificate {
     pub fn need_auth(&self) -> bool {
         match self {
@@ -320,6 +344,7 @@ impl Certificate {
             Certificate::UpdateVote(_) => <UpdateVote as Payload>::HAS_AUTH,
             Certificate::MintToken(_) => <MintToken as Payload>::HAS_AUTH,
             Certificate::EvmMapping(_) => <EvmMapping as Payload>::HAS_AUTH,
+            Certificate::MultisigRegistration(_) => <MultisigRegistration as Payload>::HAS_AUTH,
         }
     }
 }
@@ -363,6 +388,7 @@ mod tests {
             Certificate::UpdateVote(_) => true,
             Certificate::MintToken(_) => false,
             Certificate::EvmMapping(_) => true,
+            Certificate::MultisigRegistration(_) => false,
         };
         TestResult::from_bool(certificate.need_auth() == expected_result)
     }
@@ -0,0 +1,90 @@
+use crate::{
+    certificate::CertificateSlice,
+    multisig::{Declaration, Identifier},
+    transaction::{Payload, PayloadAuthData, PayloadData, PayloadSlice},
+};
+use chain_core::{
+    packer::Codec,
+    property::{DeserializeFromSlice, ReadError, Serialize, WriteError},
+};
+use typed_bytes::{ByteArray, ByteBuilder};
+
+/// Register a multisig declaration on chain.
+///
+/// Once registered, funds can be sent to the multisig account through
+/// its identifier and spent by gathering a threshold of the owners' signatures.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultisigRegistration {
+    declaration: Declaration,
+}
+
+impl MultisigRegistration {
+    pub fn new(declaration: Declaration) -> Self {
+        Self { declaration }
+    }
+
+    pub fn declaration(&self) -> &Declaration {
+        &self.declaration
+    }
+
+    /// identifier of the multisig account created by this registration
+    pub fn identifier(&self) -> Identifier {
+        self.declaration.to_identifier()
+    }
+
+    pub fn serialize_in(&self, bb: ByteBuilder<Self>) -> ByteBuilder<Self> {
+        let bytes = self
+            .declaration
+            .serialize_as_vec()
+            .expect("memory serialize is expected to just work");
+        bb.bytes(&bytes)
+    }
+
+    pub fn serialize(&self) -> ByteArray<Self> {
+        self.serialize_in(ByteBuilder::new()).finalize()
+    }
+}
+
+/* Auth/Payload ************************************************************* */
+
+impl Payload for MultisigRegistration {
+    const HAS_DATA: bool = true;
+    const HAS_AUTH: bool = false;
+    type Auth = ();
+
+    fn payload_data(&self) -> PayloadData<Self> {
+        PayloadData(
+            self.serialize_in(ByteBuilder::new())
+                .finalize_as_vec()
+                .into(),
+            std::marker::PhantomData,
+        )
+    }
+
+    fn payload_auth_data(_: &Self::Auth) -> PayloadAuthData<Self> {
+        PayloadAuthData(Vec::with_capacity(0).into(), std::marker::PhantomData)
+    }
+
+    fn payload_to_certificate_slice(p: PayloadSlice<'_, Self>) -> Option<CertificateSlice<'_>> {
+        Some(CertificateSlice::from(p))
+    }
+}
+
+/* Ser/De ******************************************************************* */
+
+impl Serialize for MultisigRegistration {
+    fn serialized_size(&self) -> usize {
+        self.declaration.serialized_size()
+    }
+
+    fn serialize<W: std::io::Write>(&self, codec: &mut Codec<W>) -> Result<(), WriteError> {
+        self.declaration.serialize(codec)
+    }
+}
+
+impl DeserializeFromSlice for MultisigRegistration {
+    fn deserialize_from_slice(codec: &mut Codec<&[u8]>) -> Result<Self, ReadError> {
+        let declaration = Declaration::deserialize_from_slice(codec)?;
+        Ok(Self::new(declaration))
+    }
+}
@@ -218,7 +218,7 @@ impl Arbitrary for VotePlan {
 
         let voting_token = TokenIdentifier::arbitrary(g);
 
-        Self::new(
+        let vote_plan = Self::new(
             vote_start,
             vote_end,
             committee_end,
@@ -226,7 +226,13 @@ impl Arbitrary for VotePlan {
             payload_type,
             keys,
             voting_token,
-        )
+        );
+        if payload_type.tally_type() == vote::PayloadType::Private && bool::arbitrary(g) {
+            let threshold = g.next_u32() % keys_n + 1;
+            vote_plan.with_committee_threshold(threshold as u8)
+        } else {
+            vote_plan
+        }
     }
 }
 
@@ -257,6 +263,9 @@ fn arbitrary_decrypted_private_tally<G: Gen>(g: &mut G) -> DecryptedPrivateTally
     let committee_size = (g.next_u32() % 2 + 1) as usize; // very time consuming
     let committee_manager =
         CommitteeMembersManager::new(&mut rng, &crs_seed, committee_size, committee_size);
+    // shares that are not those of the first committee members are encoded
+    // with their member indices
+    let first_member_index = u8::arbitrary(g) % 3;
 
     for _ in 0..proposals_n {
         let n_options = NonZeroU8::arbitrary(g);
@@ -280,6 +289,9 @@ fn arbitrary_decrypted_private_tally<G: Gen>(g: &mut G) -> DecryptedPrivateTally
                 .map(|_| u64::arbitrary(g))
                 .collect::<Box<[_]>>(),
             decrypt_shares: decrypte_shares.into_boxed_slice(),
+            member_indices: (0..committee_size as u8)
+                .map(|index| index + first_member_index)
+                .collect(),
         });
     }
     DecryptedPrivateTally::new(inner).unwrap()
@@ -308,9 +320,15 @@ impl Arbitrary for TallyProof {
     }
 }
 
+impl Arbitrary for MultisigRegistration {
+    fn arbitrary<G: Gen>(g: &mut G) -> Self {
+        MultisigRegistration::new(Arbitrary::arbitrary(g))
+    }
+}
+
 impl Arbitrary for Certificate {
     fn arbitrary<G: Gen>(g: &mut G) -> Self {
-        let option = u8::arbitrary(g) % 11;
+        let option = u8::arbitrary(g) % 12;
         match option {
             0 => Certificate::StakeDelegation(Arbitrary::arbitrary(g)),
             1 => Certificate::OwnerStakeDelegation(Arbitrary::arbitrary(g)),
@@ -323,11 +341,20 @@ impl Arbitrary for Certificate {
             8 => Certificate::UpdateProposal(Arbitrary::arbitrary(g)),
             9 => Certificate::UpdateVote(Arbitrary::arbitrary(g)),
             10 => Certificate::MintToken(Arbitrary::arbitrary(g)),
+            11 => Certificate::MultisigRegistration(Arbitrary::arbitrary(g)),
             _ => panic!("unimplemented"),
         }
     }
 }
 
+#[quickcheck]
+fn multisig_registration_serialization_bijection(b: MultisigRegistration) -> TestResult {
+    let b_got = b.serialize();
+    let result =
+        MultisigRegistration::deserialize_from_slice(&mut Codec::new(b_got.as_ref())).unwrap();
+    TestResult::from_bool(b == result)
+}
+
 #[quickcheck]
 fn pool_reg_serialization_bijection(b: PoolRegistration) -> TestResult {
     let b_got = b.serialize();
@@ -29,6 +29,11 @@ pub type ExternalProposalId = DigestOf<Blake2b256, ExternalProposalDocument>;
 /// the vote plan identifier on the blockchain
 pub type VotePlanId = DigestOf<Blake2b256, VotePlan>;
 
+/// set on the payload type byte of the vote plans carrying a committee
+/// threshold, which is then appended after the voting token. Vote plans
+/// without a threshold keep the original layout, and so their identifier.
+const COMMITTEE_THRESHOLD_FLAG: u8 = 0x80;
+
 /// a vote plan for the voting system
 ///
 /// A vote plan defines what is being voted, for how long and how long
@@ -50,6 +55,10 @@ pub struct VotePlan {
     payload_type: vote::PayloadType,
     /// encrypting votes public keys
     committee_public_keys: Vec<chain_vote::MemberPublicKey>,
+    /// number of committee members needed to decrypt the tally, if the
+    /// committee keys were generated with the distributed key generation.
+    /// All the members are needed otherwise.
+    committee_threshold: Option<u8>,
     /// voting token used for weigthing the votes for any proposal in this voteplan
     voting_token: TokenIdentifier,
 }
@@ -150,7 +159,7 @@ impl Proposal {
 }
 
 impl VoteAction {
-    fn serialize_in(&self, bb: ByteBuilder<VotePlan>) -> ByteBuilder<VotePlan> {
+    pub(crate) fn serialize_in(&self, bb: ByteBuilder<VotePlan>) -> ByteBuilder<VotePlan> {
         match self {
             Self::OffChain => bb.u8(0),
             Self::Treasury { action } => bb.u8(1).sub(|bb| action.serialize_in(bb)),
@@ -207,10 +216,20 @@ impl VotePlan {
             proposals,
             payload_type,
             committee_public_keys,
+            committee_threshold: None,
             voting_token,
         }
     }
 
+    /// Set the number of committee members needed to decrypt the tally, for
+    /// committee keys generated with the distributed key generation
+    pub fn with_committee_threshold(self, committee_threshold: u8) -> Self {
+        Self {
+            committee_threshold: Some(committee_threshold),
+            ..self
+        }
+    }
+
     pub fn check_governance(&self, governance: &Governance) -> bool {
         self.proposals()
             .iter()
@@ -259,6 +278,21 @@ impl VotePlan {
         &self.committee_public_keys
     }
 
+    pub fn committee_threshold(&self) -> Option<u8> {
+        self.committee_threshold
+    }
+
+    /// The key the votes of a private vote plan are encrypted to
+    pub fn election_public_key(&self) -> chain_vote::ElectionPublicKey {
+        match self.committee_threshold {
+            Some(threshold) => chain_vote::ElectionPublicKey::from_threshold_participants(
+                &self.committee_public_keys,
+                threshold as usize,
+            ),
+            None => chain_vote::ElectionPublicKey::from_participants(&self.committee_public_keys),
+        }
+    }
+
     pub fn voting_token(&self) -> &TokenIdentifier {
         &self.voting_token
     }
@@ -303,20 +337,29 @@ impl VotePlan {
     }
 
     pub fn serialize_in(&self, bb: ByteBuilder<Self>) -> ByteBuilder<Self> {
-        bb.u32(self.vote_start.epoch)
+        let payload_type = match self.committee_threshold {
+            Some(_) => self.payload_type as u8 | COMMITTEE_THRESHOLD_FLAG,
+            None => self.payload_type as u8,
+        };
+        let bb = bb
+            .u32(self.vote_start.epoch)
             .u32(self.vote_start.slot_id)
             .u32(self.vote_end.epoch)
             .u32(self.vote_end.slot_id)
             .u32(self.committee_end.epoch)
             .u32(self.committee_end.slot_id)
-            .u8(self.payload_type as u8)
+            .u8(payload_type)
             .iter8(&mut self.proposals.iter(), |bb, proposal| {
                 proposal.serialize_in(bb)
             })
             .iter8(self.committee_public_keys.iter(), |bb, key| {
                 bb.bytes(key.to_bytes().as_ref())
             })
-            .bytes(self.voting_token.bytes().as_ref())
+            .bytes(self.voting_token.bytes().as_ref());
+        match self.committee_threshold {
+            Some(threshold) => bb.u8(threshold),
+            None => bb,
+        }
     }
 
     pub fn serialize(&self) -> ByteArray<Self> {
@@ -432,8 +475,9 @@ impl DeserializeFromSlice for VotePlan {
             slot_id: codec.get_be_u32()?,
         };
 
-        let payload_type = codec
-            .get_u8()?
+        let payload_type = codec.get_u8()?;
+        let has_committee_threshold = payload_type & COMMITTEE_THRESHOLD_FLAG != 0;
+        let payload_type = (payload_type & !COMMITTEE_THRESHOLD_FLAG)
             .try_into()
             .map_err(|e: vote::TryFromIntError| ReadError::StructureInvalid(e.to_string()))?;
 
@@ -469,6 +513,20 @@ impl DeserializeFromSlice for VotePlan {
 
         let voting_token = TokenIdentifier::deserialize(codec)?;
 
+        let committee_threshold = if has_committee_threshold {
+            match codec.get_u8()? {
+                threshold if threshold == 0 || threshold > member_keys_len => {
+                    return Err(ReadError::StructureInvalid(format!(
+                        "committee threshold {} is not between 1 and the number of committee members {}",
+                        threshold, member_keys_len
+                    )))
+                }
+                threshold => Some(threshold),
+            }
+        } else {
+            None
+        };
+
         Ok(Self {
             vote_start,
             vote_end,
@@ -476,6 +534,7 @@ impl DeserializeFromSlice for VotePlan {
             proposals,
             payload_type,
             committee_public_keys,
+            committee_threshold,
             voting_token,
         })
     }
@@ -485,7 +544,7 @@ impl DeserializeFromSlice for VotePlan {
 mod tests {
     use super::*;
     use crate::block::BlockDate;
-    use crate::testing::VoteTestGen;
+    use crate::testing::{data::CommitteeMembersManager, VoteTestGen};
     use crate::tokens::name::{TokenName, TOKEN_NAME_MAX_SIZE};
     use crate::tokens::policy_hash::{PolicyHash, POLICY_HASH_SIZE};
     use chain_core::property::BlockDate as BlockDateProp;
@@ -502,6 +561,73 @@ mod tests {
         decoded == vote_plan
     }
 
+    fn vote_plan_with_keys(committee_public_keys: Vec<MemberPublicKey>) -> VotePlan {
+        VotePlan::new(
+            BlockDate::from_epoch_slot_id(1, 0),
+            BlockDate::from_epoch_slot_id(2, 0),
+            BlockDate::from_epoch_slot_id(3, 0),
+            Proposals::new(),
+            vote::PayloadType::Private,
+            committee_public_keys,
+            TokenIdentifier {
+                policy_hash: PolicyHash::from([0u8; POLICY_HASH_SIZE]),
+                token_name: TokenName::try_from(Vec::new()).unwrap(),
+            },
+        )
+    }
+
+    #[test]
+    pub fn encoding_without_committee_threshold_is_unchanged() {
+        let vote_plan = vote_plan_with_keys(Vec::new());
+
+        let mut expected = vec![
+            0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 3, 0, 0, 0, 0,
+        ];
+        // payload type, no proposals and no committee keys
+        expected.extend([2, 0, 0]);
+        expected.extend([0u8; POLICY_HASH_SIZE]);
+        // empty token name
+        expected.push(0);
+
+        assert_eq!(vote_plan.serialize().as_slice(), expected.as_slice());
+    }
+
+    #[test]
+    pub fn committee_threshold_is_a_flagged_trailing_extension() {
+        let members = CommitteeMembersManager::new(&mut rand::thread_rng(), &[], 1, 3);
+        let keys: Vec<_> = members.members().iter().map(|m| m.public_key()).collect();
+        let vote_plan = vote_plan_with_keys(keys.clone());
+        let threshold_vote_plan = vote_plan.clone().with_committee_threshold(2);
+
+        let legacy = vote_plan.serialize().as_slice().to_vec();
+        let extended = threshold_vote_plan.serialize().as_slice().to_vec();
+        let payload_type_position = 24;
+        assert_eq!(
+            extended[payload_type_position],
+            legacy[payload_type_position] | COMMITTEE_THRESHOLD_FLAG
+        );
+        assert_eq!(
+            extended[..payload_type_position],
+            legacy[..payload_type_position]
+        );
+        assert_eq!(
+            extended[payload_type_position + 1..legacy.len()],
+            legacy[payload_type_position + 1..]
+        );
+        assert_eq!(extended[legacy.len()..], [2]);
+        assert_ne!(vote_plan.to_id(), threshold_vote_plan.to_id());
+
+        let decoded = VotePlan::deserialize_from_slice(&mut Codec::new(extended.as_slice()))
+            .expect("can decode a vote plan with a committee threshold");
+        assert_eq!(decoded, threshold_vote_plan);
+
+        for invalid_threshold in [0, keys.len() as u8 + 1] {
+            let mut invalid = extended.clone();
+            *invalid.last_mut().unwrap() = invalid_threshold;
+            assert!(VotePlan::deserialize_from_slice(&mut Codec::new(invalid.as_slice())).is_err());
+        }
+    }
+
     #[test]
     pub fn proposals_are_full() {
         let mut proposals = VoteTestGen::proposals(Proposals::MAX_LEN);
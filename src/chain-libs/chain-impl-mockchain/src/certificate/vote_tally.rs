@@ -15,6 +15,13 @@ use chain_vote::TallyDecryptShare;
 use thiserror::Error;
 use typed_bytes::{ByteArray, ByteBuilder};
 
+/// set on the tally type byte of the private tallies whose decrypt shares are
+/// each preceded by the index of the committee member who produced it. The
+/// original layout, without the indices, is kept when the shares are those
+/// of the first committee members in order, which is always the case when
+/// the vote plan has no committee threshold.
+const MEMBER_INDICES_FLAG: u8 = 0x80;
+
 #[derive(Debug, Eq, PartialEq, Hash, Clone)]
 pub struct VoteTally {
     id: VotePlanId,
@@ -41,8 +48,12 @@ pub enum TallyProof {
 }
 
 #[derive(Debug, Error)]
-#[error("decrypt_shares in the proposal should have the same options amount")]
-pub struct DecryptedPrivateTallyError {}
+pub enum DecryptedPrivateTallyError {
+    #[error("decrypt_shares in the proposal should have the same options amount")]
+    OptionsMismatch,
+    #[error("the proposal should have one member index per decrypt share")]
+    MemberIndicesMismatch,
+}
 
 #[derive(Debug, Eq, PartialEq, Hash, Clone)]
 pub struct DecryptedPrivateTally {
@@ -52,9 +63,21 @@ pub struct DecryptedPrivateTally {
 #[derive(Debug, Eq, PartialEq, Hash, Clone)]
 pub struct DecryptedPrivateTallyProposal {
     pub decrypt_shares: Box<[TallyDecryptShare]>,
+    /// index in the vote plan committee of the member who produced each of
+    /// the `decrypt_shares`
+    pub member_indices: Box<[u8]>,
     pub tally_result: Box<[u64]>,
 }
 
+impl DecryptedPrivateTallyProposal {
+    fn member_indices_in_committee_order(&self) -> bool {
+        self.member_indices
+            .iter()
+            .enumerate()
+            .all(|(position, index)| position == *index as usize)
+    }
+}
+
 impl VoteTallyPayload {
     pub fn payload_type(&self) -> PayloadType {
         match self {
@@ -101,14 +124,22 @@ impl VoteTally {
     }
 
     pub fn serialize_in(&self, bb: ByteBuilder<Self>) -> ByteBuilder<Self> {
-        let bb = bb.bytes(self.id().as_ref()).u8(self.tally_type() as u8);
+        let bb = bb.bytes(self.id().as_ref());
 
         match &self.payload {
-            VoteTallyPayload::Public => bb,
+            VoteTallyPayload::Public => bb.u8(self.tally_type() as u8),
             VoteTallyPayload::Private { inner: proposals } => {
-                bb.u8(proposals.inner.len().try_into().unwrap()).fold(
-                    proposals.inner.iter(),
-                    |bb, proposal| {
+                let with_member_indices = !proposals
+                    .iter()
+                    .all(DecryptedPrivateTallyProposal::member_indices_in_committee_order);
+                let tally_type = if with_member_indices {
+                    self.tally_type() as u8 | MEMBER_INDICES_FLAG
+                } else {
+                    self.tally_type() as u8
+                };
+                bb.u8(tally_type)
+                    .u8(proposals.inner.len().try_into().unwrap())
+                    .fold(proposals.inner.iter(), |bb, proposal| {
                         // Shares per proposal, n_members x n_options
                         let n_members = proposal.decrypt_shares.len().try_into().unwrap();
                         if n_members == 0 {
@@ -117,13 +148,23 @@ impl VoteTally {
                             let n_options = proposal.tally_result.len().try_into().unwrap();
                             bb.u8(n_members)
                                 .u8(n_options)
-                                .fold(proposal.decrypt_shares.iter(), |bb, s| {
-                                    bb.bytes(&s.to_bytes())
-                                })
+                                .fold(
+                                    proposal
+                                        .member_indices
+                                        .iter()
+                                        .zip(proposal.decrypt_shares.iter()),
+                                    |bb, (index, s)| {
+                                        let bb = if with_member_indices {
+                                            bb.u8(*index)
+                                        } else {
+                                            bb
+                                        };
+                                        bb.bytes(&s.to_bytes())
+                                    },
+                                )
                                 .fold(proposal.tally_result.iter(), |bb, count| bb.u64(*count))
                         }
-                    },
-                )
+                    })
             }
         }
     }
@@ -173,19 +214,24 @@ impl DecryptedPrivateTally {
     pub fn new(
         proposals: Vec<DecryptedPrivateTallyProposal>,
     ) -> Result<Self, DecryptedPrivateTallyError> {
-        if proposals.iter().all(|proposal| {
+        if !proposals.iter().all(|proposal| {
             let mut shares = proposal.decrypt_shares.iter();
             match shares.next() {
                 Some(first_share) => shares.all(|share| share.options() == first_share.options()),
                 None => true,
             }
         }) {
-            Ok(Self {
-                inner: proposals.into_boxed_slice(),
-            })
-        } else {
-            Err(DecryptedPrivateTallyError {})
+            return Err(DecryptedPrivateTallyError::OptionsMismatch);
+        }
+        if proposals
+            .iter()
+            .any(|proposal| proposal.member_indices.len() != proposal.decrypt_shares.len())
+        {
+            return Err(DecryptedPrivateTallyError::MemberIndicesMismatch);
         }
+        Ok(Self {
+            inner: proposals.into_boxed_slice(),
+        })
     }
 
     pub fn iter(&self) -> impl Iterator<Item = &DecryptedPrivateTallyProposal> {
@@ -258,13 +304,29 @@ impl DeserializeFromSlice for TallyProof {
 impl DeserializeFromSlice for VoteTally {
     fn deserialize_from_slice(codec: &mut Codec<&[u8]>) -> Result<Self, ReadError> {
         let id = <[u8; 32]>::deserialize(codec)?.into();
-        let payload_type = codec
-            .get_u8()?
+        let payload_type = codec.get_u8()?;
+        let with_member_indices = payload_type & MEMBER_INDICES_FLAG != 0;
+        let payload_type = (payload_type & !MEMBER_INDICES_FLAG)
             .try_into()
             .map_err(|e: TryFromIntError| ReadError::StructureInvalid(e.to_string()))?;
 
         let payload = match payload_type {
+            PayloadType::Public if with_member_indices => {
+                return Err(ReadError::StructureInvalid(
+                    "public tallies have no decrypt shares".to_owned(),
+                ))
+            }
             PayloadType::Public => VoteTallyPayload::Public,
+            PayloadType::RankedChoice
+            | PayloadType::Approval
+            | PayloadType::Quadratic
+            | PayloadType::PrivateQuadratic => {
+                return Err(ReadError::StructureInvalid(format!(
+                    "{:?} votes are counted with a {:?} tally",
+                    payload_type,
+                    payload_type.tally_type()
+                )))
+            }
             PayloadType::Private => {
                 let proposals_number = codec.get_u8()? as usize;
                 let mut proposals = Vec::with_capacity(proposals_number);
@@ -273,7 +335,15 @@ impl DeserializeFromSlice for VoteTally {
                     let options_number = codec.get_u8()? as usize;
                     let share_bytes = TallyDecryptShare::bytes_len(options_number);
                     let mut shares = Vec::with_capacity(shares_number);
-                    for _j in 0..shares_number {
+                    let mut member_indices = Vec::with_capacity(shares_number);
+                    for j in 0..shares_number {
+                        // without the indices, the shares are those of the
+                        // first committee members in order
+                        member_indices.push(if with_member_indices {
+                            codec.get_u8()?
+                        } else {
+                            j as u8
+                        });
                         let s_buf = codec.get_slice(share_bytes)?;
                         let share = TallyDecryptShare::from_bytes(s_buf).ok_or_else(|| {
                             ReadError::StructureInvalid(
@@ -290,6 +360,7 @@ impl DeserializeFromSlice for VoteTally {
                     let decrypted = decrypted.into_boxed_slice();
                     proposals.push(DecryptedPrivateTallyProposal {
                         decrypt_shares: shares,
+                        member_indices: member_indices.into_boxed_slice(),
                         tally_result: decrypted,
                     });
                 }
@@ -304,3 +375,68 @@ impl DeserializeFromSlice for VoteTally {
         Ok(Self { id, payload })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::data::CommitteeMembersManager;
+    use chain_vote::{Crs, EncryptedTally};
+
+    const OPTIONS: usize = 2;
+
+    fn private_tally(member_indices: &[u8]) -> VoteTally {
+        let mut rng = rand::thread_rng();
+        let members = CommitteeMembersManager::new(&mut rng, &[], 3, 3);
+        let encrypted_tally =
+            EncryptedTally::new(OPTIONS, members.election_pk(), Crs::from_hash(&[]));
+        let decrypt_shares = member_indices
+            .iter()
+            .map(|index| {
+                encrypted_tally
+                    .partial_decrypt(&mut rng, members.members()[*index as usize].secret_key())
+            })
+            .collect();
+        let proposal = DecryptedPrivateTallyProposal {
+            decrypt_shares,
+            member_indices: member_indices.into(),
+            tally_result: vec![0; OPTIONS].into_boxed_slice(),
+        };
+        VoteTally::new_private(
+            [0u8; 32].into(),
+            DecryptedPrivateTally::new(vec![proposal]).unwrap(),
+        )
+    }
+
+    fn decode(bytes: &[u8]) -> VoteTally {
+        VoteTally::deserialize_from_slice(&mut Codec::new(bytes)).expect("can decode the tally")
+    }
+
+    #[test]
+    fn shares_of_the_first_members_keep_the_original_layout() {
+        let tally = private_tally(&[0, 1, 2]);
+        let bytes = tally.serialize().as_slice().to_vec();
+        let share_len = TallyDecryptShare::bytes_len(OPTIONS);
+
+        // id, tally type, number of proposals, shares and options, then the
+        // shares and the results
+        assert_eq!(bytes.len(), 32 + 1 + 1 + 2 + 3 * share_len + OPTIONS * 8);
+        assert_eq!(bytes[32], PayloadType::Private as u8);
+        assert_eq!(decode(&bytes), tally);
+    }
+
+    #[test]
+    fn shares_of_other_members_carry_their_indices() {
+        let tally = private_tally(&[0, 2]);
+        let bytes = tally.serialize().as_slice().to_vec();
+        let share_len = TallyDecryptShare::bytes_len(OPTIONS);
+
+        assert_eq!(
+            bytes.len(),
+            32 + 1 + 1 + 2 + 2 * (1 + share_len) + OPTIONS * 8
+        );
+        assert_eq!(bytes[32], PayloadType::Private as u8 | MEMBER_INDICES_FLAG);
+        assert_eq!(bytes[36], 0);
+        assert_eq!(bytes[37 + share_len], 2);
+        assert_eq!(decode(&bytes), tally);
+    }
+}
@@ -41,6 +41,7 @@ pub enum Fragment {
     MintToken(Transaction<certificate::MintToken>),
     Evm(EvmTransaction),
     EvmMapping(Transaction<certificate::EvmMapping>),
+    MultisigRegistration(Transaction<certificate::MultisigRegistration>),
 }
 
 impl PartialEq for Fragment {
@@ -69,6 +70,7 @@ pub(super) enum FragmentTag {
     MintToken = 13,
     Evm = 14,
     EvmMapping = 15,
+    MultisigRegistration = 16,
 }
 
 impl FragmentTag {
@@ -90,6 +92,7 @@ impl FragmentTag {
             13 => Some(FragmentTag::MintToken),
             14 => Some(FragmentTag::Evm),
             15 => Some(FragmentTag::EvmMapping),
+            16 => Some(FragmentTag::MultisigRegistration),
             _ => None,
         }
     }
@@ -115,6 +118,7 @@ impl Fragment {
             Fragment::MintToken(_) => FragmentTag::MintToken,
             Fragment::Evm(_) => FragmentTag::Evm,
             Fragment::EvmMapping(_) => FragmentTag::EvmMapping,
+            Fragment::MultisigRegistration(_) => FragmentTag::MultisigRegistration,
         }
     }
 
@@ -188,6 +192,9 @@ impl Deserialize for Fragment {
             Some(FragmentTag::EvmMapping) => {
                 Transaction::deserialize(&mut codec).map(Fragment::EvmMapping)
             }
+            Some(FragmentTag::MultisigRegistration) => {
+                Transaction::deserialize(&mut codec).map(Fragment::MultisigRegistration)
+            }
             None => Err(ReadError::UnknownTag(tag as u32)),
         }
     }
@@ -214,6 +221,7 @@ impl Serialize for Fragment {
                 Fragment::MintToken(mint_token) => mint_token.serialized_size(),
                 Fragment::Evm(deployment) => deployment.serialized_size(),
                 Fragment::EvmMapping(evm_mapping) => evm_mapping.serialized_size(),
+                Fragment::MultisigRegistration(registration) => registration.serialized_size(),
             }
             + Codec::u32_size()
     }
@@ -239,6 +247,7 @@ impl Serialize for Fragment {
             Fragment::MintToken(mint_token) => mint_token.serialize(&mut tmp)?,
             Fragment::Evm(deployment) => deployment.serialize(&mut tmp)?,
             Fragment::EvmMapping(evm_mapping) => evm_mapping.serialize(&mut tmp)?,
+            Fragment::MultisigRegistration(registration) => registration.serialize(&mut tmp)?,
         };
         let bytes = tmp.into_inner();
         codec.put_be_u32(bytes.len() as u32)?;
@@ -9,7 +9,7 @@ use quickcheck_macros::quickcheck;
 
 impl Arbitrary for Fragment {
     fn arbitrary<G: Gen>(g: &mut G) -> Self {
-        let r = g.next_u32() % 14;
+        let r = g.next_u32() % 15;
         match r {
             0 => Fragment::Initial(Arbitrary::arbitrary(g)),
             1 => Fragment::OldUtxoDeclaration(Arbitrary::arbitrary(g)),
@@ -25,6 +25,7 @@ impl Arbitrary for Fragment {
             11 => Fragment::VoteCast(Arbitrary::arbitrary(g)),
             12 => Fragment::VoteTally(Arbitrary::arbitrary(g)),
             13 => Fragment::MintToken(Arbitrary::arbitrary(g)),
+            14 => Fragment::MultisigRegistration(Arbitrary::arbitrary(g)),
             _ => unreachable!(),
         }
     }
@@ -68,8 +68,9 @@ impl LeadershipData {
         }
     }
 
+    /// get the BFT leader expected to create the block at the given date
     #[inline]
-    pub(crate) fn get_leader_at(&self, date: BlockDate) -> BftLeaderId {
+    pub fn get_leader_at(&self, date: BlockDate) -> BftLeaderId {
         let BftRoundRobinIndex(ofs) = self.offset(date.slot_id as u64);
         self.leaders[ofs as usize].clone()
     }
@@ -3,7 +3,7 @@ mod vrfeval;
 use crate::{
     certificate::PoolId,
     chaineval::PraosNonce,
-    date::{BlockDate, Epoch},
+    date::{BlockDate, Epoch, SlotId},
     header::{Header, HeaderDesc, Proof},
     leadership::{Error, ErrorKind, Verification},
     setting::ActiveSlotsCoeff,
@@ -16,6 +16,34 @@ pub(crate) use vrfeval::witness_to_nonce;
 use vrfeval::VrfEvaluator;
 pub use vrfeval::{Threshold, VrfEvalFailure, Witness, WitnessOutput};
 
+/// Parameters of the leader election of a pool for an epoch, sufficient to
+/// evaluate the election with the VRF key of the pool without the rest of
+/// the leadership data.
+#[derive(Debug, Clone)]
+pub struct LeaderElection {
+    pub epoch_nonce: PraosNonce,
+    pub stake: PercentStake,
+    pub active_slots_coeff: ActiveSlotsCoeff,
+}
+
+impl LeaderElection {
+    /// Evaluate whether the pool is elected leader for the slot, returning
+    /// the witness of the election on success.
+    pub fn evaluate(
+        &self,
+        slot_id: SlotId,
+        vrf_key: &SecretKey<RistrettoGroup2HashDh>,
+    ) -> Option<Witness> {
+        let evaluator = VrfEvaluator {
+            stake: self.stake.clone(),
+            nonce: &self.epoch_nonce,
+            slot_id,
+            active_slots_coeff: self.active_slots_coeff,
+        };
+        evaluator.evaluate(vrf_key)
+    }
+}
+
 /// Genesis Praos leadership data for a specific epoch
 pub struct LeadershipData {
     epoch_nonce: PraosNonce,
@@ -76,22 +104,9 @@ impl LeadershipData {
         &self.nodes
     }
 
-    pub fn leader(
-        &self,
-        pool_id: &PoolId,
-        vrf_key: &SecretKey<RistrettoGroup2HashDh>,
-        date: BlockDate,
-    ) -> Result<Option<Witness>, Error> {
-        if date.epoch != self.epoch {
-            return Err(Error::new_(
-                ErrorKind::Failure,
-                GenesisError::InvalidEpoch {
-                    actual: date.epoch,
-                    expected: self.epoch,
-                },
-            ));
-        }
-
+    /// Get the parameters of the leader election of the pool for this epoch,
+    /// or `None` if the pool has no stake.
+    pub fn leader_election(&self, pool_id: &PoolId) -> Result<Option<LeaderElection>, Error> {
         let stake_snapshot = &self.distribution;
 
         match stake_snapshot.get_stake_for(pool_id) {
@@ -107,22 +122,39 @@ impl LeadershipData {
                     ));
                 }
 
-                let percent_stake = PercentStake {
-                    stake,
-                    total: total_stake,
-                };
-
-                let evaluator = VrfEvaluator {
-                    stake: percent_stake,
-                    nonce: &self.epoch_nonce,
-                    slot_id: date.slot_id,
+                Ok(Some(LeaderElection {
+                    epoch_nonce: self.epoch_nonce.clone(),
+                    stake: PercentStake {
+                        stake,
+                        total: total_stake,
+                    },
                     active_slots_coeff: self.active_slots_coeff,
-                };
-                Ok(evaluator.evaluate(vrf_key))
+                }))
             }
         }
     }
 
+    pub fn leader(
+        &self,
+        pool_id: &PoolId,
+        vrf_key: &SecretKey<RistrettoGroup2HashDh>,
+        date: BlockDate,
+    ) -> Result<Option<Witness>, Error> {
+        if date.epoch != self.epoch {
+            return Err(Error::new_(
+                ErrorKind::Failure,
+                GenesisError::InvalidEpoch {
+                    actual: date.epoch,
+                    expected: self.epoch,
+                },
+            ));
+        }
+
+        Ok(self
+            .leader_election(pool_id)?
+            .and_then(|election| election.evaluate(date.slot_id, vrf_key)))
+    }
+
     pub(crate) fn verify(&self, block_header: &Header) -> Verification {
         if block_header.block_date().epoch != self.epoch {
             return Verification::Failure(Error::new_(
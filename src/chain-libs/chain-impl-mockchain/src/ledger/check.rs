@@ -1,8 +1,10 @@
-use super::{Block0Error, Error};
+use super::{Block0Error, Error, OutputAddress};
 use crate::certificate;
+use crate::date::BlockDate;
+use crate::script;
 use crate::transaction::*;
 use crate::value::Value;
-use chain_addr::Address;
+use chain_addr::{Address, Kind};
 use thiserror::Error;
 
 pub const CHECK_TX_MAXIMUM_INPUTS: u8 = 255;
@@ -59,6 +61,30 @@ pub(super) fn valid_output_value(output: &Output<Address>) -> LedgerCheck {
     )
 }
 
+/// Check that the script witness unlocks the script address of the spent output
+///
+/// * the output is locked to a script address
+/// * the revealed script matches the address and is satisfied at the given date
+pub(super) fn valid_script_witness(
+    utxo: &UtxoPointer,
+    output: OutputAddress,
+    witness: &script::Witness,
+    data: &WitnessScriptData,
+    date: BlockDate,
+) -> LedgerCheck {
+    let identifier = match output.address.kind() {
+        Kind::Script(identifier) => script::Identifier::from(*identifier),
+        _ => return Err(Error::ExpectingUtxoWitness),
+    };
+    witness
+        .verify(&identifier, data, date)
+        .map_err(|error| Error::ScriptInvalidWitness {
+            utxo: *utxo,
+            output,
+            error,
+        })
+}
+
 /// check that the transaction input/outputs/witnesses is valid for stake_owner_delegation
 ///
 /// * Only 1 input (subsequently 1 witness), no output
@@ -1,14 +1,16 @@
-use super::governance::Governance;
+use super::governance::{Governance, ParametersGovernanceAction};
 use super::ledger::{Error, Ledger, LedgerStaticParameters};
 use super::pots::{self, Pots};
 use super::LeadersParticipationRecord;
-use crate::certificate::{VotePlan, VotePlanId};
+use crate::certificate::VotePlanId;
 use crate::chaintypes::ChainLength;
 use crate::config::ConfigParam;
 use crate::date::BlockDate;
 use crate::key::Hash;
 use crate::ledger::token_distribution::TokenTotals;
 use crate::stake::PoolsState;
+use crate::tokens::identifier::TokenIdentifier;
+use crate::value::Value;
 use crate::vote::{VotePlanLedger, VotePlanManager};
 use crate::{account, legacy, multisig, setting, update, utxo};
 use chain_addr::Address;
@@ -47,7 +49,9 @@ pub enum Entry<'a> {
     ),
     StakePool((&'a crate::certificate::PoolId, &'a crate::stake::PoolState)),
     LeaderParticipation((&'a crate::certificate::PoolId, &'a u32)),
-    VotePlan(&'a VotePlan),
+    VotePlan(&'a VotePlanManager),
+    GovernanceParametersLog(&'a ParametersGovernanceAction),
+    TokenTotal((&'a TokenIdentifier, &'a Value)),
 }
 
 #[derive(Clone)]
@@ -78,7 +82,9 @@ pub enum EntryOwned {
     MultisigDeclaration((crate::multisig::Identifier, crate::multisig::Declaration)),
     StakePool((crate::certificate::PoolId, crate::stake::PoolState)),
     LeaderParticipation((crate::certificate::PoolId, u32)),
-    VotePlan(VotePlan),
+    VotePlan(VotePlanManager),
+    GovernanceParametersLog(ParametersGovernanceAction),
+    TokenTotal((TokenIdentifier, Value)),
     StopEntry,
 }
 
@@ -123,6 +129,10 @@ impl EntryOwned {
                 Some(Entry::LeaderParticipation((pool_id, participation)))
             }
             EntryOwned::VotePlan(vote_plan) => Some(Entry::VotePlan(vote_plan)),
+            EntryOwned::GovernanceParametersLog(action) => {
+                Some(Entry::GovernanceParametersLog(action))
+            }
+            EntryOwned::TokenTotal((token, value)) => Some(Entry::TokenTotal((token, value))),
             EntryOwned::StopEntry => None,
         }
     }
@@ -157,6 +167,8 @@ enum IterState<'a> {
     Pots(pots::Entries<'a>),
     LeaderParticipations(imhamt::HamtIter<'a, crate::certificate::PoolId, u32>),
     VotePlan(imhamt::HamtIter<'a, VotePlanId, VotePlanManager>),
+    GovernanceParametersLogs(std::vec::IntoIter<&'a ParametersGovernanceAction>),
+    TokenTotals(imhamt::HamtIter<'a, TokenIdentifier, Value>),
     Done,
 }
 
@@ -252,11 +264,27 @@ impl<'a> Iterator for LedgerIterator<'a> {
                 Some(x) => Some(Entry::LeaderParticipation(x)),
             },
             IterState::VotePlan(iter) => match iter.next() {
+                None => {
+                    // the logs are kept in the order they were registered
+                    let logs = self.ledger.governance.parameters.logs().collect::<Vec<_>>();
+                    self.state = IterState::GovernanceParametersLogs(logs.into_iter());
+                    self.next()
+                }
+                Some((_, plan_manager)) => Some(Entry::VotePlan(plan_manager)),
+            },
+            IterState::GovernanceParametersLogs(iter) => match iter.next() {
+                None => {
+                    self.state = IterState::TokenTotals(self.ledger.token_totals.iter());
+                    self.next()
+                }
+                Some(x) => Some(Entry::GovernanceParametersLog(x)),
+            },
+            IterState::TokenTotals(iter) => match iter.next() {
                 None => {
                     self.state = IterState::Done;
                     self.next()
                 }
-                Some((_, plan_manager)) => Some(Entry::VotePlan(plan_manager.plan())),
+                Some(x) => Some(Entry::TokenTotal(x)),
             },
             IterState::Done => None,
         }
@@ -282,14 +310,13 @@ impl<'a> std::iter::FromIterator<Entry<'a>> for Result<Ledger, Error> {
         let mut updates = update::UpdateState::new();
         let mut multisig_accounts = vec![];
         let mut multisig_declarations = vec![];
-        let delegation = PoolsState::new();
+        let mut delegation = PoolsState::new();
         let mut globals = None;
         let mut pots = Pots::zero();
         let mut leaders_log = LeadersParticipationRecord::new();
-        // TODO: votes don't have their entry
         let mut votes = VotePlanLedger::new();
-        let governance = Governance::default();
-        let token_totals = TokenTotals::default();
+        let mut governance = Governance::default();
+        let mut token_totals = TokenTotals::default();
 
         for entry in iter {
             match entry {
@@ -328,7 +355,7 @@ impl<'a> std::iter::FromIterator<Entry<'a>> for Result<Ledger, Error> {
                     multisig_declarations.push((id.clone(), decl.clone()));
                 }
                 Entry::StakePool((pool_id, pool_state)) => {
-                    let _ = delegation
+                    delegation.stake_pools = delegation
                         .stake_pools
                         .insert(pool_id.clone(), pool_state.clone())
                         .unwrap();
@@ -337,16 +364,18 @@ impl<'a> std::iter::FromIterator<Entry<'a>> for Result<Ledger, Error> {
                 Entry::LeaderParticipation((pool_id, pool_participation)) => leaders_log
                     .set_for(pool_id.clone(), *pool_participation)
                     .unwrap(),
-                Entry::VotePlan(vote_plan) => {
-                    // TODO: don't use default
+                Entry::VotePlan(plan_manager) => {
                     votes.plans = votes
                         .plans
-                        .insert(
-                            vote_plan.to_id(),
-                            VotePlanManager::new(vote_plan.clone(), Default::default()),
-                        )
+                        .insert(plan_manager.id().clone(), plan_manager.clone())
                         .unwrap();
                 }
+                Entry::GovernanceParametersLog(action) => {
+                    governance.parameters.logs_register(action.clone());
+                }
+                Entry::TokenTotal((token, value)) => {
+                    token_totals = token_totals.add(token.clone(), *value)?;
+                }
             }
         }
 
@@ -468,8 +497,14 @@ mod tests {
                 Entry::LeaderParticipation((pool_id, pool_record)) => {
                     println!("LeaderParticipation {} {}", pool_id, pool_record);
                 }
-                Entry::VotePlan(plan) => {
-                    println!("VotePlan {}", plan.to_id());
+                Entry::VotePlan(plan_manager) => {
+                    println!("VotePlan {}", plan_manager.id());
+                }
+                Entry::GovernanceParametersLog(action) => {
+                    println!("GovernanceParametersLog {:?}", action);
+                }
+                Entry::TokenTotal((token, value)) => {
+                    println!("TokenTotal {} {}", token, value);
                 }
             }
         }
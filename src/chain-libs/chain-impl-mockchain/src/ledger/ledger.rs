@@ -8,7 +8,7 @@ use super::pots::Pots;
 use super::reward_info::{EpochRewardsInfo, RewardsInfoParameters};
 use super::token_distribution::{TokenDistribution, TokenTotals};
 
-use crate::certificate::MintToken;
+use crate::certificate::{MintToken, MultisigRegistration};
 use crate::chaineval::HeaderContentEvalContext;
 use crate::chaintypes::{ChainLength, ConsensusType, HeaderId};
 use crate::config::{self, ConfigParam};
@@ -25,7 +25,7 @@ use crate::treasury::Treasury;
 use crate::update::UpdateState;
 use crate::value::*;
 use crate::vote::{VotePlanLedger, VotePlanLedgerError, VotePlanStatus};
-use crate::{account, certificate, legacy, multisig, setting, stake, update, utxo};
+use crate::{account, certificate, legacy, multisig, script, setting, stake, update, utxo};
 use crate::{
     certificate::{
         BftLeaderBindingSignature, OwnerStakeDelegation, PoolId, UpdateProposal, UpdateProposalId,
@@ -301,8 +301,15 @@ pub enum Error {
     UpdateNotAllowedYet,
     #[error("Voting error")]
     VotePlan(#[from] VotePlanLedgerError),
-    #[error("Scripts addresses are not yet supported by the system")]
-    ScriptsAddressNotAllowedYet,
+    #[error("Script witness does not unlock the UTxO")]
+    ScriptInvalidWitness {
+        utxo: UtxoPointer,
+        output: OutputAddress,
+        #[source]
+        error: script::ScriptError,
+    },
+    #[error("Expecting a script witness to spend from a script address")]
+    ExpectingScriptWitness,
     #[error("Protocol update proposal payload signature failed")]
     UpdateProposalSignatureFailed,
     #[error("Protocol update vote payload signature failed")]
@@ -494,6 +501,11 @@ impl Ledger {
                 Fragment::EvmMapping(_tx) => {
                     return Err(Error::DisabledEvmTransactions);
                 }
+                Fragment::MultisigRegistration(tx) => {
+                    let tx = tx.as_slice();
+                    check::valid_block0_cert_transaction(&tx)?;
+                    ledger = ledger.apply_multisig_registration(&tx.payload().into_payload())?;
+                }
             }
         }
 
@@ -683,7 +695,23 @@ impl Ledger {
                     )?;
                     reward_info.add_to_account(single_account, distr.taxed);
                 }
-                AccountIdentifier::Multi(_multi_account) => unimplemented!(),
+                AccountIdentifier::Multi(multi_account) => {
+                    match self
+                        .multisig
+                        .add_rewards_to_account(multi_account, epoch, distr.taxed)
+                    {
+                        Ok(multisig) => {
+                            self.multisig = multisig;
+                            reward_info.add_to_multisig_account(multi_account, distr.taxed);
+                        }
+                        // the multisig declaration has not been registered (yet),
+                        // the rewards would not be spendable so they go to the treasury
+                        Err(multisig::LedgerError::DoesntExist) => {
+                            self.pots.treasury_add(distr.taxed)?;
+                        }
+                        Err(error) => return Err(error.into()),
+                    }
+                }
             },
             None => {
                 if reg.owners.len() > 1 {
@@ -987,6 +1015,14 @@ impl Ledger {
             Fragment::EvmMapping(_tx) => {
                 return Err(Error::DisabledEvmTransactions);
             }
+            Fragment::MultisigRegistration(tx) => {
+                let tx = tx.as_slice();
+
+                let (new_ledger_, _fee) = new_ledger.apply_transaction(&fragment_id, &tx)?;
+
+                new_ledger =
+                    new_ledger_.apply_multisig_registration(&tx.payload().into_payload())?;
+            }
         }
 
         Ok(new_ledger)
@@ -1298,6 +1334,14 @@ impl Ledger {
         Ok(self)
     }
 
+    pub fn apply_multisig_registration(
+        mut self,
+        registration: &MultisigRegistration,
+    ) -> Result<Self, Error> {
+        self.multisig = self.multisig.add_account(registration.declaration())?;
+        Ok(self)
+    }
+
     pub fn mint_token(self, mt: MintToken) -> Result<Self, Error> {
         mt.policy.check_minting_tx()?;
         self.mint_token_unchecked(mt)
@@ -1332,6 +1376,10 @@ impl Ledger {
         &self.accounts
     }
 
+    pub fn multisig(&self) -> &multisig::Ledger {
+        &self.multisig
+    }
+
     pub fn updates(&self) -> &UpdateState {
         &self.updates
     }
@@ -1512,9 +1560,9 @@ impl Ledger {
                     let identifier = multisig::Identifier::from(*identifier);
                     self.multisig = self.multisig.add_value(&identifier, output.value)?;
                 }
-                Kind::Script(_identifier) => {
-                    // TODO: scripts address kinds are not yet supported
-                    return Err(Error::ScriptsAddressNotAllowedYet);
+                Kind::Script(_) => {
+                    // the script is only revealed when spending the output
+                    new_utxos.push((index as u8, output.clone()));
                 }
             }
         }
@@ -1603,15 +1651,16 @@ impl Ledger {
                     });
                 }
 
+                let public_key = match associated_output.address.public_key() {
+                    Some(public_key) => public_key,
+                    None => return Err(Error::ExpectingScriptWitness),
+                };
                 let data_to_verify = WitnessUtxoData::new(
                     &self.static_params.block0_initial_hash,
                     sign_data_hash,
                     WitnessUtxoVersion::Normal,
                 );
-                let verified = signature.verify(
-                    associated_output.address.public_key().unwrap(),
-                    &data_to_verify,
-                );
+                let verified = signature.verify(public_key, &data_to_verify);
                 if verified == chain_crypto::Verification::Failed {
                     return Err(Error::UtxoInvalidSignature {
                         utxo: *utxo,
@@ -1621,6 +1670,28 @@ impl Ledger {
                 };
                 Ok(self)
             }
+            Witness::Script(script_witness) => {
+                let (new_utxos, associated_output) =
+                    self.utxos.remove(&utxo.transaction_id, utxo.output_index)?;
+                self.utxos = new_utxos;
+                if utxo.value != associated_output.value {
+                    return Err(Error::UtxoValueNotMatching {
+                        expected: utxo.value,
+                        value: associated_output.value,
+                    });
+                }
+
+                let data_to_verify =
+                    WitnessScriptData::new(&self.static_params.block0_initial_hash, sign_data_hash);
+                check::valid_script_witness(
+                    utxo,
+                    associated_output,
+                    script_witness,
+                    &data_to_verify,
+                    self.date(),
+                )?;
+                Ok(self)
+            }
         }
     }
 
@@ -1712,6 +1783,7 @@ fn match_identifier_witness<'a>(
     match witness {
         Witness::OldUtxo(..) => Err(Error::ExpectingAccountWitness),
         Witness::Utxo(_) => Err(Error::ExpectingAccountWitness),
+        Witness::Script(_) => Err(Error::ExpectingAccountWitness),
         Witness::Account(nonce, sig) => {
             // refine account to a single account identifier
             let account = account
@@ -2003,6 +2075,8 @@ mod tests {
             (Witness::OldUtxo(..), Err(_)) => TestResult::passed(),
             (Witness::Utxo(_), Ok(_)) => TestResult::error("expecting error, but got success"),
             (Witness::Utxo(_), Err(_)) => TestResult::passed(),
+            (Witness::Script(_), Ok(_)) => TestResult::error("expecting error, but got success"),
+            (Witness::Script(_), Err(_)) => TestResult::passed(),
             (Witness::Account(_, _), Ok(_)) => TestResult::passed(),
             (Witness::Account(_, _), Err(_)) => TestResult::error("unexpected error"),
             (Witness::Multisig(_, _), _) => TestResult::discard(),
@@ -23,26 +23,35 @@
 //!
 //!
 //! For serializing the Ledger the approach is simple:
+//! * Write the format header (magic bytes and format version)
 //! * Iterate the Ledger
 //! * Pack each entry
 //! * Flag the end of packing
 //!
 //! For deserializing:
+//! * Check the format header
 //! * Load all serialized `Entry` into a `Vec`
 //! * Use the `from_iter` ledger method to load it from the newly created vector.
 //!
 //! Notice that the `ledger::iter::Entry` type holds references to the data types but when loading
 //! them from the serialized object we need to hold them. That is why we use the `EntryOwned` type
 //! instead for deserializing. This data is then cloned as necessary into the final deserialized ledger.
+//!
+//! The format version must be bumped every time the packing of an entry changes, a ledger
+//! serialized with another version is refused instead of being misread.
+//!
+//! The EVM state is not part of the ledger in this build (the `evm` module is not compiled),
+//! so it is not serialized either.
 
+use super::governance::ParametersGovernanceAction;
 use super::pots;
 use super::{Entry, EntryOwned};
 use crate::accounting::account::{
     AccountState, DelegationRatio, DelegationType, LastRewards, SpendingCounterIncreasing,
 };
 use crate::certificate::{
-    PoolId, PoolRegistration, Proposal, Proposals, UpdateProposal, UpdateProposalId, UpdateVoterId,
-    VoteAction, VotePlan,
+    PoolId, PoolRegistration, Proposal, Proposals, PushProposal, UpdateProposal, UpdateProposalId,
+    UpdateVoterId, VoteAction, VotePlan,
 };
 use crate::config::ConfigParam;
 use crate::date::BlockDate;
@@ -59,8 +68,11 @@ use crate::tokens::policy_hash::{PolicyHash, POLICY_HASH_SIZE};
 use crate::transaction::Output;
 use crate::update::UpdateProposalState;
 use crate::value::Value;
-use crate::vote;
-use crate::{config, key, multisig, utxo};
+use crate::vote::{
+    self, Choice, CommitteeId, IncrementalTally, RankedChoiceResult, SpentCredits, TallyResult,
+    VotePlanManager, Weight,
+};
+use crate::{account, config, key, multisig, utxo};
 use chain_addr::{Address, Discrimination};
 use chain_core::property::WriteError;
 use chain_core::{
@@ -69,9 +81,18 @@ use chain_core::{
 };
 use chain_crypto::digest::{DigestAlg, DigestOf};
 use chain_time::era::{pack_time_era, unpack_time_era};
+use chain_vote::{EncryptedCredits, EncryptedTally};
 use imhamt::Hamt;
+use std::collections::{hash_map::DefaultHasher, HashSet};
 use std::io::Write;
 use std::sync::Arc;
+use typed_bytes::ByteBuilder;
+
+/// magic bytes at the start of a serialized ledger
+const LEDGER_MAGIC: &[u8; 4] = b"JLDG";
+
+/// version of the ledger serialization format
+const LEDGER_FORMAT_VERSION: u16 = 1;
 
 #[cfg(test)]
 use crate::{
@@ -811,6 +832,7 @@ fn pack_vote_proposal<W: std::io::Write>(
 ) -> Result<(), WriteError> {
     pack_digestof(proposal.external_id(), codec)?;
     codec.put_u8(proposal.options().as_byte())?;
+    pack_vote_action(proposal.action(), codec)?;
     Ok(())
 }
 
@@ -822,8 +844,19 @@ fn unpack_proposal(codec: &mut Codec<&[u8]>) -> Result<Proposal, ReadError> {
     Ok(Proposal::new(external_id, options, action))
 }
 
-fn unpack_vote_action(_codec: &mut Codec<&[u8]>) -> Result<VoteAction, ReadError> {
-    todo!()
+fn pack_vote_action<W: std::io::Write>(
+    action: &VoteAction,
+    codec: &mut Codec<W>,
+) -> Result<(), WriteError> {
+    // same format as in the vote plan certificate
+    let bytes = action
+        .serialize_in(ByteBuilder::<VotePlan>::new())
+        .finalize_as_vec();
+    codec.put_bytes(&bytes)
+}
+
+fn unpack_vote_action(codec: &mut Codec<&[u8]>) -> Result<VoteAction, ReadError> {
+    VoteAction::deserialize(codec)
 }
 
 fn pack_vote_proposals<W: std::io::Write>(
@@ -841,7 +874,13 @@ fn unpack_proposals(codec: &mut Codec<&[u8]>) -> Result<Proposals, ReadError> {
     let mut proposals = Proposals::new();
     let size = codec.get_be_u64()?;
     for _ in 0..size {
-        let _ = proposals.push(unpack_proposal(codec)?);
+        if let PushProposal::Full { .. } = proposals.push(unpack_proposal(codec)?) {
+            return Err(ReadError::InvalidData(format!(
+                "too many proposals: {}, the maximum is {}",
+                size,
+                Proposals::MAX_LEN
+            )));
+        }
     }
     Ok(proposals)
 }
@@ -921,6 +960,7 @@ fn pack_vote_plan<W: std::io::Write>(
     pack_payload_type(vote_plan.payload_type(), codec)?;
     pack_vote_proposals(vote_plan.proposals(), codec)?;
     pack_committee_public_keys(vote_plan.committee_public_keys(), codec)?;
+    codec.put_u8(vote_plan.committee_threshold().unwrap_or(0))?;
     pack_voting_token(vote_plan.voting_token(), codec)?;
     Ok(())
 }
@@ -932,8 +972,16 @@ fn unpack_vote_plan(codec: &mut Codec<&[u8]>) -> Result<VotePlan, ReadError> {
     let payload_type = unpack_payload_type(codec)?;
     let proposals = unpack_proposals(codec)?;
     let keys = unpack_committee_public_keys(codec)?;
+    let committee_threshold = codec.get_u8()?;
+    if committee_threshold as usize > keys.len() {
+        return Err(ReadError::StructureInvalid(format!(
+            "committee threshold {} is greater than the number of committee members {}",
+            committee_threshold,
+            keys.len()
+        )));
+    }
     let voting_token = unpack_voting_token(codec)?;
-    Ok(VotePlan::new(
+    let vote_plan = VotePlan::new(
         vote_start,
         vote_end,
         committee_end,
@@ -941,7 +989,269 @@ fn unpack_vote_plan(codec: &mut Codec<&[u8]>) -> Result<VotePlan, ReadError> {
         payload_type,
         keys,
         voting_token,
-    ))
+    );
+    match committee_threshold {
+        0 => Ok(vote_plan),
+        threshold => Ok(vote_plan.with_committee_threshold(threshold)),
+    }
+}
+
+fn pack_committee<W: std::io::Write>(
+    committee: &HashSet<CommitteeId>,
+    codec: &mut Codec<W>,
+) -> Result<(), WriteError> {
+    // sorted so the same ledger is always packed the same way
+    let mut committee: Vec<_> = committee.iter().collect();
+    committee.sort_by(|a, b| a.as_ref().cmp(b.as_ref()));
+    codec.put_be_u64(committee.len() as u64)?;
+    for id in committee {
+        codec.put_bytes(id.as_ref())?;
+    }
+    Ok(())
+}
+
+fn unpack_committee(codec: &mut Codec<&[u8]>) -> Result<HashSet<CommitteeId>, ReadError> {
+    let size = codec.get_be_u64()?;
+    let mut committee = HashSet::new();
+    for _ in 0..size {
+        let id = CommitteeId::try_from(codec.get_slice(CommitteeId::COMMITTEE_ID_SIZE)?)
+            .map_err(|e| ReadError::InvalidData(e.to_string()))?;
+        committee.insert(id);
+    }
+    Ok(committee)
+}
+
+fn pack_voters<W: std::io::Write>(
+    voters: &Hamt<DefaultHasher, account::Identifier, ()>,
+    codec: &mut Codec<W>,
+) -> Result<(), WriteError> {
+    codec.put_be_u64(voters.size() as u64)?;
+    for (identifier, _) in voters.iter() {
+        pack_account_identifier(identifier, codec)?;
+    }
+    Ok(())
+}
+
+fn unpack_voters(
+    codec: &mut Codec<&[u8]>,
+) -> Result<Hamt<DefaultHasher, account::Identifier, ()>, ReadError> {
+    let size = codec.get_be_u64()?;
+    let mut voters = Vec::new();
+    for _ in 0..size {
+        voters.push((unpack_account_identifier(codec)?, ()));
+    }
+    Ok(voters.into_iter().collect())
+}
+
+fn pack_tally_result<W: std::io::Write>(
+    result: &TallyResult,
+    codec: &mut Codec<W>,
+) -> Result<(), WriteError> {
+    codec.put_u8(result.results().len() as u8)?;
+    for weight in result.results() {
+        codec.put_be_u64((*weight).into())?;
+    }
+    Ok(())
+}
+
+fn unpack_tally_result(
+    options: &vote::Options,
+    codec: &mut Codec<&[u8]>,
+) -> Result<TallyResult, ReadError> {
+    let size = codec.get_u8()?;
+    let mut results = Vec::with_capacity(size as usize);
+    for _ in 0..size {
+        results.push(Weight::from(codec.get_be_u64()?));
+    }
+    TallyResult::from_results(options.clone(), results).ok_or_else(|| {
+        ReadError::InvalidData("tally results do not match the proposal's options".to_string())
+    })
+}
+
+fn pack_ranked_choice_result<W: std::io::Write>(
+    result: &RankedChoiceResult,
+    codec: &mut Codec<W>,
+) -> Result<(), WriteError> {
+    codec.put_be_u64(result.ballots().count() as u64)?;
+    for (ranking, weight) in result.ballots() {
+        codec.put_u8(ranking.len() as u8)?;
+        for choice in ranking {
+            codec.put_u8(choice.as_byte())?;
+        }
+        codec.put_be_u64(weight.into())?;
+    }
+    Ok(())
+}
+
+fn unpack_ranked_choice_result(
+    options: &vote::Options,
+    codec: &mut Codec<&[u8]>,
+) -> Result<RankedChoiceResult, ReadError> {
+    let size = codec.get_be_u64()?;
+    let mut ballots = Vec::new();
+    for _ in 0..size {
+        let length = codec.get_u8()?;
+        let mut ranking = Vec::with_capacity(length as usize);
+        for _ in 0..length {
+            ranking.push(Choice::new(codec.get_u8()?));
+        }
+        let weight = Weight::from(codec.get_be_u64()?);
+        ballots.push((ranking.into_boxed_slice(), weight));
+    }
+    RankedChoiceResult::from_ballots(options.clone(), ballots)
+        .map_err(|e| ReadError::InvalidData(e.to_string()))
+}
+
+fn pack_incremental_tally<W: std::io::Write>(
+    tally: &IncrementalTally,
+    codec: &mut Codec<W>,
+) -> Result<(), WriteError> {
+    match tally {
+        IncrementalTally::Public(result) => {
+            codec.put_u8(0)?;
+            pack_tally_result(result, codec)
+        }
+        IncrementalTally::Private(encrypted_tally) => {
+            codec.put_u8(1)?;
+            let bytes = encrypted_tally.to_bytes();
+            codec.put_be_u64(bytes.len() as u64)?;
+            codec.put_bytes(&bytes)
+        }
+        IncrementalTally::Decrypted(result) => {
+            codec.put_u8(2)?;
+            pack_tally_result(result, codec)
+        }
+        IncrementalTally::RankedChoice(result) => {
+            codec.put_u8(3)?;
+            pack_ranked_choice_result(result, codec)
+        }
+        IncrementalTally::Approval(result) => {
+            codec.put_u8(4)?;
+            pack_tally_result(result, codec)
+        }
+        IncrementalTally::Quadratic(result) => {
+            codec.put_u8(5)?;
+            pack_tally_result(result, codec)
+        }
+    }
+}
+
+fn unpack_incremental_tally(
+    options: &vote::Options,
+    codec: &mut Codec<&[u8]>,
+) -> Result<IncrementalTally, ReadError> {
+    match codec.get_u8()? {
+        0 => Ok(IncrementalTally::Public(unpack_tally_result(
+            options, codec,
+        )?)),
+        1 => {
+            let size = codec.get_be_u64()? as usize;
+            let encrypted_tally =
+                EncryptedTally::from_bytes(codec.get_slice(size)?).ok_or_else(|| {
+                    ReadError::InvalidData("invalid encrypted tally in a vote plan".to_string())
+                })?;
+            Ok(IncrementalTally::Private(encrypted_tally))
+        }
+        2 => Ok(IncrementalTally::Decrypted(unpack_tally_result(
+            options, codec,
+        )?)),
+        3 => Ok(IncrementalTally::RankedChoice(unpack_ranked_choice_result(
+            options, codec,
+        )?)),
+        4 => Ok(IncrementalTally::Approval(unpack_tally_result(
+            options, codec,
+        )?)),
+        5 => Ok(IncrementalTally::Quadratic(unpack_tally_result(
+            options, codec,
+        )?)),
+        code => Err(ReadError::UnknownTag(code as u32)),
+    }
+}
+
+fn pack_spent_credits<W: std::io::Write>(
+    spent_credits: &SpentCredits,
+    codec: &mut Codec<W>,
+) -> Result<(), WriteError> {
+    match spent_credits {
+        SpentCredits::Public(credits) => {
+            codec.put_u8(0)?;
+            codec.put_be_u64(*credits)
+        }
+        SpentCredits::Private(encrypted_credits) => {
+            codec.put_u8(1)?;
+            codec.put_bytes(&encrypted_credits.to_bytes())
+        }
+    }
+}
+
+fn unpack_spent_credits(codec: &mut Codec<&[u8]>) -> Result<SpentCredits, ReadError> {
+    match codec.get_u8()? {
+        0 => Ok(SpentCredits::Public(codec.get_be_u64()?)),
+        1 => {
+            let bytes = codec.get_slice(EncryptedCredits::BYTES_LEN)?;
+            let encrypted_credits = EncryptedCredits::from_bytes(bytes).ok_or_else(|| {
+                ReadError::InvalidData("invalid encrypted credits in a vote plan".to_string())
+            })?;
+            Ok(SpentCredits::Private(encrypted_credits))
+        }
+        code => Err(ReadError::UnknownTag(code as u32)),
+    }
+}
+
+fn pack_vote_plan_manager<W: std::io::Write>(
+    manager: &VotePlanManager,
+    codec: &mut Codec<W>,
+) -> Result<(), WriteError> {
+    pack_vote_plan(manager.plan(), codec)?;
+    pack_committee(manager.committee_set(), codec)?;
+    // one state per proposal of the vote plan
+    for (voters, tally) in manager.proposal_states() {
+        pack_voters(voters, codec)?;
+        pack_incremental_tally(tally, codec)?;
+    }
+    codec.put_be_u64(manager.spent_credits().count() as u64)?;
+    for (identifier, spent_credits) in manager.spent_credits() {
+        pack_account_identifier(identifier, codec)?;
+        pack_spent_credits(spent_credits, codec)?;
+    }
+    Ok(())
+}
+
+fn unpack_vote_plan_manager(codec: &mut Codec<&[u8]>) -> Result<VotePlanManager, ReadError> {
+    let plan = unpack_vote_plan(codec)?;
+    let committee = unpack_committee(codec)?;
+    let mut proposal_states = Vec::with_capacity(plan.proposals().len());
+    for proposal in plan.proposals().iter() {
+        let voters = unpack_voters(codec)?;
+        let tally = unpack_incremental_tally(proposal.options(), codec)?;
+        proposal_states.push((voters, tally));
+    }
+    let size = codec.get_be_u64()?;
+    let mut spent_credits = Vec::new();
+    for _ in 0..size {
+        let identifier = unpack_account_identifier(codec)?;
+        spent_credits.push((identifier, unpack_spent_credits(codec)?));
+    }
+    VotePlanManager::restore(
+        plan,
+        committee,
+        proposal_states,
+        spent_credits.into_iter().collect(),
+    )
+    .ok_or_else(|| ReadError::InvalidData("votes do not match their vote plan".to_string()))
+}
+
+fn pack_parameters_governance_action<W: std::io::Write>(
+    action: &ParametersGovernanceAction,
+    codec: &mut Codec<W>,
+) -> Result<(), WriteError> {
+    codec.put_bytes(&action.serialize_in(ByteBuilder::new()).finalize_as_vec())
+}
+
+fn unpack_parameters_governance_action(
+    codec: &mut Codec<&[u8]>,
+) -> Result<ParametersGovernanceAction, ReadError> {
+    ParametersGovernanceAction::deserialize(codec)
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -958,6 +1268,8 @@ enum EntrySerializeCode {
     StakePool = 9,
     LeaderParticipation = 10,
     VotePlan = 11,
+    GovernanceParametersLog = 12,
+    TokenTotal = 13,
     SerializationEnd = 99,
 }
 
@@ -976,6 +1288,8 @@ impl EntrySerializeCode {
             9 => Some(EntrySerializeCode::StakePool),
             10 => Some(EntrySerializeCode::LeaderParticipation),
             11 => Some(EntrySerializeCode::VotePlan),
+            12 => Some(EntrySerializeCode::GovernanceParametersLog),
+            13 => Some(EntrySerializeCode::TokenTotal),
             99 => Some(EntrySerializeCode::SerializationEnd),
             _ => None,
         }
@@ -1037,9 +1351,18 @@ fn pack_entry<W: std::io::Write>(
             pack_digestof(pool_id, codec)?;
             codec.put_be_u32(**participation)?;
         }
-        Entry::VotePlan(vote_plan) => {
+        Entry::VotePlan(vote_plan_manager) => {
             codec.put_u8(EntrySerializeCode::VotePlan as u8)?;
-            pack_vote_plan(vote_plan, codec)?;
+            pack_vote_plan_manager(vote_plan_manager, codec)?;
+        }
+        Entry::GovernanceParametersLog(action) => {
+            codec.put_u8(EntrySerializeCode::GovernanceParametersLog as u8)?;
+            pack_parameters_governance_action(action, codec)?;
+        }
+        Entry::TokenTotal((token, value)) => {
+            codec.put_u8(EntrySerializeCode::TokenTotal as u8)?;
+            pack_voting_token(token, codec)?;
+            codec.put_be_u64(value.0)?;
         }
     }
     Ok(())
@@ -1096,8 +1419,16 @@ fn unpack_entry_owned(codec: &mut Codec<&[u8]>) -> Result<EntryOwned, ReadError>
             Ok(EntryOwned::LeaderParticipation((pool_id, v)))
         }
         EntrySerializeCode::VotePlan => {
-            let vote_plan = unpack_vote_plan(codec)?;
-            Ok(EntryOwned::VotePlan(vote_plan))
+            let vote_plan_manager = unpack_vote_plan_manager(codec)?;
+            Ok(EntryOwned::VotePlan(vote_plan_manager))
+        }
+        EntrySerializeCode::GovernanceParametersLog => Ok(EntryOwned::GovernanceParametersLog(
+            unpack_parameters_governance_action(codec)?,
+        )),
+        EntrySerializeCode::TokenTotal => {
+            let token = unpack_voting_token(codec)?;
+            let value = Value(codec.get_be_u64()?);
+            Ok(EntryOwned::TokenTotal((token, value)))
         }
         EntrySerializeCode::SerializationEnd => Ok(EntryOwned::StopEntry),
     }
@@ -1120,6 +1451,8 @@ fn unpack_entries(codec: &mut Codec<&[u8]>) -> Result<Vec<EntryOwned>, ReadError
 
 impl Serialize for Ledger {
     fn serialize<W: std::io::Write>(&self, codec: &mut Codec<W>) -> Result<(), WriteError> {
+        codec.put_bytes(LEDGER_MAGIC)?;
+        codec.put_be_u16(LEDGER_FORMAT_VERSION)?;
         for entry in self.iter() {
             pack_entry(&entry, codec)?;
         }
@@ -1130,6 +1463,18 @@ impl Serialize for Ledger {
 
 impl DeserializeFromSlice for Ledger {
     fn deserialize_from_slice(codec: &mut Codec<&[u8]>) -> Result<Self, ReadError> {
+        if codec.get_slice(LEDGER_MAGIC.len())? != LEDGER_MAGIC {
+            return Err(ReadError::InvalidData(
+                "not a serialized ledger".to_string(),
+            ));
+        }
+        let version = codec.get_be_u16()?;
+        if version != LEDGER_FORMAT_VERSION {
+            return Err(ReadError::InvalidData(format!(
+                "unsupported ledger format version {}, expected {}",
+                version, LEDGER_FORMAT_VERSION
+            )));
+        }
         let owned_entries = unpack_entries(codec)?;
         let entries = owned_entries
             .iter()
@@ -1142,7 +1487,9 @@ impl DeserializeFromSlice for Ledger {
 #[cfg(test)]
 pub mod test {
     use super::*;
-    use crate::testing::{ConfigBuilder, LedgerBuilder, StakePoolBuilder};
+    use crate::certificate::VoteCast;
+    use crate::ledger::token_distribution::{TokenDistribution, TokenTotals};
+    use crate::testing::{ConfigBuilder, LedgerBuilder, StakePoolBuilder, TestGen, VoteTestGen};
     use cardano_legacy_address::Addr;
     use chain_crypto::Blake2b256;
     use quickcheck::{quickcheck, TestResult};
@@ -1161,6 +1508,22 @@ pub mod test {
         assert_eq!(address, new_address);
     }
 
+    #[test]
+    pub fn unpack_proposals_rejects_more_than_max_len() {
+        let mut codec = Codec::new(Vec::new());
+        codec.put_be_u64(Proposals::MAX_LEN as u64 + 1).unwrap();
+        for _ in 0..=Proposals::MAX_LEN {
+            pack_vote_proposal(&VoteTestGen::proposal(), &mut codec).unwrap();
+        }
+
+        let inner = codec.into_inner();
+        let mut codec = Codec::new(inner.as_slice());
+        assert!(matches!(
+            unpack_proposals(&mut codec),
+            Err(ReadError::InvalidData(_))
+        ));
+    }
+
     #[test]
     pub fn discrimination_pack_unpack_bijection() {
         let vec = Vec::new();
@@ -1379,6 +1742,99 @@ pub mod test {
         assert_eq!(ledger, other_ledger);
     }
 
+    #[test]
+    pub fn ledger_deserialize_rejects_other_format_version() {
+        let test_ledger = LedgerBuilder::from_config(ConfigBuilder::new())
+            .faucet_value(Value(42000))
+            .build()
+            .expect("cannot build test ledger");
+
+        let ledger: Ledger = test_ledger.into();
+        let mut vec = Vec::new();
+        ledger.serialize(&mut Codec::new(&mut vec)).unwrap();
+        let version = (LEDGER_FORMAT_VERSION + 1).to_be_bytes();
+        vec[LEDGER_MAGIC.len()..LEDGER_MAGIC.len() + 2].copy_from_slice(&version);
+
+        let mut codec = Codec::new(vec.as_slice());
+        assert!(Ledger::deserialize_from_slice(&mut codec).is_err());
+    }
+
+    #[test]
+    pub fn vote_plan_manager_with_votes_pack_unpack_bijection() {
+        let vote_plan = VoteTestGen::vote_plan_with_payload_type(vote::PayloadType::Quadratic);
+        let token = vote_plan.voting_token().clone();
+        let voter = TestGen::identifier();
+        let account_ledger = account::Ledger::default()
+            .add_account(voter.clone(), Value(1_000), ())
+            .unwrap()
+            .token_add(&voter, token.clone(), Value(1_000))
+            .unwrap();
+        let token_totals = TokenTotals::default().add(token, Value(1_000)).unwrap();
+        let committee = std::iter::once(CommitteeId::from([1; CommitteeId::COMMITTEE_ID_SIZE]))
+            .collect::<HashSet<_>>();
+        let vote_cast = VoteCast::new(
+            vote_plan.to_id(),
+            0,
+            vote::Payload::quadratic(Choice::new(1), 4),
+        );
+
+        let manager = VotePlanManager::new(vote_plan, committee)
+            .vote(
+                BlockDate {
+                    epoch: 1,
+                    slot_id: 1,
+                },
+                voter,
+                vote_cast,
+                TokenDistribution::new(&token_totals, &account_ledger),
+            )
+            .unwrap();
+
+        assert!(!pack_unpack_bijection(
+            &pack_vote_plan_manager,
+            &unpack_vote_plan_manager,
+            manager
+        )
+        .is_failure());
+    }
+
+    #[test]
+    pub fn vote_plan_unpack_rejects_a_threshold_above_the_committee_size() {
+        let members = VoteTestGen::committee_members_manager(3, 3);
+        let keys = members.members().iter().map(|m| m.public_key()).collect();
+        let vote_plan = VotePlan::new(
+            BlockDate {
+                epoch: 1,
+                slot_id: 0,
+            },
+            BlockDate {
+                epoch: 2,
+                slot_id: 0,
+            },
+            BlockDate {
+                epoch: 3,
+                slot_id: 0,
+            },
+            VoteTestGen::proposals(1),
+            vote::PayloadType::Private,
+            keys,
+            VoteTestGen::vote_plan().voting_token().clone(),
+        )
+        .with_committee_threshold(3);
+
+        let mut codec = Codec::new(Vec::new());
+        pack_vote_plan(&vote_plan, &mut codec).unwrap();
+        let mut bytes = codec.into_inner();
+        let threshold_position = bytes.len() - vote_plan.voting_token().bytes().len() - 1;
+        assert_eq!(bytes[threshold_position], 3);
+
+        bytes[threshold_position] = 4;
+        assert!(matches!(
+            unpack_vote_plan(&mut Codec::new(bytes.as_slice())),
+            Err(ReadError::StructureInvalid(_))
+        ));
+    }
+
     #[cfg(test)]
     fn pack_unpack_bijection<T, Pack, Unpack>(
         pack_method: &Pack,
@@ -1517,5 +1973,21 @@ pub mod test {
                 update_proposal_state
             )
         }
+
+        fn vote_plan_manager_pack_unpack_bijection(vote_plan: VotePlan) -> TestResult {
+            pack_unpack_bijection(
+                &pack_vote_plan_manager,
+                &unpack_vote_plan_manager,
+                VotePlanManager::new(vote_plan, HashSet::new())
+            )
+        }
+
+        fn parameters_governance_action_pack_unpack_bijection(action: ParametersGovernanceAction) -> TestResult {
+            pack_unpack_bijection(
+                &pack_parameters_governance_action,
+                &unpack_parameters_governance_action,
+                action
+            )
+        }
     }
 }
@@ -1,6 +1,6 @@
-use crate::account;
 use crate::certificate::PoolId;
 use crate::value::Value;
+use crate::{account, multisig};
 use std::collections::BTreeMap;
 use std::default::Default;
 
@@ -43,6 +43,8 @@ pub struct EpochRewardsInfo {
     pub stake_pools: BTreeMap<PoolId, (Value, Value)>,
     /// Amount added to each account. structure can be empty.
     pub accounts: BTreeMap<account::Identifier, Value>,
+    /// Amount added to each multisig account. structure can be empty.
+    pub multisig_accounts: BTreeMap<multisig::Identifier, Value>,
 }
 
 impl EpochRewardsInfo {
@@ -54,6 +56,7 @@ impl EpochRewardsInfo {
             treasury: Value::zero(),
             stake_pools: BTreeMap::new(),
             accounts: BTreeMap::new(),
+            multisig_accounts: BTreeMap::new(),
         }
     }
 
@@ -76,6 +79,13 @@ impl EpochRewardsInfo {
         }
     }
 
+    pub fn add_to_multisig_account(&mut self, account: &multisig::Identifier, value: Value) {
+        if self.params.report_accounts {
+            let ent = self.multisig_accounts.entry(account.clone()).or_default();
+            *ent = (*ent + value).unwrap()
+        }
+    }
+
     pub fn total(&self) -> Value {
         (self.drawn + self.fees).unwrap()
     }
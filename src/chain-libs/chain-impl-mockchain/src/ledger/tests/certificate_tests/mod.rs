@@ -1,3 +1,4 @@
+pub mod multisig;
 pub mod pool_registration;
 pub mod pool_update;
 pub mod tokens;
@@ -0,0 +1,76 @@
+#![cfg(test)]
+
+use crate::{
+    certificate::MultisigRegistration,
+    key::BftLeaderId,
+    multisig::{DeclElement, Declaration, DeclarationError},
+    testing::{builders::*, data::Wallet, ConfigBuilder, LedgerBuilder},
+    value::*,
+};
+
+fn declaration(owners: &[&Wallet], threshold: u8) -> Declaration {
+    let owners = owners
+        .iter()
+        .map(|owner| DeclElement::from_publickey(&owner.public_key()))
+        .collect();
+    Declaration::new(threshold, owners).unwrap()
+}
+
+#[test]
+pub fn multisig_registration_in_block0() {
+    let alice = Wallet::from_value(Value(100));
+    let bob = Wallet::from_value(Value(100));
+    let declaration = declaration(&[&alice, &bob], 2);
+    let registration =
+        create_initial_multisig_registration(MultisigRegistration::new(declaration.clone()));
+
+    let leader = BftLeaderId::from(alice.public_key());
+    let config_builder = ConfigBuilder::new().with_leaders(&[leader]);
+
+    let test_ledger = LedgerBuilder::from_config(config_builder)
+        .faucets_wallets(vec![&alice, &bob])
+        .certs(&[registration])
+        .build()
+        .expect("ledger should be built with multisig registration certificate");
+
+    assert_eq!(
+        test_ledger
+            .ledger
+            .multisig()
+            .get_declaration_by_id(&declaration.to_identifier()),
+        Ok(&declaration)
+    );
+}
+
+#[test]
+pub fn multisig_registration_twice_in_block0() {
+    let alice = Wallet::from_value(Value(100));
+    let bob = Wallet::from_value(Value(100));
+    let registration = create_initial_multisig_registration(MultisigRegistration::new(
+        declaration(&[&alice, &bob], 1),
+    ));
+
+    let leader = BftLeaderId::from(alice.public_key());
+    let config_builder = ConfigBuilder::new().with_leaders(&[leader]);
+
+    let result = LedgerBuilder::from_config(config_builder)
+        .faucets_wallets(vec![&alice, &bob])
+        .certs(&[registration.clone(), registration])
+        .build();
+
+    assert!(
+        result.is_err(),
+        "the same multisig declaration should not be registered twice"
+    );
+}
+
+#[test]
+pub fn multisig_declaration_with_single_owner_is_invalid() {
+    let alice = Wallet::from_value(Value(100));
+    let owners = vec![DeclElement::from_publickey(&alice.public_key())];
+
+    assert_eq!(
+        Declaration::new(1, owners),
+        Err(DeclarationError::HasNotEnoughOwners)
+    );
+}
@@ -5,4 +5,5 @@ pub mod certificate_tests;
 pub mod discrimination_tests;
 pub mod initial_funds_tests;
 pub mod ledger_tests;
+pub mod script_tests;
 pub mod transaction_tests;
@@ -0,0 +1,138 @@
+#![cfg(test)]
+
+use crate::{
+    account::AccountAlg,
+    date::BlockDate,
+    fee::FeeAlgorithm,
+    fragment::Fragment,
+    key::Hash,
+    ledger::Error,
+    script::{self, Script, ScriptError},
+    testing::{data::AddressData, ConfigBuilder, LedgerBuilder, TestLedger},
+    transaction::*,
+    value::*,
+};
+use chain_addr::{Address, Discrimination, Kind};
+use chain_crypto::SecretKey;
+
+fn script_address(script: &Script) -> Address {
+    Address(
+        Discrimination::Test,
+        Kind::Script(script.to_identifier().into()),
+    )
+}
+
+fn spend_from_script(
+    test_ledger: &TestLedger,
+    script: &Script,
+    destination: &Address,
+    sign: impl FnOnce(&WitnessScriptData, &mut script::Witness),
+) -> Fragment {
+    let address = script_address(script);
+    let entry = test_ledger
+        .utxos()
+        .find(|entry| entry.output.address == address)
+        .expect("script address was not funded");
+    let fee = test_ledger.fee().fees_for_inputs_outputs(1u8, 1u8);
+    let output = Output {
+        address: destination.clone(),
+        value: (entry.output.value - fee).unwrap(),
+    };
+    let input = Input::from_utxo_entry(entry);
+    let tx_builder = TxBuilder::new()
+        .set_nopayload()
+        .set_expiry_date(BlockDate::first().next_epoch())
+        .set_ios(&[input], &[output]);
+
+    let data = WitnessScriptData::new(
+        &test_ledger.block0_hash,
+        &tx_builder.get_auth_data_for_witness().hash(),
+    );
+    let mut witness = script::Witness::new(script.clone());
+    sign(&data, &mut witness);
+
+    let tx = tx_builder
+        .set_witnesses(&[Witness::Script(witness)])
+        .set_payload_auth(&());
+    Fragment::Transaction(tx)
+}
+
+#[test]
+pub fn vesting_script_address_unlocks_after_date() {
+    let owner = SecretKey::<AccountAlg>::generate(rand_core::OsRng);
+    let unlock = BlockDate {
+        epoch: 0,
+        slot_id: 10,
+    };
+    let script = Script::all(vec![
+        Script::Signature(Hash::hash_bytes(owner.to_public().as_ref())),
+        Script::After(unlock),
+    ])
+    .unwrap();
+    let receiver = AddressData::utxo(Discrimination::Test);
+
+    let mut test_ledger = LedgerBuilder::from_config(ConfigBuilder::new())
+        .prefill_address(script_address(&script), Value(1000))
+        .build()
+        .expect("cannot build test ledger");
+
+    let fragment = spend_from_script(&test_ledger, &script, &receiver.address, |data, w| {
+        w.add_signature(owner.to_public(), owner.sign(data))
+            .unwrap()
+    });
+    assert_err_match!(
+        Error::ScriptInvalidWitness {
+            error: ScriptError::NotSatisfied,
+            ..
+        },
+        test_ledger.apply_transaction(fragment.clone())
+    );
+
+    test_ledger.fast_forward_to(unlock);
+    assert!(test_ledger.apply_transaction(fragment).is_ok());
+    assert!(test_ledger
+        .utxos()
+        .any(|entry| entry.output.address == receiver.address));
+}
+
+#[test]
+pub fn hash_lock_script_address_requires_preimage() {
+    let secret = b"payout secret".to_vec();
+    let script = Script::HashLock(Hash::hash_bytes(&secret));
+    let other = Script::HashLock(Hash::hash_bytes(b"other secret"));
+    let receiver = AddressData::utxo(Discrimination::Test);
+
+    let mut test_ledger = LedgerBuilder::from_config(ConfigBuilder::new())
+        .prefill_address(script_address(&script), Value(1000))
+        .build()
+        .expect("cannot build test ledger");
+
+    let fragment = spend_from_script(&test_ledger, &script, &receiver.address, |_, w| {
+        w.add_preimage(b"wrong secret".to_vec()).unwrap()
+    });
+    assert_err_match!(
+        Error::ScriptInvalidWitness {
+            error: ScriptError::NotSatisfied,
+            ..
+        },
+        test_ledger.apply_transaction(fragment)
+    );
+
+    // revealing a different script than the one committed in the address
+    let fragment = spend_from_script(&test_ledger, &script, &receiver.address, |_, w| {
+        *w = script::Witness::new(other);
+        w.add_preimage(b"other secret".to_vec()).unwrap()
+    });
+    assert_err_match!(
+        Error::ScriptInvalidWitness {
+            error: ScriptError::IdentifierMismatch,
+            ..
+        },
+        test_ledger.apply_transaction(fragment)
+    );
+
+    let fragment = spend_from_script(&test_ledger, &script, &receiver.address, |_, w| {
+        w.add_preimage(secret).unwrap()
+    });
+    assert!(test_ledger.apply_transaction(fragment).is_ok());
+}
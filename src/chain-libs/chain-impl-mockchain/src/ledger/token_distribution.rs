@@ -74,4 +74,8 @@ impl TokenTotals {
     pub fn get_total(&self, token: &TokenIdentifier) -> Option<Value> {
         self.0.lookup(token).copied()
     }
+
+    pub fn iter(&self) -> imhamt::HamtIter<'_, TokenIdentifier, Value> {
+        self.0.iter()
+    }
 }
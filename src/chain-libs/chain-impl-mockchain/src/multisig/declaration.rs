@@ -1,4 +1,8 @@
 use crate::{account, key};
+use chain_core::{
+    packer::Codec,
+    property::{Deserialize, DeserializeFromSlice, ReadError, Serialize, WriteError},
+};
 use chain_crypto::{PublicKey, Signature};
 
 use super::index::{Index, TreeIndex, LEVEL_MAXLIMIT};
@@ -41,6 +45,8 @@ pub enum DeclarationError {
     HasTooManyOwners,
     #[error("Sub not implemented")]
     SubNotImplemented,
+    #[error("Sub declarations nested too deep")]
+    SubTooDeep,
 }
 
 impl std::fmt::Display for Identifier {
@@ -49,6 +55,11 @@ impl std::fmt::Display for Identifier {
     }
 }
 
+/// Maximum nesting of declarations: the owners of a declaration can be sub
+/// declarations, whose owners must be keys, as the witnesses only address
+/// the signatures up to the second level.
+pub const DECLARATION_MAX_DEPTH: usize = 2;
+
 /// Declaration of a multisig account parameters which is:
 ///
 /// * a threshold that need to be between 1 and the size of owners
@@ -60,6 +71,14 @@ pub struct Declaration {
 }
 
 impl Declaration {
+    /// Create a new declaration from a threshold and a set of owners,
+    /// checking that the parameters are valid.
+    pub fn new(threshold: u8, owners: Vec<DeclElement>) -> Result<Self, DeclarationError> {
+        let declaration = Declaration { threshold, owners };
+        declaration.is_valid()?;
+        Ok(declaration)
+    }
+
     pub fn threshold(&self) -> usize {
         self.threshold as usize
     }
@@ -67,6 +86,10 @@ impl Declaration {
     pub fn total(&self) -> usize {
         self.owners.len()
     }
+
+    pub fn owners(&self) -> &[DeclElement] {
+        &self.owners
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -106,6 +129,10 @@ impl Declaration {
     }
 
     pub fn is_valid(&self) -> Result<(), DeclarationError> {
+        self.is_valid_at_depth(1)
+    }
+
+    fn is_valid_at_depth(&self, depth: usize) -> Result<(), DeclarationError> {
         if self.threshold < 1 || self.threshold as usize > self.owners.len() {
             return Err(DeclarationError::ThresholdInvalid);
         }
@@ -115,6 +142,14 @@ impl Declaration {
         if self.owners.len() > LEVEL_MAXLIMIT {
             return Err(DeclarationError::HasTooManyOwners);
         }
+        for owner in &self.owners {
+            if let DeclElement::Sub(sub) = owner {
+                if depth >= DECLARATION_MAX_DEPTH {
+                    return Err(DeclarationError::SubTooDeep);
+                }
+                sub.is_valid_at_depth(depth + 1)?;
+            }
+        }
         Ok(())
     }
 
@@ -132,5 +167,100 @@ impl Declaration {
     }
 }
 
+const DECL_ELEMENT_TAG_SUB: u8 = 0;
+const DECL_ELEMENT_TAG_OWNER: u8 = 1;
+
+impl Serialize for Declaration {
+    fn serialized_size(&self) -> usize {
+        Codec::u8_size()
+            + Codec::u8_size()
+            + self
+                .owners
+                .iter()
+                .map(|owner| owner.serialized_size())
+                .sum::<usize>()
+    }
+
+    fn serialize<W: std::io::Write>(&self, codec: &mut Codec<W>) -> Result<(), WriteError> {
+        codec.put_u8(self.threshold)?;
+        codec.put_u8(self.owners.len() as u8)?;
+        for owner in self.owners.iter() {
+            owner.serialize(codec)?;
+        }
+        Ok(())
+    }
+}
+
+impl DeserializeFromSlice for Declaration {
+    fn deserialize_from_slice(codec: &mut Codec<&[u8]>) -> Result<Self, ReadError> {
+        deserialize_declaration(codec, 1)
+    }
+}
+
+fn deserialize_declaration(
+    codec: &mut Codec<&[u8]>,
+    depth: usize,
+) -> Result<Declaration, ReadError> {
+    let threshold = codec.get_u8()?;
+    let len = codec.get_u8()? as usize;
+    if len > LEVEL_MAXLIMIT {
+        return Err(ReadError::StructureInvalid(format!(
+            "multisig declaration has {} owners, maximum is {}",
+            len, LEVEL_MAXLIMIT
+        )));
+    }
+    let mut owners = Vec::with_capacity(len);
+    for _ in 0..len {
+        owners.push(deserialize_element(codec, depth)?);
+    }
+    Ok(Declaration { threshold, owners })
+}
+
+// `depth` is the level of the declaration the element belongs to
+fn deserialize_element(codec: &mut Codec<&[u8]>, depth: usize) -> Result<DeclElement, ReadError> {
+    match codec.get_u8()? {
+        DECL_ELEMENT_TAG_SUB => {
+            if depth >= DECLARATION_MAX_DEPTH {
+                return Err(ReadError::StructureInvalid(format!(
+                    "multisig declaration nested deeper than {} levels",
+                    DECLARATION_MAX_DEPTH
+                )));
+            }
+            deserialize_declaration(codec, depth + 1).map(DeclElement::Sub)
+        }
+        DECL_ELEMENT_TAG_OWNER => key::Hash::deserialize(codec).map(DeclElement::Owner),
+        tag => Err(ReadError::UnknownTag(tag as u32)),
+    }
+}
+
+impl Serialize for DeclElement {
+    fn serialized_size(&self) -> usize {
+        Codec::u8_size()
+            + match self {
+                DeclElement::Sub(declaration) => declaration.serialized_size(),
+                DeclElement::Owner(hash) => hash.serialized_size(),
+            }
+    }
+
+    fn serialize<W: std::io::Write>(&self, codec: &mut Codec<W>) -> Result<(), WriteError> {
+        match self {
+            DeclElement::Sub(declaration) => {
+                codec.put_u8(DECL_ELEMENT_TAG_SUB)?;
+                declaration.serialize(codec)
+            }
+            DeclElement::Owner(hash) => {
+                codec.put_u8(DECL_ELEMENT_TAG_OWNER)?;
+                hash.serialize(codec)
+            }
+        }
+    }
+}
+
+impl DeserializeFromSlice for DeclElement {
+    fn deserialize_from_slice(codec: &mut Codec<&[u8]>) -> Result<Self, ReadError> {
+        deserialize_element(codec, 1)
+    }
+}
+
 pub type Pk = PublicKey<account::AccountAlg>;
 pub type Sig = Signature<WitnessMultisigData, account::AccountAlg>;
@@ -8,7 +8,7 @@ pub struct Index(u8);
 
 impl Index {
     pub fn from_u8(v: u8) -> Option<Self> {
-        if v as usize > LEVEL_MAXLIMIT {
+        if v as usize >= LEVEL_MAXLIMIT {
             None
         } else {
             Some(Index(v))
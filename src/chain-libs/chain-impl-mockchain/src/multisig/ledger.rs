@@ -3,7 +3,8 @@ use std::collections::hash_map::DefaultHasher;
 use thiserror::Error;
 
 use super::declaration::{Declaration, DeclarationError, Identifier};
-use crate::accounting::account::{self, DelegationType, Iter, SpendingCounter};
+use crate::accounting::account::{self, AccountState, DelegationType, Iter, SpendingCounter};
+use crate::date::Epoch;
 use crate::value::{Value, ValueError};
 
 #[derive(Clone, PartialEq, Eq, Default)]
@@ -30,6 +31,8 @@ pub enum LedgerError {
     IdentifierMismatch,
     #[error("Multisig account's threshold not met")]
     ThresholdNotMet,
+    #[error("Multisig participant signed more than once")]
+    DuplicateParticipant,
 }
 
 impl From<InsertError> for LedgerError {
@@ -99,6 +102,35 @@ impl Ledger {
         })
     }
 
+    /// Add rewards to an existing multisig account.
+    ///
+    /// Contrary to single accounts, a multisig account cannot be created
+    /// implicitly by a reward payout: the declaration needs to be registered
+    /// first, otherwise the funds would not be spendable.
+    pub fn add_rewards_to_account(
+        &self,
+        identifier: &Identifier,
+        epoch: Epoch,
+        value: Value,
+    ) -> Result<Self, LedgerError> {
+        if self.declarations.lookup(identifier).is_none() {
+            return Err(LedgerError::DoesntExist);
+        }
+        let new_accounts = self
+            .accounts
+            .add_rewards_to_account(identifier, epoch, value, ())
+            .map_err(account::LedgerError::from)?;
+        Ok(Self {
+            accounts: new_accounts,
+            declarations: self.declarations.clone(),
+        })
+    }
+
+    /// Get the account state of a registered multisig account.
+    pub fn get_state(&self, identifier: &Identifier) -> Result<&AccountState<()>, LedgerError> {
+        self.accounts.get_state(identifier).map_err(|e| e.into())
+    }
+
     pub fn iter_accounts(&self) -> Iter<'_, Identifier, ()> {
         self.accounts.iter()
     }
@@ -144,7 +176,7 @@ impl Ledger {
     }
 
     /// Gets the `&Declaration` for the given `&Identifier`.
-    pub(crate) fn get_declaration_by_id(
+    pub fn get_declaration_by_id(
         &self,
         identifier: &Identifier,
     ) -> Result<&Declaration, LedgerError> {
@@ -137,6 +137,160 @@ mod test {
         }
     }
 
+    #[test]
+    fn multisig_works_depth2() {
+        let mut rng = rand_core::OsRng;
+        let (sk1, pk1, o1, i1) = make_participant(&mut rng, 0);
+        let (sk2, pk2, o2, i2) = make_participant(&mut rng, 1);
+        let (sk3, pk3, o3, _) = make_participant(&mut rng, 2);
+        let (_, _, o4, _) = make_participant(&mut rng, 3);
+
+        // the owner at index 1 is a 2 out of 2 sub declaration
+        let sub =
+            Declaration::new(2, vec![DeclElement::Owner(o2), DeclElement::Owner(o3)]).unwrap();
+        let decl = Declaration::new(
+            2,
+            vec![
+                DeclElement::Owner(o1),
+                DeclElement::Sub(sub),
+                DeclElement::Owner(o4),
+            ],
+        )
+        .unwrap();
+
+        let fake_sign_data: TransactionSignData = vec![1, 2, 3].into();
+        let fake_sign_data_hash = TransactionSignDataHash::digest(&fake_sign_data);
+        let fake_block0_hash = key::Hash::hash_bytes(&[1, 2, 3, 4, 5, 6, 7]);
+        let msg = WitnessMultisigData::new(
+            &fake_block0_hash,
+            &fake_sign_data_hash,
+            SpendingCounter::zero(),
+        );
+
+        // participant 1 and both owners of the sub declaration
+        {
+            let mut witness_builder = WitnessBuilder::new();
+            witness_builder.append(TreeIndex::D1(i1), pk1.clone(), sk1.sign(&msg).coerce());
+            witness_builder.append(TreeIndex::D2(i2, i1), pk2.clone(), sk2.sign(&msg).coerce());
+            witness_builder.append(TreeIndex::D2(i2, i2), pk3.clone(), sk3.sign(&msg).coerce());
+            let witness = witness_builder.finalize();
+
+            assert!(
+                witness.verify(&decl, &msg),
+                "multisignature [1+2.*] 2/3 failed"
+            );
+        }
+
+        // the sub declaration threshold is not met
+        {
+            let mut witness_builder = WitnessBuilder::new();
+            witness_builder.append(TreeIndex::D1(i1), pk1.clone(), sk1.sign(&msg).coerce());
+            witness_builder.append(TreeIndex::D2(i2, i1), pk2, sk2.sign(&msg).coerce());
+            let witness = witness_builder.finalize();
+
+            assert!(
+                !witness.verify(&decl, &msg),
+                "multisignature with 1/2 of the sub declaration succeeded"
+            );
+        }
+
+        // a second level index must point to a sub declaration
+        {
+            let mut witness_builder = WitnessBuilder::new();
+            witness_builder.append(TreeIndex::D1(i1), pk1.clone(), sk1.sign(&msg).coerce());
+            witness_builder.append(TreeIndex::D2(i1, i1), pk3, sk3.sign(&msg).coerce());
+            let witness = witness_builder.finalize();
+
+            assert!(
+                !witness.verify(&decl, &msg),
+                "multisignature with a second level index on a key succeeded"
+            );
+        }
+    }
+
+    #[test]
+    fn nested_declarations_are_limited() {
+        use chain_core::{
+            packer::Codec,
+            property::{DeserializeFromSlice, ReadError, Serialize},
+        };
+
+        let owner = || DeclElement::Owner(key::Hash::hash_bytes(&[0]));
+        let invalid_sub = Declaration {
+            threshold: 3,
+            owners: vec![owner(), owner()],
+        };
+        assert_eq!(
+            Declaration::new(1, vec![owner(), DeclElement::Sub(invalid_sub)]),
+            Err(DeclarationError::ThresholdInvalid)
+        );
+
+        let sub = Declaration::new(1, vec![owner(), owner()]).unwrap();
+        let nested = Declaration {
+            threshold: 1,
+            owners: vec![owner(), DeclElement::Sub(sub)],
+        };
+        let too_deep = Declaration {
+            threshold: 1,
+            owners: vec![owner(), DeclElement::Sub(nested.clone())],
+        };
+        assert!(nested.is_valid().is_ok());
+        assert_eq!(too_deep.is_valid(), Err(DeclarationError::SubTooDeep));
+
+        let bytes = nested.serialize_as_vec().unwrap();
+        let decoded =
+            Declaration::deserialize_from_slice(&mut Codec::new(bytes.as_slice())).unwrap();
+        assert_eq!(decoded, nested);
+
+        let bytes = too_deep.serialize_as_vec().unwrap();
+        assert!(matches!(
+            Declaration::deserialize_from_slice(&mut Codec::new(bytes.as_slice())),
+            Err(ReadError::StructureInvalid(_))
+        ));
+    }
+
+    #[test]
+    fn multisig_witness_serialization_bijection() {
+        use chain_core::{
+            packer::Codec,
+            property::{DeserializeFromSlice, Serialize},
+        };
+
+        let mut rng = rand_core::OsRng;
+        let (sk1, pk1, _, i1) = make_participant(&mut rng, 0);
+        let (sk2, pk2, _, i2) = make_participant(&mut rng, 1);
+
+        let fake_sign_data: TransactionSignData = vec![1, 2, 3].into();
+        let fake_sign_data_hash = TransactionSignDataHash::digest(&fake_sign_data);
+        let fake_block0_hash = key::Hash::hash_bytes(&[1, 2, 3, 4, 5, 6, 7]);
+        let msg = WitnessMultisigData::new(
+            &fake_block0_hash,
+            &fake_sign_data_hash,
+            SpendingCounter::zero(),
+        );
+
+        let mut witness_builder = WitnessBuilder::new();
+        witness_builder.append(TreeIndex::D1(i1), pk1, sk1.sign(&msg).coerce());
+        witness_builder.append(TreeIndex::D1(i2), pk2, sk2.sign(&msg).coerce());
+        let witness = witness_builder.finalize();
+
+        let bytes = witness.serialize_as_vec().unwrap();
+        let decoded = Witness::deserialize_from_slice(&mut Codec::new(bytes.as_slice())).unwrap();
+        assert_eq!(decoded.iter().count(), 2);
+        assert_eq!(decoded.serialize_as_vec().unwrap(), bytes);
+    }
+
+    impl Arbitrary for Declaration {
+        fn arbitrary<G: Gen>(g: &mut G) -> Self {
+            let total = 2 + usize::arbitrary(g) % (super::index::LEVEL_MAXLIMIT - 1);
+            let threshold = 1 + u8::arbitrary(g) % total as u8;
+            let owners = (0..total)
+                .map(|_| DeclElement::Owner(Arbitrary::arbitrary(g)))
+                .collect();
+            Declaration { threshold, owners }
+        }
+    }
+
     impl Arbitrary for Identifier {
         fn arbitrary<G: Gen>(g: &mut G) -> Self {
             let mut b = [0u8; 32];
@@ -35,27 +35,37 @@ impl std::fmt::Display for Witness {
 }
 
 impl Witness {
+    /// Iterate over the collected signatures, in index order.
+    pub fn iter(&self) -> impl Iterator<Item = &(TreeIndex, Pk, Sig)> {
+        self.0.iter()
+    }
+
+    /// Check the signatures against the declaration. The signatures of the
+    /// owners of a sub declaration count as the signature of the sub
+    /// declaration once they meet its threshold.
     pub fn verify(&self, declaration: &Declaration, msg: &WitnessMultisigData) -> bool {
         let mut v = Vec::new();
+        let mut subs: BTreeMap<Index, Vec<(Index, Pk)>> = BTreeMap::new();
         for (ti, pk, sig) in self.0.iter() {
+            if sig.verify(pk, msg) == Verification::Failed {
+                return false;
+            };
             match ti {
-                TreeIndex::D2(_, _) => {
-                    // Code doesn't support multi level verification yet
-                    return false;
-                }
-                TreeIndex::D1(i) => {
-                    //let sig: Signature<[u8], account::AccountAlg> = sig.clone().coerce();
-                    if sig.verify(pk, msg) == Verification::Failed {
-                        return false;
-                    };
-                    v.push((*i, pk.clone()))
-                }
+                TreeIndex::D1(i) => v.push((*i, DeclElement::from_publickey(pk))),
+                TreeIndex::D2(r, i) => subs.entry(*r).or_default().push((*i, pk.clone())),
+            }
+        }
+        for (r, witnesses) in subs {
+            let sub = match declaration.owners.get(r.to_usize()) {
+                Some(DeclElement::Sub(sub)) => sub,
+                _ => return false,
+            };
+            if verify_identifier_threshold(sub, &witnesses).is_err() {
+                return false;
             }
+            v.push((r, DeclElement::Sub(sub.clone())));
         }
-        if verify_identifier_threshold(declaration, &v[..]).is_err() {
-            return false;
-        };
-        true
+        verify_owners_threshold(declaration, &v).is_ok()
     }
 }
 
@@ -103,7 +113,7 @@ impl DeserializeFromSlice for Witness {
         let mut v = vec![(first_index, first_key, first_sig)];
 
         let mut prev_index = first_index;
-        for _ in 0..len {
+        for _ in 1..len {
             let ti = deserialize_index(codec)?;
             if ti <= prev_index {
                 return Err(ReadError::StructureInvalid(
@@ -133,6 +143,19 @@ impl WitnessBuilder {
         assert!(r.is_none());
     }
 
+    /// Check whether a signature has already been collected at the given index.
+    pub fn contains(&self, index: &TreeIndex) -> bool {
+        self.0.contains_key(index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
     pub fn finalize(&self) -> Witness {
         let mut v = Vec::new();
         for (idx, (pk, sig)) in self.0.iter() {
@@ -150,26 +173,39 @@ pub fn verify_identifier_threshold(
     declaration: &Declaration,
     witnesses: &[(Index, PublicKey<Ed25519>)],
 ) -> Result<(), LedgerError> {
-    if witnesses.len() < declaration.threshold() {
+    let owners: Vec<_> = witnesses
+        .iter()
+        .map(|(i, pk)| (*i, DeclElement::from_publickey(pk)))
+        .collect();
+    verify_owners_threshold(declaration, &owners)
+}
+
+/// Same as `verify_identifier_threshold`, with the owners that signed
+/// given as keys or as satisfied sub declarations.
+fn verify_owners_threshold(
+    declaration: &Declaration,
+    owners: &[(Index, DeclElement)],
+) -> Result<(), LedgerError> {
+    if owners.len() < declaration.threshold() {
         return Err(LedgerError::ThresholdNotMet);
     }
 
     let mut opt = vec![None; declaration.total()];
 
-    for (i, w) in witnesses {
+    for (i, w) in owners {
         let idx = i.to_usize();
         if idx >= opt.len() {
             return Err(LedgerError::ParticipantOutOfBound);
         }
-        opt[idx] = Some(w.clone())
+        if opt[idx].replace(w).is_some() {
+            return Err(LedgerError::DuplicateParticipant);
+        }
     }
     let mut r = Vec::new();
-    for (i, v) in opt.iter().enumerate() {
-        // here we abuse DeclElement::Owner to mean hash
+    for (i, v) in opt.into_iter().enumerate() {
         match v {
-            //None => r.push(DeclElement::Owner(declaration.owners[i].to_hash().clone())),
             None => r.push(declaration.owners[i].clone()),
-            Some(p) => r.push(DeclElement::from_publickey(p)),
+            Some(owner) => r.push(owner.clone()),
         }
     }
     let got = owners_to_identifier(declaration.threshold() as u8, &r);
@@ -0,0 +1,261 @@
+//! Minimal, deterministic predicate language used as spending condition
+//! of the script addresses (`chain_addr::Kind::Script`).
+//!
+//! The address only commits to the hash of the script, the script itself
+//! is revealed in the witness when spending the funds.
+mod predicate;
+mod witness;
+
+pub use predicate::{Identifier, Script, ScriptError, SCRIPT_MAX_DEPTH, SCRIPT_MAX_SUBSCRIPTS};
+pub use witness::{
+    Witness, WitnessScriptData, SCRIPT_MAX_PREIMAGE_SIZE, SCRIPT_MAX_WITNESS_ENTRIES,
+};
+
+#[cfg(any(test, feature = "property-test-api"))]
+mod test {
+    use super::*;
+    #[cfg(test)]
+    use crate::{
+        account,
+        date::BlockDate,
+        key,
+        transaction::{TransactionSignData, TransactionSignDataHash},
+    };
+    #[cfg(test)]
+    use chain_core::{
+        packer::Codec,
+        property::{BlockDate as _, DeserializeFromSlice, Serialize},
+    };
+    #[cfg(test)]
+    use chain_crypto::SecretKey;
+    use quickcheck::{Arbitrary, Gen};
+    #[cfg(test)]
+    use quickcheck_macros::quickcheck;
+
+    #[cfg(test)]
+    fn witness_data() -> WitnessScriptData {
+        let fake_sign_data: TransactionSignData = vec![1, 2, 3].into();
+        let fake_sign_data_hash = TransactionSignDataHash::digest(&fake_sign_data);
+        let fake_block0_hash = key::Hash::hash_bytes(&[1, 2, 3, 4, 5, 6, 7]);
+        WitnessScriptData::new(&fake_block0_hash, &fake_sign_data_hash)
+    }
+
+    #[cfg(test)]
+    fn make_signer() -> (SecretKey<account::AccountAlg>, key::Hash) {
+        let sk = SecretKey::generate(rand_core::OsRng);
+        let hash = key::Hash::hash_bytes(sk.to_public().as_ref());
+        (sk, hash)
+    }
+
+    #[test]
+    fn vesting_script_unlocks_after_date() {
+        let (sk, owner) = make_signer();
+        let unlock = BlockDate::from_epoch_slot_id(10, 0);
+        let script = Script::all(vec![Script::Signature(owner), Script::After(unlock)]).unwrap();
+        let msg = witness_data();
+
+        let mut witness = Witness::new(script.clone());
+        witness
+            .add_signature(sk.to_public(), sk.sign(&msg))
+            .unwrap();
+
+        let id = script.to_identifier();
+        assert_eq!(
+            witness.verify(&id, &msg, BlockDate::from_epoch_slot_id(9, 99)),
+            Err(ScriptError::NotSatisfied)
+        );
+        assert_eq!(witness.verify(&id, &msg, unlock), Ok(()));
+    }
+
+    #[test]
+    fn escrow_script_hash_lock_or_refund() {
+        let (buyer_sk, buyer) = make_signer();
+        let (seller_sk, seller) = make_signer();
+        let secret = b"escrow secret".to_vec();
+        let deadline = BlockDate::from_epoch_slot_id(5, 0);
+        let script = Script::any(vec![
+            Script::all(vec![
+                Script::Signature(seller),
+                Script::HashLock(key::Hash::hash_bytes(&secret)),
+                Script::Before(deadline),
+            ])
+            .unwrap(),
+            Script::all(vec![Script::Signature(buyer), Script::After(deadline)]).unwrap(),
+        ])
+        .unwrap();
+        let id = script.to_identifier();
+        let msg = witness_data();
+        let before_deadline = BlockDate::from_epoch_slot_id(4, 0);
+
+        let mut claim = Witness::new(script.clone());
+        claim
+            .add_signature(seller_sk.to_public(), seller_sk.sign(&msg))
+            .unwrap();
+        assert_eq!(
+            claim.verify(&id, &msg, before_deadline),
+            Err(ScriptError::NotSatisfied)
+        );
+        claim.add_preimage(secret).unwrap();
+        assert_eq!(claim.verify(&id, &msg, before_deadline), Ok(()));
+        assert_eq!(
+            claim.verify(&id, &msg, deadline),
+            Err(ScriptError::NotSatisfied)
+        );
+
+        let mut refund = Witness::new(script);
+        refund
+            .add_signature(buyer_sk.to_public(), buyer_sk.sign(&msg))
+            .unwrap();
+        assert_eq!(
+            refund.verify(&id, &msg, before_deadline),
+            Err(ScriptError::NotSatisfied)
+        );
+        assert_eq!(refund.verify(&id, &msg, deadline), Ok(()));
+    }
+
+    #[test]
+    fn signature_threshold_and_invalid_witnesses() {
+        let signers: Vec<_> = (0..3).map(|_| make_signer()).collect();
+        let script = Script::Threshold(
+            2,
+            signers
+                .iter()
+                .map(|(_, hash)| Script::Signature(hash.clone()))
+                .collect(),
+        );
+        let id = script.to_identifier();
+        let msg = witness_data();
+        let date = BlockDate::first();
+
+        let mut witness = Witness::new(script.clone());
+        let (sk, _) = &signers[0];
+        witness
+            .add_signature(sk.to_public(), sk.sign(&msg))
+            .unwrap();
+        assert_eq!(
+            witness.verify(&id, &msg, date),
+            Err(ScriptError::NotSatisfied)
+        );
+        let (sk, _) = &signers[2];
+        witness
+            .add_signature(sk.to_public(), sk.sign(&msg))
+            .unwrap();
+        assert_eq!(witness.verify(&id, &msg, date), Ok(()));
+
+        let other = Script::any(vec![Script::After(date)]).unwrap();
+        assert_eq!(
+            witness.verify(&other.to_identifier(), &msg, date),
+            Err(ScriptError::IdentifierMismatch)
+        );
+
+        let (sk, _) = &signers[1];
+        let (other_sk, _) = make_signer();
+        witness
+            .add_signature(sk.to_public(), other_sk.sign(&msg))
+            .unwrap();
+        assert_eq!(
+            witness.verify(&id, &msg, date),
+            Err(ScriptError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn script_structure_limits() {
+        let leaf = Script::After(BlockDate::first());
+        assert_eq!(
+            Script::Threshold(0, vec![leaf.clone()]).is_valid(),
+            Err(ScriptError::ThresholdInvalid)
+        );
+        assert_eq!(
+            Script::Threshold(2, vec![leaf.clone()]).is_valid(),
+            Err(ScriptError::ThresholdInvalid)
+        );
+        assert_eq!(
+            Script::Threshold(1, vec![leaf.clone(); SCRIPT_MAX_SUBSCRIPTS + 1]).is_valid(),
+            Err(ScriptError::TooManySubScripts)
+        );
+        assert_eq!(
+            Script::all(vec![leaf.clone(); 256]),
+            Err(ScriptError::TooManySubScripts)
+        );
+        assert_eq!(
+            Script::any(vec![leaf.clone(); SCRIPT_MAX_SUBSCRIPTS + 1]),
+            Err(ScriptError::TooManySubScripts)
+        );
+        assert!(
+            Script::Threshold(1, vec![leaf.clone(); SCRIPT_MAX_SUBSCRIPTS + 1])
+                .serialize_as_vec()
+                .is_err()
+        );
+
+        let too_deep =
+            (0..=SCRIPT_MAX_DEPTH).fold(leaf, |script, _| Script::any(vec![script]).unwrap());
+        assert_eq!(too_deep.is_valid(), Err(ScriptError::TooDeep));
+        let bytes = too_deep.serialize_as_vec().unwrap();
+        assert!(Script::deserialize_from_slice(&mut Codec::new(bytes.as_slice())).is_err());
+    }
+
+    #[test]
+    fn witness_entry_limits() {
+        let (sk, _) = make_signer();
+        let msg = witness_data();
+        let mut witness = Witness::new(Script::After(BlockDate::first()));
+        for _ in 0..SCRIPT_MAX_WITNESS_ENTRIES {
+            witness
+                .add_signature(sk.to_public(), sk.sign(&msg))
+                .unwrap();
+            witness
+                .add_preimage(vec![0; SCRIPT_MAX_PREIMAGE_SIZE])
+                .unwrap();
+        }
+        assert_eq!(
+            witness.add_signature(sk.to_public(), sk.sign(&msg)),
+            Err(ScriptError::TooManySignatures)
+        );
+        assert_eq!(
+            witness.add_preimage(Vec::new()),
+            Err(ScriptError::TooManyPreimages)
+        );
+        assert_eq!(
+            Witness::new(Script::After(BlockDate::first())).add_preimage(vec![
+                0;
+                SCRIPT_MAX_PREIMAGE_SIZE
+                    + 1
+            ]),
+            Err(ScriptError::PreimageTooLong)
+        );
+
+        let bytes = witness.serialize_as_vec().unwrap();
+        let decoded = Witness::deserialize_from_slice(&mut Codec::new(bytes.as_slice())).unwrap();
+        assert_eq!(decoded, witness);
+    }
+
+    #[quickcheck]
+    fn script_serialization_bijection(script: Script) -> bool {
+        let bytes = script.serialize_as_vec().unwrap();
+        let decoded = Script::deserialize_from_slice(&mut Codec::new(bytes.as_slice())).unwrap();
+        decoded == script && bytes.len() == script.serialized_size()
+    }
+
+    fn arbitrary_script<G: Gen>(g: &mut G, depth: usize) -> Script {
+        let max = if depth + 1 < SCRIPT_MAX_DEPTH { 5 } else { 4 };
+        match u8::arbitrary(g) % max {
+            0 => Script::Signature(Arbitrary::arbitrary(g)),
+            1 => Script::After(Arbitrary::arbitrary(g)),
+            2 => Script::Before(Arbitrary::arbitrary(g)),
+            3 => Script::HashLock(Arbitrary::arbitrary(g)),
+            _ => {
+                let len = 1 + usize::arbitrary(g) % 4;
+                let threshold = 1 + u8::arbitrary(g) % len as u8;
+                let scripts = (0..len).map(|_| arbitrary_script(g, depth + 1)).collect();
+                Script::Threshold(threshold, scripts)
+            }
+        }
+    }
+
+    impl Arbitrary for Script {
+        fn arbitrary<G: Gen>(g: &mut G) -> Self {
+            arbitrary_script(g, 0)
+        }
+    }
+}
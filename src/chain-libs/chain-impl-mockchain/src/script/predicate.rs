@@ -0,0 +1,282 @@
+use crate::{date::BlockDate, key};
+use chain_core::{
+    packer::Codec,
+    property::{Deserialize, DeserializeFromSlice, ReadError, Serialize, WriteError},
+};
+use thiserror::Error;
+
+/// Maximum number of nested thresholds in a script
+pub const SCRIPT_MAX_DEPTH: usize = 4;
+
+/// Maximum number of sub scripts in a threshold
+pub const SCRIPT_MAX_SUBSCRIPTS: usize = 16;
+
+/// Script Identifier, used as the `chain_addr::Kind::Script` payload
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Identifier(key::Hash);
+
+impl AsRef<[u8]> for Identifier {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_ref()
+    }
+}
+
+impl From<[u8; 32]> for Identifier {
+    fn from(a: [u8; 32]) -> Self {
+        Identifier(a.into())
+    }
+}
+
+impl From<Identifier> for [u8; 32] {
+    fn from(a: Identifier) -> Self {
+        a.0.into()
+    }
+}
+
+impl std::fmt::Display for Identifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum ScriptError {
+    #[error("Invalid threshold")]
+    ThresholdInvalid,
+    #[error("Too many sub scripts")]
+    TooManySubScripts,
+    #[error("Script is nested too deeply")]
+    TooDeep,
+    #[error("Script does not match the identifier of the address")]
+    IdentifierMismatch,
+    #[error("Invalid signature in the script witness")]
+    InvalidSignature,
+    #[error("Preimage in the script witness is too long")]
+    PreimageTooLong,
+    #[error("Too many signatures in the script witness")]
+    TooManySignatures,
+    #[error("Too many preimages in the script witness")]
+    TooManyPreimages,
+    #[error("Script conditions are not satisfied")]
+    NotSatisfied,
+}
+
+/// Spending condition of a script address.
+///
+/// Scripts are evaluated deterministically against the witness provided
+/// to spend from the address and the date of the ledger. They can express:
+///
+/// * signature requirement: the owner of the key needs to sign the transaction
+/// * time locks: the funds can only be spent after (or before) a given date
+/// * hash locks: the funds can only be spent by revealing a secret
+/// * thresholds: at least `n` of the sub scripts need to be satisfied,
+///   which gives `all` (`n == len`) and `any` (`n == 1`) combinators
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Script {
+    /// satisfied by a signature of the key whose hash is given
+    Signature(key::Hash),
+    /// satisfied once the ledger reached the given date (included)
+    After(BlockDate),
+    /// satisfied as long as the ledger has not reached the given date
+    Before(BlockDate),
+    /// satisfied by revealing a preimage of the given hash
+    HashLock(key::Hash),
+    /// satisfied when at least the given number of sub scripts are satisfied
+    Threshold(u8, Vec<Script>),
+}
+
+/// Everything a script is evaluated against
+pub(crate) struct EvaluationContext<'a> {
+    pub date: BlockDate,
+    pub signers: &'a [key::Hash],
+    pub revealed: &'a [key::Hash],
+}
+
+impl Script {
+    /// Script satisfied when all the given scripts are satisfied
+    pub fn all(scripts: Vec<Script>) -> Result<Self, ScriptError> {
+        check_subscripts(&scripts)?;
+        Ok(Script::Threshold(scripts.len() as u8, scripts))
+    }
+
+    /// Script satisfied when any of the given scripts is satisfied
+    pub fn any(scripts: Vec<Script>) -> Result<Self, ScriptError> {
+        check_subscripts(&scripts)?;
+        Ok(Script::Threshold(1, scripts))
+    }
+
+    /// Get the identifier associated with a script
+    ///
+    /// # Panics
+    ///
+    /// Panics if a threshold has more than `SCRIPT_MAX_SUBSCRIPTS` sub scripts
+    pub fn to_identifier(&self) -> Identifier {
+        let bytes = self
+            .serialize_as_vec()
+            .expect("memory serialize is expected to just work");
+        Identifier(key::Hash::hash_bytes(&bytes))
+    }
+
+    pub fn is_valid(&self) -> Result<(), ScriptError> {
+        self.is_valid_at(0)
+    }
+
+    fn is_valid_at(&self, depth: usize) -> Result<(), ScriptError> {
+        match self {
+            Script::Threshold(threshold, scripts) => {
+                if depth >= SCRIPT_MAX_DEPTH {
+                    return Err(ScriptError::TooDeep);
+                }
+                if scripts.len() > SCRIPT_MAX_SUBSCRIPTS {
+                    return Err(ScriptError::TooManySubScripts);
+                }
+                if *threshold < 1 || *threshold as usize > scripts.len() {
+                    return Err(ScriptError::ThresholdInvalid);
+                }
+                scripts
+                    .iter()
+                    .try_for_each(|script| script.is_valid_at(depth + 1))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn evaluate(&self, context: &EvaluationContext<'_>) -> bool {
+        match self {
+            Script::Signature(hash) => context.signers.contains(hash),
+            Script::After(date) => context.date >= *date,
+            Script::Before(date) => context.date < *date,
+            Script::HashLock(hash) => context.revealed.contains(hash),
+            Script::Threshold(threshold, scripts) => {
+                scripts
+                    .iter()
+                    .filter(|script| script.evaluate(context))
+                    .count()
+                    >= *threshold as usize
+            }
+        }
+    }
+}
+
+const SCRIPT_TAG_SIGNATURE: u8 = 0;
+const SCRIPT_TAG_AFTER: u8 = 1;
+const SCRIPT_TAG_BEFORE: u8 = 2;
+const SCRIPT_TAG_HASH_LOCK: u8 = 3;
+const SCRIPT_TAG_THRESHOLD: u8 = 4;
+
+fn serialize_date<W: std::io::Write>(
+    date: &BlockDate,
+    codec: &mut Codec<W>,
+) -> Result<(), WriteError> {
+    codec.put_be_u32(date.epoch)?;
+    codec.put_be_u32(date.slot_id)
+}
+
+fn deserialize_date(codec: &mut Codec<&[u8]>) -> Result<BlockDate, ReadError> {
+    let epoch = codec.get_be_u32()?;
+    let slot_id = codec.get_be_u32()?;
+    Ok(BlockDate { epoch, slot_id })
+}
+
+impl Serialize for Script {
+    fn serialized_size(&self) -> usize {
+        Codec::u8_size()
+            + match self {
+                Script::Signature(hash) | Script::HashLock(hash) => hash.serialized_size(),
+                Script::After(_) | Script::Before(_) => Codec::u32_size() * 2,
+                Script::Threshold(_, scripts) => {
+                    Codec::u8_size()
+                        + Codec::u8_size()
+                        + scripts
+                            .iter()
+                            .map(|script| script.serialized_size())
+                            .sum::<usize>()
+                }
+            }
+    }
+
+    fn serialize<W: std::io::Write>(&self, codec: &mut Codec<W>) -> Result<(), WriteError> {
+        match self {
+            Script::Signature(hash) => {
+                codec.put_u8(SCRIPT_TAG_SIGNATURE)?;
+                hash.serialize(codec)
+            }
+            Script::After(date) => {
+                codec.put_u8(SCRIPT_TAG_AFTER)?;
+                serialize_date(date, codec)
+            }
+            Script::Before(date) => {
+                codec.put_u8(SCRIPT_TAG_BEFORE)?;
+                serialize_date(date, codec)
+            }
+            Script::HashLock(hash) => {
+                codec.put_u8(SCRIPT_TAG_HASH_LOCK)?;
+                hash.serialize(codec)
+            }
+            Script::Threshold(threshold, scripts) => {
+                codec.put_u8(SCRIPT_TAG_THRESHOLD)?;
+                if scripts.len() > SCRIPT_MAX_SUBSCRIPTS {
+                    return Err(WriteError::IoError(std::io::Error::new(
+                        std::io::ErrorKind::InvalidInput,
+                        format!(
+                            "script threshold has {} sub scripts, maximum is {}",
+                            scripts.len(),
+                            SCRIPT_MAX_SUBSCRIPTS
+                        ),
+                    )));
+                }
+                codec.put_u8(*threshold)?;
+                codec.put_u8(scripts.len() as u8)?;
+                for script in scripts.iter() {
+                    script.serialize(codec)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+fn check_subscripts(scripts: &[Script]) -> Result<(), ScriptError> {
+    if scripts.len() > SCRIPT_MAX_SUBSCRIPTS {
+        Err(ScriptError::TooManySubScripts)
+    } else {
+        Ok(())
+    }
+}
+
+fn deserialize_script(codec: &mut Codec<&[u8]>, depth: usize) -> Result<Script, ReadError> {
+    match codec.get_u8()? {
+        SCRIPT_TAG_SIGNATURE => key::Hash::deserialize(codec).map(Script::Signature),
+        SCRIPT_TAG_AFTER => deserialize_date(codec).map(Script::After),
+        SCRIPT_TAG_BEFORE => deserialize_date(codec).map(Script::Before),
+        SCRIPT_TAG_HASH_LOCK => key::Hash::deserialize(codec).map(Script::HashLock),
+        SCRIPT_TAG_THRESHOLD => {
+            if depth >= SCRIPT_MAX_DEPTH {
+                return Err(ReadError::StructureInvalid(format!(
+                    "script is nested more than {} levels",
+                    SCRIPT_MAX_DEPTH
+                )));
+            }
+            let threshold = codec.get_u8()?;
+            let len = codec.get_u8()? as usize;
+            if len > SCRIPT_MAX_SUBSCRIPTS {
+                return Err(ReadError::StructureInvalid(format!(
+                    "script threshold has {} sub scripts, maximum is {}",
+                    len, SCRIPT_MAX_SUBSCRIPTS
+                )));
+            }
+            let mut scripts = Vec::with_capacity(len);
+            for _ in 0..len {
+                scripts.push(deserialize_script(codec, depth + 1)?);
+            }
+            Ok(Script::Threshold(threshold, scripts))
+        }
+        tag => Err(ReadError::UnknownTag(tag as u32)),
+    }
+}
+
+impl DeserializeFromSlice for Script {
+    fn deserialize_from_slice(codec: &mut Codec<&[u8]>) -> Result<Self, ReadError> {
+        deserialize_script(codec, 0)
+    }
+}
@@ -0,0 +1,200 @@
+use super::predicate::{EvaluationContext, Identifier, Script, ScriptError};
+use crate::{
+    account,
+    date::BlockDate,
+    key::{
+        self, deserialize_public_key, deserialize_signature, serialize_public_key,
+        serialize_signature,
+    },
+};
+use chain_core::{
+    packer::Codec,
+    property::{DeserializeFromSlice, ReadError, Serialize, WriteError},
+};
+use chain_crypto::{PublicKey, Signature, Verification};
+
+pub use crate::transaction::WitnessScriptData;
+
+/// Maximum size of a preimage revealed to unlock a hash lock
+pub const SCRIPT_MAX_PREIMAGE_SIZE: usize = 64;
+
+/// Maximum number of signatures, and of preimages, in a script witness
+pub const SCRIPT_MAX_WITNESS_ENTRIES: usize = u8::MAX as usize;
+
+pub type Pk = PublicKey<account::AccountAlg>;
+pub type Sig = Signature<WitnessScriptData, account::AccountAlg>;
+
+/// Witness for spending from a script address: the script itself, the
+/// signatures of the transaction and the preimages unlocking hash locks.
+#[derive(Debug, Clone)]
+pub struct Witness {
+    script: Script,
+    signatures: Vec<(Pk, Sig)>,
+    preimages: Vec<Vec<u8>>,
+}
+
+impl PartialEq for Witness {
+    fn eq(&self, other: &Self) -> bool {
+        self.script == other.script
+            && self.preimages == other.preimages
+            && self.signatures.len() == other.signatures.len()
+            && self
+                .signatures
+                .iter()
+                .zip(other.signatures.iter())
+                .all(|((pk1, sig1), (pk2, sig2))| pk1 == pk2 && sig1.as_ref() == sig2.as_ref())
+    }
+}
+impl Eq for Witness {}
+
+impl std::fmt::Display for Witness {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "Script Witness {} signatures {} preimages",
+            self.signatures.len(),
+            self.preimages.len()
+        )
+    }
+}
+
+impl Witness {
+    pub fn new(script: Script) -> Self {
+        Witness {
+            script,
+            signatures: Vec::new(),
+            preimages: Vec::new(),
+        }
+    }
+
+    pub fn script(&self) -> &Script {
+        &self.script
+    }
+
+    pub fn add_signature(&mut self, publickey: Pk, sig: Sig) -> Result<(), ScriptError> {
+        if self.signatures.len() >= SCRIPT_MAX_WITNESS_ENTRIES {
+            return Err(ScriptError::TooManySignatures);
+        }
+        self.signatures.push((publickey, sig));
+        Ok(())
+    }
+
+    pub fn add_preimage(&mut self, preimage: Vec<u8>) -> Result<(), ScriptError> {
+        if preimage.len() > SCRIPT_MAX_PREIMAGE_SIZE {
+            return Err(ScriptError::PreimageTooLong);
+        }
+        if self.preimages.len() >= SCRIPT_MAX_WITNESS_ENTRIES {
+            return Err(ScriptError::TooManyPreimages);
+        }
+        self.preimages.push(preimage);
+        Ok(())
+    }
+
+    /// Verify that the witness unlocks the script address with the given identifier:
+    ///
+    /// * the script hashes to the identifier
+    /// * all the signatures are valid for the transaction
+    /// * the script conditions are satisfied at the given date
+    pub fn verify(
+        &self,
+        identifier: &Identifier,
+        msg: &WitnessScriptData,
+        date: BlockDate,
+    ) -> Result<(), ScriptError> {
+        // an invalid script cannot be serialized to compute its identifier
+        self.script.is_valid()?;
+        if &self.script.to_identifier() != identifier {
+            return Err(ScriptError::IdentifierMismatch);
+        }
+
+        let mut signers = Vec::with_capacity(self.signatures.len());
+        for (pk, sig) in self.signatures.iter() {
+            if sig.verify(pk, msg) == Verification::Failed {
+                return Err(ScriptError::InvalidSignature);
+            }
+            signers.push(key::Hash::hash_bytes(pk.as_ref()));
+        }
+        let revealed: Vec<_> = self
+            .preimages
+            .iter()
+            .map(|preimage| key::Hash::hash_bytes(preimage.as_slice()))
+            .collect();
+
+        let context = EvaluationContext {
+            date,
+            signers: &signers,
+            revealed: &revealed,
+        };
+        if self.script.evaluate(&context) {
+            Ok(())
+        } else {
+            Err(ScriptError::NotSatisfied)
+        }
+    }
+}
+
+impl Serialize for Witness {
+    fn serialized_size(&self) -> usize {
+        self.script.serialized_size()
+            + Codec::u8_size()
+            + self
+                .signatures
+                .iter()
+                .map(|(pk, sig)| pk.as_ref().len() + sig.as_ref().len())
+                .sum::<usize>()
+            + Codec::u8_size()
+            + self
+                .preimages
+                .iter()
+                .map(|preimage| Codec::u8_size() + preimage.len())
+                .sum::<usize>()
+    }
+
+    fn serialize<W: std::io::Write>(&self, codec: &mut Codec<W>) -> Result<(), WriteError> {
+        self.script.serialize(codec)?;
+        codec.put_u8(self.signatures.len() as u8)?;
+        for (pk, sig) in self.signatures.iter() {
+            serialize_public_key(pk, codec)?;
+            serialize_signature(sig, codec)?;
+        }
+        codec.put_u8(self.preimages.len() as u8)?;
+        for preimage in self.preimages.iter() {
+            codec.put_u8(preimage.len() as u8)?;
+            codec.put_bytes(preimage)?;
+        }
+        Ok(())
+    }
+}
+
+impl DeserializeFromSlice for Witness {
+    fn deserialize_from_slice(codec: &mut Codec<&[u8]>) -> Result<Self, ReadError> {
+        let script = Script::deserialize_from_slice(codec)?;
+
+        let nb_signatures = codec.get_u8()? as usize;
+        let mut signatures = Vec::with_capacity(nb_signatures);
+        for _ in 0..nb_signatures {
+            let pk = deserialize_public_key(codec)?;
+            let sig = deserialize_signature(codec)?;
+            signatures.push((pk, sig));
+        }
+
+        let nb_preimages = codec.get_u8()? as usize;
+        let mut preimages = Vec::with_capacity(nb_preimages);
+        for _ in 0..nb_preimages {
+            let len = codec.get_u8()? as usize;
+            if len > SCRIPT_MAX_PREIMAGE_SIZE {
+                return Err(ReadError::StructureInvalid(format!(
+                    "script preimage of {} bytes, maximum is {}",
+                    len, SCRIPT_MAX_PREIMAGE_SIZE
+                )));
+            }
+            preimages.push(codec.get_bytes(len)?);
+        }
+
+        Ok(Witness {
+            script,
+            signatures,
+            preimages,
+        })
+    }
+}
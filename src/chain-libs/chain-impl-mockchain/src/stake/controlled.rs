@@ -40,15 +40,12 @@ impl StakeControl {
                     // single or multisig account are not present in utxos
                     panic!("internal error: accounts in utxo")
                 }
-                Kind::Script(_) => {
-                    // scripts are not present in utxo
-                    panic!("internal error: script in utxo")
-                }
                 Kind::Group(_spending_key, account_key) => {
                     let identifier = account_key.clone().into();
                     sc.add_to(identifier, stake)
                 }
-                Kind::Single(_) => sc.add_unassigned(stake),
+                // script outputs are not delegated, like single addresses
+                Kind::Single(_) | Kind::Script(_) => sc.add_unassigned(stake),
             }
         })
     }
@@ -74,6 +71,11 @@ impl StakeControl {
         self.control.lookup(identifier).copied()
     }
 
+    /// iterate over the accounts and the total stake each of them controls
+    pub fn iter(&self) -> impl Iterator<Item = (&Identifier, &Stake)> {
+        self.control.iter()
+    }
+
     /// get the ratio controlled by the given account
     ///
     /// the ratio is based on the total assigned stake, stake that is
@@ -1,7 +1,8 @@
 use crate::{
     account::DelegationType,
     certificate::{
-        Certificate, MintToken, PoolUpdate, UpdateProposalId, VoteCast, VotePlan, VoteTally,
+        Certificate, MintToken, MultisigRegistration, PoolUpdate, UpdateProposalId, VoteCast,
+        VotePlan, VoteTally,
     },
     config::ConfigParam,
     date::BlockDate,
@@ -123,6 +124,10 @@ pub fn create_initial_mint_token(mint_token: MintToken) -> Fragment {
     fragment(mint_token.into(), vec![], &[], &[])
 }
 
+pub fn create_initial_multisig_registration(registration: MultisigRegistration) -> Fragment {
+    fragment(registration.into(), vec![], &[], &[])
+}
+
 fn set_initial_ios<P: Payload>(
     builder: TxBuilderState<SetTtl<P>>,
     inputs: &[Input],
@@ -200,6 +205,11 @@ fn fragment(
             let tx = builder.set_payload_auth(&());
             Fragment::MintToken(tx)
         }
+        Certificate::MultisigRegistration(s) => {
+            let builder = set_initial_ios(TxBuilder::new().set_payload(&s), inputs, outputs);
+            let tx = builder.set_payload_auth(&());
+            Fragment::MultisigRegistration(tx)
+        }
         _ => unreachable!(),
     }
 }
@@ -255,6 +255,18 @@ impl TestTxCertBuilder {
                 let tx = builder.set_payload_auth(&signature);
                 Fragment::EvmMapping(tx)
             }
+            Certificate::MultisigRegistration(registration) => {
+                let builder = self.set_initial_ios(
+                    valid_until,
+                    TxBuilder::new().set_payload(registration),
+                    funder,
+                    inputs,
+                    outputs,
+                    make_witness,
+                );
+                let tx = builder.set_payload_auth(&());
+                Fragment::MultisigRegistration(tx)
+            }
         }
     }
 
@@ -318,8 +330,13 @@ pub fn tally_sign(
     let signature = SingleAccountBindingSignature::new(&auth_data, |d| key.sign_slice(d.0));
 
     match payload_type {
-        PayloadType::Public => TallyProof::Public { id, signature },
-        PayloadType::Private => TallyProof::Private { id, signature },
+        PayloadType::Public
+        | PayloadType::RankedChoice
+        | PayloadType::Approval
+        | PayloadType::Quadratic => TallyProof::Public { id, signature },
+        PayloadType::Private | PayloadType::PrivateQuadratic => {
+            TallyProof::Private { id, signature }
+        }
     }
 }
 
@@ -12,6 +12,17 @@ use rand::thread_rng;
 pub fn decrypt_tally(
     vote_plan_status: &VotePlanStatus,
     members: &CommitteeMembersManager,
+) -> Result<DecryptedPrivateTally, DecryptedPrivateTallyError> {
+    let all_members = (0..members.members().len()).collect::<Vec<_>>();
+    decrypt_tally_with_members(vote_plan_status, members, &all_members)
+}
+
+/// Decrypt the tally with the shares of the given members only, which is
+/// enough for committee keys generated with the distributed key generation
+pub fn decrypt_tally_with_members(
+    vote_plan_status: &VotePlanStatus,
+    members: &CommitteeMembersManager,
+    member_indices: &[usize],
 ) -> Result<DecryptedPrivateTally, DecryptedPrivateTallyError> {
     let members_pks: Vec<chain_vote::MemberPublicKey> = members
         .members()
@@ -24,15 +35,24 @@ pub fn decrypt_tally(
         .iter()
         .map(|proposal| {
             let encrypted_tally = proposal.tally.private_encrypted().unwrap();
-            let decrypt_shares = members
-                .members()
+            let decrypt_shares = member_indices
                 .iter()
-                .map(|member| member.secret_key())
+                .map(|index| members.members()[*index].secret_key())
                 .map(|secret_key| encrypted_tally.partial_decrypt(&mut thread_rng(), secret_key))
                 .collect::<Vec<_>>();
-            let validated_tally = encrypted_tally
-                .validate_partial_decryptions(&members_pks, &decrypt_shares)
-                .expect("Invalid shares");
+            let validated_tally = match members.threshold() {
+                Some(threshold) => encrypted_tally.validate_threshold_partial_decryptions(
+                    &members_pks,
+                    threshold,
+                    &member_indices
+                        .iter()
+                        .copied()
+                        .zip(decrypt_shares.iter().cloned())
+                        .collect::<Vec<_>>(),
+                ),
+                None => encrypted_tally.validate_partial_decryptions(&members_pks, &decrypt_shares),
+            }
+            .expect("Invalid shares");
 
             (decrypt_shares, validated_tally)
         })
@@ -45,6 +65,7 @@ pub fn decrypt_tally(
         .zip(tallies.into_iter())
         .map(|(shares, tally)| DecryptedPrivateTallyProposal {
             decrypt_shares: shares.into_boxed_slice(),
+            member_indices: member_indices.iter().map(|index| *index as u8).collect(),
             tally_result: tally.votes.into_boxed_slice(),
         })
         .collect();
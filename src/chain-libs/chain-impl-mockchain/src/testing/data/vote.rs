@@ -1,7 +1,9 @@
 use crate::vote::VotePlanStatus;
 use chain_vote::{
-    committee::MemberSecretKey, Crs, ElectionPublicKey, MemberCommunicationKey, MemberPublicKey,
-    MemberState, TallyDecryptShare,
+    committee::MemberSecretKey,
+    dkg::{Environment, MemberPhase1},
+    Crs, ElectionPublicKey, MemberCommunicationKey, MemberPublicKey, MemberState,
+    TallyDecryptShare,
 };
 use rand::thread_rng;
 use rand_core::CryptoRng;
@@ -10,10 +12,13 @@ use rand_core::RngCore;
 pub struct CommitteeMembersManager {
     members: Vec<CommitteeMember>,
     crs: Crs,
+    // set when the keys were generated with the distributed key generation
+    threshold: Option<usize>,
 }
 
 pub struct CommitteeMember {
-    state: MemberState,
+    secret_key: MemberSecretKey,
+    public_key: MemberPublicKey,
 }
 
 impl CommitteeMembersManager {
@@ -35,10 +40,82 @@ impl CommitteeMembersManager {
         let mut members = Vec::new();
         for i in 0..members_no {
             let state = MemberState::new(rng, threshold, &crs, &public_keys, i);
-            members.push(CommitteeMember { state })
+            members.push(CommitteeMember {
+                secret_key: state.secret_key().clone(),
+                public_key: state.public_key(),
+            })
         }
 
-        Self { members, crs }
+        Self {
+            members,
+            crs,
+            threshold: None,
+        }
+    }
+
+    /// Generate the committee keys with an honest run of the distributed key
+    /// generation, so that any `threshold` members can decrypt the tally.
+    pub fn new_dkg(
+        rng: &mut (impl RngCore + CryptoRng),
+        crs_seed: &[u8],
+        threshold: usize,
+        members_no: usize,
+    ) -> Self {
+        let crs = Crs::from_hash(crs_seed);
+        let environment = Environment::new(threshold, members_no, &crs).unwrap();
+        let communication_keys = (0..members_no)
+            .map(|_| MemberCommunicationKey::new(rng))
+            .collect::<Vec<_>>();
+        let communication_pks = communication_keys
+            .iter()
+            .map(MemberCommunicationKey::to_public)
+            .collect::<Vec<_>>();
+
+        let (phases, b1): (Vec<_>, Vec<_>) = (0..members_no)
+            .map(|index| MemberPhase1::init(rng, &environment, &communication_pks, index).unwrap())
+            .unzip();
+        let (phases, b2): (Vec<_>, Vec<_>) = phases
+            .into_iter()
+            .zip(communication_keys.iter())
+            .map(|(phase, key)| phase.to_phase_2(rng, key, &b1))
+            .unzip();
+        let (phases, b3): (Vec<_>, Vec<_>) = phases
+            .into_iter()
+            .map(|phase| phase.to_phase_3(&communication_pks, &b1, &b2).unwrap())
+            .unzip();
+        let (phases, b4): (Vec<_>, Vec<_>) = phases
+            .into_iter()
+            .map(|phase| phase.to_phase_4(&b3))
+            .unzip();
+        let (phases, b5): (Vec<_>, Vec<_>) = phases
+            .into_iter()
+            .map(|phase| phase.to_phase_5(&b1, &b3, &b4))
+            .unzip();
+
+        // the members are ordered by index
+        let members = phases
+            .into_iter()
+            .enumerate()
+            .map(|(index, phase)| {
+                let (secret_key, keys) = phase.finalize(&b1, &b3, &b5).unwrap();
+                CommitteeMember {
+                    public_key: keys.member_public_keys()[index].clone(),
+                    secret_key,
+                }
+            })
+            .collect();
+
+        Self {
+            members,
+            crs,
+            threshold: Some(threshold),
+        }
+    }
+
+    /// Number of members needed to decrypt the tally, if the keys were
+    /// generated with the distributed key generation
+    pub fn threshold(&self) -> Option<usize> {
+        self.threshold
     }
 
     pub fn members(&self) -> &[CommitteeMember] {
@@ -54,7 +131,10 @@ impl CommitteeMembersManager {
 
     pub fn election_pk(&self) -> ElectionPublicKey {
         let keys: Vec<_> = self.members().iter().map(|x| x.public_key()).collect();
-        ElectionPublicKey::from_participants(&keys)
+        match self.threshold {
+            Some(threshold) => ElectionPublicKey::from_threshold_participants(&keys, threshold),
+            None => ElectionPublicKey::from_participants(&keys),
+        }
     }
 
     pub fn crs(&self) -> &Crs {
@@ -64,11 +144,11 @@ impl CommitteeMembersManager {
 
 impl CommitteeMember {
     pub fn public_key(&self) -> MemberPublicKey {
-        self.state.public_key()
+        self.public_key.clone()
     }
 
     pub fn secret_key(&self) -> &MemberSecretKey {
-        self.state.secret_key()
+        &self.secret_key
     }
 
     pub fn produce_decrypt_shares(
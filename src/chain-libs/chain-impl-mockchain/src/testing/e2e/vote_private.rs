@@ -1,4 +1,5 @@
 use crate::testing::decrypt_tally;
+use crate::testing::decrypt_tally_with_members;
 use crate::testing::TestGen;
 use crate::testing::VoteTestGen;
 use crate::tokens::name::TokenName;
@@ -6,7 +7,7 @@ use crate::tokens::name::TOKEN_NAME_MAX_SIZE;
 use crate::vote::VoteError::AlreadyVoted;
 use crate::vote::VotePlanLedgerError::VoteError;
 use crate::{
-    certificate::VotePlan,
+    certificate::{DecryptedPrivateTally, DecryptedPrivateTallyProposal, VotePlan},
     fee::LinearFee,
     header::BlockDate,
     testing::{
@@ -15,8 +16,9 @@ use crate::{
         verifiers::LedgerStateVerifier,
     },
     value::Value,
-    vote::{Choice, PayloadType},
+    vote::{Choice, PayloadType, Weight},
 };
+use chain_vote::CreditsOpening;
 use imhamt::UpdateError::ValueCallbackError;
 
 const ALICE: &str = "Alice";
@@ -107,6 +109,116 @@ pub fn private_vote_cast_action_transfer_to_rewards_all_shares() {
         .has_remaining_rewards_equals_to(&Value(1100));
 }
 
+#[test]
+pub fn private_vote_threshold_tally_with_a_missing_share() {
+    let mut rng = TestGen::rand();
+    let favorable = Choice::new(1);
+    let members = VoteTestGen::dkg_committee_members_manager(MEMBERS_NO, THRESHOLD);
+
+    let voting_token = TokenName::try_from(vec![0u8; TOKEN_NAME_MAX_SIZE]).unwrap();
+
+    let (mut ledger, controller) = prepare_scenario()
+        .with_config(
+            ConfigBuilder::new()
+                .with_fee(LinearFee::new(1, 1, 1))
+                .with_rewards(Value(1000)),
+        )
+        .with_initials(vec![wallet(ALICE)
+            .with(1_000)
+            .with_token(voting_token, 1_000)
+            .owns(STAKE_POOL)
+            .committee_member()])
+        .with_vote_plans(vec![vote_plan(VOTE_PLAN)
+            .owner(ALICE)
+            .consecutive_epoch_dates()
+            .payload_type(PayloadType::Private)
+            .committee_keys(members.members_keys())
+            .committee_threshold(THRESHOLD as u8)
+            .with_proposal(
+                proposal(VoteTestGen::external_proposal_id())
+                    .options(3)
+                    .action_transfer_to_rewards(100),
+            )])
+        .build()
+        .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+    let proposal = vote_plan.proposal(0);
+
+    controller
+        .cast_vote_private(
+            &alice,
+            &vote_plan,
+            &proposal.id(),
+            favorable,
+            &mut ledger,
+            &mut rng,
+        )
+        .unwrap();
+    alice.confirm_transaction();
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    let vote_plans = ledger.ledger.active_vote_plans();
+    let vote_plan_status = vote_plans
+        .iter()
+        .find(|c_vote_plan| {
+            let vote_plan: VotePlan = vote_plan.clone().into();
+            c_vote_plan.id == vote_plan.to_id()
+        })
+        .unwrap();
+
+    // the member 1 does not publish its share
+    let shares = decrypt_tally_with_members(vote_plan_status, &members, &[0, 2]).unwrap();
+
+    let alter_shares = |alter: &dyn Fn(&DecryptedPrivateTallyProposal) -> (Vec<u8>, usize)| {
+        DecryptedPrivateTally::new(
+            shares
+                .iter()
+                .map(|proposal| {
+                    let (member_indices, n_shares) = alter(proposal);
+                    DecryptedPrivateTallyProposal {
+                        decrypt_shares: proposal.decrypt_shares[..n_shares].into(),
+                        member_indices: member_indices.into_boxed_slice(),
+                        tally_result: proposal.tally_result.clone(),
+                    }
+                })
+                .collect(),
+        )
+        .unwrap()
+    };
+    // less than the threshold
+    let too_few = alter_shares(&|proposal| (proposal.member_indices[..1].to_vec(), 1));
+    assert!(controller
+        .tally_vote_private(&alice, &vote_plan, too_few, &mut ledger)
+        .is_err());
+    // the share of the member 2 attributed to the member 1
+    let misattributed = alter_shares(&|_| (vec![0, 1], 2));
+    assert!(controller
+        .tally_vote_private(&alice, &vote_plan, misattributed, &mut ledger)
+        .is_err());
+
+    controller
+        .tally_vote_private(&alice, &vote_plan, shares, &mut ledger)
+        .unwrap();
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    ledger.apply_protocol_changes().unwrap();
+
+    LedgerStateVerifier::new(ledger.into())
+        .info("rewards pot is increased")
+        .pots()
+        .has_remaining_rewards_equals_to(&Value(1100));
+}
+
 #[test]
 pub fn shouldnt_panic_when_no_initial_tokens_and_no_votes() {
     let members = VoteTestGen::committee_members_manager(MEMBERS_NO, THRESHOLD);
@@ -322,3 +434,112 @@ pub fn vote_on_different_proposal() {
         )
         .is_ok());
 }
+
+#[test]
+pub fn private_quadratic_vote_plan_tally() {
+    let mut rng = TestGen::rand();
+    let members = VoteTestGen::committee_members_manager(MEMBERS_NO, THRESHOLD);
+
+    let voting_token = TokenName::try_from(vec![0u8; TOKEN_NAME_MAX_SIZE]).unwrap();
+
+    let (mut ledger, controller) = prepare_scenario()
+        .with_config(
+            ConfigBuilder::new()
+                .with_fee(LinearFee::new(1, 1, 1))
+                .with_rewards(Value(1000)),
+        )
+        .with_initials(vec![wallet(ALICE)
+            .with(1_000)
+            .with_token(voting_token, 400)
+            .owns(STAKE_POOL)
+            .committee_member()])
+        .with_vote_plans(vec![vote_plan(VOTE_PLAN)
+            .owner(ALICE)
+            .consecutive_epoch_dates()
+            .payload_type(PayloadType::PrivateQuadratic)
+            .committee_keys(members.members_keys())
+            .with_proposal(
+                proposal(VoteTestGen::external_proposal_id())
+                    .options(3)
+                    .action_off_chain(),
+            )
+            .with_proposal(
+                proposal(VoteTestGen::external_proposal_id())
+                    .options(3)
+                    .action_off_chain(),
+            )])
+        .build()
+        .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+    let mut spent = CreditsOpening::default();
+
+    controller
+        .cast_vote_private_quadratic(
+            &alice,
+            &vote_plan,
+            &vote_plan.proposal(0).id(),
+            Choice::new(2),
+            100,
+            400,
+            &mut spent,
+            &mut ledger,
+            &mut rng,
+        )
+        .unwrap();
+    alice.confirm_transaction();
+    controller
+        .cast_vote_private_quadratic(
+            &alice,
+            &vote_plan,
+            &vote_plan.proposal(1).id(),
+            Choice::new(1),
+            225,
+            400,
+            &mut spent,
+            &mut ledger,
+            &mut rng,
+        )
+        .unwrap();
+    alice.confirm_transaction();
+    assert_eq!(spent.credits(), 325);
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    let vote_plans = ledger.ledger.active_vote_plans();
+    let vote_plan_status = vote_plans
+        .iter()
+        .find(|c_vote_plan| {
+            let vote_plan: VotePlan = vote_plan.clone().into();
+            c_vote_plan.id == vote_plan.to_id()
+        })
+        .unwrap();
+
+    let shares = decrypt_tally(vote_plan_status, &members).unwrap();
+
+    controller
+        .tally_vote_private(&alice, &vote_plan, shares, &mut ledger)
+        .unwrap();
+
+    let vote_plans = ledger.ledger.active_vote_plans();
+    let results = |index: usize| {
+        vote_plans[0].proposals[index]
+            .tally
+            .result()
+            .unwrap()
+            .results()
+            .to_vec()
+    };
+    assert_eq!(
+        results(0),
+        vec![Weight::from(0), Weight::from(0), Weight::from(10)]
+    );
+    assert_eq!(
+        results(1),
+        vec![Weight::from(0), Weight::from(15), Weight::from(0)]
+    );
+}
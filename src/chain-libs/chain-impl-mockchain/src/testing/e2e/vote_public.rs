@@ -1,6 +1,7 @@
 use crate::testing::VoteTestGen;
 use crate::tokens::name::{TokenName, TOKEN_NAME_MAX_SIZE};
 use crate::{
+    certificate::VotePlan,
     fee::{LinearFee, PerCertificateFee, PerVoteCertificateFee},
     header::BlockDate,
     testing::{
@@ -9,9 +10,10 @@ use crate::{
         verifiers::LedgerStateVerifier,
     },
     value::Value,
-    vote::Choice,
+    vote::{Choice, PayloadType, Tally, VoteError, VotePlanLedgerError, Weight},
 };
 use core::num::NonZeroU64;
+use imhamt::UpdateError::ValueCallbackError;
 
 const ALICE: &str = "Alice";
 const BOB: &str = "Bob";
@@ -618,3 +620,365 @@ pub fn voting_consistency() {
         .for_vote_plan(&vote_plan)
         .votes_were_casted_on_proposals(vec![0u8, 1u8, 2u8]);
 }
+
+#[test]
+pub fn ranked_choice_vote_plan_tally() {
+    let voting_token = TokenName::try_from(vec![0u8; TOKEN_NAME_MAX_SIZE]).unwrap();
+
+    let (mut ledger, controller) = prepare_scenario()
+        .with_config(
+            ConfigBuilder::new()
+                .with_fee(LinearFee::new(1, 1, 1))
+                .with_rewards(Value(1000)),
+        )
+        .with_initials(vec![
+            wallet(ALICE)
+                .with(1_000)
+                .with_token(voting_token.clone(), 400)
+                .owns(STAKE_POOL)
+                .committee_member(),
+            wallet(BOB)
+                .with(1_000)
+                .with_token(voting_token, 300)
+                .delegates_to(STAKE_POOL),
+        ])
+        .with_vote_plans(vec![vote_plan(VOTE_PLAN)
+            .owner(ALICE)
+            .consecutive_epoch_dates()
+            .payload_type(PayloadType::RankedChoice)
+            .with_proposal(
+                proposal(VoteTestGen::external_proposal_id())
+                    .options(3)
+                    .action_off_chain(),
+            )])
+        .build()
+        .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let bob = controller.wallet(BOB).unwrap();
+    let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+    let proposal = vote_plan.proposal(0);
+
+    controller
+        .cast_vote_choices(
+            &alice,
+            &vote_plan,
+            &proposal.id(),
+            vec![Choice::new(2), Choice::new(1)],
+            &mut ledger,
+        )
+        .unwrap();
+    alice.confirm_transaction();
+    controller
+        .cast_vote_choices(
+            &bob,
+            &vote_plan,
+            &proposal.id(),
+            vec![Choice::new(1), Choice::new(0)],
+            &mut ledger,
+        )
+        .unwrap();
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    controller
+        .tally_vote_public(&alice, &vote_plan, &mut ledger)
+        .unwrap();
+
+    let vote_plans = ledger.ledger.active_vote_plans();
+    let result = vote_plans[0].proposals[0]
+        .tally
+        .ranked_choice_result()
+        .unwrap();
+    let runoff = result.instant_runoff();
+    assert_eq!(runoff.winner(), Some(Choice::new(2)));
+    assert_eq!(
+        runoff.rounds()[0].to_vec(),
+        vec![Weight::from(0), Weight::from(300), Weight::from(400)]
+    );
+}
+
+#[test]
+pub fn approval_vote_plan_tally() {
+    let voting_token = TokenName::try_from(vec![0u8; TOKEN_NAME_MAX_SIZE]).unwrap();
+
+    let (mut ledger, controller) = prepare_scenario()
+        .with_config(
+            ConfigBuilder::new()
+                .with_fee(LinearFee::new(1, 1, 1))
+                .with_rewards(Value(1000)),
+        )
+        .with_initials(vec![
+            wallet(ALICE)
+                .with(1_000)
+                .with_token(voting_token.clone(), 400)
+                .owns(STAKE_POOL)
+                .committee_member(),
+            wallet(BOB)
+                .with(1_000)
+                .with_token(voting_token, 300)
+                .delegates_to(STAKE_POOL),
+        ])
+        .with_vote_plans(vec![vote_plan(VOTE_PLAN)
+            .owner(ALICE)
+            .consecutive_epoch_dates()
+            .payload_type(PayloadType::Approval)
+            .with_proposal(
+                proposal(VoteTestGen::external_proposal_id())
+                    .options(3)
+                    .action_off_chain(),
+            )])
+        .build()
+        .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let bob = controller.wallet(BOB).unwrap();
+    let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+    let proposal = vote_plan.proposal(0);
+
+    controller
+        .cast_vote_choices(
+            &alice,
+            &vote_plan,
+            &proposal.id(),
+            vec![Choice::new(0), Choice::new(2)],
+            &mut ledger,
+        )
+        .unwrap();
+    alice.confirm_transaction();
+    controller
+        .cast_vote_choices(
+            &bob,
+            &vote_plan,
+            &proposal.id(),
+            vec![Choice::new(2)],
+            &mut ledger,
+        )
+        .unwrap();
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    controller
+        .tally_vote_public(&alice, &vote_plan, &mut ledger)
+        .unwrap();
+
+    let vote_plans = ledger.ledger.active_vote_plans();
+    assert_eq!(
+        vote_plans[0].proposals[0]
+            .tally
+            .result()
+            .unwrap()
+            .results()
+            .to_vec(),
+        vec![Weight::from(400), Weight::from(0), Weight::from(700)]
+    );
+}
+
+#[test]
+#[should_panic]
+pub fn approval_vote_plan_with_on_chain_action() {
+    let (_ledger, _controller) = prepare_scenario()
+        .with_config(
+            ConfigBuilder::new()
+                .with_fee(LinearFee::new(1, 1, 1))
+                .with_rewards(Value(1000)),
+        )
+        .with_initials(vec![wallet(ALICE)
+            .with(1_000)
+            .owns(STAKE_POOL)
+            .committee_member()])
+        .with_vote_plans(vec![vote_plan(VOTE_PLAN)
+            .owner(ALICE)
+            .consecutive_epoch_dates()
+            .payload_type(PayloadType::Approval)
+            .with_proposal(
+                proposal(VoteTestGen::external_proposal_id())
+                    .options(3)
+                    .action_rewards_add(100),
+            )])
+        .build()
+        .unwrap();
+}
+
+#[test]
+pub fn quadratic_vote_plan_tally() {
+    let voting_token = TokenName::try_from(vec![0u8; TOKEN_NAME_MAX_SIZE]).unwrap();
+
+    let (mut ledger, controller) = prepare_scenario()
+        .with_config(
+            ConfigBuilder::new()
+                .with_fee(LinearFee::new(1, 1, 1))
+                .with_rewards(Value(1000)),
+        )
+        .with_initials(vec![
+            wallet(ALICE)
+                .with(1_000)
+                .with_token(voting_token.clone(), 400)
+                .owns(STAKE_POOL)
+                .committee_member(),
+            wallet(BOB)
+                .with(1_000)
+                .with_token(voting_token, 300)
+                .delegates_to(STAKE_POOL),
+        ])
+        .with_vote_plans(vec![vote_plan(VOTE_PLAN)
+            .owner(ALICE)
+            .consecutive_epoch_dates()
+            .payload_type(PayloadType::Quadratic)
+            .with_proposal(
+                proposal(VoteTestGen::external_proposal_id())
+                    .options(3)
+                    .action_off_chain(),
+            )
+            .with_proposal(
+                proposal(VoteTestGen::external_proposal_id())
+                    .options(3)
+                    .action_off_chain(),
+            )])
+        .build()
+        .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let bob = controller.wallet(BOB).unwrap();
+    let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+
+    controller
+        .cast_vote_quadratic(
+            &alice,
+            &vote_plan,
+            &vote_plan.proposal(0).id(),
+            Choice::new(2),
+            100,
+            &mut ledger,
+        )
+        .unwrap();
+    alice.confirm_transaction();
+    controller
+        .cast_vote_quadratic(
+            &alice,
+            &vote_plan,
+            &vote_plan.proposal(1).id(),
+            Choice::new(1),
+            300,
+            &mut ledger,
+        )
+        .unwrap();
+    alice.confirm_transaction();
+    controller
+        .cast_vote_quadratic(
+            &bob,
+            &vote_plan,
+            &vote_plan.proposal(0).id(),
+            Choice::new(1),
+            225,
+            &mut ledger,
+        )
+        .unwrap();
+
+    ledger.fast_forward_to(BlockDate {
+        epoch: 1,
+        slot_id: 1,
+    });
+
+    controller
+        .tally_vote_public(&alice, &vote_plan, &mut ledger)
+        .unwrap();
+
+    let vote_plans = ledger.ledger.active_vote_plans();
+    assert!(matches!(
+        vote_plans[0].proposals[0].tally,
+        Tally::Quadratic { .. }
+    ));
+    let results = |index: usize| {
+        vote_plans[0].proposals[index]
+            .tally
+            .result()
+            .unwrap()
+            .results()
+            .to_vec()
+    };
+    assert_eq!(
+        results(0),
+        vec![Weight::from(0), Weight::from(15), Weight::from(10)]
+    );
+    assert_eq!(
+        results(1),
+        vec![Weight::from(0), Weight::from(17), Weight::from(0)]
+    );
+}
+
+#[test]
+pub fn quadratic_vote_over_budget() {
+    let voting_token = TokenName::try_from(vec![0u8; TOKEN_NAME_MAX_SIZE]).unwrap();
+
+    let (mut ledger, controller) = prepare_scenario()
+        .with_config(
+            ConfigBuilder::new()
+                .with_fee(LinearFee::new(1, 1, 1))
+                .with_rewards(Value(1000)),
+        )
+        .with_initials(vec![wallet(ALICE)
+            .with(1_000)
+            .with_token(voting_token, 400)
+            .owns(STAKE_POOL)
+            .committee_member()])
+        .with_vote_plans(vec![vote_plan(VOTE_PLAN)
+            .owner(ALICE)
+            .consecutive_epoch_dates()
+            .payload_type(PayloadType::Quadratic)
+            .with_proposal(
+                proposal(VoteTestGen::external_proposal_id())
+                    .options(3)
+                    .action_off_chain(),
+            )
+            .with_proposal(
+                proposal(VoteTestGen::external_proposal_id())
+                    .options(3)
+                    .action_off_chain(),
+            )])
+        .build()
+        .unwrap();
+
+    let mut alice = controller.wallet(ALICE).unwrap();
+    let vote_plan = controller.vote_plan(VOTE_PLAN).unwrap();
+    let inner_vote_plan: VotePlan = vote_plan.clone().into();
+
+    controller
+        .cast_vote_quadratic(
+            &alice,
+            &vote_plan,
+            &vote_plan.proposal(0).id(),
+            Choice::new(1),
+            300,
+            &mut ledger,
+        )
+        .unwrap();
+    alice.confirm_transaction();
+
+    assert_eq!(
+        controller
+            .cast_vote_quadratic(
+                &alice,
+                &vote_plan,
+                &vote_plan.proposal(1).id(),
+                Choice::new(1),
+                101,
+                &mut ledger,
+            )
+            .err()
+            .unwrap(),
+        crate::ledger::ledger::Error::VotePlan(VotePlanLedgerError::VoteError {
+            id: inner_vote_plan.to_id(),
+            reason: ValueCallbackError(VoteError::InsufficientCredits {
+                requested: 101,
+                available: 100,
+            })
+        })
+    );
+}
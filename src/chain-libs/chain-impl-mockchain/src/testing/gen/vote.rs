@@ -16,7 +16,7 @@ use crate::{
 };
 use chain_core::property::BlockDate as BlockDateProp;
 use chain_crypto::digest::DigestOf;
-use chain_vote::{Crs, ElectionPublicKey, Vote};
+use chain_vote::{CreditsOpening, Crs, Vote};
 use rand::SeedableRng;
 use rand_chacha::ChaCha20Rng;
 use rand_core::{CryptoRng, RngCore};
@@ -81,12 +81,18 @@ impl VoteTestGen {
     }
 
     pub fn vote_plan() -> VotePlan {
+        Self::vote_plan_with_payload_type(vote::PayloadType::Public)
+    }
+
+    /// a vote plan with off chain proposals, which can be used with any
+    /// public payload type
+    pub fn vote_plan_with_payload_type(payload_type: vote::PayloadType) -> VotePlan {
         VotePlan::new(
             BlockDate::from_epoch_slot_id(1, 0),
             BlockDate::from_epoch_slot_id(2, 0),
             BlockDate::from_epoch_slot_id(3, 0),
             VoteTestGen::proposals(3),
-            vote::PayloadType::Public,
+            payload_type,
             Vec::new(),
             TokenIdentifier {
                 policy_hash: PolicyHash::from([0u8; POLICY_HASH_SIZE]),
@@ -104,6 +110,15 @@ impl VoteTestGen {
         CommitteeMembersManager::new(&mut rng, crs_seed, threshold, members_no)
     }
 
+    pub fn dkg_committee_members_manager(
+        members_no: usize,
+        threshold: usize,
+    ) -> CommitteeMembersManager {
+        let crs_seed = b"This should be a shared seed among the different committee members. Could be the id of the previous VotePlan";
+        let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+        CommitteeMembersManager::new_dkg(&mut rng, crs_seed, threshold, members_no)
+    }
+
     pub fn private_vote_plan() -> VotePlan {
         Self::private_vote_plan_with_committees_manager(&Self::committee_members_manager(3, 1))
     }
@@ -150,8 +165,7 @@ impl VoteTestGen {
         choice: Choice,
         rng: &mut R,
     ) -> Payload {
-        let encrypting_key =
-            ElectionPublicKey::from_participants(vote_plan.committee_public_keys());
+        let encrypting_key = vote_plan.election_public_key();
 
         let crs = Crs::from_hash(vote_plan.to_id().as_ref());
         let (encrypted_vote, proof) = encrypting_key.encrypt_and_prove_vote(
@@ -170,6 +184,42 @@ impl VoteTestGen {
         }
     }
 
+    /// encrypt a quadratic vote allocating `credits` out of the `budget`
+    /// of the voter, returning the opening of the credits spent once the
+    /// vote is accepted
+    pub fn private_quadratic_vote_cast_payload_for<R: RngCore + CryptoRng>(
+        vote_plan: &VotePlan,
+        proposal: &Proposal,
+        choice: Choice,
+        credits: u64,
+        budget: u64,
+        spent: &CreditsOpening,
+        rng: &mut R,
+    ) -> (Payload, CreditsOpening) {
+        let encrypting_key = vote_plan.election_public_key();
+
+        let crs = Crs::from_hash(vote_plan.to_id().as_ref());
+        let (encrypted_vote, proof, allocation, opening) = vote::encrypt_quadratic_vote(
+            rng,
+            &crs,
+            &encrypting_key,
+            Vote::new(
+                proposal.options().choice_range().clone().max().unwrap() as usize + 1,
+                choice.as_byte() as usize,
+            )
+            .unwrap(),
+            credits,
+            budget,
+            spent,
+        )
+        .expect("not enough credits to cast the vote");
+
+        (
+            Payload::private_quadratic(encrypted_vote, proof, allocation),
+            opening,
+        )
+    }
+
     pub fn vote_cast_payload() -> vote::Payload {
         vote::Payload::public(vote::Choice::new(1))
     }
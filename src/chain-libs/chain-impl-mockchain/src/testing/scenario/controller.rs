@@ -14,6 +14,7 @@ use crate::{
     },
     vote::{Choice, Payload, PayloadType},
 };
+use chain_vote::CreditsOpening;
 
 #[cfg(test)]
 use super::scenario_builder::{prepare_scenario, stake_pool, wallet};
@@ -234,7 +235,41 @@ impl Controller {
             test_ledger,
             |vote_plan, _proposal| match vote_plan.payload_type() {
                 PayloadType::Public => Payload::Public { choice },
-                PayloadType::Private => panic!("this is a private vote plan"),
+                PayloadType::Private | PayloadType::PrivateQuadratic => {
+                    panic!("this is a private vote plan")
+                }
+                PayloadType::RankedChoice | PayloadType::Approval => {
+                    panic!("this vote plan expects multiple choices")
+                }
+                PayloadType::Quadratic => panic!("this vote plan expects credits"),
+            },
+        )
+    }
+
+    /// cast a ranked choice (most preferred first) or an approval ballot,
+    /// depending on the payload type of the vote plan
+    pub fn cast_vote_choices(
+        &self,
+        owner: &Wallet,
+        vote_plan_def: &VotePlanDef,
+        id: &ExternalProposalId,
+        choices: Vec<Choice>,
+        test_ledger: &mut TestLedger,
+    ) -> Result<(), LedgerError> {
+        self.cast_vote(
+            owner,
+            vote_plan_def,
+            id,
+            test_ledger,
+            |vote_plan, _proposal| match vote_plan.payload_type() {
+                PayloadType::RankedChoice => Payload::ranked_choice(choices.clone()),
+                PayloadType::Approval => Payload::approval(choices.clone()),
+                PayloadType::Public
+                | PayloadType::Private
+                | PayloadType::Quadratic
+                | PayloadType::PrivateQuadratic => {
+                    panic!("this vote plan expects a single choice")
+                }
             },
         )
     }
@@ -257,14 +292,83 @@ impl Controller {
             id,
             test_ledger,
             |vote_plan, proposal| match vote_plan.payload_type() {
-                PayloadType::Public => panic!("this is a public vote plan"),
+                PayloadType::Public
+                | PayloadType::RankedChoice
+                | PayloadType::Approval
+                | PayloadType::Quadratic => {
+                    panic!("this is a public vote plan")
+                }
                 PayloadType::Private => {
                     VoteTestGen::private_vote_cast_payload_for(vote_plan, proposal, choice, rng)
                 }
+                PayloadType::PrivateQuadratic => panic!("this vote plan expects credits"),
+            },
+        )
+    }
+
+    /// cast a public quadratic vote allocating `credits` to the `choice`
+    pub fn cast_vote_quadratic(
+        &self,
+        owner: &Wallet,
+        vote_plan_def: &VotePlanDef,
+        id: &ExternalProposalId,
+        choice: Choice,
+        credits: u64,
+        test_ledger: &mut TestLedger,
+    ) -> Result<(), LedgerError> {
+        self.cast_vote(
+            owner,
+            vote_plan_def,
+            id,
+            test_ledger,
+            |vote_plan, _proposal| match vote_plan.payload_type() {
+                PayloadType::Quadratic => Payload::quadratic(choice, credits),
+                _ => panic!("this is not a public quadratic vote plan"),
             },
         )
     }
 
+    /// cast an encrypted quadratic vote allocating `credits` to the `choice`,
+    /// out of the `budget` of the owner. `spent` is updated with the credits
+    /// allocated if the vote is accepted by the ledger.
+    #[allow(clippy::too_many_arguments)]
+    pub fn cast_vote_private_quadratic<R>(
+        &self,
+        owner: &Wallet,
+        vote_plan_def: &VotePlanDef,
+        id: &ExternalProposalId,
+        choice: Choice,
+        credits: u64,
+        budget: u64,
+        spent: &mut CreditsOpening,
+        test_ledger: &mut TestLedger,
+        rng: &mut R,
+    ) -> Result<(), LedgerError>
+    where
+        R: RngCore + CryptoRng,
+    {
+        let mut opening = None;
+        self.cast_vote(
+            owner,
+            vote_plan_def,
+            id,
+            test_ledger,
+            |vote_plan, proposal| match vote_plan.payload_type() {
+                PayloadType::PrivateQuadratic => {
+                    let (payload, new_opening) =
+                        VoteTestGen::private_quadratic_vote_cast_payload_for(
+                            vote_plan, proposal, choice, credits, budget, spent, rng,
+                        );
+                    opening = Some(new_opening);
+                    payload
+                }
+                _ => panic!("this is not a private quadratic vote plan"),
+            },
+        )?;
+        *spent = opening.expect("the vote cast has been produced");
+        Ok(())
+    }
+
     fn cast_vote<F>(
         &self,
         owner: &Wallet,
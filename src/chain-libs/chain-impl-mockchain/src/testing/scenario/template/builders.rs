@@ -262,6 +262,7 @@ pub struct VotePlanDefBuilder {
     tally_date: Option<BlockDate>,
     end_tally_date: Option<BlockDate>,
     committee_keys: Vec<MemberPublicKey>,
+    committee_threshold: Option<u8>,
     proposals: Vec<ProposalDef>,
     voting_token: TokenIdentifier,
 }
@@ -276,6 +277,7 @@ impl VotePlanDefBuilder {
             tally_date: Option::None,
             end_tally_date: Option::None,
             committee_keys: Vec::new(),
+            committee_threshold: None,
             proposals: Vec::new(),
             voting_token: TokenIdentifier {
                 policy_hash: PolicyHash::from([0u8; POLICY_HASH_SIZE]),
@@ -299,6 +301,11 @@ impl VotePlanDefBuilder {
         self
     }
 
+    pub fn committee_threshold(&mut self, committee_threshold: u8) -> &mut Self {
+        self.committee_threshold = Some(committee_threshold);
+        self
+    }
+
     pub fn vote_phases(&mut self, start_epoch: u32, tally_epoch: u32, end_epoch: u32) -> &mut Self {
         self.vote_date = Some(BlockDate {
             epoch: start_epoch,
@@ -351,6 +358,7 @@ impl VotePlanDefBuilder {
             end_tally_date: self.end_tally_date.unwrap(),
             proposals: self.proposals,
             committee_keys: self.committee_keys,
+            committee_threshold: self.committee_threshold,
             voting_token: self.voting_token,
         }
     }
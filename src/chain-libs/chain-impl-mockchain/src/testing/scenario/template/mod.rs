@@ -142,6 +142,7 @@ pub struct VotePlanDef {
     tally_date: BlockDate,
     end_tally_date: BlockDate,
     committee_keys: Vec<MemberPublicKey>,
+    committee_threshold: Option<u8>,
     proposals: Vec<ProposalDef>,
     voting_token: TokenIdentifier,
 }
@@ -195,6 +196,9 @@ impl VotePlanDef {
                 vote_plan.committee_start().epoch,
                 vote_plan.committee_end().epoch,
             );
+        if let Some(committee_threshold) = vote_plan.committee_threshold() {
+            builder.committee_threshold(committee_threshold);
+        }
 
         for proposal in vote_plan.proposals().iter() {
             let mut proposal_builder = ProposalDefBuilder::new(proposal.external_id().clone());
@@ -245,7 +249,7 @@ impl From<VotePlanDef> for VotePlan {
             let _ = proposals.push(proposal.into());
         }
 
-        VotePlan::new(
+        let vote_plan = VotePlan::new(
             dto.vote_date,
             dto.tally_date,
             dto.end_tally_date,
@@ -253,7 +257,11 @@ impl From<VotePlanDef> for VotePlan {
             dto.payload_type,
             dto.committee_keys,
             dto.voting_token,
-        )
+        );
+        match dto.committee_threshold {
+            Some(committee_threshold) => vote_plan.with_committee_threshold(committee_threshold),
+            None => vote_plan,
+        }
     }
 }
 
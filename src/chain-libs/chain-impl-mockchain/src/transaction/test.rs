@@ -198,7 +198,7 @@ impl Arbitrary for TransactionSigningKey {
 
 impl Arbitrary for Witness {
     fn arbitrary<G: Gen>(g: &mut G) -> Self {
-        let opt = u8::arbitrary(g) % 3;
+        let opt = u8::arbitrary(g) % 4;
         match opt {
             0 => Witness::Utxo(SpendingSignature::arbitrary(g)),
             1 => Witness::Account(
@@ -209,6 +209,7 @@ impl Arbitrary for Witness {
                 let sk: SecretKey<Ed25519> = arbitrary_secret_key(g);
                 Witness::OldUtxo(sk.to_public(), [0u8; 32], Signature::arbitrary(g))
             }
+            3 => Witness::Script(crate::script::Witness::new(Arbitrary::arbitrary(g))),
             _ => panic!("not implemented"),
         }
     }
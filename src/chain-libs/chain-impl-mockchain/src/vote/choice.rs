@@ -29,7 +29,7 @@ pub struct Options {
 pub struct Choice(u8);
 
 impl Options {
-    const NUM_CHOICES_MAX: u8 = 0b0001_0000;
+    pub(crate) const NUM_CHOICES_MAX: u8 = 0b0001_0000;
 
     /// create a new `Options` with the given number of available choices
     ///
@@ -46,6 +46,16 @@ pub enum VotePlanLedgerError {
 
     #[error("Private vote plan must contain at least one committee member key")]
     VotePlanMissingCommitteeMemberKey,
+
+    #[error("Committee threshold {threshold} is invalid for a {payload_type:?} vote plan with {members} committee members")]
+    VotePlanInvalidCommitteeThreshold {
+        threshold: u8,
+        members: usize,
+        payload_type: PayloadType,
+    },
+
+    #[error("{payload_type:?} vote plans can only contain off chain proposals")]
+    VotePlanActionNotSupported { payload_type: PayloadType },
 }
 
 impl VotePlanLedger {
@@ -114,12 +124,38 @@ impl VotePlanLedger {
             });
         }
 
-        if let PayloadType::Private = vote_plan.payload_type() {
-            if vote_plan.committee_public_keys().is_empty() {
-                return Err(VotePlanLedgerError::VotePlanMissingCommitteeMemberKey);
+        let payload_type = vote_plan.payload_type();
+        if payload_type.tally_type() == PayloadType::Private
+            && vote_plan.committee_public_keys().is_empty()
+        {
+            return Err(VotePlanLedgerError::VotePlanMissingCommitteeMemberKey);
+        }
+
+        if let Some(threshold) = vote_plan.committee_threshold() {
+            let members = vote_plan.committee_public_keys().len();
+            if payload_type.tally_type() != PayloadType::Private
+                || threshold == 0
+                || threshold as usize > members
+            {
+                return Err(VotePlanLedgerError::VotePlanInvalidCommitteeThreshold {
+                    threshold,
+                    members,
+                    payload_type,
+                });
             }
         }
 
+        // the governance criteria are only defined on single choice results
+        // weighted by the stake
+        if !matches!(payload_type, PayloadType::Public | PayloadType::Private)
+            && vote_plan
+                .proposals()
+                .iter()
+                .any(|proposal| !matches!(proposal.action(), VoteAction::OffChain))
+        {
+            return Err(VotePlanLedgerError::VotePlanActionNotSupported { payload_type });
+        }
+
         let id = vote_plan.to_id();
         let manager = VotePlanManager::new(vote_plan, committee);
 
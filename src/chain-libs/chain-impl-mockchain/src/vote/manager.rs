@@ -9,13 +9,19 @@ use crate::{
     rewards::Ratio,
     stake::Stake,
     tokens::identifier::TokenIdentifier,
-    vote::{self, CommitteeId, Options, Tally, TallyResult, VotePlanStatus, VoteProposalStatus},
+    vote::{
+        self, tally::validate_choices, CommitteeId, Options, RankedChoiceResult, Tally,
+        TallyResult, VotePlanStatus, VoteProposalStatus,
+    },
 };
 use crate::{
     certificate::DecryptedPrivateTallyProposal,
     vote::{Choice, Payload, PayloadType},
 };
-use chain_vote::{committee, Ballot, Crs, ElectionPublicKey, EncryptedTally};
+use chain_vote::{
+    committee, quadratic::credits_weight, Ballot, Crs, ElectionPublicKey, EncryptedCredits,
+    EncryptedTally, QuadraticBallot,
+};
 use imhamt::Hamt;
 use thiserror::Error;
 
@@ -35,18 +41,33 @@ pub struct VotePlanManager {
     plan: Arc<VotePlan>,
     committee: Arc<HashSet<CommitteeId>>,
     proposal_managers: ProposalManagers,
+    spent_credits: Hamt<DefaultHasher, account::Identifier, SpentCredits>,
 }
 
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub enum ValidatedPayload {
     Public(Choice),
     Private(Ballot),
+    RankedChoice(Vec<Choice>),
+    Approval(Vec<Choice>),
+    Quadratic { choice: Choice, credits: u64 },
+    PrivateQuadratic(QuadraticBallot),
 }
 
 #[derive(Clone, Eq, PartialEq, Debug)]
 struct ValidatedVoteCast {
     payload: ValidatedPayload,
     proposal_index: usize,
+    /// the credits spent by the voter in a quadratic vote plan, including
+    /// this vote cast
+    spent_credits: Option<SpentCredits>,
+}
+
+/// the credits spent by a voter across the proposals of a quadratic vote plan
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub(crate) enum SpentCredits {
+    Public(u64),
+    Private(EncryptedCredits),
 }
 
 #[derive(Clone, PartialEq, Eq)]
@@ -74,6 +95,9 @@ pub enum IncrementalTally {
     Public(TallyResult),
     Private(EncryptedTally),
     Decrypted(TallyResult),
+    RankedChoice(RankedChoiceResult),
+    Approval(TallyResult),
+    Quadratic(TallyResult),
 }
 
 #[derive(Debug, Error, Clone, PartialEq, Eq)]
@@ -81,6 +105,12 @@ pub enum VoteError {
     #[error("Invalid option choice")]
     InvalidChoice { options: Options, choice: Choice },
 
+    #[error("The ballot does not contain any choice")]
+    EmptyBallot,
+
+    #[error("The choice {choice:?} appears more than once in the ballot")]
+    DuplicateChoice { choice: Choice },
+
     #[error("Invalid vote plan, expected {expected}")]
     InvalidVotePlan {
         expected: VotePlanId,
@@ -133,6 +163,36 @@ pub enum VoteError {
     // maybe add the expected token id to the error message?
     #[error("Account has no voting power")]
     ZeroVotingPower,
+
+    #[error(
+        "Not enough credits left in the vote plan: {requested} requested, {available} available"
+    )]
+    InsufficientCredits { requested: u64, available: u64 },
+}
+
+impl ValidatedPayload {
+    fn payload_type(&self) -> PayloadType {
+        match self {
+            Self::Public(_) => PayloadType::Public,
+            Self::Private(_) => PayloadType::Private,
+            Self::RankedChoice(_) => PayloadType::RankedChoice,
+            Self::Approval(_) => PayloadType::Approval,
+            Self::Quadratic { .. } => PayloadType::Quadratic,
+            Self::PrivateQuadratic(_) => PayloadType::PrivateQuadratic,
+        }
+    }
+}
+
+impl IncrementalTally {
+    fn payload_type(&self) -> PayloadType {
+        match self {
+            Self::Public(_) => PayloadType::Public,
+            Self::Private(_) | Self::Decrypted(_) => PayloadType::Private,
+            Self::RankedChoice(_) => PayloadType::RankedChoice,
+            Self::Approval(_) => PayloadType::Approval,
+            Self::Quadratic(_) => PayloadType::Quadratic,
+        }
+    }
 }
 
 impl ProposalManager {
@@ -144,13 +204,25 @@ impl ProposalManager {
     /// possible : ZK is not necessarily allowing this).
     ///
     // TODO: improve this docstring to clarify the public/private
-    fn new_public(proposal: &Proposal) -> Self {
-        let results = TallyResult::new(proposal.options().clone());
+    fn new_public(proposal: &Proposal, payload_type: PayloadType) -> Self {
+        let options = proposal.options().clone();
+        let tally = match payload_type {
+            PayloadType::RankedChoice => {
+                IncrementalTally::RankedChoice(RankedChoiceResult::new(options.clone()))
+            }
+            PayloadType::Approval => IncrementalTally::Approval(TallyResult::new(options.clone())),
+            PayloadType::Quadratic => {
+                IncrementalTally::Quadratic(TallyResult::new(options.clone()))
+            }
+            PayloadType::Public | PayloadType::Private | PayloadType::PrivateQuadratic => {
+                IncrementalTally::Public(TallyResult::new(options.clone()))
+            }
+        };
 
         Self {
             votes_by_voters: Hamt::new(),
-            options: proposal.options().clone(),
-            tally: IncrementalTally::Public(results),
+            options,
+            tally,
             action: proposal.action().clone(),
         }
     }
@@ -176,16 +248,15 @@ impl ProposalManager {
 
     /// apply the given vote cast to the proposal
     ///
-    /// if there is already a vote present for this proposal it will
-    /// simply replace the previously set one
+    /// the tally is updated in place, nothing is changed if the vote
+    /// cannot be applied
     ///
-    #[must_use = "Add the vote in a new ProposalManager, does not modify self"]
     pub fn vote(
-        &self,
+        &mut self,
         identifier: account::Identifier,
         payload: ValidatedPayload,
         token_distribution: &TokenDistribution<TokenIdentifier>,
-    ) -> Result<Self, VoteError> {
+    ) -> Result<(), VoteError> {
         // Part of DDoS protection: do not record a new ballot if the account already voted for this
         // proposal. This protects the system from flooding in a system with cheap/free voting
         // transactions.
@@ -194,51 +265,56 @@ impl ProposalManager {
             .insert(identifier.clone(), ())
             .map_err(|_| VoteError::AlreadyVoted)?;
 
-        let tally = if let Some(stake) = token_distribution
+        let stake = token_distribution
             .get_account(&identifier)
             // we ignore the error since at this point we know that the account exists, since it
             // was verified with the input.
             .ok()
             .flatten()
-        {
-            match (self.tally.clone(), payload) {
-                (IncrementalTally::Public(mut result), ValidatedPayload::Public(choice)) => {
+            .ok_or(VoteError::ZeroVotingPower)?;
+
+        match (&mut self.tally, payload) {
+            (IncrementalTally::Public(result), ValidatedPayload::Public(choice)) => {
+                result.add_vote(choice, stake)?;
+            }
+            (IncrementalTally::Private(encrypted_tally), ValidatedPayload::Private(ballot)) => {
+                encrypted_tally.add(&ballot, stake.0);
+            }
+            (IncrementalTally::RankedChoice(result), ValidatedPayload::RankedChoice(ranking)) => {
+                result.add_vote(&ranking, stake)?;
+            }
+            (IncrementalTally::Approval(result), ValidatedPayload::Approval(approvals)) => {
+                // the choices were validated with the payload, adding them cannot fail
+                // half way through
+                for choice in approvals {
                     result.add_vote(choice, stake)?;
-                    IncrementalTally::Public(result)
-                }
-                (
-                    IncrementalTally::Private(mut encrypted_tally),
-                    ValidatedPayload::Private(ballot),
-                ) => {
-                    encrypted_tally.add(&ballot, stake.0);
-                    IncrementalTally::Private(encrypted_tally)
-                }
-                (IncrementalTally::Public(_), ValidatedPayload::Private(_)) => {
-                    return Err(VoteError::InvalidPayloadType {
-                        received: PayloadType::Private,
-                        expected: PayloadType::Public,
-                    })
-                }
-                (IncrementalTally::Private(_), ValidatedPayload::Public(_)) => {
-                    return Err(VoteError::InvalidPayloadType {
-                        received: PayloadType::Public,
-                        expected: PayloadType::Private,
-                    })
-                }
-                (IncrementalTally::Decrypted(_), _) => {
-                    unreachable!("tried to add vote after the voting period")
                 }
             }
-        } else {
-            return Err(VoteError::ZeroVotingPower);
-        };
+            (
+                IncrementalTally::Quadratic(result),
+                ValidatedPayload::Quadratic { choice, credits },
+            ) => {
+                result.add_vote(choice, credits_weight(credits))?;
+            }
+            (
+                IncrementalTally::Private(encrypted_tally),
+                ValidatedPayload::PrivateQuadratic(ballot),
+            ) => {
+                encrypted_tally.add_quadratic(&ballot);
+            }
+            (IncrementalTally::Decrypted(_), _) => {
+                unreachable!("tried to add vote after the voting period")
+            }
+            (tally, payload) => {
+                return Err(VoteError::InvalidPayloadType {
+                    received: payload.payload_type(),
+                    expected: tally.payload_type(),
+                })
+            }
+        }
 
-        Ok(Self {
-            votes_by_voters,
-            tally,
-            options: self.options.clone(),
-            action: self.action.clone(),
-        })
+        self.votes_by_voters = votes_by_voters;
+        Ok(())
     }
 
     fn check_already_voted(&self, identifier: &account::Identifier) -> Result<(), VoteError> {
@@ -258,11 +334,21 @@ impl ProposalManager {
 
         let payload = cast.into_payload();
 
-        match payload {
-            Payload::Public { choice } => Ok(ValidatedPayload::Public(choice)),
-            Payload::Private { .. } => Err(VoteError::InvalidPayloadType {
-                received: PayloadType::Private,
-                expected: PayloadType::Public,
+        match (self.tally.payload_type(), payload) {
+            (PayloadType::Public, Payload::Public { choice }) => {
+                Ok(ValidatedPayload::Public(choice))
+            }
+            (PayloadType::RankedChoice, Payload::RankedChoice { ranking }) => {
+                validate_choices(&self.options, &ranking)?;
+                Ok(ValidatedPayload::RankedChoice(ranking))
+            }
+            (PayloadType::Approval, Payload::Approval { approvals }) => {
+                validate_choices(&self.options, &approvals)?;
+                Ok(ValidatedPayload::Approval(approvals))
+            }
+            (expected, payload) => Err(VoteError::InvalidPayloadType {
+                received: payload.payload_type(),
+                expected,
             }),
         }
     }
@@ -279,30 +365,108 @@ impl ProposalManager {
         let payload = cast.into_payload();
 
         match payload {
-            Payload::Public { .. } => Err(VoteError::InvalidPayloadType {
-                received: PayloadType::Public,
-                expected: PayloadType::Private,
-            }),
             Payload::Private {
                 encrypted_vote,
                 proof,
             } => {
-                let actual_size = encrypted_vote.as_inner().len();
-                let expected_size = self.options.choice_range().len();
-                if actual_size != expected_size {
-                    Err(VoteError::PrivateVoteInvalidSize {
-                        expected: expected_size,
-                        actual: actual_size,
-                    })
-                } else {
-                    Ok(ValidatedPayload::Private(Ballot::try_from_vote_and_proof(
-                        encrypted_vote.as_inner().clone(),
-                        proof.as_inner(),
-                        crs,
-                        election_pk,
-                    )?))
+                self.check_private_vote_size(&encrypted_vote)?;
+                Ok(ValidatedPayload::Private(Ballot::try_from_vote_and_proof(
+                    encrypted_vote.as_inner().clone(),
+                    proof.as_inner(),
+                    crs,
+                    election_pk,
+                )?))
+            }
+            payload => Err(VoteError::InvalidPayloadType {
+                received: payload.payload_type(),
+                expected: PayloadType::Private,
+            }),
+        }
+    }
+
+    fn check_private_vote_size(
+        &self,
+        encrypted_vote: &vote::EncryptedVote,
+    ) -> Result<(), VoteError> {
+        let actual_size = encrypted_vote.as_inner().len();
+        let expected_size = self.options.choice_range().len();
+        if actual_size != expected_size {
+            Err(VoteError::PrivateVoteInvalidSize {
+                expected: expected_size,
+                actual: actual_size,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// validate a quadratic vote of a voter who can still allocate
+    /// `available_credits` in the vote plan, who already spent `spent_credits`
+    pub fn validate_quadratic_vote(
+        &self,
+        identifier: &account::Identifier,
+        cast: VoteCast,
+        available_credits: u64,
+        spent_credits: u64,
+    ) -> Result<(ValidatedPayload, SpentCredits), VoteError> {
+        self.check_already_voted(identifier)?;
+
+        match (self.tally.payload_type(), cast.into_payload()) {
+            (PayloadType::Quadratic, Payload::Quadratic { choice, credits }) => {
+                if credits > available_credits {
+                    return Err(VoteError::InsufficientCredits {
+                        requested: credits,
+                        available: available_credits,
+                    });
                 }
+                Ok((
+                    ValidatedPayload::Quadratic { choice, credits },
+                    SpentCredits::Public(spent_credits + credits),
+                ))
             }
+            (expected, payload) => Err(VoteError::InvalidPayloadType {
+                received: payload.payload_type(),
+                expected,
+            }),
+        }
+    }
+
+    /// validate an encrypted quadratic vote of a voter with the given `budget`,
+    /// who already spent the encrypted `spent_credits` in the vote plan
+    pub fn validate_private_quadratic_vote(
+        &self,
+        identifier: &account::Identifier,
+        cast: VoteCast,
+        crs: &Crs,
+        election_pk: &ElectionPublicKey,
+        budget: u64,
+        spent_credits: &EncryptedCredits,
+    ) -> Result<(ValidatedPayload, SpentCredits), VoteError> {
+        self.check_already_voted(identifier)?;
+
+        match cast.into_payload() {
+            Payload::PrivateQuadratic {
+                encrypted_vote,
+                proof,
+                allocation,
+            } => {
+                self.check_private_vote_size(&encrypted_vote)?;
+                let ballot = QuadraticBallot::try_from_vote_and_proofs(
+                    encrypted_vote.as_inner().clone(),
+                    proof.as_inner(),
+                    allocation.as_inner(),
+                    crs,
+                    election_pk,
+                    budget,
+                    spent_credits,
+                )?;
+                let spent_credits = SpentCredits::Private(ballot.spent_credits().clone());
+                Ok((ValidatedPayload::PrivateQuadratic(ballot), spent_credits))
+            }
+            payload => Err(VoteError::InvalidPayloadType {
+                received: payload.payload_type(),
+                expected: PayloadType::PrivateQuadratic,
+            }),
         }
     }
 
@@ -316,15 +480,20 @@ impl ProposalManager {
     where
         F: FnMut(&VoteAction),
     {
-        let results = match &self.tally {
-            IncrementalTally::Public(result) => result,
+        match &self.tally {
+            IncrementalTally::Public(results) => {
+                if self.check(token_distribution.get_total().into(), governance, results) {
+                    f(&self.action)
+                }
+            }
+            // only off chain proposals are allowed for multiple choices and
+            // quadratic ballots, there is no action to execute
+            IncrementalTally::RankedChoice(_)
+            | IncrementalTally::Approval(_)
+            | IncrementalTally::Quadratic(_) => {}
             IncrementalTally::Private(_) | IncrementalTally::Decrypted(..) => {
                 return Err(TallyError::InvalidPrivacy);
             }
-        };
-
-        if self.check(token_distribution.get_total().into(), governance, results) {
-            f(&self.action)
         }
 
         Ok(Self {
@@ -338,6 +507,7 @@ impl ProposalManager {
     pub fn finalize_private_tally<F>(
         &self,
         committee_pks: &[committee::MemberPublicKey],
+        committee_threshold: Option<u8>,
         decrypted_proposal: &DecryptedPrivateTallyProposal,
         governance: &Governance,
         token_distribution: &TokenDistribution<TokenIdentifier>,
@@ -348,7 +518,10 @@ impl ProposalManager {
     {
         let encrypted_tally = match &self.tally {
             IncrementalTally::Private(encrypted_tally) => encrypted_tally,
-            IncrementalTally::Public(_) => {
+            IncrementalTally::Public(_)
+            | IncrementalTally::RankedChoice(_)
+            | IncrementalTally::Approval(_)
+            | IncrementalTally::Quadratic(_) => {
                 return Err(TallyError::InvalidPrivacy);
             }
             IncrementalTally::Decrypted(_) => return Err(TallyError::TallyAlreadyDecrypted),
@@ -357,11 +530,36 @@ impl ProposalManager {
         let verifiable_tally = chain_vote::Tally {
             votes: decrypted_proposal.tally_result.to_vec(),
         };
-        if !verifiable_tally.verify(
-            encrypted_tally,
-            committee_pks,
-            &decrypted_proposal.decrypt_shares,
-        ) {
+        let verified = match committee_threshold {
+            Some(threshold) => {
+                let decrypt_shares = decrypted_proposal
+                    .member_indices
+                    .iter()
+                    .map(|index| *index as usize)
+                    .zip(decrypted_proposal.decrypt_shares.iter().cloned())
+                    .collect::<Vec<_>>();
+                verifiable_tally.verify_threshold(
+                    encrypted_tally,
+                    committee_pks,
+                    threshold as usize,
+                    &decrypt_shares,
+                )
+            }
+            // the shares of all the members are needed, in the committee order
+            None => {
+                decrypted_proposal
+                    .member_indices
+                    .iter()
+                    .enumerate()
+                    .all(|(position, index)| position == *index as usize)
+                    && verifiable_tally.verify(
+                        encrypted_tally,
+                        committee_pks,
+                        &decrypted_proposal.decrypt_shares,
+                    )
+            }
+        };
+        if !verified {
             return Err(TallyError::InvalidDecryption);
         }
 
@@ -474,19 +672,20 @@ impl ProposalManager {
 impl ProposalManagers {
     fn new(plan: &VotePlan) -> Self {
         match plan.payload_type() {
-            PayloadType::Public => {
+            payload_type @ (PayloadType::Public
+            | PayloadType::RankedChoice
+            | PayloadType::Approval
+            | PayloadType::Quadratic) => {
                 let managers = plan
                     .proposals()
                     .iter()
-                    .map(ProposalManager::new_public)
+                    .map(|proposal| ProposalManager::new_public(proposal, payload_type))
                     .collect();
                 Self::Public { managers }
             }
-            PayloadType::Private => {
+            PayloadType::Private | PayloadType::PrivateQuadratic => {
                 let crs = Arc::new(Crs::from_hash(plan.to_id().as_ref()));
-                let election_pk = Arc::new(ElectionPublicKey::from_participants(
-                    plan.committee_public_keys(),
-                ));
+                let election_pk = Arc::new(plan.election_public_key());
 
                 let managers = plan
                     .proposals()
@@ -532,17 +731,15 @@ impl ProposalManagers {
         token_distribution: &TokenDistribution<TokenIdentifier>,
     ) -> Result<Self, VoteError> {
         let proposal_index = vote_cast.proposal_index;
-        if let Some(manager) = self.managers().get(proposal_index) {
-            let updated_manager =
-                manager.vote(identifier, vote_cast.payload, token_distribution)?;
-            // only clone the array if it does make sens to do so:
-            //
-            // * the index exist
-            // * updated_manager succeed
+        if proposal_index < self.managers().len() {
+            // the managers of the previous state are left untouched, the vote
+            // is applied in place on the copy of the new state
             let mut updated = self.clone();
-            // not unsafe to call this function since we already know this
-            // `proposal_index` already exist in the array
-            unsafe { *updated.managers_mut().get_unchecked_mut(proposal_index) = updated_manager };
+            updated.managers_mut()[proposal_index].vote(
+                identifier,
+                vote_cast.payload,
+                token_distribution,
+            )?;
             Ok(updated)
         } else {
             unreachable!("the vote has been already validated");
@@ -608,12 +805,69 @@ impl ProposalManagers {
         Ok(ValidatedVoteCast {
             payload,
             proposal_index,
+            spent_credits: None,
+        })
+    }
+
+    /// validate the quadratic vote against the proposal, and against the
+    /// credits the voter can still allocate out of their `budget`
+    fn validate_quadratic_vote(
+        &self,
+        identifier: &account::Identifier,
+        cast: VoteCast,
+        budget: u64,
+        spent_credits: Option<&SpentCredits>,
+    ) -> Result<ValidatedVoteCast, VoteError> {
+        let proposal_index = cast.proposal_index() as usize;
+        let manager =
+            self.managers()
+                .get(proposal_index)
+                .ok_or_else(|| VoteError::InvalidVoteProposal {
+                    num_proposals: self.managers().len(),
+                    vote: cast.clone(),
+                })?;
+        let (payload, spent_credits) = match self {
+            Self::Public { .. } => {
+                let spent = match spent_credits {
+                    Some(SpentCredits::Public(spent)) => *spent,
+                    _ => 0,
+                };
+                manager.validate_quadratic_vote(
+                    identifier,
+                    cast,
+                    budget.saturating_sub(spent),
+                    spent,
+                )
+            }
+            Self::Private {
+                crs, election_pk, ..
+            } => {
+                let spent = match spent_credits {
+                    Some(SpentCredits::Private(spent)) => spent.clone(),
+                    _ => EncryptedCredits::zero(),
+                };
+                manager.validate_private_quadratic_vote(
+                    identifier,
+                    cast,
+                    crs,
+                    election_pk,
+                    budget,
+                    &spent,
+                )
+            }
+        }?;
+
+        Ok(ValidatedVoteCast {
+            payload,
+            proposal_index,
+            spent_credits: Some(spent_credits),
         })
     }
 
     pub fn finalize_private_tally<F>(
         &self,
         committee_pks: &[committee::MemberPublicKey],
+        committee_threshold: Option<u8>,
         decrypted_tally: &DecryptedPrivateTally,
         governance: &Governance,
         token_distribution: &TokenDistribution<TokenIdentifier>,
@@ -634,6 +888,7 @@ impl ProposalManagers {
                 {
                     proposals.push(proposal_manager.finalize_private_tally(
                         committee_pks,
+                        committee_threshold,
                         decrypted_proposal,
                         governance,
                         token_distribution,
@@ -664,6 +919,7 @@ impl VotePlanManager {
             plan: Arc::new(plan),
             proposal_managers,
             committee: Arc::new(committee),
+            spent_credits: Hamt::new(),
         }
     }
 
@@ -694,6 +950,9 @@ impl VotePlanManager {
                     IncrementalTally::Decrypted(result) => Tally::Private {
                         state: PrivateTallyState::Decrypted { result },
                     },
+                    IncrementalTally::RankedChoice(result) => Tally::RankedChoice { result },
+                    IncrementalTally::Approval(result) => Tally::Approval { result },
+                    IncrementalTally::Quadratic(result) => Tally::Quadratic { result },
                 },
                 votes: manager.votes_by_voters.clone(),
             })
@@ -708,6 +967,7 @@ impl VotePlanManager {
             vote_end: self.plan().vote_end(),
             committee_end: self.plan().committee_end(),
             committee_public_keys,
+            committee_threshold: self.plan().committee_threshold(),
             proposals,
             voting_token: self.plan().voting_token().clone(),
         }
@@ -725,6 +985,58 @@ impl VotePlanManager {
         &self.committee
     }
 
+    /// the voters and the current tally of every proposal, in the order
+    /// of the proposals of the vote plan
+    pub(crate) fn proposal_states(
+        &self,
+    ) -> impl Iterator<
+        Item = (
+            &Hamt<DefaultHasher, account::Identifier, ()>,
+            &IncrementalTally,
+        ),
+    > {
+        self.proposal_managers
+            .managers()
+            .iter()
+            .map(|manager| (&manager.votes_by_voters, &manager.tally))
+    }
+
+    pub(crate) fn spent_credits(
+        &self,
+    ) -> impl Iterator<Item = (&account::Identifier, &SpentCredits)> {
+        self.spent_credits.iter()
+    }
+
+    /// restore the manager of a vote plan with the votes and the tallies
+    /// recorded before, see [`proposal_states`](Self::proposal_states)
+    ///
+    /// returns `None` if there is not one state per proposal of the plan or
+    /// if a tally does not match the payload type of the plan.
+    pub(crate) fn restore(
+        plan: VotePlan,
+        committee: HashSet<CommitteeId>,
+        proposal_states: Vec<(
+            Hamt<DefaultHasher, account::Identifier, ()>,
+            IncrementalTally,
+        )>,
+        spent_credits: Hamt<DefaultHasher, account::Identifier, SpentCredits>,
+    ) -> Option<Self> {
+        let mut manager = Self::new(plan, committee);
+        let managers = manager.proposal_managers.managers_mut();
+        if managers.len() != proposal_states.len() {
+            return None;
+        }
+        for (manager, (votes_by_voters, tally)) in managers.iter_mut().zip(proposal_states) {
+            if manager.tally.payload_type() != tally.payload_type() {
+                return None;
+            }
+            manager.votes_by_voters = votes_by_voters;
+            manager.tally = tally;
+        }
+        manager.spent_credits = spent_credits;
+        Some(manager)
+    }
+
     /// return true if the vote plan has elapsed i.e. the vote is
     /// no longer interesting to track in the ledger and it can be
     /// GCed.
@@ -749,6 +1061,13 @@ impl VotePlanManager {
     ///   of the managed proposals
     /// * if the payload type of the vote is not the expected one
     ///
+    /// # quadratic votes
+    ///
+    /// The vote plan has no separate credit parameter: the budget of credits
+    /// a voter allocates across all the proposals of a quadratic vote plan
+    /// is their voting power, i.e. the amount of the voting token of the
+    /// vote plan they hold.
+    ///
     pub fn vote(
         &self,
         block_date: BlockDate,
@@ -777,19 +1096,44 @@ impl VotePlanManager {
             });
         }
 
-        let vote = self.proposal_managers.validate_vote(&identifier, cast)?;
+        let token_distribution = token_distribution.token(self.plan.voting_token());
 
-        let proposal_managers = self.proposal_managers.vote(
-            identifier,
-            vote,
-            &token_distribution.token(self.plan.voting_token()),
-        )?;
+        let vote = if self.plan().payload_type().is_quadratic() {
+            // the budget of credits of the voter is their voting power
+            let budget = token_distribution
+                .get_account(&identifier)
+                .ok()
+                .flatten()
+                .map_or(0, |value| value.0);
+            self.proposal_managers.validate_quadratic_vote(
+                &identifier,
+                cast,
+                budget,
+                self.spent_credits.lookup(&identifier),
+            )?
+        } else {
+            self.proposal_managers.validate_vote(&identifier, cast)?
+        };
+
+        let spent_credits = match &vote.spent_credits {
+            Some(spent) => self.spent_credits.insert_or_update_simple(
+                identifier.clone(),
+                spent.clone(),
+                |_| Some(spent.clone()),
+            ),
+            None => self.spent_credits.clone(),
+        };
+
+        let proposal_managers =
+            self.proposal_managers
+                .vote(identifier, vote, &token_distribution)?;
 
         Ok(Self {
             proposal_managers,
             plan: Arc::clone(&self.plan),
             id: self.id.clone(),
             committee: Arc::clone(&self.committee),
+            spent_credits,
         })
     }
 
@@ -815,7 +1159,7 @@ impl VotePlanManager {
             return Err(VoteError::InvalidTallyCommittee);
         }
 
-        if self.plan.payload_type() != PayloadType::Public {
+        if self.plan.payload_type().tally_type() != PayloadType::Public {
             return Err(TallyError::InvalidPrivacy.into());
         }
 
@@ -830,6 +1174,7 @@ impl VotePlanManager {
             plan: Arc::clone(&self.plan),
             id: self.id.clone(),
             committee: Arc::clone(&self.committee),
+            spent_credits: self.spent_credits.clone(),
         })
     }
 
@@ -860,6 +1205,7 @@ impl VotePlanManager {
 
         let proposal_managers = self.proposal_managers.finalize_private_tally(
             committee_pks,
+            self.plan.committee_threshold(),
             decrypted_tally,
             governance,
             &token_distribution.token(self.plan.voting_token()),
@@ -870,6 +1216,7 @@ impl VotePlanManager {
             plan: Arc::clone(&self.plan),
             id: self.id.clone(),
             committee: Arc::clone(&self.committee),
+            spent_credits: self.spent_credits.clone(),
         })
     }
 }
@@ -902,7 +1249,7 @@ mod tests {
         let vote_cast = VoteCast::new(vote_plan.to_id(), 0, vote_cast_payload);
 
         let mut proposal_manager =
-            ProposalManager::new_public(vote_plan.proposals().get(0).unwrap());
+            ProposalManager::new_public(vote_plan.proposals().get(0).unwrap(), PayloadType::Public);
 
         let identifier = TestGen::identifier();
 
@@ -920,7 +1267,7 @@ mod tests {
         let token_distribution =
             TokenDistribution::new(&token_totals, &account_ledger).token(vote_plan.voting_token());
 
-        proposal_manager = proposal_manager
+        proposal_manager
             .vote(identifier, vote, &token_distribution)
             .unwrap();
 
@@ -931,6 +1278,139 @@ mod tests {
 
         assert_eq!(tally.results()[1], 100.into());
     }
+
+    fn proposal_manager_vote(
+        vote_plan: &VotePlan,
+        payload_type: PayloadType,
+        payloads: Vec<(vote::Payload, u64)>,
+    ) -> Result<ProposalManager, VoteError> {
+        let mut proposal_manager =
+            ProposalManager::new_public(vote_plan.proposals().get(0).unwrap(), payload_type);
+        let mut account_ledger = account::Ledger::default();
+        let mut identifiers = Vec::new();
+        for (_, stake) in payloads.iter() {
+            let identifier = TestGen::identifier();
+            account_ledger = account_ledger
+                .add_account(identifier.clone(), Value(0), ())
+                .unwrap()
+                .token_add(&identifier, vote_plan.voting_token().clone(), Value(*stake))
+                .unwrap();
+            identifiers.push(identifier);
+        }
+        let token_totals = Default::default();
+        let token_distribution =
+            TokenDistribution::new(&token_totals, &account_ledger).token(vote_plan.voting_token());
+
+        for ((payload, _), identifier) in payloads.into_iter().zip(identifiers) {
+            let vote_cast = VoteCast::new(vote_plan.to_id(), 0, payload);
+            let vote = proposal_manager.validate_public_vote(&identifier, vote_cast)?;
+            proposal_manager.vote(identifier, vote, &token_distribution)?;
+        }
+        Ok(proposal_manager)
+    }
+
+    #[test]
+    pub fn proposal_manager_ranked_choice_votes() {
+        let vote_plan = VoteTestGen::vote_plan_with_payload_type(PayloadType::RankedChoice);
+        let proposal_manager = proposal_manager_vote(
+            &vote_plan,
+            PayloadType::RankedChoice,
+            vec![
+                (
+                    vote::Payload::ranked_choice(vec![Choice::new(1), Choice::new(0)]),
+                    30,
+                ),
+                (vote::Payload::ranked_choice(vec![Choice::new(0)]), 40),
+                (vote::Payload::ranked_choice(vec![Choice::new(2)]), 20),
+            ],
+        )
+        .unwrap();
+
+        let result = match proposal_manager.tally {
+            IncrementalTally::RankedChoice(result) => result,
+            _ => unreachable!(),
+        };
+        assert_eq!(result.participation(), Stake::from_value(Value(90)));
+        assert_eq!(result.instant_runoff().winner(), Some(Choice::new(0)));
+    }
+
+    #[test]
+    pub fn proposal_manager_approval_votes() {
+        let vote_plan = VoteTestGen::vote_plan_with_payload_type(PayloadType::Approval);
+        let proposal_manager = proposal_manager_vote(
+            &vote_plan,
+            PayloadType::Approval,
+            vec![
+                (
+                    vote::Payload::approval(vec![Choice::new(1), Choice::new(3)]),
+                    30,
+                ),
+                (vote::Payload::approval(vec![Choice::new(3)]), 40),
+            ],
+        )
+        .unwrap();
+
+        let result = match proposal_manager.tally {
+            IncrementalTally::Approval(result) => result,
+            _ => unreachable!(),
+        };
+        assert_eq!(
+            result.results().to_vec(),
+            vec![
+                vote::Weight::from(0),
+                vote::Weight::from(30),
+                vote::Weight::from(0),
+                vote::Weight::from(70)
+            ]
+        );
+    }
+
+    #[test]
+    pub fn proposal_manager_rejects_invalid_ballots() {
+        let vote_plan = VoteTestGen::vote_plan_with_payload_type(PayloadType::Approval);
+        assert_eq!(
+            proposal_manager_vote(
+                &vote_plan,
+                PayloadType::Approval,
+                vec![(vote::Payload::approval(Vec::new()), 10)],
+            )
+            .err()
+            .unwrap(),
+            VoteError::EmptyBallot
+        );
+        assert_eq!(
+            proposal_manager_vote(
+                &vote_plan,
+                PayloadType::Approval,
+                vec![(
+                    vote::Payload::approval(vec![Choice::new(2), Choice::new(2)]),
+                    10
+                )],
+            )
+            .err()
+            .unwrap(),
+            VoteError::DuplicateChoice {
+                choice: Choice::new(2)
+            }
+        );
+        assert_eq!(
+            proposal_manager_vote(
+                &vote_plan,
+                PayloadType::Approval,
+                vec![(
+                    vote::Payload::ranked_choice(vec![Choice::new(2), Choice::new(1)]),
+                    10
+                )],
+            )
+            .err()
+            .unwrap(),
+            VoteError::InvalidPayloadType {
+                received: PayloadType::RankedChoice,
+                expected: PayloadType::Approval,
+            }
+        );
+    }
+
     use rand_core::OsRng;
 
     #[test]
@@ -948,7 +1428,8 @@ mod tests {
 
         let identifier = TestGen::identifier();
 
-        let proposal_manager = ProposalManager::new_public(vote_plan.proposals().get(0).unwrap());
+        let proposal_manager =
+            ProposalManager::new_public(vote_plan.proposals().get(0).unwrap(), PayloadType::Public);
 
         assert_eq!(
             proposal_manager
@@ -1465,9 +1946,9 @@ mod tests {
         );
 
         let mut first_proposal_manager =
-            ProposalManager::new_public(vote_plan.proposals().get(0).unwrap());
+            ProposalManager::new_public(vote_plan.proposals().get(0).unwrap(), PayloadType::Public);
         let mut second_proposal_manager =
-            ProposalManager::new_public(vote_plan.proposals().get(1).unwrap());
+            ProposalManager::new_public(vote_plan.proposals().get(1).unwrap(), PayloadType::Public);
 
         let identifier = TestGen::identifier();
         let proposals = ProposalManagers::new(&vote_plan);
@@ -1486,7 +1967,7 @@ mod tests {
                 ),
             )
             .unwrap();
-        first_proposal_manager = first_proposal_manager
+        first_proposal_manager
             .vote(
                 identifier.clone(),
                 first_vote_cast.payload.clone(),
@@ -1504,7 +1985,7 @@ mod tests {
                 ),
             )
             .unwrap();
-        second_proposal_manager = second_proposal_manager
+        second_proposal_manager
             .vote(
                 identifier.clone(),
                 second_vote_cast.payload.clone(),
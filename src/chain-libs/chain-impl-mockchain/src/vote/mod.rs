@@ -12,13 +12,19 @@ mod privacy;
 mod status;
 mod tally;
 
+pub(crate) use self::manager::{IncrementalTally, SpentCredits};
 pub use self::{
     choice::{Choice, Options},
     committee::CommitteeId,
     ledger::{VotePlanLedger, VotePlanLedgerError},
     manager::{ValidatedPayload, VoteError, VotePlanManager},
-    payload::{EncryptedVote, Payload, PayloadType, ProofOfCorrectVote, TryFromIntError},
-    privacy::encrypt_vote,
+    payload::{
+        CreditAllocation, EncryptedVote, Payload, PayloadType, ProofOfCorrectVote, TryFromIntError,
+    },
+    privacy::{encrypt_quadratic_vote, encrypt_vote},
     status::{VotePlanStatus, VoteProposalStatus},
-    tally::{PrivateTallyState, Tally, TallyError, TallyResult, Weight},
+    tally::{
+        InstantRunoff, PrivateTallyState, RankedChoiceResult, Tally, TallyError, TallyResult,
+        Weight,
+    },
 };
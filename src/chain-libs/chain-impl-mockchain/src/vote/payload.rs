@@ -1,4 +1,4 @@
-use crate::vote::Choice;
+use crate::vote::{Choice, Options};
 use chain_core::packer::Codec;
 use chain_core::property::ReadError;
 use chain_vote::Ciphertext;
@@ -11,6 +11,16 @@ use typed_bytes::{ByteArray, ByteBuilder};
 /// this defines how the vote must be published on chain.
 /// Be careful because the default is set to `Public`.
 ///
+/// `RankedChoice` and `Approval` ballots are published in clear, like
+/// `Public` ones, but a single vote cast carries respectively an ordered
+/// ranking of the options or the set of the approved options.
+///
+/// `Quadratic` and `PrivateQuadratic` ballots allocate credits out of the
+/// voting power of the voter in the vote plan, the weight of a ballot being
+/// the square root of the credits it allocates. `PrivateQuadratic` ballots
+/// are encrypted like `Private` ones, and prove that the voter does not
+/// allocate more than their budget.
+///
 /// ```
 /// use chain_impl_mockchain::vote::PayloadType;
 /// assert_eq!(PayloadType::Public, PayloadType::default());
@@ -21,6 +31,10 @@ use typed_bytes::{ByteArray, ByteBuilder};
 pub enum PayloadType {
     Public = 1,
     Private = 2,
+    RankedChoice = 3,
+    Approval = 4,
+    Quadratic = 5,
+    PrivateQuadratic = 6,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -32,6 +46,24 @@ pub enum Payload {
         encrypted_vote: EncryptedVote,
         proof: ProofOfCorrectVote,
     },
+    /// the options ordered by preference, the most preferred first
+    RankedChoice {
+        ranking: Vec<Choice>,
+    },
+    /// the options approved by the voter
+    Approval {
+        approvals: Vec<Choice>,
+    },
+    /// a single choice, weighted by the square root of the allocated `credits`
+    Quadratic {
+        choice: Choice,
+        credits: u64,
+    },
+    PrivateQuadratic {
+        encrypted_vote: EncryptedVote,
+        proof: ProofOfCorrectVote,
+        allocation: CreditAllocation,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -40,6 +72,9 @@ pub struct ProofOfCorrectVote(chain_vote::ProofOfCorrectVote);
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct EncryptedVote(chain_vote::EncryptedVote);
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CreditAllocation(chain_vote::CreditAllocation);
+
 #[derive(Debug, Error)]
 pub enum TryFromIntError {
     #[error("Found a `0` PayloadType. This is unexpected and known to be an error to read a 0.")]
@@ -60,10 +95,38 @@ impl Payload {
         }
     }
 
+    pub fn ranked_choice(ranking: Vec<Choice>) -> Self {
+        Self::RankedChoice { ranking }
+    }
+
+    pub fn approval(approvals: Vec<Choice>) -> Self {
+        Self::Approval { approvals }
+    }
+
+    pub fn quadratic(choice: Choice, credits: u64) -> Self {
+        Self::Quadratic { choice, credits }
+    }
+
+    pub fn private_quadratic(
+        encrypted_vote: EncryptedVote,
+        proof: ProofOfCorrectVote,
+        allocation: CreditAllocation,
+    ) -> Self {
+        Self::PrivateQuadratic {
+            encrypted_vote,
+            proof,
+            allocation,
+        }
+    }
+
     pub fn payload_type(&self) -> PayloadType {
         match self {
             Self::Public { .. } => PayloadType::Public,
             Self::Private { .. } => PayloadType::Private,
+            Self::RankedChoice { .. } => PayloadType::RankedChoice,
+            Self::Approval { .. } => PayloadType::Approval,
+            Self::Quadratic { .. } => PayloadType::Quadratic,
+            Self::PrivateQuadratic { .. } => PayloadType::PrivateQuadratic,
         }
     }
 
@@ -80,6 +143,18 @@ impl Payload {
             } => bb
                 .sub(|bb| encrypted_vote.serialize_in(bb))
                 .sub(|bb| proof.serialize_in(bb)),
+            Self::RankedChoice { ranking: choices } | Self::Approval { approvals: choices } => {
+                bb.iter8(choices, |bb, choice| bb.u8(choice.as_byte()))
+            }
+            Self::Quadratic { choice, credits } => bb.u8(choice.as_byte()).u64(*credits),
+            Self::PrivateQuadratic {
+                encrypted_vote,
+                proof,
+                allocation,
+            } => bb
+                .sub(|bb| encrypted_vote.serialize_in(bb))
+                .sub(|bb| proof.serialize_in(bb))
+                .sub(|bb| allocation.serialize_in(bb)),
         }
     }
 
@@ -99,10 +174,35 @@ impl Payload {
                     proof,
                 })
             }
+            PayloadType::RankedChoice => read_choices(codec).map(Self::ranked_choice),
+            PayloadType::Approval => read_choices(codec).map(Self::approval),
+            PayloadType::Quadratic => {
+                let choice = codec.get_u8().map(Choice::new)?;
+                let credits = codec.get_be_u64()?;
+                Ok(Self::quadratic(choice, credits))
+            }
+            PayloadType::PrivateQuadratic => {
+                let encrypted_vote = EncryptedVote::read(codec)?;
+                let proof = ProofOfCorrectVote::read(codec)?;
+                let allocation = CreditAllocation::read(codec)?;
+                Ok(Self::private_quadratic(encrypted_vote, proof, allocation))
+            }
         }
     }
 }
 
+fn read_choices(codec: &mut Codec<&[u8]>) -> Result<Vec<Choice>, ReadError> {
+    let len = codec.get_u8()?;
+    if len > Options::NUM_CHOICES_MAX {
+        return Err(ReadError::StructureInvalid(format!(
+            "{} choices in a ballot, maximum is {}",
+            len,
+            Options::NUM_CHOICES_MAX
+        )));
+    }
+    (0..len).map(|_| codec.get_u8().map(Choice::new)).collect()
+}
+
 impl ProofOfCorrectVote {
     pub(crate) fn from_inner(proof: chain_vote::ProofOfCorrectVote) -> Self {
         assert!(
@@ -169,6 +269,32 @@ impl EncryptedVote {
     }
 }
 
+impl CreditAllocation {
+    pub(crate) fn from_inner(allocation: chain_vote::CreditAllocation) -> Self {
+        assert!(
+            allocation.options() <= u8::MAX as usize,
+            "number of options is too large in an internally obtained allocation"
+        );
+        Self(allocation)
+    }
+
+    pub(super) fn as_inner(&self) -> &chain_vote::CreditAllocation {
+        &self.0
+    }
+
+    pub(crate) fn serialize_in(&self, bb: ByteBuilder<Self>) -> ByteBuilder<Self> {
+        bb.bytes(&self.0.to_bytes())
+    }
+
+    pub fn serialize(&self) -> ByteArray<Self> {
+        self.serialize_in(ByteBuilder::new()).finalize()
+    }
+
+    pub(crate) fn read(codec: &mut Codec<&[u8]>) -> Result<Self, ReadError> {
+        chain_vote::CreditAllocation::from_buffer(codec).map(Self)
+    }
+}
+
 impl TryFrom<u8> for PayloadType {
     type Error = TryFromIntError;
     fn try_from(value: u8) -> Result<Self, Self::Error> {
@@ -176,11 +302,32 @@ impl TryFrom<u8> for PayloadType {
             0 => Err(TryFromIntError::Zero),
             1 => Ok(Self::Public),
             2 => Ok(Self::Private),
+            3 => Ok(Self::RankedChoice),
+            4 => Ok(Self::Approval),
+            5 => Ok(Self::Quadratic),
+            6 => Ok(Self::PrivateQuadratic),
             _ => Err(TryFromIntError::InvalidValue { value }),
         }
     }
 }
 
+impl PayloadType {
+    /// the type of the tally certificate used to count the votes: only the
+    /// `Private` and `PrivateQuadratic` ballots need to be decrypted by the
+    /// committee
+    pub fn tally_type(self) -> PayloadType {
+        match self {
+            Self::Private | Self::PrivateQuadratic => Self::Private,
+            Self::Public | Self::RankedChoice | Self::Approval | Self::Quadratic => Self::Public,
+        }
+    }
+
+    /// the ballots of this type spend the credits of the voters in the vote plan
+    pub fn is_quadratic(self) -> bool {
+        matches!(self, Self::Quadratic | Self::PrivateQuadratic)
+    }
+}
+
 impl Default for PayloadType {
     fn default() -> Self {
         PayloadType::Public
@@ -195,22 +342,34 @@ mod tests {
 
     impl Arbitrary for PayloadType {
         fn arbitrary<G: Gen>(g: &mut G) -> Self {
-            if g.next_u32() % 2 == 0 {
-                Self::Public
-            } else {
-                Self::Private
+            match g.next_u32() % 6 {
+                0 => Self::Public,
+                1 => Self::Private,
+                2 => Self::RankedChoice,
+                3 => Self::Approval,
+                4 => Self::Quadratic,
+                _ => Self::PrivateQuadratic,
             }
         }
     }
 
+    fn arbitrary_choices<G: Gen>(g: &mut G) -> Vec<Choice> {
+        let len = g.next_u32() % (Options::NUM_CHOICES_MAX as u32 + 1);
+        (0..len).map(|_| Choice::arbitrary(g)).collect()
+    }
+
     impl Arbitrary for Payload {
         fn arbitrary<G: Gen>(g: &mut G) -> Self {
-            use chain_vote::{MemberCommunicationKey, MemberState, Vote};
+            use chain_vote::{CreditsOpening, MemberCommunicationKey, MemberState, Vote};
             use rand_core::SeedableRng;
 
-            match PayloadType::arbitrary(g) {
+            let payload_type = PayloadType::arbitrary(g);
+            match payload_type {
                 PayloadType::Public => Payload::public(Choice::arbitrary(g)),
-                PayloadType::Private => {
+                PayloadType::RankedChoice => Payload::ranked_choice(arbitrary_choices(g)),
+                PayloadType::Approval => Payload::approval(arbitrary_choices(g)),
+                PayloadType::Quadratic => Payload::quadratic(Choice::arbitrary(g), g.next_u64()),
+                PayloadType::Private | PayloadType::PrivateQuadratic => {
                     let mut seed = [0u8; 32];
                     g.fill_bytes(&mut seed);
                     let mut gen = rand_chacha::ChaCha20Rng::from_seed(seed);
@@ -222,14 +381,31 @@ mod tests {
                     let ek = ElectionPublicKey::from_participants(&participants);
                     let vote_options = 3;
                     let choice = g.next_u32() % vote_options;
-                    let (vote, proof) = ek.encrypt_and_prove_vote(
-                        &mut gen,
-                        &h,
-                        Vote::new(vote_options as usize, choice as usize).unwrap(),
-                    );
-                    Payload::private(
+                    let vote = Vote::new(vote_options as usize, choice as usize).unwrap();
+                    if payload_type == PayloadType::Private {
+                        let (vote, proof) = ek.encrypt_and_prove_vote(&mut gen, &h, vote);
+                        return Payload::private(
+                            EncryptedVote::from_inner(vote),
+                            ProofOfCorrectVote::from_inner(proof),
+                        );
+                    }
+
+                    let budget = g.next_u32() as u64;
+                    let credits = g.next_u64() % (budget + 1);
+                    let (vote, proof, allocation, _) = ek
+                        .encrypt_and_prove_quadratic_vote(
+                            &mut gen,
+                            &h,
+                            vote,
+                            credits,
+                            budget,
+                            &CreditsOpening::new(),
+                        )
+                        .unwrap();
+                    Payload::private_quadratic(
                         EncryptedVote::from_inner(vote),
                         ProofOfCorrectVote::from_inner(proof),
+                        CreditAllocation::from_inner(allocation),
                     )
                 }
             }
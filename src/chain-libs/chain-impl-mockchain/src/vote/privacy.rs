@@ -1,5 +1,5 @@
-use crate::vote::{EncryptedVote, ProofOfCorrectVote};
-use chain_vote::{Crs, ElectionPublicKey, Vote};
+use crate::vote::{CreditAllocation, EncryptedVote, ProofOfCorrectVote};
+use chain_vote::{CreditsOpening, Crs, ElectionPublicKey, InsufficientCredits, Vote};
 use rand_core::{CryptoRng, RngCore};
 
 #[allow(dead_code)]
@@ -15,3 +15,35 @@ pub fn encrypt_vote<R: RngCore + CryptoRng>(
         ProofOfCorrectVote::from_inner(proof),
     )
 }
+
+/// encrypt a quadratic vote allocating `credits` out of the `budget` of the
+/// voter, who already spent the credits opened by `spent` in the vote plan.
+///
+/// The returned `CreditsOpening` must be kept by the voter to cast their
+/// next ballots in the same vote plan.
+pub fn encrypt_quadratic_vote<R: RngCore + CryptoRng>(
+    rng: &mut R,
+    crs: &Crs,
+    public_key: &ElectionPublicKey,
+    vote: Vote,
+    credits: u64,
+    budget: u64,
+    spent: &CreditsOpening,
+) -> Result<
+    (
+        EncryptedVote,
+        ProofOfCorrectVote,
+        CreditAllocation,
+        CreditsOpening,
+    ),
+    InsufficientCredits,
+> {
+    let (ev, proof, allocation, opening) =
+        public_key.encrypt_and_prove_quadratic_vote(rng, crs, vote, credits, budget, spent)?;
+    Ok((
+        EncryptedVote::from_inner(ev),
+        ProofOfCorrectVote::from_inner(proof),
+        CreditAllocation::from_inner(allocation),
+        opening,
+    ))
+}
@@ -16,6 +16,7 @@ pub struct VotePlanStatus {
     pub vote_end: BlockDate,
     pub committee_end: BlockDate,
     pub committee_public_keys: Vec<MemberPublicKey>,
+    pub committee_threshold: Option<u8>,
     pub proposals: Vec<VoteProposalStatus>,
     pub voting_token: TokenIdentifier,
 }
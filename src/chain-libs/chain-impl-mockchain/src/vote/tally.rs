@@ -4,6 +4,7 @@ use crate::{
     vote::{Choice, Options},
 };
 use chain_vote::EncryptedTally;
+use std::collections::BTreeMap;
 use std::fmt;
 use thiserror::Error;
 
@@ -24,10 +25,46 @@ pub struct TallyResult {
     options: Options,
 }
 
+/// the ranked choice ballots, aggregated by ranking
+///
+/// the results are computed with an instant-runoff, see
+/// [`RankedChoiceResult::instant_runoff`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RankedChoiceResult {
+    ballots: BTreeMap<Box<[Choice]>, Weight>,
+
+    options: Options,
+}
+
+/// the rounds of an instant-runoff
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstantRunoff {
+    /// for every round, the weight of the ballots counted for each option.
+    /// The options eliminated in the previous rounds have a null weight.
+    rounds: Vec<Box<[Weight]>>,
+    winner: Option<Choice>,
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum Tally {
-    Public { result: TallyResult },
-    Private { state: PrivateTallyState },
+    Public {
+        result: TallyResult,
+    },
+    Private {
+        state: PrivateTallyState,
+    },
+    RankedChoice {
+        result: RankedChoiceResult,
+    },
+    /// the number of approvals (weighted) received by every option
+    Approval {
+        result: TallyResult,
+    },
+    /// the weight received by every option, each ballot weighing the square
+    /// root of the credits it allocates
+    Quadratic {
+        result: TallyResult,
+    },
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -82,9 +119,13 @@ impl Tally {
         matches!(self, Self::Private { .. })
     }
 
+    /// the results of the tally, per option. For ranked choice votes, see
+    /// [`Tally::ranked_choice_result`] instead.
     pub fn result(&self) -> Option<&TallyResult> {
         match self {
-            Self::Public { result } => Some(result),
+            Self::Public { result } | Self::Approval { result } | Self::Quadratic { result } => {
+                Some(result)
+            }
             Self::Private {
                 state: PrivateTallyState::Decrypted { result },
             } => Some(result),
@@ -92,6 +133,13 @@ impl Tally {
         }
     }
 
+    pub fn ranked_choice_result(&self) -> Option<&RankedChoiceResult> {
+        match self {
+            Self::RankedChoice { result } => Some(result),
+            _ => None,
+        }
+    }
+
     pub fn private_encrypted(&self) -> Result<&EncryptedTally, TallyError> {
         match self {
             Self::Private {
@@ -100,7 +148,10 @@ impl Tally {
             Self::Private {
                 state: PrivateTallyState::Decrypted { .. },
             } => Err(TallyError::TallyAlreadyDecrypted),
-            Self::Public { .. } => Err(TallyError::InvalidPrivacy),
+            Self::Public { .. }
+            | Self::RankedChoice { .. }
+            | Self::Approval { .. }
+            | Self::Quadratic { .. } => Err(TallyError::InvalidPrivacy),
         }
     }
 
@@ -125,6 +176,19 @@ impl TallyResult {
         Self { results, options }
     }
 
+    /// rebuild the results of a tally from the weight of every option
+    ///
+    /// returns `None` if there is not exactly one weight per option.
+    pub(crate) fn from_results(options: Options, results: Vec<Weight>) -> Option<Self> {
+        if results.len() != options.choice_range().len() {
+            return None;
+        }
+        Some(Self {
+            results: results.into(),
+            options,
+        })
+    }
+
     pub fn results(&self) -> &[Weight] {
         &self.results
     }
@@ -173,6 +237,146 @@ impl TallyResult {
     }
 }
 
+impl RankedChoiceResult {
+    pub fn new(options: Options) -> Self {
+        Self {
+            ballots: BTreeMap::new(),
+            options,
+        }
+    }
+
+    pub fn options(&self) -> &Options {
+        &self.options
+    }
+
+    /// rebuild the ballots of a tally, see [`ballots`](Self::ballots)
+    ///
+    /// # Errors
+    ///
+    /// The function will fail if one of the rankings is not a valid ballot
+    pub(crate) fn from_ballots<I>(options: Options, ballots: I) -> Result<Self, VoteError>
+    where
+        I: IntoIterator<Item = (Box<[Choice]>, Weight)>,
+    {
+        let mut result = Self::new(options);
+        for (ranking, weight) in ballots {
+            validate_choices(&result.options, &ranking)?;
+            result.ballots.insert(ranking, weight);
+        }
+        Ok(result)
+    }
+
+    /// the distinct rankings and the total weight of the ballots casting them
+    pub fn ballots(&self) -> impl Iterator<Item = (&[Choice], Weight)> {
+        self.ballots
+            .iter()
+            .map(|(ranking, weight)| (ranking.as_ref(), *weight))
+    }
+
+    pub fn participation(&self) -> Stake {
+        let s: u64 = self.ballots.values().map(|w| w.0).sum();
+        Stake::from_value(Value(s))
+    }
+
+    /// add a ranking, most preferred option first, and its weight on the tally
+    ///
+    /// if the vote's weight is null (`0`), nothing will be changed.
+    ///
+    /// # Errors
+    ///
+    /// The function will fail if the ranking is empty, contains a choice
+    /// which is not a valid `Option` or the same choice more than once
+    pub fn add_vote<W>(&mut self, ranking: &[Choice], weight: W) -> Result<(), VoteError>
+    where
+        W: Into<Weight>,
+    {
+        validate_choices(&self.options, ranking)?;
+
+        let weight = weight.into();
+        if !weight.is_zero() {
+            let total = self.ballots.entry(ranking.into()).or_default();
+            *total = total.saturating_add(weight);
+        }
+        Ok(())
+    }
+
+    /// run an instant-runoff on the ballots
+    ///
+    /// at every round each ballot counts for its most preferred option
+    /// which is not eliminated yet. An option with a strict majority of
+    /// the counted weight wins, otherwise the option with the lowest weight
+    /// is eliminated (in case of a tie, the one with the highest index) and
+    /// a new round starts.
+    pub fn instant_runoff(&self) -> InstantRunoff {
+        let len = self.options.choice_range().len();
+        let mut eliminated = vec![false; len];
+        let mut rounds = Vec::new();
+
+        loop {
+            let mut counts = vec![Weight(0); len];
+            for (ranking, weight) in self.ballots.iter() {
+                if let Some(choice) = ranking
+                    .iter()
+                    .find(|choice| !eliminated[choice.as_byte() as usize])
+                {
+                    let index = choice.as_byte() as usize;
+                    counts[index] = counts[index].saturating_add(*weight);
+                }
+            }
+
+            let counted: u128 = counts.iter().map(|w| w.0 as u128).sum();
+            let winner = counts
+                .iter()
+                .position(|w| w.0 as u128 * 2 > counted)
+                .map(|index| Choice::new(index as u8));
+            let remaining = (0..len).filter(|index| !eliminated[*index]);
+            let last = remaining.clone().rev().min_by_key(|index| counts[*index]);
+            let nr_remaining = remaining.count();
+            rounds.push(counts.into_boxed_slice());
+
+            if winner.is_some() || counted == 0 {
+                return InstantRunoff { rounds, winner };
+            }
+            match last {
+                Some(index) if nr_remaining > 1 => eliminated[index] = true,
+                _ => return InstantRunoff { rounds, winner },
+            }
+        }
+    }
+}
+
+impl InstantRunoff {
+    pub fn rounds(&self) -> &[Box<[Weight]>] {
+        &self.rounds
+    }
+
+    /// the option with a majority of the counted weight in the last round,
+    /// if any ballot was cast
+    pub fn winner(&self) -> Option<Choice> {
+        self.winner
+    }
+}
+
+/// check that the `choices` of a ranked choice or approval ballot are not
+/// empty, valid for the `options` and distinct
+pub(crate) fn validate_choices(options: &Options, choices: &[Choice]) -> Result<(), VoteError> {
+    if choices.is_empty() {
+        return Err(VoteError::EmptyBallot);
+    }
+    for (index, choice) in choices.iter().enumerate() {
+        if !options.validate(*choice) {
+            return Err(VoteError::InvalidChoice {
+                options: options.clone(),
+                choice: *choice,
+            });
+        }
+        if choices[..index].contains(choice) {
+            return Err(VoteError::DuplicateChoice { choice: *choice });
+        }
+    }
+    Ok(())
+}
+
 impl From<Stake> for Weight {
     fn from(stake: Stake) -> Self {
         Self(stake.into())
@@ -205,7 +409,7 @@ impl fmt::Display for Weight {
 
 #[cfg(test)]
 mod tests {
-    use super::{Tally, TallyResult, Weight};
+    use super::{RankedChoiceResult, Tally, TallyResult, Weight};
     use crate::{
         stake::Stake,
         vote::{Choice, Options, VoteError},
@@ -266,6 +470,76 @@ mod tests {
         assert_eq!(*tally_result.options(), options);
     }
 
+    #[test]
+    pub fn ranked_choice_add_invalid_ballots_test() {
+        let options = Options::new_length(3u8).unwrap();
+        let mut result = RankedChoiceResult::new(options.clone());
+        assert_eq!(result.add_vote(&[], Weight(1)), Err(VoteError::EmptyBallot));
+        assert_eq!(
+            result.add_vote(&[Choice::new(1), Choice::new(3)], Weight(1)),
+            Err(VoteError::InvalidChoice {
+                options,
+                choice: Choice::new(3)
+            })
+        );
+        assert_eq!(
+            result.add_vote(&[Choice::new(1), Choice::new(0), Choice::new(1)], Weight(1)),
+            Err(VoteError::DuplicateChoice {
+                choice: Choice::new(1)
+            })
+        );
+        assert_eq!(result.participation(), Stake(0));
+    }
+
+    #[test]
+    pub fn instant_runoff_majority_in_first_round_test() {
+        let mut result = RankedChoiceResult::new(Options::new_length(3u8).unwrap());
+        result
+            .add_vote(&[Choice::new(2), Choice::new(0)], Weight(6))
+            .unwrap();
+        result.add_vote(&[Choice::new(0)], Weight(4)).unwrap();
+
+        let runoff = result.instant_runoff();
+        assert_eq!(runoff.winner(), Some(Choice::new(2)));
+        assert_eq!(runoff.rounds().len(), 1);
+        assert_eq!(
+            runoff.rounds()[0].to_vec(),
+            vec![Weight(4), Weight(0), Weight(6)]
+        );
+    }
+
+    #[test]
+    pub fn instant_runoff_transfers_eliminated_ballots_test() {
+        let mut result = RankedChoiceResult::new(Options::new_length(3u8).unwrap());
+        result.add_vote(&[Choice::new(0)], Weight(4)).unwrap();
+        result
+            .add_vote(&[Choice::new(1), Choice::new(0)], Weight(3))
+            .unwrap();
+        result.add_vote(&[Choice::new(2)], Weight(2)).unwrap();
+        result
+            .add_vote(&[Choice::new(2), Choice::new(1)], Weight(2))
+            .unwrap();
+
+        let runoff = result.instant_runoff();
+        assert_eq!(result.participation(), Stake(11));
+        assert_eq!(
+            runoff.rounds().to_vec(),
+            vec![
+                vec![Weight(4), Weight(3), Weight(4)].into_boxed_slice(),
+                vec![Weight(7), Weight(0), Weight(4)].into_boxed_slice(),
+            ]
+        );
+        assert_eq!(runoff.winner(), Some(Choice::new(0)));
+    }
+
+    #[test]
+    pub fn instant_runoff_without_ballots_test() {
+        let result = RankedChoiceResult::new(Options::new_length(3u8).unwrap());
+        let runoff = result.instant_runoff();
+        assert_eq!(runoff.winner(), None);
+        assert_eq!(runoff.rounds().len(), 1);
+    }
+
     #[quickcheck]
     pub fn tally(tally_result: TallyResult) -> TestResult {
         let tally = Tally::new_public(tally_result.clone());
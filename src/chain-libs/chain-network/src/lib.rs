@@ -5,6 +5,8 @@ pub mod core;
 pub mod data;
 pub mod error;
 pub mod grpc;
+#[cfg(feature = "ntt")]
+pub mod ntt;
 
 /// Version of the protocol implemented by this crate.
 ///
@@ -0,0 +1,313 @@
+use super::codec::{Bytes, Decode, Encode, HandshakeMessage, Method};
+use super::connection::{Connection, InboundStream, Streams};
+use super::frame::{self, Frame, Kind, StreamId};
+
+use crate::data::block::{Block, BlockEvent, BlockId, BlockIds, ChainPullRequest, Header};
+use crate::data::fragment::{Fragment, FragmentIds};
+use crate::data::p2p::{AuthenticatedNodeId, NodeId};
+use crate::data::{Gossip, HandshakeResponse};
+use crate::error::{Code, Error, HandshakeError};
+use crate::PROTOCOL_VERSION;
+use futures::prelude::*;
+use tokio::io::{AsyncWriteExt, BufReader};
+use tokio::net::{TcpStream, ToSocketAddrs};
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+/// The inbound subscription stream of block events.
+pub type BlockSubscription = InboundStream<BlockEvent>;
+
+/// The inbound subscription stream of fragments.
+pub type FragmentSubscription = InboundStream<Fragment>;
+
+/// The inbound subscription stream of P2P gossip.
+pub type GossipSubscription = InboundStream<Gossip>;
+
+/// Client of the NTT protocol.
+///
+/// All requests made with a client and its clones are multiplexed
+/// over a single TCP connection, which is closed when the client,
+/// its clones and all streams obtained from them are dropped.
+#[derive(Clone)]
+pub struct Client {
+    conn: Connection,
+    next_stream: Arc<AtomicU32>,
+}
+
+impl Client {
+    /// Connects to the NTT server at the given address.
+    pub async fn connect<A: ToSocketAddrs>(addr: A) -> Result<Self, Error> {
+        let socket = TcpStream::connect(addr).await.map_err(frame::io_error)?;
+        Self::new(socket).await
+    }
+
+    /// Establishes the protocol session over an already connected socket.
+    ///
+    /// This function spawns the tasks driving the connection on the
+    /// current Tokio runtime.
+    pub async fn new(mut socket: TcpStream) -> Result<Self, Error> {
+        socket
+            .write_all(&frame::preamble())
+            .await
+            .map_err(frame::io_error)?;
+        let (reader, writer) = socket.into_split();
+        let mut reader = BufReader::new(reader);
+        let version = frame::read_preamble(&mut reader).await?;
+        if version != PROTOCOL_VERSION {
+            return Err(Error::new(
+                Code::FailedPrecondition,
+                format!("unsupported protocol version {}", version),
+            ));
+        }
+
+        let streams = Arc::new(Streams::new());
+        let (conn, write_task) = Connection::new(writer, streams.clone());
+        tokio::spawn(write_task);
+        let reader_conn = conn.clone();
+        tokio::spawn(async move {
+            // Frames are routed until the server closes the connection or
+            // sends something that cannot be parsed.
+            while let Ok(Some(frame)) = frame::read_frame(&mut reader).await {
+                let id = frame.stream;
+                if frame.kind == Kind::Credit {
+                    if streams.grant(&frame).is_err() {
+                        break;
+                    }
+                } else if !streams.route(frame) {
+                    reader_conn.send_control(Frame::control(id, Kind::Cancel));
+                }
+            }
+            streams.close();
+        });
+
+        Ok(Client {
+            conn,
+            next_stream: Arc::new(AtomicU32::new(1)),
+        })
+    }
+
+    async fn open<T>(
+        &self,
+        method: Method,
+        request: &impl Encode,
+    ) -> Result<InboundStream<T>, Error>
+    where
+        T: Decode,
+    {
+        let (_, stream) = self.open_stream(method, request).await?;
+        Ok(stream)
+    }
+
+    async fn open_stream<T>(
+        &self,
+        method: Method,
+        request: &impl Encode,
+    ) -> Result<(StreamId, InboundStream<T>), Error>
+    where
+        T: Decode,
+    {
+        let id: StreamId = self.next_stream.fetch_add(1, Ordering::Relaxed);
+        let frames = self.conn.streams().register(id);
+        // Created before sending the request so that the stream
+        // unregisters itself if sending fails.
+        let stream = InboundStream::new(self.conn.clone(), id, frames, true);
+        let mut payload = vec![method as u8];
+        request.encode(&mut payload);
+        self.conn.send(Frame::new(id, Kind::Open, payload)).await?;
+        Ok((id, stream))
+    }
+
+    async fn open_with_outbound<T, S>(
+        &self,
+        method: Method,
+        outbound: S,
+    ) -> Result<InboundStream<T>, Error>
+    where
+        T: Decode,
+        S: Stream + Send + 'static,
+        S::Item: Encode,
+    {
+        let (id, stream) = self.open_stream(method, &()).await?;
+        let sender = self.conn.open_outbound(id);
+        tokio::spawn(async move {
+            // Sending only fails when the connection is gone or the
+            // stream has been closed, which the inbound stream will report.
+            let _ = sender.send_stream(outbound).await;
+        });
+        Ok(stream)
+    }
+
+    async fn unary<T: Decode>(&self, method: Method, request: &impl Encode) -> Result<T, Error> {
+        let mut stream = self.open(method, request).await?;
+        match stream.next().await {
+            Some(res) => res,
+            None => Err(Error::new(
+                Code::Internal,
+                "the response stream has ended without a result",
+            )),
+        }
+    }
+
+    async fn completion(mut stream: InboundStream<()>) -> Result<(), Error> {
+        while let Some(res) = stream.next().await {
+            res?;
+        }
+        Ok(())
+    }
+
+    /// Requests the identifier of the genesis block from the service node.
+    ///
+    /// The implementation can also perform version information checks to
+    /// ascertain that the client use compatible protocol versions.
+    ///
+    /// This method should be called first after establishing the client
+    /// connection.
+    pub async fn handshake(&mut self, nonce: &[u8]) -> Result<HandshakeResponse, HandshakeError> {
+        let res: HandshakeMessage = self
+            .unary(Method::Handshake, &Bytes(nonce))
+            .await
+            .map_err(HandshakeError::Rpc)?;
+        if res.version != PROTOCOL_VERSION {
+            return Err(HandshakeError::UnsupportedVersion(
+                res.version.to_string().into(),
+            ));
+        }
+        let block0_id =
+            BlockId::try_from(&res.block0[..]).map_err(HandshakeError::InvalidBlock0)?;
+        let node_id = NodeId::try_from(&res.node_id[..]).map_err(HandshakeError::InvalidNodeId)?;
+        let auth = node_id
+            .authenticated(&res.signature)
+            .map_err(HandshakeError::MalformedSignature)?;
+        Ok(HandshakeResponse {
+            block0_id,
+            auth,
+            nonce: res.nonce.into(),
+        })
+    }
+
+    pub async fn client_auth(&mut self, auth: AuthenticatedNodeId) -> Result<(), Error> {
+        let stream = self.open(Method::ClientAuth, &auth).await?;
+        Self::completion(stream).await
+    }
+
+    /// One-off request for a list of peers known to the remote node.
+    pub async fn peers(&mut self, limit: u32) -> Result<Gossip, Error> {
+        self.unary(Method::Peers, &limit).await
+    }
+
+    /// Requests the header of the tip block in the node's chain.
+    pub async fn tip(&mut self) -> Result<Header, Error> {
+        self.unary(Method::Tip, &()).await
+    }
+
+    /// Requests the identified blocks in a streamed response.
+    pub async fn get_blocks(&mut self, ids: BlockIds) -> Result<InboundStream<Block>, Error> {
+        self.open(Method::GetBlocks, &ids).await
+    }
+
+    /// Requests the headers of the identified blocks in a streamed response.
+    pub async fn get_headers(&mut self, ids: BlockIds) -> Result<InboundStream<Header>, Error> {
+        self.open(Method::GetHeaders, &ids).await
+    }
+
+    /// Requests the identified fragments in a streamed response.
+    pub async fn get_fragments(
+        &mut self,
+        ids: FragmentIds,
+    ) -> Result<InboundStream<Fragment>, Error> {
+        self.open(Method::GetFragments, &ids).await
+    }
+
+    /// Stream blocks from the provided range.
+    pub async fn pull_blocks(
+        &mut self,
+        from: BlockIds,
+        to: BlockId,
+    ) -> Result<InboundStream<Block>, Error> {
+        self.open(Method::PullBlocks, &ChainPullRequest { from, to })
+            .await
+    }
+
+    /// Stream blocks from the first of the given starting points
+    /// that is found in the peer's chain, to the chain's tip.
+    pub async fn pull_blocks_to_tip(
+        &mut self,
+        from: BlockIds,
+    ) -> Result<InboundStream<Block>, Error> {
+        self.open(Method::PullBlocksToTip, &from).await
+    }
+
+    /// Requests headers of blocks in the blockchain's chronological order,
+    /// in the range between the latest of the given starting points, and
+    /// the given ending point.
+    pub async fn pull_headers(
+        &mut self,
+        from: BlockIds,
+        to: BlockId,
+    ) -> Result<InboundStream<Header>, Error> {
+        self.open(Method::PullHeaders, &ChainPullRequest { from, to })
+            .await
+    }
+
+    /// The outbound counterpart of `pull_headers`, called in response to a
+    /// `BlockEvent::Missing` solicitation.
+    pub async fn push_headers<S>(&mut self, headers: S) -> Result<(), Error>
+    where
+        S: Stream<Item = Header> + Send + Sync + 'static,
+    {
+        let stream = self
+            .open_with_outbound(Method::PushHeaders, headers)
+            .await?;
+        Self::completion(stream).await
+    }
+
+    /// Uploads blocks to the service in response to `BlockEvent::Solicit`.
+    pub async fn upload_blocks<S>(&mut self, blocks: S) -> Result<(), Error>
+    where
+        S: Stream<Item = Block> + Send + Sync + 'static,
+    {
+        let stream = self
+            .open_with_outbound(Method::UploadBlocks, blocks)
+            .await?;
+        Self::completion(stream).await
+    }
+
+    /// Establishes a bidirectional stream of notifications for blocks
+    /// created or accepted by either of the peers.
+    pub async fn block_subscription<S>(&mut self, outbound: S) -> Result<BlockSubscription, Error>
+    where
+        S: Stream<Item = Header> + Send + Sync + 'static,
+    {
+        let stream = self
+            .open_with_outbound(Method::BlockSubscription, outbound)
+            .await?;
+        Ok(stream)
+    }
+
+    /// Establishes a bidirectional stream for exchanging fragments
+    /// created or accepted by either of the peers.
+    pub async fn fragment_subscription<S>(
+        &mut self,
+        outbound: S,
+    ) -> Result<FragmentSubscription, Error>
+    where
+        S: Stream<Item = Fragment> + Send + Sync + 'static,
+    {
+        let stream = self
+            .open_with_outbound(Method::FragmentSubscription, outbound)
+            .await?;
+        Ok(stream)
+    }
+
+    /// Establishes a bidirectional stream for exchanging network gossip.
+    pub async fn gossip_subscription<S>(&mut self, outbound: S) -> Result<GossipSubscription, Error>
+    where
+        S: Stream<Item = Gossip> + Send + Sync + 'static,
+    {
+        let stream = self
+            .open_with_outbound(Method::GossipSubscription, outbound)
+            .await?;
+        Ok(stream)
+    }
+}
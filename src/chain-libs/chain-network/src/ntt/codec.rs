@@ -0,0 +1,481 @@
+//! Binary encoding of the protocol messages carried in frame payloads.
+//!
+//! Byte strings are prefixed with their big-endian `u32` length,
+//! sequences are prefixed with the big-endian `u32` count of items.
+
+use crate::data::block::{self, Block, BlockEvent, BlockId, BlockIds, ChainPullRequest, Header};
+use crate::data::fragment::{self, Fragment, FragmentIds};
+use crate::data::gossip::{Gossip, Node};
+use crate::data::p2p::{AuthenticatedNodeId, NodeId};
+use crate::error::{Code, Error};
+
+pub trait Encode {
+    fn encode(&self, buf: &mut Vec<u8>);
+}
+
+pub trait Decode: Sized {
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, Error>;
+}
+
+/// Method codes carried in the payload of `Open` frames.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub(super) enum Method {
+    Handshake = 0,
+    ClientAuth = 1,
+    Tip = 2,
+    Peers = 3,
+    GetBlocks = 4,
+    GetHeaders = 5,
+    GetFragments = 6,
+    PullHeaders = 7,
+    PullBlocks = 8,
+    PullBlocksToTip = 9,
+    PushHeaders = 10,
+    UploadBlocks = 11,
+    BlockSubscription = 12,
+    FragmentSubscription = 13,
+    GossipSubscription = 14,
+}
+
+impl Method {
+    /// Whether the client sends a stream of items after opening
+    /// a stream with this method.
+    pub fn has_inbound_stream(self) -> bool {
+        matches!(
+            self,
+            Method::PushHeaders
+                | Method::UploadBlocks
+                | Method::BlockSubscription
+                | Method::FragmentSubscription
+                | Method::GossipSubscription
+        )
+    }
+}
+
+impl TryFrom<u8> for Method {
+    type Error = Error;
+
+    fn try_from(code: u8) -> Result<Self, Error> {
+        use Method::*;
+
+        let method = match code {
+            0 => Handshake,
+            1 => ClientAuth,
+            2 => Tip,
+            3 => Peers,
+            4 => GetBlocks,
+            5 => GetHeaders,
+            6 => GetFragments,
+            7 => PullHeaders,
+            8 => PullBlocks,
+            9 => PullBlocksToTip,
+            10 => PushHeaders,
+            11 => UploadBlocks,
+            12 => BlockSubscription,
+            13 => FragmentSubscription,
+            14 => GossipSubscription,
+            _ => {
+                return Err(Error::new(
+                    Code::Unimplemented,
+                    format!("unknown method {}", code),
+                ))
+            }
+        };
+        Ok(method)
+    }
+}
+
+pub struct Reader<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(buf: &'a [u8]) -> Self {
+        Reader { buf }
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], Error> {
+        if self.buf.len() < n {
+            return Err(Error::new(Code::InvalidArgument, "truncated message"));
+        }
+        let (head, tail) = self.buf.split_at(n);
+        self.buf = tail;
+        Ok(head)
+    }
+
+    pub fn u8(&mut self) -> Result<u8, Error> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub fn u32(&mut self) -> Result<u32, Error> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    pub fn bytes(&mut self) -> Result<&'a [u8], Error> {
+        let len = self.u32()? as usize;
+        self.take(len)
+    }
+
+    /// Returns the rest of the message.
+    pub fn remaining(&mut self) -> &'a [u8] {
+        std::mem::take(&mut self.buf)
+    }
+
+    pub fn read<T: Decode>(&mut self) -> Result<T, Error> {
+        T::decode(self)
+    }
+
+    pub fn finish(self) -> Result<(), Error> {
+        if self.buf.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::new(
+                Code::InvalidArgument,
+                "trailing bytes after the message",
+            ))
+        }
+    }
+}
+
+/// Decodes a message that takes up the whole payload.
+pub(super) fn decode<T: Decode>(payload: &[u8]) -> Result<T, Error> {
+    let mut reader = Reader::new(payload);
+    let message = reader.read()?;
+    reader.finish()?;
+    Ok(message)
+}
+
+pub(super) fn encode<T: Encode + ?Sized>(message: &T) -> Vec<u8> {
+    let mut buf = Vec::new();
+    message.encode(&mut buf);
+    buf
+}
+
+fn put_u32(buf: &mut Vec<u8>, value: u32) {
+    buf.extend_from_slice(&value.to_be_bytes());
+}
+
+fn put_bytes(buf: &mut Vec<u8>, bytes: &[u8]) {
+    put_u32(buf, bytes.len() as u32);
+    buf.extend_from_slice(bytes);
+}
+
+fn put_seq<'a, I, T>(buf: &mut Vec<u8>, items: I)
+where
+    I: ExactSizeIterator<Item = &'a T>,
+    T: Encode + 'a,
+{
+    put_u32(buf, items.len() as u32);
+    for item in items {
+        item.encode(buf);
+    }
+}
+
+fn read_seq<'a, F, T>(reader: &mut Reader<'a>, mut f: F) -> Result<Vec<T>, Error>
+where
+    F: FnMut(&mut Reader<'a>) -> Result<T, Error>,
+{
+    let count = reader.u32()? as usize;
+    // Every item takes at least one byte, so a count over the remaining
+    // length is malformed and must not be used to preallocate.
+    if count > reader.buf.len() {
+        return Err(Error::new(Code::InvalidArgument, "truncated message"));
+    }
+    (0..count).map(|_| f(reader)).collect()
+}
+
+impl Encode for () {
+    fn encode(&self, _: &mut Vec<u8>) {}
+}
+
+impl Decode for () {
+    fn decode(_: &mut Reader<'_>) -> Result<Self, Error> {
+        Ok(())
+    }
+}
+
+impl Encode for u32 {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        put_u32(buf, *self)
+    }
+}
+
+impl Decode for u32 {
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, Error> {
+        reader.u32()
+    }
+}
+
+macro_rules! bytes_codec {
+    ($ty:ty) => {
+        impl Encode for $ty {
+            fn encode(&self, buf: &mut Vec<u8>) {
+                put_bytes(buf, self.as_bytes())
+            }
+        }
+
+        impl Decode for $ty {
+            fn decode(reader: &mut Reader<'_>) -> Result<Self, Error> {
+                Ok(<$ty>::from_bytes(reader.bytes()?))
+            }
+        }
+    };
+}
+
+bytes_codec!(Block);
+bytes_codec!(Header);
+bytes_codec!(Fragment);
+bytes_codec!(Node);
+
+impl Encode for BlockId {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        put_bytes(buf, self.as_bytes())
+    }
+}
+
+impl Decode for BlockId {
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, Error> {
+        BlockId::try_from(reader.bytes()?)
+    }
+}
+
+impl Encode for BlockIds {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        put_seq(buf, self.iter())
+    }
+}
+
+impl Decode for BlockIds {
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, Error> {
+        let ids = read_seq(reader, |r| r.bytes())?;
+        block::try_ids_from_iter(ids)
+    }
+}
+
+impl Encode for FragmentIds {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        put_u32(buf, self.len() as u32);
+        for id in self.iter() {
+            put_bytes(buf, id.as_bytes());
+        }
+    }
+}
+
+impl Decode for FragmentIds {
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, Error> {
+        let ids = read_seq(reader, |r| r.bytes())?;
+        fragment::try_ids_from_iter(ids)
+    }
+}
+
+impl Encode for Gossip {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        put_seq(buf, self.nodes.iter())
+    }
+}
+
+impl Decode for Gossip {
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, Error> {
+        let nodes = read_seq(reader, Node::decode)?;
+        Ok(Gossip {
+            nodes: nodes.into(),
+        })
+    }
+}
+
+impl Encode for ChainPullRequest {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        self.from.encode(buf);
+        self.to.encode(buf);
+    }
+}
+
+impl Decode for ChainPullRequest {
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, Error> {
+        let from = reader.read()?;
+        let to = reader.read()?;
+        Ok(ChainPullRequest { from, to })
+    }
+}
+
+impl Encode for BlockEvent {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        match self {
+            BlockEvent::Announce(header) => {
+                buf.push(0);
+                header.encode(buf);
+            }
+            BlockEvent::Solicit(ids) => {
+                buf.push(1);
+                ids.encode(buf);
+            }
+            BlockEvent::Missing(req) => {
+                buf.push(2);
+                req.encode(buf);
+            }
+        }
+    }
+}
+
+impl Decode for BlockEvent {
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, Error> {
+        match reader.u8()? {
+            0 => Ok(BlockEvent::Announce(reader.read()?)),
+            1 => Ok(BlockEvent::Solicit(reader.read()?)),
+            2 => Ok(BlockEvent::Missing(reader.read()?)),
+            tag => Err(Error::new(
+                Code::InvalidArgument,
+                format!("unknown block event tag {}", tag),
+            )),
+        }
+    }
+}
+
+impl Encode for AuthenticatedNodeId {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        put_bytes(buf, self.id().as_bytes());
+        put_bytes(buf, self.signature());
+    }
+}
+
+impl Decode for AuthenticatedNodeId {
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, Error> {
+        let node_id = NodeId::try_from(reader.bytes()?)?;
+        node_id.authenticated(reader.bytes()?)
+    }
+}
+
+/// A byte string encoded with its length.
+pub(super) struct Bytes<'a>(pub &'a [u8]);
+
+impl Encode for Bytes<'_> {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        put_bytes(buf, self.0)
+    }
+}
+
+/// The response to the `Handshake` request. The identifiers are kept
+/// in the raw form so that the client can report which one is invalid.
+pub(super) struct HandshakeMessage {
+    pub version: u32,
+    pub block0: Vec<u8>,
+    pub node_id: Vec<u8>,
+    pub signature: Vec<u8>,
+    pub nonce: Vec<u8>,
+}
+
+impl Encode for HandshakeMessage {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        put_u32(buf, self.version);
+        put_bytes(buf, &self.block0);
+        put_bytes(buf, &self.node_id);
+        put_bytes(buf, &self.signature);
+        put_bytes(buf, &self.nonce);
+    }
+}
+
+impl Decode for HandshakeMessage {
+    fn decode(reader: &mut Reader<'_>) -> Result<Self, Error> {
+        Ok(HandshakeMessage {
+            version: reader.u32()?,
+            block0: reader.bytes()?.into(),
+            node_id: reader.bytes()?.into(),
+            signature: reader.bytes()?.into(),
+            nonce: reader.bytes()?.into(),
+        })
+    }
+}
+
+fn code_to_u8(code: Code) -> u8 {
+    match code {
+        Code::Canceled => 0,
+        Code::Unknown => 1,
+        Code::InvalidArgument => 2,
+        Code::NotFound => 3,
+        Code::FailedPrecondition => 4,
+        Code::Aborted => 5,
+        Code::Unimplemented => 6,
+        Code::Internal => 7,
+        Code::Unavailable => 8,
+        // When a new case has to be added here, remember to
+        // add the corresponding case in code_from_u8 below.
+    }
+}
+
+fn code_from_u8(code: u8) -> Code {
+    match code {
+        0 => Code::Canceled,
+        2 => Code::InvalidArgument,
+        3 => Code::NotFound,
+        4 => Code::FailedPrecondition,
+        5 => Code::Aborted,
+        6 => Code::Unimplemented,
+        7 => Code::Internal,
+        8 => Code::Unavailable,
+        _ => Code::Unknown,
+    }
+}
+
+/// Encodes the payload of an `Error` frame.
+pub(super) fn encode_error(err: &Error) -> Vec<u8> {
+    // The code is carried separately, so only the underlying cause
+    // needs to be described.
+    let message = match std::error::Error::source(err) {
+        Some(source) => source.to_string(),
+        None => err.to_string(),
+    };
+    let mut buf = vec![code_to_u8(err.code())];
+    buf.extend_from_slice(message.as_bytes());
+    buf
+}
+
+/// Decodes the payload of an `Error` frame.
+pub(super) fn decode_error(payload: &[u8]) -> Error {
+    match payload.split_first() {
+        Some((code, message)) => Error::new(
+            code_from_u8(*code),
+            String::from_utf8_lossy(message).into_owned(),
+        ),
+        None => Error::new(Code::Unknown, "the peer reported an unspecified error"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block_id(byte: u8) -> BlockId {
+        BlockId::try_from(&[byte; 32][..]).unwrap()
+    }
+
+    #[test]
+    fn block_event_roundtrip() {
+        let event = BlockEvent::Missing(ChainPullRequest {
+            from: vec![block_id(1), block_id(2)].into(),
+            to: block_id(3),
+        });
+        match decode::<BlockEvent>(&encode(&event)).unwrap() {
+            BlockEvent::Missing(req) => {
+                assert_eq!(&req.from[..], &[block_id(1), block_id(2)]);
+                assert_eq!(req.to, block_id(3));
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn truncated_message_is_rejected() {
+        let ids: BlockIds = vec![block_id(1)].into();
+        let bytes = encode(&ids);
+        assert!(decode::<BlockIds>(&bytes[..bytes.len() - 1]).is_err());
+        assert!(decode::<BlockIds>(&[0, 0, 0xff, 0xff]).is_err());
+    }
+
+    #[test]
+    fn error_roundtrip() {
+        let err = Error::new(Code::NotFound, "no such block");
+        let decoded = decode_error(&encode_error(&err));
+        assert_eq!(decoded.code(), Code::NotFound);
+    }
+}
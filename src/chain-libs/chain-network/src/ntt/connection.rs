@@ -0,0 +1,569 @@
+//! Multiplexing of protocol streams over a single connection.
+
+use super::codec::{self, Decode};
+use super::frame::{self, Frame, Kind, StreamId};
+use crate::error::{Code, Error};
+use futures::prelude::*;
+use tokio::io::{AsyncWrite, AsyncWriteExt, BufWriter};
+use tokio::sync::{mpsc, Semaphore};
+
+use std::collections::{HashMap, HashSet};
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+/// Number of outbound frames that can be queued for writing
+/// before the senders have to wait.
+const OUTBOUND_BUFFER: usize = 64;
+
+/// Number of `Data` frames the sender of a stream direction can have
+/// in flight before it has to wait for credit from the receiver.
+const STREAM_WINDOW: u32 = 16;
+
+/// Number of consumed `Data` frames for which the receiver
+/// grants credit at once.
+const CREDIT_BATCH: u32 = STREAM_WINDOW / 2;
+
+/// Number of inbound frames that can be buffered per stream: a full window
+/// of `Data` frames and the frame terminating the stream direction.
+/// A peer that sends more than it has credit for has the stream reset,
+/// so that it does not hold up the frames of the other streams
+/// on the connection.
+const INBOUND_BUFFER: usize = STREAM_WINDOW as usize + 1;
+
+#[derive(Default)]
+struct Table {
+    senders: HashMap<StreamId, mpsc::Sender<Frame>>,
+    windows: HashMap<StreamId, Arc<Semaphore>>,
+    overrun: HashSet<StreamId>,
+}
+
+/// Routing table for the frames received on the connection,
+/// and the send credit of the outbound stream directions.
+pub(super) struct Streams {
+    inner: Mutex<Option<Table>>,
+}
+
+impl Streams {
+    pub fn new() -> Self {
+        Streams {
+            inner: Mutex::new(Some(Table::default())),
+        }
+    }
+
+    /// Registers a stream to receive inbound frames. If the connection
+    /// has already been closed, the returned receiver is terminated.
+    pub fn register(&self, id: StreamId) -> mpsc::Receiver<Frame> {
+        let (tx, rx) = mpsc::channel(INBOUND_BUFFER);
+        if let Some(table) = self.inner.lock().unwrap().as_mut() {
+            table.overrun.remove(&id);
+            table.senders.insert(id, tx);
+        }
+        rx
+    }
+
+    /// Sets up the send credit for the outbound direction of a stream.
+    /// If the connection has already been closed, the returned window
+    /// is closed as well.
+    fn open_window(&self, id: StreamId) -> Arc<Semaphore> {
+        let window = Arc::new(Semaphore::new(STREAM_WINDOW as usize));
+        match self.inner.lock().unwrap().as_mut() {
+            Some(table) => {
+                if let Some(old) = table.windows.insert(id, window.clone()) {
+                    old.close();
+                }
+            }
+            None => window.close(),
+        }
+        window
+    }
+
+    /// Stops routing inbound frames to the stream, leaving its outbound
+    /// direction open.
+    pub fn unregister_inbound(&self, id: StreamId) {
+        if let Some(table) = self.inner.lock().unwrap().as_mut() {
+            table.senders.remove(&id);
+            table.overrun.remove(&id);
+        }
+    }
+
+    /// Unregisters both directions of the stream. A sender waiting
+    /// for credit on the stream fails.
+    pub fn unregister(&self, id: StreamId) {
+        if let Some(table) = self.inner.lock().unwrap().as_mut() {
+            table.senders.remove(&id);
+            table.overrun.remove(&id);
+            if let Some(window) = table.windows.remove(&id) {
+                window.close();
+            }
+        }
+    }
+
+    /// Adds the credit carried by a `Credit` frame to the window of the
+    /// stream. Credit for unknown streams is ignored, and the window never
+    /// grows past its initial size, as a receiver has no more room than that.
+    pub fn grant(&self, frame: &Frame) -> Result<(), Error> {
+        let credit: u32 = codec::decode(&frame.payload)?;
+        if let Some(table) = self.inner.lock().unwrap().as_ref() {
+            if let Some(window) = table.windows.get(&frame.stream) {
+                let room = (STREAM_WINDOW as usize).saturating_sub(window.available_permits());
+                window.add_permits(room.min(credit as usize));
+            }
+        }
+        Ok(())
+    }
+
+    /// Delivers the frame to the registered stream without waiting.
+    /// Frames for unknown streams are silently dropped.
+    ///
+    /// Returns `false` if the peer has sent more frames than it had credit
+    /// for and the stream has been reset; the caller should then cancel
+    /// the stream with the peer.
+    pub fn route(&self, frame: Frame) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+        let table = match inner.as_mut() {
+            Some(table) => table,
+            None => return true,
+        };
+        let id = frame.stream;
+        let tx = match table.senders.get(&id) {
+            Some(tx) => tx,
+            None => return true,
+        };
+        match tx.try_send(frame) {
+            Ok(()) => true,
+            // The stream may have been dropped by the receiving side,
+            // which is not an error.
+            Err(mpsc::error::TrySendError::Closed(_)) => true,
+            Err(mpsc::error::TrySendError::Full(_)) => {
+                // Dropping the sender terminates the receiver once it has
+                // drained the buffered frames.
+                table.senders.remove(&id);
+                table.overrun.insert(id);
+                false
+            }
+        }
+    }
+
+    /// Checks whether the stream has been reset for overrunning its window,
+    /// as opposed to being terminated by the connection closing.
+    fn take_overrun(&self, id: StreamId) -> bool {
+        match self.inner.lock().unwrap().as_mut() {
+            Some(table) => table.overrun.remove(&id),
+            None => false,
+        }
+    }
+
+    /// Terminates all registered streams and stops accepting new ones.
+    pub fn close(&self) {
+        if let Some(table) = self.inner.lock().unwrap().take() {
+            for window in table.windows.into_values() {
+                window.close();
+            }
+        }
+    }
+}
+
+/// Handle to send frames on the connection and to receive frames
+/// for the streams it has registered.
+#[derive(Clone)]
+pub(super) struct Connection {
+    frames: mpsc::Sender<Frame>,
+    // Control frames bypass the outbound buffer, so that granting
+    // credit or canceling a stream never waits for, or is lost behind,
+    // the data frames queued on the connection.
+    control: mpsc::UnboundedSender<Frame>,
+    streams: Arc<Streams>,
+}
+
+impl Connection {
+    /// Creates a connection handle writing into the given write half of the
+    /// socket. The returned future drives the writing and must be spawned;
+    /// it completes when all handles to the connection have been dropped,
+    /// or on the first write error.
+    pub fn new<W>(writer: W, streams: Arc<Streams>) -> (Self, impl Future<Output = ()>)
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let (frames, frames_rx) = mpsc::channel(OUTBOUND_BUFFER);
+        let (control, control_rx) = mpsc::unbounded_channel();
+        let conn = Connection {
+            frames,
+            control,
+            streams,
+        };
+        (conn, write_frames(writer, control_rx, frames_rx))
+    }
+
+    pub fn streams(&self) -> &Streams {
+        &self.streams
+    }
+
+    pub async fn send(&self, frame: Frame) -> Result<(), Error> {
+        self.frames
+            .send(frame)
+            .await
+            .map_err(|_| Error::new(Code::Unavailable, "connection closed"))
+    }
+
+    pub fn send_error(
+        &self,
+        id: StreamId,
+        err: &Error,
+    ) -> impl Future<Output = Result<(), Error>> + '_ {
+        self.send(Frame::new(id, Kind::Error, codec::encode_error(err)))
+    }
+
+    /// Sends a control frame ahead of the queued data frames.
+    /// This is used from contexts that cannot wait.
+    pub fn send_control(&self, frame: Frame) {
+        // The connection may have been closed, which the streams
+        // will learn about anyway.
+        let _ = self.control.send(frame);
+    }
+
+    /// Opens the outbound direction of the stream.
+    pub fn open_outbound(&self, id: StreamId) -> OutboundStream {
+        OutboundStream {
+            conn: self.clone(),
+            id,
+            window: self.streams.open_window(id),
+        }
+    }
+}
+
+/// The outbound direction of a protocol stream, sending items
+/// as the peer grants credit for them.
+pub(super) struct OutboundStream {
+    conn: Connection,
+    id: StreamId,
+    window: Arc<Semaphore>,
+}
+
+impl OutboundStream {
+    /// Sends an item, waiting for credit from the peer if the window
+    /// of the stream is exhausted.
+    pub async fn send_data<T: codec::Encode>(&self, item: &T) -> Result<(), Error> {
+        let payload = codec::encode(item);
+        self.window
+            .acquire()
+            .await
+            .map_err(|_| Error::new(Code::Canceled, "the stream has been closed"))?
+            .forget();
+        self.conn
+            .send(Frame::new(self.id, Kind::Data, payload))
+            .await
+    }
+
+    /// Sends the items of the stream, ending the outbound direction of
+    /// the protocol stream when the local stream is exhausted.
+    pub async fn send_stream<S>(&self, stream: S) -> Result<(), Error>
+    where
+        S: Stream,
+        S::Item: codec::Encode,
+    {
+        futures::pin_mut!(stream);
+        while let Some(item) = stream.next().await {
+            self.send_data(&item).await?;
+        }
+        self.conn.send(Frame::control(self.id, Kind::End)).await
+    }
+}
+
+async fn write_frames<W>(
+    writer: W,
+    mut control: mpsc::UnboundedReceiver<Frame>,
+    mut frames: mpsc::Receiver<Frame>,
+) where
+    W: AsyncWrite + Unpin,
+{
+    let mut writer = BufWriter::new(writer);
+    while let Some(frame) =
+        future::poll_fn(|cx| poll_next_frame(cx, &mut control, &mut frames)).await
+    {
+        if frame::write_frame(&mut writer, &frame).await.is_err() {
+            return;
+        }
+        // Coalesce the frames that are already queued into one write.
+        while let Ok(frame) = control.try_recv().or_else(|_| frames.try_recv()) {
+            if frame::write_frame(&mut writer, &frame).await.is_err() {
+                return;
+            }
+        }
+        if writer.flush().await.is_err() {
+            return;
+        }
+    }
+    let _ = writer.shutdown().await;
+}
+
+/// Polls for the next frame to write, giving priority to control frames.
+/// Both channels are closed together when the last connection handle is
+/// dropped, so the end of the data frames ends the connection.
+fn poll_next_frame(
+    cx: &mut Context<'_>,
+    control: &mut mpsc::UnboundedReceiver<Frame>,
+    frames: &mut mpsc::Receiver<Frame>,
+) -> Poll<Option<Frame>> {
+    if let Poll::Ready(Some(frame)) = control.poll_recv(cx) {
+        return Poll::Ready(Some(frame));
+    }
+    frames.poll_recv(cx)
+}
+
+/// The inbound direction of a protocol stream, decoding the received items.
+#[must_use = "streams do nothing unless polled"]
+pub struct InboundStream<T> {
+    id: StreamId,
+    conn: Connection,
+    frames: mpsc::Receiver<Frame>,
+    consumed: u32,
+    done: bool,
+    cancel_on_drop: bool,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+impl<T> InboundStream<T> {
+    pub(super) fn new(
+        conn: Connection,
+        id: StreamId,
+        frames: mpsc::Receiver<Frame>,
+        cancel_on_drop: bool,
+    ) -> Self {
+        InboundStream {
+            id,
+            conn,
+            frames,
+            consumed: 0,
+            done: false,
+            cancel_on_drop,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: Decode> Stream for InboundStream<T> {
+    type Item = Result<T, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.done {
+            return Poll::Ready(None);
+        }
+        let frame = match futures::ready!(self.frames.poll_recv(cx)) {
+            Some(frame) => frame,
+            None => {
+                self.done = true;
+                let err = if self.conn.streams().take_overrun(self.id) {
+                    Error::new(
+                        Code::Aborted,
+                        "the stream was reset because the peer sent more than it had credit for",
+                    )
+                } else {
+                    Error::new(Code::Unavailable, "connection closed")
+                };
+                return Poll::Ready(Some(Err(err)));
+            }
+        };
+        let item = match frame.kind {
+            Kind::Data => {
+                // The buffer slot of the frame is free now, so the peer
+                // can be granted credit for it.
+                self.consumed += 1;
+                if self.consumed >= CREDIT_BATCH {
+                    let credit = codec::encode(&self.consumed);
+                    self.conn
+                        .send_control(Frame::new(self.id, Kind::Credit, credit));
+                    self.consumed = 0;
+                }
+                codec::decode(&frame.payload)
+            }
+            Kind::End => {
+                self.done = true;
+                return Poll::Ready(None);
+            }
+            Kind::Error => {
+                self.done = true;
+                Err(codec::decode_error(&frame.payload))
+            }
+            Kind::Cancel => {
+                self.done = true;
+                Err(Error::new(
+                    Code::Canceled,
+                    "the stream was canceled by the peer",
+                ))
+            }
+            Kind::Open | Kind::Credit => {
+                self.done = true;
+                Err(Error::new(
+                    Code::InvalidArgument,
+                    "unexpected control frame on an open stream",
+                ))
+            }
+        };
+        Poll::Ready(Some(item))
+    }
+}
+
+impl<T> Drop for InboundStream<T> {
+    fn drop(&mut self) {
+        if self.cancel_on_drop {
+            // On the client, the response stream stands for the whole
+            // request, so the outbound direction is closed as well.
+            self.conn.streams().unregister(self.id);
+            if !self.done {
+                self.conn
+                    .send_control(Frame::control(self.id, Kind::Cancel));
+            }
+        } else {
+            self.conn.streams().unregister_inbound(self.id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tokio::io::DuplexStream;
+
+    /// Drives one end of an in-memory connection the way the client
+    /// and the server drive theirs.
+    fn spawn_end(socket: DuplexStream) -> Connection {
+        let (mut reader, writer) = tokio::io::split(socket);
+        let streams = Arc::new(Streams::new());
+        let (conn, write_task) = Connection::new(writer, streams.clone());
+        tokio::spawn(write_task);
+        tokio::spawn(async move {
+            while let Ok(Some(frame)) = frame::read_frame(&mut reader).await {
+                if frame.kind == Kind::Credit {
+                    streams.grant(&frame).unwrap();
+                } else {
+                    streams.route(frame);
+                }
+            }
+            streams.close();
+        });
+        conn
+    }
+
+    #[tokio::test]
+    async fn slow_consumer_receives_every_frame() {
+        const ITEMS: u32 = STREAM_WINDOW * 8;
+
+        let (a, b) = tokio::io::duplex(64 * 1024);
+        let sender = spawn_end(a);
+        let receiver = spawn_end(b);
+        let frames = receiver.streams().register(1);
+        let mut inbound = InboundStream::<u32>::new(receiver.clone(), 1, frames, false);
+        let outbound = sender.open_outbound(1);
+        let send_task =
+            tokio::spawn(async move { outbound.send_stream(stream::iter(0..ITEMS)).await });
+
+        let mut received = Vec::new();
+        while let Some(item) = inbound.next().await {
+            received.push(item.unwrap());
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        assert_eq!(received, (0..ITEMS).collect::<Vec<_>>());
+        send_task.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn sender_waits_for_credit() {
+        let (a, b) = tokio::io::duplex(64 * 1024);
+        let sender = spawn_end(a);
+        let receiver = spawn_end(b);
+        let frames = receiver.streams().register(1);
+        let mut inbound = InboundStream::<u32>::new(receiver.clone(), 1, frames, false);
+        let outbound = sender.open_outbound(1);
+        let send_task = tokio::spawn(async move {
+            for i in 0..=STREAM_WINDOW {
+                outbound.send_data(&i).await?;
+            }
+            Ok::<_, Error>(())
+        });
+
+        // The frame past the window is only sent once credit is granted.
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!send_task.is_finished());
+        for i in 0..=STREAM_WINDOW {
+            assert_eq!(inbound.next().await.unwrap().unwrap(), i);
+        }
+        send_task.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn closing_the_stream_fails_a_waiting_sender() {
+        let streams = Arc::new(Streams::new());
+        let (conn, write_task) = Connection::new(tokio::io::sink(), streams.clone());
+        tokio::spawn(write_task);
+        let outbound = conn.open_outbound(1);
+        for i in 0..STREAM_WINDOW {
+            outbound.send_data(&i).await.unwrap();
+        }
+        streams.unregister(1);
+        assert_eq!(
+            outbound.send_data(&0u32).await.unwrap_err().code(),
+            Code::Canceled
+        );
+    }
+
+    #[test]
+    fn credit_does_not_grow_the_window() {
+        let streams = Streams::new();
+        let window = streams.open_window(1);
+        let credit = Frame::new(1, Kind::Credit, codec::encode(&u32::MAX));
+        streams.grant(&credit).unwrap();
+        assert_eq!(window.available_permits(), STREAM_WINDOW as usize);
+
+        window.try_acquire_many(4).unwrap().forget();
+        streams.grant(&credit).unwrap();
+        assert_eq!(window.available_permits(), STREAM_WINDOW as usize);
+
+        // Credit for unknown streams is ignored.
+        streams
+            .grant(&Frame::new(2, Kind::Credit, credit.payload.clone()))
+            .unwrap();
+        assert!(streams.grant(&Frame::control(1, Kind::Credit)).is_err());
+    }
+
+    #[test]
+    fn overrunning_stream_is_reset_without_blocking_others() {
+        let streams = Streams::new();
+        let mut slow = streams.register(1);
+        let mut fast = streams.register(2);
+
+        for _ in 0..INBOUND_BUFFER {
+            assert!(streams.route(Frame::control(1, Kind::Data)));
+        }
+        assert!(!streams.route(Frame::control(1, Kind::Data)));
+
+        // Frames for the other stream are still delivered.
+        assert!(streams.route(Frame::control(2, Kind::Data)));
+        assert_eq!(fast.try_recv().unwrap().stream, 2);
+
+        // Frames arriving after the reset are dropped.
+        assert!(streams.route(Frame::control(1, Kind::Data)));
+        for _ in 0..INBOUND_BUFFER {
+            assert!(slow.try_recv().is_ok());
+        }
+        assert_eq!(
+            slow.try_recv().unwrap_err(),
+            mpsc::error::TryRecvError::Disconnected
+        );
+        assert!(streams.take_overrun(1));
+        assert!(!streams.take_overrun(2));
+    }
+
+    #[test]
+    fn unregistering_clears_overrun() {
+        let streams = Streams::new();
+        let _rx = streams.register(1);
+        for _ in 0..=INBOUND_BUFFER {
+            streams.route(Frame::control(1, Kind::Data));
+        }
+        streams.unregister(1);
+        assert!(!streams.take_overrun(1));
+    }
+}
@@ -0,0 +1,183 @@
+use crate::error::{Code, Error};
+use tokio::io::{self, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Magic bytes sent by the client when the connection is established,
+/// followed by the big-endian protocol version.
+pub(super) const PREAMBLE_MAGIC: [u8; 4] = *b"JNTT";
+
+pub(super) const PREAMBLE_LEN: usize = 8;
+
+/// Length of the frame header: the payload length, the stream identifier
+/// and the frame kind.
+pub(super) const HEADER_LEN: usize = 9;
+
+/// Maximum size of the frame payload accepted by the protocol implementation.
+pub const MAX_PAYLOAD_LEN: usize = 16 * 1024 * 1024;
+
+pub(super) type StreamId = u32;
+
+/// Kind of a protocol frame.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub(super) enum Kind {
+    /// Opens a new stream with a request. The payload is the method code
+    /// followed by the encoded request.
+    Open = 0,
+    /// Carries one item of the stream.
+    Data = 1,
+    /// Ends the sender's direction of the stream.
+    End = 2,
+    /// Terminates the stream in both directions with an error.
+    Error = 3,
+    /// Signals that the sender is no longer interested in the stream.
+    Cancel = 4,
+    /// Grants the peer credit to send more `Data` frames on the stream.
+    /// The payload is the big-endian `u32` number of frames.
+    Credit = 5,
+}
+
+impl TryFrom<u8> for Kind {
+    type Error = Error;
+
+    fn try_from(code: u8) -> Result<Self, Error> {
+        match code {
+            0 => Ok(Kind::Open),
+            1 => Ok(Kind::Data),
+            2 => Ok(Kind::End),
+            3 => Ok(Kind::Error),
+            4 => Ok(Kind::Cancel),
+            5 => Ok(Kind::Credit),
+            _ => Err(Error::new(
+                Code::InvalidArgument,
+                format!("unknown frame kind {}", code),
+            )),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(super) struct Frame {
+    pub stream: StreamId,
+    pub kind: Kind,
+    pub payload: Vec<u8>,
+}
+
+impl Frame {
+    pub fn new(stream: StreamId, kind: Kind, payload: Vec<u8>) -> Self {
+        Frame {
+            stream,
+            kind,
+            payload,
+        }
+    }
+
+    pub fn control(stream: StreamId, kind: Kind) -> Self {
+        Frame::new(stream, kind, Vec::new())
+    }
+
+    pub fn header(&self) -> [u8; HEADER_LEN] {
+        let mut header = [0; HEADER_LEN];
+        header[0..4].copy_from_slice(&(self.payload.len() as u32).to_be_bytes());
+        header[4..8].copy_from_slice(&self.stream.to_be_bytes());
+        header[8] = self.kind as u8;
+        header
+    }
+
+    /// Parses the frame header, returning the stream identifier, the kind
+    /// and the length of the payload that follows.
+    pub fn parse_header(header: &[u8; HEADER_LEN]) -> Result<(StreamId, Kind, usize), Error> {
+        let len = u32::from_be_bytes(header[0..4].try_into().unwrap()) as usize;
+        if len > MAX_PAYLOAD_LEN {
+            return Err(Error::new(
+                Code::InvalidArgument,
+                format!("frame payload of {} bytes exceeds the size limit", len),
+            ));
+        }
+        let stream = u32::from_be_bytes(header[4..8].try_into().unwrap());
+        let kind = Kind::try_from(header[8])?;
+        Ok((stream, kind, len))
+    }
+}
+
+pub(super) fn preamble() -> [u8; PREAMBLE_LEN] {
+    let mut preamble = [0; PREAMBLE_LEN];
+    preamble[0..4].copy_from_slice(&PREAMBLE_MAGIC);
+    preamble[4..8].copy_from_slice(&crate::PROTOCOL_VERSION.to_be_bytes());
+    preamble
+}
+
+/// Reads the connection preamble, returning the protocol version
+/// announced by the peer.
+pub(super) async fn read_preamble<R>(reader: &mut R) -> Result<u32, Error>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut preamble = [0; PREAMBLE_LEN];
+    reader.read_exact(&mut preamble).await.map_err(io_error)?;
+    if preamble[0..4] != PREAMBLE_MAGIC {
+        return Err(Error::new(
+            Code::InvalidArgument,
+            "the peer does not speak the NTT protocol",
+        ));
+    }
+    Ok(u32::from_be_bytes(preamble[4..8].try_into().unwrap()))
+}
+
+/// Reads the next frame, resolving to `None` if the peer has closed
+/// the connection on a frame boundary.
+pub(super) async fn read_frame<R>(reader: &mut R) -> Result<Option<Frame>, Error>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut header = [0; HEADER_LEN];
+    match reader.read_exact(&mut header).await {
+        Ok(_) => {}
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(io_error(e)),
+    }
+    let (stream, kind, len) = Frame::parse_header(&header)?;
+    let mut payload = vec![0; len];
+    reader.read_exact(&mut payload).await.map_err(io_error)?;
+    Ok(Some(Frame::new(stream, kind, payload)))
+}
+
+pub(super) async fn write_frame<W>(writer: &mut W, frame: &Frame) -> io::Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    writer.write_all(&frame.header()).await?;
+    writer.write_all(&frame.payload).await
+}
+
+pub(super) fn io_error(e: io::Error) -> Error {
+    Error::new(Code::Unavailable, e)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_roundtrip() {
+        let frame = Frame::new(42, Kind::Data, vec![1, 2, 3]);
+        let header = frame.header();
+        let (stream, kind, len) = Frame::parse_header(&header).unwrap();
+        assert_eq!(stream, 42);
+        assert_eq!(kind, Kind::Data);
+        assert_eq!(len, 3);
+    }
+
+    #[test]
+    fn oversized_payload_is_rejected() {
+        let mut header = Frame::control(1, Kind::Data).header();
+        header[0..4].copy_from_slice(&(MAX_PAYLOAD_LEN as u32 + 1).to_be_bytes());
+        assert!(Frame::parse_header(&header).is_err());
+    }
+
+    #[test]
+    fn unknown_kind_is_rejected() {
+        let mut header = Frame::control(1, Kind::End).header();
+        header[8] = 6;
+        assert!(Frame::parse_header(&header).is_err());
+    }
+}
@@ -0,0 +1,38 @@
+//! A compact binary protocol over raw TCP connections, serving the same
+//! requests as the gRPC protocol without the overhead of HTTP/2.
+//!
+//! After connecting, the client sends an 8-byte preamble made of the
+//! magic bytes `JNTT` and the big-endian protocol version, and the server
+//! responds with its own preamble. The rest of the connection is a sequence
+//! of frames in both directions, each starting with a 9-byte header:
+//!
+//! | bytes | content                                  |
+//! |-------|------------------------------------------|
+//! | 0..4  | length of the payload, big-endian        |
+//! | 4..8  | stream identifier, big-endian            |
+//! | 8     | frame kind                               |
+//!
+//! The client opens a stream with an `Open` frame carrying the method code
+//! and the request. Items of the streams in either direction are sent in
+//! `Data` frames, and the sender of a stream direction closes it with
+//! an `End` frame. Either side can terminate a stream with an `Error` frame,
+//! and the client can abandon a stream it is no longer interested in
+//! with a `Cancel` frame.
+//!
+//! Each direction of a stream is flow controlled: the sender starts with
+//! credit for 16 `Data` frames and has to wait for `Credit` frames from the
+//! receiver, granting more as the receiver consumes the items, before it can
+//! send further `Data` frames. A sender that exceeds its credit has the
+//! stream reset.
+
+mod codec;
+mod connection;
+mod frame;
+
+pub mod client;
+pub mod server;
+
+pub use client::Client;
+pub use connection::InboundStream;
+pub use frame::MAX_PAYLOAD_LEN;
+pub use server::Server;
@@ -0,0 +1,374 @@
+use super::codec::{self, Decode, HandshakeMessage, Method, Reader};
+use super::connection::{Connection, InboundStream, OutboundStream, Streams};
+use super::frame::{self, Frame, Kind, StreamId};
+
+use crate::core::server::{BlockService, FragmentService, GossipService, Node, PushStream};
+use crate::data::block::{BlockIds, ChainPullRequest};
+use crate::data::fragment::FragmentIds;
+use crate::data::p2p::AuthenticatedNodeId;
+use crate::data::Peer;
+use crate::error::{Code, Error};
+use crate::PROTOCOL_VERSION;
+use futures::prelude::*;
+use tokio::io::{AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Default limit on the number of streams a client can have open
+/// at the same time on one connection.
+const DEFAULT_MAX_STREAMS: usize = 256;
+
+/// Builder to customize the NTT server.
+pub struct Builder {
+    max_streams_per_connection: usize,
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Builder::new()
+    }
+}
+
+impl Builder {
+    pub fn new() -> Self {
+        Builder {
+            max_streams_per_connection: DEFAULT_MAX_STREAMS,
+        }
+    }
+
+    /// Limits the number of streams a client can have open at the same
+    /// time on one connection. Requests over the limit are rejected
+    /// with an `Unavailable` error.
+    pub fn max_streams_per_connection(&mut self, limit: usize) -> &mut Self {
+        self.max_streams_per_connection = limit;
+        self
+    }
+
+    pub fn build<T: Node>(&self, inner: T) -> Server<T> {
+        Server {
+            inner: Arc::new(inner),
+            max_streams: self.max_streams_per_connection,
+        }
+    }
+}
+
+/// Server of the NTT protocol, serving requests with the node
+/// implementation.
+pub struct Server<T> {
+    inner: Arc<T>,
+    max_streams: usize,
+}
+
+impl<T> Clone for Server<T> {
+    fn clone(&self) -> Self {
+        Server {
+            inner: Arc::clone(&self.inner),
+            max_streams: self.max_streams,
+        }
+    }
+}
+
+impl<T: Node> Server<T> {
+    pub fn new(inner: T) -> Self {
+        Builder::new().build(inner)
+    }
+
+    /// Accepts connections on the listener, serving each in a spawned task.
+    ///
+    /// Resolves only if accepting a connection fails.
+    pub async fn serve(self, listener: TcpListener) -> std::io::Result<()> {
+        loop {
+            let (socket, _) = listener.accept().await?;
+            let server = self.clone();
+            tokio::spawn(async move {
+                // Errors on a connection are not of concern to the listener,
+                // the peer will find the connection closed.
+                let _ = server.serve_connection(socket).await;
+            });
+        }
+    }
+
+    /// Serves requests on a single accepted connection until the client
+    /// closes it.
+    pub async fn serve_connection(&self, mut socket: TcpStream) -> Result<(), Error> {
+        let peer = Peer::from(socket.peer_addr().map_err(frame::io_error)?);
+        let version = frame::read_preamble(&mut socket).await?;
+        socket
+            .write_all(&frame::preamble())
+            .await
+            .map_err(frame::io_error)?;
+        if version != PROTOCOL_VERSION {
+            // The client checks the version in our preamble
+            // and will hang up as well.
+            return Err(Error::new(
+                Code::FailedPrecondition,
+                format!("unsupported protocol version {}", version),
+            ));
+        }
+
+        let (reader, writer) = socket.into_split();
+        let mut reader = BufReader::new(reader);
+        let streams = Arc::new(Streams::new());
+        let (conn, write_task) = Connection::new(writer, streams.clone());
+        let write_task = tokio::spawn(write_task);
+        let mut tasks = HashMap::new();
+        let res = loop {
+            let frame = match frame::read_frame(&mut reader).await {
+                Ok(Some(frame)) => frame,
+                Ok(None) => break Ok(()),
+                Err(e) => break Err(e),
+            };
+            match frame.kind {
+                Kind::Open => self.open(&conn, peer.clone(), &mut tasks, frame).await,
+                Kind::Credit => {
+                    if let Err(e) = streams.grant(&frame) {
+                        break Err(e);
+                    }
+                }
+                Kind::Cancel => {
+                    if let Some(task) = tasks.remove(&frame.stream) {
+                        task.abort();
+                    }
+                    streams.unregister(frame.stream);
+                }
+                _ => {
+                    let id = frame.stream;
+                    if !streams.route(frame) {
+                        // The client has sent more than it had credit for;
+                        // reset the request rather than holding up
+                        // the other streams on the connection.
+                        if let Some(task) = tasks.remove(&id) {
+                            task.abort();
+                        }
+                        streams.unregister(id);
+                        let err = Error::new(
+                            Code::Aborted,
+                            "the stream was reset because the client sent more than it had credit for",
+                        );
+                        conn.send_control(Frame::new(id, Kind::Error, codec::encode_error(&err)));
+                    }
+                }
+            }
+        };
+
+        for task in tasks.into_values() {
+            task.abort();
+        }
+        streams.close();
+        drop(conn);
+        let _ = write_task.await;
+        res
+    }
+
+    async fn open(
+        &self,
+        conn: &Connection,
+        peer: Peer,
+        tasks: &mut HashMap<StreamId, JoinHandle<()>>,
+        frame: Frame,
+    ) {
+        let id = frame.stream;
+        tasks.retain(|_, task| !task.is_finished());
+        if tasks.contains_key(&id) {
+            let err = Error::new(Code::InvalidArgument, "the stream is already open");
+            let _ = conn.send_error(id, &err).await;
+            return;
+        }
+        if tasks.len() >= self.max_streams {
+            let err = Error::new(Code::Unavailable, "too many concurrent streams");
+            let _ = conn.send_error(id, &err).await;
+            return;
+        }
+        let mut reader = Reader::new(&frame.payload);
+        let method = match reader.u8().and_then(Method::try_from) {
+            Ok(method) => method,
+            Err(e) => {
+                let _ = conn.send_error(id, &e).await;
+                return;
+            }
+        };
+        // The inbound stream must be registered before reading further
+        // frames, as the client sends its items right after the request.
+        let inbound = if method.has_inbound_stream() {
+            Some(conn.streams().register(id))
+        } else {
+            None
+        };
+        let request = reader.remaining().to_vec();
+        let handler = Handler {
+            node: Arc::clone(&self.inner),
+            conn: conn.clone(),
+            outbound: conn.open_outbound(id),
+            id,
+            peer,
+        };
+        let task = tokio::spawn(async move {
+            match handler.handle(method, &request, inbound).await {
+                Ok(()) => {
+                    let _ = handler.conn.send(Frame::control(id, Kind::End)).await;
+                }
+                Err(e) => {
+                    let _ = handler.conn.send_error(id, &e).await;
+                }
+            }
+            handler.conn.streams().unregister(id);
+        });
+        tasks.insert(id, task);
+    }
+}
+
+/// Serves a single request stream.
+struct Handler<T> {
+    node: Arc<T>,
+    conn: Connection,
+    outbound: OutboundStream,
+    id: StreamId,
+    peer: Peer,
+}
+
+impl<T: Node> Handler<T> {
+    fn block_service(&self) -> Result<&T::BlockService, Error> {
+        self.node.block_service().ok_or_else(Error::unimplemented)
+    }
+
+    fn fragment_service(&self) -> Result<&T::FragmentService, Error> {
+        self.node
+            .fragment_service()
+            .ok_or_else(Error::unimplemented)
+    }
+
+    fn gossip_service(&self) -> Result<&T::GossipService, Error> {
+        self.node.gossip_service().ok_or_else(Error::unimplemented)
+    }
+
+    /// Wraps the frames received from the client into the stream
+    /// passed to the service.
+    fn push_stream<I>(&self, frames: Option<mpsc::Receiver<Frame>>) -> PushStream<I>
+    where
+        I: Decode + Send + 'static,
+    {
+        let frames = frames.expect("the inbound stream should be registered for the method");
+        Box::pin(InboundStream::new(
+            self.conn.clone(),
+            self.id,
+            frames,
+            false,
+        ))
+    }
+
+    async fn send_stream<S, I>(&self, stream: S) -> Result<(), Error>
+    where
+        S: Stream<Item = Result<I, Error>>,
+        I: codec::Encode,
+    {
+        futures::pin_mut!(stream);
+        while let Some(item) = stream.next().await {
+            self.outbound.send_data(&item?).await?;
+        }
+        Ok(())
+    }
+
+    async fn handle(
+        &self,
+        method: Method,
+        request: &[u8],
+        inbound: Option<mpsc::Receiver<Frame>>,
+    ) -> Result<(), Error> {
+        match method {
+            Method::Handshake => {
+                let mut reader = Reader::new(request);
+                let nonce = reader.bytes()?;
+                reader.finish()?;
+                let hr = self.node.handshake(self.peer.clone(), nonce).await?;
+                let res = HandshakeMessage {
+                    version: PROTOCOL_VERSION,
+                    block0: hr.block0_id.as_bytes().into(),
+                    node_id: hr.auth.id().as_bytes().into(),
+                    signature: hr.auth.signature().into(),
+                    nonce: hr.nonce.into(),
+                };
+                self.outbound.send_data(&res).await
+            }
+            Method::ClientAuth => {
+                let auth: AuthenticatedNodeId = codec::decode(request)?;
+                self.node.client_auth(self.peer.clone(), auth).await
+            }
+            Method::Tip => {
+                codec::decode::<()>(request)?;
+                let header = self.block_service()?.tip().await?;
+                self.outbound.send_data(&header).await
+            }
+            Method::Peers => {
+                let limit = codec::decode::<u32>(request)?;
+                let peers = self.gossip_service()?.peers(limit).await?;
+                self.outbound.send_data(&peers).await
+            }
+            Method::GetBlocks => {
+                let ids: BlockIds = codec::decode(request)?;
+                let stream = self.block_service()?.get_blocks(ids).await?;
+                self.send_stream(stream).await
+            }
+            Method::GetHeaders => {
+                let ids: BlockIds = codec::decode(request)?;
+                let stream = self.block_service()?.get_headers(ids).await?;
+                self.send_stream(stream).await
+            }
+            Method::GetFragments => {
+                let ids: FragmentIds = codec::decode(request)?;
+                let stream = self.fragment_service()?.get_fragments(ids).await?;
+                self.send_stream(stream).await
+            }
+            Method::PullHeaders => {
+                let ChainPullRequest { from, to } = codec::decode(request)?;
+                let stream = self.block_service()?.pull_headers(from, to).await?;
+                self.send_stream(stream).await
+            }
+            Method::PullBlocks => {
+                let ChainPullRequest { from, to } = codec::decode(request)?;
+                let stream = self.block_service()?.pull_blocks(from, to).await?;
+                self.send_stream(stream).await
+            }
+            Method::PullBlocksToTip => {
+                let from: BlockIds = codec::decode(request)?;
+                let stream = self.block_service()?.pull_blocks_to_tip(from).await?;
+                self.send_stream(stream).await
+            }
+            Method::PushHeaders => {
+                let inbound = self.push_stream(inbound);
+                self.block_service()?.push_headers(inbound).await
+            }
+            Method::UploadBlocks => {
+                let inbound = self.push_stream(inbound);
+                self.block_service()?.upload_blocks(inbound).await
+            }
+            Method::BlockSubscription => {
+                let inbound = self.push_stream(inbound);
+                let outbound = self
+                    .block_service()?
+                    .block_subscription(self.peer.clone(), inbound)
+                    .await?;
+                self.send_stream(outbound).await
+            }
+            Method::FragmentSubscription => {
+                let inbound = self.push_stream(inbound);
+                let outbound = self
+                    .fragment_service()?
+                    .fragment_subscription(self.peer.clone(), inbound)
+                    .await?;
+                self.send_stream(outbound).await
+            }
+            Method::GossipSubscription => {
+                let inbound = self.push_stream(inbound);
+                let outbound = self
+                    .gossip_service()?
+                    .gossip_subscription(self.peer.clone(), inbound)
+                    .await?;
+                self.send_stream(outbound).await
+            }
+        }
+    }
+}
@@ -0,0 +1,143 @@
+//! Export and import of a range of blocks as a single file, to seed a store
+//! without downloading the blocks.
+//!
+//! The archive holds the blocks from the oldest to the most recent:
+//!
+//! ```text
+//! magic (4 bytes) ++ version (u16) ++ number of blocks (u32)
+//!     ++ (block length (u32) ++ block)*
+//! ```
+//!
+//! Integers are little endian. The `BlockInfo` of the blocks are not
+//! exported, they are computed from the blocks when importing them.
+
+use crate::{BlockInfo, BlockStore, Error};
+use std::io::{Read, Write};
+
+const ARCHIVE_MAGIC: &[u8; 4] = b"BLKA";
+const ARCHIVE_VERSION: u16 = 1;
+
+/// The result of [`BlockStore::import_blocks`].
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    /// the number of blocks added to the store
+    pub imported: u32,
+    /// the number of blocks that were already stored
+    pub skipped: u32,
+    /// the ID of the last block of the archive
+    pub last_block_id: Option<Vec<u8>>,
+}
+
+impl BlockStore {
+    /// Write `distance` blocks ending at `to_block` to the given writer, see
+    /// [`BlockStore::iter`] for the meaning of `distance`.
+    pub fn export_blocks<W: Write>(
+        &self,
+        to_block: &[u8],
+        distance: u32,
+        mut writer: W,
+    ) -> Result<(), Error> {
+        writer
+            .write_all(ARCHIVE_MAGIC)
+            .and_then(|()| writer.write_all(&ARCHIVE_VERSION.to_le_bytes()))
+            .and_then(|()| writer.write_all(&distance.to_le_bytes()))
+            .map_err(Error::ArchiveWrite)?;
+
+        for block in self.iter(to_block, distance)? {
+            let block = block?;
+            let block = block.as_ref();
+            writer
+                .write_all(&(block.len() as u32).to_le_bytes())
+                .and_then(|()| writer.write_all(block))
+                .map_err(Error::ArchiveWrite)?;
+        }
+
+        writer.flush().map_err(Error::ArchiveWrite)
+    }
+
+    /// Add the blocks of an archive written by [`BlockStore::export_blocks`]
+    /// to the store. The `decode` function computes the `BlockInfo` of a block
+    /// from its content, it returns `None` if the block cannot be decoded.
+    /// Blocks longer than `max_block_size` bytes are rejected before being
+    /// read.
+    ///
+    /// The parent of the first block of the archive must be stored already,
+    /// unless it is the root, and every block must be one block longer than
+    /// its parent. The content of the blocks is not validated otherwise.
+    pub fn import_blocks<R, F>(
+        &self,
+        mut reader: R,
+        max_block_size: u32,
+        decode: F,
+    ) -> Result<ImportReport, Error>
+    where
+        R: Read,
+        F: Fn(&[u8]) -> Option<BlockInfo>,
+    {
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic).map_err(Error::ArchiveRead)?;
+        if &magic != ARCHIVE_MAGIC {
+            return Err(Error::InvalidArchive);
+        }
+        let mut version = [0u8; 2];
+        reader
+            .read_exact(&mut version)
+            .map_err(Error::ArchiveRead)?;
+        if u16::from_le_bytes(version) != ARCHIVE_VERSION {
+            return Err(Error::InvalidArchive);
+        }
+        let count = read_u32(&mut reader)?;
+
+        let mut report = ImportReport::default();
+        for _ in 0..count {
+            let size = read_u32(&mut reader)?;
+            if size > max_block_size {
+                return Err(Error::ArchiveBlockTooLarge {
+                    size,
+                    max: max_block_size,
+                });
+            }
+            let mut block = vec![0u8; size as usize];
+            reader.read_exact(&mut block).map_err(Error::ArchiveRead)?;
+            let block_info = decode(&block).ok_or(Error::InvalidArchive)?;
+            self.check_archived_chain_length(&block_info)?;
+            let id = block_info.id().as_ref().to_vec();
+            match self.put_block(&block, block_info) {
+                Ok(()) => report.imported += 1,
+                Err(Error::BlockAlreadyPresent) => report.skipped += 1,
+                Err(err) => return Err(err),
+            }
+            report.last_block_id = Some(id);
+        }
+
+        Ok(report)
+    }
+}
+
+impl BlockStore {
+    /// check that the block is one block longer than its parent, when the
+    /// parent is stored
+    fn check_archived_chain_length(&self, block_info: &BlockInfo) -> Result<(), Error> {
+        let expected = if block_info.parent_id().as_ref() == self.root_id.as_ref() {
+            0
+        } else {
+            match self.get_block_info(block_info.parent_id().as_ref()) {
+                Ok(parent) => parent.chain_length() + 1,
+                // reported as a missing parent when the block is stored
+                Err(Error::BlockNotFound) => return Ok(()),
+                Err(err) => return Err(err),
+            }
+        };
+        if block_info.chain_length() == expected {
+            Ok(())
+        } else {
+            Err(Error::InvalidArchive)
+        }
+    }
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> Result<u32, Error> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes).map_err(Error::ArchiveRead)?;
+    Ok(u32::from_le_bytes(bytes))
+}
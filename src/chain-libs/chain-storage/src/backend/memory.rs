@@ -0,0 +1,142 @@
+use super::{Backend, Iter, Transaction, Tree};
+use crate::{Error, Value};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
+type Trees = HashMap<Tree, BTreeMap<Vec<u8>, Vec<u8>>>;
+
+/// The volatile storage kept in memory, for tests and short-lived stores.
+#[derive(Default)]
+pub struct MemoryBackend {
+    trees: RwLock<Trees>,
+}
+
+impl MemoryBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, Trees> {
+        self.trees.read().expect("memory backend lock poisoned")
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, Trees> {
+        self.trees.write().expect("memory backend lock poisoned")
+    }
+
+    fn collect<'a, I>(entries: I) -> Iter<'static>
+    where
+        I: Iterator<Item = (&'a Vec<u8>, &'a Vec<u8>)>,
+    {
+        let entries: Vec<_> = entries
+            .map(|(key, value)| Ok((Value::from(key.clone()), Value::from(value.clone()))))
+            .collect();
+        Box::new(entries.into_iter())
+    }
+}
+
+impl Backend for MemoryBackend {
+    fn get(&self, tree: Tree, key: &[u8]) -> Result<Option<Value>, Error> {
+        Ok(self
+            .read()
+            .get(&tree)
+            .and_then(|entries| entries.get(key))
+            .map(|value| Value::from(value.clone())))
+    }
+
+    fn insert(&self, tree: Tree, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        self.write()
+            .entry(tree)
+            .or_default()
+            .insert(key.to_vec(), value.to_vec());
+        Ok(())
+    }
+
+    fn remove(&self, tree: Tree, key: &[u8]) -> Result<(), Error> {
+        if let Some(entries) = self.write().get_mut(&tree) {
+            entries.remove(key);
+        }
+        Ok(())
+    }
+
+    fn clear(&self, tree: Tree) -> Result<(), Error> {
+        self.write().remove(&tree);
+        Ok(())
+    }
+
+    fn iter(&self, tree: Tree) -> Iter<'_> {
+        match self.read().get(&tree) {
+            Some(entries) => Self::collect(entries.iter()),
+            None => Box::new(std::iter::empty()),
+        }
+    }
+
+    fn scan_prefix<'a>(&'a self, tree: Tree, prefix: &[u8]) -> Iter<'a> {
+        match self.read().get(&tree) {
+            Some(entries) => Self::collect(
+                entries
+                    .range(prefix.to_vec()..)
+                    .take_while(|(key, _)| key.starts_with(prefix)),
+            ),
+            None => Box::new(std::iter::empty()),
+        }
+    }
+
+    fn transaction(
+        &self,
+        f: &mut dyn FnMut(&mut dyn Transaction) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        let mut trees = self.write();
+        let mut transaction = MemoryTransaction {
+            trees: &*trees,
+            changes: HashMap::new(),
+        };
+        f(&mut transaction)?;
+        let changes = transaction.changes;
+        for ((tree, key), value) in changes {
+            let entries = trees.entry(tree).or_default();
+            match value {
+                Some(value) => entries.insert(key, value),
+                None => entries.remove(&key),
+            };
+        }
+        Ok(())
+    }
+
+    fn flush(&self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// the changes of the transaction are only applied once it succeeds
+struct MemoryTransaction<'a> {
+    trees: &'a Trees,
+    changes: HashMap<(Tree, Vec<u8>), Option<Vec<u8>>>,
+}
+
+impl Transaction for MemoryTransaction<'_> {
+    fn get(&mut self, tree: Tree, key: &[u8]) -> Result<Option<Value>, Error> {
+        let value = match self.changes.get(&(tree, key.to_vec())) {
+            Some(change) => change.clone(),
+            None => self
+                .trees
+                .get(&tree)
+                .and_then(|entries| entries.get(key))
+                .cloned(),
+        };
+        Ok(value.map(Value::from))
+    }
+
+    fn insert(&mut self, tree: Tree, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        self.changes
+            .insert((tree, key.to_vec()), Some(value.to_vec()));
+        Ok(())
+    }
+
+    fn remove(&mut self, tree: Tree, key: &[u8]) -> Result<(), Error> {
+        self.changes.insert((tree, key.to_vec()), None);
+        Ok(())
+    }
+}
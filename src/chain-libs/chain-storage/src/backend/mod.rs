@@ -0,0 +1,178 @@
+//! Storage engines for the volatile part of the block store.
+//!
+//! The volatile storage is a set of key-value collections (see [`Tree`]). An
+//! engine implements [`Backend`] to store them, the block store only relies on
+//! this trait. The permanent storage is always kept in `data-pile` files.
+
+mod memory;
+#[cfg(feature = "rocksdb")]
+mod rocks_db;
+mod sled_db;
+
+pub use self::memory::MemoryBackend;
+#[cfg(feature = "rocksdb")]
+pub use self::rocks_db::RocksDbBackend;
+pub use self::sled_db::SledBackend;
+
+use crate::{Error, Value};
+use std::{fmt, path::Path, str::FromStr, sync::Arc};
+
+/// The collections of the volatile storage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Tree {
+    /// Binary data of blocks stored in the volatile storage.
+    Blocks,
+    /// Correspondence between IDs and chain lengths of blocks stored in the
+    /// permanent storage.
+    PermanentStoreBlocks,
+    /// Block information (see `BlockInfo`) for volatile storage.
+    Info,
+    /// Maintains conversion from chain length to block IDs. This tree has
+    /// empty values and keys in the form of `bytes(chain_length) ++ block_id`.
+    /// Such structure allows to get all blocks on the given chain length by
+    /// using prefix `bytes(chain_length)`.
+    ChainLengthIndex,
+    /// Holds references to blocks in the volatile storage that have no
+    /// descendants. This allows to quickly determine which branches should be
+    /// removed.
+    BranchesTips,
+    /// Converts a tag name to a block ID.
+    Tags,
+}
+
+impl Tree {
+    pub const ALL: [Tree; 6] = [
+        Tree::Blocks,
+        Tree::PermanentStoreBlocks,
+        Tree::Info,
+        Tree::ChainLengthIndex,
+        Tree::BranchesTips,
+        Tree::Tags,
+    ];
+
+    /// the name of the collection in the storage engine
+    pub fn name(self) -> &'static str {
+        match self {
+            Tree::Blocks => "blocks",
+            Tree::PermanentStoreBlocks => "permanent_store",
+            Tree::Info => "info",
+            Tree::ChainLengthIndex => "length_to_block_ids",
+            Tree::BranchesTips => "branches_tips",
+            Tree::Tags => "tags",
+        }
+    }
+}
+
+/// Iterator over the key-value pairs of a collection, in the order of the
+/// keys.
+pub type Iter<'a> = Box<dyn Iterator<Item = Result<(Value, Value), Error>> + 'a>;
+
+/// A key-value storage engine holding the volatile storage.
+pub trait Backend: Send + Sync {
+    fn get(&self, tree: Tree, key: &[u8]) -> Result<Option<Value>, Error>;
+
+    fn contains_key(&self, tree: Tree, key: &[u8]) -> Result<bool, Error> {
+        self.get(tree, key).map(|value| value.is_some())
+    }
+
+    fn insert(&self, tree: Tree, key: &[u8], value: &[u8]) -> Result<(), Error>;
+
+    fn remove(&self, tree: Tree, key: &[u8]) -> Result<(), Error>;
+
+    /// remove all the entries of the collection
+    fn clear(&self, tree: Tree) -> Result<(), Error>;
+
+    fn iter(&self, tree: Tree) -> Iter<'_>;
+
+    /// iterate over the entries whose key starts with `prefix`
+    fn scan_prefix<'a>(&'a self, tree: Tree, prefix: &[u8]) -> Iter<'a>;
+
+    /// Run `f` atomically: either all the changes it makes are applied or
+    /// none of them is. `f` may be run several times if the transaction
+    /// conflicts with another one.
+    fn transaction(
+        &self,
+        f: &mut dyn FnMut(&mut dyn Transaction) -> Result<(), Error>,
+    ) -> Result<(), Error>;
+
+    /// make sure the changes are written to the disk
+    fn flush(&self) -> Result<(), Error>;
+}
+
+/// The operations available in a [`Backend::transaction`].
+pub trait Transaction {
+    fn get(&mut self, tree: Tree, key: &[u8]) -> Result<Option<Value>, Error>;
+
+    fn insert(&mut self, tree: Tree, key: &[u8], value: &[u8]) -> Result<(), Error>;
+
+    fn remove(&mut self, tree: Tree, key: &[u8]) -> Result<(), Error>;
+}
+
+/// The storage engines available for the volatile storage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StorageEngine {
+    #[default]
+    Sled,
+    /// only available with the `rocksdb` feature
+    RocksDb,
+}
+
+impl StorageEngine {
+    /// the name of the directory of the volatile storage in the storage
+    /// directory
+    pub fn directory_name(self) -> &'static str {
+        match self {
+            StorageEngine::Sled => "volatile",
+            StorageEngine::RocksDb => "volatile-rocksdb",
+        }
+    }
+
+    pub(crate) fn open(self, path: &Path) -> Result<Arc<dyn Backend>, Error> {
+        match self {
+            StorageEngine::Sled => Ok(Arc::new(SledBackend::open(path)?)),
+            #[cfg(feature = "rocksdb")]
+            StorageEngine::RocksDb => Ok(Arc::new(RocksDbBackend::open(path)?)),
+            #[cfg(not(feature = "rocksdb"))]
+            StorageEngine::RocksDb => Err(Error::UnsupportedEngine(self)),
+        }
+    }
+}
+
+impl fmt::Display for StorageEngine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageEngine::Sled => f.write_str("sled"),
+            StorageEngine::RocksDb => f.write_str("rocksdb"),
+        }
+    }
+}
+
+impl FromStr for StorageEngine {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sled" => Ok(StorageEngine::Sled),
+            "rocksdb" => Ok(StorageEngine::RocksDb),
+            _ => Err(format!(
+                "unknown storage engine '{}', expected 'sled' or 'rocksdb'",
+                s
+            )),
+        }
+    }
+}
+
+/// Copy all the collections of `from` into `to`, returns the number of
+/// copied entries.
+pub(crate) fn copy_trees(from: &dyn Backend, to: &dyn Backend) -> Result<u64, Error> {
+    let mut count = 0;
+    for tree in Tree::ALL {
+        for entry in from.iter(tree) {
+            let (key, value) = entry?;
+            to.insert(tree, key.as_ref(), value.as_ref())?;
+            count += 1;
+        }
+    }
+    to.flush()?;
+    Ok(count)
+}
@@ -0,0 +1,160 @@
+use super::{Backend, Iter, Transaction, Tree};
+use crate::{Error, Value};
+use rocksdb::{
+    ColumnFamily, Direction, ErrorKind, IteratorMode, OptimisticTransactionDB, Options,
+    SingleThreaded,
+};
+use std::path::Path;
+
+type Db = OptimisticTransactionDB<SingleThreaded>;
+
+/// The volatile storage kept in a RocksDB database, each [`Tree`] being a
+/// column family.
+pub struct RocksDbBackend {
+    db: Db,
+}
+
+impl RocksDbBackend {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let mut options = Options::default();
+        options.create_if_missing(true);
+        options.create_missing_column_families(true);
+        let db = Db::open_cf(&options, path, Tree::ALL.iter().map(|tree| tree.name()))?;
+        Ok(Self { db })
+    }
+
+    fn column_family(&self, tree: Tree) -> &ColumnFamily {
+        self.db
+            .cf_handle(tree.name())
+            .expect("column families are created when opening the database")
+    }
+}
+
+impl Backend for RocksDbBackend {
+    fn get(&self, tree: Tree, key: &[u8]) -> Result<Option<Value>, Error> {
+        Ok(self
+            .db
+            .get_cf(self.column_family(tree), key)?
+            .map(Value::from))
+    }
+
+    fn insert(&self, tree: Tree, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        self.db
+            .put_cf(self.column_family(tree), key, value)
+            .map_err(Into::into)
+    }
+
+    fn remove(&self, tree: Tree, key: &[u8]) -> Result<(), Error> {
+        self.db
+            .delete_cf(self.column_family(tree), key)
+            .map_err(Into::into)
+    }
+
+    fn clear(&self, tree: Tree) -> Result<(), Error> {
+        for entry in self
+            .db
+            .iterator_cf(self.column_family(tree), IteratorMode::Start)
+        {
+            let (key, _) = entry?;
+            self.db.delete_cf(self.column_family(tree), key)?;
+        }
+        Ok(())
+    }
+
+    fn iter(&self, tree: Tree) -> Iter<'_> {
+        Box::new(
+            self.db
+                .iterator_cf(self.column_family(tree), IteratorMode::Start)
+                .map(map_entry),
+        )
+    }
+
+    fn scan_prefix<'a>(&'a self, tree: Tree, prefix: &[u8]) -> Iter<'a> {
+        let prefix = prefix.to_vec();
+        Box::new(
+            self.db
+                .iterator_cf(
+                    self.column_family(tree),
+                    IteratorMode::From(&prefix, Direction::Forward),
+                )
+                .take_while(move |entry| {
+                    entry
+                        .as_ref()
+                        .map_or(true, |(key, _)| key.starts_with(&prefix))
+                })
+                .map(map_entry),
+        )
+    }
+
+    fn transaction(
+        &self,
+        f: &mut dyn FnMut(&mut dyn Transaction) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        // optimistic transactions fail on commit if one of the keys they read
+        // was modified in the meantime, they are then run again, as well as
+        // when the closure reports a conflict
+        loop {
+            let mut transaction = RocksDbTransaction {
+                backend: self,
+                transaction: self.db.transaction(),
+            };
+            match f(&mut transaction) {
+                Ok(()) => {}
+                Err(Error::TransactionConflict) => continue,
+                Err(err) => return Err(err),
+            }
+            match transaction.transaction.commit().map_err(transaction_error) {
+                Ok(()) => return Ok(()),
+                Err(Error::TransactionConflict) => continue,
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    fn flush(&self) -> Result<(), Error> {
+        self.db.flush().map_err(Into::into)
+    }
+}
+
+struct RocksDbTransaction<'a> {
+    backend: &'a RocksDbBackend,
+    transaction: rocksdb::Transaction<'a, Db>,
+}
+
+impl Transaction for RocksDbTransaction<'_> {
+    fn get(&mut self, tree: Tree, key: &[u8]) -> Result<Option<Value>, Error> {
+        Ok(self
+            .transaction
+            .get_for_update_cf(self.backend.column_family(tree), key, true)
+            .map_err(transaction_error)?
+            .map(Value::from))
+    }
+
+    fn insert(&mut self, tree: Tree, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        self.transaction
+            .put_cf(self.backend.column_family(tree), key, value)
+            .map_err(transaction_error)
+    }
+
+    fn remove(&mut self, tree: Tree, key: &[u8]) -> Result<(), Error> {
+        self.transaction
+            .delete_cf(self.backend.column_family(tree), key)
+            .map_err(transaction_error)
+    }
+}
+
+/// RocksDB reports the conflicts of optimistic transactions as busy errors
+fn transaction_error(err: rocksdb::Error) -> Error {
+    match err.kind() {
+        ErrorKind::Busy | ErrorKind::TryAgain => Error::TransactionConflict,
+        _ => err.into(),
+    }
+}
+
+fn map_entry(
+    entry: Result<(Box<[u8]>, Box<[u8]>), rocksdb::Error>,
+) -> Result<(Value, Value), Error> {
+    entry
+        .map(|(key, value)| (Value::from(key), Value::from(value)))
+        .map_err(Into::into)
+}
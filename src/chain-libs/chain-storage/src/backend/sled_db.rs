@@ -0,0 +1,140 @@
+use super::{Backend, Iter, Transaction, Tree};
+use crate::{Error, Value};
+use sled::transaction::{
+    ConflictableTransactionError, TransactionError, Transactional, TransactionalTree,
+    UnabortableTransactionError,
+};
+use std::{cell::RefCell, path::Path};
+
+/// The volatile storage kept in a `sled` database.
+pub struct SledBackend {
+    // in the order of `Tree::ALL`
+    trees: Vec<sled::Tree>,
+
+    // needs to be kept so that the database is always closed correctly
+    db: sled::Db,
+}
+
+impl SledBackend {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::new(sled::open(path)?)
+    }
+
+    /// open a temporary database, removed when it is dropped
+    pub fn temporary() -> Result<Self, Error> {
+        let db = sled::Config::new()
+            .temporary(true)
+            .open()
+            .map_err(|err| Error::Open(err.into()))?;
+        Self::new(db)
+    }
+
+    fn new(db: sled::Db) -> Result<Self, Error> {
+        let trees = Tree::ALL
+            .iter()
+            .map(|tree| db.open_tree(tree.name()))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { trees, db })
+    }
+
+    fn tree(&self, tree: Tree) -> &sled::Tree {
+        &self.trees[tree as usize]
+    }
+}
+
+impl Backend for SledBackend {
+    fn get(&self, tree: Tree, key: &[u8]) -> Result<Option<Value>, Error> {
+        Ok(self.tree(tree).get(key)?.map(Value::volatile))
+    }
+
+    fn contains_key(&self, tree: Tree, key: &[u8]) -> Result<bool, Error> {
+        self.tree(tree).contains_key(key).map_err(Into::into)
+    }
+
+    fn insert(&self, tree: Tree, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        self.tree(tree).insert(key, value)?;
+        Ok(())
+    }
+
+    fn remove(&self, tree: Tree, key: &[u8]) -> Result<(), Error> {
+        self.tree(tree).remove(key)?;
+        Ok(())
+    }
+
+    fn clear(&self, tree: Tree) -> Result<(), Error> {
+        self.tree(tree).clear().map_err(Into::into)
+    }
+
+    fn iter(&self, tree: Tree) -> Iter<'_> {
+        Box::new(self.tree(tree).iter().map(map_entry))
+    }
+
+    fn scan_prefix<'a>(&'a self, tree: Tree, prefix: &[u8]) -> Iter<'a> {
+        Box::new(self.tree(tree).scan_prefix(prefix).map(map_entry))
+    }
+
+    fn transaction(
+        &self,
+        f: &mut dyn FnMut(&mut dyn Transaction) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        let f = RefCell::new(f);
+        self.trees
+            .as_slice()
+            .transaction(|trees| {
+                let mut transaction = SledTransaction { trees };
+                (f.borrow_mut())(&mut transaction).map_err(|err| match err {
+                    Error::TransactionConflict => ConflictableTransactionError::Conflict,
+                    err => ConflictableTransactionError::Abort(err),
+                })
+            })
+            .map_err(|err: TransactionError<Error>| match err {
+                TransactionError::Abort(err) => err,
+                TransactionError::Storage(err) => err.into(),
+            })
+    }
+
+    fn flush(&self) -> Result<(), Error> {
+        self.db.flush()?;
+        Ok(())
+    }
+}
+
+struct SledTransaction<'a> {
+    trees: &'a [TransactionalTree],
+}
+
+impl Transaction for SledTransaction<'_> {
+    fn get(&mut self, tree: Tree, key: &[u8]) -> Result<Option<Value>, Error> {
+        self.trees[tree as usize]
+            .get(key)
+            .map(|value| value.map(Value::volatile))
+            .map_err(map_transaction_error)
+    }
+
+    fn insert(&mut self, tree: Tree, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        self.trees[tree as usize]
+            .insert(key, value)
+            .map(|_| ())
+            .map_err(map_transaction_error)
+    }
+
+    fn remove(&mut self, tree: Tree, key: &[u8]) -> Result<(), Error> {
+        self.trees[tree as usize]
+            .remove(key)
+            .map(|_| ())
+            .map_err(map_transaction_error)
+    }
+}
+
+fn map_entry(entry: sled::Result<(sled::IVec, sled::IVec)>) -> Result<(Value, Value), Error> {
+    entry
+        .map(|(key, value)| (Value::volatile(key), Value::volatile(value)))
+        .map_err(Into::into)
+}
+
+fn map_transaction_error(err: UnabortableTransactionError) -> Error {
+    match err {
+        UnabortableTransactionError::Conflict => Error::TransactionConflict,
+        UnabortableTransactionError::Storage(err) => err.into(),
+    }
+}
@@ -1,28 +1,19 @@
 use crate::{
-    permanent_store::PermanentStore, BlockInfo, ConsistencyFailure, Error, StorageIterator, Value,
+    backend::{Backend, MemoryBackend, StorageEngine, Transaction, Tree},
+    permanent_store::PermanentStore,
+    BlockInfo, ConsistencyFailure, Error, StorageIterator, Value,
 };
-use sled::{
-    transaction::{
-        ConflictableTransactionError, TransactionError, Transactional, TransactionalTree,
-    },
-    Tree,
-};
-use std::path::Path;
+use std::{path::Path, sync::Arc};
+
+pub(crate) mod maintenance;
 
 #[derive(Clone)]
 pub struct BlockStore {
-    permanent: PermanentStore,
+    pub(crate) permanent: PermanentStore,
     root_id: Value,
     id_length: usize,
 
-    blocks_tree: Tree,
-    info_tree: Tree,
-    chain_length_index_tree: Tree,
-    branches_tips_tree: Tree,
-    tags_tree: Tree,
-
-    // needs to be kept so that the database is always closed correctly
-    _db: sled::Db,
+    pub(crate) volatile: Arc<dyn Backend>,
 }
 
 enum RemoveTipResult {
@@ -31,54 +22,10 @@ enum RemoveTipResult {
     Done,
 }
 
-impl From<Error> for ConflictableTransactionError<Error> {
-    fn from(from: Error) -> Self {
-        ConflictableTransactionError::Abort(from)
-    }
-}
-
-impl From<ConsistencyFailure> for ConflictableTransactionError<Error> {
-    fn from(from: ConsistencyFailure) -> Self {
-        ConflictableTransactionError::Abort(from.into())
-    }
-}
-
-impl From<TransactionError<Error>> for Error {
-    fn from(from: TransactionError<Error>) -> Self {
-        match from {
-            TransactionError::Abort(err) => err,
-            TransactionError::Storage(err) => err.into(),
-        }
-    }
-}
-
-// Names of trees in `sled` storage. For documentation about trees please refer
-// to `sled` docs.
-mod tree {
-    // Binary data of blocks stored in the volatile storage.
-    pub const BLOCKS: &str = "blocks";
-    // Correspondence between IDs and chain lengths of blocks stored in the
-    // permanent storage.
-    pub const PERMANENT_STORE_BLOCKS: &str = "permanent_store";
-    // Block information (see `BlockInfo`) for volatile storage.
-    pub const INFO: &str = "info";
-    // Maintains conversion from chain length to block IDs. This tree has empty
-    // values and keys in the form of `bytes(chain_length) ++ block_id`. Such
-    // structure allows to get all blocks on the given chain length by using
-    // prefix `bytes(chain_length)`. `sled` allows to iterate over key-value
-    // pairs with the same prefix.
-    pub const CHAIN_LENGTH_INDEX: &str = "length_to_block_ids";
-    // Holds references to blocks in the volatile storage that have no
-    // descendants. This allows to quickly determine which branches should be
-    // removed.
-    pub const BRANCHES_TIPS: &str = "branches_tips";
-    // Converts a tag name to a block ID.
-    pub const TAGS: &str = "tags";
-}
-
 impl BlockStore {
-    /// Create a new storage handle. The path must not exist or should be a
-    /// directory. The directory will be created if it does not exist.
+    /// Create a new storage handle with the default storage engine. The path
+    /// must not exist or should be a directory. The directory will be created
+    /// if it does not exist.
     ///
     /// # Arguments
     ///
@@ -88,20 +35,43 @@ impl BlockStore {
     pub fn file<P: AsRef<Path>, I: Into<Value> + Clone>(
         path: P,
         root_id: I,
+    ) -> Result<Self, Error> {
+        Self::file_with_engine(path, root_id, StorageEngine::default())
+    }
+
+    /// Create a new storage handle keeping the volatile storage with the
+    /// given engine, see [`BlockStore::file`].
+    ///
+    /// A storage created with another engine is not opened, it needs to be
+    /// converted with [`BlockStore::convert`] first.
+    pub fn file_with_engine<P: AsRef<Path>, I: Into<Value> + Clone>(
+        path: P,
+        root_id: I,
+        engine: StorageEngine,
     ) -> Result<Self, Error> {
         if !path.as_ref().exists() {
             std::fs::create_dir(path.as_ref()).map_err(Error::Open)?;
         }
 
-        let volatile_path = path.as_ref().join("volatile");
+        let volatile_path = path.as_ref().join(engine.directory_name());
         let permanent_path = path.as_ref().join("permanent");
 
-        let volatile = sled::open(volatile_path)?;
+        if !volatile_path.exists() {
+            if let Some(found) = [StorageEngine::Sled, StorageEngine::RocksDb]
+                .into_iter()
+                .find(|other| path.as_ref().join(other.directory_name()).exists())
+            {
+                return Err(Error::EngineMismatch {
+                    expected: engine,
+                    found,
+                });
+            }
+        }
 
-        let block_id_index = volatile.open_tree(tree::PERMANENT_STORE_BLOCKS)?;
-        let permanent = PermanentStore::file(permanent_path, block_id_index, root_id.clone())?;
+        let volatile = engine.open(&volatile_path)?;
+        let permanent = PermanentStore::file(permanent_path, volatile.clone(), root_id.clone())?;
 
-        Self::new(root_id, volatile, permanent)
+        Ok(Self::new(root_id, volatile, permanent))
     }
 
     /// Open a temporary in-memory database.
@@ -111,43 +81,67 @@ impl BlockStore {
     /// * `root_id` - the ID of the root block which the first block in this
     ///   block chain should refer to as a parent.
     pub fn memory<I: Into<Value> + Clone>(root_id: I) -> Result<Self, Error> {
-        let volatile = sled::Config::new()
-            .temporary(true)
-            .open()
-            .map_err(|err| Error::Open(err.into()))?;
-        let block_id_index = volatile.open_tree(tree::PERMANENT_STORE_BLOCKS)?;
-        let permanent = PermanentStore::memory(block_id_index, root_id.clone())?;
-
-        Self::new(root_id, volatile, permanent)
+        let volatile: Arc<dyn Backend> = Arc::new(MemoryBackend::new());
+        let permanent = PermanentStore::memory(volatile.clone(), root_id.clone())?;
+
+        Ok(Self::new(root_id, volatile, permanent))
+    }
+
+    /// Copy the volatile storage of the store in the given directory from
+    /// one engine to another. The store must not be opened. The storage of
+    /// the `from` engine is kept, it can be removed once the store has been
+    /// opened with the `to` engine.
+    ///
+    /// The entries are copied to a temporary directory which is only moved in
+    /// place once the copy is complete, an interrupted conversion leaves no
+    /// storage for the `to` engine behind.
+    ///
+    /// # Returns
+    ///
+    /// The number of copied entries.
+    pub fn convert<P: AsRef<Path>>(
+        path: P,
+        from: StorageEngine,
+        to: StorageEngine,
+    ) -> Result<u64, Error> {
+        let from_path = path.as_ref().join(from.directory_name());
+        let to_path = path.as_ref().join(to.directory_name());
+        let converted_path = to_path.with_extension("convert");
+        if !from_path.exists() {
+            return Err(Error::Open(std::io::ErrorKind::NotFound.into()));
+        }
+        if to_path.exists() {
+            return Err(Error::ConversionTargetExists);
+        }
+        if converted_path.exists() {
+            std::fs::remove_dir_all(&converted_path).map_err(Error::Open)?;
+        }
+
+        let entries = {
+            let source = from.open(&from_path)?;
+            let destination = to.open(&converted_path)?;
+            let entries = crate::backend::copy_trees(source.as_ref(), destination.as_ref())?;
+            destination.flush()?;
+            entries
+        };
+        std::fs::rename(&converted_path, &to_path).map_err(Error::Open)?;
+        Ok(entries)
     }
 
     fn new<I: Into<Value>>(
         root_id: I,
-        volatile: sled::Db,
+        volatile: Arc<dyn Backend>,
         permanent: PermanentStore,
-    ) -> Result<Self, Error> {
+    ) -> Self {
         let root_id = root_id.into();
         let id_length = root_id.as_ref().len();
 
-        let blocks_tree = volatile.open_tree(tree::BLOCKS)?;
-        let info_tree = volatile.open_tree(tree::INFO)?;
-        let chain_length_index_tree = volatile.open_tree(tree::CHAIN_LENGTH_INDEX)?;
-        let branches_tips_tree = volatile.open_tree(tree::BRANCHES_TIPS)?;
-        let tags_tree = volatile.open_tree(tree::TAGS)?;
-
-        Ok(Self {
+        Self {
             permanent,
             root_id,
             id_length,
-
-            blocks_tree,
-            info_tree,
-            chain_length_index_tree,
-            branches_tips_tree,
-            tags_tree,
-
-            _db: volatile,
-        })
+            volatile,
+        }
     }
 
     /// Write a block to the store. The parent of the block must exist (unless
@@ -167,26 +161,16 @@ impl BlockStore {
             .permanent
             .contains_key(block_info.parent_id().as_ref())?;
 
-        (
-            &self.blocks_tree,
-            &self.info_tree,
-            &self.chain_length_index_tree,
-            &self.branches_tips_tree,
-        )
-            .transaction(|(blocks, info, chain_length_to_block_ids, tips)| {
-                put_block_impl(
-                    blocks,
-                    info,
-                    chain_length_to_block_ids,
-                    tips,
-                    block,
-                    &block_info,
-                    self.root_id.as_ref(),
-                    self.id_length,
-                    parent_in_permanent_store,
-                )
-            })
-            .map_err(Into::into)
+        self.volatile.transaction(&mut |transaction| {
+            put_block_impl(
+                transaction,
+                block,
+                &block_info,
+                self.root_id.as_ref(),
+                self.id_length,
+                parent_in_permanent_store,
+            )
+        })
     }
 
     /// Get a block from the storage.
@@ -199,11 +183,9 @@ impl BlockStore {
             return Ok(block);
         }
 
-        self.blocks_tree
-            .get(block_id)
-            .map_err(Into::into)
+        self.volatile
+            .get(Tree::Blocks, block_id)
             .and_then(|maybe_block| maybe_block.ok_or(Error::BlockNotFound))
-            .map(Value::volatile)
     }
 
     /// Get the `BlockInfo` instance for the requested block.
@@ -220,12 +202,11 @@ impl BlockStore {
     }
 
     fn get_block_info_volatile(&self, block_id: &[u8]) -> Result<BlockInfo, Error> {
-        self.info_tree
-            .get(block_id)
-            .map_err(Into::into)
+        self.volatile
+            .get(Tree::Info, block_id)
             .and_then(|maybe_block| maybe_block.ok_or(Error::BlockNotFound))
             .and_then(|block_info_bin| {
-                let mut block_info_reader: &[u8] = &block_info_bin;
+                let mut block_info_reader: &[u8] = block_info_bin.as_ref();
                 BlockInfo::deserialize(&mut block_info_reader, self.id_length, block_id.to_vec())
             })
     }
@@ -239,93 +220,63 @@ impl BlockStore {
             return Ok(vec![block]);
         }
 
-        self.chain_length_index_tree
-            .scan_prefix(build_chain_length_index_prefix(chain_length))
+        self.volatile
+            .scan_prefix(
+                Tree::ChainLengthIndex,
+                &build_chain_length_index_prefix(chain_length),
+            )
             .map(|scan_result| {
                 let (block_id, _) = scan_result?;
 
-                self.blocks_tree
-                    .get(block_id_from_chain_length_index(&block_id))?
+                self.volatile
+                    .get(
+                        Tree::Blocks,
+                        block_id_from_chain_length_index(block_id.as_ref()),
+                    )?
                     .ok_or(Error::Inconsistent(ConsistencyFailure::ChainLength))
-                    .map(Value::volatile)
             })
             .collect::<Result<Vec<_>, _>>()
-            .map_err(Into::into)
     }
 
     /// Add a tag for a given block. The block id can be later retrieved by this
     /// tag.
     pub fn put_tag(&self, tag_name: &str, block_id: &[u8]) -> Result<(), Error> {
-        let permanent_store_index = self.permanent.block_id_index();
-
-        (&self.info_tree, &self.tags_tree, permanent_store_index)
-            .transaction(move |(info, tags, permanent_store_index)| {
-                put_tag_impl(
-                    info,
-                    tags,
-                    permanent_store_index,
-                    tag_name,
-                    block_id,
-                    self.id_length,
-                )
-            })
-            .map_err(Into::into)
+        self.volatile.transaction(&mut |transaction| {
+            put_tag_impl(transaction, tag_name, block_id, self.id_length)
+        })
     }
 
     /// Get the block ID for the given tag.
     pub fn get_tag(&self, tag_name: &str) -> Result<Option<Value>, Error> {
-        self.tags_tree
-            .get(tag_name)
-            .map(|maybe_id_bin| maybe_id_bin.map(Value::volatile))
-            .map_err(Into::into)
+        self.volatile.get(Tree::Tags, tag_name.as_bytes())
     }
 
     /// Get identifier of all branches tips.
     pub fn get_tips_ids(&self) -> Result<Vec<Value>, Error> {
-        self.branches_tips_tree
-            .iter()
-            .map(|id_result| id_result.map(|(id, _)| Value::volatile(id)))
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(Into::into)
+        self.volatile
+            .iter(Tree::BranchesTips)
+            .map(|id_result| id_result.map(|(id, _)| id))
+            .collect()
     }
 
     /// Prune a branch with the given tip id from the storage.
     pub fn prune_branch(&self, tip_id: &[u8]) -> Result<(), Error> {
-        if !self.branches_tips_tree.contains_key(tip_id)? {
+        if !self.volatile.contains_key(Tree::BranchesTips, tip_id)? {
             return Err(Error::BranchNotFound);
         }
 
-        let permanent_store_index = self.permanent.block_id_index();
+        let mut result = RemoveTipResult::Done;
+        self.volatile.transaction(&mut |transaction| {
+            result = RemoveTipResult::NextTip {
+                id: Vec::from(tip_id),
+            };
 
-        let result = (
-            &self.blocks_tree,
-            &self.info_tree,
-            &self.chain_length_index_tree,
-            &self.branches_tips_tree,
-            permanent_store_index,
-        )
-            .transaction(
-                |(blocks, info, chain_length_to_block_ids, tips, permanent_store_index)| {
-                    let mut result = RemoveTipResult::NextTip {
-                        id: Vec::from(tip_id),
-                    };
-
-                    while let RemoveTipResult::NextTip { id } = &result {
-                        result = remove_tip_impl(
-                            blocks,
-                            info,
-                            chain_length_to_block_ids,
-                            tips,
-                            permanent_store_index,
-                            id,
-                            self.root_id.as_ref(),
-                            self.id_length,
-                        )?;
-                    }
-
-                    Ok(result)
-                },
-            )?;
+            while let RemoveTipResult::NextTip { id } = &result {
+                result = remove_tip_impl(transaction, id, self.root_id.as_ref(), self.id_length)?;
+            }
+
+            Ok(())
+        })?;
 
         if let RemoveTipResult::HitPermanentStore { id } = result {
             let block_info = self.get_block_info(&id).map_err(|err| match err {
@@ -335,7 +286,8 @@ impl BlockStore {
             let chain_length = block_info.chain_length() + 1;
 
             if self.get_blocks_by_chain_length(chain_length)?.is_empty() {
-                self.branches_tips_tree.insert(block_info.id(), &[])?;
+                self.volatile
+                    .insert(Tree::BranchesTips, block_info.id().as_ref(), &[])?;
             }
         }
 
@@ -352,7 +304,7 @@ impl BlockStore {
     }
 
     fn block_exists_volatile(&self, block_id: &[u8]) -> Result<bool, Error> {
-        self.info_tree.contains_key(block_id).map_err(Into::into)
+        self.volatile.contains_key(Tree::Info, block_id)
     }
 
     /// Determine whether block identified by `ancestor_id` is an ancestor of
@@ -396,9 +348,10 @@ impl BlockStore {
             return Ok(Some(1));
         }
 
-        let mut chain_length_iter = self
-            .chain_length_index_tree
-            .scan_prefix(build_chain_length_index_prefix(ancestor.chain_length()));
+        let mut chain_length_iter = self.volatile.scan_prefix(
+            Tree::ChainLengthIndex,
+            &build_chain_length_index_prefix(ancestor.chain_length()),
+        );
 
         // if the target length is in the volatile storage and there is only one
         // block at the given length, this block is an ancestor
@@ -446,17 +399,19 @@ impl BlockStore {
             return Ok(info);
         }
 
-        let mut chain_length_iter = self
-            .chain_length_index_tree
-            .scan_prefix(build_chain_length_index_prefix(target));
+        let mut chain_length_iter = self.volatile.scan_prefix(
+            Tree::ChainLengthIndex,
+            &build_chain_length_index_prefix(target),
+        );
 
         // if the target length is in the volatile storage and there is only one
         // block at the given length, it is an ancestor
         if let Some(chain_length_res) = chain_length_iter.next() {
             let (chain_length_index_entry, _) = chain_length_res?;
             if chain_length_iter.next().is_none() {
-                return self
-                    .get_block_info(block_id_from_chain_length_index(&chain_length_index_entry));
+                return self.get_block_info(block_id_from_chain_length_index(
+                    chain_length_index_entry.as_ref(),
+                ));
             }
         }
 
@@ -580,12 +535,12 @@ impl BlockStore {
         };
 
         match self
-            .chain_length_index_tree
-            .scan_prefix(chain_length_prefix)
+            .volatile
+            .scan_prefix(Tree::ChainLengthIndex, &chain_length_prefix)
             .next()
         {
             Some(Ok(_)) => {}
-            Some(Err(err)) => return Err(err.into()),
+            Some(Err(err)) => return Err(err),
             None => return Ok(0),
         }
 
@@ -624,14 +579,15 @@ impl BlockStore {
         self.permanent
             .put_blocks(start_chain_length, &ids, &block_refs)?;
 
-        for (i, block_info) in block_infos.iter().enumerate() {
+        for block_info in block_infos.iter() {
             let key = block_info.id().as_ref();
-            let chain_length = start_chain_length + i as u32;
 
-            self.info_tree.remove(key)?;
-            self.blocks_tree.remove(key)?;
-            self.chain_length_index_tree
-                .remove(build_chain_length_index(chain_length, key))?;
+            self.volatile.remove(Tree::Info, key)?;
+            self.volatile.remove(Tree::Blocks, key)?;
+            self.volatile.remove(
+                Tree::ChainLengthIndex,
+                &build_chain_length_index(block_info.chain_length(), key),
+            )?;
         }
 
         Ok(block_infos.len())
@@ -649,147 +605,148 @@ impl BlockStore {
             Value::from(to_block.to_vec()),
             distance,
             self.permanent.clone(),
-            self.info_tree.clone(),
-            self.blocks_tree.clone(),
+            self.volatile.clone(),
         )
     }
 }
 
 #[inline]
-#[allow(clippy::too_many_arguments)]
 fn put_block_impl(
-    blocks: &TransactionalTree,
-    info: &TransactionalTree,
-    chain_length_to_block_ids: &TransactionalTree,
-    tips: &TransactionalTree,
+    transaction: &mut dyn Transaction,
     block: &[u8],
     block_info: &BlockInfo,
     root_id: &[u8],
     id_length: usize,
     parent_external: bool,
-) -> Result<(), ConflictableTransactionError<Error>> {
+) -> Result<(), Error> {
     let parent_in_volatile_store = if parent_external || block_info.parent_id().as_ref() == root_id
     {
         false
-    } else if info.get(block_info.parent_id())?.is_none() {
-        return Err(Error::MissingParent.into());
+    } else if transaction
+        .get(Tree::Info, block_info.parent_id().as_ref())?
+        .is_none()
+    {
+        return Err(Error::MissingParent);
     } else {
         true
     };
 
     if parent_in_volatile_store {
-        let parent_block_info_bin = info
-            .get(block_info.parent_id())?
+        let parent_block_info_bin = transaction
+            .get(Tree::Info, block_info.parent_id().as_ref())?
             .ok_or(ConsistencyFailure::BlockInfo)?;
-        let mut parent_block_info_reader: &[u8] = &parent_block_info_bin;
+        let mut parent_block_info_reader: &[u8] = parent_block_info_bin.as_ref();
         let mut parent_block_info = BlockInfo::deserialize(
             &mut parent_block_info_reader,
             id_length,
             block_info.parent_id().clone(),
         )?;
         parent_block_info.add_parent_ref();
-        info.insert(
+        transaction.insert(
+            Tree::Info,
             parent_block_info.id().as_ref(),
-            parent_block_info.serialize()?,
+            &parent_block_info.serialize()?,
         )?;
     }
 
-    tips.remove(block_info.parent_id().as_ref())?;
-    tips.insert(block_info.id().as_ref(), &[])?;
+    transaction.remove(Tree::BranchesTips, block_info.parent_id().as_ref())?;
+    transaction.insert(Tree::BranchesTips, block_info.id().as_ref(), &[])?;
 
-    chain_length_to_block_ids.insert(
-        build_chain_length_index(block_info.chain_length(), block_info.id().as_ref()),
+    transaction.insert(
+        Tree::ChainLengthIndex,
+        &build_chain_length_index(block_info.chain_length(), block_info.id().as_ref()),
         &[],
     )?;
 
-    blocks.insert(block_info.id().as_ref(), block)?;
+    transaction.insert(Tree::Blocks, block_info.id().as_ref(), block)?;
 
-    info.insert(block_info.id().as_ref(), block_info.serialize()?)?;
+    transaction.insert(
+        Tree::Info,
+        block_info.id().as_ref(),
+        &block_info.serialize()?,
+    )?;
 
     Ok(())
 }
 
 #[inline]
 fn put_tag_impl(
-    info: &TransactionalTree,
-    tags: &TransactionalTree,
-    permanent_store_index: &TransactionalTree,
+    transaction: &mut dyn Transaction,
     tag_name: &str,
     block_id: &[u8],
     id_size: usize,
-) -> Result<(), ConflictableTransactionError<Error>> {
-    if let Some(info_bin) = info.get(block_id)? {
-        let mut block_info = BlockInfo::deserialize(&info_bin[..], id_size, block_id.to_vec())?;
+) -> Result<(), Error> {
+    if let Some(info_bin) = transaction.get(Tree::Info, block_id)? {
+        let mut block_info = BlockInfo::deserialize(info_bin.as_ref(), id_size, block_id.to_vec())?;
         block_info.add_tag_ref();
         let info_bin = block_info.serialize()?;
-        info.insert(block_id, info_bin)?;
-    } else if !permanent_store_index
-        .get(block_id)
-        .map(|maybe_block| maybe_block.is_some())?
+        transaction.insert(Tree::Info, block_id, &info_bin)?;
+    } else if transaction
+        .get(Tree::PermanentStoreBlocks, block_id)?
+        .is_none()
     {
-        return Err(Error::BlockNotFound.into());
+        return Err(Error::BlockNotFound);
     }
 
-    let maybe_old_block_id = tags.insert(tag_name, block_id)?;
+    let maybe_old_block_id = transaction.get(Tree::Tags, tag_name.as_bytes())?;
+    transaction.insert(Tree::Tags, tag_name.as_bytes(), block_id)?;
 
     if let Some(old_block_id) = maybe_old_block_id {
-        let info_bin = info
-            .get(old_block_id.clone())?
+        let info_bin = transaction
+            .get(Tree::Info, old_block_id.as_ref())?
             .ok_or(ConsistencyFailure::TaggedBlock)?;
-        let mut block_info = BlockInfo::deserialize(&info_bin[..], id_size, old_block_id.to_vec())?;
+        let mut block_info =
+            BlockInfo::deserialize(info_bin.as_ref(), id_size, old_block_id.as_ref().to_vec())?;
         block_info.remove_tag_ref();
         let info_bin = block_info.serialize()?;
-        info.insert(block_info.id().as_ref(), info_bin)?;
+        transaction.insert(Tree::Info, block_info.id().as_ref(), &info_bin)?;
     }
 
     Ok(())
 }
 
 #[inline]
-#[allow(clippy::too_many_arguments)]
 fn remove_tip_impl(
-    blocks: &TransactionalTree,
-    info: &TransactionalTree,
-    chain_length_to_block_ids: &TransactionalTree,
-    tips: &TransactionalTree,
-    permanent_store_index: &TransactionalTree,
+    transaction: &mut dyn Transaction,
     block_id: &[u8],
     root_id: &[u8],
     id_size: usize,
-) -> Result<RemoveTipResult, ConflictableTransactionError<Error>> {
+) -> Result<RemoveTipResult, Error> {
     // Stop when we bump into a block stored in the permanent storage.
-    if permanent_store_index
-        .get(block_id)
-        .map(|maybe_block| maybe_block.is_some())?
+    if transaction
+        .get(Tree::PermanentStoreBlocks, block_id)?
+        .is_some()
     {
         return Ok(RemoveTipResult::Done);
     }
 
-    let block_info_bin = info.get(block_id)?.ok_or(ConsistencyFailure::BlockInfo)?;
-    let mut block_info_reader: &[u8] = &block_info_bin;
+    let block_info_bin = transaction
+        .get(Tree::Info, block_id)?
+        .ok_or(ConsistencyFailure::BlockInfo)?;
+    let mut block_info_reader: &[u8] = block_info_bin.as_ref();
     let block_info = BlockInfo::deserialize(&mut block_info_reader, id_size, block_id.to_vec())?;
 
     if block_info.ref_count() != 0 {
         return Ok(RemoveTipResult::Done);
     }
 
-    info.remove(block_id)?;
-    blocks.remove(block_id)?;
+    transaction.remove(Tree::Info, block_id)?;
+    transaction.remove(Tree::Blocks, block_id)?;
 
-    chain_length_to_block_ids.remove(build_chain_length_index(
-        block_info.chain_length(),
-        block_info.id().as_ref(),
-    ))?;
+    transaction.remove(
+        Tree::ChainLengthIndex,
+        &build_chain_length_index(block_info.chain_length(), block_info.id().as_ref()),
+    )?;
 
-    tips.remove(block_id)?;
+    transaction.remove(Tree::BranchesTips, block_id)?;
 
     if block_info.parent_id().as_ref() == root_id {
         return Ok(RemoveTipResult::Done);
     }
 
-    let parent_permanent = permanent_store_index
-        .get(block_info.parent_id().as_ref())
-        .map(|maybe_block| maybe_block.is_some())?;
+    let parent_permanent = transaction
+        .get(Tree::PermanentStoreBlocks, block_info.parent_id().as_ref())?
+        .is_some();
 
     if parent_permanent {
         return Ok(RemoveTipResult::HitPermanentStore {
@@ -797,19 +754,20 @@ fn remove_tip_impl(
         });
     }
 
-    let parent_block_info_bin = info
-        .get(block_info.parent_id())?
+    let parent_block_info_bin = transaction
+        .get(Tree::Info, block_info.parent_id().as_ref())?
         .ok_or(ConsistencyFailure::MissingParentBlock)?;
-    let mut parent_block_info_reader: &[u8] = &parent_block_info_bin;
+    let mut parent_block_info_reader: &[u8] = parent_block_info_bin.as_ref();
     let mut parent_block_info = BlockInfo::deserialize(
         &mut parent_block_info_reader,
         id_size,
         block_info.parent_id().clone(),
     )?;
     parent_block_info.remove_parent_ref();
-    info.insert(
+    transaction.insert(
+        Tree::Info,
         parent_block_info.id().as_ref(),
-        parent_block_info.serialize()?,
+        &parent_block_info.serialize()?,
     )?;
 
     // If the block is inside another branch it cannot be a tip.
@@ -817,7 +775,7 @@ fn remove_tip_impl(
         return Ok(RemoveTipResult::Done);
     }
 
-    tips.insert(block_info.parent_id().as_ref(), &[])?;
+    transaction.insert(Tree::BranchesTips, block_info.parent_id().as_ref(), &[])?;
 
     // A referenced block cannot be removed.
     if parent_block_info.ref_count() != 0 {
@@ -0,0 +1,516 @@
+//! Offline maintenance of the block store: integrity check, reconstruction of
+//! the indexes and compaction of the volatile storage.
+//!
+//! The storage does not know the format of the blocks, so these operations
+//! take a `decode` function computing the `BlockInfo` of a block from its
+//! content (its ID, the ID of its parent and its chain length). It returns
+//! `None` if the block cannot be decoded.
+
+use super::{build_chain_length_index, BlockStore};
+use crate::{
+    backend::{copy_trees, StorageEngine, Tree},
+    BlockInfo, Error,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    path::Path,
+};
+
+/// A problem found by [`BlockStore::check_integrity`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntegrityIssue {
+    /// the block cannot be decoded or its content does not match its ID
+    CorruptedBlock { id: Vec<u8> },
+    /// the `BlockInfo` of the block does not match the content of the block
+    BlockInfoMismatch { id: Vec<u8> },
+    /// the `BlockInfo` of the block is stored but not the block itself
+    MissingBlock { id: Vec<u8> },
+    /// the block is stored but not its `BlockInfo`
+    MissingBlockInfo { id: Vec<u8> },
+    /// the parent of the block is not stored
+    MissingParent { id: Vec<u8> },
+    /// the number of references to the block is not the expected one
+    RefCount { id: Vec<u8> },
+    /// the chain length index does not match the stored blocks
+    ChainLengthIndex { chain_length: u32, id: Vec<u8> },
+    /// the permanent storage index does not match the permanent storage
+    PermanentIndex { chain_length: u32 },
+    /// the branch tip is not stored or has descendants
+    Tip { id: Vec<u8> },
+    /// the tag refers to a block that is not stored
+    Tag { name: String },
+}
+
+/// The result of [`BlockStore::check_integrity`].
+#[derive(Debug, Default)]
+pub struct IntegrityReport {
+    pub permanent_blocks: u32,
+    pub volatile_blocks: usize,
+    pub issues: Vec<IntegrityIssue>,
+}
+
+impl IntegrityReport {
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// The result of [`BlockStore::rebuild_indexes`].
+#[derive(Debug, Default)]
+pub struct RepairReport {
+    /// the number of blocks indexed in the permanent storage
+    pub permanent_blocks: u32,
+    /// the number of blocks kept in the volatile storage
+    pub volatile_blocks: usize,
+    /// the IDs of the volatile blocks that could not be decoded or are not
+    /// connected to the chain anymore, they are removed
+    pub removed_blocks: Vec<Vec<u8>>,
+    /// the tags referring to blocks that are not stored, they are removed
+    pub removed_tags: Vec<String>,
+    /// the issues of the permanent storage that cannot be repaired
+    pub unrecoverable: Vec<IntegrityIssue>,
+}
+
+/// The result of [`BlockStore::compact`].
+#[derive(Debug)]
+pub struct CompactionReport {
+    pub size_before: u64,
+    pub size_after: u64,
+}
+
+impl BlockStore {
+    /// Check the consistency of the whole store: the blocks match their
+    /// `BlockInfo`, every block is linked to its parent down to the root and
+    /// the chain length index, the permanent storage index, the branches tips
+    /// and the tags only refer to stored blocks.
+    pub fn check_integrity<F>(&self, decode: F) -> Result<IntegrityReport, Error>
+    where
+        F: Fn(&[u8]) -> Option<BlockInfo>,
+    {
+        let mut report = IntegrityReport::default();
+
+        let mut permanent_ids = HashSet::new();
+        let mut previous_id = self.root_id.as_ref().to_vec();
+        let mut chain_length = 0;
+        while let Some(id) = self.permanent.get_block_id_by_chain_length(chain_length) {
+            let id = id.as_ref().to_vec();
+            match self.permanent.get_block_by_chain_length(chain_length) {
+                Some(block) => match decode(block.as_ref()) {
+                    Some(info)
+                        if info.id().as_ref() == id.as_slice()
+                            && info.parent_id().as_ref() == previous_id.as_slice()
+                            && info.chain_length() == chain_length => {}
+                    Some(_) => report
+                        .issues
+                        .push(IntegrityIssue::BlockInfoMismatch { id: id.clone() }),
+                    None => report
+                        .issues
+                        .push(IntegrityIssue::CorruptedBlock { id: id.clone() }),
+                },
+                None => report
+                    .issues
+                    .push(IntegrityIssue::MissingBlock { id: id.clone() }),
+            }
+            if self.permanent.get_chain_length(&id)? != Some(chain_length) {
+                report
+                    .issues
+                    .push(IntegrityIssue::PermanentIndex { chain_length });
+            }
+            permanent_ids.insert(id.clone());
+            previous_id = id;
+            chain_length += 1;
+        }
+        report.permanent_blocks = chain_length;
+        if self.volatile.iter(Tree::PermanentStoreBlocks).count() != permanent_ids.len() {
+            report
+                .issues
+                .push(IntegrityIssue::PermanentIndex { chain_length });
+        }
+
+        let mut tag_refs: HashMap<Vec<u8>, u32> = HashMap::new();
+        for entry in self.volatile.iter(Tree::Tags) {
+            let (name, id) = entry?;
+            if self.block_exists(id.as_ref())? {
+                if !permanent_ids.contains(id.as_ref()) {
+                    *tag_refs.entry(id.as_ref().to_vec()).or_default() += 1;
+                }
+            } else {
+                report.issues.push(IntegrityIssue::Tag {
+                    name: String::from_utf8_lossy(name.as_ref()).into_owned(),
+                });
+            }
+        }
+
+        let mut infos = HashMap::new();
+        for entry in self.volatile.iter(Tree::Info) {
+            let (id, _) = entry?;
+            let info = self.get_block_info_volatile(id.as_ref())?;
+            infos.insert(id.as_ref().to_vec(), info);
+        }
+        report.volatile_blocks = infos.len();
+
+        let mut children: HashMap<&[u8], u32> = HashMap::new();
+        for (id, info) in infos.iter() {
+            match self.volatile.get(Tree::Blocks, id)? {
+                Some(block) => match decode(block.as_ref()) {
+                    Some(decoded)
+                        if decoded.id() == info.id()
+                            && decoded.parent_id() == info.parent_id()
+                            && decoded.chain_length() == info.chain_length() => {}
+                    Some(_) => report
+                        .issues
+                        .push(IntegrityIssue::BlockInfoMismatch { id: id.clone() }),
+                    None => report
+                        .issues
+                        .push(IntegrityIssue::CorruptedBlock { id: id.clone() }),
+                },
+                None => report
+                    .issues
+                    .push(IntegrityIssue::MissingBlock { id: id.clone() }),
+            }
+
+            let parent_id = info.parent_id().as_ref();
+            let parent_chain_length = if parent_id == self.root_id.as_ref() {
+                None
+            } else if let Some(parent) = infos.get(parent_id) {
+                *children.entry(parent_id).or_default() += 1;
+                Some(parent.chain_length())
+            } else if let Some(chain_length) = self.permanent.get_chain_length(parent_id)? {
+                *children.entry(parent_id).or_default() += 1;
+                Some(chain_length)
+            } else {
+                report
+                    .issues
+                    .push(IntegrityIssue::MissingParent { id: id.clone() });
+                continue;
+            };
+            if parent_chain_length.map_or(0, |length| length + 1) != info.chain_length() {
+                report
+                    .issues
+                    .push(IntegrityIssue::BlockInfoMismatch { id: id.clone() });
+            }
+
+            let index_key = build_chain_length_index(info.chain_length(), id);
+            if !self
+                .volatile
+                .contains_key(Tree::ChainLengthIndex, &index_key)?
+            {
+                report.issues.push(IntegrityIssue::ChainLengthIndex {
+                    chain_length: info.chain_length(),
+                    id: id.clone(),
+                });
+            }
+        }
+
+        for (id, info) in infos.iter() {
+            let expected_parent_refs = children.get(id.as_slice()).copied().unwrap_or(0);
+            let expected_tag_refs = tag_refs.get(id).copied().unwrap_or(0);
+            if info.parent_ref_count() != expected_parent_refs
+                || info.ref_count() != expected_parent_refs + expected_tag_refs
+            {
+                report
+                    .issues
+                    .push(IntegrityIssue::RefCount { id: id.clone() });
+            }
+        }
+
+        for entry in self.volatile.iter(Tree::Blocks) {
+            let (id, _) = entry?;
+            if !infos.contains_key(id.as_ref()) {
+                report.issues.push(IntegrityIssue::MissingBlockInfo {
+                    id: id.as_ref().to_vec(),
+                });
+            }
+        }
+
+        for entry in self.volatile.iter(Tree::ChainLengthIndex) {
+            let (key, _) = entry?;
+            // a key too short to hold a chain length cannot refer to any block
+            if key.as_ref().len() < std::mem::size_of::<u32>() {
+                report.issues.push(IntegrityIssue::ChainLengthIndex {
+                    chain_length: 0,
+                    id: key.as_ref().to_vec(),
+                });
+                continue;
+            }
+            let (chain_length_bytes, id) = key.as_ref().split_at(std::mem::size_of::<u32>());
+            let mut bytes = [0u8; 4];
+            bytes.copy_from_slice(chain_length_bytes);
+            let chain_length = u32::from_be_bytes(bytes);
+            if infos
+                .get(id)
+                .map_or(true, |info| info.chain_length() != chain_length)
+            {
+                report.issues.push(IntegrityIssue::ChainLengthIndex {
+                    chain_length,
+                    id: id.to_vec(),
+                });
+            }
+        }
+
+        for entry in self.volatile.iter(Tree::BranchesTips) {
+            let (id, _) = entry?;
+            if children.contains_key(id.as_ref()) || !self.block_exists(id.as_ref())? {
+                report.issues.push(IntegrityIssue::Tip {
+                    id: id.as_ref().to_vec(),
+                });
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Rebuild the indexes of the store from the blocks.
+    ///
+    /// The index of the permanent storage is rebuilt from the permanent blocks
+    /// and the `BlockInfo`, the chain length index and the branches tips of the
+    /// volatile storage are rebuilt from the volatile blocks. The volatile
+    /// blocks that cannot be decoded or are not connected to the chain
+    /// anymore are removed, and so are the tags referring to missing blocks.
+    ///
+    /// The permanent blocks cannot be modified, the permanent storage is only
+    /// indexed up to its first corrupted block.
+    pub fn rebuild_indexes<F>(&self, decode: F) -> Result<RepairReport, Error>
+    where
+        F: Fn(&[u8]) -> Option<BlockInfo>,
+    {
+        let mut report = RepairReport::default();
+
+        self.volatile.clear(Tree::PermanentStoreBlocks)?;
+        let mut previous_id = self.root_id.as_ref().to_vec();
+        let mut chain_length = 0;
+        while let Some(block) = self.permanent.get_block_by_chain_length(chain_length) {
+            let info = match decode(block.as_ref()) {
+                Some(info)
+                    if info.parent_id().as_ref() == previous_id.as_slice()
+                        && info.chain_length() == chain_length =>
+                {
+                    info
+                }
+                _ => {
+                    let id = self
+                        .permanent
+                        .get_block_id_by_chain_length(chain_length)
+                        .map(|id| id.as_ref().to_vec())
+                        .unwrap_or_default();
+                    report
+                        .unrecoverable
+                        .push(IntegrityIssue::CorruptedBlock { id });
+                    break;
+                }
+            };
+            if self
+                .permanent
+                .get_block_id_by_chain_length(chain_length)
+                .map_or(true, |id| id.as_ref() != info.id().as_ref())
+            {
+                report
+                    .unrecoverable
+                    .push(IntegrityIssue::PermanentIndex { chain_length });
+            }
+            self.volatile.insert(
+                Tree::PermanentStoreBlocks,
+                info.id().as_ref(),
+                &chain_length.to_le_bytes()[..],
+            )?;
+            previous_id = info.id().as_ref().to_vec();
+            chain_length += 1;
+        }
+        report.permanent_blocks = chain_length;
+        let last_permanent_id = if chain_length > 0 {
+            Some(previous_id)
+        } else {
+            None
+        };
+
+        let mut infos = HashMap::new();
+        for entry in self.volatile.iter(Tree::Blocks) {
+            let (id, block) = entry?;
+            match decode(block.as_ref()) {
+                Some(info)
+                    if info.id().as_ref() == id.as_ref()
+                        && !self.permanent.contains_key(id.as_ref())? =>
+                {
+                    infos.insert(id.as_ref().to_vec(), info);
+                }
+                _ => report.removed_blocks.push(id.as_ref().to_vec()),
+            }
+        }
+
+        // remove the blocks whose parent is missing, along with their
+        // descendants
+        loop {
+            let orphans: Vec<Vec<u8>> = infos
+                .iter()
+                .filter(|(_, info)| {
+                    let parent_id = info.parent_id().as_ref();
+                    parent_id != self.root_id.as_ref()
+                        && !infos.contains_key(parent_id)
+                        && !self.permanent.contains_key(parent_id).unwrap_or(false)
+                })
+                .map(|(id, _)| id.clone())
+                .collect();
+            if orphans.is_empty() {
+                break;
+            }
+            for id in orphans {
+                infos.remove(&id);
+                report.removed_blocks.push(id);
+            }
+        }
+        for id in report.removed_blocks.iter() {
+            self.volatile.remove(Tree::Blocks, id)?;
+        }
+
+        let mut kept_tags = Vec::new();
+        for entry in self.volatile.iter(Tree::Tags) {
+            let (name, id) = entry?;
+            if infos.contains_key(id.as_ref()) || self.permanent.contains_key(id.as_ref())? {
+                kept_tags.push(id);
+            } else {
+                self.volatile.remove(Tree::Tags, name.as_ref())?;
+                report
+                    .removed_tags
+                    .push(String::from_utf8_lossy(name.as_ref()).into_owned());
+            }
+        }
+
+        let parents: Vec<Vec<u8>> = infos
+            .values()
+            .map(|info| info.parent_id().as_ref().to_vec())
+            .collect();
+        for parent_id in parents.iter() {
+            if let Some(parent) = infos.get_mut(parent_id) {
+                parent.add_parent_ref();
+            }
+        }
+        for id in kept_tags {
+            if let Some(info) = infos.get_mut(id.as_ref()) {
+                info.add_tag_ref();
+            }
+        }
+
+        self.volatile.clear(Tree::Info)?;
+        self.volatile.clear(Tree::ChainLengthIndex)?;
+        self.volatile.clear(Tree::BranchesTips)?;
+        for (id, info) in infos.iter() {
+            self.volatile.insert(Tree::Info, id, &info.serialize()?)?;
+            self.volatile.insert(
+                Tree::ChainLengthIndex,
+                &build_chain_length_index(info.chain_length(), id),
+                &[],
+            )?;
+            if info.parent_ref_count() == 0 {
+                self.volatile.insert(Tree::BranchesTips, id, &[])?;
+            }
+        }
+        if let Some(id) = last_permanent_id {
+            if !parents.contains(&id) {
+                self.volatile.insert(Tree::BranchesTips, &id, &[])?;
+            }
+        }
+        report.volatile_blocks = infos.len();
+
+        self.volatile.flush()?;
+        Ok(report)
+    }
+
+    /// Compact the volatile storage of the store in the given directory by
+    /// copying its content into a new database of the same engine. The store
+    /// must not be opened. The permanent storage is append only and does not
+    /// need to be compacted.
+    pub fn compact<P: AsRef<Path>>(
+        path: P,
+        engine: StorageEngine,
+    ) -> Result<CompactionReport, Error> {
+        let volatile_path = path.as_ref().join(engine.directory_name());
+        let compacted_path = volatile_path.with_extension("compact");
+        let outdated_path = volatile_path.with_extension("old");
+        if !volatile_path.exists() {
+            return Err(Error::Open(std::io::ErrorKind::NotFound.into()));
+        }
+        if compacted_path.exists() {
+            std::fs::remove_dir_all(&compacted_path).map_err(Error::Open)?;
+        }
+
+        {
+            let volatile = engine.open(&volatile_path)?;
+            let compacted = engine.open(&compacted_path)?;
+            copy_trees(volatile.as_ref(), compacted.as_ref())?;
+        }
+        let size_before = directory_size(&volatile_path).map_err(Error::Open)?;
+        let size_after = directory_size(&compacted_path).map_err(Error::Open)?;
+
+        std::fs::rename(&volatile_path, &outdated_path).map_err(Error::Open)?;
+        std::fs::rename(&compacted_path, &volatile_path).map_err(Error::Open)?;
+        std::fs::remove_dir_all(&outdated_path).map_err(Error::Open)?;
+
+        Ok(CompactionReport {
+            size_before,
+            size_after,
+        })
+    }
+}
+
+fn directory_size(path: &Path) -> std::io::Result<u64> {
+    let mut size = 0;
+    for entry in std::fs::read_dir(path)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        size += if metadata.is_dir() {
+            directory_size(&entry.path())?
+        } else {
+            metadata.len()
+        };
+    }
+    Ok(size)
+}
+
+impl fmt::Display for IntegrityIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IntegrityIssue::CorruptedBlock { id } => {
+                write!(f, "block {} is corrupted", Hex(id))
+            }
+            IntegrityIssue::BlockInfoMismatch { id } => {
+                write!(f, "block {} does not match its block info", Hex(id))
+            }
+            IntegrityIssue::MissingBlock { id } => {
+                write!(f, "block {} is indexed but not stored", Hex(id))
+            }
+            IntegrityIssue::MissingBlockInfo { id } => {
+                write!(f, "block {} is stored without its block info", Hex(id))
+            }
+            IntegrityIssue::MissingParent { id } => {
+                write!(f, "the parent of block {} is not stored", Hex(id))
+            }
+            IntegrityIssue::RefCount { id } => {
+                write!(f, "block {} has a wrong reference count", Hex(id))
+            }
+            IntegrityIssue::ChainLengthIndex { chain_length, id } => write!(
+                f,
+                "chain length index entry {} for block {} is invalid",
+                chain_length,
+                Hex(id)
+            ),
+            IntegrityIssue::PermanentIndex { chain_length } => write!(
+                f,
+                "permanent storage index is invalid at chain length {}",
+                chain_length
+            ),
+            IntegrityIssue::Tip { id } => write!(f, "branch tip {} is invalid", Hex(id)),
+            IntegrityIssue::Tag { name } => {
+                write!(f, "tag '{}' refers to a block that is not stored", name)
+            }
+        }
+    }
+}
+
+struct Hex<'a>(&'a [u8]);
+
+impl fmt::Display for Hex<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|byte| write!(f, "{:02x}", byte))
+    }
+}
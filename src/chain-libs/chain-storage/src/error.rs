@@ -1,3 +1,4 @@
+use crate::StorageEngine;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -8,6 +9,22 @@ pub enum Error {
     BlockNotFound,
     #[error("volatile store error")]
     VolatileBackendError(#[from] sled::Error),
+    #[cfg(feature = "rocksdb")]
+    #[error("volatile store error")]
+    RocksDbBackendError(#[from] rocksdb::Error),
+    #[error("the `{0}` storage engine is not supported by this build")]
+    UnsupportedEngine(StorageEngine),
+    #[error("the storage was created with the `{found}` engine, convert it to use the `{expected}` engine")]
+    EngineMismatch {
+        expected: StorageEngine,
+        found: StorageEngine,
+    },
+    #[error("the destination of the storage conversion already exists")]
+    ConversionTargetExists,
+    /// a transaction conflicted with another one, it is run again by the
+    /// storage engine
+    #[error("transaction conflict")]
+    TransactionConflict,
     #[error("permanent store error")]
     PermanentBackendError(#[from] data_pile::Error),
     #[error("Block already present in DB")]
@@ -26,6 +43,14 @@ pub enum Error {
         "cannot iterate over blocks because the provided distance is bigger than the chain length"
     )]
     CannotIterate,
+    #[error("failed to write the block archive")]
+    ArchiveWrite(#[source] std::io::Error),
+    #[error("failed to read the block archive")]
+    ArchiveRead(#[source] std::io::Error),
+    #[error("not a block archive, or a block of the archive is invalid")]
+    InvalidArchive,
+    #[error("a block of the archive is {size} bytes long, more than the limit of {max} bytes")]
+    ArchiveBlockTooLarge { size: u32, max: u32 },
 }
 
 #[derive(Debug, Error)]
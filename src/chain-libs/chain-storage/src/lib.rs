@@ -134,7 +134,26 @@
 //! │   └── flatfile    - storage file that can be transferred over the network
 //! └── volatile        - volatile storage
 //! ```
+//!
+//! # Storage engines
+//!
+//! The volatile storage is accessed through the `Backend` trait. It is kept
+//! in `sled` by default, `BlockStore::file_with_engine` selects another
+//! `StorageEngine` (RocksDB requires the `rocksdb` feature), in which case
+//! the volatile storage directory is named after the engine. An existing
+//! store is moved to another engine with `BlockStore::convert`.
+//!
+//! # Maintenance
+//!
+//! `BlockStore::check_integrity` verifies the blocks against their metadata
+//! and the indexes, `BlockStore::rebuild_indexes` rebuilds the indexes from
+//! the stored blocks and `BlockStore::compact` compacts the volatile storage
+//! of a closed store. A range of blocks can be exported to a single file with
+//! `BlockStore::export_blocks` and added to another store with
+//! `BlockStore::import_blocks`.
 
+mod archive;
+mod backend;
 mod block_info;
 mod block_store;
 mod error;
@@ -146,8 +165,15 @@ pub mod test_utils;
 mod tests;
 mod value;
 
+pub use archive::ImportReport;
+#[cfg(feature = "rocksdb")]
+pub use backend::RocksDbBackend;
+pub use backend::{Backend, Iter, MemoryBackend, SledBackend, StorageEngine, Transaction, Tree};
 pub use block_info::BlockInfo;
-pub use block_store::BlockStore;
+pub use block_store::{
+    maintenance::{CompactionReport, IntegrityIssue, IntegrityReport, RepairReport},
+    BlockStore,
+};
 pub use error::{ConsistencyFailure, Error};
 pub use iterator::StorageIterator;
 pub use value::Value;
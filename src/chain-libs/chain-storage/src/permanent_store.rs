@@ -1,18 +1,22 @@
-use crate::{BlockInfo, ConsistencyFailure, Error, Value};
-use std::path::Path;
+use crate::{
+    backend::{Backend, Tree},
+    BlockInfo, ConsistencyFailure, Error, Value,
+};
+use std::{path::Path, sync::Arc};
 
 #[derive(Clone)]
 pub(crate) struct PermanentStore {
     blocks: data_pile::Database,
     chain_length_index: data_pile::Database,
-    block_id_index: sled::Tree,
+    // the `Tree::PermanentStoreBlocks` collection of the volatile storage
+    block_id_index: Arc<dyn Backend>,
     root_id: Value,
 }
 
 impl PermanentStore {
     pub fn file<P: AsRef<Path>, I: Into<Value>>(
         path: P,
-        block_id_index: sled::Tree,
+        block_id_index: Arc<dyn Backend>,
         root_id: I,
     ) -> Result<PermanentStore, Error> {
         std::fs::create_dir_all(&path).map_err(Error::Open)?;
@@ -34,7 +38,7 @@ impl PermanentStore {
     }
 
     pub fn memory<I: Into<Value>>(
-        block_id_index: sled::Tree,
+        block_id_index: Arc<dyn Backend>,
         root_id: I,
     ) -> Result<PermanentStore, Error> {
         let blocks = data_pile::Database::memory()?;
@@ -56,6 +60,12 @@ impl PermanentStore {
             .map(Value::permanent)
     }
 
+    pub fn get_block_id_by_chain_length(&self, chain_length: u32) -> Option<Value> {
+        self.chain_length_index
+            .get_by_seqno(chain_length as usize)
+            .map(Value::permanent)
+    }
+
     pub fn get_block(&self, block_id: &[u8]) -> Result<Option<Value>, Error> {
         self.get_chain_length(block_id).map(|maybe_chain_length| {
             maybe_chain_length.and_then(|chain_length| self.get_block_by_chain_length(chain_length))
@@ -107,8 +117,11 @@ impl PermanentStore {
         Ok(Some(block_info))
     }
 
-    fn get_chain_length(&self, block_id: &[u8]) -> Result<Option<u32>, Error> {
-        let chain_length_bytes_slice = match self.block_id_index.get(block_id)? {
+    pub fn get_chain_length(&self, block_id: &[u8]) -> Result<Option<u32>, Error> {
+        let chain_length_bytes_slice = match self
+            .block_id_index
+            .get(Tree::PermanentStoreBlocks, block_id)?
+        {
             Some(block_id) => block_id,
             None => return Ok(None),
         };
@@ -122,8 +135,7 @@ impl PermanentStore {
 
     pub fn contains_key(&self, block_id: &[u8]) -> Result<bool, Error> {
         self.block_id_index
-            .contains_key(block_id)
-            .map_err(Into::into)
+            .contains_key(Tree::PermanentStoreBlocks, block_id)
     }
 
     pub fn put_blocks(
@@ -149,7 +161,8 @@ impl PermanentStore {
         for (i, id) in ids.iter().enumerate() {
             let chain_length = start_chain_length + i as u32;
             let chain_length_bytes = chain_length.to_le_bytes();
-            self.block_id_index.insert(id, &chain_length_bytes[..])?;
+            self.block_id_index
+                .insert(Tree::PermanentStoreBlocks, id, &chain_length_bytes[..])?;
         }
 
         Ok(())
@@ -160,8 +173,4 @@ impl PermanentStore {
             .iter_from_seqno(chain_length as usize)
             .ok_or(Error::BlockNotFound)
     }
-
-    pub fn block_id_index(&self) -> &sled::Tree {
-        &self.block_id_index
-    }
 }
@@ -1,6 +1,6 @@
 //! Utilities for testing the storage.
 
-use crate::Value;
+use crate::{BlockInfo, Value};
 use std::sync::atomic::{AtomicU64, Ordering};
 
 #[derive(Debug, Clone, Hash, Ord, PartialOrd, Eq, PartialEq, Copy)]
@@ -75,3 +75,25 @@ impl Block {
         Value::owned(self.serialize_as_vec().into_boxed_slice())
     }
 }
+
+/// Compute the `BlockInfo` of a block serialized with `Block::serialize`, to
+/// be used with the maintenance and archive functions of the store.
+pub fn decode_block_info(bytes: &[u8]) -> Option<BlockInfo> {
+    const HEADER_LENGTH: usize = 8 + 8 + 4 + 8;
+
+    if bytes.len() < HEADER_LENGTH {
+        return None;
+    }
+    let (id, rest) = bytes.split_at(8);
+    let (parent, rest) = rest.split_at(8);
+    let (chain_length, rest) = rest.split_at(4);
+    let (data_length, data) = rest.split_at(8);
+    if u64::from_le_bytes(data_length.try_into().unwrap()) != data.len() as u64 {
+        return None;
+    }
+    Some(BlockInfo::new(
+        id.to_vec(),
+        parent.to_vec(),
+        u32::from_le_bytes(chain_length.try_into().unwrap()),
+    ))
+}
@@ -1,6 +1,7 @@
 use crate::{
-    test_utils::{Block, BlockId},
-    BlockInfo, BlockStore, Error, Value,
+    test_utils::{decode_block_info, Block, BlockId},
+    Backend, BlockInfo, BlockStore, Error, IntegrityIssue, MemoryBackend, StorageEngine, Tree,
+    Value,
 };
 use rand_core::{OsRng, RngCore};
 use std::{collections::HashSet, iter::FromIterator};
@@ -12,6 +13,7 @@ const SECOND_BRANCH_LEN: usize = 25;
 const BIFURCATION_POINT: usize = 50;
 const FLUSH_TO_BLOCK: usize = 512;
 const FLUSH_TO_BLOCK_2: usize = 768;
+const MAX_ARCHIVED_BLOCK_SIZE: u32 = 1024 * 1024;
 
 pub fn pick_from_vector<'a, A, R: RngCore>(rng: &mut R, v: &'a [A]) -> &'a A {
     let s = rng.next_u32() as usize;
@@ -863,3 +865,432 @@ fn iterator_only_permanent_storage() {
         assert_eq!(blocks[i].serialize_as_value(), block.unwrap());
     }
 }
+
+#[test]
+fn integrity_check_valid_store() {
+    let (_file, store, blocks) = prepare_permament_store();
+    store
+        .put_tag("tip", &blocks.last().unwrap().id.serialize_as_vec())
+        .unwrap();
+
+    let report = store.check_integrity(decode_block_info).unwrap();
+
+    assert!(report.is_ok(), "{:?}", report.issues);
+    assert_eq!(report.permanent_blocks as usize, FLUSH_TO_BLOCK + 1);
+    assert_eq!(report.volatile_blocks, blocks.len() - FLUSH_TO_BLOCK - 1);
+}
+
+#[test]
+fn rebuild_corrupted_indexes() {
+    let (_file, store, blocks) = prepare_permament_store();
+    let tip = blocks.last().unwrap().id.serialize_as_vec();
+    store.put_tag("tip", &tip).unwrap();
+
+    let volatile_block = blocks[FLUSH_TO_BLOCK + 10].id.serialize_as_vec();
+    store.volatile.remove(Tree::Info, &volatile_block).unwrap();
+    store.volatile.clear(Tree::BranchesTips).unwrap();
+    store
+        .volatile
+        .remove(
+            Tree::PermanentStoreBlocks,
+            &blocks[42].id.serialize_as_vec(),
+        )
+        .unwrap();
+
+    let report = store.check_integrity(decode_block_info).unwrap();
+    assert!(report
+        .issues
+        .contains(&IntegrityIssue::MissingBlockInfo { id: volatile_block }));
+    assert!(report
+        .issues
+        .contains(&IntegrityIssue::PermanentIndex { chain_length: 42 }));
+
+    let repair = store.rebuild_indexes(decode_block_info).unwrap();
+    assert!(repair.removed_blocks.is_empty());
+    assert!(repair.removed_tags.is_empty());
+    assert!(repair.unrecoverable.is_empty());
+
+    let report = store.check_integrity(decode_block_info).unwrap();
+    assert!(report.is_ok(), "{:?}", report.issues);
+    assert_eq!(
+        store.get_tips_ids().unwrap(),
+        vec![Value::from(tip.clone())]
+    );
+    assert_eq!(
+        store
+            .is_ancestor(&blocks[42].id.serialize_as_vec(), &tip)
+            .unwrap(),
+        Some((blocks.len() - 43) as u32)
+    );
+}
+
+#[test]
+fn integrity_check_reports_truncated_chain_length_index_keys() {
+    let (_file, store, blocks) = prepare_and_fill_store(MAIN_BRANCH_LEN);
+    let tip = blocks.last().unwrap().id.serialize_as_vec();
+    store.put_tag("tip", &tip).unwrap();
+
+    store
+        .volatile
+        .insert(Tree::ChainLengthIndex, &[1, 2][..], &[][..])
+        .unwrap();
+
+    let report = store.check_integrity(decode_block_info).unwrap();
+    assert!(report.issues.contains(&IntegrityIssue::ChainLengthIndex {
+        chain_length: 0,
+        id: vec![1, 2],
+    }));
+
+    store.rebuild_indexes(decode_block_info).unwrap();
+    let report = store.check_integrity(decode_block_info).unwrap();
+    assert!(report.is_ok(), "{:?}", report.issues);
+}
+
+#[test]
+fn rebuild_indexes_removes_orphans() {
+    let (_file, store, blocks) = prepare_and_fill_store(MAIN_BRANCH_LEN);
+    let tip = blocks.last().unwrap().id.serialize_as_vec();
+    store.put_tag("tip", &tip).unwrap();
+
+    store
+        .volatile
+        .insert(
+            Tree::Blocks,
+            &blocks[BIFURCATION_POINT].id.serialize_as_vec(),
+            &b"garbage"[..],
+        )
+        .unwrap();
+
+    let report = store.check_integrity(decode_block_info).unwrap();
+    assert!(report.issues.contains(&IntegrityIssue::CorruptedBlock {
+        id: blocks[BIFURCATION_POINT].id.serialize_as_vec()
+    }));
+
+    let repair = store.rebuild_indexes(decode_block_info).unwrap();
+    assert_eq!(
+        repair.removed_blocks.len(),
+        MAIN_BRANCH_LEN - BIFURCATION_POINT
+    );
+    assert_eq!(repair.removed_tags, vec!["tip".to_owned()]);
+    assert_eq!(repair.volatile_blocks, BIFURCATION_POINT);
+
+    let report = store.check_integrity(decode_block_info).unwrap();
+    assert!(report.is_ok(), "{:?}", report.issues);
+    assert!(!store.block_exists(&tip).unwrap());
+}
+
+#[test]
+fn compact_store() {
+    let (file, store, blocks) = prepare_and_fill_store(MAIN_BRANCH_LEN);
+    let tip = blocks.last().unwrap().id.serialize_as_vec();
+    store.put_tag("tip", &tip).unwrap();
+    drop(store);
+
+    BlockStore::compact(file.path(), StorageEngine::Sled).unwrap();
+
+    let store = BlockStore::file(file.path(), BlockId(0).serialize_as_vec()).unwrap();
+    assert_eq!(store.get_tag("tip").unwrap(), Some(Value::from(tip)));
+    let report = store.check_integrity(decode_block_info).unwrap();
+    assert!(report.is_ok(), "{:?}", report.issues);
+    assert_eq!(report.volatile_blocks, MAIN_BRANCH_LEN);
+}
+
+#[test]
+fn export_import_blocks() {
+    let (_file, store, blocks) = prepare_permament_store();
+    let tip = blocks.last().unwrap().id.serialize_as_vec();
+
+    let mut archive = Vec::new();
+    store
+        .export_blocks(&tip, blocks.len() as u32, &mut archive)
+        .unwrap();
+
+    let (_other_file, other_store) = prepare_store();
+    let report = other_store
+        .import_blocks(&archive[..], MAX_ARCHIVED_BLOCK_SIZE, decode_block_info)
+        .unwrap();
+    assert_eq!(report.imported as usize, blocks.len());
+    assert_eq!(report.skipped, 0);
+    assert_eq!(report.last_block_id, Some(tip.clone()));
+
+    for block in blocks.iter() {
+        assert_eq!(
+            other_store
+                .get_block(&block.id.serialize_as_vec())
+                .unwrap()
+                .as_ref(),
+            block.serialize_as_vec().as_slice()
+        );
+    }
+
+    let report = other_store
+        .import_blocks(&archive[..], MAX_ARCHIVED_BLOCK_SIZE, decode_block_info)
+        .unwrap();
+    assert_eq!(report.imported, 0);
+    assert_eq!(report.skipped as usize, blocks.len());
+}
+
+#[test]
+fn import_blocks_rejects_invalid_archive() {
+    let (_file, store) = prepare_store();
+    match store.import_blocks(
+        &b"not an archive"[..],
+        MAX_ARCHIVED_BLOCK_SIZE,
+        decode_block_info,
+    ) {
+        Err(Error::InvalidArchive) => {}
+        res => panic!("{:?}", res),
+    }
+}
+
+#[test]
+fn import_blocks_rejects_oversized_block() {
+    let (_file, store, blocks) = prepare_and_fill_store(2);
+    let tip = blocks.last().unwrap().id.serialize_as_vec();
+    let mut archive = Vec::new();
+    store.export_blocks(&tip, 2, &mut archive).unwrap();
+
+    // claim a 4 GiB block, which must not be allocated
+    archive[10..14].copy_from_slice(&u32::MAX.to_le_bytes());
+    let (_other_file, other_store) = prepare_store();
+    match other_store.import_blocks(&archive[..], MAX_ARCHIVED_BLOCK_SIZE, decode_block_info) {
+        Err(Error::ArchiveBlockTooLarge { size, max }) => {
+            assert_eq!(size, u32::MAX);
+            assert_eq!(max, MAX_ARCHIVED_BLOCK_SIZE);
+        }
+        res => panic!("{:?}", res),
+    }
+}
+
+#[test]
+fn import_blocks_rejects_wrong_chain_length() {
+    let genesis = Block::genesis(None);
+    let mut child = genesis.make_child(None);
+    child.chain_length = 5;
+
+    let mut archive = Vec::new();
+    archive.extend_from_slice(b"BLKA");
+    archive.extend_from_slice(&1u16.to_le_bytes());
+    archive.extend_from_slice(&2u32.to_le_bytes());
+    for block in [&genesis, &child] {
+        let block = block.serialize_as_vec();
+        archive.extend_from_slice(&(block.len() as u32).to_le_bytes());
+        archive.extend_from_slice(&block);
+    }
+
+    let (_file, store) = prepare_store();
+    match store.import_blocks(&archive[..], MAX_ARCHIVED_BLOCK_SIZE, decode_block_info) {
+        Err(Error::InvalidArchive) => {}
+        res => panic!("{:?}", res),
+    }
+    assert!(!store.block_exists(&child.id.serialize_as_vec()).unwrap());
+}
+
+#[test]
+fn memory_backend_aborted_transaction() {
+    let backend = MemoryBackend::new();
+    backend.insert(Tree::Tags, b"tip", b"1").unwrap();
+
+    let res = backend.transaction(&mut |transaction| {
+        transaction.insert(Tree::Tags, b"tip", b"2")?;
+        transaction.remove(Tree::Tags, b"tip")?;
+        assert_eq!(transaction.get(Tree::Tags, b"tip")?, None);
+        Err(Error::TransactionConflict)
+    });
+    assert!(matches!(res, Err(Error::TransactionConflict)));
+    assert_eq!(
+        backend.get(Tree::Tags, b"tip").unwrap(),
+        Some(Value::from(b"1".to_vec()))
+    );
+}
+
+#[test]
+fn copy_volatile_storage_between_backends() {
+    let (_file, store, blocks) = prepare_permament_store();
+    let tip = blocks.last().unwrap().id.serialize_as_vec();
+    store.put_tag("tip", &tip).unwrap();
+
+    let backend = MemoryBackend::new();
+    let copied = crate::backend::copy_trees(store.volatile.as_ref(), &backend).unwrap();
+
+    let mut entries = 0;
+    for tree in Tree::ALL {
+        for (original, copy) in store.volatile.iter(tree).zip(backend.iter(tree)) {
+            let (original, copy) = (original.unwrap(), copy.unwrap());
+            assert_eq!(original.0.as_ref(), copy.0.as_ref());
+            assert_eq!(original.1.as_ref(), copy.1.as_ref());
+            entries += 1;
+        }
+        assert_eq!(
+            store.volatile.iter(tree).count(),
+            backend.iter(tree).count()
+        );
+    }
+    assert_eq!(copied, entries);
+    assert_eq!(
+        backend.get(Tree::Tags, b"tip").unwrap(),
+        Some(Value::from(tip))
+    );
+}
+
+#[test]
+fn open_with_other_engine() {
+    let (file, store) = prepare_store();
+    drop(store);
+
+    match BlockStore::file_with_engine(
+        file.path(),
+        BlockId(0).serialize_as_vec(),
+        StorageEngine::RocksDb,
+    ) {
+        Err(Error::EngineMismatch {
+            expected: StorageEngine::RocksDb,
+            found: StorageEngine::Sled,
+        }) => {}
+        res => panic!("{:?}", res.map(|_| ())),
+    }
+    match BlockStore::convert(file.path(), StorageEngine::RocksDb, StorageEngine::Sled) {
+        Err(Error::Open(_)) => {}
+        res => panic!("{:?}", res),
+    }
+}
+
+#[test]
+fn convert_leaves_no_partial_storage() {
+    let (file, store) = prepare_store();
+    drop(store);
+    // a leftover of an interrupted conversion is replaced
+    let leftover = file
+        .path()
+        .join(StorageEngine::RocksDb.directory_name())
+        .with_extension("convert");
+    std::fs::create_dir(&leftover).unwrap();
+
+    let res = BlockStore::convert(file.path(), StorageEngine::Sled, StorageEngine::RocksDb);
+    if cfg!(feature = "rocksdb") {
+        res.unwrap();
+    } else {
+        assert!(matches!(res, Err(Error::UnsupportedEngine(_))));
+        assert!(!file
+            .path()
+            .join(StorageEngine::RocksDb.directory_name())
+            .exists());
+    }
+    assert!(!leftover.exists());
+}
+
+#[cfg(feature = "rocksdb")]
+#[test]
+fn rocksdb_store() {
+    let file = tempfile::TempDir::new().unwrap();
+    let store = BlockStore::file_with_engine(
+        file.path(),
+        BlockId(0).serialize_as_vec(),
+        StorageEngine::RocksDb,
+    )
+    .unwrap();
+
+    let mut blocks = vec![Block::genesis(None)];
+    for _ in 1..MAIN_BRANCH_LEN {
+        blocks.push(blocks.last().unwrap().make_child(None));
+    }
+    for block in blocks.iter() {
+        let block_info = BlockInfo::new(
+            block.id.serialize_as_vec(),
+            block.parent.serialize_as_vec(),
+            block.chain_length,
+        );
+        store
+            .put_block(&block.serialize_as_vec(), block_info)
+            .unwrap();
+    }
+    let tip = blocks.last().unwrap().id.serialize_as_vec();
+    store.put_tag("tip", &tip).unwrap();
+    store
+        .flush_to_permanent_store(&blocks[BIFURCATION_POINT].id.serialize_as_vec(), 1)
+        .unwrap();
+    drop(store);
+
+    let store = BlockStore::file_with_engine(
+        file.path(),
+        BlockId(0).serialize_as_vec(),
+        StorageEngine::RocksDb,
+    )
+    .unwrap();
+    assert_eq!(store.get_tag("tip").unwrap(), Some(Value::from(tip)));
+    for block in blocks.iter() {
+        assert_eq!(
+            store
+                .get_block(&block.id.serialize_as_vec())
+                .unwrap()
+                .as_ref(),
+            block.serialize_as_vec().as_slice()
+        );
+    }
+    let report = store.check_integrity(decode_block_info).unwrap();
+    assert!(report.is_ok(), "{:?}", report.issues);
+}
+
+#[cfg(feature = "rocksdb")]
+#[test]
+fn rocksdb_convert_from_sled() {
+    let (file, store, blocks) = prepare_and_fill_store(MAIN_BRANCH_LEN);
+    let tip = blocks.last().unwrap().id.serialize_as_vec();
+    store.put_tag("tip", &tip).unwrap();
+    drop(store);
+
+    BlockStore::convert(file.path(), StorageEngine::Sled, StorageEngine::RocksDb).unwrap();
+
+    // the sled storage is kept until it is removed by the user
+    assert!(file
+        .path()
+        .join(StorageEngine::Sled.directory_name())
+        .exists());
+
+    let store = BlockStore::file_with_engine(
+        file.path(),
+        BlockId(0).serialize_as_vec(),
+        StorageEngine::RocksDb,
+    )
+    .unwrap();
+    assert_eq!(store.get_tag("tip").unwrap(), Some(Value::from(tip)));
+    let report = store.check_integrity(decode_block_info).unwrap();
+    assert!(report.is_ok(), "{:?}", report.issues);
+    assert_eq!(report.volatile_blocks, MAIN_BRANCH_LEN);
+}
+
+#[cfg(feature = "rocksdb")]
+#[test]
+fn rocksdb_concurrent_transactions_are_retried() {
+    const THREADS: u64 = 4;
+    const INCREMENTS: u64 = 50;
+
+    let file = tempfile::TempDir::new().unwrap();
+    let backend = crate::RocksDbBackend::open(file.path()).unwrap();
+    backend
+        .insert(Tree::Tags, b"counter", &0u64.to_le_bytes())
+        .unwrap();
+
+    std::thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| {
+                for _ in 0..INCREMENTS {
+                    backend
+                        .transaction(&mut |transaction| {
+                            let value = transaction.get(Tree::Tags, b"counter")?.unwrap();
+                            let mut bytes = [0u8; 8];
+                            bytes.copy_from_slice(value.as_ref());
+                            let counter = u64::from_le_bytes(bytes) + 1;
+                            transaction.insert(Tree::Tags, b"counter", &counter.to_le_bytes())
+                        })
+                        .unwrap();
+                }
+            });
+        }
+    });
+
+    let value = backend.get(Tree::Tags, b"counter").unwrap().unwrap();
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(value.as_ref());
+    assert_eq!(u64::from_le_bytes(bytes), THREADS * INCREMENTS);
+}
@@ -1,6 +1,6 @@
 use crate::cryptography::{Ciphertext, HybridCiphertext, PublicKey, SecretKey};
 use crate::encrypted_vote::{EncryptedVote, ProofOfCorrectVote, Vote};
-use crate::math::polynomial::Polynomial;
+use crate::math::polynomial::{lagrange_basis, Polynomial};
 use crate::tally::Crs;
 use crate::{GroupElement, Scalar, CURVE_HRP};
 use chain_crypto::bech32::{to_bech32_from_bytes, try_from_bech32_to_bytes, Bech32, Error};
@@ -16,11 +16,11 @@ pub struct MemberSecretKey(pub(crate) SecretKey);
 pub struct MemberPublicKey(pub(crate) PublicKey);
 
 #[derive(Clone)]
-pub struct MemberCommunicationKey(SecretKey);
+pub struct MemberCommunicationKey(pub(crate) SecretKey);
 
 /// Committee Member communication public key (with other committee members)
 #[derive(Clone)]
-pub struct MemberCommunicationPublicKey(PublicKey);
+pub struct MemberCommunicationPublicKey(pub(crate) PublicKey);
 
 /// The overall committee public key used for everyone to encrypt their vote to.
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -66,6 +66,27 @@ impl ElectionPublicKey {
         ElectionPublicKey(PublicKey { pk: k })
     }
 
+    /// Create the election public key of a committee whose keys were generated
+    /// with the distributed key generation (see [`crate::dkg`]), from the public
+    /// keys of its members, indexed by member, and the decryption threshold.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `threshold` is zero or greater than the number of members.
+    pub fn from_threshold_participants(pks: &[MemberPublicKey], threshold: usize) -> Self {
+        assert!(threshold > 0 && threshold <= pks.len());
+        // the member keys are points of a polynomial of degree `threshold - 1`
+        // in the exponent, the election key is its value at zero
+        let xs = (1..=threshold as u64)
+            .map(Scalar::from_u64)
+            .collect::<Vec<_>>();
+        let pk = GroupElement::vartime_multiscalar_multiplication(
+            xs.iter().map(|x| lagrange_basis(x, &xs, &Scalar::zero())),
+            pks[..threshold].iter().map(|pk| pk.0.pk.clone()),
+        );
+        ElectionPublicKey(PublicKey { pk })
+    }
+
     pub fn to_bytes(&self) -> Vec<u8> {
         self.0.to_bytes()
     }
@@ -92,9 +113,13 @@ impl Bech32 for ElectionPublicKey {
 }
 
 /// Initial state generated by a Member, which include keys for this election
+///
+/// The member key is used as is, so all the members are needed to decrypt
+/// the tally. See [`crate::dkg`] for the interactive protocol generating
+/// threshold keys.
 #[derive(Clone)]
-// TODO: some of the fields are needed for DKG features which are
-// not yet implemented
+// the commitments and encrypted shares are only meaningful as part of
+// the interactive key generation
 #[allow(dead_code)]
 pub struct MemberState {
     sk: MemberSecretKey,
@@ -194,7 +194,7 @@ impl SymmetricKey {
     }
 
     // Encrypt/decrypt a message using the symmetric key
-    fn process(&self, m: &[u8]) -> Vec<u8> {
+    pub(crate) fn process(&self, m: &[u8]) -> Vec<u8> {
         let mut key = self.initialise_encryption();
         let mut dat = m.to_vec();
         key.process_mut(&mut dat);
@@ -4,8 +4,10 @@ mod zkps;
 
 pub(crate) use self::{
     commitment::CommitmentKey,
-    elgamal::{HybridCiphertext, PublicKey, SecretKey},
-    zkps::{CorrectShareGenerationZkp, UnitVectorZkp},
+    elgamal::{HybridCiphertext, PublicKey, SecretKey, SymmetricKey},
+    zkps::{
+        BinaryChoiceZkp, CorrectHybridDecrKeyZkp, CorrectShareGenerationZkp, DleqZkp, UnitVectorZkp,
+    },
 };
 
 #[cfg(test)]
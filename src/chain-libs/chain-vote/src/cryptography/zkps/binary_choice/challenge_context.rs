@@ -0,0 +1,39 @@
+use crate::cryptography::{Ciphertext, PublicKey};
+use crate::{GroupElement, Scalar};
+use cryptoxide::blake2b::Blake2b;
+use cryptoxide::digest::Digest;
+
+/// Challenge context for the binary choice proof. The common reference string
+/// is the election public key, and the statement consists of the two lists of
+/// ciphertexts.
+pub struct ChallengeContext(Blake2b);
+
+impl ChallengeContext {
+    /// Initialise the challenge context, by including the common reference string and the full statement
+    pub(crate) fn new(public_key: &PublicKey, statements: [&[Ciphertext]; 2]) -> Self {
+        let mut ctx = Blake2b::new(64);
+        ctx.input(&public_key.to_bytes());
+        for statement in statements {
+            for ciphertext in statement {
+                ctx.input(&ciphertext.to_bytes());
+            }
+        }
+
+        ChallengeContext(ctx)
+    }
+
+    /// Generation of the `first_challenge`. This challenge is generated after the
+    /// announcements of both branches are "sent". Hence, we include the latter to the
+    /// challenge context and generate its corresponding scalar.
+    pub(crate) fn first_challenge(
+        &mut self,
+        announcements: &[Vec<(GroupElement, GroupElement)>; 2],
+    ) -> Scalar {
+        for (a1, a2) in announcements.iter().flatten() {
+            self.0.input(&a1.to_bytes());
+            self.0.input(&a2.to_bytes());
+        }
+
+        Scalar::hash_to_scalar(&self.0)
+    }
+}
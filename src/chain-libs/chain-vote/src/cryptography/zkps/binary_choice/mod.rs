@@ -0,0 +1,4 @@
+mod challenge_context;
+mod zkp;
+
+pub use zkp::Zkp as BinaryChoiceZkp;
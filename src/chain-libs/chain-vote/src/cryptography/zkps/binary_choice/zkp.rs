@@ -0,0 +1,255 @@
+//! Non-interactive Zero Knowledge proof that one out of two lists of ElGamal
+//! ciphertexts only contains encryptions of zero, without disclosing which one.
+//!
+//! The proof is the following:
+//!
+//! `NIZK{(pk, C_0, C_1), (b, r): for all i, C_b[i] = (g^r[i], pk^r[i])}`
+//!
+//! It is the OR composition (Cramer, Damgård and Schoenmakers) of two AND
+//! compositions of DLEQ proofs sharing the same challenge. The simulated branch
+//! gets a random challenge, and the challenge of the real branch is the
+//! difference between the hash of the announcements and the simulated one.
+//!
+//! The typical use is to prove that a ciphertext `E` encrypts a bit, with
+//! `C_0 = [E]` and `C_1 = [E - Enc(1)]`.
+#![allow(clippy::many_single_char_names)]
+use super::challenge_context::ChallengeContext;
+use crate::cryptography::{Ciphertext, PublicKey};
+use crate::{GroupElement, Scalar};
+use rand_core::{CryptoRng, RngCore};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Zkp {
+    challenges: [Scalar; 2],
+    responses: [Vec<Scalar>; 2],
+}
+
+impl Zkp {
+    /// Size of the byte representation of a proof over lists of `statements`
+    /// ciphertexts.
+    pub const fn bytes_len(statements: usize) -> usize {
+        (2 + 2 * statements) * Scalar::BYTES_LEN
+    }
+
+    /// Generate a binary choice proof, knowing that all the ciphertexts of
+    /// `statements[branch]` are encryptions of zero with the corresponding
+    /// `randomness`.
+    pub(crate) fn generate<R>(
+        public_key: &PublicKey,
+        statements: [&[Ciphertext]; 2],
+        branch: usize,
+        randomness: &[Scalar],
+        rng: &mut R,
+    ) -> Self
+    where
+        R: CryptoRng + RngCore,
+    {
+        assert!(branch < 2);
+        assert_eq!(statements[0].len(), statements[1].len());
+        assert_eq!(statements[branch].len(), randomness.len());
+        let simulated = 1 - branch;
+
+        let simulated_challenge = Scalar::random(rng);
+        let simulated_responses: Vec<Scalar> =
+            randomness.iter().map(|_| Scalar::random(rng)).collect();
+        let blinders: Vec<Scalar> = randomness.iter().map(|_| Scalar::random(rng)).collect();
+
+        let g = GroupElement::generator();
+        let mut announcements: [Vec<(GroupElement, GroupElement)>; 2] = Default::default();
+        announcements[branch] = blinders
+            .iter()
+            .map(|w| (&g * w, &public_key.pk * w))
+            .collect();
+        announcements[simulated] = Self::announcements(
+            public_key,
+            statements[simulated],
+            &simulated_challenge,
+            &simulated_responses,
+        );
+
+        let mut challenge_context = ChallengeContext::new(public_key, statements);
+        let challenge = challenge_context.first_challenge(&announcements);
+        let real_challenge = &challenge - &simulated_challenge;
+        let real_responses = blinders
+            .iter()
+            .zip(randomness.iter())
+            .map(|(w, r)| r * &real_challenge + w)
+            .collect();
+
+        let mut challenges = [Scalar::zero(), Scalar::zero()];
+        challenges[branch] = real_challenge;
+        challenges[simulated] = simulated_challenge;
+        let mut responses: [Vec<Scalar>; 2] = Default::default();
+        responses[branch] = real_responses;
+        responses[simulated] = simulated_responses;
+
+        Zkp {
+            challenges,
+            responses,
+        }
+    }
+
+    /// Verify a binary choice proof
+    pub(crate) fn verify(&self, public_key: &PublicKey, statements: [&[Ciphertext]; 2]) -> bool {
+        if statements
+            .iter()
+            .zip(self.responses.iter())
+            .any(|(statement, responses)| statement.len() != responses.len())
+        {
+            return false;
+        }
+
+        let announcements = [
+            Self::announcements(
+                public_key,
+                statements[0],
+                &self.challenges[0],
+                &self.responses[0],
+            ),
+            Self::announcements(
+                public_key,
+                statements[1],
+                &self.challenges[1],
+                &self.responses[1],
+            ),
+        ];
+
+        let mut challenge_context = ChallengeContext::new(public_key, statements);
+        let challenge = challenge_context.first_challenge(&announcements);
+        // no need for constant time equality because of the hash in challenge()
+        challenge == &self.challenges[0] + &self.challenges[1]
+    }
+
+    /// Recompute the announcements of one branch out of its challenge and responses
+    fn announcements(
+        public_key: &PublicKey,
+        statement: &[Ciphertext],
+        challenge: &Scalar,
+        responses: &[Scalar],
+    ) -> Vec<(GroupElement, GroupElement)> {
+        let g = GroupElement::generator();
+        statement
+            .iter()
+            .zip(responses.iter())
+            .map(|(ciphertext, z)| {
+                (
+                    &g * z - &ciphertext.e1 * challenge,
+                    &public_key.pk * z - &ciphertext.e2 * challenge,
+                )
+            })
+            .collect()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut output = Vec::with_capacity(Self::bytes_len(self.responses[0].len()));
+        for scalar in self
+            .challenges
+            .iter()
+            .chain(self.responses.iter().flatten())
+        {
+            output.extend_from_slice(&scalar.to_bytes());
+        }
+        output
+    }
+
+    /// Read a proof over lists of `statements` ciphertexts
+    pub fn from_bytes(slice: &[u8], statements: usize) -> Option<Self> {
+        if slice.len() != Self::bytes_len(statements) {
+            return None;
+        }
+        let mut scalars = slice
+            .chunks(Scalar::BYTES_LEN)
+            .map(Scalar::from_bytes)
+            .collect::<Option<Vec<_>>>()?
+            .into_iter();
+
+        let challenges = [scalars.next()?, scalars.next()?];
+        let responses = [
+            scalars.by_ref().take(statements).collect(),
+            scalars.collect(),
+        ];
+
+        Some(Zkp {
+            challenges,
+            responses,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cryptography::Keypair;
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+
+    fn encrypt_bit(
+        keypair: &Keypair,
+        bit: bool,
+        rng: &mut ChaCha20Rng,
+    ) -> (Ciphertext, Ciphertext, Scalar) {
+        let (ciphertext, r) = keypair.public_key.encrypt_return_r(&Scalar::from(bit), rng);
+        let one = keypair
+            .public_key
+            .encrypt_with_r(&Scalar::one(), &Scalar::zero());
+        let minus_one = &ciphertext - &one;
+        (ciphertext, minus_one, r)
+    }
+
+    #[test]
+    fn prove_bits() {
+        let mut r = ChaCha20Rng::from_seed([0u8; 32]);
+        let keypair = Keypair::generate(&mut r);
+
+        for bit in [false, true] {
+            let (ciphertext, minus_one, randomness) = encrypt_bit(&keypair, bit, &mut r);
+            let (zero, one) = ([ciphertext], [minus_one]);
+            let statements = [&zero[..], &one[..]];
+            let proof = Zkp::generate(
+                &keypair.public_key,
+                statements,
+                bit as usize,
+                &[randomness],
+                &mut r,
+            );
+            assert!(proof.verify(&keypair.public_key, statements));
+        }
+    }
+
+    #[test]
+    fn wrong_branch_fails() {
+        let mut r = ChaCha20Rng::from_seed([0u8; 32]);
+        let keypair = Keypair::generate(&mut r);
+
+        let two = keypair
+            .public_key
+            .encrypt_with_r(&Scalar::from_u64(2), &Scalar::zero());
+        let (ciphertext, minus_one, randomness) = encrypt_bit(&keypair, true, &mut r);
+        let ciphertext = &ciphertext + &two;
+        let minus_one = &minus_one + &two;
+        let (zero, one) = ([ciphertext], [minus_one]);
+        let statements = [&zero[..], &one[..]];
+        let proof = Zkp::generate(&keypair.public_key, statements, 1, &[randomness], &mut r);
+        assert!(!proof.verify(&keypair.public_key, statements));
+    }
+
+    #[test]
+    fn serialisation() {
+        let mut r = ChaCha20Rng::from_seed([0u8; 32]);
+        let keypair = Keypair::generate(&mut r);
+
+        let (ciphertext, minus_one, randomness) = encrypt_bit(&keypair, false, &mut r);
+        let (zero, one) = ([ciphertext], [minus_one]);
+        let statements = [&zero[..], &one[..]];
+        let proof = Zkp::generate(&keypair.public_key, statements, 0, &[randomness], &mut r);
+
+        let serialised_proof = proof.to_bytes();
+        assert_eq!(serialised_proof.len(), Zkp::bytes_len(1));
+        let deserialised_proof = Zkp::from_bytes(&serialised_proof, 1);
+
+        assert_eq!(deserialised_proof, Some(proof));
+        assert!(deserialised_proof
+            .unwrap()
+            .verify(&keypair.public_key, statements));
+    }
+}
@@ -1,10 +1,13 @@
+mod binary_choice;
 mod correct_decryption;
 mod correct_hybrid_decryption_key;
 mod correct_share_generation;
 mod dl_equality;
 mod unit_vector;
 
+pub use binary_choice::BinaryChoiceZkp;
 pub use correct_decryption::CorrectElGamalDecrZkp;
 pub use correct_hybrid_decryption_key::CorrectHybridDecrKeyZkp;
 pub use correct_share_generation::CorrectShareGenerationZkp;
+pub use dl_equality::DleqZkp;
 pub use unit_vector::UnitVectorZkp;
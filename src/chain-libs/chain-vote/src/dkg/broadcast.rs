@@ -0,0 +1,319 @@
+//! Messages published by the committee members at each round of the
+//! distributed key generation.
+//!
+//! Every member is expected to publish exactly one message per round, the
+//! messages of a round are then given to every member for the next round.
+use super::Environment;
+use crate::cryptography::{CorrectHybridDecrKeyZkp, HybridCiphertext};
+use crate::{GroupElement, Scalar};
+
+/// Size of the plaintext of an encrypted share: the share and the randomness
+/// of the Pedersen commitment
+pub(crate) const SHARE_PLAINTEXT_LEN: usize = Scalar::BYTES_LEN * 2;
+const ENCRYPTED_SHARE_LEN: usize = GroupElement::BYTES_LEN + SHARE_PLAINTEXT_LEN;
+const COMPLAINT_LEN: usize = 4 + GroupElement::BYTES_LEN + CorrectHybridDecrKeyZkp::PROOF_SIZE;
+const REVEALED_SHARE_LEN: usize = 4 + Scalar::BYTES_LEN * 2;
+
+/// Round 1: Pedersen commitments of the dealt polynomial and the shares of
+/// every committee member, encrypted with their communication key.
+#[derive(Clone)]
+pub struct BroadcastPhase1 {
+    pub(crate) sender: usize,
+    pub(crate) committed_coefficients: Vec<GroupElement>,
+    /// one per committee member, indexed by the recipient
+    pub(crate) encrypted_shares: Vec<HybridCiphertext>,
+}
+
+/// A complaint against a dealer which sent an invalid share. The decryption
+/// key of the share is revealed with a proof of its correctness, so anyone
+/// can check the share.
+#[derive(Clone)]
+pub struct Complaint {
+    pub(crate) accused: usize,
+    pub(crate) symmetric_key: GroupElement,
+    pub(crate) proof: CorrectHybridDecrKeyZkp,
+}
+
+/// Round 2: complaints against the dealers of invalid shares
+#[derive(Clone)]
+pub struct BroadcastPhase2 {
+    pub(crate) sender: usize,
+    pub(crate) complaints: Vec<Complaint>,
+}
+
+/// Round 3: commitments to the coefficients of the dealt polynomial
+#[derive(Clone)]
+pub struct BroadcastPhase3 {
+    pub(crate) sender: usize,
+    pub(crate) coefficients: Vec<GroupElement>,
+}
+
+/// A share (and its Pedersen commitment randomness) received from a dealer,
+/// published in clear
+#[derive(Clone)]
+pub struct RevealedShare {
+    pub(crate) dealer: usize,
+    pub(crate) share: Scalar,
+    pub(crate) randomness: Scalar,
+}
+
+/// Round 4: shares of the dealers whose coefficients commitments do not
+/// match the share received
+#[derive(Clone)]
+pub struct BroadcastPhase4 {
+    pub(crate) sender: usize,
+    pub(crate) revealed: Vec<RevealedShare>,
+}
+
+/// Round 5: shares of the dealers whose secret is reconstructed publicly
+#[derive(Clone)]
+pub struct BroadcastPhase5 {
+    pub(crate) sender: usize,
+    pub(crate) revealed: Vec<RevealedShare>,
+}
+
+impl BroadcastPhase1 {
+    pub fn sender(&self) -> usize {
+        self.sender
+    }
+
+    /// check the structure of the broadcast against the parameters of the
+    /// key generation
+    pub(crate) fn is_well_formed(&self, environment: &Environment) -> bool {
+        self.committed_coefficients.len() == environment.threshold()
+            && self.encrypted_shares.len() == environment.nr_members()
+            && self
+                .encrypted_shares
+                .iter()
+                .all(|share| share.e2.len() == SHARE_PLAINTEXT_LEN)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(
+            12 + self.committed_coefficients.len() * GroupElement::BYTES_LEN
+                + self.encrypted_shares.len() * ENCRYPTED_SHARE_LEN,
+        );
+        put_index(&mut bytes, self.sender);
+        put_index(&mut bytes, self.committed_coefficients.len());
+        for coefficient in &self.committed_coefficients {
+            bytes.extend_from_slice(&coefficient.to_bytes());
+        }
+        put_index(&mut bytes, self.encrypted_shares.len());
+        for share in &self.encrypted_shares {
+            bytes.extend_from_slice(&share.to_bytes());
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut reader = Reader(bytes);
+        let sender = reader.index()?;
+        let committed_coefficients = reader.group_elements()?;
+        let nr_shares = reader.index()?;
+        let encrypted_shares = (0..nr_shares)
+            .map(|_| HybridCiphertext::from_bytes(reader.take(ENCRYPTED_SHARE_LEN)?))
+            .collect::<Option<Vec<_>>>()?;
+        reader.finish(Self {
+            sender,
+            committed_coefficients,
+            encrypted_shares,
+        })
+    }
+}
+
+impl Complaint {
+    pub fn accused(&self) -> usize {
+        self.accused
+    }
+}
+
+impl BroadcastPhase2 {
+    pub fn sender(&self) -> usize {
+        self.sender
+    }
+
+    pub fn complaints(&self) -> &[Complaint] {
+        &self.complaints
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(8 + self.complaints.len() * COMPLAINT_LEN);
+        put_index(&mut bytes, self.sender);
+        put_index(&mut bytes, self.complaints.len());
+        for complaint in &self.complaints {
+            put_index(&mut bytes, complaint.accused);
+            bytes.extend_from_slice(&complaint.symmetric_key.to_bytes());
+            bytes.extend_from_slice(&complaint.proof.to_bytes());
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut reader = Reader(bytes);
+        let sender = reader.index()?;
+        let nr_complaints = reader.index()?;
+        let complaints = (0..nr_complaints)
+            .map(|_| {
+                let accused = reader.index()?;
+                let symmetric_key = reader.group_element()?;
+                let proof = CorrectHybridDecrKeyZkp::from_bytes(
+                    reader.take(CorrectHybridDecrKeyZkp::PROOF_SIZE)?,
+                )?;
+                Some(Complaint {
+                    accused,
+                    symmetric_key,
+                    proof,
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+        reader.finish(Self { sender, complaints })
+    }
+}
+
+impl BroadcastPhase3 {
+    pub fn sender(&self) -> usize {
+        self.sender
+    }
+
+    pub(crate) fn is_well_formed(&self, environment: &Environment) -> bool {
+        self.coefficients.len() == environment.threshold()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(8 + self.coefficients.len() * GroupElement::BYTES_LEN);
+        put_index(&mut bytes, self.sender);
+        put_index(&mut bytes, self.coefficients.len());
+        for coefficient in &self.coefficients {
+            bytes.extend_from_slice(&coefficient.to_bytes());
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut reader = Reader(bytes);
+        let sender = reader.index()?;
+        let coefficients = reader.group_elements()?;
+        reader.finish(Self {
+            sender,
+            coefficients,
+        })
+    }
+}
+
+impl RevealedShare {
+    pub fn dealer(&self) -> usize {
+        self.dealer
+    }
+}
+
+fn revealed_to_bytes(sender: usize, revealed: &[RevealedShare]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(8 + revealed.len() * REVEALED_SHARE_LEN);
+    put_index(&mut bytes, sender);
+    put_index(&mut bytes, revealed.len());
+    for share in revealed {
+        put_index(&mut bytes, share.dealer);
+        bytes.extend_from_slice(&share.share.to_bytes());
+        bytes.extend_from_slice(&share.randomness.to_bytes());
+    }
+    bytes
+}
+
+fn revealed_from_bytes(bytes: &[u8]) -> Option<(usize, Vec<RevealedShare>)> {
+    let mut reader = Reader(bytes);
+    let sender = reader.index()?;
+    let nr_revealed = reader.index()?;
+    let revealed = (0..nr_revealed)
+        .map(|_| {
+            let dealer = reader.index()?;
+            let share = reader.scalar()?;
+            let randomness = reader.scalar()?;
+            Some(RevealedShare {
+                dealer,
+                share,
+                randomness,
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+    reader.finish((sender, revealed))
+}
+
+impl BroadcastPhase4 {
+    pub fn sender(&self) -> usize {
+        self.sender
+    }
+
+    pub fn revealed(&self) -> &[RevealedShare] {
+        &self.revealed
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        revealed_to_bytes(self.sender, &self.revealed)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        revealed_from_bytes(bytes).map(|(sender, revealed)| Self { sender, revealed })
+    }
+}
+
+impl BroadcastPhase5 {
+    pub fn sender(&self) -> usize {
+        self.sender
+    }
+
+    pub fn revealed(&self) -> &[RevealedShare] {
+        &self.revealed
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        revealed_to_bytes(self.sender, &self.revealed)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        revealed_from_bytes(bytes).map(|(sender, revealed)| Self { sender, revealed })
+    }
+}
+
+pub(crate) fn put_index(bytes: &mut Vec<u8>, index: usize) {
+    bytes.extend_from_slice(&(index as u32).to_be_bytes());
+}
+
+/// Minimal reader over the binary representation of the messages
+pub(crate) struct Reader<'a>(pub(crate) &'a [u8]);
+
+impl<'a> Reader<'a> {
+    pub(crate) fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.0.len() < len {
+            return None;
+        }
+        let (taken, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Some(taken)
+    }
+
+    pub(crate) fn index(&mut self) -> Option<usize> {
+        let bytes = self.take(4)?;
+        Some(u32::from_be_bytes(bytes.try_into().unwrap()) as usize)
+    }
+
+    pub(crate) fn scalar(&mut self) -> Option<Scalar> {
+        Scalar::from_bytes(self.take(Scalar::BYTES_LEN)?)
+    }
+
+    pub(crate) fn group_element(&mut self) -> Option<GroupElement> {
+        GroupElement::from_bytes(self.take(GroupElement::BYTES_LEN)?)
+    }
+
+    pub(crate) fn group_elements(&mut self) -> Option<Vec<GroupElement>> {
+        let len = self.index()?;
+        (0..len).map(|_| self.group_element()).collect()
+    }
+
+    /// return the parsed value only if all the bytes were consumed
+    pub(crate) fn finish<T>(self, value: T) -> Option<T> {
+        if self.0.is_empty() {
+            Some(value)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,799 @@
+use super::broadcast::{
+    put_index, BroadcastPhase1, BroadcastPhase2, BroadcastPhase3, BroadcastPhase4, BroadcastPhase5,
+    Complaint, Reader, RevealedShare, SHARE_PLAINTEXT_LEN,
+};
+use super::{DkgError, Environment};
+use crate::committee::{
+    ElectionPublicKey, MemberCommunicationKey, MemberCommunicationPublicKey, MemberPublicKey,
+    MemberSecretKey,
+};
+use crate::cryptography::{
+    CorrectHybridDecrKeyZkp, HybridCiphertext, PublicKey, SecretKey, SymmetricKey,
+};
+use crate::math::polynomial::{lagrange_interpolation, Polynomial};
+use crate::{GroupElement, Scalar};
+use rand_core::{CryptoRng, RngCore};
+
+/// State of a committee member after the first round of the key generation.
+///
+/// This is the only secret state of the member: all the following rounds
+/// can be replayed from it, the communication key and the broadcasts of the
+/// previous rounds.
+#[derive(Clone)]
+pub struct MemberPhase1 {
+    environment: Environment,
+    index: usize,
+    secret: Polynomial,
+    blinding: Polynomial,
+}
+
+/// State of a committee member after checking the shares received
+#[derive(Clone)]
+pub struct MemberPhase2 {
+    environment: Environment,
+    index: usize,
+    secret: Polynomial,
+    /// shares (and commitment randomness) received from each dealer
+    received: Vec<Option<(Scalar, Scalar)>>,
+}
+
+/// State of a committee member once the qualified dealers are known
+#[derive(Clone)]
+pub struct MemberPhase3 {
+    environment: Environment,
+    index: usize,
+    received: Vec<Option<(Scalar, Scalar)>>,
+    qualified: Vec<usize>,
+}
+
+/// State of a committee member after checking the dealers' coefficients
+#[derive(Clone)]
+pub struct MemberPhase4 {
+    environment: Environment,
+    index: usize,
+    received: Vec<Option<(Scalar, Scalar)>>,
+    qualified: Vec<usize>,
+}
+
+/// State of a committee member once the dealers to reconstruct are known
+#[derive(Clone)]
+pub struct MemberPhase5 {
+    environment: Environment,
+    index: usize,
+    received: Vec<Option<(Scalar, Scalar)>>,
+    qualified: Vec<usize>,
+    reconstructed: Vec<usize>,
+}
+
+/// Public outcome of the distributed key generation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitteeKeys {
+    threshold: usize,
+    qualified: Vec<usize>,
+    election_public_key: ElectionPublicKey,
+    member_public_keys: Vec<MemberPublicKey>,
+}
+
+// committee members are 1-indexed in the polynomials
+fn member_point(index: usize) -> Scalar {
+    Scalar::from_u64(index as u64 + 1)
+}
+
+// evaluate the committed polynomial at the point of the member `index`:
+// `prod_k commitments[k]^(x^k)`
+fn evaluate_commitments(commitments: &[GroupElement], index: usize) -> GroupElement {
+    GroupElement::vartime_multiscalar_multiplication(
+        member_point(index).exp_iter().take(commitments.len()),
+        commitments.iter().cloned(),
+    )
+}
+
+// check the share against the Pedersen commitments of the dealer
+fn verify_pedersen(
+    environment: &Environment,
+    commitments: &[GroupElement],
+    index: usize,
+    share: &Scalar,
+    randomness: &Scalar,
+) -> bool {
+    let committed = GroupElement::generator() * share + environment.crs() * randomness;
+    committed == evaluate_commitments(commitments, index)
+}
+
+// check the share against the coefficients commitments of the dealer
+fn verify_feldman(coefficients: &[GroupElement], index: usize, share: &Scalar) -> bool {
+    GroupElement::generator() * share == evaluate_commitments(coefficients, index)
+}
+
+fn decode_share(plaintext: &[u8]) -> Option<(Scalar, Scalar)> {
+    if plaintext.len() != SHARE_PLAINTEXT_LEN {
+        return None;
+    }
+    let (share, randomness) = plaintext.split_at(Scalar::BYTES_LEN);
+    Some((Scalar::from_bytes(share)?, Scalar::from_bytes(randomness)?))
+}
+
+fn decrypt_share(
+    symmetric_key: &SymmetricKey,
+    ciphertext: &HybridCiphertext,
+) -> Option<(Scalar, Scalar)> {
+    decode_share(&symmetric_key.process(&ciphertext.e2))
+}
+
+// keep the first message of every committee member, ignoring the messages
+// from unknown senders
+fn by_sender<T>(
+    environment: &Environment,
+    broadcasts: &[T],
+    sender: impl Fn(&T) -> usize,
+) -> Vec<Option<&T>> {
+    let mut result = vec![None; environment.nr_members()];
+    for broadcast in broadcasts {
+        match result.get_mut(sender(broadcast)) {
+            Some(slot) if slot.is_none() => *slot = Some(broadcast),
+            _ => {}
+        }
+    }
+    result
+}
+
+fn check_communication_keys(
+    environment: &Environment,
+    committee_pks: &[MemberCommunicationPublicKey],
+) -> Result<(), DkgError> {
+    if committee_pks.len() != environment.nr_members() {
+        return Err(DkgError::CommunicationKeysMismatch {
+            expected: environment.nr_members(),
+            found: committee_pks.len(),
+        });
+    }
+    Ok(())
+}
+
+fn complaint_is_valid(
+    environment: &Environment,
+    complainer_pk: &MemberCommunicationPublicKey,
+    complainer: usize,
+    dealer: &BroadcastPhase1,
+    complaint: &Complaint,
+) -> bool {
+    let ciphertext = &dealer.encrypted_shares[complainer];
+    let symmetric_key = SymmetricKey {
+        group_repr: complaint.symmetric_key.clone(),
+    };
+    if !complaint
+        .proof
+        .verify(ciphertext, &symmetric_key, &complainer_pk.0)
+    {
+        return false;
+    }
+    match decrypt_share(&symmetric_key, ciphertext) {
+        None => true,
+        Some((share, randomness)) => !verify_pedersen(
+            environment,
+            &dealer.committed_coefficients,
+            complainer,
+            &share,
+            &randomness,
+        ),
+    }
+}
+
+/// Compute the set of qualified dealers from the messages of the first two
+/// rounds: the dealers who published well formed shares and against whom no
+/// valid complaint was raised.
+pub fn qualified_members(
+    environment: &Environment,
+    committee_pks: &[MemberCommunicationPublicKey],
+    broadcasts_1: &[BroadcastPhase1],
+    broadcasts_2: &[BroadcastPhase2],
+) -> Result<Vec<usize>, DkgError> {
+    check_communication_keys(environment, committee_pks)?;
+
+    let dealers = by_sender(environment, broadcasts_1, BroadcastPhase1::sender);
+    let mut qualified = dealers
+        .iter()
+        .map(|dealer| dealer.map_or(false, |dealer| dealer.is_well_formed(environment)))
+        .collect::<Vec<_>>();
+
+    let complainers = by_sender(environment, broadcasts_2, BroadcastPhase2::sender);
+    for (complainer, broadcast) in complainers.iter().enumerate() {
+        let broadcast = match broadcast {
+            Some(broadcast) => broadcast,
+            None => continue,
+        };
+        for complaint in &broadcast.complaints {
+            let accused = complaint.accused;
+            if !qualified.get(accused).copied().unwrap_or(false) {
+                continue;
+            }
+            let dealer = dealers[accused].expect("qualified dealers have published");
+            if complaint_is_valid(
+                environment,
+                &committee_pks[complainer],
+                complainer,
+                dealer,
+                complaint,
+            ) {
+                qualified[accused] = false;
+            }
+        }
+    }
+
+    let qualified = qualified
+        .iter()
+        .enumerate()
+        .filter(|(_, qualified)| **qualified)
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>();
+    if qualified.len() < environment.threshold() {
+        return Err(DkgError::InsufficientQualifiedMembers {
+            qualified: qualified.len(),
+            threshold: environment.threshold(),
+        });
+    }
+    Ok(qualified)
+}
+
+/// Compute the qualified dealers whose secret needs to be reconstructed
+/// publicly: the ones who did not publish their coefficients commitments and
+/// the ones whose commitments do not match a share revealed by a member.
+pub fn reconstructed_members(
+    environment: &Environment,
+    qualified: &[usize],
+    broadcasts_1: &[BroadcastPhase1],
+    broadcasts_3: &[BroadcastPhase3],
+    broadcasts_4: &[BroadcastPhase4],
+) -> Vec<usize> {
+    let dealers = by_sender(environment, broadcasts_1, BroadcastPhase1::sender);
+    let coefficients = by_sender(environment, broadcasts_3, BroadcastPhase3::sender);
+    let revealers = by_sender(environment, broadcasts_4, BroadcastPhase4::sender);
+
+    qualified
+        .iter()
+        .copied()
+        .filter(|dealer| {
+            let coefficients = match coefficients[*dealer] {
+                Some(broadcast) if broadcast.is_well_formed(environment) => &broadcast.coefficients,
+                _ => return true,
+            };
+            let committed = match dealers[*dealer] {
+                Some(broadcast) => &broadcast.committed_coefficients,
+                None => return true,
+            };
+            revealers.iter().enumerate().any(|(revealer, broadcast)| {
+                broadcast.map_or(false, |broadcast| {
+                    broadcast
+                        .revealed
+                        .iter()
+                        .filter(|revealed| revealed.dealer == *dealer)
+                        .any(|revealed| {
+                            verify_pedersen(
+                                environment,
+                                committed,
+                                revealer,
+                                &revealed.share,
+                                &revealed.randomness,
+                            ) && !verify_feldman(coefficients, revealer, &revealed.share)
+                        })
+                })
+            })
+        })
+        .collect()
+}
+
+/// Compute the keys resulting from the key generation, from all the messages
+/// published during the protocol.
+#[allow(clippy::too_many_arguments)]
+pub fn committee_keys(
+    environment: &Environment,
+    committee_pks: &[MemberCommunicationPublicKey],
+    broadcasts_1: &[BroadcastPhase1],
+    broadcasts_2: &[BroadcastPhase2],
+    broadcasts_3: &[BroadcastPhase3],
+    broadcasts_4: &[BroadcastPhase4],
+    broadcasts_5: &[BroadcastPhase5],
+) -> Result<CommitteeKeys, DkgError> {
+    let qualified = qualified_members(environment, committee_pks, broadcasts_1, broadcasts_2)?;
+    let reconstructed = reconstructed_members(
+        environment,
+        &qualified,
+        broadcasts_1,
+        broadcasts_3,
+        broadcasts_4,
+    );
+    CommitteeKeys::compute(
+        environment,
+        qualified,
+        &reconstructed,
+        broadcasts_1,
+        broadcasts_3,
+        broadcasts_5,
+    )
+}
+
+// public contribution of a qualified dealer to the committee keys
+enum Contribution<'a> {
+    Committed(&'a [GroupElement]),
+    Reconstructed(Vec<(Scalar, Scalar)>),
+}
+
+impl<'a> Contribution<'a> {
+    fn evaluate(&self, at: &Scalar) -> GroupElement {
+        match self {
+            Contribution::Committed(coefficients) => {
+                GroupElement::vartime_multiscalar_multiplication(
+                    at.exp_iter().take(coefficients.len()),
+                    coefficients.iter().cloned(),
+                )
+            }
+            Contribution::Reconstructed(points) => {
+                GroupElement::generator() * lagrange_interpolation(points, at)
+            }
+        }
+    }
+}
+
+impl CommitteeKeys {
+    fn compute(
+        environment: &Environment,
+        qualified: Vec<usize>,
+        reconstructed: &[usize],
+        broadcasts_1: &[BroadcastPhase1],
+        broadcasts_3: &[BroadcastPhase3],
+        broadcasts_5: &[BroadcastPhase5],
+    ) -> Result<Self, DkgError> {
+        let dealers = by_sender(environment, broadcasts_1, BroadcastPhase1::sender);
+        let coefficients = by_sender(environment, broadcasts_3, BroadcastPhase3::sender);
+        let revealers = by_sender(environment, broadcasts_5, BroadcastPhase5::sender);
+
+        let contributions = qualified
+            .iter()
+            .map(|dealer| {
+                if !reconstructed.contains(dealer) {
+                    let coefficients = coefficients[*dealer]
+                        .expect("dealers with missing coefficients are reconstructed");
+                    return Ok(Contribution::Committed(&coefficients.coefficients));
+                }
+                let committed = &dealers[*dealer]
+                    .expect("qualified dealers have published")
+                    .committed_coefficients;
+                let points = revealers
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(revealer, broadcast)| {
+                        (*broadcast)?
+                            .revealed
+                            .iter()
+                            .find(|revealed| revealed.dealer == *dealer)
+                            .filter(|revealed| {
+                                verify_pedersen(
+                                    environment,
+                                    committed,
+                                    revealer,
+                                    &revealed.share,
+                                    &revealed.randomness,
+                                )
+                            })
+                            .map(|revealed| (member_point(revealer), revealed.share.clone()))
+                    })
+                    .take(environment.threshold())
+                    .collect::<Vec<_>>();
+                if points.len() < environment.threshold() {
+                    return Err(DkgError::InsufficientRevealedShares { dealer: *dealer });
+                }
+                Ok(Contribution::Reconstructed(points))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let public_key_at = |at: &Scalar| {
+            contributions
+                .iter()
+                .fold(GroupElement::zero(), |acc, contribution| {
+                    acc + contribution.evaluate(at)
+                })
+        };
+
+        let election_public_key = ElectionPublicKey(PublicKey {
+            pk: public_key_at(&Scalar::zero()),
+        });
+        let member_public_keys = (0..environment.nr_members())
+            .map(|index| {
+                MemberPublicKey(PublicKey {
+                    pk: public_key_at(&member_point(index)),
+                })
+            })
+            .collect();
+
+        Ok(CommitteeKeys {
+            threshold: environment.threshold(),
+            qualified,
+            election_public_key,
+            member_public_keys,
+        })
+    }
+
+    /// Minimum number of committee members needed to decrypt the tally
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// Indices of the members whose dealt secret is part of the election key
+    pub fn qualified_members(&self) -> &[usize] {
+        &self.qualified
+    }
+
+    /// The key the votes are encrypted to
+    pub fn election_public_key(&self) -> &ElectionPublicKey {
+        &self.election_public_key
+    }
+
+    /// The public keys of the members' shares, indexed by member, used to
+    /// verify their tally decryption shares
+    pub fn member_public_keys(&self) -> &[MemberPublicKey] {
+        &self.member_public_keys
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        put_index(&mut bytes, self.threshold);
+        put_index(&mut bytes, self.qualified.len());
+        for index in &self.qualified {
+            put_index(&mut bytes, *index);
+        }
+        bytes.extend_from_slice(&self.election_public_key.to_bytes());
+        put_index(&mut bytes, self.member_public_keys.len());
+        for pk in &self.member_public_keys {
+            bytes.extend_from_slice(&pk.to_bytes());
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut reader = Reader(bytes);
+        let threshold = reader.index()?;
+        let nr_qualified = reader.index()?;
+        let qualified = (0..nr_qualified)
+            .map(|_| reader.index())
+            .collect::<Option<Vec<_>>>()?;
+        let election_public_key = ElectionPublicKey(PublicKey {
+            pk: reader.group_element()?,
+        });
+        let member_public_keys = reader
+            .group_elements()?
+            .into_iter()
+            .map(|pk| MemberPublicKey(PublicKey { pk }))
+            .collect();
+        reader.finish(Self {
+            threshold,
+            qualified,
+            election_public_key,
+            member_public_keys,
+        })
+    }
+}
+
+impl MemberPhase1 {
+    /// Start the key generation for the committee member `index`: deal a
+    /// random secret to all the committee members.
+    pub fn init<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        environment: &Environment,
+        committee_pks: &[MemberCommunicationPublicKey],
+        index: usize,
+    ) -> Result<(Self, BroadcastPhase1), DkgError> {
+        check_communication_keys(environment, committee_pks)?;
+        if index >= environment.nr_members() {
+            return Err(DkgError::InvalidMemberIndex {
+                index,
+                nr_members: environment.nr_members(),
+            });
+        }
+
+        let degree = environment.threshold() - 1;
+        let secret = Polynomial::random(rng, degree);
+        let blinding = Polynomial::random(rng, degree);
+
+        let committed_coefficients = secret
+            .get_coefficients()
+            .zip(blinding.get_coefficients())
+            .map(|(a, b)| GroupElement::generator() * a + environment.crs() * b)
+            .collect();
+
+        let encrypted_shares = committee_pks
+            .iter()
+            .enumerate()
+            .map(|(recipient, pk)| {
+                let at = member_point(recipient);
+                let mut plaintext = Vec::with_capacity(SHARE_PLAINTEXT_LEN);
+                plaintext.extend_from_slice(&secret.evaluate(&at).to_bytes());
+                plaintext.extend_from_slice(&blinding.evaluate(&at).to_bytes());
+                pk.0.hybrid_encrypt(&plaintext, rng)
+            })
+            .collect();
+
+        let broadcast = BroadcastPhase1 {
+            sender: index,
+            committed_coefficients,
+            encrypted_shares,
+        };
+        let state = MemberPhase1 {
+            environment: environment.clone(),
+            index,
+            secret,
+            blinding,
+        };
+        Ok((state, broadcast))
+    }
+
+    pub fn environment(&self) -> &Environment {
+        &self.environment
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Check the shares received from the other dealers and complain about
+    /// the invalid ones.
+    pub fn to_phase_2<R: RngCore + CryptoRng>(
+        self,
+        rng: &mut R,
+        communication_key: &MemberCommunicationKey,
+        broadcasts_1: &[BroadcastPhase1],
+    ) -> (MemberPhase2, BroadcastPhase2) {
+        let environment = &self.environment;
+        let communication_pk = communication_key.to_public();
+        let mut received = vec![None; environment.nr_members()];
+        let mut complaints = Vec::new();
+
+        for (dealer, broadcast) in by_sender(environment, broadcasts_1, BroadcastPhase1::sender)
+            .into_iter()
+            .enumerate()
+        {
+            if dealer == self.index {
+                let at = member_point(self.index);
+                received[dealer] = Some((self.secret.evaluate(&at), self.blinding.evaluate(&at)));
+                continue;
+            }
+            // missing and malformed broadcasts disqualify the dealer publicly
+            let broadcast = match broadcast {
+                Some(broadcast) if broadcast.is_well_formed(environment) => broadcast,
+                _ => continue,
+            };
+            let ciphertext = &broadcast.encrypted_shares[self.index];
+            let symmetric_key = communication_key.0.recover_symmetric_key(ciphertext);
+            match decrypt_share(&symmetric_key, ciphertext) {
+                Some((share, randomness))
+                    if verify_pedersen(
+                        environment,
+                        &broadcast.committed_coefficients,
+                        self.index,
+                        &share,
+                        &randomness,
+                    ) =>
+                {
+                    received[dealer] = Some((share, randomness));
+                }
+                _ => {
+                    let proof = CorrectHybridDecrKeyZkp::generate(
+                        ciphertext,
+                        &communication_pk.0,
+                        &symmetric_key,
+                        &communication_key.0,
+                        rng,
+                    );
+                    complaints.push(Complaint {
+                        accused: dealer,
+                        symmetric_key: symmetric_key.group_repr,
+                        proof,
+                    });
+                }
+            }
+        }
+
+        let broadcast = BroadcastPhase2 {
+            sender: self.index,
+            complaints,
+        };
+        let state = MemberPhase2 {
+            environment: self.environment,
+            index: self.index,
+            secret: self.secret,
+            received,
+        };
+        (state, broadcast)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.environment.to_bytes();
+        put_index(&mut bytes, self.index);
+        for coefficient in self
+            .secret
+            .get_coefficients()
+            .chain(self.blinding.get_coefficients())
+        {
+            bytes.extend_from_slice(&coefficient.to_bytes());
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut reader = Reader(bytes);
+        let environment = Environment::from_reader(&mut reader)?;
+        let index = reader.index()?;
+        if index >= environment.nr_members() {
+            return None;
+        }
+        let mut polynomial = || {
+            (0..environment.threshold())
+                .map(|_| reader.scalar())
+                .collect::<Option<Vec<_>>>()
+                .map(Polynomial::from_vec)
+        };
+        let secret = polynomial()?;
+        let blinding = polynomial()?;
+        reader.finish(Self {
+            environment,
+            index,
+            secret,
+            blinding,
+        })
+    }
+}
+
+impl MemberPhase2 {
+    /// Compute the qualified dealers and publish the commitments to the
+    /// coefficients of the dealt polynomial.
+    pub fn to_phase_3(
+        self,
+        committee_pks: &[MemberCommunicationPublicKey],
+        broadcasts_1: &[BroadcastPhase1],
+        broadcasts_2: &[BroadcastPhase2],
+    ) -> Result<(MemberPhase3, BroadcastPhase3), DkgError> {
+        let qualified =
+            qualified_members(&self.environment, committee_pks, broadcasts_1, broadcasts_2)?;
+        if let Some(dealer) = qualified
+            .iter()
+            .find(|dealer| self.received[**dealer].is_none())
+        {
+            return Err(DkgError::ShareMissing { dealer: *dealer });
+        }
+
+        let broadcast = BroadcastPhase3 {
+            sender: self.index,
+            coefficients: self
+                .secret
+                .get_coefficients()
+                .map(|a| GroupElement::generator() * a)
+                .collect(),
+        };
+        let state = MemberPhase3 {
+            environment: self.environment,
+            index: self.index,
+            received: self.received,
+            qualified,
+        };
+        Ok((state, broadcast))
+    }
+}
+
+impl MemberPhase3 {
+    pub fn qualified_members(&self) -> &[usize] {
+        &self.qualified
+    }
+
+    /// Check the shares received against the coefficients published by the
+    /// qualified dealers, and reveal the shares which do not match.
+    pub fn to_phase_4(self, broadcasts_3: &[BroadcastPhase3]) -> (MemberPhase4, BroadcastPhase4) {
+        let coefficients = by_sender(&self.environment, broadcasts_3, BroadcastPhase3::sender);
+        let revealed = self
+            .qualified
+            .iter()
+            .copied()
+            .filter(|dealer| *dealer != self.index)
+            .filter_map(|dealer| {
+                // a missing broadcast is reconstructed without needing a complaint
+                let broadcast = coefficients[dealer]?;
+                let (share, randomness) = self.received[dealer].clone()?;
+                if broadcast.is_well_formed(&self.environment)
+                    && verify_feldman(&broadcast.coefficients, self.index, &share)
+                {
+                    None
+                } else {
+                    Some(RevealedShare {
+                        dealer,
+                        share,
+                        randomness,
+                    })
+                }
+            })
+            .collect();
+
+        let broadcast = BroadcastPhase4 {
+            sender: self.index,
+            revealed,
+        };
+        let state = MemberPhase4 {
+            environment: self.environment,
+            index: self.index,
+            received: self.received,
+            qualified: self.qualified,
+        };
+        (state, broadcast)
+    }
+}
+
+impl MemberPhase4 {
+    /// Reveal the shares of the qualified dealers whose secret needs to be
+    /// reconstructed publicly.
+    pub fn to_phase_5(
+        self,
+        broadcasts_1: &[BroadcastPhase1],
+        broadcasts_3: &[BroadcastPhase3],
+        broadcasts_4: &[BroadcastPhase4],
+    ) -> (MemberPhase5, BroadcastPhase5) {
+        let reconstructed = reconstructed_members(
+            &self.environment,
+            &self.qualified,
+            broadcasts_1,
+            broadcasts_3,
+            broadcasts_4,
+        );
+        let revealed = reconstructed
+            .iter()
+            .filter_map(|dealer| {
+                let (share, randomness) = self.received[*dealer].clone()?;
+                Some(RevealedShare {
+                    dealer: *dealer,
+                    share,
+                    randomness,
+                })
+            })
+            .collect();
+
+        let broadcast = BroadcastPhase5 {
+            sender: self.index,
+            revealed,
+        };
+        let state = MemberPhase5 {
+            environment: self.environment,
+            index: self.index,
+            received: self.received,
+            qualified: self.qualified,
+            reconstructed,
+        };
+        (state, broadcast)
+    }
+}
+
+impl MemberPhase5 {
+    /// Compute the member's secret share of the election key, and the public
+    /// keys of the committee.
+    pub fn finalize(
+        self,
+        broadcasts_1: &[BroadcastPhase1],
+        broadcasts_3: &[BroadcastPhase3],
+        broadcasts_5: &[BroadcastPhase5],
+    ) -> Result<(MemberSecretKey, CommitteeKeys), DkgError> {
+        let share = Scalar::sum(self.qualified.iter().map(|dealer| {
+            self.received[*dealer]
+                .as_ref()
+                .map(|(share, _)| share.clone())
+                .expect("shares of qualified dealers are checked in phase 3")
+        }))
+        .expect("there is at least one qualified dealer");
+
+        let keys = CommitteeKeys::compute(
+            &self.environment,
+            self.qualified,
+            &self.reconstructed,
+            broadcasts_1,
+            broadcasts_3,
+            broadcasts_5,
+        )?;
+        let secret_key = MemberSecretKey(SecretKey { sk: share });
+        debug_assert_eq!(secret_key.to_public(), keys.member_public_keys[self.index]);
+        Ok((secret_key, keys))
+    }
+}
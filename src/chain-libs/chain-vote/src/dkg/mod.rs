@@ -0,0 +1,362 @@
+//! Distributed key generation of the committee keys, following the
+//! protocol of Gennaro, Jarecki, Krawczyk and Rabin ("Secure Distributed
+//! Key Generation for Discrete-Log Based Cryptosystems").
+//!
+//! Every committee member deals a random secret to all the members with a
+//! verifiable secret sharing scheme. The election key is the sum of the
+//! secrets of the qualified dealers, and any `threshold` members can decrypt
+//! the tally without ever reconstructing the election secret key.
+//!
+//! The protocol runs in 5 rounds. At each round every member publishes a
+//! broadcast message, and reads the messages of all the members for the
+//! previous rounds:
+//!
+//! 1. deal the shares, encrypted to the communication key of each member,
+//!    with Pedersen commitments to the dealt polynomial;
+//! 2. complain against the dealers of invalid shares;
+//! 3. publish commitments to the coefficients of the dealt polynomial;
+//! 4. reveal the shares which do not match those commitments;
+//! 5. reveal the shares of the dealers whose secret is reconstructed.
+mod broadcast;
+mod committee;
+
+use self::broadcast::{put_index, Reader};
+use crate::tally::Crs;
+use crate::GroupElement;
+
+pub use self::broadcast::{
+    BroadcastPhase1, BroadcastPhase2, BroadcastPhase3, BroadcastPhase4, BroadcastPhase5, Complaint,
+    RevealedShare,
+};
+pub use self::committee::{
+    committee_keys, qualified_members, reconstructed_members, CommitteeKeys, MemberPhase1,
+    MemberPhase2, MemberPhase3, MemberPhase4, MemberPhase5,
+};
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum DkgError {
+    #[error("invalid threshold {threshold} for a committee of {nr_members} members")]
+    InvalidThreshold { threshold: usize, nr_members: usize },
+    #[error("invalid member index {index} for a committee of {nr_members} members")]
+    InvalidMemberIndex { index: usize, nr_members: usize },
+    #[error("expected the communication keys of {expected} members, found {found}")]
+    CommunicationKeysMismatch { expected: usize, found: usize },
+    #[error("only {qualified} qualified members, at least {threshold} are needed")]
+    InsufficientQualifiedMembers { qualified: usize, threshold: usize },
+    #[error("no valid share received from the qualified dealer {dealer}")]
+    ShareMissing { dealer: usize },
+    #[error("not enough valid shares revealed to reconstruct the secret of dealer {dealer}")]
+    InsufficientRevealedShares { dealer: usize },
+}
+
+/// Public parameters of the key generation, shared by all the members.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Environment {
+    threshold: usize,
+    nr_members: usize,
+    crs: Crs,
+}
+
+impl Environment {
+    /// `threshold` is the number of members needed to decrypt the tally
+    pub fn new(threshold: usize, nr_members: usize, crs: &Crs) -> Result<Self, DkgError> {
+        if threshold == 0 || threshold > nr_members {
+            return Err(DkgError::InvalidThreshold {
+                threshold,
+                nr_members,
+            });
+        }
+        Ok(Self {
+            threshold,
+            nr_members,
+            crs: crs.clone(),
+        })
+    }
+
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    pub fn nr_members(&self) -> usize {
+        self.nr_members
+    }
+
+    pub(crate) fn crs(&self) -> &Crs {
+        &self.crs
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(8 + GroupElement::BYTES_LEN);
+        put_index(&mut bytes, self.threshold);
+        put_index(&mut bytes, self.nr_members);
+        bytes.extend_from_slice(&self.crs.to_bytes());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut reader = Reader(bytes);
+        let environment = Self::from_reader(&mut reader)?;
+        reader.finish(environment)
+    }
+
+    pub(crate) fn from_reader(reader: &mut Reader) -> Option<Self> {
+        let threshold = reader.index()?;
+        let nr_members = reader.index()?;
+        let crs = reader.group_element()?;
+        Self::new(threshold, nr_members, &crs).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::committee::MemberSecretKey;
+    use crate::committee::{MemberCommunicationKey, MemberCommunicationPublicKey};
+    use crate::cryptography::HybridCiphertext;
+    use crate::{Ballot, ElectionPublicKey, EncryptedTally, TallyOptimizationTable, Vote};
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+
+    struct Run {
+        environment: Environment,
+        communication_keys: Vec<MemberCommunicationKey>,
+        communication_pks: Vec<MemberCommunicationPublicKey>,
+    }
+
+    impl Run {
+        fn new(rng: &mut ChaCha20Rng, threshold: usize, nr_members: usize) -> Self {
+            let crs = Crs::from_hash(b"dkg test crs");
+            let environment = Environment::new(threshold, nr_members, &crs).unwrap();
+            let communication_keys = (0..nr_members)
+                .map(|_| MemberCommunicationKey::new(rng))
+                .collect::<Vec<_>>();
+            let communication_pks = communication_keys
+                .iter()
+                .map(MemberCommunicationKey::to_public)
+                .collect();
+            Run {
+                environment,
+                communication_keys,
+                communication_pks,
+            }
+        }
+
+        // run all the rounds, letting the test tamper with the broadcasts
+        // of the first and third rounds
+        fn execute(
+            &self,
+            rng: &mut ChaCha20Rng,
+            tamper_1: impl Fn(&mut Vec<BroadcastPhase1>),
+            tamper_3: impl Fn(&mut Vec<BroadcastPhase3>),
+        ) -> Result<Vec<(MemberSecretKey, CommitteeKeys)>, DkgError> {
+            let (members, mut b1): (Vec<_>, Vec<_>) = (0..self.environment.nr_members())
+                .map(|index| {
+                    MemberPhase1::init(rng, &self.environment, &self.communication_pks, index)
+                        .unwrap()
+                })
+                .unzip();
+            tamper_1(&mut b1);
+
+            let (members, b2): (Vec<_>, Vec<_>) = members
+                .into_iter()
+                .zip(self.communication_keys.iter())
+                .map(|(member, key)| member.to_phase_2(rng, key, &b1))
+                .unzip();
+
+            let (members, mut b3): (Vec<_>, Vec<_>) = members
+                .into_iter()
+                .map(|member| member.to_phase_3(&self.communication_pks, &b1, &b2))
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .unzip();
+            tamper_3(&mut b3);
+
+            let (members, b4): (Vec<_>, Vec<_>) = members
+                .into_iter()
+                .map(|member| member.to_phase_4(&b3))
+                .unzip();
+            let (members, b5): (Vec<_>, Vec<_>) = members
+                .into_iter()
+                .map(|member| member.to_phase_5(&b1, &b3, &b4))
+                .unzip();
+
+            let results = members
+                .into_iter()
+                .map(|member| member.finalize(&b1, &b3, &b5))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let observed = committee_keys(
+                &self.environment,
+                &self.communication_pks,
+                &b1,
+                &b2,
+                &b3,
+                &b4,
+                &b5,
+            )?;
+            for (_, keys) in &results {
+                assert_eq!(keys, &observed);
+            }
+            Ok(results)
+        }
+    }
+
+    fn assert_threshold_decryption(
+        rng: &mut ChaCha20Rng,
+        run: &Run,
+        results: &[(MemberSecretKey, CommitteeKeys)],
+        decrypting: &[usize],
+    ) {
+        let keys = &results[0].1;
+        let election_pk = keys.election_public_key();
+        let crs = run.environment.crs();
+        assert_eq!(
+            &ElectionPublicKey::from_threshold_participants(
+                keys.member_public_keys(),
+                keys.threshold()
+            ),
+            election_pk
+        );
+
+        let mut tally = EncryptedTally::new(3, election_pk.clone(), crs.clone());
+        for (choice, weight) in [(0, 4), (2, 1), (0, 2)] {
+            let (vote, proof) =
+                election_pk.encrypt_and_prove_vote(rng, crs, Vote::new(3, choice).unwrap());
+            let ballot = Ballot::try_from_vote_and_proof(vote, &proof, crs, election_pk).unwrap();
+            tally.add(&ballot, weight);
+        }
+
+        let shares = decrypting
+            .iter()
+            .map(|index| (*index, tally.partial_decrypt(rng, &results[*index].0)))
+            .collect::<Vec<_>>();
+        let table = TallyOptimizationTable::generate(7.try_into().unwrap());
+        let result = tally
+            .validate_threshold_partial_decryptions(
+                keys.member_public_keys(),
+                keys.threshold(),
+                &shares,
+            )
+            .unwrap()
+            .decrypt_tally(&table)
+            .unwrap();
+        assert_eq!(result.votes, vec![6, 0, 1]);
+        assert!(result.verify_threshold(
+            &tally,
+            keys.member_public_keys(),
+            keys.threshold(),
+            &shares
+        ));
+
+        // less than threshold shares are not enough
+        assert!(tally
+            .validate_threshold_partial_decryptions(
+                keys.member_public_keys(),
+                keys.threshold(),
+                &shares[1..],
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn honest_run_threshold_decryption() {
+        let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+        let run = Run::new(&mut rng, 3, 5);
+        let results = run.execute(&mut rng, |_| {}, |_| {}).unwrap();
+
+        let keys = &results[0].1;
+        assert_eq!(keys.qualified_members(), &[0, 1, 2, 3, 4]);
+        for (index, (secret_key, keys)) in results.iter().enumerate() {
+            assert_eq!(secret_key.to_public(), keys.member_public_keys()[index]);
+        }
+        assert_threshold_decryption(&mut rng, &run, &results, &[4, 0, 2]);
+    }
+
+    #[test]
+    fn invalid_share_disqualifies_dealer() {
+        let mut rng = ChaCha20Rng::from_seed([1u8; 32]);
+        let run = Run::new(&mut rng, 2, 4);
+        let garbage = HybridCiphertext {
+            e1: GroupElement::generator(),
+            e2: vec![0u8; broadcast::SHARE_PLAINTEXT_LEN].into_boxed_slice(),
+        };
+        let results = run
+            .execute(
+                &mut rng,
+                |b1| b1[1].encrypted_shares[3] = garbage.clone(),
+                |_| {},
+            )
+            .unwrap();
+
+        let keys = &results[0].1;
+        assert_eq!(keys.qualified_members(), &[0, 2, 3]);
+        assert_threshold_decryption(&mut rng, &run, &results, &[1, 3]);
+    }
+
+    #[test]
+    fn invalid_coefficients_are_reconstructed() {
+        let mut rng = ChaCha20Rng::from_seed([2u8; 32]);
+        let run = Run::new(&mut rng, 2, 3);
+        let results = run
+            .execute(
+                &mut rng,
+                |_| {},
+                |b3| {
+                    b3[0].coefficients[1] = GroupElement::generator();
+                    b3.remove(2);
+                },
+            )
+            .unwrap();
+
+        let keys = &results[0].1;
+        assert_eq!(keys.qualified_members(), &[0, 1, 2]);
+        for (index, (secret_key, keys)) in results.iter().enumerate() {
+            assert_eq!(secret_key.to_public(), keys.member_public_keys()[index]);
+        }
+        assert_threshold_decryption(&mut rng, &run, &results, &[0, 2]);
+    }
+
+    #[test]
+    fn too_many_disqualified_dealers() {
+        let mut rng = ChaCha20Rng::from_seed([3u8; 32]);
+        let run = Run::new(&mut rng, 3, 3);
+        let result = run.execute(&mut rng, |b1| b1.truncate(2), |_| {});
+        assert_eq!(
+            result.err(),
+            Some(DkgError::InsufficientQualifiedMembers {
+                qualified: 2,
+                threshold: 3
+            })
+        );
+    }
+
+    #[test]
+    fn serialization_roundtrip() {
+        let mut rng = ChaCha20Rng::from_seed([4u8; 32]);
+        let run = Run::new(&mut rng, 2, 3);
+        assert!(Environment::new(4, 3, run.environment.crs()).is_err());
+        assert_eq!(
+            Environment::from_bytes(&run.environment.to_bytes()),
+            Some(run.environment.clone())
+        );
+
+        let (member, b1) =
+            MemberPhase1::init(&mut rng, &run.environment, &run.communication_pks, 1).unwrap();
+        let restored = MemberPhase1::from_bytes(&member.to_bytes()).unwrap();
+        assert_eq!(restored.to_bytes(), member.to_bytes());
+        assert_eq!(
+            BroadcastPhase1::from_bytes(&b1.to_bytes())
+                .unwrap()
+                .to_bytes(),
+            b1.to_bytes()
+        );
+
+        let results = run.execute(&mut rng, |_| {}, |_| {}).unwrap();
+        let keys = &results[0].1;
+        assert_eq!(
+            CommitteeKeys::from_bytes(&keys.to_bytes()).as_ref(),
+            Some(keys)
+        );
+        assert!(CommitteeKeys::from_bytes(&keys.to_bytes()[1..]).is_none());
+    }
+}
@@ -51,6 +51,15 @@ impl Ballot {
         })
     }
 
+    /// Build a ballot out of a vote verified by other means, e.g. the weighted
+    /// vote of a quadratic ballot
+    pub(crate) fn from_verified_vote(
+        vote: EncryptedVote,
+        fingerprint: ElectionFingerprint,
+    ) -> Self {
+        Self { vote, fingerprint }
+    }
+
     pub fn vote(&self) -> &EncryptedVote {
         &self.vote
     }
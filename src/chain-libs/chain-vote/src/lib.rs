@@ -2,8 +2,10 @@
 mod macros;
 pub mod committee;
 mod cryptography;
+pub mod dkg;
 mod encrypted_vote;
 mod math;
+pub mod quadratic;
 pub mod tally;
 
 // re-export under a debug module
@@ -33,5 +35,8 @@ pub use crate::{
         Ballot, BallotVerificationError, EncryptedVote, ProofOfCorrectVote,
         UnitVectorInitializationError, Vote,
     },
+    quadratic::{
+        CreditAllocation, CreditsOpening, EncryptedCredits, InsufficientCredits, QuadraticBallot,
+    },
     tally::{Crs, EncryptedTally, Tally, TallyDecryptShare},
 };
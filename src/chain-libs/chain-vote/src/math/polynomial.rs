@@ -121,9 +121,60 @@ impl std::ops::Mul<Polynomial> for Polynomial {
     }
 }
 
+/// Evaluate at `at` the Lagrange basis polynomial of the point `x` for the
+/// set of points `xs`:
+///
+/// `l(at) = prod_{x' in xs, x' != x} (at - x') / (x - x')`
+pub fn lagrange_basis(x: &Scalar, xs: &[Scalar], at: &Scalar) -> Scalar {
+    let mut numerator = Scalar::one();
+    let mut denominator = Scalar::one();
+    for other in xs.iter().filter(|other| *other != x) {
+        numerator = &numerator * &(at - other);
+        denominator = &denominator * &(x - other);
+    }
+    numerator * denominator.inverse()
+}
+
+/// Evaluate at `at` the unique polynomial of degree `points.len() - 1` going
+/// through all the `(x, y)` points
+pub fn lagrange_interpolation(points: &[(Scalar, Scalar)], at: &Scalar) -> Scalar {
+    let xs = points.iter().map(|(x, _)| x.clone()).collect::<Vec<_>>();
+    Scalar::sum(points.iter().map(|(x, y)| y * lagrange_basis(x, &xs, at)))
+        .unwrap_or_else(Scalar::zero)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+
+    #[test]
+    fn lagrange_interpolation_recovers_polynomial() {
+        let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+        let poly = Polynomial::random(&mut rng, 2);
+
+        let points = [1u64, 3, 4]
+            .iter()
+            .map(|x| {
+                let x = Scalar::from_u64(*x);
+                let y = poly.evaluate(&x);
+                (x, y)
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            lagrange_interpolation(&points, &Scalar::zero()),
+            poly.at_zero()
+        );
+        let at = Scalar::from_u64(7);
+        assert_eq!(lagrange_interpolation(&points, &at), poly.evaluate(&at));
+        // not enough points to recover the polynomial
+        assert_ne!(
+            lagrange_interpolation(&points[..2], &Scalar::zero()),
+            poly.at_zero()
+        );
+    }
     #[test]
     fn poly_tests() {
         let poly_deg_4 = Polynomial::new(4).set2(Scalar::one(), Scalar::from_u64(3));
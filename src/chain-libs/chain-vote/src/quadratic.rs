@@ -0,0 +1,732 @@
+//! Quadratic voting with encrypted ballots.
+//!
+//! In a quadratic vote, each voter has a budget of credits for the whole vote
+//! plan, and allocating `c` credits to a proposal gives a voting weight of
+//! `floor(sqrt(c))` to the chosen option. With encrypted ballots, neither the
+//! weight nor the credits are disclosed: the voter encrypts the unit vector of
+//! the vote multiplied by the weight `w`, and proves in zero knowledge that
+//!
+//! * the vote is a unit vector, with the usual [`ProofOfCorrectVote`];
+//! * `w` is a `WEIGHT_BITS` bits integer: each bit `b_j` is encrypted and
+//!   proven to be either `0` or `1`;
+//! * each entry of the weighted vote encrypts either `0` or `w`, depending
+//!   on the corresponding entry of the unit vector;
+//! * the allocated credits are `c = w^2 = sum(2^j * b_j * w)`, each
+//!   `b_j * w` being proven in the same way as the weighted vote entries;
+//! * the credits already spent in the vote plan plus `c` do not exceed the
+//!   budget: the remainder `budget - spent - c` is decomposed in
+//!   `CREDITS_BITS` encrypted bits, and a DLEQ proof shows that the
+//!   recomposed remainder plus `spent` plus `c` minus `budget` encrypts zero.
+//!
+//! The ledger keeps the [`EncryptedCredits`] spent by each voter in the vote
+//! plan, and the voter keeps the corresponding [`CreditsOpening`] to prove
+//! the following allocations.
+
+use crate::committee::ElectionPublicKey;
+use crate::cryptography::{BinaryChoiceZkp, Ciphertext, DleqZkp, PublicKey};
+use crate::encrypted_vote::{
+    Ballot, BallotVerificationError, EncryptedVote, ProofOfCorrectVote, Vote,
+};
+use crate::tally::Crs;
+use crate::{GroupElement, Scalar};
+use chain_core::packer::Codec;
+use chain_core::property::ReadError;
+use rand_core::{CryptoRng, RngCore};
+
+/// Number of bits of the voting weight of a quadratic ballot
+pub const WEIGHT_BITS: usize = 32;
+
+/// Number of bits of the credits of a voter
+pub const CREDITS_BITS: usize = 64;
+
+/// Credits spent by a voter in a vote plan, encrypted under the election
+/// public key. The voter did not spend any credits yet if it is
+/// `Ciphertext::zero()`.
+pub type EncryptedCredits = Ciphertext;
+
+/// Opening of the [`EncryptedCredits`] of a voter: the amount of credits
+/// spent and the randomness of their encryption.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CreditsOpening {
+    credits: u64,
+    randomness: Scalar,
+}
+
+/// An encrypted value along with the proof binding it to the rest of the
+/// allocation.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct ProvenCiphertext {
+    ciphertext: Ciphertext,
+    proof: BinaryChoiceZkp,
+}
+
+/// Proof that a quadratic ballot allocates `w^2` credits, out of the budget
+/// of the voter, to a vote of weight `w`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CreditAllocation {
+    weighted_vote: Vec<ProvenCiphertext>,
+    weight_bits: Vec<ProvenCiphertext>,
+    credits: Vec<ProvenCiphertext>,
+    remainder_bits: Vec<ProvenCiphertext>,
+    budget_proof: DleqZkp,
+}
+
+/// Submitted quadratic ballot, whose vote and credit allocation are always
+/// verified.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct QuadraticBallot {
+    ballot: Ballot,
+    spent_credits: EncryptedCredits,
+    max_weight: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Not enough credits left: {requested} requested, {available} available")]
+pub struct InsufficientCredits {
+    pub requested: u64,
+    pub available: u64,
+}
+
+/// The voting weight obtained by allocating `credits`, i.e. the integer
+/// square root of `credits`.
+pub fn credits_weight(credits: u64) -> u64 {
+    let credits = credits as u128;
+    let mut weight = (credits as f64).sqrt() as u128;
+    while weight * weight > credits {
+        weight -= 1;
+    }
+    while (weight + 1) * (weight + 1) <= credits {
+        weight += 1;
+    }
+    weight as u64
+}
+
+/// The encryption of `1` with a null randomness
+fn encrypted_one() -> Ciphertext {
+    Ciphertext {
+        e1: GroupElement::zero(),
+        e2: GroupElement::generator(),
+    }
+}
+
+/// Encrypted value of the integer whose bits are encrypted in `bits`, least
+/// significant bit first
+fn recompose<'a>(bits: impl Iterator<Item = &'a ProvenCiphertext>) -> Ciphertext {
+    bits.enumerate().fold(Ciphertext::zero(), |acc, (j, bit)| {
+        acc + &bit.ciphertext * (1u64 << j)
+    })
+}
+
+fn recompose_randomness(randomness: &[Scalar]) -> Scalar {
+    randomness
+        .iter()
+        .enumerate()
+        .fold(Scalar::zero(), |acc, (j, r)| {
+            acc + Scalar::from_u64(1u64 << j) * r
+        })
+}
+
+fn bit_statements(bit: &Ciphertext) -> [[Ciphertext; 1]; 2] {
+    [[bit.clone()], [bit - encrypted_one()]]
+}
+
+fn product_statements(
+    bit: &Ciphertext,
+    product: &Ciphertext,
+    weight: &Ciphertext,
+) -> [[Ciphertext; 2]; 2] {
+    [
+        [bit.clone(), product.clone()],
+        [bit - encrypted_one(), product - weight],
+    ]
+}
+
+fn verify_bits(pk: &PublicKey, bits: &[ProvenCiphertext]) -> bool {
+    bits.iter().all(|bit| {
+        let [zero, one] = bit_statements(&bit.ciphertext);
+        bit.proof.verify(pk, [&zero[..], &one[..]])
+    })
+}
+
+fn verify_products<'a>(
+    pk: &PublicKey,
+    bits: impl Iterator<Item = &'a Ciphertext>,
+    products: &[ProvenCiphertext],
+    weight: &Ciphertext,
+) -> bool {
+    bits.zip(products.iter()).all(|(bit, product)| {
+        let [zero, one] = product_statements(bit, &product.ciphertext, weight);
+        product.proof.verify(pk, [&zero[..], &one[..]])
+    })
+}
+
+/// Encrypt the `bits` least significant bits of `value`, along with the proofs
+/// that they are bits. Returns the randomness used for each encryption.
+fn prove_bits<R: RngCore + CryptoRng>(
+    pk: &PublicKey,
+    value: u64,
+    bits: usize,
+    rng: &mut R,
+) -> (Vec<ProvenCiphertext>, Vec<Scalar>) {
+    (0..bits)
+        .map(|j| {
+            let bit = (value >> j) & 1 == 1;
+            let (ciphertext, r) = pk.encrypt_return_r(&Scalar::from(bit), rng);
+            let [zero, one] = bit_statements(&ciphertext);
+            let proof = BinaryChoiceZkp::generate(
+                pk,
+                [&zero[..], &one[..]],
+                bit as usize,
+                std::slice::from_ref(&r),
+                rng,
+            );
+            (ProvenCiphertext { ciphertext, proof }, r)
+        })
+        .unzip()
+}
+
+/// Encrypt `bit * weight`, along with the proof that it is the product of the
+/// encrypted `bit` and the encrypted `weight`. Returns the randomness used for
+/// the encryption.
+#[allow(clippy::too_many_arguments)]
+fn prove_product<R: RngCore + CryptoRng>(
+    pk: &PublicKey,
+    bit: bool,
+    encrypted_bit: &Ciphertext,
+    bit_randomness: &Scalar,
+    weight: u64,
+    encrypted_weight: &Ciphertext,
+    weight_randomness: &Scalar,
+    rng: &mut R,
+) -> (ProvenCiphertext, Scalar) {
+    let value = if bit { weight } else { 0 };
+    let (ciphertext, r) = pk.encrypt_return_r(&Scalar::from_u64(value), rng);
+    let [zero, one] = product_statements(encrypted_bit, &ciphertext, encrypted_weight);
+    let witness = if bit {
+        [bit_randomness.clone(), &r - weight_randomness]
+    } else {
+        [bit_randomness.clone(), r.clone()]
+    };
+    let proof = BinaryChoiceZkp::generate(pk, [&zero[..], &one[..]], bit as usize, &witness, rng);
+    (ProvenCiphertext { ciphertext, proof }, r)
+}
+
+impl CreditsOpening {
+    /// The opening of the credits of a voter who did not vote yet in the vote
+    /// plan, matching an `EncryptedCredits` of `Ciphertext::zero()`.
+    pub fn new() -> Self {
+        Self {
+            credits: 0,
+            randomness: Scalar::zero(),
+        }
+    }
+
+    /// The credits spent by the voter
+    pub fn credits(&self) -> u64 {
+        self.credits
+    }
+
+    pub const BYTES_LEN: usize = 8 + Scalar::BYTES_LEN;
+
+    pub fn to_bytes(&self) -> [u8; Self::BYTES_LEN] {
+        let mut output = [0u8; Self::BYTES_LEN];
+        output[..8].copy_from_slice(&self.credits.to_be_bytes());
+        output[8..].copy_from_slice(&self.randomness.to_bytes());
+        output
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != Self::BYTES_LEN {
+            return None;
+        }
+        let mut credits = [0u8; 8];
+        credits.copy_from_slice(&bytes[..8]);
+        Some(Self {
+            credits: u64::from_be_bytes(credits),
+            randomness: Scalar::from_bytes(&bytes[8..])?,
+        })
+    }
+}
+
+impl Default for CreditsOpening {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ElectionPublicKey {
+    /// Take a quadratic vote and encrypt it, allocating `credits` credits out of
+    /// the `budget` of the voter, who already spent the credits opened by `spent`.
+    /// Only the largest square lower or equal to `credits` is allocated, as
+    /// the remaining credits would not increase the weight of the vote.
+    ///
+    /// Returns the encrypted vote, the proof of correct vote, the proof of
+    /// the credit allocation, and the opening of the credits spent by the voter
+    /// once the ballot is accepted.
+    pub fn encrypt_and_prove_quadratic_vote<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        crs: &Crs,
+        vote: Vote,
+        credits: u64,
+        budget: u64,
+        spent: &CreditsOpening,
+    ) -> Result<
+        (
+            EncryptedVote,
+            ProofOfCorrectVote,
+            CreditAllocation,
+            CreditsOpening,
+        ),
+        InsufficientCredits,
+    > {
+        let weight = credits_weight(credits);
+        let allocated = weight * weight;
+        let available = budget.saturating_sub(spent.credits);
+        if allocated > available {
+            return Err(InsufficientCredits {
+                requested: credits,
+                available,
+            });
+        }
+        let pk = &self.0;
+
+        let vote_randomness: Vec<Scalar> = vote.iter().map(|_| Scalar::random(rng)).collect();
+        let encrypted_vote: EncryptedVote = vote
+            .iter()
+            .zip(vote_randomness.iter())
+            .map(|(v, r)| pk.encrypt_with_r(&Scalar::from(v), r))
+            .collect();
+        let proof =
+            ProofOfCorrectVote::generate(rng, crs, pk, &vote, &vote_randomness, &encrypted_vote);
+
+        let (weight_bits, weight_bits_randomness) = prove_bits(pk, weight, WEIGHT_BITS, rng);
+        let encrypted_weight = recompose(weight_bits.iter());
+        let weight_randomness = recompose_randomness(&weight_bits_randomness);
+
+        let weighted_vote = vote
+            .iter()
+            .zip(encrypted_vote.iter().zip(vote_randomness.iter()))
+            .map(|(v, (ciphertext, r))| {
+                prove_product(
+                    pk,
+                    v,
+                    ciphertext,
+                    r,
+                    weight,
+                    &encrypted_weight,
+                    &weight_randomness,
+                    rng,
+                )
+                .0
+            })
+            .collect();
+
+        let (credit_products, credits_randomness): (Vec<_>, Vec<_>) = weight_bits
+            .iter()
+            .zip(weight_bits_randomness.iter())
+            .enumerate()
+            .map(|(j, (bit, r))| {
+                prove_product(
+                    pk,
+                    (weight >> j) & 1 == 1,
+                    &bit.ciphertext,
+                    r,
+                    weight,
+                    &encrypted_weight,
+                    &weight_randomness,
+                    rng,
+                )
+            })
+            .unzip();
+        let encrypted_credits = recompose(credit_products.iter());
+        let credits_randomness = recompose_randomness(&credits_randomness);
+
+        let (remainder_bits, remainder_randomness) =
+            prove_bits(pk, available - allocated, CREDITS_BITS, rng);
+        let encrypted_remainder = recompose(remainder_bits.iter());
+        let remainder_randomness = recompose_randomness(&remainder_randomness);
+
+        let encrypted_spent =
+            pk.encrypt_with_r(&Scalar::from_u64(spent.credits), &spent.randomness);
+        let zero = Self::budget_statement(
+            &encrypted_remainder,
+            &encrypted_spent,
+            &encrypted_credits,
+            budget,
+        );
+        let budget_proof = DleqZkp::generate(
+            &GroupElement::generator(),
+            &pk.pk,
+            &zero.e1,
+            &zero.e2,
+            &(&remainder_randomness + &spent.randomness + &credits_randomness),
+            rng,
+        );
+
+        let opening = CreditsOpening {
+            credits: spent.credits + allocated,
+            randomness: &spent.randomness + &credits_randomness,
+        };
+        let allocation = CreditAllocation {
+            weighted_vote,
+            weight_bits,
+            credits: credit_products,
+            remainder_bits,
+            budget_proof,
+        };
+        Ok((encrypted_vote, proof, allocation, opening))
+    }
+
+    /// The ciphertext that encrypts zero if the budget is respected
+    fn budget_statement(
+        remainder: &Ciphertext,
+        spent: &Ciphertext,
+        credits: &Ciphertext,
+        budget: u64,
+    ) -> Ciphertext {
+        remainder + spent + credits - &encrypted_one() * budget
+    }
+}
+
+impl CreditAllocation {
+    /// Verify the allocation against the encrypted unit vector `vote`, and
+    /// return the credits spent by the voter including this allocation.
+    fn verify(
+        &self,
+        pk: &ElectionPublicKey,
+        vote: &[Ciphertext],
+        budget: u64,
+        spent: &EncryptedCredits,
+    ) -> Option<EncryptedCredits> {
+        if self.weighted_vote.len() != vote.len()
+            || self.weight_bits.len() != WEIGHT_BITS
+            || self.credits.len() != WEIGHT_BITS
+            || self.remainder_bits.len() != CREDITS_BITS
+        {
+            return None;
+        }
+        let raw_pk = &pk.0;
+
+        if !verify_bits(raw_pk, &self.weight_bits) || !verify_bits(raw_pk, &self.remainder_bits) {
+            return None;
+        }
+
+        let encrypted_weight = recompose(self.weight_bits.iter());
+        if !verify_products(raw_pk, vote.iter(), &self.weighted_vote, &encrypted_weight)
+            || !verify_products(
+                raw_pk,
+                self.weight_bits.iter().map(|bit| &bit.ciphertext),
+                &self.credits,
+                &encrypted_weight,
+            )
+        {
+            return None;
+        }
+
+        let encrypted_credits = recompose(self.credits.iter());
+        let zero = ElectionPublicKey::budget_statement(
+            &recompose(self.remainder_bits.iter()),
+            spent,
+            &encrypted_credits,
+            budget,
+        );
+        if !self
+            .budget_proof
+            .verify(&GroupElement::generator(), &raw_pk.pk, &zero.e1, &zero.e2)
+        {
+            return None;
+        }
+
+        Some(spent + encrypted_credits)
+    }
+
+    /// The number of options of the vote
+    pub fn options(&self) -> usize {
+        self.weighted_vote.len()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![self.weighted_vote.len() as u8];
+        for proven in self
+            .weighted_vote
+            .iter()
+            .chain(self.weight_bits.iter())
+            .chain(self.credits.iter())
+            .chain(self.remainder_bits.iter())
+        {
+            bytes.extend_from_slice(&proven.ciphertext.to_bytes());
+            bytes.extend_from_slice(&proven.proof.to_bytes());
+        }
+        bytes.extend_from_slice(&self.budget_proof.to_bytes());
+        bytes
+    }
+
+    /// Try to read a `CreditAllocation` from a buffer
+    pub fn from_buffer(codec: &mut Codec<&[u8]>) -> Result<Self, ReadError> {
+        fn read_proven(
+            codec: &mut Codec<&[u8]>,
+            count: usize,
+            statements: usize,
+        ) -> Result<Vec<ProvenCiphertext>, ReadError> {
+            (0..count)
+                .map(|_| {
+                    let ciphertext =
+                        Ciphertext::from_bytes(codec.get_slice(Ciphertext::BYTES_LEN)?)
+                            .ok_or_else(|| {
+                                ReadError::StructureInvalid(
+                                    "Invalid encoded ciphertext".to_string(),
+                                )
+                            })?;
+                    let proof = BinaryChoiceZkp::from_bytes(
+                        codec.get_slice(BinaryChoiceZkp::bytes_len(statements))?,
+                        statements,
+                    )
+                    .ok_or_else(|| {
+                        ReadError::StructureInvalid("Invalid binary choice proof".to_string())
+                    })?;
+                    Ok(ProvenCiphertext { ciphertext, proof })
+                })
+                .collect()
+        }
+
+        let options = codec.get_u8()? as usize;
+        let weighted_vote = read_proven(codec, options, 2)?;
+        let weight_bits = read_proven(codec, WEIGHT_BITS, 1)?;
+        let credits = read_proven(codec, WEIGHT_BITS, 2)?;
+        let remainder_bits = read_proven(codec, CREDITS_BITS, 1)?;
+        let budget_proof = DleqZkp::from_bytes(codec.get_slice(DleqZkp::BYTES_LEN)?)
+            .ok_or_else(|| ReadError::StructureInvalid("Invalid budget proof".to_string()))?;
+
+        Ok(Self {
+            weighted_vote,
+            weight_bits,
+            credits,
+            remainder_bits,
+            budget_proof,
+        })
+    }
+}
+
+impl QuadraticBallot {
+    /// Verify the encrypted `vote` with its `proof`, and the credit
+    /// `allocation` of the voter with the given `budget`, who already spent
+    /// the encrypted credits `spent` in the vote plan.
+    pub fn try_from_vote_and_proofs(
+        vote: EncryptedVote,
+        proof: &ProofOfCorrectVote,
+        allocation: &CreditAllocation,
+        crs: &Crs,
+        pk: &ElectionPublicKey,
+        budget: u64,
+        spent: &EncryptedCredits,
+    ) -> Result<Self, BallotVerificationError> {
+        let ballot = Ballot::try_from_vote_and_proof(vote, proof, crs, pk)?;
+        let spent_credits = allocation
+            .verify(pk, ballot.vote(), budget, spent)
+            .ok_or(BallotVerificationError)?;
+
+        let weighted_vote = allocation
+            .weighted_vote
+            .iter()
+            .map(|proven| proven.ciphertext.clone())
+            .collect();
+        Ok(Self {
+            ballot: Ballot::from_verified_vote(weighted_vote, *ballot.fingerprint()),
+            spent_credits,
+            max_weight: credits_weight(budget),
+        })
+    }
+
+    /// The vote multiplied by the (encrypted) weight of the ballot
+    pub fn weighted_ballot(&self) -> &Ballot {
+        &self.ballot
+    }
+
+    /// The credits spent by the voter in the vote plan, including this ballot
+    pub fn spent_credits(&self) -> &EncryptedCredits {
+        &self.spent_credits
+    }
+
+    /// Upper bound of the weight of the ballot
+    pub fn max_weight(&self) -> u64 {
+        self.max_weight
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        committee::{MemberCommunicationKey, MemberState},
+        EncryptedTally, TallyOptimizationTable,
+    };
+    use rand_chacha::ChaCha20Rng;
+    use rand_core::SeedableRng;
+
+    fn setup(rng: &mut ChaCha20Rng) -> (MemberState, ElectionPublicKey, Crs) {
+        let h = Crs::from_hash(b"quadratic vote plan");
+        let mc = [MemberCommunicationKey::new(rng).to_public()];
+        let member = MemberState::new(rng, 1, &h, &mc, 0);
+        let ek = ElectionPublicKey::from_participants(&[member.public_key()]);
+        (member, ek, h)
+    }
+
+    #[test]
+    fn weight_of_credits() {
+        assert_eq!(credits_weight(0), 0);
+        assert_eq!(credits_weight(1), 1);
+        assert_eq!(credits_weight(8), 2);
+        assert_eq!(credits_weight(9), 3);
+        assert_eq!(credits_weight(u64::MAX), u32::MAX as u64);
+    }
+
+    #[test]
+    fn quadratic_votes_tally() {
+        let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+        let (member, ek, h) = setup(&mut rng);
+        let budget = 20;
+        let options = 3;
+
+        let mut tallies = vec![EncryptedTally::new(options, ek.clone(), h.clone()); 2];
+        let mut spent = EncryptedCredits::zero();
+        let mut opening = CreditsOpening::new();
+
+        // 9 credits on the first proposal and 10 on the second one, of which
+        // only 9 are allocated
+        for (tally, credits) in tallies.iter_mut().zip([9, 10]) {
+            let (vote, proof, allocation, new_opening) = ek
+                .encrypt_and_prove_quadratic_vote(
+                    &mut rng,
+                    &h,
+                    Vote::new(options, 1).unwrap(),
+                    credits,
+                    budget,
+                    &opening,
+                )
+                .unwrap();
+            let ballot = QuadraticBallot::try_from_vote_and_proofs(
+                vote,
+                &proof,
+                &allocation,
+                &h,
+                &ek,
+                budget,
+                &spent,
+            )
+            .unwrap();
+            tally.add_quadratic(&ballot);
+            spent = ballot.spent_credits().clone();
+            opening = new_opening;
+        }
+        assert_eq!(opening.credits(), 18);
+
+        assert_eq!(
+            ek.encrypt_and_prove_quadratic_vote(
+                &mut rng,
+                &h,
+                Vote::new(options, 0).unwrap(),
+                4,
+                budget,
+                &opening,
+            )
+            .unwrap_err(),
+            InsufficientCredits {
+                requested: 4,
+                available: 2
+            }
+        );
+
+        let table = TallyOptimizationTable::generate_with_balance(
+            credits_weight(budget).try_into().unwrap(),
+            1.try_into().unwrap(),
+        );
+        for tally in tallies {
+            let shares = vec![tally.partial_decrypt(&mut rng, member.secret_key())];
+            let result = tally
+                .validate_partial_decryptions(&[member.public_key()], &shares)
+                .unwrap()
+                .decrypt_tally(&table)
+                .unwrap();
+            assert_eq!(result.votes, vec![0, 3, 0]);
+        }
+    }
+
+    #[test]
+    fn over_budget_allocation_fails() {
+        let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+        let (_, ek, h) = setup(&mut rng);
+
+        let (vote, proof, allocation, opening) = ek
+            .encrypt_and_prove_quadratic_vote(
+                &mut rng,
+                &h,
+                Vote::new(2, 0).unwrap(),
+                16,
+                20,
+                &CreditsOpening::new(),
+            )
+            .unwrap();
+
+        // the budget of the voter is lower than the one the proof was built for
+        assert!(QuadraticBallot::try_from_vote_and_proofs(
+            vote.clone(),
+            &proof,
+            &allocation,
+            &h,
+            &ek,
+            10,
+            &EncryptedCredits::zero(),
+        )
+        .is_err());
+
+        // the voter already spent credits
+        let spent = ek.0.encrypt(&Scalar::from_u64(10), &mut rng);
+        assert!(QuadraticBallot::try_from_vote_and_proofs(
+            vote,
+            &proof,
+            &allocation,
+            &h,
+            &ek,
+            20,
+            &spent,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn serialisation() {
+        let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+        let (_, ek, h) = setup(&mut rng);
+
+        let (vote, proof, allocation, _) = ek
+            .encrypt_and_prove_quadratic_vote(
+                &mut rng,
+                &h,
+                Vote::new(4, 2).unwrap(),
+                7,
+                100,
+                &CreditsOpening::new(),
+            )
+            .unwrap();
+
+        let bytes = allocation.to_bytes();
+        let deserialised =
+            CreditAllocation::from_buffer(&mut Codec::new(bytes.as_slice())).unwrap();
+        assert_eq!(deserialised, allocation);
+        assert_eq!(
+            CreditsOpening::from_bytes(&opening.to_bytes()),
+            Some(opening)
+        );
+        assert!(QuadraticBallot::try_from_vote_and_proofs(
+            vote,
+            &proof,
+            &deserialised,
+            &h,
+            &ek,
+            100,
+            &EncryptedCredits::zero(),
+        )
+        .is_ok());
+    }
+}
@@ -4,11 +4,12 @@ use crate::{
     committee::*,
     cryptography::{Ciphertext, CorrectShareGenerationZkp},
     encrypted_vote::Ballot,
-    math::babystep::baby_step_giant_step,
+    math::{babystep::baby_step_giant_step, polynomial::lagrange_basis},
+    quadratic::QuadraticBallot,
     TallyOptimizationTable,
 };
 
-use crate::GroupElement;
+use crate::{GroupElement, Scalar};
 use cryptoxide::blake2b::Blake2b;
 use cryptoxide::digest::Digest;
 use rand_core::{CryptoRng, RngCore};
@@ -54,9 +55,10 @@ pub struct EncryptedTally {
 }
 
 /// `TallyDecryptShare` contains one decryption share per existing option. All committee
-/// members (todo: this will change once DKG is completed)
-/// need to submit a `TallyDecryptShare` in order to successfully decrypt
-/// the `EncryptedTally`.
+/// members need to submit a `TallyDecryptShare` in order to successfully decrypt
+/// the `EncryptedTally`, unless the committee keys were generated with the
+/// distributed key generation (see [`crate::dkg`]), in which case any `threshold`
+/// members are enough.
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub struct TallyDecryptShare {
     elements: Vec<ProvenDecryptShare>,
@@ -75,6 +77,8 @@ pub struct TallyDecryptShare {
 pub struct ValidatedTally {
     r: Vec<Ciphertext>,
     decrypt_shares: Vec<TallyDecryptShare>,
+    /// Lagrange coefficients of the shares, for threshold decryption
+    lagrange_coefficients: Option<Vec<Scalar>>,
     max_stake: u64,
 }
 
@@ -132,6 +136,22 @@ impl EncryptedTally {
         self.max_stake += weight;
     }
 
+    /// Add a submitted quadratic `ballot` to the tally. The weight of the
+    /// ballot is already encrypted in its vote, and only an upper bound of it
+    /// is known.
+    ///
+    /// As with `add`, trying to add a ballot validated for a different election
+    /// or with a different number of options will result in a panic.
+    pub fn add_quadratic(&mut self, ballot: &QuadraticBallot) {
+        let weighted_ballot = ballot.weighted_ballot();
+        assert_eq!(weighted_ballot.vote().len(), self.r.len());
+        assert_eq!(weighted_ballot.fingerprint(), &self.fingerprint);
+        for (ri, ci) in self.r.iter_mut().zip(weighted_ballot.vote().iter()) {
+            *ri = &*ri + ci;
+        }
+        self.max_stake += ballot.max_weight();
+    }
+
     /// Given a single committee member's `secret_key`, returns a partial decryption of
     /// the `EncryptedTally`
     pub fn partial_decrypt<R: RngCore + CryptoRng>(
@@ -175,6 +195,56 @@ impl EncryptedTally {
         Ok(ValidatedTally {
             r: self.r.clone(),
             decrypt_shares: decrypt_shares.to_vec(),
+            lagrange_coefficients: None,
+            max_stake: self.max_stake,
+        })
+    }
+
+    /// Validates the `decrypt_shares` of a committee whose keys were generated
+    /// with the distributed key generation. Each share comes with the index of
+    /// the member who produced it, and is verified against the corresponding
+    /// entry of `member_pks`. Invalid and duplicated shares are ignored, and a
+    /// `DecryptionError` is returned if less than `threshold` valid shares remain.
+    pub fn validate_threshold_partial_decryptions(
+        &self,
+        member_pks: &[MemberPublicKey],
+        threshold: usize,
+        decrypt_shares: &[(usize, TallyDecryptShare)],
+    ) -> Result<ValidatedTally, DecryptionError> {
+        let mut indices = Vec::with_capacity(threshold);
+        let mut shares = Vec::with_capacity(threshold);
+        for (index, decrypt_share) in decrypt_shares {
+            if indices.len() == threshold {
+                break;
+            }
+            let pk = match member_pks.get(*index) {
+                Some(pk) => pk,
+                None => continue,
+            };
+            if indices.contains(index) || !decrypt_share.verify(self, pk) {
+                continue;
+            }
+            indices.push(*index);
+            shares.push(decrypt_share.clone());
+        }
+        if threshold == 0 || indices.len() < threshold {
+            return Err(DecryptionError);
+        }
+
+        // committee members are 1-indexed in the key generation polynomials
+        let xs = indices
+            .iter()
+            .map(|index| Scalar::from_u64(*index as u64 + 1))
+            .collect::<Vec<_>>();
+        let lagrange_coefficients = xs
+            .iter()
+            .map(|x| lagrange_basis(x, &xs, &Scalar::zero()))
+            .collect();
+
+        Ok(ValidatedTally {
+            r: self.r.clone(),
+            decrypt_shares: shares,
+            lagrange_coefficients: Some(lagrange_coefficients),
             max_stake: self.max_stake,
         })
     }
@@ -237,8 +307,15 @@ impl ValidatedTally {
     // `decrypt_tally`.
     fn decrypt(&self) -> Vec<GroupElement> {
         let state: Vec<GroupElement> = self.r.iter().map(|c| c.e2.clone()).collect();
-        let ris = (0..state.len())
-            .map(|i| GroupElement::sum(self.decrypt_shares.iter().map(|ds| &ds.elements[i].r1)));
+        let ris = (0..state.len()).map(|i| match &self.lagrange_coefficients {
+            None => GroupElement::sum(self.decrypt_shares.iter().map(|ds| &ds.elements[i].r1)),
+            Some(coefficients) => GroupElement::vartime_multiscalar_multiplication(
+                coefficients.iter().cloned(),
+                self.decrypt_shares
+                    .iter()
+                    .map(|ds| ds.elements[i].r1.clone()),
+            ),
+        });
 
         state
             .iter()
@@ -369,12 +446,34 @@ impl Tally {
         pks: &[MemberPublicKey],
         decrypt_shares: &[TallyDecryptShare],
     ) -> bool {
-        let validated_decryptions =
-            match encrypted_tally.validate_partial_decryptions(pks, decrypt_shares) {
-                Ok(dec) => dec,
-                Err(_) => return false,
-            };
+        match encrypted_tally.validate_partial_decryptions(pks, decrypt_shares) {
+            Ok(validated_decryptions) => self.verify_decryption(&validated_decryptions),
+            Err(_) => false,
+        }
+    }
+
+    /// Verifies that the decrypted tally was correctly obtained from the given
+    /// `EncryptedTally` and the `TallyDecryptShare`s of at least `threshold`
+    /// members of a committee whose keys were generated with the distributed
+    /// key generation.
+    pub fn verify_threshold(
+        &self,
+        encrypted_tally: &EncryptedTally,
+        member_pks: &[MemberPublicKey],
+        threshold: usize,
+        decrypt_shares: &[(usize, TallyDecryptShare)],
+    ) -> bool {
+        match encrypted_tally.validate_threshold_partial_decryptions(
+            member_pks,
+            threshold,
+            decrypt_shares,
+        ) {
+            Ok(validated_decryptions) => self.verify_decryption(&validated_decryptions),
+            Err(_) => false,
+        }
+    }
 
+    fn verify_decryption(&self, validated_decryptions: &ValidatedTally) -> bool {
         let r_results = validated_decryptions.decrypt();
         let gen = GroupElement::generator();
         for (i, &w) in self.votes.iter().enumerate() {
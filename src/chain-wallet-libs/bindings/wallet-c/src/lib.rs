@@ -1,4 +1,5 @@
 pub mod settings;
+pub mod sync;
 pub mod time;
 use std::{
     ffi::{CStr, CString},
@@ -9,13 +10,18 @@ use wallet_core::c::{
     fragment::{fragment_delete, fragment_from_raw, fragment_id},
     spending_counters_delete, symmetric_cipher_decrypt,
     time::BlockDate,
-    vote, wallet_delete_error, wallet_delete_proposal, wallet_delete_settings,
-    wallet_delete_wallet, wallet_id, wallet_import_keys, wallet_set_state,
-    wallet_spending_counters, wallet_total_value, wallet_vote_cast, SpendingCounters,
-    TransactionOut,
+    vote, wallet_add_utxo_keystore, wallet_add_utxo_root_key, wallet_convert,
+    wallet_convert_ignored, wallet_convert_transactions_get, wallet_convert_transactions_size,
+    wallet_delete_conversion, wallet_delete_error, wallet_delete_proposal, wallet_delete_settings,
+    wallet_delete_wallet, wallet_export_keystore, wallet_id, wallet_import_keys,
+    wallet_import_keystore, wallet_keystore_change_password, wallet_keystore_create_root_key,
+    wallet_retrieve_funds, wallet_set_state, wallet_spending_counters, wallet_total_value,
+    wallet_utxo_value, wallet_vote_cast, SpendingCounters, TransactionOut,
 };
+pub use wallet_core::RecoveryScheme;
 use wallet_core::{
-    Error as ErrorRust, Fragment as FragmentRust, Proposal as ProposalRust, Wallet as WalletRust,
+    Conversion as ConversionRust, Error as ErrorRust, Fragment as FragmentRust,
+    Proposal as ProposalRust, Wallet as WalletRust,
 };
 
 #[repr(C)]
@@ -27,6 +33,8 @@ pub struct Proposal {}
 #[repr(C)]
 pub struct Fragment;
 #[repr(C)]
+pub struct Conversion {}
+#[repr(C)]
 pub struct Error {}
 
 #[repr(C)]
@@ -36,6 +44,7 @@ pub type WalletPtr = *mut Wallet;
 pub type SettingsPtr = *mut Settings;
 pub type ProposalPtr = *mut Proposal;
 pub type FragmentPtr = *mut Fragment;
+pub type ConversionPtr = *mut Conversion;
 pub type ErrorPtr = *mut Error;
 pub type EncryptingVoteKeyPtr = *mut EncryptingVoteKey;
 
@@ -77,6 +86,224 @@ pub unsafe extern "C" fn iohk_jormungandr_wallet_import_keys(
     r.into_c_api() as ErrorPtr
 }
 
+/// recover a wallet from a keystore holding the key of its account
+///
+/// The recovered wallet will be returned in `wallet_out`.
+///
+/// # parameters
+///
+/// * keystore: the JSON document of the keystore
+/// * keystore_length: the number of bytes of the keystore
+/// * password: byte buffer with the password of the keystore
+/// * password_length: length of the password buffer
+/// * wallet_out: the recovered wallet
+///
+/// # Errors
+///
+/// On error the function returns a `ErrorPtr`. On success `NULL` is returned.
+/// The `ErrorPtr` can then be observed to gathered details of the error.
+/// Don't forget to call `iohk_jormungandr_wallet_delete_error` to free
+/// the `ErrorPtr` from memory and avoid memory leaks.
+///
+/// * this function may fail if one of the pointers is null;
+/// * the keystore is malformed or does not hold the key of an account;
+/// * the password is not the one of the keystore.
+///
+/// # Safety
+///
+/// This function dereference raw pointers. Even though
+/// the function checks if the pointers are null. Mind not to put random values
+/// in or you may see unexpected behaviors
+///
+#[no_mangle]
+pub unsafe extern "C" fn iohk_jormungandr_wallet_import_keystore(
+    keystore: *const u8,
+    keystore_length: usize,
+    password: *const u8,
+    password_length: usize,
+    wallet_out: *mut WalletPtr,
+) -> ErrorPtr {
+    let r = wallet_import_keystore(
+        keystore,
+        keystore_length,
+        password,
+        password_length,
+        wallet_out as *mut *mut WalletRust,
+    );
+
+    r.into_c_api() as ErrorPtr
+}
+
+/// encrypt the key of the wallet's account with a password in a new keystore
+///
+/// The JSON document of the keystore is returned in `keystore_out`. Don't
+/// forget to call `iohk_jormungandr_wallet_delete_buffer` to free it.
+///
+/// # Errors
+///
+/// On error the function returns a `ErrorPtr`. On success `NULL` is returned.
+/// The `ErrorPtr` can then be observed to gathered details of the error.
+/// Don't forget to call `iohk_jormungandr_wallet_delete_error` to free
+/// the `ErrorPtr` from memory and avoid memory leaks.
+///
+/// * this function may fail if one of the pointers is null.
+///
+/// # Safety
+///
+/// This function dereference raw pointers. Even though
+/// the function checks if the pointers are null. Mind not to put random values
+/// in or you may see unexpected behaviors
+///
+#[no_mangle]
+pub unsafe extern "C" fn iohk_jormungandr_wallet_export_keystore(
+    wallet: WalletPtr,
+    password: *const u8,
+    password_length: usize,
+    keystore_out: *mut *const u8,
+    keystore_out_length: *mut usize,
+) -> ErrorPtr {
+    let r = wallet_export_keystore(
+        wallet as *mut WalletRust,
+        password,
+        password_length,
+        keystore_out,
+        keystore_out_length,
+    );
+
+    r.into_c_api() as ErrorPtr
+}
+
+/// add the root key of a legacy wallet from a keystore, see
+/// `iohk_jormungandr_wallet_add_utxo_root_key`
+///
+/// # Errors
+///
+/// On error the function returns a `ErrorPtr`. On success `NULL` is returned.
+/// The `ErrorPtr` can then be observed to gathered details of the error.
+/// Don't forget to call `iohk_jormungandr_wallet_delete_error` to free
+/// the `ErrorPtr` from memory and avoid memory leaks.
+///
+/// * this function may fail if one of the pointers is null;
+/// * the keystore is malformed or does not hold the root key of a legacy
+///   wallet;
+/// * the password is not the one of the keystore.
+///
+/// # Safety
+///
+/// This function dereference raw pointers. Even though
+/// the function checks if the pointers are null. Mind not to put random values
+/// in or you may see unexpected behaviors
+///
+#[no_mangle]
+pub unsafe extern "C" fn iohk_jormungandr_wallet_add_utxo_keystore(
+    wallet: WalletPtr,
+    keystore: *const u8,
+    keystore_length: usize,
+    password: *const u8,
+    password_length: usize,
+) -> ErrorPtr {
+    let r = wallet_add_utxo_keystore(
+        wallet as *mut WalletRust,
+        keystore,
+        keystore_length,
+        password,
+        password_length,
+    );
+
+    r.into_c_api() as ErrorPtr
+}
+
+/// encrypt the 96 bytes root key of a legacy wallet with a password in a new
+/// keystore
+///
+/// The JSON document of the keystore is returned in `keystore_out`. Don't
+/// forget to call `iohk_jormungandr_wallet_delete_buffer` to free it.
+///
+/// # Errors
+///
+/// On error the function returns a `ErrorPtr`. On success `NULL` is returned.
+/// The `ErrorPtr` can then be observed to gathered details of the error.
+/// Don't forget to call `iohk_jormungandr_wallet_delete_error` to free
+/// the `ErrorPtr` from memory and avoid memory leaks.
+///
+/// * this function may fail if one of the pointers is null;
+/// * the `root_key` is not a valid extended private key.
+///
+/// # Safety
+///
+/// This function dereference raw pointers. Even though
+/// the function checks if the pointers are null. Mind not to put random values
+/// in or you may see unexpected behaviors
+///
+#[no_mangle]
+pub unsafe extern "C" fn iohk_jormungandr_wallet_keystore_create_root_key(
+    root_key: *const u8,
+    scheme: RecoveryScheme,
+    password: *const u8,
+    password_length: usize,
+    keystore_out: *mut *const u8,
+    keystore_out_length: *mut usize,
+) -> ErrorPtr {
+    let r = wallet_keystore_create_root_key(
+        root_key,
+        scheme,
+        password,
+        password_length,
+        keystore_out,
+        keystore_out_length,
+    );
+
+    r.into_c_api() as ErrorPtr
+}
+
+/// encrypt the key of a keystore with a new password
+///
+/// The JSON document of the new keystore is returned in `keystore_out`.
+/// Don't forget to call `iohk_jormungandr_wallet_delete_buffer` to free it.
+///
+/// # Errors
+///
+/// On error the function returns a `ErrorPtr`. On success `NULL` is returned.
+/// The `ErrorPtr` can then be observed to gathered details of the error.
+/// Don't forget to call `iohk_jormungandr_wallet_delete_error` to free
+/// the `ErrorPtr` from memory and avoid memory leaks.
+///
+/// * this function may fail if one of the pointers is null;
+/// * the keystore is malformed;
+/// * the `old_password` is not the one of the keystore.
+///
+/// # Safety
+///
+/// This function dereference raw pointers. Even though
+/// the function checks if the pointers are null. Mind not to put random values
+/// in or you may see unexpected behaviors
+///
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn iohk_jormungandr_wallet_keystore_change_password(
+    keystore: *const u8,
+    keystore_length: usize,
+    old_password: *const u8,
+    old_password_length: usize,
+    new_password: *const u8,
+    new_password_length: usize,
+    keystore_out: *mut *const u8,
+    keystore_out_length: *mut usize,
+) -> ErrorPtr {
+    let r = wallet_keystore_change_password(
+        keystore,
+        keystore_length,
+        old_password,
+        old_password_length,
+        new_password,
+        new_password_length,
+        keystore_out,
+        keystore_out_length,
+    );
+
+    r.into_c_api() as ErrorPtr
+}
+
 /// get the wallet id
 ///
 /// This ID is the identifier to use against the blockchain/explorer to retrieve
@@ -172,6 +399,238 @@ pub unsafe extern "C" fn iohk_jormungandr_wallet_total_value(
     r.into_c_api() as ErrorPtr
 }
 
+/// add the root key of a legacy wallet (Daedalus or Yoroi) to the wallet
+///
+/// The funds declared in the block0 at the addresses of the legacy wallet are
+/// then found by `iohk_jormungandr_wallet_retrieve_funds` and can be moved to
+/// the wallet's account with `iohk_jormungandr_wallet_convert`.
+///
+/// # parameters
+///
+/// * wallet: the recovered wallet (see recover function);
+/// * root_key: the extended private key at the root of the legacy wallet in
+///     the form of a 96 bytes array;
+/// * scheme: the derivation scheme of the legacy wallet's addresses.
+///
+/// # Errors
+///
+/// On error the function returns a `ErrorPtr`. On success `NULL` is returned.
+/// The `ErrorPtr` can then be observed to gathered details of the error.
+/// Don't forget to call `iohk_jormungandr_wallet_delete_error` to free
+/// the `ErrorPtr` from memory and avoid memory leaks.
+///
+/// * this function may fail if the wallet or root_key pointer is null;
+/// * the `root_key` is not a valid extended private key.
+///
+/// # Safety
+///
+/// This function dereference raw pointers. Even though
+/// the function checks if the pointers are null. Mind not to put random values
+/// in or you may see unexpected behaviors
+///
+#[no_mangle]
+pub unsafe extern "C" fn iohk_jormungandr_wallet_add_utxo_root_key(
+    wallet: WalletPtr,
+    root_key: *const u8,
+    scheme: RecoveryScheme,
+) -> ErrorPtr {
+    wallet_add_utxo_root_key(wallet as *mut WalletRust, root_key, scheme).into_c_api() as ErrorPtr
+}
+
+/// retrieve the funds of the wallet from the block0
+///
+/// The settings of the blockchain are returned in `settings_out`. Don't
+/// forget to call `iohk_jormungandr_wallet_delete_settings` to free them.
+///
+/// # Errors
+///
+/// On error the function returns a `ErrorPtr`. On success `NULL` is returned.
+/// The `ErrorPtr` can then be observed to gathered details of the error.
+/// Don't forget to call `iohk_jormungandr_wallet_delete_error` to free
+/// the `ErrorPtr` from memory and avoid memory leaks.
+///
+/// * this function may fail if one of the pointers is null;
+/// * the block0 is not valid.
+///
+/// # Safety
+///
+/// This function dereference raw pointers. Even though
+/// the function checks if the pointers are null. Mind not to put random values
+/// in or you may see unexpected behaviors
+///
+#[no_mangle]
+pub unsafe extern "C" fn iohk_jormungandr_wallet_retrieve_funds(
+    wallet: WalletPtr,
+    block0: *const u8,
+    block0_length: usize,
+    settings_out: *mut SettingsPtr,
+) -> ErrorPtr {
+    let r = wallet_retrieve_funds(
+        wallet as *mut WalletRust,
+        block0,
+        block0_length,
+        settings_out as *mut *mut SettingsRust,
+    );
+
+    r.into_c_api() as ErrorPtr
+}
+
+/// get the value of the legacy wallets that is not converted to the account
+/// yet
+///
+/// After calling this function the results is returned in the `value_out`.
+///
+/// # Errors
+///
+/// * this function may fail if the wallet pointer is null;
+///
+/// On error the function returns a `ErrorPtr`. On success `NULL` is returned.
+/// The `ErrorPtr` can then be observed to gathered details of the error.
+/// Don't forget to call `iohk_jormungandr_wallet_delete_error` to free
+/// the `ErrorPtr` from memory and avoid memory leaks.
+///
+/// If the `value_out` pointer is null, this function does nothing
+///
+/// # Safety
+///
+/// This function dereference raw pointers. Even though
+/// the function checks if the pointers are null. Mind not to put random values
+/// in or you may see unexpected behaviors
+///
+#[no_mangle]
+pub unsafe extern "C" fn iohk_jormungandr_wallet_utxo_value(
+    wallet: WalletPtr,
+    value_out: *mut u64,
+) -> ErrorPtr {
+    wallet_utxo_value(wallet as *mut WalletRust, value_out).into_c_api() as ErrorPtr
+}
+
+/// build the transactions moving the funds of the legacy wallets to the
+/// wallet's account
+///
+/// The conversion is returned in `conversion_out`. Use
+/// `iohk_jormungandr_wallet_convert_transactions_size` and
+/// `iohk_jormungandr_wallet_convert_transactions_get` to get the
+/// transactions to send to the blockchain and don't forget to call
+/// `iohk_jormungandr_wallet_delete_conversion` to free the conversion.
+///
+/// # Errors
+///
+/// On error the function returns a `ErrorPtr`. On success `NULL` is returned.
+/// The `ErrorPtr` can then be observed to gathered details of the error.
+/// Don't forget to call `iohk_jormungandr_wallet_delete_error` to free
+/// the `ErrorPtr` from memory and avoid memory leaks.
+///
+/// * this function may fail if one of the pointers is null;
+/// * the transactions can't be built.
+///
+/// # Safety
+///
+/// This function dereference raw pointers. Even though
+/// the function checks if the pointers are null. Mind not to put random values
+/// in or you may see unexpected behaviors
+///
+#[no_mangle]
+pub unsafe extern "C" fn iohk_jormungandr_wallet_convert(
+    wallet: WalletPtr,
+    settings: SettingsPtr,
+    valid_until: BlockDate,
+    conversion_out: *mut ConversionPtr,
+) -> ErrorPtr {
+    let r = wallet_convert(
+        wallet as *mut WalletRust,
+        settings as *mut SettingsRust,
+        valid_until,
+        conversion_out as *mut *mut ConversionRust,
+    );
+
+    r.into_c_api() as ErrorPtr
+}
+
+/// get the number of transactions of the conversion
+///
+/// # Safety
+///
+/// This function dereference raw pointers. Even though
+/// the function checks if the pointers are null. Mind not to put random values
+/// in or you may see unexpected behaviors
+///
+#[no_mangle]
+pub unsafe extern "C" fn iohk_jormungandr_wallet_convert_transactions_size(
+    conversion: ConversionPtr,
+) -> usize {
+    wallet_convert_transactions_size(conversion as *mut ConversionRust)
+}
+
+/// get the transaction at the given index of the conversion
+///
+/// The returned buffer is owned by the conversion, it is valid until the
+/// conversion is deleted.
+///
+/// # Errors
+///
+/// On error the function returns a `ErrorPtr`. On success `NULL` is returned.
+/// The `ErrorPtr` can then be observed to gathered details of the error.
+/// Don't forget to call `iohk_jormungandr_wallet_delete_error` to free
+/// the `ErrorPtr` from memory and avoid memory leaks.
+///
+/// * this function may fail if one of the pointers is null;
+/// * the index is out of bound.
+///
+/// # Safety
+///
+/// This function dereference raw pointers. Even though
+/// the function checks if the pointers are null. Mind not to put random values
+/// in or you may see unexpected behaviors
+///
+#[no_mangle]
+pub unsafe extern "C" fn iohk_jormungandr_wallet_convert_transactions_get(
+    conversion: ConversionPtr,
+    index: usize,
+    transaction_out: *mut *const u8,
+    transaction_size: *mut usize,
+) -> ErrorPtr {
+    let r = wallet_convert_transactions_get(
+        conversion as *mut ConversionRust,
+        index,
+        transaction_out,
+        transaction_size,
+    );
+
+    r.into_c_api() as ErrorPtr
+}
+
+/// get the total value and the number of the UTxOs ignored by the
+/// conversion, they are not worth the fee of their input
+///
+/// # Errors
+///
+/// On error the function returns a `ErrorPtr`. On success `NULL` is returned.
+/// The `ErrorPtr` can then be observed to gathered details of the error.
+/// Don't forget to call `iohk_jormungandr_wallet_delete_error` to free
+/// the `ErrorPtr` from memory and avoid memory leaks.
+///
+/// * this function may fail if the conversion pointer is null;
+///
+/// If the `value_out` or `ignored_out` pointer is null, it is not set.
+///
+/// # Safety
+///
+/// This function dereference raw pointers. Even though
+/// the function checks if the pointers are null. Mind not to put random values
+/// in or you may see unexpected behaviors
+///
+#[no_mangle]
+pub unsafe extern "C" fn iohk_jormungandr_wallet_convert_ignored(
+    conversion: ConversionPtr,
+    value_out: *mut u64,
+    ignored_out: *mut usize,
+) -> ErrorPtr {
+    let r = wallet_convert_ignored(conversion as *mut ConversionRust, value_out, ignored_out);
+
+    r.into_c_api() as ErrorPtr
+}
+
 /// update the wallet account state
 ///
 /// this is the value retrieved from any jormungandr endpoint that allows to query
@@ -549,6 +1008,19 @@ pub unsafe extern "C" fn iohk_jormungandr_wallet_delete_wallet(wallet: WalletPtr
     wallet_delete_wallet(wallet as *mut WalletRust)
 }
 
+/// delete the pointer and free the allocated memory
+///
+/// # Safety
+///
+/// This function dereference raw pointers. Even though
+/// the function checks if the pointers are null. Mind not to put random values
+/// in or you may see unexpected behaviors
+///
+#[no_mangle]
+pub unsafe extern "C" fn iohk_jormungandr_wallet_delete_conversion(conversion: ConversionPtr) {
+    wallet_delete_conversion(conversion as *mut ConversionRust)
+}
+
 /// delete the pointer
 ///
 /// # Safety
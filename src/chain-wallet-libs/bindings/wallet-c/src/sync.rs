@@ -0,0 +1,186 @@
+use std::os::raw::c_char;
+use wallet_core::{
+    c::sync::{
+        wallet_delete_sync_report, wallet_sync, wallet_sync_report_get, wallet_sync_report_size,
+        wallet_sync_report_state_updated, wallet_sync_response_write, SyncFragmentStatus,
+        SyncResponsePtr, SyncTransport,
+    },
+    sync::SyncReport as SyncReportRust,
+};
+
+use crate::{ErrorPtr, SettingsPtr, SettingsRust, WalletPtr, WalletRust};
+
+#[repr(C)]
+pub struct SyncReport {}
+
+pub type SyncReportPtr = *mut SyncReport;
+
+/// update the wallet and its settings from a node
+///
+/// The fees and the maximum expiry of the transactions are taken from the
+/// settings of the node. The pending transactions found in a block are
+/// confirmed and, once no transaction is waiting in the mempool of the node
+/// anymore, the value and spending counters of the wallet are reset to the
+/// ones of its account.
+///
+/// The HTTP requests are sent by the host with the `get` function of
+/// `transport`, the body of the responses is given back with
+/// `iohk_jormungandr_wallet_sync_response_write`. The function blocks until
+/// all the requests are done.
+///
+/// # parameters
+///
+/// * wallet: the recovered wallet (see recover function);
+/// * settings: the settings of the blockchain, updated from the node;
+/// * node_url: the NUL terminated address of the REST API of the node
+///     without the `/api` path, for example `http://127.0.0.1:8443`;
+/// * transport: the HTTP client of the host;
+/// * report_out: what changed in the wallet, to delete with
+///     `iohk_jormungandr_wallet_delete_sync_report`.
+///
+/// # Errors
+///
+/// On error the function returns a `ErrorPtr`. On success `NULL` is returned.
+/// The `ErrorPtr` can then be observed to gathered details of the error.
+/// Don't forget to call `iohk_jormungandr_wallet_delete_error` to free
+/// the `ErrorPtr` from memory and avoid memory leaks.
+///
+/// * this function may fail if one of the pointers is null or `transport`
+///   has no `get` function;
+/// * the node is not reachable, replies with unexpected documents or follows
+///   the blockchain of another block0 than the one of `settings`.
+///
+/// # Safety
+///
+/// This function dereference raw pointers. Even though
+/// the function checks if the pointers are null. Mind not to put random values
+/// in or you may see unexpected behaviors
+///
+#[no_mangle]
+pub unsafe extern "C" fn iohk_jormungandr_wallet_sync(
+    wallet: WalletPtr,
+    settings: SettingsPtr,
+    node_url: *const c_char,
+    transport: SyncTransport,
+    report_out: *mut SyncReportPtr,
+) -> ErrorPtr {
+    let r = wallet_sync(
+        wallet as *mut WalletRust,
+        settings as *mut SettingsRust,
+        node_url,
+        transport,
+        report_out as *mut *mut SyncReportRust,
+    );
+
+    r.into_c_api() as ErrorPtr
+}
+
+/// append `length` bytes to the body of the response to a request of
+/// `iohk_jormungandr_wallet_sync`
+///
+/// The function is meant to be called from the `get` function of the
+/// `SyncTransport`, the response is not valid anymore once it returns.
+///
+/// # Errors
+///
+/// On error the function returns a `ErrorPtr`. On success `NULL` is returned.
+/// The `ErrorPtr` can then be observed to gathered details of the error.
+/// Don't forget to call `iohk_jormungandr_wallet_delete_error` to free
+/// the `ErrorPtr` from memory and avoid memory leaks.
+///
+/// * this function may fail if one of the pointers is null.
+///
+/// # Safety
+///
+/// This function dereference raw pointers. Even though
+/// the function checks if the pointers are null. Mind not to put random values
+/// in or you may see unexpected behaviors
+///
+#[no_mangle]
+pub unsafe extern "C" fn iohk_jormungandr_wallet_sync_response_write(
+    response: SyncResponsePtr,
+    data: *const u8,
+    length: usize,
+) -> ErrorPtr {
+    let r = wallet_sync_response_write(response, data, length);
+
+    r.into_c_api() as ErrorPtr
+}
+
+/// get the number of transactions of the report with the given status
+///
+/// # Safety
+///
+/// This function dereference raw pointers. Even though
+/// the function checks if the pointers are null. Mind not to put random values
+/// in or you may see unexpected behaviors
+///
+#[no_mangle]
+pub unsafe extern "C" fn iohk_jormungandr_wallet_sync_report_size(
+    report: SyncReportPtr,
+    status: SyncFragmentStatus,
+) -> usize {
+    wallet_sync_report_size(report as *mut SyncReportRust, status)
+}
+
+/// get the fragment id of the transaction at the given index of the
+/// transactions of the report with the given status
+///
+/// `id_out` must point to 32 bytes of allocated writable memory.
+///
+/// # Errors
+///
+/// On error the function returns a `ErrorPtr`. On success `NULL` is returned.
+/// The `ErrorPtr` can then be observed to gathered details of the error.
+/// Don't forget to call `iohk_jormungandr_wallet_delete_error` to free
+/// the `ErrorPtr` from memory and avoid memory leaks.
+///
+/// * this function may fail if one of the pointers is null;
+/// * the index is out of bound.
+///
+/// # Safety
+///
+/// This function dereference raw pointers. Even though
+/// the function checks if the pointers are null. Mind not to put random values
+/// in or you may see unexpected behaviors
+///
+#[no_mangle]
+pub unsafe extern "C" fn iohk_jormungandr_wallet_sync_report_get(
+    report: SyncReportPtr,
+    status: SyncFragmentStatus,
+    index: usize,
+    id_out: *mut u8,
+) -> ErrorPtr {
+    let r = wallet_sync_report_get(report as *mut SyncReportRust, status, index, id_out);
+
+    r.into_c_api() as ErrorPtr
+}
+
+/// get whether the value and spending counters of the wallet were reset to
+/// the state of its account on the node
+///
+/// # Safety
+///
+/// This function dereference raw pointers. Even though
+/// the function checks if the pointers are null. Mind not to put random values
+/// in or you may see unexpected behaviors
+///
+#[no_mangle]
+pub unsafe extern "C" fn iohk_jormungandr_wallet_sync_report_state_updated(
+    report: SyncReportPtr,
+) -> bool {
+    wallet_sync_report_state_updated(report as *mut SyncReportRust)
+}
+
+/// delete the pointer and free the allocated memory
+///
+/// # Safety
+///
+/// This function dereference raw pointers. Even though
+/// the function checks if the pointers are null. Mind not to put random values
+/// in or you may see unexpected behaviors
+///
+#[no_mangle]
+pub unsafe extern "C" fn iohk_jormungandr_wallet_delete_sync_report(report: SyncReportPtr) {
+    wallet_delete_sync_report(report as *mut SyncReportRust)
+}
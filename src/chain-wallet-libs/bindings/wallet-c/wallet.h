@@ -25,6 +25,47 @@ typedef enum Discrimination
   Discrimination_Test,
 } Discrimination;
 
+/**
+ * The derivation scheme of a legacy wallet, see `Wallet::add_utxo_root_key`
+ */
+typedef enum RecoveryScheme
+{
+  /**
+   * BIP44 derivation of the Yoroi and Icarus wallets
+   */
+  RecoveryScheme_Bip44 = 0,
+  /**
+   * random index derivation of the Daedalus wallets
+   */
+  RecoveryScheme_Rindex,
+} RecoveryScheme;
+
+/**
+ * the pending fragments of a [`SyncReport`]
+ */
+typedef enum SyncFragmentStatus
+{
+  /**
+   * the fragments found in a block
+   */
+  SyncFragmentStatus_Confirmed = 0,
+  /**
+   * the fragments that will never be added to the blockchain
+   */
+  SyncFragmentStatus_Expired,
+  /**
+   * the fragments that may still be added to the blockchain
+   */
+  SyncFragmentStatus_Pending,
+} SyncFragmentStatus;
+
+typedef struct Conversion
+{
+
+} Conversion;
+
+typedef struct Conversion *ConversionPtr;
+
 typedef struct Error
 {
 
@@ -56,6 +97,41 @@ typedef struct SpendingCounters
   uintptr_t len;
 } SpendingCounters;
 
+/**
+ * the body of a response of the node, filled by the host
+ */
+typedef struct SyncResponse SyncResponse;
+
+typedef struct SyncReport
+{
+
+} SyncReport;
+
+typedef struct SyncReport *SyncReportPtr;
+
+typedef struct SyncResponse *SyncResponsePtr;
+
+/**
+ * send a GET request to the NUL terminated `url`
+ *
+ * The body of the response is given to the library with
+ * `wallet_sync_response_write`. The function returns the HTTP status of the
+ * response or `0` if the request could not be sent.
+ */
+typedef uint16_t (*SyncGet)(void *context, const char *url, SyncResponsePtr response);
+
+/**
+ * the HTTP client of the host used to synchronise the wallet
+ */
+typedef struct SyncTransport
+{
+  /**
+   * passed as is to `get`, e.g. the HTTP client of the host
+   */
+  void *context;
+  SyncGet get;
+} SyncTransport;
+
 typedef struct Proposal
 {
 
@@ -324,6 +400,168 @@ ErrorPtr iohk_jormungandr_vote_proposal_new_public(const uint8_t *vote_plan_id,
                                                    uint8_t num_choices,
                                                    ProposalPtr *proposal_out);
 
+/**
+ * add the root key of a legacy wallet from a keystore, see
+ * `iohk_jormungandr_wallet_add_utxo_root_key`
+ *
+ * # Errors
+ *
+ * On error the function returns a `ErrorPtr`. On success `NULL` is returned.
+ * The `ErrorPtr` can then be observed to gathered details of the error.
+ * Don't forget to call `iohk_jormungandr_wallet_delete_error` to free
+ * the `ErrorPtr` from memory and avoid memory leaks.
+ *
+ * * this function may fail if one of the pointers is null;
+ * * the keystore is malformed or does not hold the root key of a legacy
+ *   wallet;
+ * * the password is not the one of the keystore.
+ *
+ * # Safety
+ *
+ * This function dereference raw pointers. Even though
+ * the function checks if the pointers are null. Mind not to put random values
+ * in or you may see unexpected behaviors
+ *
+ */
+ErrorPtr iohk_jormungandr_wallet_add_utxo_keystore(WalletPtr wallet,
+                                                   const uint8_t *keystore,
+                                                   uintptr_t keystore_length,
+                                                   const uint8_t *password,
+                                                   uintptr_t password_length);
+
+/**
+ * add the root key of a legacy wallet (Daedalus or Yoroi) to the wallet
+ *
+ * The funds declared in the block0 at the addresses of the legacy wallet are
+ * then found by `iohk_jormungandr_wallet_retrieve_funds` and can be moved to
+ * the wallet's account with `iohk_jormungandr_wallet_convert`.
+ *
+ * # parameters
+ *
+ * * wallet: the recovered wallet (see recover function);
+ * * root_key: the extended private key at the root of the legacy wallet in
+ *     the form of a 96 bytes array;
+ * * scheme: the derivation scheme of the legacy wallet's addresses.
+ *
+ * # Errors
+ *
+ * On error the function returns a `ErrorPtr`. On success `NULL` is returned.
+ * The `ErrorPtr` can then be observed to gathered details of the error.
+ * Don't forget to call `iohk_jormungandr_wallet_delete_error` to free
+ * the `ErrorPtr` from memory and avoid memory leaks.
+ *
+ * * this function may fail if the wallet or root_key pointer is null;
+ * * the `root_key` is not a valid extended private key.
+ *
+ * # Safety
+ *
+ * This function dereference raw pointers. Even though
+ * the function checks if the pointers are null. Mind not to put random values
+ * in or you may see unexpected behaviors
+ *
+ */
+ErrorPtr iohk_jormungandr_wallet_add_utxo_root_key(WalletPtr wallet,
+                                                   const uint8_t *root_key,
+                                                   enum RecoveryScheme scheme);
+
+/**
+ * build the transactions moving the funds of the legacy wallets to the
+ * wallet's account
+ *
+ * The conversion is returned in `conversion_out`. Use
+ * `iohk_jormungandr_wallet_convert_transactions_size` and
+ * `iohk_jormungandr_wallet_convert_transactions_get` to get the
+ * transactions to send to the blockchain and don't forget to call
+ * `iohk_jormungandr_wallet_delete_conversion` to free the conversion.
+ *
+ * # Errors
+ *
+ * On error the function returns a `ErrorPtr`. On success `NULL` is returned.
+ * The `ErrorPtr` can then be observed to gathered details of the error.
+ * Don't forget to call `iohk_jormungandr_wallet_delete_error` to free
+ * the `ErrorPtr` from memory and avoid memory leaks.
+ *
+ * * this function may fail if one of the pointers is null;
+ * * the transactions can't be built.
+ *
+ * # Safety
+ *
+ * This function dereference raw pointers. Even though
+ * the function checks if the pointers are null. Mind not to put random values
+ * in or you may see unexpected behaviors
+ *
+ */
+ErrorPtr iohk_jormungandr_wallet_convert(WalletPtr wallet,
+                                         SettingsPtr settings,
+                                         struct BlockDate valid_until,
+                                         ConversionPtr *conversion_out);
+
+/**
+ * get the total value and the number of the UTxOs ignored by the
+ * conversion, they are not worth the fee of their input
+ *
+ * # Errors
+ *
+ * On error the function returns a `ErrorPtr`. On success `NULL` is returned.
+ * The `ErrorPtr` can then be observed to gathered details of the error.
+ * Don't forget to call `iohk_jormungandr_wallet_delete_error` to free
+ * the `ErrorPtr` from memory and avoid memory leaks.
+ *
+ * * this function may fail if the conversion pointer is null;
+ *
+ * If the `value_out` or `ignored_out` pointer is null, it is not set.
+ *
+ * # Safety
+ *
+ * This function dereference raw pointers. Even though
+ * the function checks if the pointers are null. Mind not to put random values
+ * in or you may see unexpected behaviors
+ *
+ */
+ErrorPtr iohk_jormungandr_wallet_convert_ignored(ConversionPtr conversion,
+                                                 uint64_t *value_out,
+                                                 uintptr_t *ignored_out);
+
+/**
+ * get the transaction at the given index of the conversion
+ *
+ * The returned buffer is owned by the conversion, it is valid until the
+ * conversion is deleted.
+ *
+ * # Errors
+ *
+ * On error the function returns a `ErrorPtr`. On success `NULL` is returned.
+ * The `ErrorPtr` can then be observed to gathered details of the error.
+ * Don't forget to call `iohk_jormungandr_wallet_delete_error` to free
+ * the `ErrorPtr` from memory and avoid memory leaks.
+ *
+ * * this function may fail if one of the pointers is null;
+ * * the index is out of bound.
+ *
+ * # Safety
+ *
+ * This function dereference raw pointers. Even though
+ * the function checks if the pointers are null. Mind not to put random values
+ * in or you may see unexpected behaviors
+ *
+ */
+ErrorPtr iohk_jormungandr_wallet_convert_transactions_get(ConversionPtr conversion,
+                                                          uintptr_t index,
+                                                          const uint8_t **transaction_out,
+                                                          uintptr_t *transaction_size);
+
+/**
+ * get the number of transactions of the conversion
+ *
+ * # Safety
+ *
+ * This function dereference raw pointers. Even though
+ * the function checks if the pointers are null. Mind not to put random values
+ * in or you may see unexpected behaviors
+ *
+ */
+uintptr_t iohk_jormungandr_wallet_convert_transactions_size(ConversionPtr conversion);
+
 /**
  * Delete a binary buffer that was returned by this library alongside with its
  * length.
@@ -336,6 +574,18 @@ ErrorPtr iohk_jormungandr_vote_proposal_new_public(const uint8_t *vote_plan_id,
  */
 void iohk_jormungandr_wallet_delete_buffer(uint8_t *ptr, uintptr_t length);
 
+/**
+ * delete the pointer and free the allocated memory
+ *
+ * # Safety
+ *
+ * This function dereference raw pointers. Even though
+ * the function checks if the pointers are null. Mind not to put random values
+ * in or you may see unexpected behaviors
+ *
+ */
+void iohk_jormungandr_wallet_delete_conversion(ConversionPtr conversion);
+
 /**
  * delete the pointer and free the allocated memory
  *
@@ -384,6 +634,18 @@ void iohk_jormungandr_wallet_delete_settings(SettingsPtr settings);
  */
 void iohk_jormungandr_wallet_delete_string(char *ptr);
 
+/**
+ * delete the pointer and free the allocated memory
+ *
+ * # Safety
+ *
+ * This function dereference raw pointers. Even though
+ * the function checks if the pointers are null. Mind not to put random values
+ * in or you may see unexpected behaviors
+ *
+ */
+void iohk_jormungandr_wallet_delete_sync_report(SyncReportPtr report);
+
 /**
  * delete the pointer, zero all the keys and free the allocated memory
  *
@@ -438,6 +700,34 @@ char *iohk_jormungandr_wallet_error_details(ErrorPtr error);
  */
 char *iohk_jormungandr_wallet_error_to_string(ErrorPtr error);
 
+/**
+ * encrypt the key of the wallet's account with a password in a new keystore
+ *
+ * The JSON document of the keystore is returned in `keystore_out`. Don't
+ * forget to call `iohk_jormungandr_wallet_delete_buffer` to free it.
+ *
+ * # Errors
+ *
+ * On error the function returns a `ErrorPtr`. On success `NULL` is returned.
+ * The `ErrorPtr` can then be observed to gathered details of the error.
+ * Don't forget to call `iohk_jormungandr_wallet_delete_error` to free
+ * the `ErrorPtr` from memory and avoid memory leaks.
+ *
+ * * this function may fail if one of the pointers is null.
+ *
+ * # Safety
+ *
+ * This function dereference raw pointers. Even though
+ * the function checks if the pointers are null. Mind not to put random values
+ * in or you may see unexpected behaviors
+ *
+ */
+ErrorPtr iohk_jormungandr_wallet_export_keystore(WalletPtr wallet,
+                                                 const uint8_t *password,
+                                                 uintptr_t password_length,
+                                                 const uint8_t **keystore_out,
+                                                 uintptr_t *keystore_out_length);
+
 /**
  * get the wallet id
  *
@@ -505,6 +795,135 @@ ErrorPtr iohk_jormungandr_wallet_id(WalletPtr wallet,
 ErrorPtr iohk_jormungandr_wallet_import_keys(const uint8_t *account_key,
                                              WalletPtr *wallet_out);
 
+/**
+ * recover a wallet from a keystore holding the key of its account
+ *
+ * The recovered wallet will be returned in `wallet_out`.
+ *
+ * # parameters
+ *
+ * * keystore: the JSON document of the keystore
+ * * keystore_length: the number of bytes of the keystore
+ * * password: byte buffer with the password of the keystore
+ * * password_length: length of the password buffer
+ * * wallet_out: the recovered wallet
+ *
+ * # Errors
+ *
+ * On error the function returns a `ErrorPtr`. On success `NULL` is returned.
+ * The `ErrorPtr` can then be observed to gathered details of the error.
+ * Don't forget to call `iohk_jormungandr_wallet_delete_error` to free
+ * the `ErrorPtr` from memory and avoid memory leaks.
+ *
+ * * this function may fail if one of the pointers is null;
+ * * the keystore is malformed or does not hold the key of an account;
+ * * the password is not the one of the keystore.
+ *
+ * # Safety
+ *
+ * This function dereference raw pointers. Even though
+ * the function checks if the pointers are null. Mind not to put random values
+ * in or you may see unexpected behaviors
+ *
+ */
+ErrorPtr iohk_jormungandr_wallet_import_keystore(const uint8_t *keystore,
+                                                 uintptr_t keystore_length,
+                                                 const uint8_t *password,
+                                                 uintptr_t password_length,
+                                                 WalletPtr *wallet_out);
+
+/**
+ * encrypt the key of a keystore with a new password
+ *
+ * The JSON document of the new keystore is returned in `keystore_out`.
+ * Don't forget to call `iohk_jormungandr_wallet_delete_buffer` to free it.
+ *
+ * # Errors
+ *
+ * On error the function returns a `ErrorPtr`. On success `NULL` is returned.
+ * The `ErrorPtr` can then be observed to gathered details of the error.
+ * Don't forget to call `iohk_jormungandr_wallet_delete_error` to free
+ * the `ErrorPtr` from memory and avoid memory leaks.
+ *
+ * * this function may fail if one of the pointers is null;
+ * * the keystore is malformed;
+ * * the `old_password` is not the one of the keystore.
+ *
+ * # Safety
+ *
+ * This function dereference raw pointers. Even though
+ * the function checks if the pointers are null. Mind not to put random values
+ * in or you may see unexpected behaviors
+ *
+ */
+ErrorPtr iohk_jormungandr_wallet_keystore_change_password(const uint8_t *keystore,
+                                                          uintptr_t keystore_length,
+                                                          const uint8_t *old_password,
+                                                          uintptr_t old_password_length,
+                                                          const uint8_t *new_password,
+                                                          uintptr_t new_password_length,
+                                                          const uint8_t **keystore_out,
+                                                          uintptr_t *keystore_out_length);
+
+/**
+ * encrypt the 96 bytes root key of a legacy wallet with a password in a new
+ * keystore
+ *
+ * The JSON document of the keystore is returned in `keystore_out`. Don't
+ * forget to call `iohk_jormungandr_wallet_delete_buffer` to free it.
+ *
+ * # Errors
+ *
+ * On error the function returns a `ErrorPtr`. On success `NULL` is returned.
+ * The `ErrorPtr` can then be observed to gathered details of the error.
+ * Don't forget to call `iohk_jormungandr_wallet_delete_error` to free
+ * the `ErrorPtr` from memory and avoid memory leaks.
+ *
+ * * this function may fail if one of the pointers is null;
+ * * the `root_key` is not a valid extended private key.
+ *
+ * # Safety
+ *
+ * This function dereference raw pointers. Even though
+ * the function checks if the pointers are null. Mind not to put random values
+ * in or you may see unexpected behaviors
+ *
+ */
+ErrorPtr iohk_jormungandr_wallet_keystore_create_root_key(const uint8_t *root_key,
+                                                          enum RecoveryScheme scheme,
+                                                          const uint8_t *password,
+                                                          uintptr_t password_length,
+                                                          const uint8_t **keystore_out,
+                                                          uintptr_t *keystore_out_length);
+
+/**
+ * retrieve the funds of the wallet from the block0
+ *
+ * The settings of the blockchain are returned in `settings_out`. Don't
+ * forget to call `iohk_jormungandr_wallet_delete_settings` to free them.
+ *
+ * # Errors
+ *
+ * On error the function returns a `ErrorPtr`. On success `NULL` is returned.
+ * The `ErrorPtr` can then be observed to gathered details of the error.
+ * Don't forget to call `iohk_jormungandr_wallet_delete_error` to free
+ * the `ErrorPtr` from memory and avoid memory leaks.
+ *
+ * * this function may fail if one of the pointers is null;
+ * * the block0 is not valid.
+ *
+ * # Safety
+ *
+ * This function dereference raw pointers. Even though
+ * the function checks if the pointers are null. Mind not to put random values
+ * in or you may see unexpected behaviors
+ *
+ */
+ErrorPtr iohk_jormungandr_wallet_retrieve_funds(WalletPtr wallet,
+                                                const uint8_t *block0,
+                                                uintptr_t block0_length,
+                                                SettingsPtr *settings_out);
+
 /**
  * update the wallet account state
  *
@@ -602,6 +1021,136 @@ ErrorPtr iohk_jormungandr_wallet_settings_new(struct SettingsInit settings_init,
 ErrorPtr iohk_jormungandr_wallet_spending_counters(WalletPtr wallet,
                                                    struct SpendingCounters *spending_counters_ptr);
 
+/**
+ * update the wallet and its settings from a node
+ *
+ * The fees and the maximum expiry of the transactions are taken from the
+ * settings of the node. The pending transactions found in a block are
+ * confirmed and, once no transaction is waiting in the mempool of the node
+ * anymore, the value and spending counters of the wallet are reset to the
+ * ones of its account.
+ *
+ * The HTTP requests are sent by the host with the `get` function of
+ * `transport`, the body of the responses is given back with
+ * `iohk_jormungandr_wallet_sync_response_write`. The function blocks until
+ * all the requests are done.
+ *
+ * # parameters
+ *
+ * * wallet: the recovered wallet (see recover function);
+ * * settings: the settings of the blockchain, updated from the node;
+ * * node_url: the NUL terminated address of the REST API of the node
+ *     without the `/api` path, for example `http://127.0.0.1:8443`;
+ * * transport: the HTTP client of the host;
+ * * report_out: what changed in the wallet, to delete with
+ *     `iohk_jormungandr_wallet_delete_sync_report`.
+ *
+ * # Errors
+ *
+ * On error the function returns a `ErrorPtr`. On success `NULL` is returned.
+ * The `ErrorPtr` can then be observed to gathered details of the error.
+ * Don't forget to call `iohk_jormungandr_wallet_delete_error` to free
+ * the `ErrorPtr` from memory and avoid memory leaks.
+ *
+ * * this function may fail if one of the pointers is null or `transport`
+ *   has no `get` function;
+ * * the node is not reachable, replies with unexpected documents or follows
+ *   the blockchain of another block0 than the one of `settings`.
+ *
+ * # Safety
+ *
+ * This function dereference raw pointers. Even though
+ * the function checks if the pointers are null. Mind not to put random values
+ * in or you may see unexpected behaviors
+ *
+ */
+ErrorPtr iohk_jormungandr_wallet_sync(WalletPtr wallet,
+                                      SettingsPtr settings,
+                                      const char *node_url,
+                                      struct SyncTransport transport,
+                                      SyncReportPtr *report_out);
+
+/**
+ * get the fragment id of the transaction at the given index of the
+ * transactions of the report with the given status
+ *
+ * `id_out` must point to 32 bytes of allocated writable memory.
+ *
+ * # Errors
+ *
+ * On error the function returns a `ErrorPtr`. On success `NULL` is returned.
+ * The `ErrorPtr` can then be observed to gathered details of the error.
+ * Don't forget to call `iohk_jormungandr_wallet_delete_error` to free
+ * the `ErrorPtr` from memory and avoid memory leaks.
+ *
+ * * this function may fail if one of the pointers is null;
+ * * the index is out of bound.
+ *
+ * # Safety
+ *
+ * This function dereference raw pointers. Even though
+ * the function checks if the pointers are null. Mind not to put random values
+ * in or you may see unexpected behaviors
+ *
+ */
+ErrorPtr iohk_jormungandr_wallet_sync_report_get(SyncReportPtr report,
+                                                 enum SyncFragmentStatus status,
+                                                 uintptr_t index,
+                                                 uint8_t *id_out);
+
+/**
+ * get the number of transactions of the report with the given status
+ *
+ * # Safety
+ *
+ * This function dereference raw pointers. Even though
+ * the function checks if the pointers are null. Mind not to put random values
+ * in or you may see unexpected behaviors
+ *
+ */
+uintptr_t iohk_jormungandr_wallet_sync_report_size(SyncReportPtr report,
+                                                   enum SyncFragmentStatus status);
+
+/**
+ * get whether the value and spending counters of the wallet were reset to
+ * the state of its account on the node
+ *
+ * # Safety
+ *
+ * This function dereference raw pointers. Even though
+ * the function checks if the pointers are null. Mind not to put random values
+ * in or you may see unexpected behaviors
+ *
+ */
+bool iohk_jormungandr_wallet_sync_report_state_updated(SyncReportPtr report);
+
+/**
+ * append `length` bytes to the body of the response to a request of
+ * `iohk_jormungandr_wallet_sync`
+ *
+ * The function is meant to be called from the `get` function of the
+ * `SyncTransport`, the response is not valid anymore once it returns.
+ *
+ * # Errors
+ *
+ * On error the function returns a `ErrorPtr`. On success `NULL` is returned.
+ * The `ErrorPtr` can then be observed to gathered details of the error.
+ * Don't forget to call `iohk_jormungandr_wallet_delete_error` to free
+ * the `ErrorPtr` from memory and avoid memory leaks.
+ *
+ * * this function may fail if one of the pointers is null.
+ *
+ * # Safety
+ *
+ * This function dereference raw pointers. Even though
+ * the function checks if the pointers are null. Mind not to put random values
+ * in or you may see unexpected behaviors
+ *
+ */
+ErrorPtr iohk_jormungandr_wallet_sync_response_write(SyncResponsePtr response,
+                                                     const uint8_t *data,
+                                                     uintptr_t length);
+
 /**
  * get the total value in the wallet
  *
@@ -631,6 +1180,33 @@ ErrorPtr iohk_jormungandr_wallet_spending_counters(WalletPtr wallet,
 ErrorPtr iohk_jormungandr_wallet_total_value(WalletPtr wallet,
                                              uint64_t *total_out);
 
+/**
+ * get the value of the legacy wallets that is not converted to the account
+ * yet
+ *
+ * After calling this function the results is returned in the `value_out`.
+ *
+ * # Errors
+ *
+ * * this function may fail if the wallet pointer is null;
+ *
+ * On error the function returns a `ErrorPtr`. On success `NULL` is returned.
+ * The `ErrorPtr` can then be observed to gathered details of the error.
+ * Don't forget to call `iohk_jormungandr_wallet_delete_error` to free
+ * the `ErrorPtr` from memory and avoid memory leaks.
+ *
+ * If the `value_out` pointer is null, this function does nothing
+ *
+ * # Safety
+ *
+ * This function dereference raw pointers. Even though
+ * the function checks if the pointers are null. Mind not to put random values
+ * in or you may see unexpected behaviors
+ *
+ */
+ErrorPtr iohk_jormungandr_wallet_utxo_value(WalletPtr wallet,
+                                            uint64_t *value_out);
+
 /**
  * build the vote cast transaction
  *
@@ -4,14 +4,19 @@
 mod macros;
 pub mod fragment;
 pub mod settings;
+#[cfg(feature = "sync")]
+pub mod sync;
 pub mod time;
 pub mod vote;
 
-use crate::{Error, Proposal, Result, Wallet};
+use crate::{
+    keystore_change_password, keystore_create, Conversion, Error, Proposal, RecoveryScheme, Result,
+    Wallet,
+};
 use chain_impl_mockchain::{
     account::SpendingCounterIncreasing, fragment::Fragment, value::Value, vote::Choice,
 };
-use std::convert::TryInto;
+use std::{convert::TryInto, time::SystemTime};
 
 use thiserror::Error;
 pub use wallet::Settings;
@@ -23,6 +28,7 @@ pub type SettingsPtr = *mut Settings;
 pub type ProposalPtr = *mut Proposal;
 pub type ErrorPtr = *mut Error;
 pub type FragmentPtr = *mut Fragment;
+pub type ConversionPtr = *mut Conversion;
 
 #[derive(Debug, Error)]
 #[error("null pointer")]
@@ -34,6 +40,7 @@ struct OutOfBound;
 
 pub const FRAGMENT_ID_LENGTH: usize = 32;
 pub const NONCES_SIZE: usize = 8 * 4;
+pub const ROOT_KEY_LENGTH: usize = 96;
 
 /// recover a wallet from an account and a list of utxo keys
 ///
@@ -80,6 +87,222 @@ pub unsafe fn wallet_import_keys(account_key: *const u8, wallet_out: *mut Wallet
     }
 }
 
+/// add the root key of a legacy wallet (Daedalus or Yoroi) to the wallet
+///
+/// The funds declared in the block0 at the addresses of the legacy wallet are
+/// then found by `wallet_retrieve_funds` and can be moved to the wallet's
+/// account with `wallet_convert`.
+///
+/// # parameters
+///
+/// * wallet: the recovered wallet (see recover function);
+/// * root_key: the extended private key at the root of the legacy wallet in
+///     the form of a 96 bytes array;
+/// * scheme: the derivation scheme of the legacy wallet's addresses.
+///
+/// # Safety
+///
+/// This function dereference raw pointers (wallet and root_key). Even though
+/// the function checks if the pointers are null. Mind not to put random values
+/// in or you may see unexpected behaviors
+///
+/// # errors
+///
+/// The function may fail if:
+///
+/// * the `wallet` or `root_key` is null pointer
+/// * the `root_key` is not a valid extended private key
+///
+pub unsafe fn wallet_add_utxo_root_key(
+    wallet: WalletPtr,
+    root_key: *const u8,
+    scheme: RecoveryScheme,
+) -> Result {
+    let wallet = non_null_mut!(wallet);
+    let root_key: &u8 = non_null!(root_key);
+
+    let root_key: &[u8] = std::slice::from_raw_parts(root_key as *const u8, ROOT_KEY_LENGTH);
+
+    match wallet.add_utxo_root_key(root_key, scheme) {
+        Ok(()) => Result::success(),
+        Err(err) => err.into(),
+    }
+}
+
+/// retrieve the funds of the wallet from the block0
+///
+/// The settings of the blockchain are returned in `settings_out`, they can be
+/// deleted with `wallet_delete_settings`.
+///
+/// # parameters
+///
+/// * wallet: the recovered wallet (see recover function);
+/// * block0: the pointer to the bytes of the block0;
+/// * block0_length: the number of bytes of the block0;
+/// * settings_out: the settings of the blockchain.
+///
+/// # Safety
+///
+/// This function dereference raw pointers (wallet, block0 and settings_out).
+/// Even though the function checks if the pointers are null. Mind not to put
+/// random values in or you may see unexpected behaviors
+///
+/// # errors
+///
+/// The function may fail if:
+///
+/// * one of the pointers is null
+/// * the block0 is not valid
+///
+pub unsafe fn wallet_retrieve_funds(
+    wallet: WalletPtr,
+    block0: *const u8,
+    block0_length: usize,
+    settings_out: *mut SettingsPtr,
+) -> Result {
+    let wallet = non_null_mut!(wallet);
+    let block0: &u8 = non_null!(block0);
+    let settings_out = non_null_mut!(settings_out);
+
+    let block0: &[u8] = std::slice::from_raw_parts(block0 as *const u8, block0_length);
+
+    match wallet.retrieve_funds(block0) {
+        Ok(settings) => {
+            *settings_out = Box::into_raw(Box::new(settings));
+            Result::success()
+        }
+        Err(err) => err.into(),
+    }
+}
+
+/// build the transactions moving the funds of the legacy wallets to the
+/// wallet's account
+///
+/// The conversion is returned in `conversion_out`, use
+/// `wallet_convert_transactions_size` and `wallet_convert_transactions_get`
+/// to get the transactions to send to the blockchain and delete it with
+/// `wallet_delete_conversion`.
+///
+/// # Safety
+///
+/// This function dereference raw pointers (wallet, settings and
+/// conversion_out). Even though the function checks if the pointers are
+/// null. Mind not to put random values in or you may see unexpected
+/// behaviors
+///
+/// # errors
+///
+/// The function may fail if:
+///
+/// * one of the pointers is null
+/// * the transactions can't be built
+///
+pub unsafe fn wallet_convert(
+    wallet: WalletPtr,
+    settings: SettingsPtr,
+    valid_until: BlockDate,
+    conversion_out: *mut ConversionPtr,
+) -> Result {
+    let wallet = non_null_mut!(wallet);
+    let settings = non_null!(settings);
+    let conversion_out = non_null_mut!(conversion_out);
+
+    match wallet.convert(settings.clone(), &valid_until.into()) {
+        Ok(conversion) => {
+            *conversion_out = Box::into_raw(Box::new(conversion));
+            Result::success()
+        }
+        Err(err) => err.into(),
+    }
+}
+
+/// get the number of transactions of the conversion
+///
+/// # Safety
+///
+/// This function dereference raw pointers. Even though the function checks
+/// if the pointers are null. Mind not to put random values in or you may see
+/// unexpected behaviors
+///
+pub unsafe fn wallet_convert_transactions_size(conversion: ConversionPtr) -> usize {
+    conversion
+        .as_ref()
+        .map(|conversion| conversion.transactions().len())
+        .unwrap_or_default()
+}
+
+/// get the transaction at the given index of the conversion
+///
+/// The returned buffer is owned by the conversion, it is valid until the
+/// conversion is deleted with `wallet_delete_conversion`.
+///
+/// # Safety
+///
+/// This function dereference raw pointers. Even though the function checks
+/// if the pointers are null. Mind not to put random values in or you may see
+/// unexpected behaviors
+///
+/// # errors
+///
+/// The function may fail if:
+///
+/// * one of the pointers is null
+/// * the index is out of bound
+///
+pub unsafe fn wallet_convert_transactions_get(
+    conversion: ConversionPtr,
+    index: usize,
+    transaction_out: *mut *const u8,
+    transaction_size: *mut usize,
+) -> Result {
+    let conversion = non_null!(conversion);
+    let transaction_out = non_null_mut!(transaction_out);
+    let transaction_size = non_null_mut!(transaction_size);
+
+    if let Some(transaction) = conversion.transactions().get(index) {
+        *transaction_out = transaction.as_ptr();
+        *transaction_size = transaction.len();
+        Result::success()
+    } else {
+        Error::wallet_conversion().with(OutOfBound).into()
+    }
+}
+
+/// get the total value and the number of the UTxOs ignored by the conversion
+///
+/// These UTxOs are not worth the fee of their input.
+///
+/// # Safety
+///
+/// This function dereference raw pointers. Even though the function checks
+/// if the pointers are null. Mind not to put random values in or you may see
+/// unexpected behaviors
+///
+/// # errors
+///
+/// * this function may fail if the conversion pointer is null;
+///
+/// If the `value_out` or `ignored_out` pointer is null, it is not set.
+///
+pub unsafe fn wallet_convert_ignored(
+    conversion: ConversionPtr,
+    value_out: *mut u64,
+    ignored_out: *mut usize,
+) -> Result {
+    let conversion = non_null!(conversion);
+
+    if let Some(value_out) = value_out.as_mut() {
+        let value: Value = conversion.ignored().iter().map(|input| input.value()).sum();
+        *value_out = *value.as_ref();
+    }
+
+    if let Some(ignored_out) = ignored_out.as_mut() {
+        *ignored_out = conversion.ignored().len();
+    }
+
+    Result::success()
+}
+
 /// get the wallet id
 ///
 /// This ID is the identifier to use against the blockchain/explorer to retrieve
@@ -212,6 +435,33 @@ pub unsafe fn wallet_total_value(wallet: WalletPtr, total_out: *mut u64) -> Resu
     Result::success()
 }
 
+/// get the value of the legacy wallets that is not converted to the account
+/// yet
+///
+/// After calling this function the results is returned in the `value_out`.
+///
+/// # Errors
+///
+/// * this function may fail if the wallet pointer is null;
+///
+/// If the `value_out` pointer is null, this function does nothing
+///
+/// # Safety
+///
+/// This function dereference raw pointers. Even though
+/// the function checks if the pointers are null. Mind not to put random values
+/// in or you may see unexpected behaviors
+///
+pub unsafe fn wallet_utxo_value(wallet: WalletPtr, value_out: *mut u64) -> Result {
+    let wallet = non_null!(wallet);
+
+    if let Some(value_out) = value_out.as_mut() {
+        *value_out = *wallet.utxo_value().as_ref();
+    }
+
+    Result::success()
+}
+
 #[repr(C)]
 pub struct SpendingCounters {
     pub data: *mut u32,
@@ -320,6 +570,238 @@ pub unsafe fn wallet_vote_cast(
     Result::success()
 }
 
+/// recover a wallet from a keystore holding the key of its account
+///
+/// # parameters
+///
+/// * keystore: the JSON document of the keystore
+/// * keystore_length: the number of bytes of the keystore
+/// * password: byte buffer with the password of the keystore
+/// * password_length: length of the password buffer
+/// * wallet_out: the recovered wallet
+///
+/// # Safety
+///
+/// This function dereference raw pointers. Even though the function checks if
+/// the pointers are null. Mind not to put random values in or you may see
+/// unexpected behaviors.
+///
+/// # errors
+///
+/// The function may fail if:
+///
+/// * one of the pointers is null
+/// * the keystore is malformed or does not hold the key of an account
+/// * the password is not the one of the keystore
+///
+pub unsafe fn wallet_import_keystore(
+    keystore: *const u8,
+    keystore_length: usize,
+    password: *const u8,
+    password_length: usize,
+    wallet_out: *mut WalletPtr,
+) -> Result {
+    let wallet_out = non_null_mut!(wallet_out);
+    let keystore = match keystore_str(keystore, keystore_length) {
+        Ok(keystore) => keystore,
+        Err(err) => return err.into(),
+    };
+    let password = non_null_array!(password, password_length);
+
+    match Wallet::from_keystore(keystore, password) {
+        Ok(wallet) => {
+            *wallet_out = Box::into_raw(Box::new(wallet));
+            Result::success()
+        }
+        Err(err) => err.into(),
+    }
+}
+
+/// encrypt the key of the wallet's account with a password in a new keystore
+///
+/// The JSON document of the keystore is returned in `keystore_out`, it is in
+/// the heap, so make sure to call the delete_buffer function.
+///
+/// # Safety
+///
+/// This function dereference raw pointers. Even though the function checks if
+/// the pointers are null. Mind not to put random values in or you may see
+/// unexpected behaviors.
+///
+/// # errors
+///
+/// The function may fail if one of the pointers is null.
+///
+pub unsafe fn wallet_export_keystore(
+    wallet: WalletPtr,
+    password: *const u8,
+    password_length: usize,
+    keystore_out: *mut *const u8,
+    keystore_out_length: *mut usize,
+) -> Result {
+    let wallet = non_null!(wallet);
+    let password = non_null_array!(password, password_length);
+    let keystore_out = non_null_mut!(keystore_out);
+    let keystore_out_length = non_null_mut!(keystore_out_length);
+
+    match wallet.export_keystore(password, SystemTime::now()) {
+        Ok(keystore) => {
+            write_buffer(keystore, keystore_out, keystore_out_length);
+            Result::success()
+        }
+        Err(err) => err.into(),
+    }
+}
+
+/// add the root key of a legacy wallet from a keystore, see
+/// `wallet_add_utxo_root_key`
+///
+/// # Safety
+///
+/// This function dereference raw pointers. Even though the function checks if
+/// the pointers are null. Mind not to put random values in or you may see
+/// unexpected behaviors.
+///
+/// # errors
+///
+/// The function may fail if:
+///
+/// * one of the pointers is null
+/// * the keystore is malformed or does not hold the root key of a legacy
+///   wallet
+/// * the password is not the one of the keystore
+///
+pub unsafe fn wallet_add_utxo_keystore(
+    wallet: WalletPtr,
+    keystore: *const u8,
+    keystore_length: usize,
+    password: *const u8,
+    password_length: usize,
+) -> Result {
+    let wallet = non_null_mut!(wallet);
+    let keystore = match keystore_str(keystore, keystore_length) {
+        Ok(keystore) => keystore,
+        Err(err) => return err.into(),
+    };
+    let password = non_null_array!(password, password_length);
+
+    match wallet.add_utxo_keystore(keystore, password) {
+        Ok(()) => Result::success(),
+        Err(err) => err.into(),
+    }
+}
+
+/// encrypt the root key of a legacy wallet with a password in a new keystore
+///
+/// The JSON document of the keystore is returned in `keystore_out`, it is in
+/// the heap, so make sure to call the delete_buffer function.
+///
+/// # parameters
+///
+/// * root_key: the extended private key at the root of the legacy wallet in
+///     the form of a 96 bytes array;
+/// * scheme: the derivation scheme of the legacy wallet's addresses.
+///
+/// # Safety
+///
+/// This function dereference raw pointers. Even though the function checks if
+/// the pointers are null. Mind not to put random values in or you may see
+/// unexpected behaviors.
+///
+/// # errors
+///
+/// The function may fail if:
+///
+/// * one of the pointers is null
+/// * the `root_key` is not a valid extended private key
+///
+pub unsafe fn wallet_keystore_create_root_key(
+    root_key: *const u8,
+    scheme: RecoveryScheme,
+    password: *const u8,
+    password_length: usize,
+    keystore_out: *mut *const u8,
+    keystore_out_length: *mut usize,
+) -> Result {
+    let root_key = non_null_array!(root_key, ROOT_KEY_LENGTH);
+    let password = non_null_array!(password, password_length);
+    let keystore_out = non_null_mut!(keystore_out);
+    let keystore_out_length = non_null_mut!(keystore_out_length);
+
+    match keystore_create(scheme.into(), root_key, password, SystemTime::now()) {
+        Ok(keystore) => {
+            write_buffer(keystore, keystore_out, keystore_out_length);
+            Result::success()
+        }
+        Err(err) => err.into(),
+    }
+}
+
+/// encrypt the key of a keystore with a new password
+///
+/// The JSON document of the new keystore is returned in `keystore_out`, it
+/// is in the heap, so make sure to call the delete_buffer function.
+///
+/// # Safety
+///
+/// This function dereference raw pointers. Even though the function checks if
+/// the pointers are null. Mind not to put random values in or you may see
+/// unexpected behaviors.
+///
+/// # errors
+///
+/// The function may fail if:
+///
+/// * one of the pointers is null
+/// * the keystore is malformed
+/// * the `old_password` is not the one of the keystore
+///
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn wallet_keystore_change_password(
+    keystore: *const u8,
+    keystore_length: usize,
+    old_password: *const u8,
+    old_password_length: usize,
+    new_password: *const u8,
+    new_password_length: usize,
+    keystore_out: *mut *const u8,
+    keystore_out_length: *mut usize,
+) -> Result {
+    let keystore = match keystore_str(keystore, keystore_length) {
+        Ok(keystore) => keystore,
+        Err(err) => return err.into(),
+    };
+    let old_password = non_null_array!(old_password, old_password_length);
+    let new_password = non_null_array!(new_password, new_password_length);
+    let keystore_out = non_null_mut!(keystore_out);
+    let keystore_out_length = non_null_mut!(keystore_out_length);
+
+    match keystore_change_password(keystore, old_password, new_password) {
+        Ok(keystore) => {
+            write_buffer(keystore, keystore_out, keystore_out_length);
+            Result::success()
+        }
+        Err(err) => err.into(),
+    }
+}
+
+unsafe fn keystore_str<'a>(
+    keystore: *const u8,
+    length: usize,
+) -> std::result::Result<&'a str, Error> {
+    if keystore.is_null() {
+        return Err(Error::invalid_input("keystore").with(NulPtr));
+    }
+    std::str::from_utf8(std::slice::from_raw_parts(keystore, length))
+        .map_err(|e| Error::invalid_input("keystore").with(e))
+}
+
+fn write_buffer(data: String, out: &mut *const u8, out_length: &mut usize) {
+    let data = data.into_bytes().into_boxed_slice();
+    *out_length = data.len();
+    *out = Box::into_raw(data) as *const u8;
+}
+
 /// decrypt payload of the wallet transfer protocol
 ///
 /// Parameters
@@ -408,6 +890,19 @@ pub unsafe fn wallet_delete_wallet(wallet: WalletPtr) {
     }
 }
 
+/// delete the pointer and free the allocated memory
+///
+/// # Safety
+///
+/// The pointer must have been previously returned by this library
+pub unsafe fn wallet_delete_conversion(conversion: ConversionPtr) {
+    if !conversion.is_null() {
+        let boxed = Box::from_raw(conversion);
+
+        std::mem::drop(boxed);
+    }
+}
+
 /// delete the pointer
 ///
 /// # Safety
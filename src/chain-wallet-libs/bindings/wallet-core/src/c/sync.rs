@@ -0,0 +1,256 @@
+//! synchronisation of the wallet with a node, the HTTP requests are sent by
+//! the host of the bindings through a [`SyncTransport`]
+use super::{NulPtr, OutOfBound, SettingsPtr, WalletPtr, FRAGMENT_ID_LENGTH};
+use crate::{
+    sync::{SyncClient, SyncReport, Transport, TransportError},
+    Error, FragmentId, Result,
+};
+use std::{
+    ffi::{c_void, CStr, CString},
+    os::raw::c_char,
+};
+use thiserror::Error;
+
+pub type SyncResponsePtr = *mut SyncResponse;
+pub type SyncReportPtr = *mut SyncReport;
+
+/// send a GET request to the NUL terminated `url`
+///
+/// The body of the response is given to the library with
+/// `wallet_sync_response_write`. The function returns the HTTP status of the
+/// response or `0` if the request could not be sent.
+pub type SyncGet = unsafe extern "C" fn(
+    context: *mut c_void,
+    url: *const c_char,
+    response: SyncResponsePtr,
+) -> u16;
+
+/// the HTTP client of the host used to synchronise the wallet
+#[repr(C)]
+pub struct SyncTransport {
+    /// passed as is to `get`, e.g. the HTTP client of the host
+    pub context: *mut c_void,
+    pub get: Option<SyncGet>,
+}
+
+/// the body of a response of the node, filled by the host
+#[derive(Default)]
+pub struct SyncResponse {
+    body: Vec<u8>,
+}
+
+/// the pending fragments of a [`SyncReport`]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SyncFragmentStatus {
+    /// the fragments found in a block
+    Confirmed,
+    /// the fragments that will never be added to the blockchain
+    Expired,
+    /// the fragments that may still be added to the blockchain
+    Pending,
+}
+
+#[derive(Debug, Error)]
+#[error("the host could not send the request")]
+struct RequestFailed;
+
+#[derive(Debug, Error)]
+#[error("the host did not provide a transport")]
+struct NoTransport;
+
+impl Transport for SyncTransport {
+    fn get(&mut self, url: &str) -> std::result::Result<Vec<u8>, TransportError> {
+        let get = self
+            .get
+            .ok_or_else(|| TransportError::Request(Box::new(NoTransport)))?;
+        let url = CString::new(url).map_err(|e| TransportError::Request(Box::new(e)))?;
+        let mut response = SyncResponse::default();
+
+        match unsafe { get(self.context, url.as_ptr(), &mut response) } {
+            0 => Err(TransportError::Request(Box::new(RequestFailed))),
+            200..=299 => Ok(response.body),
+            status => Err(TransportError::Status(status)),
+        }
+    }
+}
+
+impl SyncReport {
+    fn fragments(&self, status: SyncFragmentStatus) -> &[FragmentId] {
+        match status {
+            SyncFragmentStatus::Confirmed => &self.confirmed,
+            SyncFragmentStatus::Expired => &self.expired,
+            SyncFragmentStatus::Pending => &self.pending,
+        }
+    }
+}
+
+/// update the wallet and its settings from the node at `node_url`
+///
+/// `node_url` is the NUL terminated address of the REST API of the node
+/// without the `/api` path, for example `http://127.0.0.1:8443`. The requests
+/// are sent with the `get` function of `transport` and block until it
+/// returns.
+///
+/// What changed in the wallet is returned in `report_out`, it can be deleted
+/// with `wallet_delete_sync_report`.
+///
+/// # Safety
+///
+/// This function dereference raw pointers. Even though the function checks
+/// if the pointers are null. Mind not to put random values in or you may see
+/// unexpected behaviors
+///
+/// # errors
+///
+/// The function may fail if:
+///
+/// * one of the pointers is null or `transport` has no `get` function;
+/// * the node is not reachable, replies with unexpected documents or follows
+///   the blockchain of another block0 than the one of `settings`.
+///
+pub unsafe fn wallet_sync(
+    wallet: WalletPtr,
+    settings: SettingsPtr,
+    node_url: *const c_char,
+    transport: SyncTransport,
+    report_out: *mut SyncReportPtr,
+) -> Result {
+    let wallet = non_null_mut!(wallet);
+    let settings = non_null_mut!(settings);
+    let report_out = non_null_mut!(report_out);
+    if node_url.is_null() {
+        return Error::invalid_input("node_url").with(NulPtr).into();
+    }
+    if transport.get.is_none() {
+        return Error::invalid_input("transport").with(NoTransport).into();
+    }
+
+    let node_url = match CStr::from_ptr(node_url).to_str() {
+        Ok(node_url) => node_url,
+        Err(e) => return Error::invalid_input("node_url").with(e).into(),
+    };
+
+    match SyncClient::new(node_url, transport).sync(wallet, settings) {
+        Ok(report) => {
+            *report_out = Box::into_raw(Box::new(report));
+            Result::success()
+        }
+        Err(err) => err.into(),
+    }
+}
+
+/// append `length` bytes of the body of the response to a request sent by
+/// `SyncTransport::get`
+///
+/// The function is meant to be called from `SyncTransport::get`, the
+/// response is not valid anymore once it returns.
+///
+/// # Safety
+///
+/// This function dereference raw pointers. Even though the function checks
+/// if the pointers are null. Mind not to put random values in or you may see
+/// unexpected behaviors
+///
+/// # errors
+///
+/// * this function may fail if one of the pointers is null;
+///
+pub unsafe fn wallet_sync_response_write(
+    response: SyncResponsePtr,
+    data: *const u8,
+    length: usize,
+) -> Result {
+    let response = non_null_mut!(response);
+    let data = non_null_array!(data, length);
+
+    response.body.extend_from_slice(data);
+
+    Result::success()
+}
+
+/// get the number of fragments of the report with the given status
+///
+/// # Safety
+///
+/// This function dereference raw pointers. Even though the function checks
+/// if the pointers are null. Mind not to put random values in or you may see
+/// unexpected behaviors
+///
+pub unsafe fn wallet_sync_report_size(report: SyncReportPtr, status: SyncFragmentStatus) -> usize {
+    report
+        .as_ref()
+        .map(|report| report.fragments(status).len())
+        .unwrap_or_default()
+}
+
+/// get the id of the fragment at the given index of the fragments of the
+/// report with the given status
+///
+/// # Safety
+///
+/// This function dereference raw pointers. Even though the function checks
+/// if the pointers are null. Mind not to put random values in or you may see
+/// unexpected behaviors
+///
+/// the `id_out` needs to be ready allocated 32bytes memory. If not this will
+/// result in an undefined behavior, in the best scenario it will be a buffer
+/// overflow.
+///
+/// # errors
+///
+/// The function may fail if:
+///
+/// * one of the pointers is null
+/// * the index is out of bound
+///
+pub unsafe fn wallet_sync_report_get(
+    report: SyncReportPtr,
+    status: SyncFragmentStatus,
+    index: usize,
+    id_out: *mut u8,
+) -> Result {
+    let report = non_null!(report);
+    if id_out.is_null() {
+        return Error::invalid_input("id_out").with(NulPtr).into();
+    }
+
+    if let Some(id) = report.fragments(status).get(index) {
+        let id_out = std::slice::from_raw_parts_mut(id_out, FRAGMENT_ID_LENGTH);
+        id_out.copy_from_slice(id.as_bytes());
+        Result::success()
+    } else {
+        Error::invalid_input("index").with(OutOfBound).into()
+    }
+}
+
+/// get whether the value and spending counters of the wallet were reset to
+/// the state of its account on the node
+///
+/// `false` is returned if the report pointer is null.
+///
+/// # Safety
+///
+/// This function dereference raw pointers. Even though the function checks
+/// if the pointers are null. Mind not to put random values in or you may see
+/// unexpected behaviors
+///
+pub unsafe fn wallet_sync_report_state_updated(report: SyncReportPtr) -> bool {
+    report
+        .as_ref()
+        .map(|report| report.state_updated)
+        .unwrap_or_default()
+}
+
+/// delete the pointer and free the allocated memory
+///
+/// # Safety
+///
+/// The pointer must have been previously returned by this library
+pub unsafe fn wallet_delete_sync_report(report: SyncReportPtr) {
+    if !report.is_null() {
+        let boxed = Box::from_raw(report);
+
+        std::mem::drop(boxed);
+    }
+}
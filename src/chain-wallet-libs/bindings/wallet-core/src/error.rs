@@ -74,6 +74,13 @@ pub enum ErrorCode {
 
     /// invalid spending counters provided to the set_state function
     InvalidSpendingCounters = 12,
+
+    /// the keystore is malformed, of an unsupported version or does not
+    /// hold the expected kind of key
+    InvalidKeystore = 13,
+
+    /// the wallet could not be synchronised with the node, see the details
+    WalletSync = 14,
 }
 
 #[derive(Debug)]
@@ -119,6 +126,13 @@ pub enum ErrorKind {
 
     /// invalid spending counters provided to the set_state function
     InvalidSpendingCounters,
+
+    /// the keystore is malformed, of an unsupported version or does not
+    /// hold the expected kind of key
+    InvalidKeystore,
+
+    /// the wallet could not be synchronised with the node
+    WalletSync,
 }
 
 impl ErrorKind {
@@ -140,6 +154,8 @@ impl ErrorKind {
             Self::InvalidFragment => ErrorCode::InvalidFragment,
             Self::InvalidTransactionValidityDate => ErrorCode::InvalidTransactionValidityDate,
             Self::InvalidSpendingCounters => ErrorCode::InvalidSpendingCounters,
+            Self::InvalidKeystore => ErrorCode::InvalidKeystore,
+            Self::WalletSync => ErrorCode::WalletSync,
         }
     }
 }
@@ -269,6 +285,33 @@ impl Error {
         }
     }
 
+    pub fn keystore(err: wallet::keystore::Error) -> Self {
+        let kind = match err {
+            wallet::keystore::Error::AuthenticationFailed => {
+                ErrorKind::SymmetricCipherInvalidPassword
+            }
+            _ => ErrorKind::InvalidKeystore,
+        };
+        Self {
+            kind,
+            details: Some(Box::new(err)),
+        }
+    }
+
+    pub fn invalid_keystore() -> Self {
+        Self {
+            kind: ErrorKind::InvalidKeystore,
+            details: None,
+        }
+    }
+
+    pub fn wallet_sync() -> Self {
+        Self {
+            kind: ErrorKind::WalletSync,
+            details: None,
+        }
+    }
+
     /// set some details to the `Result` object if the `Result` is of
     /// error kind
     ///
@@ -419,6 +462,8 @@ impl Display for ErrorKind {
             Self::InvalidSpendingCounters => {
                 f.write_str("invalid spending counters provided to the set account state function")
             }
+            Self::InvalidKeystore => f.write_str("invalid keystore"),
+            Self::WalletSync => f.write_str("wallet synchronisation failed"),
         }
     }
 }
@@ -0,0 +1,70 @@
+use crate::Error;
+use rand::rngs::OsRng;
+use std::time::SystemTime;
+pub use wallet::keystore::KeyScheme;
+use wallet::{keystore::Keystore, ScrubbedBytes};
+
+/// Encrypt a secret key with a password in a new keystore
+///
+/// # Parameters
+///
+/// * `scheme`: what the secret key is, the key of the account of a wallet or
+///   the root key of a legacy wallet;
+/// * `secret_key`: the 64 bytes account key or the 96 bytes root key;
+/// * `password`: the password of the keystore;
+/// * `created_at`: the creation time recorded in the keystore, usually the
+///   current time.
+///
+/// Returns the JSON document of the keystore.
+///
+/// # Errors
+///
+/// The function fails if the secret key is not valid for the given scheme.
+///
+pub fn keystore_create(
+    scheme: KeyScheme,
+    secret_key: &[u8],
+    password: &[u8],
+    created_at: SystemTime,
+) -> Result<String, Error> {
+    Keystore::create(scheme, secret_key, password, created_at, OsRng)
+        .map(|keystore| keystore.to_json())
+        .map_err(Error::keystore)
+}
+
+/// Decrypt the secret key of a keystore
+///
+/// # Errors
+///
+/// The function fails if the keystore is malformed or if the password is
+/// not the one of the keystore.
+///
+pub fn keystore_unlock(
+    keystore: &str,
+    password: &[u8],
+) -> Result<(KeyScheme, ScrubbedBytes), Error> {
+    let keystore = Keystore::from_json(keystore).map_err(Error::keystore)?;
+    let secret_key = keystore.unlock(password).map_err(Error::keystore)?;
+    Ok((keystore.scheme(), secret_key))
+}
+
+/// Encrypt the secret key of a keystore with a new password
+///
+/// Returns the JSON document of the new keystore, the metadata of the
+/// keystore are kept.
+///
+/// # Errors
+///
+/// The function fails if the keystore is malformed or if `old_password` is
+/// not the password of the keystore.
+///
+pub fn keystore_change_password(
+    keystore: &str,
+    old_password: &[u8],
+    new_password: &[u8],
+) -> Result<String, Error> {
+    Keystore::from_json(keystore)
+        .and_then(|keystore| keystore.change_password(old_password, new_password, OsRng))
+        .map(|keystore| keystore.to_json())
+        .map_err(Error::keystore)
+}
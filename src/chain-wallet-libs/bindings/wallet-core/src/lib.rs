@@ -1,14 +1,20 @@
 pub mod c;
+mod conversion;
 mod error;
+mod keystore;
+#[cfg(feature = "sync")]
+pub mod sync;
 mod tx_builder;
 mod vote;
 mod wallet;
 
 pub use self::{
+    conversion::Conversion,
     error::{Error, ErrorCode, ErrorKind, Result},
+    keystore::{keystore_change_password, keystore_create, keystore_unlock, KeyScheme},
     tx_builder::TxBuilder,
     vote::Proposal,
-    wallet::Wallet,
+    wallet::{RecoveryScheme, Wallet},
 };
 pub use ::wallet::Settings;
 pub use chain_impl_mockchain::{
@@ -0,0 +1,536 @@
+//! Synchronisation of a [`Wallet`] with the REST API of a node.
+//!
+//! The [`SyncClient`] fetches the settings of the blockchain, the statuses
+//! of the pending fragments of the wallet and the state of its account, then
+//! updates the wallet from them:
+//!
+//! * the pending fragments added in a block are confirmed;
+//! * the pending fragments rejected by the node are reported as expired, as
+//!   well as the ones it does not know about once their `valid_until` date
+//!   has passed: until then they may still reach the node, e.g. when it was
+//!   restarted or they were sent to another node;
+//! * once no fragment is waiting in the mempool anymore, the value and the
+//!   spending counters of the wallet are reset to the ones of the account on
+//!   the node, which drops the expired fragments from the wallet.
+//!
+//! The HTTP requests go through a [`Transport`] so that the hosts of the
+//! bindings can use their own HTTP stack. A blocking transport based on
+//! `reqwest` is available with the `sync-http` feature.
+//!
+//! Only the fields of the documents of the node the client needs are
+//! decoded, so this module does not depend on `jormungandr-lib`.
+
+use crate::{Error, Settings, Wallet};
+use chain_impl_mockchain::{
+    account::SpendingCounterIncreasing,
+    block::BlockDate,
+    fee::{LinearFee, PerCertificateFee, PerVoteCertificateFee},
+    fragment::FragmentId,
+    value::Value,
+};
+use serde::{de::DeserializeOwned, Deserialize};
+use std::{
+    collections::HashMap,
+    num::NonZeroU64,
+    time::{Duration, SystemTime},
+};
+use thiserror::Error;
+
+/// HTTP client used by a [`SyncClient`] to query the node
+pub trait Transport {
+    /// Send a GET request to `url` and return the body of the response.
+    ///
+    /// A response with an HTTP status other than a success must be returned
+    /// as [`TransportError::Status`].
+    fn get(&mut self, url: &str) -> Result<Vec<u8>, TransportError>;
+}
+
+#[derive(Debug, Error)]
+pub enum TransportError {
+    #[error("the node replied with the HTTP status {0}")]
+    Status(u16),
+    #[error("the request to the node failed")]
+    Request(#[source] Box<dyn std::error::Error + Send + Sync>),
+}
+
+/// the details of an error of kind `WalletSync`
+#[derive(Debug, Error)]
+pub enum SyncError {
+    #[error("the node follows the blockchain of block0 {found}, expected {expected}")]
+    Block0Mismatch { expected: String, found: String },
+    #[error("request to {url} failed")]
+    Transport {
+        url: String,
+        #[source]
+        source: TransportError,
+    },
+    #[error("invalid response from {url}")]
+    InvalidResponse {
+        url: String,
+        #[source]
+        source: serde_json::Error,
+    },
+}
+
+/// What a [`SyncClient::sync`] changed in the wallet
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyncReport {
+    /// the pending fragments found in a block
+    pub confirmed: Vec<FragmentId>,
+    /// the pending fragments rejected by the node, or unknown to it past
+    /// their `valid_until` date, they will never be added to the blockchain
+    pub expired: Vec<FragmentId>,
+    /// the fragments still waiting in the mempool of the node, or unknown to
+    /// it but still valid
+    pub pending: Vec<FragmentId>,
+    /// `true` if the value and spending counters of the wallet were reset
+    /// to the state of the account on the node
+    pub state_updated: bool,
+}
+
+/// the settings of the node, `GET /api/v0/settings`
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NodeSettings {
+    block0_hash: String,
+    fees: NodeFees,
+    tx_max_expiry_epochs: u8,
+}
+
+#[derive(Deserialize)]
+struct NodeFees {
+    constant: u64,
+    coefficient: u64,
+    certificate: u64,
+    #[serde(default)]
+    per_certificate_fees: NodePerCertificateFees,
+    #[serde(default)]
+    per_vote_certificate_fees: NodePerVoteCertificateFees,
+}
+
+#[derive(Default, Deserialize)]
+struct NodePerCertificateFees {
+    certificate_pool_registration: Option<NonZeroU64>,
+    certificate_stake_delegation: Option<NonZeroU64>,
+    certificate_owner_stake_delegation: Option<NonZeroU64>,
+}
+
+#[derive(Default, Deserialize)]
+struct NodePerVoteCertificateFees {
+    certificate_vote_plan: Option<NonZeroU64>,
+    certificate_vote_cast: Option<NonZeroU64>,
+}
+
+impl From<NodeFees> for LinearFee {
+    fn from(fees: NodeFees) -> Self {
+        let mut linear_fee = LinearFee::new(fees.constant, fees.coefficient, fees.certificate);
+        linear_fee.per_certificate_fees(PerCertificateFee::new(
+            fees.per_certificate_fees.certificate_pool_registration,
+            fees.per_certificate_fees.certificate_stake_delegation,
+            fees.per_certificate_fees.certificate_owner_stake_delegation,
+        ));
+        linear_fee.per_vote_certificate_fees(PerVoteCertificateFee::new(
+            fees.per_vote_certificate_fees.certificate_vote_plan,
+            fees.per_vote_certificate_fees.certificate_vote_cast,
+        ));
+        linear_fee
+    }
+}
+
+/// the status of a fragment, `GET /api/v1/fragments/statuses`
+#[derive(Deserialize)]
+enum NodeFragmentStatus {
+    Pending,
+    Rejected {},
+    InABlock {},
+}
+
+/// the state of an account, `GET /api/v0/account/{id}`
+#[derive(Deserialize)]
+struct NodeAccountState {
+    value: u64,
+    counters: [u32; SpendingCounterIncreasing::LANES],
+}
+
+pub struct SyncClient<T> {
+    base_url: String,
+    transport: T,
+}
+
+impl<T: Transport> SyncClient<T> {
+    /// `base_url` is the address of the REST API of the node without the
+    /// `/api` path, for example `http://127.0.0.1:8443`.
+    pub fn new(base_url: impl Into<String>, transport: T) -> Self {
+        let base_url = base_url.into().trim_end_matches('/').to_owned();
+        Self {
+            base_url,
+            transport,
+        }
+    }
+
+    /// Update the wallet and its settings from the node
+    ///
+    /// The fees and the maximum expiry of the transactions are taken from
+    /// the settings of the node, see the module documentation for the
+    /// update of the wallet.
+    ///
+    /// # Errors
+    ///
+    /// The function fails with the `WalletSync` kind of error if the node
+    /// is not reachable, replies with unexpected documents or follows the
+    /// blockchain of another block0 than the one of `settings`.
+    ///
+    pub fn sync(
+        &mut self,
+        wallet: &mut Wallet,
+        settings: &mut Settings,
+    ) -> Result<SyncReport, Error> {
+        self.sync_at(wallet, settings, SystemTime::now())
+    }
+
+    /// Same as [`sync`](Self::sync) with the current time given by the
+    /// caller, for the hosts where `SystemTime::now` is not available such
+    /// as WebAssembly in a browser.
+    pub fn sync_at(
+        &mut self,
+        wallet: &mut Wallet,
+        settings: &mut Settings,
+        now: SystemTime,
+    ) -> Result<SyncReport, Error> {
+        self.sync_settings(settings)?;
+
+        let mut report = self.sync_fragments(wallet, current_date(settings, now))?;
+
+        // the account state of the node does not include the fragments of the
+        // mempool yet, resetting the wallet to it would reuse their counters
+        if report.pending.is_empty() {
+            let (value, counters) = self.account_state(wallet)?;
+            wallet.set_state(value, counters)?;
+            report.state_updated = true;
+        }
+
+        Ok(report)
+    }
+
+    fn sync_settings(&mut self, settings: &mut Settings) -> Result<(), Error> {
+        let node: NodeSettings = self
+            .get_json("/api/v0/settings")
+            .map_err(|e| Error::wallet_sync().with(e))?;
+
+        let expected = settings.block0_initial_hash.to_string();
+        if node.block0_hash != expected {
+            return Err(Error::wallet_sync().with(SyncError::Block0Mismatch {
+                expected,
+                found: node.block0_hash,
+            }));
+        }
+
+        settings.fees = node.fees.into();
+        settings.transaction_max_expiry_epochs = node.tx_max_expiry_epochs;
+
+        Ok(())
+    }
+
+    fn sync_fragments(
+        &mut self,
+        wallet: &mut Wallet,
+        current_date: Option<BlockDate>,
+    ) -> Result<SyncReport, Error> {
+        let mut report = SyncReport::default();
+
+        let pending = wallet.pending_transactions();
+        if pending.is_empty() {
+            return Ok(report);
+        }
+
+        let ids: Vec<String> = pending.iter().map(ToString::to_string).collect();
+        let path = format!("/api/v1/fragments/statuses?fragment_ids={}", ids.join(","));
+        let mut statuses: HashMap<String, NodeFragmentStatus> = self
+            .get_json(&path)
+            .map_err(|e| Error::wallet_sync().with(e))?;
+
+        for (id, key) in pending.into_iter().zip(ids) {
+            match statuses.remove(&key) {
+                Some(NodeFragmentStatus::InABlock {}) => {
+                    wallet.confirm_transaction(id);
+                    report.confirmed.push(id);
+                }
+                Some(NodeFragmentStatus::Pending) => report.pending.push(id),
+                Some(NodeFragmentStatus::Rejected {}) => report.expired.push(id),
+                None => match (wallet.pending_valid_until(&id), current_date) {
+                    (Some(valid_until), Some(current_date)) if current_date <= valid_until => {
+                        report.pending.push(id)
+                    }
+                    (Some(_), None) => report.pending.push(id),
+                    _ => report.expired.push(id),
+                },
+            }
+        }
+
+        Ok(report)
+    }
+
+    fn account_state(
+        &mut self,
+        wallet: &Wallet,
+    ) -> Result<(Value, [u32; SpendingCounterIncreasing::LANES]), Error> {
+        let path = format!("/api/v0/account/{}", wallet.id());
+        match self.get_json::<NodeAccountState>(&path) {
+            Ok(state) => Ok((Value(state.value), state.counters)),
+            // the account is only created on the blockchain when it first
+            // receives funds
+            Err(SyncError::Transport {
+                source: TransportError::Status(404),
+                ..
+            }) => Ok((Value::zero(), [0; SpendingCounterIncreasing::LANES])),
+            Err(e) => Err(Error::wallet_sync().with(e)),
+        }
+    }
+
+    fn get_json<D: DeserializeOwned>(&mut self, path: &str) -> Result<D, SyncError> {
+        let url = format!("{}{}", self.base_url, path);
+        let body = match self.transport.get(&url) {
+            Ok(body) => body,
+            Err(source) => return Err(SyncError::Transport { url, source }),
+        };
+        serde_json::from_slice(&body).map_err(|source| SyncError::InvalidResponse { url, source })
+    }
+}
+
+/// the date of the blockchain at `now`, `None` before the block0
+fn current_date(settings: &Settings, now: SystemTime) -> Option<BlockDate> {
+    let block0_date = SystemTime::UNIX_EPOCH + Duration::from_secs(settings.block0_date.0);
+    if now < block0_date {
+        return None;
+    }
+    ::wallet::time::block_date_from_system_time(settings, now).ok()
+}
+
+#[cfg(feature = "sync-http")]
+pub use self::http::HttpTransport;
+
+#[cfg(feature = "sync-http")]
+mod http {
+    use super::{Transport, TransportError};
+
+    /// blocking [`Transport`] based on `reqwest`
+    #[derive(Default)]
+    pub struct HttpTransport {
+        client: reqwest::blocking::Client,
+    }
+
+    impl HttpTransport {
+        pub fn new() -> Self {
+            Self::default()
+        }
+    }
+
+    impl Transport for HttpTransport {
+        fn get(&mut self, url: &str) -> Result<Vec<u8>, TransportError> {
+            let response = self
+                .client
+                .get(url)
+                .send()
+                .map_err(|e| TransportError::Request(Box::new(e)))?;
+
+            let status = response.status();
+            if !status.is_success() {
+                return Err(TransportError::Status(status.as_u16()));
+            }
+
+            response
+                .bytes()
+                .map(|bytes| bytes.to_vec())
+                .map_err(|e| TransportError::Request(Box::new(e)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ErrorKind, Options, Proposal};
+    use chain_impl_mockchain::{certificate::VotePlanId, header::HeaderId, vote::Choice};
+    use jormungandr_lib::interfaces::SettingsDto;
+    use std::{
+        convert::TryFrom as _,
+        time::{Duration, SystemTime},
+    };
+
+    const BLOCK0: &[u8] = include_bytes!("../../../test-vectors/block0");
+    const ACCOUNT_KEY: &str = include_str!("../../../test-vectors/free_keys/key1.prv");
+    const NODE: &str = "http://node";
+
+    #[derive(Default)]
+    struct MockTransport {
+        replies: HashMap<String, Result<Vec<u8>, u16>>,
+    }
+
+    impl MockTransport {
+        fn reply(&mut self, path: &str, body: serde_json::Value) {
+            let body = serde_json::to_vec(&body).unwrap();
+            self.replies.insert(format!("{}{}", NODE, path), Ok(body));
+        }
+    }
+
+    impl Transport for MockTransport {
+        fn get(&mut self, url: &str) -> Result<Vec<u8>, TransportError> {
+            match self.replies.get(url) {
+                Some(Ok(body)) => Ok(body.clone()),
+                Some(Err(status)) => Err(TransportError::Status(*status)),
+                None => Err(TransportError::Status(404)),
+            }
+        }
+    }
+
+    fn node_settings(settings: &Settings) -> serde_json::Value {
+        let dto = SettingsDto {
+            block0_hash: settings.block0_initial_hash.to_string(),
+            block0_time: SystemTime::UNIX_EPOCH + Duration::from_secs(settings.block0_date.0),
+            curr_slot_start_time: None,
+            consensus_version: "bft".to_owned(),
+            fees: settings.fees.clone(),
+            block_content_max_size: 102400,
+            epoch_stability_depth: 10,
+            slot_duration: u64::from(settings.slot_duration),
+            slots_per_epoch: 10,
+            treasury_tax: chain_impl_mockchain::rewards::TaxType::zero(),
+            reward_params: chain_impl_mockchain::rewards::Parameters::zero(),
+            discrimination: settings.discrimination,
+            tx_max_expiry_epochs: settings.transaction_max_expiry_epochs,
+        };
+        serde_json::to_value(dto).unwrap()
+    }
+
+    fn account_state(value: u64, counter: u32) -> serde_json::Value {
+        serde_json::json!({
+            "delegation": { "pools": [] },
+            "value": value,
+            "counters": [counter, 0, 0, 0, 0, 0, 0, 0],
+            "tokens": {},
+            "last_rewards": { "epoch": 0, "reward": 0 },
+        })
+    }
+
+    fn wallet() -> (Wallet, Settings) {
+        let mut wallet =
+            Wallet::recover_free_keys(&hex::decode(ACCOUNT_KEY.trim()).unwrap()).unwrap();
+        let settings = wallet.retrieve_funds(BLOCK0).unwrap();
+        (wallet, settings)
+    }
+
+    #[test]
+    fn reset_state_once_no_fragment_is_pending() {
+        let (mut wallet, mut settings) = wallet();
+        wallet.set_state(Value(1_000_000), [0; 8]).unwrap();
+
+        let proposal = Proposal::new_public(
+            VotePlanId::try_from(&[0u8; 32][..]).unwrap(),
+            0,
+            Options::new_length(3).unwrap(),
+        );
+        let valid_until = ::wallet::time::max_expiration_date(
+            &settings,
+            SystemTime::UNIX_EPOCH + Duration::from_secs(settings.block0_date.0),
+        )
+        .unwrap();
+        wallet
+            .vote(settings.clone(), &proposal, Choice::new(1), &valid_until, 0)
+            .unwrap();
+        let fragment_id = wallet.pending_transactions()[0];
+
+        let mut transport = MockTransport::default();
+        transport.reply("/api/v0/settings", node_settings(&settings));
+        transport.reply(
+            &format!("/api/v0/account/{}", wallet.id()),
+            account_state(1_000_000, 0),
+        );
+        let statuses = format!("/api/v1/fragments/statuses?fragment_ids={}", fragment_id);
+        transport.reply(
+            &statuses,
+            serde_json::json!({ fragment_id.to_string(): "Pending" }),
+        );
+        let mut client = SyncClient::new(format!("{}/", NODE), transport);
+
+        let report = client.sync(&mut wallet, &mut settings).unwrap();
+        assert_eq!(report.pending, vec![fragment_id]);
+        assert!(!report.state_updated);
+        assert_eq!(wallet.spending_counter()[0], 1);
+
+        client.transport.reply(
+            &statuses,
+            serde_json::json!({ fragment_id.to_string(): { "Rejected": { "reason": "test" } } }),
+        );
+
+        let report = client.sync(&mut wallet, &mut settings).unwrap();
+        assert_eq!(report.expired, vec![fragment_id]);
+        assert!(report.state_updated);
+        assert!(wallet.pending_transactions().is_empty());
+        assert_eq!(wallet.spending_counter()[0], 0);
+        assert_eq!(wallet.total_value(), Value(1_000_000));
+    }
+
+    #[test]
+    fn unknown_fragment_expires_after_valid_until() {
+        let (mut wallet, mut settings) = wallet();
+        wallet.set_state(Value(1_000_000), [0; 8]).unwrap();
+
+        let proposal = Proposal::new_public(
+            VotePlanId::try_from(&[0u8; 32][..]).unwrap(),
+            0,
+            Options::new_length(3).unwrap(),
+        );
+        let block0_time = SystemTime::UNIX_EPOCH + Duration::from_secs(settings.block0_date.0);
+        let valid_until = ::wallet::time::max_expiration_date(&settings, block0_time).unwrap();
+        wallet
+            .vote(settings.clone(), &proposal, Choice::new(1), &valid_until, 0)
+            .unwrap();
+        let fragment_id = wallet.pending_transactions()[0];
+
+        let mut transport = MockTransport::default();
+        transport.reply("/api/v0/settings", node_settings(&settings));
+        transport.reply(
+            &format!("/api/v0/account/{}", wallet.id()),
+            account_state(1_000_000, 0),
+        );
+        // the node does not know about the fragment
+        transport.reply(
+            &format!("/api/v1/fragments/statuses?fragment_ids={}", fragment_id),
+            serde_json::json!({}),
+        );
+        let mut client = SyncClient::new(NODE, transport);
+
+        let slot_duration = Duration::from_secs(settings.slot_duration.into());
+        let report = client
+            .sync_at(&mut wallet, &mut settings, block0_time + slot_duration)
+            .unwrap();
+        assert_eq!(report.pending, vec![fragment_id]);
+        assert!(report.expired.is_empty());
+        assert!(!report.state_updated);
+        assert_eq!(wallet.spending_counter()[0], 1);
+
+        let years_later = block0_time + Duration::from_secs(10 * 365 * 24 * 3600);
+        let report = client
+            .sync_at(&mut wallet, &mut settings, years_later)
+            .unwrap();
+        assert_eq!(report.expired, vec![fragment_id]);
+        assert!(report.state_updated);
+        assert!(wallet.pending_transactions().is_empty());
+        assert_eq!(wallet.spending_counter()[0], 0);
+    }
+
+    #[test]
+    fn reject_node_of_another_blockchain() {
+        let (mut wallet, mut settings) = wallet();
+
+        let mut other = settings.clone();
+        other.block0_initial_hash = HeaderId::zero_hash();
+        let mut transport = MockTransport::default();
+        transport.reply("/api/v0/settings", node_settings(&other));
+
+        let error = SyncClient::new(NODE, transport)
+            .sync(&mut wallet, &mut settings)
+            .unwrap_err();
+        assert!(matches!(error.kind(), ErrorKind::WalletSync));
+    }
+}
@@ -1,23 +1,57 @@
-use crate::{Error, Proposal};
-use chain_core::property::Serialize as _;
+use crate::{keystore_create, keystore_unlock, Conversion, Error, KeyScheme, Proposal};
+use chain_core::{
+    packer::Codec,
+    property::{DeserializeFromSlice as _, Serialize as _},
+};
 use chain_crypto::SecretKey;
 use chain_impl_mockchain::{
     account::SpendingCounterIncreasing,
-    block::BlockDate,
+    block::{Block, BlockDate},
     fragment::{Fragment, FragmentId},
     value::Value,
     vote::Choice,
 };
-use wallet::{transaction::WitnessInput, AccountId, Settings};
+use ed25519_bip32::{XPrv, XPRV_SIZE};
+use std::{collections::HashMap, convert::TryInto as _, time::SystemTime};
+use wallet::{
+    transaction::{AccountSecretKey, WitnessInput},
+    AccountId, Bip44Scheme, RindexScheme, Settings, UtxoScheme, UtxoWallet,
+};
 
 /// the wallet
 ///
 /// * use the `recover` function to recover the wallet from the mnemonics/password;
+/// * use `add_utxo_root_key` to add the root key of a legacy (Daedalus or
+///   Yoroi) wallet whose funds were declared in the block0;
 /// * use the `retrieve_funds` to retrieve initial funds (if necessary) from the block0;
 ///   then you can use `total_value` to see how much was recovered from the initial block0;
+/// * use `convert` to move the funds of the legacy wallets to the account.
 ///
 pub struct Wallet {
     account: wallet::Wallet,
+    utxos: Vec<UtxoWallet<Box<dyn UtxoScheme + Send>>>,
+    /// the last date at which the pending transactions can be added to the
+    /// blockchain
+    valid_until: HashMap<FragmentId, BlockDate>,
+}
+
+/// The derivation scheme of a legacy wallet, see `Wallet::add_utxo_root_key`
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecoveryScheme {
+    /// BIP44 derivation of the Yoroi and Icarus wallets
+    Bip44,
+    /// random index derivation of the Daedalus wallets
+    Rindex,
+}
+
+impl From<RecoveryScheme> for KeyScheme {
+    fn from(scheme: RecoveryScheme) -> Self {
+        match scheme {
+            RecoveryScheme::Bip44 => KeyScheme::Bip44,
+            RecoveryScheme::Rindex => KeyScheme::Rindex,
+        }
+    }
 }
 
 impl Wallet {
@@ -48,7 +82,174 @@ impl Wallet {
     pub fn recover_free_keys(account_key: &[u8]) -> Result<Self, Error> {
         let account = wallet::Wallet::new_from_key(SecretKey::from_binary(account_key).unwrap());
 
-        Ok(Wallet { account })
+        Ok(Wallet {
+            account,
+            utxos: Vec::new(),
+            valid_until: HashMap::new(),
+        })
+    }
+
+    /// Recover a wallet from a keystore holding the key of its account
+    ///
+    /// # Errors
+    ///
+    /// The function fails if the keystore is malformed, if it does not hold
+    /// the key of an account or if the password is not the one of the
+    /// keystore.
+    ///
+    pub fn from_keystore(keystore: &str, password: &[u8]) -> Result<Self, Error> {
+        match keystore_unlock(keystore, password)? {
+            (KeyScheme::Ed25519Extended, account_key) => Self::recover_free_keys(&account_key),
+            (KeyScheme::Bip44, _) | (KeyScheme::Rindex, _) => Err(Error::invalid_keystore()),
+        }
+    }
+
+    /// Encrypt the key of the account of the wallet with a password in a new
+    /// keystore
+    ///
+    /// Returns the JSON document of the keystore, created at `created_at`.
+    ///
+    pub fn export_keystore(
+        &self,
+        password: &[u8],
+        created_at: SystemTime,
+    ) -> Result<String, Error> {
+        match self.account.secret_key() {
+            AccountSecretKey::Ed25519Extended(account_key) => keystore_create(
+                KeyScheme::Ed25519Extended,
+                account_key.leak_secret().as_ref(),
+                password,
+                created_at,
+            ),
+            AccountSecretKey::Ed25519(_) => Err(Error::invalid_keystore()),
+        }
+    }
+
+    /// Add the root key of a legacy wallet from a keystore, see
+    /// `add_utxo_root_key`
+    ///
+    /// # Errors
+    ///
+    /// The function fails if the keystore is malformed, if it does not hold
+    /// the root key of a legacy wallet or if the password is not the one of
+    /// the keystore.
+    ///
+    pub fn add_utxo_keystore(&mut self, keystore: &str, password: &[u8]) -> Result<(), Error> {
+        match keystore_unlock(keystore, password)? {
+            (KeyScheme::Bip44, root_key) => {
+                self.add_utxo_root_key(&root_key, RecoveryScheme::Bip44)
+            }
+            (KeyScheme::Rindex, root_key) => {
+                self.add_utxo_root_key(&root_key, RecoveryScheme::Rindex)
+            }
+            (KeyScheme::Ed25519Extended, _) => Err(Error::invalid_keystore()),
+        }
+    }
+
+    /// Add the root key of a legacy wallet, the UTxOs at its addresses are
+    /// then found by `retrieve_funds`
+    ///
+    /// # Parameters
+    ///
+    /// * `root_key`: the 96 bytes extended private key at the root of the
+    ///   derivation tree of the legacy wallet;
+    /// * `scheme`: how the addresses of the legacy wallet are derived.
+    ///
+    /// # Errors
+    ///
+    /// The function fails if `root_key` is not a valid extended private key.
+    ///
+    pub fn add_utxo_root_key(
+        &mut self,
+        root_key: &[u8],
+        scheme: RecoveryScheme,
+    ) -> Result<(), Error> {
+        let root_key: [u8; XPRV_SIZE] = root_key
+            .try_into()
+            .map_err(|_| Error::invalid_input("root_key"))?;
+        let root_key =
+            XPrv::from_bytes_verified(root_key).map_err(|_| Error::wallet_recovering())?;
+
+        let scheme: Box<dyn UtxoScheme + Send> = match scheme {
+            RecoveryScheme::Bip44 => Box::new(Bip44Scheme::from_root_key(root_key)),
+            RecoveryScheme::Rindex => Box::new(RindexScheme::from_root_key(root_key)),
+        };
+        self.utxos.push(UtxoWallet::new(scheme));
+
+        Ok(())
+    }
+
+    /// Retrieve the funds of the wallet from the block0
+    ///
+    /// Returns the settings of the blockchain, needed to build transactions.
+    ///
+    /// # Errors
+    ///
+    /// The function fails if the block0 is not valid.
+    ///
+    pub fn retrieve_funds(&mut self, block0_bytes: &[u8]) -> Result<Settings, Error> {
+        let block0 = Block::deserialize_from_slice(&mut Codec::new(block0_bytes))
+            .map_err(|e| Error::invalid_input("block0").with(e))?;
+        let settings = Settings::new(&block0).map_err(|_| Error::invalid_input("block0"))?;
+
+        for fragment in block0.contents().iter() {
+            let id = fragment.hash();
+            self.check_fragment(&id, fragment)?;
+            self.account.confirm(&id);
+        }
+
+        Ok(settings)
+    }
+
+    /// Move all the funds of the legacy wallets to the account
+    ///
+    /// The returned transactions need to be sent to the blockchain, each of
+    /// them spends up to 255 UTxOs. The UTxOs worth less than the fee of
+    /// their input are not spent and are listed as ignored.
+    ///
+    /// The funds are removed from `utxo_value` and pending on the account
+    /// until the transactions are confirmed with `confirm_transaction`.
+    ///
+    pub fn convert(
+        &mut self,
+        settings: Settings,
+        valid_until: &BlockDate,
+    ) -> Result<Conversion, Error> {
+        let address = self.account.account_id().address(settings.discrimination());
+        let mut fragments = Vec::new();
+        let mut ignored = Vec::new();
+
+        for utxos in self.utxos.iter() {
+            let conversion = utxos
+                .sweep(&settings, *valid_until, &address)
+                .map_err(|e| Error::wallet_conversion().with(e))?;
+            fragments.extend(conversion.transactions);
+            ignored.extend(conversion.ignored);
+        }
+
+        let mut transactions = Vec::with_capacity(fragments.len());
+        for fragment in fragments {
+            let id = fragment.hash();
+            self.check_fragment(&id, &fragment)?;
+            self.valid_until.insert(id, *valid_until);
+            transactions.push(fragment.serialize_as_vec().unwrap());
+        }
+
+        Ok(Conversion {
+            ignored,
+            transactions,
+        })
+    }
+
+    fn check_fragment(&mut self, id: &FragmentId, fragment: &Fragment) -> Result<(), Error> {
+        for utxos in self.utxos.iter_mut() {
+            utxos.check_fragment(id, fragment);
+        }
+        self.account
+            .check_fragment(id, fragment)
+            .map_err(|e| Error::wallet_recovering().with(e))?;
+
+        Ok(())
     }
 
     /// use this function to confirm a transaction has been properly received
@@ -56,6 +257,19 @@ impl Wallet {
     /// This function will automatically update the state of the wallet
     pub fn confirm_transaction(&mut self, id: FragmentId) {
         self.account.confirm(&id);
+        self.valid_until.remove(&id);
+    }
+
+    /// the last date at which a pending transaction can be added to the
+    /// blockchain
+    pub(crate) fn pending_valid_until(&self, id: &FragmentId) -> Option<BlockDate> {
+        self.valid_until.get(id).copied()
+    }
+
+    /// get the transactions of the wallet waiting for a confirmation
+    ///
+    pub fn pending_transactions(&self) -> Vec<FragmentId> {
+        self.account.pending_transactions().copied().collect()
     }
 
     /// get the current spending counter
@@ -86,6 +300,12 @@ impl Wallet {
         self.account.value()
     }
 
+    /// get the value of the legacy wallets that is not converted yet
+    ///
+    pub fn utxo_value(&self) -> Value {
+        self.utxos.iter().map(|utxos| utxos.value()).sum()
+    }
+
     /// Update the wallet's account state.
     ///
     /// The values to update the account state with can be retrieved from a
@@ -101,6 +321,8 @@ impl Wallet {
         value: Value,
         counters: [u32; SpendingCounterIncreasing::LANES],
     ) -> Result<(), Error> {
+        // the pending transactions are dropped with the previous state
+        self.valid_until.clear();
         self.account
             .set_state(
                 value,
@@ -170,6 +392,7 @@ impl Wallet {
         let id = fragment.hash();
 
         account_tx_builder.add_fragment_id(id);
+        self.valid_until.insert(id, *valid_until);
 
         Ok(fragment.serialize_as_vec().unwrap().into_boxed_slice())
     }
@@ -15,8 +15,11 @@ extern "C" {
 }
 
 #[wasm_bindgen]
+#[derive(Clone)]
 pub struct Fragment(pub(crate) FragmentLib);
 
+crate::impl_collection!(Fragments, Fragment);
+
 #[wasm_bindgen]
 impl Fragment {
     pub fn id(&self) -> FragmentId {
@@ -5,15 +5,19 @@ pub use certificates::{
     vote_cast::{ElectionPublicKey, Payload, VoteCast},
     vote_plan::VotePlanId,
 };
+use chain_core::{packer::Codec, property::DeserializeFromSlice};
 use chain_impl_mockchain::account::SpendingCounter;
 use chain_impl_mockchain::{
-    certificate::VoteCast as VoteCastLib, fragment::Fragment as FragmentLib,
+    block::BlockDate, certificate::VoteCast as VoteCastLib, fragment::Fragment as FragmentLib,
 };
-pub use fragment::{Fragment, FragmentId};
+pub use fragment::{Fragment, FragmentId, Fragments};
+use std::time::{Duration, SystemTime};
+pub use sync::{SyncReport, SyncTransport};
 use wasm_bindgen::prelude::*;
 
 mod certificates;
 mod fragment;
+mod sync;
 mod utils;
 
 #[wasm_bindgen]
@@ -36,6 +40,161 @@ impl Settings {
     }
 }
 
+/// Wallet for the account of the user, recovering the funds declared in the
+/// block0 at the addresses of legacy (Daedalus or Yoroi) wallets.
+#[wasm_bindgen]
+pub struct Wallet(wallet_core::Wallet);
+
+/// The derivation scheme of a legacy wallet.
+#[wasm_bindgen]
+#[derive(Clone, Copy)]
+pub enum RecoveryScheme {
+    /// BIP44 derivation of the Yoroi and Icarus wallets
+    Bip44,
+    /// random index derivation of the Daedalus wallets
+    Rindex,
+}
+
+impl From<RecoveryScheme> for wallet_core::RecoveryScheme {
+    fn from(scheme: RecoveryScheme) -> Self {
+        match scheme {
+            RecoveryScheme::Bip44 => Self::Bip44,
+            RecoveryScheme::Rindex => Self::Rindex,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Wallet {
+    /// Recover the wallet from the Ed25519Extended private key of its account.
+    pub fn import_keys(account_key: &[u8]) -> Result<Wallet, JsValue> {
+        wallet_core::Wallet::recover_free_keys(account_key)
+            .map(Wallet)
+            .map_err(|e| JsValue::from(e.to_string()))
+    }
+
+    /// Recover the wallet from a keystore holding the key of its account.
+    pub fn from_keystore(keystore: &str, password: &[u8]) -> Result<Wallet, JsValue> {
+        wallet_core::Wallet::from_keystore(keystore, password)
+            .map(Wallet)
+            .map_err(|e| JsValue::from(e.to_string()))
+    }
+
+    /// Encrypt the key of the account with a password in a new keystore and
+    /// return its JSON document.
+    pub fn export_keystore(&self, password: &[u8]) -> Result<String, JsValue> {
+        self.0
+            .export_keystore(password, now())
+            .map_err(|e| JsValue::from(e.to_string()))
+    }
+
+    /// Add the root key of a legacy wallet from a keystore, see
+    /// `add_utxo_root_key`.
+    pub fn add_utxo_keystore(&mut self, keystore: &str, password: &[u8]) -> Result<(), JsValue> {
+        self.0
+            .add_utxo_keystore(keystore, password)
+            .map_err(|e| JsValue::from(e.to_string()))
+    }
+
+    /// Add the 96 bytes root key of a legacy wallet, its funds are then found
+    /// by `retrieve_funds`.
+    pub fn add_utxo_root_key(
+        &mut self,
+        root_key: &[u8],
+        scheme: RecoveryScheme,
+    ) -> Result<(), JsValue> {
+        self.0
+            .add_utxo_root_key(root_key, scheme.into())
+            .map_err(|e| JsValue::from(e.to_string()))
+    }
+
+    /// Retrieve the funds of the wallet from the block0 and return the
+    /// settings of the blockchain.
+    pub fn retrieve_funds(&mut self, block0: &[u8]) -> Result<Settings, JsValue> {
+        self.0
+            .retrieve_funds(block0)
+            .map(Settings)
+            .map_err(|e| JsValue::from(e.to_string()))
+    }
+
+    /// The value of the account, including the pending conversions.
+    pub fn total_value(&self) -> u64 {
+        self.0.total_value().0
+    }
+
+    /// The value of the legacy wallets that is not converted yet.
+    pub fn utxo_value(&self) -> u64 {
+        self.0.utxo_value().0
+    }
+
+    /// Build the transactions moving the funds of the legacy wallets to the
+    /// account.
+    ///
+    /// The UTxOs that are not worth the fee of their input are not spent and
+    /// remain in `utxo_value`.
+    pub fn convert(
+        &mut self,
+        settings: &Settings,
+        valid_until_epoch: u32,
+        valid_until_slot: u32,
+    ) -> Result<Fragments, JsValue> {
+        let valid_until = BlockDate {
+            epoch: valid_until_epoch,
+            slot_id: valid_until_slot,
+        };
+        let conversion = self
+            .0
+            .convert(settings.0.clone(), &valid_until)
+            .map_err(|e| JsValue::from(e.to_string()))?;
+
+        conversion
+            .transactions()
+            .iter()
+            .map(|bytes| {
+                FragmentLib::deserialize_from_slice(&mut Codec::new(bytes.as_slice()))
+                    .map(Fragment)
+                    .map_err(|e| JsValue::from(e.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(Fragments::from)
+    }
+
+    /// Confirm a transaction of the wallet was added to the blockchain.
+    pub fn confirm_transaction(&mut self, fragment_id: &FragmentId) {
+        self.0.confirm_transaction(fragment_id.0);
+    }
+}
+
+/// Encrypt the 96 bytes root key of a legacy wallet with a password in a new
+/// keystore and return its JSON document.
+#[wasm_bindgen]
+pub fn keystore_create_root_key(
+    root_key: &[u8],
+    scheme: RecoveryScheme,
+    password: &[u8],
+) -> Result<String, JsValue> {
+    let scheme = wallet_core::RecoveryScheme::from(scheme);
+    wallet_core::keystore_create(scheme.into(), root_key, password, now())
+        .map_err(|e| JsValue::from(e.to_string()))
+}
+
+/// Encrypt the key of a keystore with a new password and return the JSON
+/// document of the new keystore.
+#[wasm_bindgen]
+pub fn keystore_change_password(
+    keystore: &str,
+    old_password: &[u8],
+    new_password: &[u8],
+) -> Result<String, JsValue> {
+    wallet_core::keystore_change_password(keystore, old_password, new_password)
+        .map_err(|e| JsValue::from(e.to_string()))
+}
+
+/// `SystemTime::now` is not available in the browser
+fn now() -> SystemTime {
+    SystemTime::UNIX_EPOCH + Duration::from_millis(js_sys::Date::now() as u64)
+}
+
 #[wasm_bindgen]
 impl VoteCastTxBuilder {
     /// Initializing of the VoteCastTxBuilder
@@ -0,0 +1,114 @@
+use crate::{fragment::FragmentIds, FragmentId, Settings, Wallet};
+use wallet_core::sync::{SyncClient, Transport, TransportError};
+use wasm_bindgen::{prelude::*, JsCast};
+
+#[wasm_bindgen(typescript_custom_section)]
+const SYNC_TRANSPORT: &str = r#"
+/**
+ * HTTP client of the host used to synchronise the wallet.
+ *
+ * `get` sends a GET request to `url` and returns the HTTP status and the body
+ * of the response. It is called synchronously, so it must not return a
+ * promise. Throwing reports a request that could not be sent.
+ */
+export interface SyncTransport {
+  get(url: string): { status: number; body: Uint8Array };
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "SyncTransport")]
+    pub type SyncTransport;
+
+    #[wasm_bindgen(method, catch)]
+    fn get(this: &SyncTransport, url: &str) -> Result<SyncResponse, JsValue>;
+
+    type SyncResponse;
+
+    #[wasm_bindgen(method, getter)]
+    fn status(this: &SyncResponse) -> u16;
+
+    #[wasm_bindgen(method, getter)]
+    fn body(this: &SyncResponse) -> Option<Vec<u8>>;
+}
+
+impl Transport for &SyncTransport {
+    fn get(&mut self, url: &str) -> Result<Vec<u8>, TransportError> {
+        let response = SyncTransport::get(self, url).map_err(|e| {
+            let message = e
+                .dyn_ref::<js_sys::Error>()
+                .map(|e| String::from(e.message()))
+                .unwrap_or_else(|| format!("{:?}", e));
+            TransportError::Request(message.into())
+        })?;
+
+        match response.status() {
+            200..=299 => Ok(response.body().unwrap_or_default()),
+            status => Err(TransportError::Status(status)),
+        }
+    }
+}
+
+/// What a synchronisation changed in the wallet.
+#[wasm_bindgen]
+pub struct SyncReport(wallet_core::sync::SyncReport);
+
+#[wasm_bindgen]
+impl SyncReport {
+    /// The pending transactions found in a block.
+    pub fn confirmed(&self) -> FragmentIds {
+        fragment_ids(&self.0.confirmed)
+    }
+
+    /// The pending transactions rejected by the node, or unknown to it past
+    /// their `valid_until` date, they will never be added to the blockchain.
+    pub fn expired(&self) -> FragmentIds {
+        fragment_ids(&self.0.expired)
+    }
+
+    /// The transactions still waiting in the mempool of the node, or unknown
+    /// to it but still valid.
+    pub fn pending(&self) -> FragmentIds {
+        fragment_ids(&self.0.pending)
+    }
+
+    /// Whether the value and spending counters of the wallet were reset to
+    /// the state of its account on the node.
+    pub fn state_updated(&self) -> bool {
+        self.0.state_updated
+    }
+}
+
+fn fragment_ids(ids: &[wallet_core::FragmentId]) -> FragmentIds {
+    ids.iter()
+        .map(|id| JsValue::from(FragmentId(*id)))
+        .collect::<js_sys::Array>()
+        .unchecked_into()
+}
+
+#[wasm_bindgen]
+impl Wallet {
+    /// Update the wallet and its settings from the node at `node_url`, the
+    /// address of its REST API without the `/api` path.
+    ///
+    /// The fees and the maximum expiry of the transactions are taken from
+    /// the settings of the node. The pending transactions found in a block
+    /// are confirmed and, once no transaction is waiting in the mempool of
+    /// the node anymore, the value and spending counters of the wallet are
+    /// reset to the ones of its account.
+    ///
+    /// The requests are sent with the `get` method of `transport`, which
+    /// must return synchronously.
+    pub fn sync(
+        &mut self,
+        settings: &mut Settings,
+        node_url: &str,
+        transport: &SyncTransport,
+    ) -> Result<SyncReport, JsValue> {
+        SyncClient::new(node_url, transport)
+            .sync_at(&mut self.0, &mut settings.0, crate::now())
+            .map(SyncReport)
+            .map_err(|e| JsValue::from(e.to_string()))
+    }
+}
@@ -0,0 +1,482 @@
+//! Password protected storage of the secret keys of a wallet.
+//!
+//! A keystore is a JSON document holding one secret key, encrypted with a
+//! key derived from a password, and the metadata identifying it:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "account_id": "<32 hex encoded bytes>",
+//!   "scheme": "ed25519-extended",
+//!   "created_at": 1660000000,
+//!   "crypto": {
+//!     "kdf": "pbkdf2-hmac-sha512",
+//!     "iterations": 100000,
+//!     "salt": "<16 hex encoded bytes>",
+//!     "cipher": "chacha20-poly1305",
+//!     "nonce": "<12 hex encoded bytes>",
+//!     "ciphertext": "<hex encoded bytes>",
+//!     "tag": "<16 hex encoded bytes>"
+//!   }
+//! }
+//! ```
+//!
+//! * `scheme` is `ed25519-extended` for the 64 bytes key of an account,
+//!   `bip44` or `rindex` for the 96 bytes root key of a legacy Yoroi or
+//!   Daedalus wallet;
+//! * `account_id` is the public key of the secret key, which is the account
+//!   id for the key of an account;
+//! * `created_at` is the creation time in seconds since the Unix epoch.
+//!
+//! The encryption key is derived from the password with PBKDF2 HMAC-SHA512,
+//! with at most [`MAX_ITERATIONS`] iterations, and the secret key is
+//! encrypted with ChaCha20-Poly1305. The version, the scheme, the account id
+//! and the creation time are authenticated with the secret key: they can't be
+//! modified without the password.
+
+use crate::ScrubbedBytes;
+use chain_crypto::{Ed25519Extended, SecretKey};
+use cryptoxide::{chacha20poly1305::ChaCha20Poly1305, hmac::Hmac, pbkdf2::pbkdf2, sha2::Sha512};
+use ed25519_bip32::{XPrv, XPRV_SIZE};
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::{
+    convert::TryInto as _,
+    time::{Duration, SystemTime},
+};
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+/// the version of the keystore format
+pub const KEYSTORE_VERSION: u8 = 1;
+/// the default number of iterations of the key derivation function
+pub const DEFAULT_ITERATIONS: u32 = 100_000;
+/// the maximum number of iterations of the key derivation function, so that
+/// a crafted keystore cannot make unlocking it run for hours
+pub const MAX_ITERATIONS: u32 = 10_000_000;
+
+const KDF: &str = "pbkdf2-hmac-sha512";
+const CIPHER: &str = "chacha20-poly1305";
+const SALT_SIZE: usize = 16;
+const NONCE_SIZE: usize = 12;
+const TAG_SIZE: usize = 16;
+const ACCOUNT_ID_SIZE: usize = 32;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("unsupported keystore version {0}")]
+    UnsupportedVersion(u8),
+    #[error("unsupported key derivation function `{0}`")]
+    UnsupportedKdf(String),
+    #[error("unsupported cipher `{0}`")]
+    UnsupportedCipher(String),
+    #[error("invalid length of the {0}")]
+    InvalidLength(&'static str),
+    #[error("invalid secret key for the {0:?} scheme")]
+    InvalidSecretKey(KeyScheme),
+    #[error("the number of iterations of the key derivation function can't be zero")]
+    NoIterations,
+    #[error("{0} iterations of the key derivation function is more than the maximum of {max}", max = MAX_ITERATIONS)]
+    TooManyIterations(u32),
+    #[error("wrong password")]
+    AuthenticationFailed,
+    #[error("malformed keystore")]
+    Malformed(#[from] serde_json::Error),
+}
+
+/// what the secret key of a keystore is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum KeyScheme {
+    /// the 64 bytes extended key of an account
+    Ed25519Extended,
+    /// the 96 bytes root key of a legacy Yoroi (BIP44) wallet
+    Bip44,
+    /// the 96 bytes root key of a legacy Daedalus (random index) wallet
+    Rindex,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Keystore {
+    version: u8,
+    #[serde(with = "hex_bytes")]
+    account_id: Vec<u8>,
+    scheme: KeyScheme,
+    created_at: u64,
+    crypto: Crypto,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Crypto {
+    kdf: String,
+    iterations: u32,
+    #[serde(with = "hex_bytes")]
+    salt: Vec<u8>,
+    cipher: String,
+    #[serde(with = "hex_bytes")]
+    nonce: Vec<u8>,
+    #[serde(with = "hex_bytes")]
+    ciphertext: Vec<u8>,
+    #[serde(with = "hex_bytes")]
+    tag: Vec<u8>,
+}
+
+impl KeyScheme {
+    fn tag(self) -> u8 {
+        match self {
+            Self::Ed25519Extended => 0,
+            Self::Bip44 => 1,
+            Self::Rindex => 2,
+        }
+    }
+
+    /// the public key of the secret key
+    fn account_id(self, secret_key: &[u8]) -> Result<Vec<u8>, Error> {
+        match self {
+            Self::Ed25519Extended => SecretKey::<Ed25519Extended>::from_binary(secret_key)
+                .map(|key| key.to_public().as_ref().to_vec())
+                .map_err(|_| Error::InvalidSecretKey(self)),
+            Self::Bip44 | Self::Rindex => {
+                let bytes: [u8; XPRV_SIZE] = secret_key
+                    .try_into()
+                    .map_err(|_| Error::InvalidSecretKey(self))?;
+                XPrv::from_bytes_verified(bytes)
+                    .map(|key| key.public().public_key_slice().to_vec())
+                    .map_err(|_| Error::InvalidSecretKey(self))
+            }
+        }
+    }
+}
+
+impl Keystore {
+    /// encrypt `secret_key` with `password`, deriving the encryption key with
+    /// [`DEFAULT_ITERATIONS`]
+    pub fn create<R>(
+        scheme: KeyScheme,
+        secret_key: &[u8],
+        password: &[u8],
+        created_at: SystemTime,
+        rng: R,
+    ) -> Result<Self, Error>
+    where
+        R: RngCore + CryptoRng,
+    {
+        Self::create_with_iterations(
+            scheme,
+            secret_key,
+            password,
+            created_at,
+            DEFAULT_ITERATIONS,
+            rng,
+        )
+    }
+
+    pub fn create_with_iterations<R>(
+        scheme: KeyScheme,
+        secret_key: &[u8],
+        password: &[u8],
+        created_at: SystemTime,
+        iterations: u32,
+        mut rng: R,
+    ) -> Result<Self, Error>
+    where
+        R: RngCore + CryptoRng,
+    {
+        check_iterations(iterations)?;
+
+        let account_id = scheme.account_id(secret_key)?;
+        let created_at = created_at
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        let mut salt = vec![0; SALT_SIZE];
+        rng.fill_bytes(&mut salt);
+        let mut nonce = vec![0; NONCE_SIZE];
+        rng.fill_bytes(&mut nonce);
+
+        let mut keystore = Self {
+            version: KEYSTORE_VERSION,
+            account_id,
+            scheme,
+            created_at,
+            crypto: Crypto {
+                kdf: KDF.to_owned(),
+                iterations,
+                salt,
+                cipher: CIPHER.to_owned(),
+                nonce,
+                ciphertext: vec![0; secret_key.len()],
+                tag: vec![0; TAG_SIZE],
+            },
+        };
+
+        let key = keystore.derive_key(password);
+        let mut cipher =
+            ChaCha20Poly1305::new(&*key, &keystore.crypto.nonce, &keystore.associated_data());
+        cipher.encrypt(
+            secret_key,
+            &mut keystore.crypto.ciphertext,
+            &mut keystore.crypto.tag,
+        );
+
+        Ok(keystore)
+    }
+
+    /// decrypt the secret key of the keystore
+    pub fn unlock(&self, password: &[u8]) -> Result<ScrubbedBytes, Error> {
+        self.check()?;
+
+        let key = self.derive_key(password);
+        let mut cipher = ChaCha20Poly1305::new(&*key, &self.crypto.nonce, &self.associated_data());
+        let mut secret_key = ScrubbedBytes::from(vec![0; self.crypto.ciphertext.len()]);
+        if cipher.decrypt(&self.crypto.ciphertext, &mut secret_key, &self.crypto.tag) {
+            Ok(secret_key)
+        } else {
+            Err(Error::AuthenticationFailed)
+        }
+    }
+
+    /// re-encrypt the secret key with `new_password`, keeping the metadata
+    /// and the number of iterations of the key derivation function
+    pub fn change_password<R>(
+        &self,
+        old_password: &[u8],
+        new_password: &[u8],
+        rng: R,
+    ) -> Result<Self, Error>
+    where
+        R: RngCore + CryptoRng,
+    {
+        let secret_key = self.unlock(old_password)?;
+        Self::create_with_iterations(
+            self.scheme,
+            &secret_key,
+            new_password,
+            self.created_at(),
+            self.crypto.iterations,
+            rng,
+        )
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        let keystore: Self = serde_json::from_str(json)?;
+        keystore.check()?;
+        Ok(keystore)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("a keystore is always serializable")
+    }
+
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    pub fn account_id(&self) -> &[u8] {
+        &self.account_id
+    }
+
+    pub fn scheme(&self) -> KeyScheme {
+        self.scheme
+    }
+
+    pub fn created_at(&self) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(self.created_at)
+    }
+
+    fn check(&self) -> Result<(), Error> {
+        if self.version != KEYSTORE_VERSION {
+            return Err(Error::UnsupportedVersion(self.version));
+        }
+        if self.crypto.kdf != KDF {
+            return Err(Error::UnsupportedKdf(self.crypto.kdf.clone()));
+        }
+        if self.crypto.cipher != CIPHER {
+            return Err(Error::UnsupportedCipher(self.crypto.cipher.clone()));
+        }
+        check_iterations(self.crypto.iterations)?;
+
+        let lengths = [
+            ("account id", self.account_id.len(), ACCOUNT_ID_SIZE),
+            ("salt", self.crypto.salt.len(), SALT_SIZE),
+            ("nonce", self.crypto.nonce.len(), NONCE_SIZE),
+            ("tag", self.crypto.tag.len(), TAG_SIZE),
+        ];
+        for (name, length, expected) in lengths.iter() {
+            if length != expected {
+                return Err(Error::InvalidLength(*name));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn derive_key(&self, password: &[u8]) -> Zeroizing<[u8; 32]> {
+        let mut key = Zeroizing::new([0; 32]);
+        let mut mac = Hmac::new(Sha512::new(), password);
+        pbkdf2(
+            &mut mac,
+            &self.crypto.salt,
+            self.crypto.iterations,
+            &mut *key,
+        );
+        key
+    }
+
+    /// the metadata authenticated with the secret key
+    fn associated_data(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(2 + ACCOUNT_ID_SIZE + 8);
+        data.push(self.version);
+        data.push(self.scheme.tag());
+        data.extend_from_slice(&self.account_id);
+        data.extend_from_slice(&self.created_at.to_be_bytes());
+        data
+    }
+}
+
+mod hex_bytes {
+    use serde::{de::Error as _, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&hex::encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        hex::decode(hex).map_err(D::Error::custom)
+    }
+}
+
+fn check_iterations(iterations: u32) -> Result<(), Error> {
+    if iterations == 0 {
+        Err(Error::NoIterations)
+    } else if iterations > MAX_ITERATIONS {
+        Err(Error::TooManyIterations(iterations))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaChaRng;
+
+    const ITERATIONS: u32 = 16;
+
+    fn account_key() -> Vec<u8> {
+        SecretKey::<Ed25519Extended>::generate(ChaChaRng::seed_from_u64(0))
+            .leak_secret()
+            .as_ref()
+            .to_vec()
+    }
+
+    fn keystore(password: &[u8]) -> Keystore {
+        Keystore::create_with_iterations(
+            KeyScheme::Ed25519Extended,
+            &account_key(),
+            password,
+            SystemTime::UNIX_EPOCH + Duration::from_secs(1_660_000_000),
+            ITERATIONS,
+            ChaChaRng::seed_from_u64(1),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn create_unlock() {
+        let keystore = keystore(b"password");
+
+        let key = SecretKey::<Ed25519Extended>::from_binary(&account_key()).unwrap();
+        assert_eq!(keystore.account_id(), key.to_public().as_ref());
+        assert_eq!(&*keystore.unlock(b"password").unwrap(), &account_key()[..]);
+        assert!(matches!(
+            keystore.unlock(b"wrong password"),
+            Err(Error::AuthenticationFailed)
+        ));
+    }
+
+    #[test]
+    fn json_roundtrip() {
+        let keystore = keystore(b"password");
+        let decoded = Keystore::from_json(&keystore.to_json()).unwrap();
+
+        assert_eq!(decoded.scheme(), KeyScheme::Ed25519Extended);
+        assert_eq!(decoded.created_at(), keystore.created_at());
+        assert_eq!(&*decoded.unlock(b"password").unwrap(), &account_key()[..]);
+    }
+
+    #[test]
+    fn change_password() {
+        let keystore = keystore(b"password")
+            .change_password(b"password", b"new password", ChaChaRng::seed_from_u64(2))
+            .unwrap();
+
+        assert!(keystore.unlock(b"password").is_err());
+        assert_eq!(
+            &*keystore.unlock(b"new password").unwrap(),
+            &account_key()[..]
+        );
+    }
+
+    #[test]
+    fn metadata_is_authenticated() {
+        let mut keystore = keystore(b"password");
+        keystore.created_at += 1;
+
+        assert!(matches!(
+            keystore.unlock(b"password"),
+            Err(Error::AuthenticationFailed)
+        ));
+    }
+
+    #[test]
+    fn iterations_are_bounded() {
+        assert!(matches!(
+            Keystore::create_with_iterations(
+                KeyScheme::Ed25519Extended,
+                &account_key(),
+                b"password",
+                SystemTime::now(),
+                MAX_ITERATIONS + 1,
+                ChaChaRng::seed_from_u64(1),
+            ),
+            Err(Error::TooManyIterations(_))
+        ));
+
+        // checked before deriving the key of a crafted keystore
+        let mut keystore = keystore(b"password");
+        keystore.crypto.iterations = u32::MAX;
+        assert!(matches!(
+            keystore.unlock(b"password"),
+            Err(Error::TooManyIterations(u32::MAX))
+        ));
+    }
+
+    #[test]
+    fn legacy_root_key() {
+        let root_key = [0x48; XPRV_SIZE];
+        assert!(Keystore::create_with_iterations(
+            KeyScheme::Bip44,
+            &root_key,
+            b"password",
+            SystemTime::now(),
+            ITERATIONS,
+            ChaChaRng::seed_from_u64(1),
+        )
+        .is_ok());
+        assert!(matches!(
+            Keystore::create_with_iterations(
+                KeyScheme::Rindex,
+                &account_key(),
+                b"password",
+                SystemTime::now(),
+                ITERATIONS,
+                ChaChaRng::seed_from_u64(1),
+            ),
+            Err(Error::InvalidSecretKey(KeyScheme::Rindex))
+        ));
+    }
+}
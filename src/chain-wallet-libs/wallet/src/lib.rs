@@ -2,16 +2,24 @@
 
 mod account;
 mod blockchain;
+pub mod keystore;
 mod password;
 mod scheme;
 mod states;
 pub mod time;
 pub mod transaction;
+mod utxo;
 
 pub use self::{
     account::{EitherAccount, Error, Wallet},
     blockchain::Settings,
     password::{Password, ScrubbedBytes},
+    scheme::{
+        bip44::{Bip44Scheme, DEFAULT_GAP_LIMIT},
+        rindex::RindexScheme,
+        SpendingKey, UtxoScheme,
+    },
     transaction::{AccountWitnessBuilder, TransactionBuilder},
+    utxo::{Conversion, UtxoWallet},
 };
 pub use hdkeygen::account::AccountId;
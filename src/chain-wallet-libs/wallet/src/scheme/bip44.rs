@@ -0,0 +1,122 @@
+//! BIP44 wallets (Yoroi, Icarus): the addresses are derived sequentially on
+//! an external and an internal chain of an account.
+
+use super::{SpendingKey, UtxoScheme};
+use cardano_legacy_address::{Addr, ExtendedAddr};
+use chain_addr::{Address, Kind};
+use chain_path_derivation::{
+    bip44::{self, Bip44},
+    Derivation, HardDerivation, SoftDerivation,
+};
+use ed25519_bip32::{DerivationScheme, XPrv, XPub};
+use hdkeygen::Key;
+
+/// the number of consecutive unused addresses after which the discovery
+/// of a chain stops, as used by the BIP44 wallets
+pub const DEFAULT_GAP_LIMIT: u32 = 20;
+
+/// recognises the addresses of an account of a BIP44 wallet
+pub struct Bip44Scheme {
+    gap_limit: u32,
+    external: Chain,
+    internal: Chain,
+}
+
+struct Chain {
+    change: Key<XPrv, Bip44<bip44::Change>>,
+    addresses: Vec<(Key<XPrv, Bip44<bip44::Address>>, XPub)>,
+}
+
+impl Bip44Scheme {
+    /// use the first account of the wallet of the given root key
+    pub fn from_root_key(root: XPrv) -> Self {
+        const FIRST_ACCOUNT: HardDerivation =
+            HardDerivation::new_unchecked(Derivation::new(0x8000_0000));
+
+        let root = Key::new_unchecked(root, bip44::new(), DerivationScheme::V2);
+        Self::from_account_key(root.bip44().cardano().account(FIRST_ACCOUNT))
+    }
+
+    pub fn from_account_key(account: Key<XPrv, Bip44<bip44::Account>>) -> Self {
+        Self::with_gap_limit(account, DEFAULT_GAP_LIMIT)
+    }
+
+    pub fn with_gap_limit(account: Key<XPrv, Bip44<bip44::Account>>, gap_limit: u32) -> Self {
+        let mut external = Chain::new(account.external());
+        let mut internal = Chain::new(account.internal());
+        external.extend(gap_limit);
+        internal.extend(gap_limit);
+        Self {
+            gap_limit,
+            external,
+            internal,
+        }
+    }
+
+    fn check<F>(&mut self, matches: F) -> Option<SpendingKey>
+    where
+        F: Fn(&XPub) -> bool,
+    {
+        let gap_limit = self.gap_limit;
+        self.external
+            .check(&matches, gap_limit)
+            .or_else(|| self.internal.check(&matches, gap_limit))
+    }
+}
+
+impl Chain {
+    fn new(change: Key<XPrv, Bip44<bip44::Change>>) -> Self {
+        Self {
+            change,
+            addresses: Vec::new(),
+        }
+    }
+
+    /// derive the addresses up to `len`
+    fn extend(&mut self, len: u32) {
+        for index in self.addresses.len() as u32..len {
+            let key = self
+                .change
+                .address(SoftDerivation::new_unchecked(Derivation::new(index)));
+            let public = key.public().public_key().clone();
+            self.addresses.push((key, public));
+        }
+    }
+
+    fn check<F>(&mut self, matches: F, gap_limit: u32) -> Option<SpendingKey>
+    where
+        F: Fn(&XPub) -> bool,
+    {
+        let index = self
+            .addresses
+            .iter()
+            .position(|(_, public)| matches(public))?;
+        let key = self.addresses[index].0.clone().coerce_unchecked();
+
+        // keep `gap_limit` addresses to discover after the last used one
+        self.extend(index as u32 + 1 + gap_limit);
+
+        Some(key)
+    }
+}
+
+impl UtxoScheme for Bip44Scheme {
+    fn check_legacy_address(&mut self, address: &Addr) -> Option<SpendingKey> {
+        let attributes = address.deconstruct().attributes;
+        // the legacy BIP44 addresses do not embed their derivation path
+        if attributes.derivation_path.is_some() {
+            return None;
+        }
+
+        self.check(|public| &ExtendedAddr::new(public, attributes.clone()).to_address() == address)
+    }
+
+    fn check_address(&mut self, address: &Address) -> Option<SpendingKey> {
+        let pk = match address.kind() {
+            Kind::Single(pk) | Kind::Group(pk, _) => pk,
+            _ => return None,
+        };
+
+        self.check(|public| public.public_key_slice() == pk.as_ref())
+    }
+}
@@ -1,7 +1,38 @@
+pub mod bip44;
+pub mod rindex;
+
+use cardano_legacy_address::Addr;
+use chain_addr::Address;
 use chain_impl_mockchain::{
     fragment::Fragment,
     transaction::{Input, Output, Witness},
 };
+use chain_path_derivation::AnyScheme;
+use ed25519_bip32::XPrv;
+use hdkeygen::Key;
+
+/// the key spending the UTxOs of an address of a wallet
+pub type SpendingKey = Key<XPrv, AnyScheme>;
+
+/// Recognises the addresses of a wallet and finds the key to spend the
+/// UTxOs sent to them.
+pub trait UtxoScheme {
+    /// check an address of the legacy format, as used in the UTxO
+    /// declarations of the block0
+    fn check_legacy_address(&mut self, address: &Addr) -> Option<SpendingKey>;
+
+    fn check_address(&mut self, address: &Address) -> Option<SpendingKey>;
+}
+
+impl<S: UtxoScheme + ?Sized> UtxoScheme for Box<S> {
+    fn check_legacy_address(&mut self, address: &Addr) -> Option<SpendingKey> {
+        (**self).check_legacy_address(address)
+    }
+
+    fn check_address(&mut self, address: &Address) -> Option<SpendingKey> {
+        (**self).check_address(address)
+    }
+}
 
 pub(crate) fn on_tx_output<FO>(fragment: &Fragment, on_output: FO)
 where
@@ -24,6 +55,7 @@ where
         Fragment::VoteTally(tx) => for_each_output(tx, on_output),
         Fragment::MintToken(tx) => for_each_output(tx, on_output),
         Fragment::EvmMapping(tx) => for_each_output(tx, on_output),
+        Fragment::MultisigRegistration(tx) => for_each_output(tx, on_output),
     }
 }
 
@@ -105,5 +137,10 @@ where
             .inputs_and_witnesses()
             .iter()
             .for_each(on_input),
+        Fragment::MultisigRegistration(tx) => tx
+            .as_slice()
+            .inputs_and_witnesses()
+            .iter()
+            .for_each(on_input),
     }
 }
@@ -0,0 +1,52 @@
+//! Random index wallets (Daedalus): the addresses embed their derivation
+//! path, encrypted with a key derived from the root public key.
+
+use super::{SpendingKey, UtxoScheme};
+use cardano_legacy_address::{Addr, AddressMatchXPub};
+use chain_addr::Address;
+use chain_path_derivation::rindex::{self, Rindex};
+use ed25519_bip32::{DerivationScheme, XPrv};
+use hdkeygen::{
+    rindex::{decode_derivation_path, HdKey},
+    Key,
+};
+
+/// the number of derivations of an address: `m / account / address`
+const DERIVATION_PATH_LENGTH: usize = 2;
+
+/// recognises the legacy addresses of a random index wallet
+pub struct RindexScheme {
+    root: Key<XPrv, Rindex<rindex::Root>>,
+    hd_key: HdKey,
+}
+
+impl RindexScheme {
+    pub fn from_root_key(root: XPrv) -> Self {
+        let root = Key::new_unchecked(root, rindex::new(), DerivationScheme::V1);
+        let hd_key = root.hd_key();
+        Self { root, hd_key }
+    }
+}
+
+impl UtxoScheme for RindexScheme {
+    fn check_legacy_address(&mut self, address: &Addr) -> Option<SpendingKey> {
+        let payload = address.deconstruct().attributes.derivation_path?;
+        let path = decode_derivation_path(&self.hd_key.decrypt(&payload).ok()?)?;
+        if path.len() != DERIVATION_PATH_LENGTH {
+            return None;
+        }
+
+        // the payload only proves the address was generated with the wallet's
+        // key, the derived key still needs to match the address
+        let key = self.root.key(&path.coerce_unchecked());
+        match address.identical_with_xpub(key.public().public_key()) {
+            AddressMatchXPub::Yes => Some(key.coerce_unchecked()),
+            AddressMatchXPub::No => None,
+        }
+    }
+
+    fn check_address(&mut self, _address: &Address) -> Option<SpendingKey> {
+        // random index wallets predate the current address format
+        None
+    }
+}
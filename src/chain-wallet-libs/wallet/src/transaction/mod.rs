@@ -5,5 +5,7 @@ mod witness_builder;
 pub use self::{
     builder::{AddInputStatus, Error, TransactionBuilder, WitnessInput},
     strategy::{InputStrategy, OutputStrategy, Strategy, StrategyBuilder, DEFAULT_STRATEGIES},
-    witness_builder::{AccountSecretKey, AccountWitnessBuilder},
+    witness_builder::{
+        AccountSecretKey, AccountWitnessBuilder, OldUtxoWitnessBuilder, UtxoWitnessBuilder,
+    },
 };
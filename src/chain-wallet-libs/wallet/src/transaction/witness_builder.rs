@@ -1,4 +1,4 @@
-use chain_crypto::{Ed25519, Ed25519Extended, SecretKey, Signature};
+use chain_crypto::{Ed25519, Ed25519Extended, PublicKey, SecretKey, Signature};
 use chain_impl_mockchain::{
     account,
     accounting::account::SpendingCounter,
@@ -6,7 +6,7 @@ use chain_impl_mockchain::{
     key::SpendingSignature,
     transaction::{TransactionSignDataHash, Witness, WitnessAccountData, WitnessUtxoData},
 };
-use ed25519_bip32::XPrv;
+use ed25519_bip32::{XPrv, XPub};
 use hdkeygen::Key;
 
 pub trait WitnessBuilder<SecretKey, WitnessData: AsRef<[u8]>, Signature> {
@@ -23,6 +23,13 @@ pub trait WitnessBuilder<SecretKey, WitnessData: AsRef<[u8]>, Signature> {
 
 pub struct UtxoWitnessBuilder;
 
+/// witness of a UTxO declared in the block0 at a legacy address, the public
+/// key and chain code are checked against the address
+pub struct OldUtxoWitnessBuilder {
+    public_key: PublicKey<Ed25519>,
+    chain_code: [u8; 32],
+}
+
 #[derive(Clone)]
 pub enum AccountSecretKey {
     Ed25519(SecretKey<Ed25519>),
@@ -58,6 +65,42 @@ impl<D> WitnessBuilder<Key<XPrv, D>, WitnessUtxoData, SpendingSignature<WitnessU
     }
 }
 
+impl OldUtxoWitnessBuilder {
+    pub fn new<D>(key: &Key<XPub, D>) -> Self {
+        let xpub = key.public_key();
+        Self {
+            public_key: key.pk(),
+            chain_code: *xpub.chain_code(),
+        }
+    }
+}
+
+impl<D> WitnessBuilder<Key<XPrv, D>, WitnessUtxoData, SpendingSignature<WitnessUtxoData>>
+    for OldUtxoWitnessBuilder
+{
+    fn build_sign_data(
+        &self,
+        block0: &HeaderId,
+        sign_data_hash: &TransactionSignDataHash,
+    ) -> WitnessUtxoData {
+        Witness::new_old_utxo_data(block0, sign_data_hash)
+    }
+
+    fn sign(&self, witness_data: WitnessUtxoData, secret_key: Key<XPrv, D>) -> Witness {
+        let signature = Signature::from_binary(
+            secret_key
+                .sign::<WitnessUtxoData, &[u8]>(witness_data.as_ref())
+                .as_ref(),
+        )
+        .unwrap();
+        Witness::OldUtxo(self.public_key.clone(), self.chain_code, signature)
+    }
+
+    fn build(&self, signature: SpendingSignature<WitnessUtxoData>) -> Witness {
+        Witness::OldUtxo(self.public_key.clone(), self.chain_code, signature)
+    }
+}
+
 impl WitnessBuilder<SecretKey<Ed25519Extended>, WitnessUtxoData, SpendingSignature<WitnessUtxoData>>
     for UtxoWitnessBuilder
 {
@@ -0,0 +1,156 @@
+use crate::{
+    scheme::{on_tx_input_and_witnesses, on_tx_output, SpendingKey, UtxoScheme},
+    transaction::{Error, OldUtxoWitnessBuilder, UtxoWitnessBuilder, WitnessInput},
+    Settings, TransactionBuilder,
+};
+use chain_addr::Address;
+use chain_impl_mockchain::{
+    block::BlockDate,
+    fragment::{Fragment, FragmentId},
+    key::SpendingSignature,
+    transaction::{Balance, Input, InputEnum, NoExtra, Output, UtxoPointer, WitnessUtxoData},
+    value::Value,
+};
+use std::collections::BTreeMap;
+
+/// the maximum number of inputs of a transaction
+const MAX_INPUTS: usize = 255;
+
+/// A wallet holding UTxOs, for example funds declared in the block0 at
+/// the legacy addresses of a Daedalus or Yoroi wallet.
+///
+/// The UTxOs are found by checking the fragments with
+/// [`UtxoWallet::check_fragment`], starting with the ones of the block0.
+/// They are then moved to an account with [`UtxoWallet::sweep`].
+pub struct UtxoWallet<S> {
+    scheme: S,
+    utxos: BTreeMap<UtxoPointer, OwnedUtxo>,
+}
+
+struct OwnedUtxo {
+    key: SpendingKey,
+    /// declared in the block0 at a legacy address
+    legacy: bool,
+}
+
+/// The transactions moving the funds of a [`UtxoWallet`].
+pub struct Conversion {
+    pub transactions: Vec<Fragment>,
+    /// the UTxOs that are not worth spending, their value does not cover
+    /// the fee of their input
+    pub ignored: Vec<Input>,
+}
+
+impl<S: UtxoScheme> UtxoWallet<S> {
+    pub fn new(scheme: S) -> Self {
+        Self {
+            scheme,
+            utxos: BTreeMap::new(),
+        }
+    }
+
+    /// the total value of the UTxOs of the wallet
+    pub fn value(&self) -> Value {
+        self.utxos.keys().map(|pointer| pointer.value).sum()
+    }
+
+    pub fn utxos(&self) -> impl Iterator<Item = &UtxoPointer> {
+        self.utxos.keys()
+    }
+
+    /// Add the UTxOs the fragment sends to the wallet and remove the ones it
+    /// spends.
+    ///
+    /// Returns `true` if the fragment concerns the wallet.
+    pub fn check_fragment(&mut self, fragment_id: &FragmentId, fragment: &Fragment) -> bool {
+        let mut found = false;
+
+        if let Fragment::OldUtxoDeclaration(declaration) = fragment {
+            for (index, (address, value)) in declaration.addrs.iter().enumerate() {
+                if let Some(key) = self.scheme.check_legacy_address(address) {
+                    let pointer = UtxoPointer::new(*fragment_id, index as u8, *value);
+                    self.utxos.insert(pointer, OwnedUtxo { key, legacy: true });
+                    found = true;
+                }
+            }
+            return found;
+        }
+
+        on_tx_input_and_witnesses(fragment, |(input, _)| {
+            if let InputEnum::UtxoInput(pointer) = input.to_enum() {
+                found |= self.utxos.remove(&pointer).is_some();
+            }
+        });
+        on_tx_output(fragment, |(index, output)| {
+            if let Some(key) = self.scheme.check_address(&output.address) {
+                let pointer = UtxoPointer::new(*fragment_id, index as u8, output.value);
+                self.utxos.insert(pointer, OwnedUtxo { key, legacy: false });
+                found = true;
+            }
+        });
+
+        found
+    }
+
+    /// Build the transactions sending all the funds of the wallet to
+    /// `address`, each of them spending up to 255 UTxOs.
+    ///
+    /// The UTxOs stay in the wallet until the transactions are checked with
+    /// [`UtxoWallet::check_fragment`].
+    pub fn sweep(
+        &self,
+        settings: &Settings,
+        valid_until: BlockDate,
+        address: &Address,
+    ) -> Result<Conversion, Error> {
+        let (spendable, ignored): (Vec<_>, Vec<_>) = self
+            .utxos
+            .iter()
+            .partition(|(pointer, _)| settings.is_input_worth(&Input::from_utxo(**pointer)));
+
+        let mut conversion = Conversion {
+            transactions: Vec::new(),
+            ignored: ignored
+                .into_iter()
+                .map(|(pointer, _)| Input::from_utxo(*pointer))
+                .collect(),
+        };
+
+        for utxos in spendable.chunks(MAX_INPUTS) {
+            let mut builder: TransactionBuilder<
+                NoExtra,
+                SpendingKey,
+                WitnessUtxoData,
+                SpendingSignature<WitnessUtxoData>,
+            > = TransactionBuilder::new(settings.clone(), NoExtra, valid_until);
+            let mut witnesses = Vec::with_capacity(utxos.len());
+
+            for (pointer, utxo) in utxos {
+                let input = Input::from_utxo(**pointer);
+                if utxo.legacy {
+                    builder.add_input(input, OldUtxoWitnessBuilder::new(&utxo.key.public()));
+                } else {
+                    builder.add_input(input, UtxoWitnessBuilder);
+                }
+                witnesses.push(WitnessInput::SecretKey(utxo.key.clone()));
+            }
+
+            match builder.check_balance_with(0, 1) {
+                Balance::Positive(value) => {
+                    builder.add_output(Output::from_address(address.clone(), value));
+                }
+                Balance::Zero | Balance::Negative(_) => {
+                    conversion.ignored.extend_from_slice(builder.inputs());
+                    continue;
+                }
+            }
+
+            let transaction = builder.finalize_tx((), witnesses)?;
+            conversion
+                .transactions
+                .push(Fragment::Transaction(transaction));
+        }
+
+        Ok(conversion)
+    }
+}
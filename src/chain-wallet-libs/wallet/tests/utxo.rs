@@ -0,0 +1,156 @@
+use cardano_legacy_address::{Addr, Attributes, ExtendedAddr};
+use chain_addr::Address;
+use chain_crypto::{PublicKey, SecretKey};
+use chain_impl_mockchain::{
+    block::{Block, BlockDate},
+    fee::FeeAlgorithm as _,
+    fragment::Fragment,
+    ledger::Ledger,
+    legacy::UtxoDeclaration,
+    value::Value,
+};
+use chain_path_derivation::{bip44, rindex, Derivation, HardDerivation, SoftDerivation};
+use chain_ser::{deser::DeserializeFromSlice, packer::Codec};
+use ed25519_bip32::{DerivationScheme, XPrv};
+use hdkeygen::Key;
+use wallet::{Bip44Scheme, RindexScheme, Settings, UtxoScheme, UtxoWallet};
+
+const BLOCK0: &[u8] = include_bytes!("../../test-vectors/block0");
+const ACCOUNT_KEY: &str = include_str!("../../test-vectors/free_keys/key1.prv");
+const UTXO_VALUE: Value = Value(1_000_000);
+
+fn root_key(seed: u8) -> XPrv {
+    let mut bytes = [seed; 96];
+    bytes[0] &= 0b1111_1000;
+    bytes[31] &= 0b0001_1111;
+    bytes[31] |= 0b0100_0000;
+    XPrv::from_bytes_verified(bytes).unwrap()
+}
+
+fn bip44_address(root: &XPrv, change: u32, index: u32) -> Addr {
+    let account = Key::new_unchecked(root.clone(), bip44::new(), DerivationScheme::V2)
+        .bip44()
+        .cardano()
+        .account(HardDerivation::new_unchecked(Derivation::new(0x8000_0000)));
+    let key = account
+        .change(SoftDerivation::new_unchecked(Derivation::new(change)))
+        .address(SoftDerivation::new_unchecked(Derivation::new(index)));
+    ExtendedAddr::new_simple(key.public().public_key(), None).to_address()
+}
+
+fn rindex_address(root: &XPrv, account: u32, index: u32) -> Addr {
+    let root = Key::new_unchecked(root.clone(), rindex::new(), DerivationScheme::V1);
+    let path = rindex::new()
+        .account(Derivation::new(account))
+        .address(Derivation::new(index));
+    let key = root.key(&path);
+
+    // CBOR indefinite array of the two derivation indexes
+    let mut derivation_path = vec![0x9f, 0x1a];
+    derivation_path.extend_from_slice(&account.to_be_bytes());
+    derivation_path.push(0x1a);
+    derivation_path.extend_from_slice(&index.to_be_bytes());
+    derivation_path.push(0xff);
+    let payload = root.hd_key().encrypt(&derivation_path);
+
+    ExtendedAddr::new(
+        key.public().public_key(),
+        Attributes::new_bootstrap_era(Some(payload), None),
+    )
+    .to_address()
+}
+
+fn account_value(ledger: &Ledger, account: &wallet::Wallet) -> Value {
+    ledger
+        .accounts()
+        .get_state(&PublicKey::from(account.account_id()).into())
+        .map(|state| state.value)
+        .unwrap_or_else(|_| Value::zero())
+}
+
+fn sweep<S: UtxoScheme>(
+    utxos: &mut UtxoWallet<S>,
+    mut ledger: Ledger,
+    settings: &Settings,
+    valid_until: BlockDate,
+    address: &Address,
+) -> Ledger {
+    let conversion = utxos.sweep(settings, valid_until, address).unwrap();
+    assert!(conversion.ignored.is_empty());
+    assert_eq!(conversion.transactions.len(), 1);
+
+    for fragment in conversion.transactions.iter() {
+        ledger = ledger.apply_fragment(fragment, ledger.date()).unwrap();
+        assert!(utxos.check_fragment(&fragment.hash(), fragment));
+    }
+    assert_eq!(utxos.value(), Value::zero());
+
+    ledger
+}
+
+#[test]
+fn sweep_legacy_utxos() {
+    let bip44_root = root_key(1);
+    let rindex_root = root_key(2);
+
+    let block0 = Block::deserialize_from_slice(&mut Codec::new(BLOCK0)).unwrap();
+    let declaration = Fragment::OldUtxoDeclaration(UtxoDeclaration {
+        addrs: vec![
+            (bip44_address(&bip44_root, 0, 0), UTXO_VALUE),
+            (bip44_address(&root_key(3), 0, 0), UTXO_VALUE),
+            (bip44_address(&bip44_root, 1, 10), UTXO_VALUE),
+            // only found once the address 10 extended the discovery
+            (bip44_address(&bip44_root, 1, 25), UTXO_VALUE),
+            (
+                rindex_address(&rindex_root, 0x8000_0000, 0x8000_0001),
+                UTXO_VALUE,
+            ),
+            (
+                rindex_address(&root_key(3), 0x8000_0000, 0x8000_0001),
+                UTXO_VALUE,
+            ),
+        ],
+    });
+    let fragments: Vec<Fragment> = block0
+        .contents()
+        .iter()
+        .cloned()
+        .chain(std::iter::once(declaration))
+        .collect();
+    let ledger = Ledger::new(block0.header().id(), fragments.iter()).unwrap();
+    let settings = Settings::new(&block0).unwrap();
+
+    let mut bip44 = UtxoWallet::new(Bip44Scheme::from_root_key(bip44_root));
+    let mut rindex = UtxoWallet::new(RindexScheme::from_root_key(rindex_root));
+    for fragment in fragments.iter() {
+        bip44.check_fragment(&fragment.hash(), fragment);
+        rindex.check_fragment(&fragment.hash(), fragment);
+    }
+    assert_eq!(bip44.utxos().count(), 3);
+    assert_eq!(rindex.utxos().count(), 1);
+
+    let account = wallet::Wallet::new_from_key(
+        SecretKey::from_binary(&hex::decode(ACCOUNT_KEY.trim()).unwrap()).unwrap(),
+    );
+    let address = account.account_id().address(settings.discrimination());
+    let value_before = account_value(&ledger, &account);
+
+    let current_time =
+        std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(settings.block0_date.0);
+    let valid_until = wallet::time::max_expiration_date(&settings, current_time).unwrap();
+
+    let ledger = sweep(&mut bip44, ledger, &settings, valid_until, &address);
+    let ledger = sweep(&mut rindex, ledger, &settings, valid_until, &address);
+
+    let fees = settings
+        .fees
+        .fees_for_inputs_outputs(3, 1)
+        .saturating_add(settings.fees.fees_for_inputs_outputs(1, 1));
+    assert_eq!(
+        account_value(&ledger, &account),
+        value_before
+            .saturating_add(Value(4 * UTXO_VALUE.0))
+            .checked_sub(fees)
+            .unwrap()
+    );
+}
@@ -23,6 +23,7 @@ pub enum Certificate {
     UpdateVote(UpdateVote),
     MintToken(MintToken),
     EvmMapping(EvmMapping),
+    MultisigRegistration(MultisigRegistration),
 }
 
 pub struct StakeDelegation(certificate::StakeDelegation);
@@ -50,6 +51,8 @@ pub struct MintToken(certificate::MintToken);
 
 pub struct EvmMapping(certificate::EvmMapping);
 
+pub struct MultisigRegistration(certificate::MultisigRegistration);
+
 #[Object]
 impl StakeDelegation {
     // FIXME: Maybe a new Account type would be better?
@@ -273,6 +276,39 @@ impl EvmMapping {
     }
 }
 
+#[Object]
+impl MultisigRegistration {
+    /// The address of the registered multisig account
+    pub async fn account(&self, context: &Context<'_>) -> Address {
+        let discrimination = extract_context(context).db.blockchain_config.discrimination;
+        let id: [u8; 32] = self.0.identifier().into();
+        Address::from(&ExplorerAddress::New(chain_addr::Address(
+            discrimination,
+            chain_addr::Kind::Multisig(id),
+        )))
+    }
+
+    /// Number of owners' signatures required to spend from the account
+    pub async fn threshold(&self) -> i32 {
+        self.0.declaration().threshold() as i32
+    }
+
+    /// Hashes of the owners' public keys, in declaration order
+    pub async fn owners(&self) -> Vec<String> {
+        use chain_impl_mockchain::multisig::DeclElement;
+
+        self.0
+            .declaration()
+            .owners()
+            .iter()
+            .filter_map(|owner| match owner {
+                DeclElement::Owner(hash) => Some(hash.to_string()),
+                DeclElement::Sub(_) => None,
+            })
+            .collect()
+    }
+}
+
 /*------------------------------*/
 /*------- Conversions ---------*/
 /*----------------------------*/
@@ -302,6 +338,9 @@ impl From<chain_impl_mockchain::certificate::Certificate> for Certificate {
             certificate::Certificate::UpdateVote(c) => Certificate::UpdateVote(UpdateVote(c)),
             certificate::Certificate::MintToken(c) => Certificate::MintToken(MintToken(c)),
             certificate::Certificate::EvmMapping(c) => Certificate::EvmMapping(EvmMapping(c)),
+            certificate::Certificate::MultisigRegistration(c) => {
+                Certificate::MultisigRegistration(MultisigRegistration(c))
+            }
         }
     }
 }
@@ -365,3 +404,9 @@ impl From<certificate::EvmMapping> for EvmMapping {
         EvmMapping(evm_mapping)
     }
 }
+
+impl From<certificate::MultisigRegistration> for MultisigRegistration {
+    fn from(registration: certificate::MultisigRegistration) -> Self {
+        MultisigRegistration(registration)
+    }
+}
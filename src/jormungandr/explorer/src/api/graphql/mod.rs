@@ -13,17 +13,18 @@ use self::{
     scalars::{
         BlockCount, ChainLength, EpochNumber, ExternalProposalId, IndexCursor, NonZero,
         PayloadType, PoolCount, PoolId, PublicKey, Slot, TransactionCount, Value, VoteOptionRange,
-        VotePlanId, VotePlanStatusCount, Weight,
+        VotePlanId, VotePlanStatusCount, VoteStatusCount, Weight,
     },
 };
 use crate::db::{
     indexing::{
         BlockProducer, EpochData, ExplorerAddress, ExplorerBlock, ExplorerTransaction,
-        ExplorerVote, ExplorerVotePlan, ExplorerVoteProposal, ExplorerVoteTally, StakePoolData,
+        ExplorerVote, ExplorerVotePlan, ExplorerVoteProposal, ExplorerVoteTally,
+        ExplorerVoteTurnout, StakePoolData,
     },
     persistent_sequence::PersistentSequence,
-    tally::compute_public_tally,
-    ExplorerDb, Settings as ChainSettings,
+    tally::{compute_public_tally, total_weight, winners},
+    ChainEvent, ExplorerDb, Settings as ChainSettings,
 };
 use async_graphql::{
     connection::{query, Connection, Edge, EmptyFields},
@@ -1263,6 +1264,25 @@ pub struct VotePayloadPrivateStatus {
     encrypted_vote: EncryptedVote,
 }
 
+#[derive(Clone, SimpleObject)]
+pub struct VotePayloadRankedChoiceStatus {
+    /// the choices, most preferred first
+    ranking: Vec<i32>,
+}
+
+#[derive(Clone, SimpleObject)]
+pub struct VotePayloadApprovalStatus {
+    approvals: Vec<i32>,
+}
+
+#[derive(Clone, SimpleObject)]
+pub struct VotePayloadQuadraticStatus {
+    choice: i32,
+    /// the credits allocated to the choice, the weight of the vote is their
+    /// square root
+    credits: Weight,
+}
+
 #[Object]
 impl VotePayloadPublicStatus {
     pub async fn choice(&self, _context: &Context<'_>) -> i32 {
@@ -1287,19 +1307,35 @@ impl VotePayloadPrivateStatus {
 pub enum VotePayloadStatus {
     Public(VotePayloadPublicStatus),
     Private(VotePayloadPrivateStatus),
+    RankedChoice(VotePayloadRankedChoiceStatus),
+    Approval(VotePayloadApprovalStatus),
+    Quadratic(VotePayloadQuadraticStatus),
 }
 
-// TODO do proper vote tally
 #[derive(Clone, SimpleObject)]
 pub struct TallyPublicStatus {
     results: Vec<Weight>,
     options: VoteOptionRange,
+    /// the options with the highest weight, more than one in case of a draw
+    /// and none as long as no weight was cast. For a ranked choice proposal,
+    /// the winner of the instant-runoff
+    winners: Vec<i32>,
+    /// the weight cast on the proposal, an approval ballot counts once no
+    /// matter how many options it approves
+    total_weight: Weight,
+    /// the weight of every option in each round of the instant-runoff of a
+    /// ranked choice proposal, `results` is the last round
+    instant_runoff_rounds: Option<Vec<Vec<Weight>>>,
 }
 
 #[derive(Clone, SimpleObject)]
 pub struct TallyPrivateStatus {
+    /// the decrypted results, available once the vote tally certificate is
+    /// on chain
     results: Option<Vec<Weight>>,
     options: VoteOptionRange,
+    winners: Option<Vec<i32>>,
+    total_weight: Option<Weight>,
 }
 
 #[derive(Clone, Union)]
@@ -1308,6 +1344,27 @@ pub enum TallyStatus {
     Private(TallyPrivateStatus),
 }
 
+#[derive(Clone)]
+pub struct VoteTurnout(ExplorerVoteTurnout);
+
+#[Object]
+impl VoteTurnout {
+    /// the number of distinct addresses that cast a vote
+    pub async fn voters(&self) -> VoteStatusCount {
+        self.0.voters.into()
+    }
+
+    /// the stake held by the voters
+    pub async fn voting_power(&self) -> Value {
+        self.0.voting_power.into()
+    }
+
+    /// the stake of the whole ledger
+    pub async fn total_voting_power(&self) -> Value {
+        self.0.total_voting_power.into()
+    }
+}
+
 #[derive(Clone, SimpleObject)]
 pub struct VotePlanStatus {
     id: VotePlanId,
@@ -1316,6 +1373,12 @@ pub struct VotePlanStatus {
     committee_end: BlockDate,
     payload_type: PayloadType,
     proposals: Vec<VoteProposalStatus>,
+    /// the date of the vote tally, the results of the proposals are final
+    /// once it is set
+    tally_date: Option<BlockDate>,
+    /// the participation across all the proposals, an address that voted
+    /// for several proposals is only counted once
+    turnout: VoteTurnout,
 }
 
 impl VotePlanStatus {
@@ -1324,7 +1387,7 @@ impl VotePlanStatus {
         context: &Context<'_>,
     ) -> FieldResult<Self> {
         let vote_plan_id = chain_impl_mockchain::certificate::VotePlanId::from_str(&vote_plan_id.0)
-            .map_err(|err| -> FieldError { ApiError::InvalidAddress(err.to_string()).into() })?;
+            .map_err(|err| -> FieldError { ApiError::InvalidVotePlanId(err.to_string()).into() })?;
         if let Some(vote_plan) = extract_context(context)
             .db
             .get_vote_plan_by_id(&vote_plan_id)
@@ -1344,6 +1407,8 @@ impl VotePlanStatus {
             committee_end,
             payload_type,
             proposals,
+            tally_date,
+            turnout,
         } = (*vote_plan).clone();
 
         VotePlanStatus {
@@ -1357,87 +1422,126 @@ impl VotePlanStatus {
                 .map(|proposal| VoteProposalStatus {
                     proposal_id: ExternalProposalId::from(proposal.proposal_id.clone()),
                     options: VoteOptionRange::from(proposal.options.clone()),
-                    tally: proposal.tally.clone().map_or(
-                        Some(generic_tally_status(
-                            ExplorerVoteProposal {
-                                proposal_id: proposal.proposal_id,
-                                options: proposal.options,
-                                tally: proposal.tally,
-                                votes: proposal.votes.clone(),
-                            },
-                            payload_type,
-                        )),
-                        |tally| Some(TallyStatus::from(tally)),
-                    ),
+                    tally: Some(proposal.tally.clone().map_or_else(
+                        || generic_tally_status(&proposal, payload_type),
+                        TallyStatus::from,
+                    )),
+                    turnout: VoteTurnout(proposal.turnout.clone()),
                     votes: proposal
                         .votes
                         .iter()
-                        .map(|(key, vote)| match vote.as_ref() {
-                            ExplorerVote::Public(choice) => VoteStatus {
-                                address: key.into(),
-                                payload: VotePayloadStatus::Public(VotePayloadPublicStatus {
-                                    choice: choice.as_byte().into(),
-                                }),
-                            },
-                            ExplorerVote::Private {
-                                proof,
-                                encrypted_vote,
-                            } => VoteStatus {
-                                address: key.into(),
-                                payload: VotePayloadStatus::Private(VotePayloadPrivateStatus {
-                                    proof: proof.clone(),
-                                    encrypted_vote: encrypted_vote.clone(),
-                                }),
-                            },
+                        .map(|(key, vote)| VoteStatus {
+                            address: key.into(),
+                            payload: VotePayloadStatus::from(vote.as_ref()),
                         })
                         .collect(),
                 })
                 .collect(),
+            tally_date: tally_date.map(BlockDate::from),
+            turnout: VoteTurnout(turnout),
+        }
+    }
+}
+
+impl From<&ExplorerVote> for VotePayloadStatus {
+    fn from(vote: &ExplorerVote) -> Self {
+        match vote {
+            ExplorerVote::Public(choice) => VotePayloadStatus::Public(VotePayloadPublicStatus {
+                choice: choice.as_byte().into(),
+            }),
+            ExplorerVote::Private {
+                proof,
+                encrypted_vote,
+            } => VotePayloadStatus::Private(VotePayloadPrivateStatus {
+                proof: proof.clone(),
+                encrypted_vote: encrypted_vote.clone(),
+            }),
+            ExplorerVote::RankedChoice(ranking) => {
+                VotePayloadStatus::RankedChoice(VotePayloadRankedChoiceStatus {
+                    ranking: ranking
+                        .iter()
+                        .map(|choice| choice.as_byte().into())
+                        .collect(),
+                })
+            }
+            ExplorerVote::Approval(approvals) => {
+                VotePayloadStatus::Approval(VotePayloadApprovalStatus {
+                    approvals: approvals
+                        .iter()
+                        .map(|choice| choice.as_byte().into())
+                        .collect(),
+                })
+            }
+            ExplorerVote::Quadratic { choice, credits } => {
+                VotePayloadStatus::Quadratic(VotePayloadQuadraticStatus {
+                    choice: choice.as_byte().into(),
+                    credits: Weight(credits.to_string()),
+                })
+            }
         }
     }
 }
 
 // if the tally is None, convert to generic tally result as per rest api requirements
-pub fn generic_tally_status(p: ExplorerVoteProposal, payload: OtherPayloadType) -> TallyStatus {
+pub fn generic_tally_status(p: &ExplorerVoteProposal, payload: OtherPayloadType) -> TallyStatus {
     match payload {
-        OtherPayloadType::Public => {
+        OtherPayloadType::Public
+        | OtherPayloadType::RankedChoice
+        | OtherPayloadType::Approval
+        | OtherPayloadType::Quadratic => {
             let s = StakeControl::default();
-            match compute_public_tally(&p, &s) {
-                ExplorerVoteTally::Public { results, options } => {
-                    TallyStatus::Public(TallyPublicStatus {
-                        results: results.iter().map(Into::into).collect(),
-                        options: options.into(),
-                    })
-                }
-                ExplorerVoteTally::Private { results, options } => {
-                    TallyStatus::Private(TallyPrivateStatus {
-                        results: results.map(|res| res.iter().map(Into::into).collect()),
-                        options: options.into(),
-                    })
-                }
-            }
+            TallyStatus::from(compute_public_tally(p, &s))
+        }
+        OtherPayloadType::Private | OtherPayloadType::PrivateQuadratic => {
+            TallyStatus::from(ExplorerVoteTally::Private {
+                results: Some(vec![0u64.into(); p.options.choice_range().end as usize]),
+                options: p.options.clone(),
+            })
         }
-        OtherPayloadType::Private => TallyStatus::Private(TallyPrivateStatus {
-            results: Some(vec![
-                Weight("0".to_string());
-                p.options.choice_range().end as usize
-            ]),
-            options: p.options.into(),
-        }),
     }
 }
 
+fn tally_winners(results: &[chain_impl_mockchain::vote::Weight]) -> Vec<i32> {
+    winners(results)
+        .into_iter()
+        .map(|choice| choice.as_byte().into())
+        .collect()
+}
+
 impl From<ExplorerVoteTally> for TallyStatus {
     fn from(tally: ExplorerVoteTally) -> Self {
         match tally {
-            ExplorerVoteTally::Public { results, options } => {
-                TallyStatus::Public(TallyPublicStatus {
-                    results: results.iter().map(Into::into).collect(),
-                    options: options.into(),
-                })
-            }
+            ExplorerVoteTally::Public {
+                results,
+                options,
+                winners,
+                total_weight,
+                rounds,
+            } => TallyStatus::Public(TallyPublicStatus {
+                winners: winners
+                    .into_iter()
+                    .map(|choice| choice.as_byte().into())
+                    .collect(),
+                total_weight: Weight(total_weight.to_string()),
+                instant_runoff_rounds: if rounds.is_empty() {
+                    None
+                } else {
+                    Some(
+                        rounds
+                            .iter()
+                            .map(|round| round.iter().map(Into::into).collect())
+                            .collect(),
+                    )
+                },
+                results: results.iter().map(Into::into).collect(),
+                options: options.into(),
+            }),
             ExplorerVoteTally::Private { results, options } => {
                 TallyStatus::Private(TallyPrivateStatus {
+                    winners: results.as_deref().map(tally_winners),
+                    total_weight: results
+                        .as_deref()
+                        .map(|results| Weight(total_weight(results).to_string())),
                     results: results.map(|res| res.iter().map(Into::into).collect()),
                     options: options.into(),
                 })
@@ -1452,11 +1556,20 @@ pub struct VoteStatus {
     payload: VotePayloadStatus,
 }
 
+#[derive(Clone, SimpleObject)]
+pub struct AccountVoteStatus {
+    vote_plan_id: VotePlanId,
+    proposal_id: ExternalProposalId,
+    proposal_index: i32,
+    payload: VotePayloadStatus,
+}
+
 #[derive(Clone)]
 pub struct VoteProposalStatus {
     proposal_id: ExternalProposalId,
     options: VoteOptionRange,
     tally: Option<TallyStatus>,
+    turnout: VoteTurnout,
     votes: Vec<VoteStatus>,
 }
 
@@ -1474,6 +1587,10 @@ impl VoteProposalStatus {
         self.tally.as_ref()
     }
 
+    pub async fn turnout(&self) -> &VoteTurnout {
+        &self.turnout
+    }
+
     pub async fn votes(
         &self,
         first: Option<i32>,
@@ -1621,10 +1738,137 @@ impl Query {
     ) -> FieldResult<VotePlanStatus> {
         VotePlanStatus::vote_plan_from_id(VotePlanId(id), context).await
     }
+
+    /// get the votes cast by an address in all the vote plans of the main
+    /// branch, sorted by vote start date
+    pub async fn vote_history(
+        &self,
+        context: &Context<'_>,
+        bech32: String,
+    ) -> FieldResult<Vec<AccountVoteStatus>> {
+        let address = Address::from_bech32(&bech32)?;
+        let (_, state_ref) = extract_context(context).db.get_tip().await;
+
+        let mut vote_plans = state_ref.state().get_vote_plans();
+        vote_plans.sort_by_key(|(_, vote_plan)| vote_plan.vote_start);
+
+        Ok(vote_plans
+            .into_iter()
+            .flat_map(|(id, vote_plan)| {
+                vote_plan
+                    .proposals
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(index, proposal)| {
+                        proposal
+                            .votes
+                            .lookup(&address.id)
+                            .map(|vote| AccountVoteStatus {
+                                vote_plan_id: VotePlanId::from(id.clone()),
+                                proposal_id: ExternalProposalId::from(proposal.proposal_id.clone()),
+                                proposal_index: index as i32,
+                                payload: VotePayloadStatus::from(vote.as_ref()),
+                            })
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect())
+    }
+}
+
+/// The main branch switched to another branch, the blocks above the fork
+/// point are not part of the main branch anymore and the events of the new
+/// branch follow.
+#[derive(SimpleObject)]
+pub struct Rollback {
+    previous_tip: Block,
+    fork_point: Block,
+}
+
+#[derive(SimpleObject)]
+pub struct NewBlock {
+    block: Block,
+}
+
+#[derive(SimpleObject)]
+pub struct NewTransaction {
+    transaction: Transaction,
+    block: Block,
+}
+
+#[derive(SimpleObject)]
+pub struct NewVoteCast {
+    vote_plan_id: VotePlanId,
+    proposal_index: i32,
+    voter: Address,
+    payload: VotePayloadStatus,
+    transaction: Transaction,
+    block: Block,
+}
+
+#[derive(Union)]
+pub enum BlockEvent {
+    NewBlock(NewBlock),
+    Rollback(Rollback),
+}
+
+#[derive(Union)]
+pub enum TransactionEvent {
+    NewTransaction(NewTransaction),
+    Rollback(Rollback),
+}
+
+#[derive(Union)]
+pub enum VoteCastEvent {
+    NewVoteCast(NewVoteCast),
+    Rollback(Rollback),
+}
+
+impl Rollback {
+    fn new(previous_tip: HeaderHash, fork_point: HeaderHash) -> Self {
+        Rollback {
+            previous_tip: Block::from_valid_hash(previous_tip),
+            fork_point: Block::from_valid_hash(fork_point),
+        }
+    }
+}
+
+fn chain_events(context: &Context<'_>) -> impl futures::Stream<Item = ChainEvent> {
+    use futures::StreamExt;
+    extract_context(context)
+        .db
+        .chain_subscription()
+        // unlike the tip, the subscribers can't afford to silently skip blocks:
+        // end the stream when they fell behind so that they resynchronize
+        .take_while(|event| futures::future::ready(event.is_ok()))
+        .filter_map(|event| async move { event.ok() })
+}
+
+fn touches_address(transaction: &ExplorerTransaction, address: &ExplorerAddress) -> bool {
+    transaction
+        .inputs
+        .iter()
+        .map(|input| &input.address)
+        .chain(transaction.outputs.iter().map(|output| &output.address))
+        .any(|a| a == address)
+}
+
+fn transactions_in_order(block: &ExplorerBlock) -> Vec<&ExplorerTransaction> {
+    let mut transactions: Vec<_> = block.transactions.values().collect();
+    transactions.sort_by_key(|transaction| transaction.offset_in_block);
+    transactions
 }
 
 pub struct Subscription;
 
+/// The changes of the blockchain as they happen.
+///
+/// `blocks`, `transactionsByAddress`, `voteCasts` and `stakePoolBlocks`
+/// follow the main branch without gaps. The changes are buffered for a
+/// subscriber reading them slower than they happen, a subscriber falling
+/// more than 256 changes of the main branch behind is not sent the changes
+/// it missed: its subscription ends instead, after the changes it already
+/// received. It has to query what it missed before subscribing again.
 #[Subscription]
 impl Subscription {
     async fn tip(&self, context: &Context<'_>) -> impl futures::Stream<Item = Branch> {
@@ -1639,6 +1883,143 @@ impl Subscription {
                     .map(|(hash, state)| Branch::from_id_and_state(hash, state))
             })
     }
+
+    /// the blocks appended to the main branch
+    async fn blocks(&self, context: &Context<'_>) -> impl futures::Stream<Item = BlockEvent> {
+        use futures::StreamExt;
+        chain_events(context).map(|event| match event {
+            ChainEvent::Block(block) => BlockEvent::NewBlock(NewBlock {
+                block: Block::from_contents(block),
+            }),
+            ChainEvent::Rollback {
+                previous_tip,
+                fork_point,
+            } => BlockEvent::Rollback(Rollback::new(previous_tip, fork_point)),
+        })
+    }
+
+    /// the transactions of the main branch spending from or sending to the
+    /// given address
+    async fn transactions_by_address(
+        &self,
+        context: &Context<'_>,
+        bech32: String,
+    ) -> FieldResult<impl futures::Stream<Item = TransactionEvent>> {
+        use futures::StreamExt;
+        let address = Address::from_bech32(&bech32)?.id;
+        Ok(chain_events(context)
+            .map(move |event| {
+                let events = match event {
+                    ChainEvent::Block(block) => transactions_in_order(&block)
+                        .into_iter()
+                        .filter(|transaction| touches_address(transaction, &address))
+                        .map(|transaction| {
+                            TransactionEvent::NewTransaction(NewTransaction {
+                                transaction: Transaction {
+                                    id: transaction.id,
+                                    block_hashes: vec![block.id],
+                                    contents: Some(transaction.clone()),
+                                },
+                                block: Block::from_contents(Arc::clone(&block)),
+                            })
+                        })
+                        .collect(),
+                    ChainEvent::Rollback {
+                        previous_tip,
+                        fork_point,
+                    } => vec![TransactionEvent::Rollback(Rollback::new(
+                        previous_tip,
+                        fork_point,
+                    ))],
+                };
+                futures::stream::iter(events)
+            })
+            .flatten())
+    }
+
+    /// the votes cast in the main branch for the given vote plan, optionally
+    /// restricted to one of its proposals
+    async fn vote_casts(
+        &self,
+        context: &Context<'_>,
+        vote_plan_id: VotePlanId,
+        proposal_index: Option<i32>,
+    ) -> FieldResult<impl futures::Stream<Item = VoteCastEvent>> {
+        use futures::StreamExt;
+        let vote_plan_id = certificate::VotePlanId::from_str(&vote_plan_id.0)
+            .map_err(|err| -> FieldError { ApiError::InvalidVotePlanId(err.to_string()).into() })?;
+        Ok(chain_events(context)
+            .map(move |event| {
+                let events = match event {
+                    ChainEvent::Block(block) => transactions_in_order(&block)
+                        .into_iter()
+                        .filter_map(|transaction| match &transaction.certificate {
+                            Some(certificate::Certificate::VoteCast(vote_cast))
+                                if vote_cast.vote_plan() == &vote_plan_id
+                                    && proposal_index.map_or(true, |index| {
+                                        index == i32::from(vote_cast.proposal_index())
+                                    }) =>
+                            {
+                                Some(VoteCastEvent::NewVoteCast(NewVoteCast {
+                                    vote_plan_id: vote_plan_id.clone().into(),
+                                    proposal_index: vote_cast.proposal_index().into(),
+                                    voter: Address::from(&transaction.inputs[0].address),
+                                    payload: VotePayloadStatus::from(&ExplorerVote::from(
+                                        vote_cast.payload(),
+                                    )),
+                                    transaction: Transaction {
+                                        id: transaction.id,
+                                        block_hashes: vec![block.id],
+                                        contents: Some(transaction.clone()),
+                                    },
+                                    block: Block::from_contents(Arc::clone(&block)),
+                                }))
+                            }
+                            _ => None,
+                        })
+                        .collect(),
+                    ChainEvent::Rollback {
+                        previous_tip,
+                        fork_point,
+                    } => vec![VoteCastEvent::Rollback(Rollback::new(
+                        previous_tip,
+                        fork_point,
+                    ))],
+                };
+                futures::stream::iter(events)
+            })
+            .flatten())
+    }
+
+    /// the blocks of the main branch produced by the given stake pool
+    async fn stake_pool_blocks(
+        &self,
+        context: &Context<'_>,
+        id: PoolId,
+    ) -> impl futures::Stream<Item = BlockEvent> {
+        use futures::StreamExt;
+        let pool_id = id.0;
+        chain_events(context).filter_map(move |event| {
+            let event = match event {
+                ChainEvent::Block(block) => match &block.producer {
+                    BlockProducer::StakePool(producer) if *producer == pool_id => {
+                        Some(BlockEvent::NewBlock(NewBlock {
+                            block: Block::from_contents(block),
+                        }))
+                    }
+                    _ => None,
+                },
+                ChainEvent::Rollback {
+                    previous_tip,
+                    fork_point,
+                } => Some(BlockEvent::Rollback(Rollback::new(
+                    previous_tip,
+                    fork_point,
+                ))),
+            };
+            futures::future::ready(event)
+        })
+    }
 }
 
 pub type Schema = async_graphql::Schema<Query, EmptyMutation, Subscription>;
@@ -1651,3 +2032,218 @@ pub struct EContext {
 fn extract_context<'a>(context: &Context<'a>) -> &'a EContext {
     context.data_unchecked::<EContext>()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{
+        tests::{apply_tip, TestChain},
+        CHAIN_EVENTS_BUFFER_SIZE,
+    };
+    use chain_addr::AddressReadable;
+    use chain_core::property::{Block as _, Fragment as _};
+    use chain_impl_mockchain::{
+        certificate::{Certificate, VoteCast},
+        testing::{data::Wallet, TestGen, VoteTestGen},
+        value::Value as InternalValue,
+        vote::{Choice, Payload},
+    };
+    use futures::{stream::BoxStream, StreamExt};
+    use serde_json::{json, Value as Json};
+
+    fn schema(db: ExplorerDb) -> Schema {
+        Schema::build(Query {}, EmptyMutation, Subscription {})
+            .data(EContext {
+                db,
+                settings: ChainSettings {
+                    address_bech32_prefix: "ca".to_owned(),
+                    query_depth_limit: 30,
+                    query_complexity_limit: 5000,
+                },
+            })
+            .finish()
+    }
+
+    /// the events sent once this returns are received by the subscription
+    async fn subscribe(schema: &Schema, query: &str) -> BoxStream<'static, Json> {
+        let mut events = schema
+            .execute_stream(query)
+            .map(|response| {
+                assert!(response.errors.is_empty(), "{:?}", response.errors);
+                response.data.into_json().unwrap()
+            })
+            .boxed();
+        // the subscription is set up the first time it is polled
+        assert!(futures::poll!(events.next()).is_pending());
+        events
+    }
+
+    async fn next_events(events: &mut BoxStream<'static, Json>, count: usize) -> Vec<Json> {
+        let mut received = Vec::with_capacity(count);
+        for _ in 0..count {
+            received.push(events.next().await.unwrap());
+        }
+        assert!(futures::poll!(events.next()).is_pending());
+        received
+    }
+
+    #[tokio::test]
+    async fn transactions_are_filtered_by_address() {
+        let wallets: Vec<_> = (0..3)
+            .map(|_| Wallet::from_value(InternalValue(100)))
+            .collect();
+        let (alice, bob, carol) = (&wallets[0], &wallets[1], &wallets[2]);
+        let mut chain = TestChain::with_wallets(&[alice, bob, carol]);
+        let db = ExplorerDb::bootstrap(chain.block0(), None).unwrap();
+        let schema = schema(db.clone());
+        let bech32 = AddressReadable::from_address("ca", &bob.as_address()).to_string();
+        let mut events = subscribe(
+            &schema,
+            &format!(
+                "subscription {{ transactionsByAddress(bech32: \"{}\") {{ \
+                 ... on NewTransaction {{ transaction {{ id }} block {{ id }} }} }} }}",
+                bech32
+            ),
+        )
+        .await;
+
+        let unrelated = chain.transfer(alice, carol, InternalValue(10));
+        let received = chain.transfer(alice, bob, InternalValue(10));
+        let sent = chain.transfer(bob, carol, InternalValue(10));
+        let first = chain.next_block_with(vec![unrelated]);
+        let second = chain.next_block_with(vec![received.clone(), sent.clone()]);
+        apply_tip(&db, &first).await;
+        apply_tip(&db, &second).await;
+
+        let transaction = |fragment: &chain_impl_mockchain::fragment::Fragment| {
+            json!({ "transactionsByAddress": {
+                "transaction": { "id": fragment.id().to_string() },
+                "block": { "id": second.id().to_string() },
+            }})
+        };
+        assert_eq!(
+            next_events(&mut events, 2).await,
+            vec![transaction(&received), transaction(&sent)]
+        );
+    }
+
+    #[tokio::test]
+    async fn vote_casts_are_filtered_by_vote_plan_and_proposal() {
+        let voter = Wallet::from_value(InternalValue(100));
+        let mut chain = TestChain::with_wallets(&[&voter]);
+        let db = ExplorerDb::bootstrap(chain.block0(), None).unwrap();
+        let schema = schema(db.clone());
+        let (vote_plan, other_vote_plan) = (VoteTestGen::vote_plan(), VoteTestGen::vote_plan());
+        let subscription = |proposal_index: &str| {
+            format!(
+                "subscription {{ voteCasts(votePlanId: \"{}\"{}) {{ \
+                 ... on NewVoteCast {{ votePlanId proposalIndex transaction {{ id }} }} }} }}",
+                vote_plan.to_id(),
+                proposal_index
+            )
+        };
+        let mut all_proposals = subscribe(&schema, &subscription("")).await;
+        let mut second_proposal = subscribe(&schema, &subscription(", proposalIndex: 1")).await;
+
+        let vote = |vote_plan: &certificate::VotePlan, proposal_index| {
+            chain.certificate(
+                &voter,
+                Certificate::VoteCast(VoteCast::new(
+                    vote_plan.to_id(),
+                    proposal_index,
+                    Payload::public(Choice::new(0)),
+                )),
+            )
+        };
+        let votes = vec![
+            vote(&vote_plan, 0),
+            vote(&other_vote_plan, 0),
+            vote(&vote_plan, 1),
+        ];
+        let vote_plans = vec![
+            chain.certificate(&voter, Certificate::VotePlan(vote_plan.clone())),
+            chain.certificate(&voter, Certificate::VotePlan(other_vote_plan.clone())),
+        ];
+        let first = chain.next_block_with(vote_plans);
+        let second = chain.next_block_with(votes.clone());
+        apply_tip(&db, &first).await;
+        apply_tip(&db, &second).await;
+
+        let vote_cast = |fragment: &chain_impl_mockchain::fragment::Fragment, proposal_index| {
+            json!({ "voteCasts": {
+                "votePlanId": vote_plan.to_id().to_string(),
+                "proposalIndex": proposal_index,
+                "transaction": { "id": fragment.id().to_string() },
+            }})
+        };
+        assert_eq!(
+            next_events(&mut all_proposals, 2).await,
+            vec![vote_cast(&votes[0], 0), vote_cast(&votes[2], 1)]
+        );
+        assert_eq!(
+            next_events(&mut second_proposal, 1).await,
+            vec![vote_cast(&votes[2], 1)]
+        );
+    }
+
+    #[tokio::test]
+    async fn blocks_are_filtered_by_stake_pool() {
+        let owner = Wallet::from_value(InternalValue(100));
+        let mut chain = TestChain::with_wallets(&[&owner]);
+        let db = ExplorerDb::bootstrap(chain.block0(), None).unwrap();
+        let schema = schema(db.clone());
+        let (pool, other_pool) = (TestGen::stake_pool(), TestGen::stake_pool());
+        let mut events = subscribe(
+            &schema,
+            &format!(
+                "subscription {{ stakePoolBlocks(id: \"{}\") {{ \
+                 ... on NewBlock {{ block {{ id }} }} }} }}",
+                pool.id()
+            ),
+        )
+        .await;
+
+        let registrations = vec![
+            chain.certificate(&owner, Certificate::PoolRegistration(pool.info())),
+            chain.certificate(&owner, Certificate::PoolRegistration(other_pool.info())),
+        ];
+        let mut blocks = vec![chain.next_block_with(registrations)];
+        blocks.push(chain.next_pool_block(&pool));
+        blocks.push(chain.next_pool_block(&other_pool));
+        blocks.push(chain.next_pool_block(&pool));
+        for block in &blocks {
+            apply_tip(&db, block).await;
+        }
+
+        let block = |block: &chain_impl_mockchain::block::Block| json!({ "stakePoolBlocks": { "block": { "id": block.id().to_string() } } });
+        assert_eq!(
+            next_events(&mut events, 2).await,
+            vec![block(&blocks[1]), block(&blocks[3])]
+        );
+    }
+
+    #[tokio::test]
+    async fn lagging_subscription_ends() {
+        let mut chain = TestChain::new();
+        let db = ExplorerDb::bootstrap(chain.block0(), None).unwrap();
+        let schema = schema(db.clone());
+        let mut events = subscribe(
+            &schema,
+            "subscription { blocks { ... on NewBlock { block { id } } } }",
+        )
+        .await;
+
+        let block = chain.next_block();
+        apply_tip(&db, &block).await;
+        assert_eq!(
+            next_events(&mut events, 1).await,
+            vec![json!({ "blocks": { "block": { "id": block.id().to_string() } } })]
+        );
+
+        for _ in 0..=CHAIN_EVENTS_BUFFER_SIZE {
+            let block = chain.next_block();
+            apply_tip(&db, &block).await;
+        }
+        assert!(events.next().await.is_none());
+    }
+}
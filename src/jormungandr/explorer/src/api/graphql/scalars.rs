@@ -203,6 +203,10 @@ impl ScalarType for ExternalProposalId {
 pub enum PayloadType {
     Public,
     Private,
+    RankedChoice,
+    Approval,
+    Quadratic,
+    PrivateQuadratic,
 }
 
 #[derive(Clone)]
@@ -336,6 +340,10 @@ impl From<vote::PayloadType> for PayloadType {
         match payload_type {
             vote::PayloadType::Public => Self::Public,
             vote::PayloadType::Private => Self::Private,
+            vote::PayloadType::RankedChoice => Self::RankedChoice,
+            vote::PayloadType::Approval => Self::Approval,
+            vote::PayloadType::Quadratic => Self::Quadratic,
+            vote::PayloadType::PrivateQuadratic => Self::PrivateQuadratic,
         }
     }
 }
@@ -0,0 +1,825 @@
+//! Checkpoints of the explorer indexes.
+//!
+//! A checkpoint holds the whole indexed state at a confirmed block, so that
+//! after a restart only the stored blocks that follow it have to be indexed
+//! again.
+
+use super::{
+    indexing::{
+        BlockProducer, EpochData, ExplorerAddress, ExplorerBlock, ExplorerInput, ExplorerOutput,
+        ExplorerTransaction, ExplorerVote, ExplorerVotePlan, ExplorerVoteProposal,
+        ExplorerVoteTally, ExplorerVoteTurnout, Hamt, StakePoolData,
+    },
+    persistent_sequence::PersistentSequence,
+    State,
+};
+use cardano_legacy_address::Addr as OldAddress;
+use chain_addr::Address;
+use chain_core::{
+    packer::Codec,
+    property::{Deserialize, DeserializeFromSlice, ReadError, Serialize, WriteError},
+};
+use chain_crypto::Blake2b256;
+use chain_impl_mockchain::{
+    account::Identifier,
+    certificate::{
+        Certificate, EvmMapping, MintToken, MultisigRegistration, OwnerStakeDelegation,
+        PoolRegistration, PoolRetirement, PoolUpdate, StakeDelegation, UpdateProposal, UpdateVote,
+        VoteCast, VotePlan, VotePlanId, VoteTally,
+    },
+    fragment::ConfigParams,
+    header::{BlockDate, ChainLength, HeaderId as HeaderHash},
+    key::BftLeaderId,
+    stake::{Stake, StakeControl},
+    value::Value,
+    vote::{Choice, Options, Payload, PayloadType, Weight},
+};
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    fs, hash,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use thiserror::Error;
+
+/// Name of the checkpoint's file in the explorer's storage directory.
+pub const CHECKPOINT_FILE_NAME: &str = "indexes.checkpoint";
+
+const CHECKPOINT_MAGIC: &[u8; 4] = b"JXCP";
+const CHECKPOINT_VERSION: u16 = 1;
+
+type Writer = Codec<Vec<u8>>;
+type Reader<'a> = Codec<&'a [u8]>;
+
+#[derive(Debug, Error)]
+pub enum CheckpointError {
+    #[error("I/O error")]
+    Io(#[from] io::Error),
+    #[error("not an explorer checkpoint")]
+    InvalidMagic,
+    #[error("unsupported explorer checkpoint version {0}")]
+    UnsupportedVersion(u16),
+    #[error("the explorer checkpoint is corrupted, its checksum does not match its content")]
+    ChecksumMismatch,
+    #[error("invalid explorer checkpoint")]
+    Read(#[from] ReadError),
+    #[error("cannot serialize the explorer checkpoint")]
+    Write(#[from] WriteError),
+}
+
+/// The indexes of the explorer after a confirmed block.
+#[derive(Clone)]
+pub struct Checkpoint {
+    /// the block the checkpoint was taken at
+    pub block: HeaderHash,
+    pub chain_length: ChainLength,
+    /// the indexes after the block
+    pub state: State,
+}
+
+impl Checkpoint {
+    /// serialize the checkpoint, followed by the checksum of its content
+    pub fn to_bytes(&self) -> Result<Vec<u8>, CheckpointError> {
+        let mut codec = Codec::new(Vec::new());
+        codec.put_bytes(CHECKPOINT_MAGIC)?;
+        codec.put_be_u16(CHECKPOINT_VERSION)?;
+        self.block.serialize(&mut codec)?;
+        codec.put_be_u32(self.chain_length.into())?;
+        write_state(&mut codec, &self.state)?;
+        let mut bytes = codec.into_inner();
+        let checksum = Blake2b256::new(&bytes);
+        bytes.extend_from_slice(checksum.as_hash_bytes());
+        Ok(bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CheckpointError> {
+        if bytes.len() < Blake2b256::HASH_SIZE {
+            return Err(CheckpointError::ChecksumMismatch);
+        }
+        let (content, checksum) = bytes.split_at(bytes.len() - Blake2b256::HASH_SIZE);
+        if Blake2b256::new(content).as_hash_bytes()[..] != *checksum {
+            return Err(CheckpointError::ChecksumMismatch);
+        }
+
+        let mut codec = Codec::new(content);
+        if codec.get_slice(CHECKPOINT_MAGIC.len())? != CHECKPOINT_MAGIC {
+            return Err(CheckpointError::InvalidMagic);
+        }
+        let version = codec.get_be_u16()?;
+        if version != CHECKPOINT_VERSION {
+            return Err(CheckpointError::UnsupportedVersion(version));
+        }
+        Ok(Checkpoint {
+            block: HeaderHash::deserialize(&mut codec)?,
+            chain_length: codec.get_be_u32()?.into(),
+            state: read_state(&mut codec)?,
+        })
+    }
+
+    /// read the checkpoint of the given storage directory, `None` if no
+    /// checkpoint was written yet
+    pub fn read_from_directory<P: AsRef<Path>>(
+        directory: P,
+    ) -> Result<Option<Self>, CheckpointError> {
+        match fs::read(directory.as_ref().join(CHECKPOINT_FILE_NAME)) {
+            Ok(bytes) => Self::from_bytes(&bytes).map(Some),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// write the checkpoint in the given storage directory, replacing the
+    /// previous one, returns the path of the file
+    ///
+    /// the file is written under a temporary name and synced to the disk
+    /// before replacing the previous one, so a partially written checkpoint
+    /// never replaces a valid one, even after a crash.
+    pub fn write_to_directory<P: AsRef<Path>>(
+        &self,
+        directory: P,
+    ) -> Result<PathBuf, CheckpointError> {
+        let directory = directory.as_ref();
+        let path = directory.join(CHECKPOINT_FILE_NAME);
+        let tmp_path = directory.join(format!("tmp.{}", CHECKPOINT_FILE_NAME));
+        let mut file = fs::File::create(&tmp_path)?;
+        file.write_all(&self.to_bytes()?)?;
+        file.sync_all()?;
+        drop(file);
+        fs::rename(tmp_path, &path)?;
+        // persist the rename itself, directories cannot be opened as files
+        // on Windows
+        #[cfg(unix)]
+        fs::File::open(directory)?.sync_all()?;
+        Ok(path)
+    }
+}
+
+fn invalid<E: std::fmt::Display>(e: E) -> ReadError {
+    ReadError::StructureInvalid(e.to_string())
+}
+
+fn write_hamt<K, V, F>(
+    codec: &mut Writer,
+    hamt: &Hamt<K, V>,
+    mut write: F,
+) -> Result<(), WriteError>
+where
+    K: Clone + Eq + hash::Hash,
+    F: FnMut(&mut Writer, &K, &V) -> Result<(), WriteError>,
+{
+    codec.put_be_u64(hamt.size() as u64)?;
+    for (key, value) in hamt.iter() {
+        write(codec, key, &**value)?;
+    }
+    Ok(())
+}
+
+fn read_hamt<K, V, F>(codec: &mut Reader, mut read: F) -> Result<Hamt<K, V>, ReadError>
+where
+    K: Clone + Eq + hash::Hash,
+    F: FnMut(&mut Reader) -> Result<(K, V), ReadError>,
+{
+    let mut hamt = Hamt::new();
+    for _ in 0..codec.get_be_u64()? {
+        let (key, value) = read(codec)?;
+        hamt = hamt.insert(key, Arc::new(value)).map_err(invalid)?;
+    }
+    Ok(hamt)
+}
+
+fn write_sequence<T, F>(
+    codec: &mut Writer,
+    sequence: &PersistentSequence<T>,
+    mut write: F,
+) -> Result<(), WriteError>
+where
+    F: FnMut(&mut Writer, &T) -> Result<(), WriteError>,
+{
+    codec.put_be_u64(sequence.len())?;
+    for i in 0..sequence.len() {
+        let element = sequence.get(i).expect("the sequence to be contiguous");
+        write(codec, &**element)?;
+    }
+    Ok(())
+}
+
+fn read_sequence<T, F>(codec: &mut Reader, mut read: F) -> Result<PersistentSequence<T>, ReadError>
+where
+    F: FnMut(&mut Reader) -> Result<T, ReadError>,
+{
+    let mut sequence = PersistentSequence::new();
+    for _ in 0..codec.get_be_u64()? {
+        sequence = sequence.append(read(codec)?);
+    }
+    Ok(sequence)
+}
+
+fn write_option<T, F>(codec: &mut Writer, option: Option<&T>, write: F) -> Result<(), WriteError>
+where
+    F: FnOnce(&mut Writer, &T) -> Result<(), WriteError>,
+{
+    match option {
+        None => codec.put_u8(0),
+        Some(value) => {
+            codec.put_u8(1)?;
+            write(codec, value)
+        }
+    }
+}
+
+fn read_option<T, F>(codec: &mut Reader, read: F) -> Result<Option<T>, ReadError>
+where
+    F: FnOnce(&mut Reader) -> Result<T, ReadError>,
+{
+    match codec.get_u8()? {
+        0 => Ok(None),
+        1 => read(codec).map(Some),
+        tag => Err(ReadError::UnknownTag(tag as u32)),
+    }
+}
+
+fn write_date(codec: &mut Writer, date: &BlockDate) -> Result<(), WriteError> {
+    codec.put_be_u32(date.epoch)?;
+    codec.put_be_u32(date.slot_id)
+}
+
+fn read_date(codec: &mut Reader) -> Result<BlockDate, ReadError> {
+    Ok(BlockDate {
+        epoch: codec.get_be_u32()?,
+        slot_id: codec.get_be_u32()?,
+    })
+}
+
+fn write_address(codec: &mut Writer, address: &ExplorerAddress) -> Result<(), WriteError> {
+    match address {
+        ExplorerAddress::New(address) => {
+            codec.put_u8(0)?;
+            Serialize::serialize(address, codec)
+        }
+        ExplorerAddress::Old(address) => {
+            codec.put_u8(1)?;
+            codec.put_be_u16(address.as_ref().len() as u16)?;
+            codec.put_bytes(address.as_ref())
+        }
+    }
+}
+
+fn read_address(codec: &mut Reader) -> Result<ExplorerAddress, ReadError> {
+    match codec.get_u8()? {
+        0 => Address::deserialize(codec).map(ExplorerAddress::New),
+        1 => {
+            let size = codec.get_be_u16()? as usize;
+            OldAddress::try_from(codec.get_slice(size)?)
+                .map(ExplorerAddress::Old)
+                .map_err(invalid)
+        }
+        tag => Err(ReadError::UnknownTag(tag as u32)),
+    }
+}
+
+fn write_options(codec: &mut Writer, options: &Options) -> Result<(), WriteError> {
+    codec.put_u8(options.choice_range().end)
+}
+
+fn read_options(codec: &mut Reader) -> Result<Options, ReadError> {
+    Options::new_length(codec.get_u8()?).map_err(invalid)
+}
+
+fn write_choices(codec: &mut Writer, choices: &[Choice]) -> Result<(), WriteError> {
+    codec.put_u8(choices.len() as u8)?;
+    for choice in choices {
+        codec.put_u8(choice.as_byte())?;
+    }
+    Ok(())
+}
+
+fn read_choices(codec: &mut Reader) -> Result<Vec<Choice>, ReadError> {
+    (0..codec.get_u8()?)
+        .map(|_| codec.get_u8().map(Choice::new))
+        .collect()
+}
+
+fn write_weights(codec: &mut Writer, weights: &[Weight]) -> Result<(), WriteError> {
+    codec.put_be_u32(weights.len() as u32)?;
+    for weight in weights {
+        codec.put_be_u64((*weight).into())?;
+    }
+    Ok(())
+}
+
+fn read_weights(codec: &mut Reader) -> Result<Vec<Weight>, ReadError> {
+    (0..codec.get_be_u32()?)
+        .map(|_| codec.get_be_u64().map(Weight::from))
+        .collect()
+}
+
+fn write_certificate(codec: &mut Writer, certificate: &Certificate) -> Result<(), WriteError> {
+    match certificate {
+        Certificate::StakeDelegation(c) => {
+            codec.put_u8(0)?;
+            Serialize::serialize(c, codec)
+        }
+        Certificate::OwnerStakeDelegation(c) => {
+            codec.put_u8(1)?;
+            Serialize::serialize(c, codec)
+        }
+        Certificate::PoolRegistration(c) => {
+            codec.put_u8(2)?;
+            Serialize::serialize(c, codec)
+        }
+        Certificate::PoolRetirement(c) => {
+            codec.put_u8(3)?;
+            Serialize::serialize(c, codec)
+        }
+        Certificate::PoolUpdate(c) => {
+            codec.put_u8(4)?;
+            Serialize::serialize(c, codec)
+        }
+        Certificate::VotePlan(c) => {
+            codec.put_u8(5)?;
+            Serialize::serialize(c, codec)
+        }
+        Certificate::VoteCast(c) => {
+            codec.put_u8(6)?;
+            Serialize::serialize(c, codec)
+        }
+        Certificate::VoteTally(c) => {
+            codec.put_u8(7)?;
+            Serialize::serialize(c, codec)
+        }
+        Certificate::UpdateProposal(c) => {
+            codec.put_u8(8)?;
+            Serialize::serialize(c, codec)
+        }
+        Certificate::UpdateVote(c) => {
+            codec.put_u8(9)?;
+            Serialize::serialize(c, codec)
+        }
+        Certificate::MintToken(c) => {
+            codec.put_u8(10)?;
+            Serialize::serialize(c, codec)
+        }
+        Certificate::EvmMapping(c) => {
+            codec.put_u8(11)?;
+            Serialize::serialize(c, codec)
+        }
+        Certificate::MultisigRegistration(c) => {
+            codec.put_u8(12)?;
+            Serialize::serialize(c, codec)
+        }
+    }
+}
+
+fn read_certificate(codec: &mut Reader) -> Result<Certificate, ReadError> {
+    Ok(match codec.get_u8()? {
+        0 => StakeDelegation::deserialize_from_slice(codec)?.into(),
+        1 => OwnerStakeDelegation::deserialize_from_slice(codec)?.into(),
+        2 => PoolRegistration::deserialize_from_slice(codec)?.into(),
+        3 => PoolRetirement::deserialize_from_slice(codec)?.into(),
+        4 => PoolUpdate::deserialize_from_slice(codec)?.into(),
+        5 => VotePlan::deserialize_from_slice(codec)?.into(),
+        6 => VoteCast::deserialize_from_slice(codec)?.into(),
+        7 => VoteTally::deserialize_from_slice(codec)?.into(),
+        8 => UpdateProposal::deserialize_from_slice(codec)?.into(),
+        9 => UpdateVote::deserialize_from_slice(codec)?.into(),
+        10 => MintToken::deserialize_from_slice(codec)?.into(),
+        11 => EvmMapping::deserialize_from_slice(codec)?.into(),
+        12 => MultisigRegistration::deserialize_from_slice(codec)?.into(),
+        tag => return Err(ReadError::UnknownTag(tag as u32)),
+    })
+}
+
+fn write_transaction(
+    codec: &mut Writer,
+    transaction: &ExplorerTransaction,
+) -> Result<(), WriteError> {
+    transaction.id.serialize(codec)?;
+    codec.put_be_u32(transaction.inputs.len() as u32)?;
+    for input in &transaction.inputs {
+        write_address(codec, &input.address)?;
+        input.value.serialize(codec)?;
+    }
+    codec.put_be_u32(transaction.outputs.len() as u32)?;
+    for output in &transaction.outputs {
+        write_address(codec, &output.address)?;
+        output.value.serialize(codec)?;
+    }
+    write_option(codec, transaction.certificate.as_ref(), write_certificate)?;
+    codec.put_be_u32(transaction.offset_in_block)?;
+    write_option(
+        codec,
+        transaction.config_params.as_ref(),
+        |codec, config_params| config_params.serialize(codec),
+    )
+}
+
+fn read_transaction(codec: &mut Reader) -> Result<ExplorerTransaction, ReadError> {
+    let id = HeaderHash::deserialize(codec)?;
+    let inputs: Vec<_> = (0..codec.get_be_u32()?)
+        .map(|_| {
+            Ok(ExplorerInput {
+                address: read_address(codec)?,
+                value: Value::deserialize(codec)?,
+            })
+        })
+        .collect::<Result<_, ReadError>>()?;
+    let outputs: Vec<_> = (0..codec.get_be_u32()?)
+        .map(|_| {
+            Ok(ExplorerOutput {
+                address: read_address(codec)?,
+                value: Value::deserialize(codec)?,
+            })
+        })
+        .collect::<Result<_, ReadError>>()?;
+    Ok(ExplorerTransaction {
+        id,
+        inputs,
+        outputs,
+        certificate: read_option(codec, read_certificate)?,
+        offset_in_block: codec.get_be_u32()?,
+        config_params: read_option(codec, ConfigParams::deserialize_from_slice)?,
+    })
+}
+
+fn write_block(codec: &mut Writer, block: &ExplorerBlock) -> Result<(), WriteError> {
+    block.id.serialize(codec)?;
+    write_date(codec, &block.date)?;
+    codec.put_be_u32(block.chain_length.into())?;
+    block.parent_hash.serialize(codec)?;
+    match &block.producer {
+        BlockProducer::None => codec.put_u8(0)?,
+        BlockProducer::StakePool(pool) => {
+            codec.put_u8(1)?;
+            codec.put_bytes(pool.as_ref())?;
+        }
+        BlockProducer::BftLeader(leader) => {
+            codec.put_u8(2)?;
+            Serialize::serialize(leader, codec)?;
+        }
+    }
+    block.total_input.serialize(codec)?;
+    block.total_output.serialize(codec)?;
+    codec.put_be_u32(block.transactions.len() as u32)?;
+    for transaction in block.transactions.values() {
+        write_transaction(codec, transaction)?;
+    }
+    Ok(())
+}
+
+fn read_block(codec: &mut Reader) -> Result<ExplorerBlock, ReadError> {
+    let id = HeaderHash::deserialize(codec)?;
+    let date = read_date(codec)?;
+    let chain_length = codec.get_be_u32()?.into();
+    let parent_hash = HeaderHash::deserialize(codec)?;
+    let producer = match codec.get_u8()? {
+        0 => BlockProducer::None,
+        1 => BlockProducer::StakePool(<[u8; 32]>::deserialize(codec)?.into()),
+        2 => BlockProducer::BftLeader(BftLeaderId::deserialize_from_slice(codec)?),
+        tag => return Err(ReadError::UnknownTag(tag as u32)),
+    };
+    let total_input = Value::deserialize(codec)?;
+    let total_output = Value::deserialize(codec)?;
+    let transactions = (0..codec.get_be_u32()?)
+        .map(|_| read_transaction(codec).map(|transaction| (transaction.id, transaction)))
+        .collect::<Result<HashMap<_, _>, _>>()?;
+    Ok(ExplorerBlock {
+        transactions,
+        id,
+        date,
+        chain_length,
+        parent_hash,
+        producer,
+        total_input,
+        total_output,
+    })
+}
+
+fn write_turnout(codec: &mut Writer, turnout: &ExplorerVoteTurnout) -> Result<(), WriteError> {
+    codec.put_be_u64(turnout.voters)?;
+    codec.put_be_u64(turnout.voting_power)?;
+    codec.put_be_u64(turnout.total_voting_power)
+}
+
+fn read_turnout(codec: &mut Reader) -> Result<ExplorerVoteTurnout, ReadError> {
+    Ok(ExplorerVoteTurnout {
+        voters: codec.get_be_u64()?,
+        voting_power: codec.get_be_u64()?,
+        total_voting_power: codec.get_be_u64()?,
+    })
+}
+
+fn write_tally(codec: &mut Writer, tally: &ExplorerVoteTally) -> Result<(), WriteError> {
+    match tally {
+        ExplorerVoteTally::Public {
+            results,
+            options,
+            winners,
+            total_weight,
+            rounds,
+        } => {
+            codec.put_u8(0)?;
+            write_weights(codec, results)?;
+            write_options(codec, options)?;
+            write_choices(codec, winners)?;
+            codec.put_be_u64(*total_weight)?;
+            codec.put_be_u32(rounds.len() as u32)?;
+            for round in rounds {
+                write_weights(codec, round)?;
+            }
+            Ok(())
+        }
+        ExplorerVoteTally::Private { results, options } => {
+            codec.put_u8(1)?;
+            write_option(codec, results.as_ref(), |codec, results| {
+                write_weights(codec, results)
+            })?;
+            write_options(codec, options)
+        }
+    }
+}
+
+fn read_tally(codec: &mut Reader) -> Result<ExplorerVoteTally, ReadError> {
+    match codec.get_u8()? {
+        0 => Ok(ExplorerVoteTally::Public {
+            results: read_weights(codec)?.into(),
+            options: read_options(codec)?,
+            winners: read_choices(codec)?,
+            total_weight: codec.get_be_u64()?,
+            rounds: (0..codec.get_be_u32()?)
+                .map(|_| read_weights(codec).map(Into::into))
+                .collect::<Result<_, _>>()?,
+        }),
+        1 => Ok(ExplorerVoteTally::Private {
+            results: read_option(codec, read_weights)?,
+            options: read_options(codec)?,
+        }),
+        tag => Err(ReadError::UnknownTag(tag as u32)),
+    }
+}
+
+/// votes are kept as the vote cast certificate they come from, which
+/// already knows how to serialize every kind of ballot
+fn write_vote(
+    codec: &mut Writer,
+    vote_plan: &VotePlanId,
+    proposal_index: u8,
+    vote: &ExplorerVote,
+) -> Result<(), WriteError> {
+    let payload = match vote {
+        ExplorerVote::Public(choice) => Payload::public(*choice),
+        ExplorerVote::Private {
+            proof,
+            encrypted_vote,
+        } => Payload::private(encrypted_vote.clone(), proof.clone()),
+        ExplorerVote::RankedChoice(ranking) => Payload::ranked_choice(ranking.clone()),
+        ExplorerVote::Approval(approvals) => Payload::approval(approvals.clone()),
+        ExplorerVote::Quadratic { choice, credits } => Payload::quadratic(*choice, *credits),
+    };
+    Serialize::serialize(
+        &VoteCast::new(vote_plan.clone(), proposal_index, payload),
+        codec,
+    )
+}
+
+fn read_vote(codec: &mut Reader) -> Result<ExplorerVote, ReadError> {
+    VoteCast::deserialize_from_slice(codec).map(|vote_cast| ExplorerVote::from(vote_cast.payload()))
+}
+
+fn write_vote_plan(codec: &mut Writer, vote_plan: &ExplorerVotePlan) -> Result<(), WriteError> {
+    codec.put_bytes(vote_plan.id.as_ref())?;
+    write_date(codec, &vote_plan.vote_start)?;
+    write_date(codec, &vote_plan.vote_end)?;
+    write_date(codec, &vote_plan.committee_end)?;
+    codec.put_u8(vote_plan.payload_type as u8)?;
+    codec.put_u8(vote_plan.proposals.len() as u8)?;
+    for (index, proposal) in vote_plan.proposals.iter().enumerate() {
+        codec.put_bytes(proposal.proposal_id.as_ref())?;
+        write_options(codec, &proposal.options)?;
+        write_option(codec, proposal.tally.as_ref(), write_tally)?;
+        write_hamt(codec, &proposal.votes, |codec, voter, vote| {
+            write_address(codec, voter)?;
+            write_vote(codec, &vote_plan.id, index as u8, vote)
+        })?;
+        write_turnout(codec, &proposal.turnout)?;
+    }
+    write_option(codec, vote_plan.tally_date.as_ref(), write_date)?;
+    write_turnout(codec, &vote_plan.turnout)
+}
+
+fn read_vote_plan(codec: &mut Reader) -> Result<ExplorerVotePlan, ReadError> {
+    let id = <[u8; 32]>::deserialize(codec)?.into();
+    let vote_start = read_date(codec)?;
+    let vote_end = read_date(codec)?;
+    let committee_end = read_date(codec)?;
+    let payload_type = PayloadType::try_from(codec.get_u8()?).map_err(invalid)?;
+    let proposals: Vec<_> = (0..codec.get_u8()?)
+        .map(|_| {
+            Ok(ExplorerVoteProposal {
+                proposal_id: <[u8; 32]>::deserialize(codec)?.into(),
+                options: read_options(codec)?,
+                tally: read_option(codec, read_tally)?,
+                votes: read_hamt(codec, |codec| Ok((read_address(codec)?, read_vote(codec)?)))?,
+                turnout: read_turnout(codec)?,
+            })
+        })
+        .collect::<Result<_, ReadError>>()?;
+    Ok(ExplorerVotePlan {
+        id,
+        vote_start,
+        vote_end,
+        committee_end,
+        payload_type,
+        proposals,
+        tally_date: read_option(codec, read_date)?,
+        turnout: read_turnout(codec)?,
+    })
+}
+
+fn write_stake_control(codec: &mut Writer, stake_control: &StakeControl) -> Result<(), WriteError> {
+    codec.put_be_u64(stake_control.unassigned().0)?;
+    let stakes: Vec<_> = stake_control.iter().collect();
+    codec.put_be_u64(stakes.len() as u64)?;
+    for (identifier, stake) in stakes {
+        Serialize::serialize(identifier, codec)?;
+        codec.put_be_u64(stake.0)?;
+    }
+    Ok(())
+}
+
+fn read_stake_control(codec: &mut Reader) -> Result<StakeControl, ReadError> {
+    let mut stake_control = StakeControl::new().add_unassigned(Stake(codec.get_be_u64()?));
+    for _ in 0..codec.get_be_u64()? {
+        let identifier = Identifier::deserialize_from_slice(codec)?;
+        stake_control = stake_control.add_to(identifier, Stake(codec.get_be_u64()?));
+    }
+    Ok(stake_control)
+}
+
+fn write_state(codec: &mut Writer, state: &State) -> Result<(), WriteError> {
+    write_hamt(codec, &state.transactions, |codec, id, block| {
+        id.serialize(codec)?;
+        block.serialize(codec)
+    })?;
+    write_hamt(codec, &state.blocks, |codec, _, block| {
+        write_block(codec, block)
+    })?;
+    write_hamt(codec, &state.addresses, |codec, address, transactions| {
+        write_address(codec, address)?;
+        write_sequence(codec, transactions, |codec, id| id.serialize(codec))
+    })?;
+    write_hamt(codec, &state.epochs, |codec, epoch, data| {
+        codec.put_be_u32(*epoch)?;
+        data.first_block.serialize(codec)?;
+        data.last_block.serialize(codec)?;
+        codec.put_be_u32(data.total_blocks)
+    })?;
+    write_hamt(codec, &state.chain_lengths, |codec, chain_length, block| {
+        codec.put_be_u32((*chain_length).into())?;
+        block.serialize(codec)
+    })?;
+    write_hamt(codec, &state.stake_pool_data, |codec, pool, data| {
+        codec.put_bytes(pool.as_ref())?;
+        Serialize::serialize(&data.registration, codec)?;
+        write_option(codec, data.retirement.as_ref(), |codec, retirement| {
+            Serialize::serialize(retirement, codec)
+        })
+    })?;
+    write_hamt(codec, &state.stake_pool_blocks, |codec, pool, blocks| {
+        codec.put_bytes(pool.as_ref())?;
+        write_sequence(codec, blocks, |codec, block| block.serialize(codec))
+    })?;
+    write_hamt(codec, &state.vote_plans, |codec, _, vote_plan| {
+        write_vote_plan(codec, vote_plan)
+    })?;
+    write_stake_control(codec, &state.stake_control)
+}
+
+fn read_state(codec: &mut Reader) -> Result<State, ReadError> {
+    Ok(State {
+        transactions: read_hamt(codec, |codec| {
+            Ok((
+                HeaderHash::deserialize(codec)?,
+                HeaderHash::deserialize(codec)?,
+            ))
+        })?,
+        blocks: read_hamt(codec, |codec| {
+            read_block(codec).map(|block| (block.id, block))
+        })?,
+        addresses: read_hamt(codec, |codec| {
+            Ok((
+                read_address(codec)?,
+                read_sequence(codec, HeaderHash::deserialize_from_slice)?,
+            ))
+        })?,
+        epochs: read_hamt(codec, |codec| {
+            Ok((
+                codec.get_be_u32()?,
+                EpochData {
+                    first_block: HeaderHash::deserialize(codec)?,
+                    last_block: HeaderHash::deserialize(codec)?,
+                    total_blocks: codec.get_be_u32()?,
+                },
+            ))
+        })?,
+        chain_lengths: read_hamt(codec, |codec| {
+            Ok((codec.get_be_u32()?.into(), HeaderHash::deserialize(codec)?))
+        })?,
+        stake_pool_data: read_hamt(codec, |codec| {
+            Ok((
+                <[u8; 32]>::deserialize(codec)?.into(),
+                StakePoolData {
+                    registration: PoolRegistration::deserialize_from_slice(codec)?,
+                    retirement: read_option(codec, PoolRetirement::deserialize_from_slice)?,
+                },
+            ))
+        })?,
+        stake_pool_blocks: read_hamt(codec, |codec| {
+            Ok((
+                <[u8; 32]>::deserialize(codec)?.into(),
+                read_sequence(codec, HeaderHash::deserialize_from_slice)?,
+            ))
+        })?,
+        vote_plans: read_hamt(codec, |codec| {
+            read_vote_plan(codec).map(|vote_plan| (vote_plan.id.clone(), vote_plan))
+        })?,
+        stake_control: read_stake_control(codec)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::tests::TestChain, *};
+    use crate::db::ExplorerDb;
+
+    async fn checkpoint() -> Checkpoint {
+        let mut chain = TestChain::new();
+        let db = ExplorerDb::bootstrap(chain.block0(), None).unwrap();
+        for _ in 0..3 {
+            let block = chain.next_block();
+            db.apply_block(block.clone()).await.unwrap();
+            db.set_tip(block.id()).await.unwrap();
+        }
+        let (block, state_ref) = db.get_tip().await;
+        Checkpoint {
+            block,
+            chain_length: ChainLength::from(3),
+            state: state_ref.state().clone(),
+        }
+    }
+
+    #[tokio::test]
+    async fn checkpoint_round_trip() {
+        let checkpoint = checkpoint().await;
+        let decoded = Checkpoint::from_bytes(&checkpoint.to_bytes().unwrap()).unwrap();
+
+        assert_eq!(decoded.block, checkpoint.block);
+        assert_eq!(decoded.chain_length, checkpoint.chain_length);
+        let (state, decoded_state) = (&checkpoint.state, &decoded.state);
+        assert_eq!(decoded_state.blocks.size(), state.blocks.size());
+        for (id, block) in state.blocks.iter() {
+            let decoded_block = decoded_state.blocks.lookup(id).unwrap();
+            assert_eq!(decoded_block.parent_hash, block.parent_hash);
+            assert_eq!(decoded_block.chain_length, block.chain_length);
+            assert_eq!(decoded_block.date, block.date);
+            assert_eq!(decoded_block.total_output, block.total_output);
+            for (id, transaction) in &block.transactions {
+                let decoded_transaction = &decoded_block.transactions[id];
+                assert_eq!(
+                    decoded_transaction.offset_in_block,
+                    transaction.offset_in_block
+                );
+                assert_eq!(decoded_transaction.outputs.len(), transaction.outputs.len());
+            }
+        }
+        for (id, block) in state.transactions.iter() {
+            assert_eq!(decoded_state.transactions.lookup(id), Some(block));
+        }
+        for (chain_length, block) in state.chain_lengths.iter() {
+            assert_eq!(
+                decoded_state.chain_lengths.lookup(chain_length),
+                Some(block)
+            );
+        }
+        for (address, transactions) in state.addresses.iter() {
+            let decoded_transactions = decoded_state.addresses.lookup(address).unwrap();
+            assert_eq!(decoded_transactions.len(), transactions.len());
+            assert_eq!(decoded_transactions.get(0u64), transactions.get(0u64));
+        }
+        assert_eq!(decoded_state.stake_control, state.stake_control);
+    }
+
+    #[tokio::test]
+    async fn corrupted_checkpoint_is_rejected() {
+        let bytes = checkpoint().await.to_bytes().unwrap();
+        for position in [0, bytes.len() / 2, bytes.len() - 1] {
+            let mut corrupted = bytes.clone();
+            corrupted[position] ^= 1;
+            assert!(matches!(
+                Checkpoint::from_bytes(&corrupted),
+                Err(CheckpointError::ChecksumMismatch)
+            ));
+        }
+    }
+}
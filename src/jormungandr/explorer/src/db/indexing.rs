@@ -13,7 +13,7 @@ use chain_impl_mockchain::{
     key::{BftLeaderId, Hash},
     transaction::{InputEnum, TransactionSlice, Witness},
     value::Value,
-    vote::{Choice, EncryptedVote, Options, PayloadType, ProofOfCorrectVote, Weight},
+    vote::{Choice, EncryptedVote, Options, Payload, PayloadType, ProofOfCorrectVote, Weight},
 };
 use error::ExplorerError as Error;
 use std::{
@@ -121,6 +121,10 @@ pub struct ExplorerVotePlan {
     pub committee_end: BlockDate,
     pub payload_type: PayloadType,
     pub proposals: Vec<ExplorerVoteProposal>,
+    /// the date of the block holding the vote tally certificate, set once
+    /// the final results are on chain
+    pub tally_date: Option<BlockDate>,
+    pub turnout: ExplorerVoteTurnout,
 }
 
 #[derive(Clone, Debug)]
@@ -130,6 +134,40 @@ pub enum ExplorerVote {
         proof: ProofOfCorrectVote,
         encrypted_vote: EncryptedVote,
     },
+    RankedChoice(Vec<Choice>),
+    Approval(Vec<Choice>),
+    Quadratic {
+        choice: Choice,
+        credits: u64,
+    },
+}
+
+impl From<&Payload> for ExplorerVote {
+    fn from(payload: &Payload) -> Self {
+        match payload {
+            Payload::Public { choice } => ExplorerVote::Public(*choice),
+            // the credits allocated in a private quadratic vote are encrypted
+            // too, only the vote itself is kept
+            Payload::Private {
+                proof,
+                encrypted_vote,
+            }
+            | Payload::PrivateQuadratic {
+                proof,
+                encrypted_vote,
+                ..
+            } => ExplorerVote::Private {
+                proof: proof.clone(),
+                encrypted_vote: encrypted_vote.clone(),
+            },
+            Payload::RankedChoice { ranking } => ExplorerVote::RankedChoice(ranking.clone()),
+            Payload::Approval { approvals } => ExplorerVote::Approval(approvals.clone()),
+            Payload::Quadratic { choice, credits } => ExplorerVote::Quadratic {
+                choice: *choice,
+                credits: *credits,
+            },
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -138,14 +176,19 @@ pub struct ExplorerVoteProposal {
     pub options: Options,
     pub tally: Option<ExplorerVoteTally>,
     pub votes: Hamt<ExplorerAddress, ExplorerVote>,
+    pub turnout: ExplorerVoteTurnout,
 }
 
-// TODO do proper vote tally
 #[derive(Clone, Debug)]
 pub enum ExplorerVoteTally {
     Public {
         results: Box<[Weight]>,
         options: Options,
+        winners: Vec<Choice>,
+        total_weight: u64,
+        /// the rounds of the instant-runoff of a ranked choice proposal,
+        /// `results` holds the last one
+        rounds: Vec<Box<[Weight]>>,
     },
     Private {
         results: Option<Vec<Weight>>,
@@ -153,6 +196,17 @@ pub enum ExplorerVoteTally {
     },
 }
 
+/// The participation to a proposal or to a whole vote plan. An account
+/// counts once no matter how many votes it casts, with the stake it holds
+/// at the time its first vote is cast. Everything is computed again from the
+/// stake distribution when the vote tally certificate is applied.
+#[derive(Clone, Debug, Default)]
+pub struct ExplorerVoteTurnout {
+    pub voters: u64,
+    pub voting_power: u64,
+    pub total_voting_power: u64,
+}
+
 #[derive(Debug)]
 pub struct ExplorerBlockBuildingContext<'a> {
     pub discrimination: Discrimination,
@@ -379,6 +433,25 @@ impl ExplorerBlock {
                         }
                     }
                 }
+                Fragment::MultisigRegistration(tx) => {
+                    let tx = tx.as_slice();
+                    match ExplorerTransaction::from(
+                        &context,
+                        &fragment_id,
+                        &tx,
+                        Some(Certificate::MultisigRegistration(
+                            tx.payload().into_payload(),
+                        )),
+                        offset,
+                        &current_block_txs,
+                    ) {
+                        Ok(tx) => Some(tx),
+                        Err(e) => {
+                            error!(error = %e, "unable to map multisig registration fragment");
+                            return Err(Error::ExplorerTransmuteFail);
+                        }
+                    }
+                }
                 Fragment::OldUtxoDeclaration(decl) => {
                     let outputs = decl
                         .addrs
@@ -514,6 +587,7 @@ impl ExplorerTransaction {
                     new_inputs.push(ExplorerInput { address, value });
                 }
                 (InputEnum::AccountInput(_, _), Witness::OldUtxo(_, _, _)) => {}
+                (InputEnum::AccountInput(_, _), Witness::Script(_)) => {}
                 (InputEnum::AccountInput(id, value), Witness::Multisig(_, _)) => {
                     let kind = chain_addr::Kind::Multisig(
                         match id.to_multi_account().as_ref().try_into() {
@@ -529,7 +603,8 @@ impl ExplorerTransaction {
                     let address = ExplorerAddress::New(Address(context.discrimination, kind));
                     new_inputs.push(ExplorerInput { address, value });
                 }
-                (InputEnum::UtxoInput(utxo_pointer), Witness::Utxo(_)) => {
+                (InputEnum::UtxoInput(utxo_pointer), Witness::Utxo(_))
+                | (InputEnum::UtxoInput(utxo_pointer), Witness::Script(_)) => {
                     let tx = utxo_pointer.transaction_id;
                     let index = utxo_pointer.output_index;
 
@@ -1,19 +1,23 @@
+pub mod checkpoint;
 pub mod error;
 pub mod indexing;
 pub mod multiverse;
 pub mod persistent_sequence;
+pub mod storage;
 pub(crate) mod tally;
 
 use self::{
+    checkpoint::Checkpoint,
     error::{BlockNotFound, ExplorerError as Error},
     indexing::{
         Addresses, Blocks, ChainLengths, EpochData, Epochs, ExplorerAddress, ExplorerBlock,
-        ExplorerVote, ExplorerVotePlan, ExplorerVoteProposal, StakePool, StakePoolBlocks,
-        StakePoolData, Transactions, VotePlans,
+        ExplorerVote, ExplorerVotePlan, ExplorerVoteProposal, ExplorerVoteTurnout, StakePool,
+        StakePoolBlocks, StakePoolData, Transactions, VotePlans,
     },
     persistent_sequence::PersistentSequence,
+    storage::Storage,
 };
-use crate::db::tally::{compute_private_tally, compute_public_tally};
+use crate::db::tally::{add_voter, compute_private_tally, compute_public_tally, compute_turnout};
 use chain_addr::Discrimination;
 use chain_core::property::Block as _;
 use chain_impl_mockchain::{
@@ -39,6 +43,16 @@ use std::{
 use tokio::sync::{broadcast, RwLock};
 use tracing::error;
 
+/// How many main branch changes a slow subscriber can fall behind before
+/// missing some.
+pub(crate) const CHAIN_EVENTS_BUFFER_SIZE: usize = 256;
+
+/// How many blocks get confirmed between two checkpoints of the indexes.
+#[cfg(not(test))]
+const CHECKPOINT_INTERVAL: u32 = 1000;
+#[cfg(test)]
+const CHECKPOINT_INTERVAL: u32 = 4;
+
 #[derive(Clone)]
 pub struct Explorer {
     pub db: ExplorerDb,
@@ -47,6 +61,20 @@ pub struct Explorer {
 #[derive(Clone)]
 struct Tip(Arc<RwLock<HeaderHash>>);
 
+/// Changes of the main branch, in the order they happen.
+#[derive(Clone, Debug)]
+pub enum ChainEvent {
+    /// A block was appended to the main branch
+    Block(Arc<ExplorerBlock>),
+    /// The main branch switched to another branch, the blocks of the previous
+    /// branch above `fork_point` are not part of the main branch anymore.
+    /// The blocks of the new branch follow as `Block` events.
+    Rollback {
+        previous_tip: HeaderHash,
+        fork_point: HeaderHash,
+    },
+}
+
 #[derive(Clone)]
 pub struct ExplorerDb {
     /// Structure that keeps all the known states to allow easy branch management
@@ -60,11 +88,17 @@ pub struct ExplorerDb {
     pub blockchain_config: BlockchainConfig,
     stable_store: StableIndex,
     tip_broadcast: tokio::sync::broadcast::Sender<(HeaderHash, multiverse::Ref)>,
+    chain_broadcast: tokio::sync::broadcast::Sender<ChainEvent>,
+    /// On-disk copy of the indexed blocks, used to rebuild the indexes
+    /// after a restart.
+    storage: Option<Storage>,
 }
 
 #[derive(Clone)]
 pub struct StableIndex {
     confirmed_block_chain_length: Arc<AtomicU32>,
+    /// chain length of the block the last checkpoint was taken at
+    checkpoint_chain_length: Arc<AtomicU32>,
 }
 
 #[derive(Clone)]
@@ -106,18 +140,121 @@ pub struct Settings {
 }
 
 impl ExplorerDb {
-    pub fn bootstrap(block0: Block) -> Result<Self, Error> {
-        let blockchain_config = BlockchainConfig::from_config_params(
-            block0
-                .contents()
-                .iter()
-                .find_map(|fragment| match fragment {
-                    Fragment::Initial(config_params) => Some(config_params),
-                    _ => None,
-                })
-                .expect("the Initial fragment to be present in the genesis block"),
+    pub fn bootstrap(block0: Block, storage: Option<Storage>) -> Result<Self, Error> {
+        if let Some(storage) = &storage {
+            storage.put_block(&block0).map_err(storage_error)?;
+        }
+        Self::bootstrap_with(block0, storage)
+    }
+
+    /// Bootstrap the indexes from the last checkpoint kept in the storage,
+    /// or from the block0 if there is no valid checkpoint. Returns `None`
+    /// if the storage is empty.
+    ///
+    /// The stored blocks following the checkpoint are indexed with
+    /// [`reindex`](Self::reindex), during which the database can already
+    /// be queried.
+    pub fn restore(storage: Storage) -> Result<Option<Self>, Error> {
+        let block0 = match storage
+            .get_blocks_by_chain_length(0)
+            .map_err(storage_error)?
+            .pop()
+        {
+            Some(block0) => block0,
+            None => return Ok(None),
+        };
+
+        let checkpoint = Checkpoint::read_from_directory(storage.path()).unwrap_or_else(|e| {
+            tracing::warn!(
+                error = %e,
+                "cannot use the checkpoint of the indexes, indexing all the stored blocks"
+            );
+            None
+        });
+
+        match checkpoint {
+            Some(checkpoint) => {
+                tracing::info!(
+                    block = %checkpoint.block,
+                    chain_length = %checkpoint.chain_length,
+                    "restoring the indexes from a checkpoint"
+                );
+                Ok(Self::with_state(
+                    blockchain_config(&block0),
+                    checkpoint.chain_length,
+                    checkpoint.block,
+                    checkpoint.state,
+                    Some(storage),
+                ))
+            }
+            None => Self::bootstrap_with(block0, Some(storage)),
+        }
+        .map(Some)
+    }
+
+    /// Rebuild the indexes from the blocks kept in the storage that follow
+    /// the current tip. Returns the number of indexed blocks.
+    ///
+    /// The tip follows the indexed blocks so that queries see the progress,
+    /// and is moved to the stored tip once all the blocks are indexed.
+    pub async fn reindex(&self) -> Result<u32, Error> {
+        let storage = match &self.storage {
+            Some(storage) => storage,
+            None => return Ok(0),
+        };
+
+        let (tip, tip_ref) = self.get_tip().await;
+        let mut chain_length = u32::from(
+            tip_ref
+                .state()
+                .blocks
+                .lookup(&tip)
+                .expect("the tip to be indexed")
+                .chain_length(),
+        ) + 1;
+        let mut indexed = 0;
+        loop {
+            let blocks = storage
+                .get_blocks_by_chain_length(chain_length)
+                .map_err(storage_error)?;
+            if blocks.is_empty() {
+                break;
+            }
+            let current_tip = self.longest_chain_tip.get_block_id().await;
+            let mut next_tip = None;
+            for block in blocks {
+                if next_tip.is_none() && block.parent_id() == current_tip {
+                    next_tip = Some(block.id());
+                }
+                match self.index_block(block).await {
+                    Ok(_) => indexed += 1,
+                    // a branch forking before the checkpoint
+                    Err(Error::AncestorNotFound(_)) => continue,
+                    Err(e) => return Err(e),
+                }
+            }
+            if let Some(tip) = next_tip {
+                self.update_tip(tip, false).await?;
+            }
+            chain_length += 1;
+        }
+
+        if let Some(tip) = storage.get_tip().map_err(storage_error)? {
+            self.set_tip(tip).await?;
+        }
+
+        tracing::info!(
+            "rebuilt the indexes up to chain length {} from the storage, {} blocks indexed",
+            chain_length - 1,
+            indexed
         );
 
+        Ok(indexed)
+    }
+
+    fn bootstrap_with(block0: Block, storage: Option<Storage>) -> Result<Self, Error> {
+        let blockchain_config = blockchain_config(&block0);
+
         let block = ExplorerBlock::resolve_from(
             &block0,
             indexing::ExplorerBlockBuildingContext {
@@ -149,24 +286,41 @@ impl ExplorerDb {
             stake_control,
         };
 
-        let block0_id = block0.id();
-        let (_, multiverse) = Multiverse::new(block0.chain_length(), block0_id, initial_state);
+        Ok(Self::with_state(
+            blockchain_config,
+            block0.chain_length(),
+            block0.id(),
+            initial_state,
+            storage,
+        ))
+    }
 
-        let block0_id = block0.id();
+    /// The database with the indexes `state` after the block `block_id`,
+    /// a confirmed block which becomes the tip.
+    fn with_state(
+        blockchain_config: BlockchainConfig,
+        chain_length: ChainLength,
+        block_id: HeaderHash,
+        state: State,
+        storage: Option<Storage>,
+    ) -> Self {
+        let (_, multiverse) = Multiverse::new(chain_length, block_id, state);
 
         let (tx, _) = broadcast::channel(10);
+        let (chain_tx, _) = broadcast::channel(CHAIN_EVENTS_BUFFER_SIZE);
 
-        let bootstraped_db = ExplorerDb {
+        ExplorerDb {
             multiverse,
-            longest_chain_tip: Tip::new(block0_id),
+            longest_chain_tip: Tip::new(block_id),
             blockchain_config,
             stable_store: StableIndex {
-                confirmed_block_chain_length: Arc::new(AtomicU32::default()),
+                confirmed_block_chain_length: Arc::new(AtomicU32::new(chain_length.into())),
+                checkpoint_chain_length: Arc::new(AtomicU32::new(chain_length.into())),
             },
             tip_broadcast: tx,
-        };
-
-        Ok(bootstraped_db)
+            chain_broadcast: chain_tx,
+            storage,
+        }
     }
 
     /// Try to add a new block to the indexes, this can fail if the parent of the block is
@@ -174,6 +328,18 @@ impl ExplorerDb {
     /// This doesn't perform any validation on the given block and the previous state, it
     /// is assumed that the Block is valid
     pub async fn apply_block(&self, block: Block) -> Result<multiverse::Ref, Error> {
+        if let Some(storage) = &self.storage {
+            storage.put_block(&block).map_err(storage_error)?;
+        }
+        self.index_block(block).await
+    }
+
+    /// Whether the block has been indexed in any of the branches.
+    pub async fn contains_block(&self, block_id: &HeaderHash) -> bool {
+        self.get_block(block_id).await.is_some()
+    }
+
+    async fn index_block(&self, block: Block) -> Result<multiverse::Ref, Error> {
         let previous_block = block.header().block_parent_hash();
         let chain_length = block.header().chain_length();
         let block_id = block.header().hash();
@@ -244,6 +410,13 @@ impl ExplorerDb {
     }
 
     pub async fn set_tip(&self, hash: HeaderHash) -> Result<(), BlockNotFound> {
+        self.update_tip(hash, true).await
+    }
+
+    /// Move the tip to the given block, `persist` tells whether the storage
+    /// should record the new tip, which is not the case while the storage
+    /// is being reindexed.
+    async fn update_tip(&self, hash: HeaderHash, persist: bool) -> Result<(), BlockNotFound> {
         // the tip changes which means now a block is confirmed (at least after
         // the initial epoch_stability_depth blocks).
 
@@ -255,6 +428,7 @@ impl ExplorerDb {
 
         let state = state_ref.state();
         let block = Arc::clone(state.blocks.lookup(&hash).unwrap());
+        let mut checkpoint = None;
 
         if let Some(confirmed_block_chain_length) = block
             .chain_length()
@@ -272,13 +446,36 @@ impl ExplorerDb {
                 .confirmed_block_chain_length
                 .store(confirmed_block_chain_length.into(), Ordering::Release);
 
+            if persist {
+                checkpoint = self
+                    .next_checkpoint(state, confirmed_block_chain_length)
+                    .await;
+            }
+
             self.multiverse
                 .gc(self.blockchain_config.epoch_stability_depth)
                 .await;
         }
 
+        if let Some(storage) = self.storage.as_ref().filter(|_| persist) {
+            storage
+                .put_tip(hash)
+                .and_then(|()| storage.gc(self.blockchain_config.epoch_stability_depth, hash))
+                .unwrap_or_else(|e| error!(error = %e, "failed to update the explorer storage"));
+
+            if let Some(checkpoint) = checkpoint {
+                save_checkpoint(storage, checkpoint).await;
+            }
+        }
+
         let mut guard = self.longest_chain_tip.0.write().await;
 
+        if self.chain_broadcast.receiver_count() > 0 {
+            for event in self.chain_events(*guard, &state_ref).await {
+                let _ = self.chain_broadcast.send(event);
+            }
+        }
+
         *guard = hash;
 
         let _ = self.tip_broadcast.send((hash, state_ref));
@@ -286,6 +483,40 @@ impl ExplorerDb {
         Ok(())
     }
 
+    /// The checkpoint to take at the confirmed block of the branch of `state`
+    /// with the given chain length, if the interval since the last
+    /// checkpoint is over.
+    async fn next_checkpoint(
+        &self,
+        state: &State,
+        chain_length: ChainLength,
+    ) -> Option<Checkpoint> {
+        self.storage.as_ref()?;
+        let last = self
+            .stable_store
+            .checkpoint_chain_length
+            .load(Ordering::Acquire);
+        if u32::from(chain_length) < last + CHECKPOINT_INTERVAL {
+            return None;
+        }
+        let block = **state.chain_lengths.lookup(&chain_length)?;
+        let state_ref = self.multiverse.get_ref(&block).await?;
+        self.stable_store
+            .checkpoint_chain_length
+            .compare_exchange(
+                last,
+                chain_length.into(),
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            )
+            .ok()?;
+        Some(Checkpoint {
+            block,
+            chain_length,
+            state: state_ref.state().clone(),
+        })
+    }
+
     pub async fn get_block_with_branches(
         &self,
         block_id: &HeaderHash,
@@ -430,6 +661,56 @@ impl ExplorerDb {
         self.multiverse.tips().await
     }
 
+    /// The events leading from the main branch ending at `previous_tip` to
+    /// the branch of `new_tip`.
+    async fn chain_events(
+        &self,
+        previous_tip: HeaderHash,
+        new_tip: &multiverse::Ref,
+    ) -> Vec<ChainEvent> {
+        let previous_state = self.multiverse.get_ref(&previous_tip).await;
+        let is_on_previous_branch = |hash: &HeaderHash| match &previous_state {
+            Some(previous_state) => previous_state.state().blocks.lookup(hash).is_some(),
+            None => *hash == previous_tip,
+        };
+
+        let new_state = new_tip.state();
+        let mut blocks = Vec::new();
+        let mut current = *new_tip.id();
+        let fork_point = loop {
+            if is_on_previous_branch(&current) {
+                break current;
+            }
+            match new_state.blocks.lookup(&current) {
+                Some(block) => {
+                    current = block.parent_hash;
+                    blocks.push(Arc::clone(block));
+                }
+                // the previous tip was garbage collected along with the
+                // blocks shared with the new branch
+                None => break current,
+            }
+        };
+
+        let mut events = Vec::with_capacity(blocks.len() + 1);
+        if fork_point != previous_tip {
+            events.push(ChainEvent::Rollback {
+                previous_tip,
+                fork_point,
+            });
+        }
+        events.extend(blocks.into_iter().rev().map(ChainEvent::Block));
+        events
+    }
+
+    /// Subscribe to the changes of the main branch.
+    pub fn chain_subscription(
+        &self,
+    ) -> impl Stream<Item = Result<ChainEvent, tokio_stream::wrappers::errors::BroadcastStreamRecvError>>
+    {
+        tokio_stream::wrappers::BroadcastStream::new(self.chain_broadcast.subscribe())
+    }
+
     pub fn tip_subscription(
         &self,
     ) -> impl Stream<
@@ -442,6 +723,45 @@ impl ExplorerDb {
     }
 }
 
+async fn save_checkpoint(storage: &Storage, checkpoint: Checkpoint) {
+    let directory = storage.path().to_path_buf();
+    let block = checkpoint.block;
+    let result = tokio::task::spawn_blocking(move || checkpoint.write_to_directory(directory))
+        .await
+        .expect("explorer checkpoint writer panicked");
+    match result {
+        Ok(path) => {
+            tracing::info!(block = %block, path = %path.display(), "saved a checkpoint of the indexes")
+        }
+        Err(e) => error!(error = %e, "failed to save a checkpoint of the indexes"),
+    }
+}
+
+fn blockchain_config(block0: &Block) -> BlockchainConfig {
+    BlockchainConfig::from_config_params(
+        block0
+            .contents()
+            .iter()
+            .find_map(|fragment| match fragment {
+                Fragment::Initial(config_params) => Some(config_params),
+                _ => None,
+            })
+            .expect("the Initial fragment to be present in the genesis block"),
+    )
+}
+
+fn storage_error(e: storage::StorageError) -> Error {
+    use std::error::Error as _;
+
+    let mut msg = e.to_string();
+    let mut source = e.source();
+    while let Some(err) = source {
+        msg = format!("{}: {}", msg, err);
+        source = err.source();
+    }
+    Error::StorageError(msg)
+}
+
 fn apply_block_to_transactions(
     mut transactions: Transactions,
     block: &ExplorerBlock,
@@ -628,8 +948,11 @@ fn apply_block_to_vote_plans(
                                     options: proposal.options().clone(),
                                     tally: None,
                                     votes: Default::default(),
+                                    turnout: Default::default(),
                                 })
                                 .collect(),
+                            tally_date: None,
+                            turnout: Default::default(),
                         }),
                     )
                     .unwrap(),
@@ -637,18 +960,27 @@ fn apply_block_to_vote_plans(
                     use chain_impl_mockchain::vote::Payload;
                     let voter = tx.inputs[0].address.clone();
                     match vote_cast.payload() {
-                        Payload::Public { choice } => vote_plans
+                        payload @ (Payload::Public { .. }
+                        | Payload::RankedChoice { .. }
+                        | Payload::Approval { .. }
+                        | Payload::Quadratic { .. }) => vote_plans
                             .update(vote_cast.vote_plan(), |vote_plan| {
+                                let turnout = count_vote(vote_plan, &voter, stake);
                                 let mut proposals = vote_plan.proposals.clone();
+                                count_proposal_vote(
+                                    &mut proposals[vote_cast.proposal_index() as usize],
+                                    &voter,
+                                    stake,
+                                );
                                 proposals[vote_cast.proposal_index() as usize].votes = proposals
                                     [vote_cast.proposal_index() as usize]
                                     .votes
                                     .insert_or_update(
                                         voter,
-                                        Arc::new(ExplorerVote::Public(*choice)),
+                                        Arc::new(ExplorerVote::from(payload)),
                                         |_| {
                                             Ok::<_, std::convert::Infallible>(Some(Arc::new(
-                                                ExplorerVote::Public(*choice),
+                                                ExplorerVote::from(payload),
                                             )))
                                         },
                                     )
@@ -660,6 +992,7 @@ fn apply_block_to_vote_plans(
 
                                 let vote_plan = ExplorerVotePlan {
                                     proposals,
+                                    turnout,
                                     ..(**vote_plan).clone()
                                 };
                                 Ok::<_, std::convert::Infallible>(Some(Arc::new(vote_plan)))
@@ -668,9 +1001,20 @@ fn apply_block_to_vote_plans(
                         Payload::Private {
                             proof,
                             encrypted_vote,
+                        }
+                        | Payload::PrivateQuadratic {
+                            proof,
+                            encrypted_vote,
+                            ..
                         } => vote_plans
                             .update(vote_cast.vote_plan(), |vote_plan| {
+                                let turnout = count_vote(vote_plan, &voter, stake);
                                 let mut proposals = vote_plan.proposals.clone();
+                                count_proposal_vote(
+                                    &mut proposals[vote_cast.proposal_index() as usize],
+                                    &voter,
+                                    stake,
+                                );
                                 proposals[vote_cast.proposal_index() as usize].votes = proposals
                                     [vote_cast.proposal_index() as usize]
                                     .votes
@@ -693,6 +1037,7 @@ fn apply_block_to_vote_plans(
 
                                 let vote_plan = ExplorerVotePlan {
                                     proposals,
+                                    turnout,
                                     ..(**vote_plan).clone()
                                 };
                                 Ok::<_, std::convert::Infallible>(Some(Arc::new(vote_plan)))
@@ -702,8 +1047,11 @@ fn apply_block_to_vote_plans(
                 }
                 Certificate::VoteTally(vote_tally) => vote_plans
                     .update(vote_tally.id(), |vote_plan| {
-                        let proposals = match vote_tally.tally_type() {
-                            PayloadType::Public => vote_plan
+                        let proposals: Vec<ExplorerVoteProposal> = match vote_tally.tally_type() {
+                            PayloadType::Public
+                            | PayloadType::RankedChoice
+                            | PayloadType::Approval
+                            | PayloadType::Quadratic => vote_plan
                                 .proposals
                                 .clone()
                                 .into_iter()
@@ -713,7 +1061,7 @@ fn apply_block_to_vote_plans(
                                     proposal
                                 })
                                 .collect(),
-                            PayloadType::Private => {
+                            PayloadType::Private | PayloadType::PrivateQuadratic => {
                                 if let Some(decrypted_tally) = vote_tally.tally_decrypted() {
                                     vote_plan
                                         .proposals
@@ -736,7 +1084,18 @@ fn apply_block_to_vote_plans(
                             }
                         };
 
+                        let proposals: Vec<_> = proposals
+                            .into_iter()
+                            .map(|mut proposal| {
+                                proposal.turnout =
+                                    compute_turnout(std::iter::once(&proposal), stake);
+                                proposal
+                            })
+                            .collect();
+
                         let vote_plan = ExplorerVotePlan {
+                            turnout: compute_turnout(&proposals, stake),
+                            tally_date: Some(block.date),
                             proposals,
                             ..(**vote_plan).clone()
                         };
@@ -752,6 +1111,33 @@ fn apply_block_to_vote_plans(
     Ok(vote_plans)
 }
 
+/// The turnout of the vote plan once `voter` cast a vote in it.
+fn count_vote(
+    vote_plan: &ExplorerVotePlan,
+    voter: &ExplorerAddress,
+    stake: &StakeControl,
+) -> ExplorerVoteTurnout {
+    let voted = vote_plan
+        .proposals
+        .iter()
+        .any(|proposal| proposal.votes.lookup(voter).is_some());
+    if voted {
+        vote_plan.turnout.clone()
+    } else {
+        add_voter(&vote_plan.turnout, voter, stake)
+    }
+}
+
+fn count_proposal_vote(
+    proposal: &mut ExplorerVoteProposal,
+    voter: &ExplorerAddress,
+    stake: &StakeControl,
+) {
+    if proposal.votes.lookup(voter).is_none() {
+        proposal.turnout = add_voter(&proposal.turnout, voter, stake);
+    }
+}
+
 fn apply_block_to_stake_control(
     mut stake_control: StakeControl,
     block: &ExplorerBlock,
@@ -885,3 +1271,311 @@ impl State {
             .collect()
     }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use chain_addr::{Address, Kind};
+    use chain_crypto::{Ed25519, SecretKey};
+    use chain_impl_mockchain::{
+        block::{self, BlockDate, BlockVersion, Contents, ContentsBuilder},
+        testing::{
+            data::{StakePool as TestStakePool, Wallet},
+            TestGen, TestTxCertBuilder, WitnessMode,
+        },
+        transaction::{NoExtra, Output, TxBuilder},
+        value::Value,
+    };
+
+    const EPOCH_STABILITY_DEPTH: u32 = 2;
+
+    pub(crate) struct TestChain {
+        pub leader: SecretKey<Ed25519>,
+        pub blocks: Vec<Block>,
+    }
+
+    impl TestChain {
+        /// a chain with only a block0 crediting an account of the leader
+        pub fn new() -> Self {
+            Self::with_wallets(&[])
+        }
+
+        /// a chain with only a block0 crediting an account of the leader and
+        /// the given wallets
+        pub fn with_wallets(wallets: &[&Wallet]) -> Self {
+            let leader = SecretKey::generate(rand::thread_rng());
+            let mut config = ConfigParams::new();
+            config.push(ConfigParam::Discrimination(Discrimination::Test));
+            config.push(ConfigParam::ConsensusVersion(ConsensusVersion::Bft));
+            config.push(ConfigParam::AddBftLeader(leader.to_public().into()));
+            config.push(ConfigParam::LinearFee(LinearFee::new(0, 0, 0)));
+            config.push(ConfigParam::EpochStabilityDepth(EPOCH_STABILITY_DEPTH));
+            let funds = TxBuilder::new()
+                .set_nopayload()
+                .set_expiry_date(BlockDate::first().next_epoch())
+                .set_ios(
+                    &[],
+                    &std::iter::once(Output::from_address(
+                        Address(Discrimination::Test, Kind::Account(leader.to_public())),
+                        Value(100),
+                    ))
+                    .chain(wallets.iter().map(|wallet| wallet.make_output()))
+                    .collect::<Vec<_>>(),
+                )
+                .set_witnesses_unchecked(&[])
+                .set_payload_auth(&());
+            let mut contents = ContentsBuilder::new();
+            contents.push(Fragment::Initial(config));
+            contents.push(Fragment::Transaction(funds));
+            let block0 = block::builder(BlockVersion::Genesis, contents.into(), |header| {
+                Ok::<_, ()>(
+                    header
+                        .set_genesis()
+                        .set_date(BlockDate::first())
+                        .into_unsigned_header()
+                        .unwrap()
+                        .generalize(),
+                )
+            })
+            .unwrap();
+            TestChain {
+                leader,
+                blocks: vec![block0],
+            }
+        }
+
+        pub fn block0(&self) -> Block {
+            self.blocks[0].clone()
+        }
+
+        /// extend the chain with an empty block in the next slot
+        pub fn next_block(&mut self) -> Block {
+            self.next_block_with(Vec::new())
+        }
+
+        /// extend the chain with a block of the fragments in the next slot
+        pub fn next_block_with(&mut self, fragments: Vec<Fragment>) -> Block {
+            let block = self.block_on(self.blocks.last().unwrap(), 1, fragments);
+            self.blocks.push(block.clone());
+            block
+        }
+
+        /// a block of the leader on top of `parent`, `slots` after it, which
+        /// is not added to the chain
+        pub fn block_on(&self, parent: &Block, slots: u32, fragments: Vec<Fragment>) -> Block {
+            let mut contents = ContentsBuilder::new();
+            for fragment in fragments {
+                contents.push(fragment);
+            }
+            let contents: Contents = contents.into();
+            block::builder(BlockVersion::Ed25519Signed, contents, |header| {
+                Ok::<_, ()>(
+                    header
+                        .set_parent(&parent.id(), parent.chain_length().increase())
+                        .set_date(slots_after(parent, slots))
+                        .into_bft_builder()
+                        .unwrap()
+                        .sign_using(&self.leader)
+                        .generalize(),
+                )
+            })
+            .unwrap()
+        }
+
+        /// extend the chain with an empty block of the stake pool in the
+        /// next slot
+        pub fn next_pool_block(&mut self, pool: &TestStakePool) -> Block {
+            let parent = self.blocks.last().unwrap();
+            let block = block::builder(BlockVersion::KesVrfproof, Contents::empty(), |header| {
+                Ok::<_, ()>(
+                    header
+                        .set_parent(&parent.id(), parent.chain_length().increase())
+                        .set_date(slots_after(parent, 1))
+                        .into_genesis_praos_builder()
+                        .unwrap()
+                        .set_consensus_data(&pool.id(), &TestGen::vrf_proof(pool))
+                        .sign_using(pool.kes().private_key())
+                        .generalize(),
+                )
+            })
+            .unwrap();
+            self.blocks.push(block.clone());
+            block
+        }
+
+        /// a transfer of `value` between the accounts of the wallets
+        pub fn transfer(&self, from: &Wallet, to: &Wallet, value: Value) -> Fragment {
+            let builder = TxBuilder::new()
+                .set_payload(&NoExtra)
+                .set_expiry_date(BlockDate::first().next_epoch())
+                .set_ios(
+                    &[from.make_input_with_value(value)],
+                    &[to.make_output_with_value(value)],
+                );
+            let witness = from
+                .clone()
+                .make_witness(&self.blocks[0].id(), builder.get_auth_data_for_witness());
+            Fragment::Transaction(builder.set_witnesses(&[witness]).set_payload_auth(&()))
+        }
+
+        /// a transaction of the wallet carrying the certificate
+        pub fn certificate(&self, wallet: &Wallet, certificate: Certificate) -> Fragment {
+            TestTxCertBuilder::new(self.blocks[0].id(), LinearFee::new(0, 0, 0)).make_transaction(
+                BlockDate::first().next_epoch(),
+                [wallet],
+                &certificate,
+                WitnessMode::Default,
+            )
+        }
+    }
+
+    fn slots_after(block: &Block, slots: u32) -> BlockDate {
+        let date = block.header().block_date();
+        BlockDate {
+            epoch: date.epoch,
+            slot_id: date.slot_id + slots,
+        }
+    }
+
+    /// apply the block and make it the tip of the main branch
+    pub(crate) async fn apply_tip(db: &ExplorerDb, block: &Block) {
+        db.apply_block(block.clone()).await.unwrap();
+        db.set_tip(block.id()).await.unwrap();
+    }
+
+    /// what the event is about, to compare it
+    fn event_ids(event: &ChainEvent) -> Vec<HeaderHash> {
+        match event {
+            ChainEvent::Block(block) => vec![block.id()],
+            ChainEvent::Rollback {
+                previous_tip,
+                fork_point,
+            } => vec![*previous_tip, *fork_point],
+        }
+    }
+
+    #[tokio::test]
+    async fn chain_subscription_follows_the_main_branch() {
+        let mut chain = TestChain::new();
+        let db = ExplorerDb::bootstrap(chain.block0(), None).unwrap();
+        let events = db.chain_subscription();
+
+        let first = chain.next_block();
+        let second = chain.next_block();
+        apply_tip(&db, &first).await;
+        apply_tip(&db, &second).await;
+        // a longer branch forking after the first block
+        let fork = chain.block_on(&first, 2, Vec::new());
+        let fork_tip = chain.block_on(&fork, 1, Vec::new());
+        db.apply_block(fork.clone()).await.unwrap();
+        apply_tip(&db, &fork_tip).await;
+
+        let events: Vec<_> = events
+            .take(5)
+            .map(|event| event_ids(&event.unwrap()))
+            .collect()
+            .await;
+        assert_eq!(
+            events,
+            vec![
+                vec![first.id()],
+                vec![second.id()],
+                vec![second.id(), first.id()],
+                vec![fork.id()],
+                vec![fork_tip.id()],
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn lagging_chain_subscriber_is_told_how_many_events_it_missed() {
+        use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+
+        let mut chain = TestChain::new();
+        let db = ExplorerDb::bootstrap(chain.block0(), None).unwrap();
+        let mut events = Box::pin(db.chain_subscription());
+
+        for _ in 0..CHAIN_EVENTS_BUFFER_SIZE + 2 {
+            let block = chain.next_block();
+            apply_tip(&db, &block).await;
+        }
+
+        assert!(matches!(
+            events.next().await,
+            Some(Err(BroadcastStreamRecvError::Lagged(2)))
+        ));
+        // the oldest events still in the buffer follow
+        let event = events.next().await.unwrap().unwrap();
+        assert_eq!(event_ids(&event), vec![chain.blocks[3].id()]);
+    }
+
+    #[tokio::test]
+    async fn restart_from_checkpoint_only_indexes_the_following_blocks() {
+        const CHAIN_LENGTH: u32 = 11;
+
+        let directory = assert_fs::TempDir::new().unwrap();
+        let mut chain = TestChain::new();
+        let db = ExplorerDb::bootstrap(
+            chain.block0(),
+            Some(Storage::file(directory.path()).unwrap()),
+        )
+        .unwrap();
+        for _ in 0..CHAIN_LENGTH {
+            let block = chain.next_block();
+            db.apply_block(block.clone()).await.unwrap();
+            db.set_tip(block.id()).await.unwrap();
+        }
+        drop(db);
+
+        // the blocks are confirmed 2 blocks after they are applied, the last
+        // checkpoint is taken when the block at chain length 8 is confirmed
+        let checkpoint_chain_length = 2 * CHECKPOINT_INTERVAL;
+        let checkpoint = Checkpoint::read_from_directory(directory.path())
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            checkpoint.block,
+            chain.blocks[checkpoint_chain_length as usize].id()
+        );
+
+        let db = ExplorerDb::restore(Storage::file(directory.path()).unwrap())
+            .unwrap()
+            .unwrap();
+        let (tip, _) = db.get_tip().await;
+        assert_eq!(tip, checkpoint.block);
+        for block in &chain.blocks[..=checkpoint_chain_length as usize] {
+            assert!(db.contains_block(&block.id()).await);
+        }
+        for block in &chain.blocks[checkpoint_chain_length as usize + 1..] {
+            assert!(!db.contains_block(&block.id()).await);
+        }
+
+        let indexed = db.reindex().await.unwrap();
+        assert_eq!(indexed, CHAIN_LENGTH - checkpoint_chain_length);
+        let (tip, tip_ref) = db.get_tip().await;
+        assert_eq!(tip, chain.blocks.last().unwrap().id());
+        for block in &chain.blocks {
+            assert!(db.contains_block(&block.id()).await);
+        }
+
+        let account = ExplorerAddress::New(Address(
+            Discrimination::Test,
+            Kind::Account(chain.leader.to_public()),
+        ));
+        assert_eq!(
+            tip_ref
+                .state()
+                .transactions_by_address(&account)
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(
+            tip_ref
+                .state()
+                .stake_control
+                .by(&account.to_identifier().unwrap()),
+            Some(Stake(100))
+        );
+    }
+}
@@ -0,0 +1,129 @@
+use chain_core::{
+    packer::Codec,
+    property::{Deserialize, ReadError, Serialize, WriteError},
+};
+use chain_impl_mockchain::block::{Block, HeaderId as HeaderHash};
+use chain_storage::{BlockInfo, BlockStore, Error as BackendError};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+const TIP_TAG: &str = "tip";
+const MINIMUM_BLOCKS_TO_FLUSH: usize = 256;
+
+#[derive(Debug, Error)]
+pub enum StorageError {
+    #[error("database backend error")]
+    Backend(#[source] BackendError),
+    #[error("deserialization error")]
+    Deserialize(#[source] ReadError),
+    #[error("serialization error")]
+    Serialize(#[source] WriteError),
+}
+
+impl From<BackendError> for StorageError {
+    fn from(source: BackendError) -> Self {
+        StorageError::Backend(source)
+    }
+}
+
+/// On-disk store of the blocks indexed by the explorer.
+///
+/// The indexes are kept in memory, the store allows to rebuild them after a
+/// restart from the last checkpoint and the local blocks that follow it
+/// instead of downloading the whole chain from the node again.
+#[derive(Clone)]
+pub struct Storage {
+    store: BlockStore,
+    path: PathBuf,
+}
+
+impl Storage {
+    pub fn file<P: AsRef<Path>>(path: P) -> Result<Self, StorageError> {
+        let store = BlockStore::file(&path, HeaderHash::zero_hash().as_bytes().to_vec())?;
+        Ok(Storage {
+            store,
+            path: path.as_ref().to_path_buf(),
+        })
+    }
+
+    /// The directory of the storage, the checkpoints of the indexes are
+    /// kept there too.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn put_block(&self, block: &Block) -> Result<(), StorageError> {
+        let id = block
+            .header()
+            .hash()
+            .serialize_as_vec()
+            .map_err(StorageError::Serialize)?;
+        let parent_id = block
+            .header()
+            .block_parent_hash()
+            .serialize_as_vec()
+            .map_err(StorageError::Serialize)?;
+        let chain_length = block.header().chain_length().into();
+        let block_info = BlockInfo::new(id, parent_id, chain_length);
+        match self.store.put_block(
+            &block.serialize_as_vec().map_err(StorageError::Serialize)?[..],
+            block_info,
+        ) {
+            Ok(()) | Err(BackendError::BlockAlreadyPresent) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Get all the stored blocks with the given chain length, one per branch.
+    pub fn get_blocks_by_chain_length(
+        &self,
+        chain_length: u32,
+    ) -> Result<Vec<Block>, StorageError> {
+        self.store
+            .get_blocks_by_chain_length(chain_length)?
+            .into_iter()
+            .map(|block| Block::deserialize(&mut Codec::new(block.as_ref())))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(StorageError::Deserialize)
+    }
+
+    pub fn get_tip(&self) -> Result<Option<HeaderHash>, StorageError> {
+        self.store
+            .get_tag(TIP_TAG)?
+            .map(|block_id| {
+                HeaderHash::deserialize(&mut Codec::new(block_id.as_ref()))
+                    .map_err(StorageError::Deserialize)
+            })
+            .transpose()
+    }
+
+    pub fn put_tip(&self, tip: HeaderHash) -> Result<(), StorageError> {
+        self.store
+            .put_tag(TIP_TAG, tip.as_bytes())
+            .map_err(Into::into)
+    }
+
+    /// Remove the branches that did not make it past the stability depth and
+    /// move the confirmed part of the main branch to the permanent store.
+    pub fn gc(&self, threshold_depth: u32, tip: HeaderHash) -> Result<(), StorageError> {
+        let tip_info = self.store.get_block_info(tip.as_bytes())?;
+        let threshold_length = match tip_info.chain_length().checked_sub(threshold_depth) {
+            Some(result) => result,
+            None => return Ok(()),
+        };
+
+        for id in self.store.get_tips_ids()? {
+            let info = self.store.get_block_info(id.as_ref())?;
+            if info.chain_length() <= threshold_length {
+                self.store.prune_branch(id.as_ref())?;
+            }
+        }
+
+        let to_block_info = self
+            .store
+            .get_nth_ancestor(tip.as_bytes(), threshold_depth)?;
+        self.store
+            .flush_to_permanent_store(to_block_info.id().as_ref(), MINIMUM_BLOCKS_TO_FLUSH)?;
+        Ok(())
+    }
+}
@@ -1,6 +1,12 @@
 use super::indexing::ExplorerVoteProposal;
-use crate::db::indexing::{ExplorerVote, ExplorerVoteTally};
-use chain_impl_mockchain::{certificate::DecryptedPrivateTallyProposal, stake::StakeControl};
+use crate::db::indexing::{ExplorerAddress, ExplorerVote, ExplorerVoteTally, ExplorerVoteTurnout};
+use chain_impl_mockchain::{
+    certificate::DecryptedPrivateTallyProposal,
+    stake::StakeControl,
+    vote::{Choice, RankedChoiceResult, Weight},
+};
+use chain_vote::quadratic::credits_weight;
+use std::collections::HashSet;
 
 pub fn compute_private_tally(
     proposal: &ExplorerVoteProposal,
@@ -17,6 +23,11 @@ pub fn compute_public_tally(
     stake: &StakeControl,
 ) -> ExplorerVoteTally {
     let mut results = vec![0u64; proposal.options.choice_range().end as usize];
+    let mut rankings = RankedChoiceResult::new(proposal.options.clone());
+    let mut ranked_choice = false;
+    // an approval ballot adds the stake of the voter to every approved
+    // option, the weight cast is the stake of the voters
+    let mut approval_weight = None;
 
     for (address, vote) in proposal.votes.iter() {
         if let Some(account_id) = address.to_identifier() {
@@ -26,6 +37,26 @@ pub fn compute_public_tally(
                         let index = choice.as_byte() as usize;
                         results[index] = results[index].saturating_add(stake.into());
                     }
+                    // the rankings are validated by the ledger when the vote
+                    // is cast, the error cannot happen
+                    ExplorerVote::RankedChoice(ranking) => {
+                        ranked_choice = true;
+                        let _ = rankings.add_vote(ranking, stake);
+                    }
+                    ExplorerVote::Approval(approvals) => {
+                        approval_weight =
+                            Some(approval_weight.unwrap_or(0u64).saturating_add(stake.into()));
+                        for choice in approvals {
+                            let index = choice.as_byte() as usize;
+                            results[index] = results[index].saturating_add(stake.into());
+                        }
+                    }
+                    // the weight of a quadratic vote only depends on the
+                    // credits allocated to it
+                    ExplorerVote::Quadratic { choice, credits } => {
+                        let index = choice.as_byte() as usize;
+                        results[index] = results[index].saturating_add(credits_weight(*credits));
+                    }
                     ExplorerVote::Private {
                         proof: _,
                         encrypted_vote: _,
@@ -37,8 +68,261 @@ pub fn compute_public_tally(
         }
     }
 
+    if ranked_choice {
+        let instant_runoff = rankings.instant_runoff();
+        let rounds = instant_runoff.rounds().to_vec();
+        return ExplorerVoteTally::Public {
+            results: rounds.last().cloned().unwrap_or_default(),
+            options: proposal.options.clone(),
+            winners: instant_runoff.winner().into_iter().collect(),
+            total_weight: rankings.participation().into(),
+            rounds,
+        };
+    }
+
+    let results: Box<[Weight]> = results.into_iter().map(u64::into).collect();
     ExplorerVoteTally::Public {
-        results: results.into_iter().map(u64::into).collect(),
+        winners: winners(&results),
+        total_weight: approval_weight.unwrap_or_else(|| total_weight(&results)),
+        results,
         options: proposal.options.clone(),
+        rounds: Vec::new(),
+    }
+}
+
+/// The options with the highest weight, there is more than one in case of a
+/// draw and none as long as no weight was cast.
+pub fn winners(results: &[Weight]) -> Vec<Choice> {
+    let max = results.iter().copied().map(u64::from).max().unwrap_or(0);
+    if max == 0 {
+        return Vec::new();
+    }
+
+    results
+        .iter()
+        .enumerate()
+        .filter(|(_, weight)| u64::from(**weight) == max)
+        .map(|(index, _)| Choice::new(index as u8))
+        .collect()
+}
+
+/// The weight cast on a proposal whose ballots count for a single option.
+pub fn total_weight(results: &[Weight]) -> u64 {
+    results
+        .iter()
+        .copied()
+        .map(u64::from)
+        .fold(0, u64::saturating_add)
+}
+
+fn voting_power(address: &ExplorerAddress, stake: &StakeControl) -> u64 {
+    address
+        .to_identifier()
+        .and_then(|account_id| stake.by(&account_id))
+        .map_or(0, u64::from)
+}
+
+/// Account for a vote cast by a voter that did not vote before.
+pub fn add_voter(
+    turnout: &ExplorerVoteTurnout,
+    voter: &ExplorerAddress,
+    stake: &StakeControl,
+) -> ExplorerVoteTurnout {
+    ExplorerVoteTurnout {
+        voters: turnout.voters + 1,
+        voting_power: turnout
+            .voting_power
+            .saturating_add(voting_power(voter, stake)),
+        total_voting_power: stake.total().into(),
+    }
+}
+
+/// Compute the turnout of the given proposals from scratch, the voters of
+/// several proposals are counted only once.
+pub fn compute_turnout<'a>(
+    proposals: impl IntoIterator<Item = &'a ExplorerVoteProposal>,
+    stake: &StakeControl,
+) -> ExplorerVoteTurnout {
+    let mut voters = HashSet::new();
+    for proposal in proposals {
+        voters.extend(proposal.votes.iter().map(|(address, _)| address));
+    }
+
+    ExplorerVoteTurnout {
+        voters: voters.len() as u64,
+        voting_power: voters
+            .into_iter()
+            .map(|address| voting_power(address, stake))
+            .fold(0, u64::saturating_add),
+        total_voting_power: stake.total().into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::indexing::Hamt;
+    use chain_addr::{Address, Discrimination, Kind};
+    use chain_crypto::{Ed25519, SecretKey};
+    use chain_impl_mockchain::{stake::Stake, vote::Options};
+    use std::sync::Arc;
+
+    /// account addresses holding the given stakes
+    fn voters(stakes: &[u64]) -> (Vec<ExplorerAddress>, StakeControl) {
+        let mut stake = StakeControl::new();
+        let addresses = stakes
+            .iter()
+            .map(|value| {
+                let key = SecretKey::<Ed25519>::generate(rand::thread_rng()).to_public();
+                stake = stake.add_to(key.clone().into(), Stake(*value));
+                ExplorerAddress::New(Address(Discrimination::Test, Kind::Account(key)))
+            })
+            .collect();
+        (addresses, stake)
+    }
+
+    fn proposal<'a>(
+        options: u8,
+        votes: impl IntoIterator<Item = (&'a ExplorerAddress, ExplorerVote)>,
+    ) -> ExplorerVoteProposal {
+        ExplorerVoteProposal {
+            proposal_id: [0; 32].into(),
+            options: Options::new_length(options).unwrap(),
+            tally: None,
+            votes: votes
+                .into_iter()
+                .fold(Hamt::new(), |votes, (address, vote)| {
+                    votes.insert(address.clone(), Arc::new(vote)).unwrap()
+                }),
+            turnout: ExplorerVoteTurnout::default(),
+        }
+    }
+
+    fn choices(indexes: &[u8]) -> Vec<Choice> {
+        indexes.iter().copied().map(Choice::new).collect()
+    }
+
+    fn weights(values: &[u64]) -> Box<[Weight]> {
+        values.iter().copied().map(Weight::from).collect()
+    }
+
+    #[test]
+    fn every_option_of_a_draw_wins() {
+        assert_eq!(winners(&weights(&[5, 1, 5])), choices(&[0, 2]));
+        assert_eq!(winners(&weights(&[3, 4, 1])), choices(&[1]));
+        assert_eq!(winners(&weights(&[0, 0, 0])), Vec::new());
+    }
+
+    #[test]
+    fn instant_runoff_eliminates_the_last_option_until_a_majority() {
+        let (addresses, stake) = voters(&[5, 4, 2, 2]);
+        let proposal = proposal(
+            4,
+            addresses.iter().zip(vec![
+                ExplorerVote::RankedChoice(choices(&[0])),
+                ExplorerVote::RankedChoice(choices(&[1])),
+                ExplorerVote::RankedChoice(choices(&[2, 1])),
+                // exhausted once option 3 is eliminated
+                ExplorerVote::RankedChoice(choices(&[3])),
+            ]),
+        );
+
+        match compute_public_tally(&proposal, &stake) {
+            ExplorerVoteTally::Public {
+                results,
+                winners,
+                total_weight,
+                rounds,
+                ..
+            } => {
+                // options 2 and 3 are tied, the one with the highest index
+                // is eliminated first
+                assert_eq!(
+                    rounds,
+                    vec![
+                        weights(&[5, 4, 2, 2]),
+                        weights(&[5, 4, 2, 0]),
+                        weights(&[5, 6, 0, 0]),
+                    ]
+                );
+                assert_eq!(results, weights(&[5, 6, 0, 0]));
+                assert_eq!(winners, choices(&[1]));
+                assert_eq!(total_weight, 13);
+            }
+            ExplorerVoteTally::Private { .. } => panic!("expected a public tally"),
+        }
+    }
+
+    #[test]
+    fn approval_voters_are_counted_once_in_the_total_weight() {
+        let (addresses, stake) = voters(&[10, 5]);
+        let proposal = proposal(
+            3,
+            addresses.iter().zip(vec![
+                ExplorerVote::Approval(choices(&[0, 1])),
+                ExplorerVote::Approval(choices(&[1])),
+            ]),
+        );
+
+        match compute_public_tally(&proposal, &stake) {
+            ExplorerVoteTally::Public {
+                results,
+                winners,
+                total_weight,
+                rounds,
+                ..
+            } => {
+                assert_eq!(results, weights(&[10, 15, 0]));
+                assert_eq!(winners, choices(&[1]));
+                assert_eq!(total_weight, 15);
+                assert!(rounds.is_empty());
+            }
+            ExplorerVoteTally::Private { .. } => panic!("expected a public tally"),
+        }
+    }
+
+    #[test]
+    fn voters_of_several_proposals_are_counted_once_in_the_turnout() {
+        let (addresses, stake) = voters(&[10, 5, 1]);
+        let first = proposal(
+            2,
+            vec![
+                (&addresses[0], ExplorerVote::Public(Choice::new(0))),
+                (&addresses[1], ExplorerVote::Public(Choice::new(1))),
+            ],
+        );
+        let second = proposal(
+            2,
+            vec![(&addresses[0], ExplorerVote::Public(Choice::new(1)))],
+        );
+
+        let turnout = compute_turnout([&first, &second], &stake);
+        assert_eq!(turnout.voters, 2);
+        assert_eq!(turnout.voting_power, 15);
+        assert_eq!(turnout.total_voting_power, 16);
+    }
+
+    #[test]
+    fn turnout_without_any_stake() {
+        let (addresses, _) = voters(&[10]);
+        let stake = StakeControl::new();
+        let proposal = proposal(
+            2,
+            vec![(&addresses[0], ExplorerVote::Public(Choice::new(0)))],
+        );
+
+        let turnout = compute_turnout([&proposal], &stake);
+        assert_eq!(turnout.voters, 1);
+        assert_eq!(turnout.voting_power, 0);
+        assert_eq!(turnout.total_voting_power, 0);
+
+        let turnout = add_voter(&ExplorerVoteTurnout::default(), &addresses[0], &stake);
+        assert_eq!(turnout.voters, 1);
+        assert_eq!(turnout.voting_power, 0);
+        assert_eq!(turnout.total_voting_power, 0);
+
+        let turnout = compute_turnout([], &stake);
+        assert_eq!(turnout.voters, 0);
+        assert_eq!(turnout.total_voting_power, 0);
     }
 }
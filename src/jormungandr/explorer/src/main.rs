@@ -8,10 +8,11 @@ use crate::indexer::Indexer;
 use anyhow::Context;
 use chain_core::{packer::Codec, property::Deserialize};
 use chain_impl_mockchain::block::Block;
-use chain_network::grpc::watch::client::{
-    BlockSubscription, Client, SyncMultiverseStream, TipSubscription,
+use chain_network::{
+    data::BlockId,
+    grpc::watch::client::{BlockSubscription, Client, SyncMultiverseStream, TipSubscription},
 };
-use db::ExplorerDb;
+use db::{storage::Storage, ExplorerDb};
 use futures::stream::StreamExt;
 use futures_util::{future, pin_mut, FutureExt, TryFutureExt};
 use settings::Settings;
@@ -50,6 +51,9 @@ pub enum BootstrapError {
 #[derive(Clone)]
 enum GlobalState {
     Bootstraping,
+    /// The indexes are being rebuilt from the storage, the database can
+    /// already be queried but does not receive blocks from the node yet.
+    Reindexing(ExplorerDb),
     Ready(Indexer),
     ShuttingDown,
 }
@@ -69,7 +73,7 @@ async fn main() -> Result<(), Error> {
 
     let mut settings = Some(settings);
 
-    let (state_tx, state_rx) = broadcast::channel(3);
+    let (state_tx, state_rx) = broadcast::channel(4);
 
     // this unwrap won't panic because the capacity is greater than 1
     state_tx.send(GlobalState::Bootstraping).unwrap();
@@ -77,15 +81,24 @@ async fn main() -> Result<(), Error> {
     let (bootstrap, mut services) = {
         let settings = settings.take().unwrap();
 
-        let mut client = Client::connect(settings.node.clone())
-            .await
-            .context("Couldn't establish connection with node")
+        let storage = settings
+            .storage
+            .as_ref()
+            .map(Storage::file)
+            .transpose()
+            .context("Couldn't open the explorer storage")
             .map_err(Error::UnrecoverableError)?;
 
-        let sync_stream = client
-            .sync_multiverse(vec![])
+        let restored_db = storage
+            .clone()
+            .map(ExplorerDb::restore)
+            .transpose()
+            .map_err(BootstrapError::DbError)?
+            .flatten();
+
+        let mut client = Client::connect(settings.node.clone())
             .await
-            .context("Failed to establish bootstrap stream")
+            .context("Couldn't establish connection with node")
             .map_err(Error::UnrecoverableError)?;
 
         let block_events = client
@@ -105,7 +118,31 @@ async fn main() -> Result<(), Error> {
 
             tokio::spawn(
                 async move {
-                    let db = bootstrap(sync_stream).await?;
+                    // the node only sends the blocks that are not known from these tips
+                    let checkpoints: Vec<BlockId> = match &restored_db {
+                        Some(db) => {
+                            state_tx
+                                .send(GlobalState::Reindexing(db.clone()))
+                                .context("failed to broadcast state")
+                                .map_err(Error::Other)?;
+                            tracing::info!("rebuilding the indexes from the storage");
+                            db.reindex().await.map_err(BootstrapError::DbError)?;
+                            db.get_branches()
+                                .await
+                                .into_iter()
+                                .map(|(hash, _)| BlockId::try_from(hash.as_ref()).unwrap())
+                                .collect()
+                        }
+                        None => Vec::new(),
+                    };
+
+                    let sync_stream = client
+                        .sync_multiverse(&checkpoints)
+                        .await
+                        .context("Failed to establish bootstrap stream")
+                        .map_err(Error::UnrecoverableError)?;
+
+                    let db = bootstrap(sync_stream, restored_db, storage).await?;
 
                     let msg = GlobalState::Ready(Indexer::new(db));
 
@@ -210,10 +247,14 @@ async fn main() -> Result<(), Error> {
     Ok(())
 }
 
-async fn bootstrap(mut sync_stream: SyncMultiverseStream) -> Result<ExplorerDb, Error> {
+async fn bootstrap(
+    mut sync_stream: SyncMultiverseStream,
+    restored_db: Option<ExplorerDb>,
+    mut storage: Option<Storage>,
+) -> Result<ExplorerDb, Error> {
     tracing::info!("starting bootstrap process");
 
-    let mut db: Option<ExplorerDb> = None;
+    let mut db: Option<ExplorerDb> = restored_db;
 
     // TODO: technically, blocks with the same length can be applied in parallel
     // but it is simpler to do it serially for now at least
@@ -229,6 +270,10 @@ async fn bootstrap(mut sync_stream: SyncMultiverseStream) -> Result<ExplorerDb,
             .map_err(Error::UnrecoverableError)?;
 
         if let Some(ref db) = db {
+            // blocks restored from the storage may be sent again by the node
+            if db.contains_block(&block.header().hash()).await {
+                continue;
+            }
             tracing::trace!(
                 "applying block {:?} {:?}",
                 block.header().hash(),
@@ -238,7 +283,9 @@ async fn bootstrap(mut sync_stream: SyncMultiverseStream) -> Result<ExplorerDb,
                 .await
                 .map_err(BootstrapError::DbError)?;
         } else {
-            db = Some(ExplorerDb::bootstrap(block).map_err(BootstrapError::DbError)?)
+            db = Some(
+                ExplorerDb::bootstrap(block, storage.take()).map_err(BootstrapError::DbError)?,
+            )
         }
     }
 
@@ -251,17 +298,26 @@ async fn rest_service(mut state: broadcast::Receiver<GlobalState>, settings: Set
     tracing::info!("starting rest task, waiting for database to be ready");
 
     let (rest_shutdown, rest_shutdown_signal) = oneshot::channel();
-    let (indexer_tx, indexer_rx) = oneshot::channel();
+    let (db_tx, db_rx) = oneshot::channel();
 
     tokio::spawn(async move {
-        let mut indexer_tx = Some(indexer_tx);
+        let mut db_tx = Some(db_tx);
+        let mut reindexing = false;
         loop {
             match state.recv().await.unwrap() {
                 GlobalState::Bootstraping => continue,
+                // queries are served while the indexes are rebuilt, the
+                // database is the same once it is ready
+                GlobalState::Reindexing(db) => {
+                    reindexing = true;
+                    if let Some(db_tx) = db_tx.take() {
+                        let _ = db_tx.send(db);
+                    }
+                }
                 GlobalState::Ready(i) => {
-                    if let Some(indexer_tx) = indexer_tx.take() {
-                        let _ = indexer_tx.send(i);
-                    } else {
+                    if let Some(db_tx) = db_tx.take() {
+                        let _ = db_tx.send(i.db);
+                    } else if !std::mem::take(&mut reindexing) {
                         panic!("received ready event twice");
                     }
                 }
@@ -273,7 +329,7 @@ async fn rest_service(mut state: broadcast::Receiver<GlobalState>, settings: Set
         }
     });
 
-    let db = indexer_rx.await.unwrap().db;
+    let db = db_rx.await.unwrap();
 
     let api = api::filter(
         db,
@@ -344,7 +400,7 @@ async fn process_subscriptions(
             .expect("state broadcast channel doesn't have enough capacity");
 
         match state {
-            GlobalState::Bootstraping => continue,
+            GlobalState::Bootstraping | GlobalState::Reindexing(_) => continue,
             GlobalState::Ready(i) => {
                 indexer.replace(i);
                 break;
@@ -412,6 +468,7 @@ impl std::fmt::Debug for GlobalState {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             GlobalState::Bootstraping => write!(f, "Bootstrapping"),
+            GlobalState::Reindexing(_) => write!(f, "Reindexing"),
             GlobalState::Ready(_) => write!(f, "Ready"),
             GlobalState::ShuttingDown => write!(f, "ShuttingDown"),
         }
@@ -47,6 +47,7 @@ pub struct Settings {
     pub query_complexity_limit: usize,
     pub tls: Option<Tls>,
     pub cors: Option<Cors>,
+    pub storage: Option<PathBuf>,
     pub log_settings: Option<LogSettings>,
 }
 
@@ -91,6 +92,8 @@ impl Settings {
 
         let log_settings = Some(Self::log_settings(&cmd, &file));
 
+        let storage = cmd.storage.clone().or_else(|| file.storage.clone());
+
         let tls = file.tls;
         let cors = file.cors;
 
@@ -102,6 +105,7 @@ impl Settings {
             query_complexity_limit,
             tls,
             cors,
+            storage,
             log_settings,
         })
     }
@@ -158,6 +162,11 @@ struct CommandLine {
     pub query_depth_limit: Option<usize>,
     #[clap(long)]
     pub query_complexity_limit: Option<usize>,
+    /// Directory where the indexed blocks are stored, so that the explorer
+    /// does not have to fetch the whole chain from the node after a restart.
+    /// If not configured anywhere, the blocks are only kept in memory.
+    #[clap(long)]
+    pub storage: Option<PathBuf>,
 
     pub config: Option<PathBuf>,
     /// Set log messages minimum severity. If not configured anywhere, defaults to "info".
@@ -193,6 +202,7 @@ pub struct Config {
     pub address_bech32_prefix: Option<String>,
     pub query_depth_limit: Option<usize>,
     pub query_complexity_limit: Option<usize>,
+    pub storage: Option<PathBuf>,
     pub logs: Option<ConfigLogSettings>,
 }
 
@@ -1,3 +1,4 @@
+mod new_multisig_registration;
 mod new_owner_stake_delegation;
 mod new_stake_delegation;
 mod new_stake_pool_registration;
@@ -19,7 +20,9 @@ use crate::jcli_lib::utils::{
     io, key_parser,
     vote::{SharesError, VotePlanError},
 };
-use chain_impl_mockchain::{block::BlockDate, certificate::DecryptedPrivateTallyError};
+use chain_impl_mockchain::{
+    block::BlockDate, certificate::DecryptedPrivateTallyError, multisig::DeclarationError,
+};
 use chain_vote::UnitVectorInitializationError;
 use clap::Parser;
 use jormungandr_lib::interfaces::{self, CertificateFromBech32Error, CertificateFromStrError};
@@ -68,6 +71,8 @@ pub enum Error {
     VotePlanDoesntNeedSignature,
     #[error("vote cast certificate does not need a signature")]
     VoteCastDoesntNeedSignature,
+    #[error("multisig registration certificate does not need a signature")]
+    MultisigRegistrationDoesntNeedSignature,
     #[error("secret key number {index} matching the expected public key has not been found")]
     KeyNotFound { index: usize },
     #[error("Invalid input, expected Signed Certificate or just Certificate")]
@@ -96,6 +101,10 @@ pub enum Error {
     TooManyVotePlanProposals { actual: usize, max: usize },
     #[error("invalid certificate, expecting a vote plan one")]
     NotVotePlanCertificate,
+    #[error("invalid certificate, expecting a multisig registration one")]
+    NotMultisigRegistrationCertificate,
+    #[error("invalid multisig declaration")]
+    MultisigDeclaration(#[from] DeclarationError),
     #[error("invalid vote plan certificate configuration")]
     VotePlanConfig(#[source] serde_yaml::Error),
     #[error("invalid base64 encoded bytes")]
@@ -108,6 +117,10 @@ pub enum Error {
     InvalidJson(#[from] serde_json::Error),
     #[error("private vote plans `committee_public_keys` cannot be empty")]
     InvalidPrivateVotePlanCommitteeKeys,
+    #[error(
+        "ranked choice, approval and quadratic vote plans can only contain off chain proposals"
+    )]
+    InvalidVotePlanAction,
     #[error(transparent)]
     VotePlanError(#[from] VotePlanError),
     #[error(transparent)]
@@ -120,6 +133,10 @@ pub enum Error {
     ConfigFileCorrupted(#[source] serde_yaml::Error),
     #[error(transparent)]
     InvalidChoice(#[from] UnitVectorInitializationError),
+    #[error(transparent)]
+    InsufficientCredits(#[from] chain_vote::InsufficientCredits),
+    #[error("invalid spent credits opening, expected {expected} hex encoded bytes")]
+    InvalidCreditsOpening { expected: usize },
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -180,6 +197,8 @@ pub enum NewArgs {
     /// create a vote cast certificate
     #[clap(subcommand)]
     VoteCast(new_vote_cast::VoteCastCmd),
+    /// create a multisig account registration certificate
+    MultisigRegistration(new_multisig_registration::MultisigRegistrationArgs),
 }
 
 #[derive(Parser)]
@@ -212,6 +231,7 @@ impl NewArgs {
             NewArgs::VoteCast(args) => args.exec()?,
             NewArgs::UpdateVote(args) => args.exec()?,
             NewArgs::UpdateProposal(args) => args.exec()?,
+            NewArgs::MultisigRegistration(args) => args.exec()?,
         }
         Ok(())
     }
@@ -0,0 +1,52 @@
+use crate::jcli_lib::{
+    certificate::{write_cert, Error},
+    utils::key_parser::parse_pub_key,
+};
+use chain_crypto::{Ed25519, PublicKey};
+use chain_impl_mockchain::{
+    certificate::{Certificate, MultisigRegistration},
+    multisig::{DeclElement, Declaration},
+};
+use clap::Parser;
+use std::path::PathBuf;
+
+/// create the multisig account registration certificate.
+///
+/// The multisig account is identified by the hash of its declaration (the
+/// threshold and the ordered list of owners), see `show multisig-id`.
+#[derive(Parser)]
+pub struct MultisigRegistrationArgs {
+    /// number of owners' signatures required to spend from the multisig account
+    #[clap(long = "threshold", name = "THRESHOLD")]
+    pub threshold: u8,
+
+    /// public key of the owner(s)
+    ///
+    /// The order of the owners matters: it is part of the multisig identifier
+    /// and the index of each owner is used when creating the witnesses.
+    #[clap(
+        long = "owner",
+        name = "OWNER_KEY",
+        value_parser = parse_pub_key::<Ed25519>,
+        required = true
+    )]
+    pub owners: Vec<PublicKey<Ed25519>>,
+
+    /// print the output certificate in the given file, if no file given
+    /// the output will be printed in the standard output
+    pub output: Option<PathBuf>,
+}
+
+impl MultisigRegistrationArgs {
+    pub fn exec(self) -> Result<(), Error> {
+        let owners = self
+            .owners
+            .iter()
+            .map(DeclElement::from_publickey)
+            .collect();
+        let declaration = Declaration::new(self.threshold, owners)?;
+
+        let cert = Certificate::MultisigRegistration(MultisigRegistration::new(declaration));
+        write_cert(self.output, cert.into())
+    }
+}
@@ -7,9 +7,10 @@ use chain_impl_mockchain::{
     certificate::{Certificate, VoteCast, VotePlanId},
     vote::{Choice, Payload},
 };
+use chain_vote::CreditsOpening;
 use clap::Parser;
 use rand_chacha::rand_core::SeedableRng;
-use std::path::PathBuf;
+use std::{io::Write as _, path::PathBuf};
 
 #[derive(Parser)]
 pub struct PublicVoteCast {
@@ -57,11 +58,111 @@ pub struct PrivateVoteCast {
     output: Option<PathBuf>,
 }
 
+#[derive(Parser)]
+pub struct ChoicesVoteCast {
+    /// the vote plan identified on the blockchain
+    #[clap(long = "vote-plan-id")]
+    vote_plan_id: VotePlanId,
+
+    /// the number of proposal in the vote plan you vote for
+    #[clap(long = "proposal-index")]
+    proposal_index: u8,
+
+    /// the numbers of the choices within the proposal you vote for. For a
+    /// ranked choice ballot, the most preferred choice comes first
+    #[clap(long = "choice", required = true)]
+    choices: Vec<u8>,
+
+    /// write the output to the given file or print it to the standard output if not defined
+    #[clap(long = "output")]
+    output: Option<PathBuf>,
+}
+
+#[derive(Parser)]
+pub struct QuadraticVoteCast {
+    /// the vote plan identified on the blockchain
+    #[clap(long = "vote-plan-id")]
+    vote_plan_id: VotePlanId,
+
+    /// the number of proposal in the vote plan you vote for
+    #[clap(long = "proposal-index")]
+    proposal_index: u8,
+
+    /// the number of choice within the proposal you vote for
+    #[clap(long = "choice")]
+    choice: u8,
+
+    /// the credits allocated to the choice, the weight of the vote is their
+    /// square root
+    #[clap(long = "credits")]
+    credits: u64,
+
+    /// write the output to the given file or print it to the standard output if not defined
+    #[clap(long = "output")]
+    output: Option<PathBuf>,
+}
+
+#[derive(Parser)]
+pub struct PrivateQuadraticVoteCast {
+    /// the vote plan identified on the blockchain
+    #[clap(long = "vote-plan-id")]
+    vote_plan_id: VotePlanId,
+
+    /// the number of proposal in the vote plan you vote for
+    #[clap(long = "proposal-index")]
+    proposal_index: u8,
+
+    /// size of voting options
+    #[clap(long = "options-size")]
+    options: usize,
+
+    /// the number of choice within the proposal you vote for
+    #[clap(long = "choice")]
+    choice: u8,
+
+    /// the credits allocated to the choice, the weight of the vote is their
+    /// square root
+    #[clap(long = "credits")]
+    credits: u64,
+
+    /// the credits budget of the voter in the vote plan, i.e. the amount of
+    /// voting tokens of the voter
+    #[clap(long = "budget")]
+    budget: u64,
+
+    /// the opening of the credits already spent in the vote plan, as written
+    /// by a previous vote cast. Not needed for the first vote in the vote plan
+    #[clap(long = "spent-credits")]
+    spent_credits: Option<PathBuf>,
+
+    /// write the opening of the credits spent, including this vote, to the
+    /// given file or print it to the standard output if not defined. It is
+    /// needed to cast the next votes in the vote plan
+    #[clap(long = "spent-credits-output")]
+    spent_credits_output: Option<PathBuf>,
+
+    /// key to encrypt the vote with
+    #[clap(long = "key-path")]
+    election_key_path: Option<PathBuf>,
+
+    /// write the output to the given file or print it to the standard output if not defined
+    #[clap(long = "output")]
+    output: Option<PathBuf>,
+}
+
 /// create a vote cast certificate
 #[derive(Parser)]
 pub enum VoteCastCmd {
     Public(PublicVoteCast),
     Private(PrivateVoteCast),
+    /// cast a ranking of the choices
+    RankedChoice(ChoicesVoteCast),
+    /// cast the set of approved choices
+    Approval(ChoicesVoteCast),
+    /// allocate credits to a choice
+    Quadratic(QuadraticVoteCast),
+    /// allocate encrypted credits to an encrypted choice
+    PrivateQuadratic(PrivateQuadraticVoteCast),
 }
 
 impl PublicVoteCast {
@@ -98,11 +199,79 @@ impl PrivateVoteCast {
     }
 }
 
+impl ChoicesVoteCast {
+    pub fn exec(self, payload: fn(Vec<Choice>) -> Payload) -> Result<(), Error> {
+        let payload = payload(self.choices.into_iter().map(Choice::new).collect());
+
+        let vote_cast = VoteCast::new(self.vote_plan_id, self.proposal_index, payload);
+        let cert = Certificate::VoteCast(vote_cast);
+        write_cert(self.output.as_deref(), cert.into())
+    }
+}
+
+impl QuadraticVoteCast {
+    pub fn exec(self) -> Result<(), Error> {
+        let payload = Payload::quadratic(Choice::new(self.choice), self.credits);
+
+        let vote_cast = VoteCast::new(self.vote_plan_id, self.proposal_index, payload);
+        let cert = Certificate::VoteCast(vote_cast);
+        write_cert(self.output.as_deref(), cert.into())
+    }
+}
+
+impl PrivateQuadraticVoteCast {
+    pub fn exec(self) -> Result<(), Error> {
+        let mut rng = rand_chacha::ChaChaRng::from_entropy();
+        let key_line = utils::io::read_line(&self.election_key_path)?;
+        let key = chain_vote::ElectionPublicKey::try_from_bech32_str(&key_line)?;
+
+        let spent = match &self.spent_credits {
+            Some(path) => {
+                let line = utils::io::read_line(&Some(path))?;
+                hex::decode(line.trim())
+                    .ok()
+                    .and_then(|bytes| CreditsOpening::from_bytes(&bytes))
+                    .ok_or(Error::InvalidCreditsOpening {
+                        expected: CreditsOpening::BYTES_LEN,
+                    })?
+            }
+            None => CreditsOpening::new(),
+        };
+
+        let vote = chain_vote::Vote::new(self.options, self.choice as usize)?;
+        let crs = chain_vote::Crs::from_hash(self.vote_plan_id.as_ref());
+        let (encrypted_vote, proof, allocation, opening) =
+            chain_impl_mockchain::vote::encrypt_quadratic_vote(
+                &mut rng,
+                &crs,
+                &key,
+                vote,
+                self.credits,
+                self.budget,
+                &spent,
+            )?;
+
+        let payload = Payload::private_quadratic(encrypted_vote, proof, allocation);
+
+        let vote_cast = VoteCast::new(self.vote_plan_id, self.proposal_index, payload);
+        let cert = Certificate::VoteCast(vote_cast);
+        write_cert(self.output.as_deref(), cert.into())?;
+
+        let mut output = utils::io::open_file_write(&self.spent_credits_output)?;
+        writeln!(output, "{}", hex::encode(opening.to_bytes()))?;
+        Ok(())
+    }
+}
+
 impl VoteCastCmd {
     pub fn exec(self) -> Result<(), Error> {
         match self {
             VoteCastCmd::Public(vote_cast) => vote_cast.exec(),
             VoteCastCmd::Private(vote_cast) => vote_cast.exec(),
+            VoteCastCmd::RankedChoice(vote_cast) => vote_cast.exec(Payload::ranked_choice),
+            VoteCastCmd::Approval(vote_cast) => vote_cast.exec(Payload::approval),
+            VoteCastCmd::Quadratic(vote_cast) => vote_cast.exec(),
+            VoteCastCmd::PrivateQuadratic(vote_cast) => vote_cast.exec(),
         }
     }
 }
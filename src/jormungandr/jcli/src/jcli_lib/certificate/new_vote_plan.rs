@@ -3,7 +3,7 @@ use crate::jcli_lib::{
     utils::io,
 };
 use chain_impl_mockchain::{
-    certificate::{self, Certificate},
+    certificate::{self, Certificate, VoteAction},
     vote::PayloadType,
 };
 use clap::Parser;
@@ -26,7 +26,8 @@ pub struct VotePlanRegistration {
 }
 
 fn validate_voteplan(voteplan: &certificate::VotePlan) -> Result<(), Error> {
-    // if voteplan is private committee member keys should be filled
+    // if voteplan is private committee member keys should be filled, multiple
+    // choices ballots can only be used with off chain proposals
     match voteplan.payload_type() {
         PayloadType::Public => {}
         PayloadType::Private => {
@@ -34,6 +35,27 @@ fn validate_voteplan(voteplan: &certificate::VotePlan) -> Result<(), Error> {
                 return Err(Error::InvalidPrivateVotePlanCommitteeKeys);
             }
         }
+        PayloadType::PrivateQuadratic => {
+            if voteplan.committee_public_keys().is_empty() {
+                return Err(Error::InvalidPrivateVotePlanCommitteeKeys);
+            }
+            if voteplan
+                .proposals()
+                .iter()
+                .any(|proposal| !matches!(proposal.action(), VoteAction::OffChain))
+            {
+                return Err(Error::InvalidVotePlanAction);
+            }
+        }
+        PayloadType::RankedChoice | PayloadType::Approval | PayloadType::Quadratic => {
+            if voteplan
+                .proposals()
+                .iter()
+                .any(|proposal| !matches!(proposal.action(), VoteAction::OffChain))
+            {
+                return Err(Error::InvalidVotePlanAction);
+            }
+        }
     }
     Ok(())
 }
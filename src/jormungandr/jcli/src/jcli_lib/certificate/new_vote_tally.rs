@@ -78,13 +78,13 @@ impl PrivateTally {
     pub fn exec(self) -> Result<(), Error> {
         let vote_plan =
             vote::get_vote_plan_by_id(Some(self.vote_plan), self.vote_plan_id.as_ref())?;
-        let shares: Vec<Vec<chain_vote::TallyDecryptShare>> =
-            vote::read_vote_plan_shares_from_file(
-                Some(self.shares),
-                vote_plan.proposals.len(),
-                None,
-            )?
-            .try_into()?;
+        let shares = vote::read_vote_plan_shares_from_file(
+            Some(self.shares),
+            vote_plan.proposals.len(),
+            None,
+        )?;
+        let member_indices = shares.member_indices().to_vec();
+        let shares: Vec<Vec<chain_vote::TallyDecryptShare>> = shares.try_into()?;
 
         let tallies = vote_plan
             .proposals
@@ -95,12 +95,16 @@ impl PrivateTally {
                     state: PrivateTallyState::Decrypted { result, .. },
                 } => Ok(DecryptedPrivateTallyProposal {
                     decrypt_shares: shares.into_boxed_slice(),
+                    member_indices: member_indices.clone().into_boxed_slice(),
                     tally_result: result.results().into_boxed_slice(),
                 }),
                 other => {
                     let found = match other {
                         Tally::Public { .. } => "public tally",
                         Tally::Private { .. } => "private encrypted tally",
+                        Tally::RankedChoice { .. } => "ranked choice tally",
+                        Tally::Approval { .. } => "approval tally",
+                        Tally::Quadratic { .. } => "quadratic tally",
                     };
                     Err(Error::PrivateTallyExpected { found })
                 }
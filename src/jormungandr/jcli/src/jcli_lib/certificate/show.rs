@@ -1,3 +1,4 @@
+mod multisig_id;
 mod stake_pool_id;
 mod vote_plan_id;
 
@@ -11,6 +12,8 @@ pub enum ShowArgs {
     StakePoolId(stake_pool_id::GetStakePoolId),
     /// get the vote plan id from the given vote plan certificate
     VotePlanId(vote_plan_id::GetVotePlanId),
+    /// get the multisig account id from the given multisig registration certificate
+    MultisigId(multisig_id::GetMultisigId),
 }
 
 impl ShowArgs {
@@ -18,6 +21,7 @@ impl ShowArgs {
         match self {
             ShowArgs::StakePoolId(args) => args.exec(),
             ShowArgs::VotePlanId(args) => args.exec(),
+            ShowArgs::MultisigId(args) => args.exec(),
         }
     }
 }
@@ -125,6 +125,9 @@ impl Sign {
                     })??
             }
             Certificate::MintToken(_) => return Err(Error::MintTokenDoesntNeedSignature),
+            Certificate::MultisigRegistration(_) => {
+                return Err(Error::MultisigRegistrationDoesntNeedSignature)
+            }
             Certificate::EvmMapping(uv) => {
                 let txbuilder = Transaction::block0_payload_builder(&uv);
                 keys_str
@@ -155,8 +158,13 @@ pub(crate) fn committee_vote_tally_sign(
     });
 
     let proof = match vote_tally.tally_type() {
-        PayloadType::Public => TallyProof::Public { id, signature },
-        PayloadType::Private => TallyProof::Private { id, signature },
+        PayloadType::Public
+        | PayloadType::RankedChoice
+        | PayloadType::Approval
+        | PayloadType::Quadratic => TallyProof::Public { id, signature },
+        PayloadType::Private | PayloadType::PrivateQuadratic => {
+            TallyProof::Private { id, signature }
+        }
     };
     Ok(SignedCertificate::VoteTally(vote_tally, proof))
 }
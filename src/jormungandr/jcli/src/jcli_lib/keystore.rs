@@ -0,0 +1,226 @@
+use crate::jcli_lib::utils::{
+    io,
+    output_file::{self, OutputFile},
+};
+use chain_crypto::{bech32::Bech32, Ed25519Bip32, Ed25519Extended, SecretKey};
+use clap::{Parser, ValueEnum};
+use rand::rngs::OsRng;
+use std::{
+    io::{Read, Write},
+    path::PathBuf,
+    time::SystemTime,
+};
+use thiserror::Error;
+use wallet::keystore::{self, KeyScheme, Keystore};
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("I/O error")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    InvalidOutput(#[from] output_file::Error),
+    #[error("invalid keystore")]
+    Keystore(#[from] keystore::Error),
+    #[error("expected an ed25519extended or an ed25519bip32 bech32 secret key")]
+    InvalidSecretKey,
+    #[error("the scheme of a legacy ed25519bip32 root key must be given with --legacy")]
+    MissingLegacyScheme,
+    #[error("--legacy is only for ed25519bip32 root keys")]
+    UnexpectedLegacyScheme,
+}
+
+#[derive(Parser, Debug)]
+#[clap(rename_all = "kebab-case")]
+pub enum KeystoreCommand {
+    /// encrypt a secret key with a password in a new keystore
+    Create(Create),
+    /// decrypt the secret key of a keystore
+    Unlock(Unlock),
+    /// encrypt the secret key of a keystore with a new password
+    ChangePassword(ChangePassword),
+    /// display the metadata of a keystore
+    Info(Info),
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum LegacyScheme {
+    /// root key of a Yoroi wallet
+    Bip44,
+    /// root key of a Daedalus wallet
+    Rindex,
+}
+
+#[derive(Parser, Debug)]
+pub struct Create {
+    /// the bech32 secret key to encrypt: the ed25519extended key of an
+    /// account or the ed25519bip32 root key of a legacy wallet
+    #[clap(long)]
+    input: PathBuf,
+
+    /// the derivation scheme of the legacy wallet of an ed25519bip32 root key
+    #[clap(long, value_enum)]
+    legacy: Option<LegacyScheme>,
+
+    /// file holding the password, prompted for if not provided
+    #[clap(long)]
+    password_file: Option<PathBuf>,
+
+    #[clap(flatten)]
+    output_file: OutputFile,
+}
+
+#[derive(Parser, Debug)]
+pub struct Unlock {
+    /// file holding the password, prompted for if not provided
+    #[clap(long)]
+    password_file: Option<PathBuf>,
+
+    /// write the bech32 secret key to the given file or to stdout if not provided
+    #[clap(long)]
+    output: Option<PathBuf>,
+
+    /// the keystore, read from the standard input if not provided
+    keystore: Option<PathBuf>,
+}
+
+#[derive(Parser, Debug)]
+pub struct ChangePassword {
+    /// file holding the current password, prompted for if not provided
+    #[clap(long)]
+    password_file: Option<PathBuf>,
+
+    /// file holding the new password, prompted for if not provided
+    #[clap(long)]
+    new_password_file: Option<PathBuf>,
+
+    /// write the new keystore to the given file or to stdout if not provided
+    #[clap(long)]
+    output: Option<PathBuf>,
+
+    /// the keystore
+    keystore: PathBuf,
+}
+
+#[derive(Parser, Debug)]
+pub struct Info {
+    /// the keystore, read from the standard input if not provided
+    keystore: Option<PathBuf>,
+}
+
+impl KeystoreCommand {
+    pub fn exec(self) -> Result<(), Error> {
+        match self {
+            KeystoreCommand::Create(args) => args.exec(),
+            KeystoreCommand::Unlock(args) => args.exec(),
+            KeystoreCommand::ChangePassword(args) => args.exec(),
+            KeystoreCommand::Info(args) => args.exec(),
+        }
+    }
+}
+
+impl Create {
+    fn exec(self) -> Result<(), Error> {
+        let line = io::read_line(&Some(&self.input))?;
+        let (scheme, secret_key) =
+            if let Ok(key) = SecretKey::<Ed25519Extended>::try_from_bech32_str(&line) {
+                if self.legacy.is_some() {
+                    return Err(Error::UnexpectedLegacyScheme);
+                }
+                let bytes = key.leak_secret().as_ref().to_vec();
+                (KeyScheme::Ed25519Extended, bytes)
+            } else if let Ok(key) = SecretKey::<Ed25519Bip32>::try_from_bech32_str(&line) {
+                let scheme = match self.legacy {
+                    Some(LegacyScheme::Bip44) => KeyScheme::Bip44,
+                    Some(LegacyScheme::Rindex) => KeyScheme::Rindex,
+                    None => return Err(Error::MissingLegacyScheme),
+                };
+                (scheme, key.leak_secret().as_ref().to_vec())
+            } else {
+                return Err(Error::InvalidSecretKey);
+            };
+
+        let password = read_password(&self.password_file, "Password of the keystore: ")?;
+        let keystore = Keystore::create(
+            scheme,
+            &secret_key,
+            password.as_bytes(),
+            SystemTime::now(),
+            OsRng,
+        )?;
+
+        writeln!(self.output_file.open()?, "{}", keystore.to_json())?;
+        Ok(())
+    }
+}
+
+impl Unlock {
+    fn exec(self) -> Result<(), Error> {
+        let keystore = read_keystore(&self.keystore)?;
+        let password = read_password(&self.password_file, "Password of the keystore: ")?;
+        let secret_key = keystore.unlock(password.as_bytes())?;
+
+        let bech32 = match keystore.scheme() {
+            KeyScheme::Ed25519Extended => SecretKey::<Ed25519Extended>::from_binary(&secret_key)
+                .map_err(|_| Error::InvalidSecretKey)?
+                .to_bech32_str(),
+            KeyScheme::Bip44 | KeyScheme::Rindex => {
+                SecretKey::<Ed25519Bip32>::from_binary(&secret_key)
+                    .map_err(|_| Error::InvalidSecretKey)?
+                    .to_bech32_str()
+            }
+        };
+
+        writeln!(io::open_file_write(&self.output)?, "{}", bech32)?;
+        Ok(())
+    }
+}
+
+impl ChangePassword {
+    fn exec(self) -> Result<(), Error> {
+        let keystore = read_keystore(&Some(&self.keystore))?;
+        let password = read_password(&self.password_file, "Current password of the keystore: ")?;
+        let new_password =
+            read_password(&self.new_password_file, "New password of the keystore: ")?;
+
+        let keystore =
+            keystore.change_password(password.as_bytes(), new_password.as_bytes(), OsRng)?;
+
+        writeln!(io::open_file_write(&self.output)?, "{}", keystore.to_json())?;
+        Ok(())
+    }
+}
+
+impl Info {
+    fn exec(self) -> Result<(), Error> {
+        let keystore = read_keystore(&self.keystore)?;
+        let created_at = keystore
+            .created_at()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let scheme = match keystore.scheme() {
+            KeyScheme::Ed25519Extended => "ed25519-extended",
+            KeyScheme::Bip44 => "bip44",
+            KeyScheme::Rindex => "rindex",
+        };
+
+        println!("version: {}", keystore.version());
+        println!("account_id: {}", hex::encode(keystore.account_id()));
+        println!("scheme: {}", scheme);
+        println!("created_at: {}", created_at);
+        Ok(())
+    }
+}
+
+fn read_keystore(path: &Option<impl AsRef<std::path::Path>>) -> Result<Keystore, Error> {
+    let mut json = String::new();
+    io::open_file_read(path)?.read_to_string(&mut json)?;
+    Ok(Keystore::from_json(&json)?)
+}
+
+fn read_password(path: &Option<PathBuf>, prompt: &str) -> Result<String, Error> {
+    match path {
+        Some(path) => Ok(io::read_line(&Some(path))?),
+        None => Ok(rpassword::prompt_password(prompt)?),
+    }
+}
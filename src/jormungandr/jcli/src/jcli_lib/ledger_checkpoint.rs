@@ -0,0 +1,87 @@
+use crate::jcli_lib::utils::io;
+use clap::Parser;
+use jormungandr_lib::interfaces::{
+    list_ledger_checkpoints, LedgerCheckpoint, LedgerCheckpointError, LEDGER_CHECKPOINTS_DIRECTORY,
+};
+use std::{
+    io::{Read, Write},
+    path::PathBuf,
+};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("I/O error")]
+    Io(#[from] std::io::Error),
+    #[error("invalid ledger checkpoint")]
+    InvalidCheckpoint(#[from] LedgerCheckpointError),
+    #[error("no valid ledger checkpoint in '{0}'")]
+    NoCheckpoint(PathBuf),
+}
+
+#[derive(Parser, Debug)]
+#[clap(rename_all = "kebab-case")]
+pub enum LedgerCheckpointCommand {
+    /// copy the most recent valid ledger checkpoint of a node's storage
+    Export(Export),
+    /// verify a ledger checkpoint and add it to a node's storage, the node
+    /// starts from it on its next restart if the checkpoint's block is on
+    /// its main branch
+    Import(Import),
+}
+
+#[derive(Parser, Debug)]
+pub struct Export {
+    /// the storage directory of the node
+    #[clap(long)]
+    storage: PathBuf,
+
+    /// write the checkpoint to the given file or to stdout if not provided
+    #[clap(long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Parser, Debug)]
+pub struct Import {
+    /// the storage directory of the node
+    #[clap(long)]
+    storage: PathBuf,
+
+    /// the checkpoint file, read from the standard input if not provided
+    input: Option<PathBuf>,
+}
+
+impl LedgerCheckpointCommand {
+    pub fn exec(self) -> Result<(), Error> {
+        match self {
+            LedgerCheckpointCommand::Export(args) => args.exec(),
+            LedgerCheckpointCommand::Import(args) => args.exec(),
+        }
+    }
+}
+
+impl Export {
+    fn exec(self) -> Result<(), Error> {
+        let directory = self.storage.join(LEDGER_CHECKPOINTS_DIRECTORY);
+        for path in list_ledger_checkpoints(&directory)? {
+            let bytes = std::fs::read(&path)?;
+            if LedgerCheckpoint::from_bytes(&bytes).is_ok() {
+                io::open_file_write(&self.output)?.write_all(&bytes)?;
+                return Ok(());
+            }
+        }
+        Err(Error::NoCheckpoint(directory))
+    }
+}
+
+impl Import {
+    fn exec(self) -> Result<(), Error> {
+        let mut bytes = Vec::new();
+        io::open_file_read(&self.input)?.read_to_end(&mut bytes)?;
+        let checkpoint = LedgerCheckpoint::from_bytes(&bytes)?;
+        let path =
+            checkpoint.write_to_directory(self.storage.join(LEDGER_CHECKPOINTS_DIRECTORY))?;
+        println!("{}", path.display());
+        Ok(())
+    }
+}
@@ -3,7 +3,11 @@ pub mod block;
 pub mod certificate;
 pub mod debug;
 pub mod key;
+pub mod keystore;
+pub mod ledger_checkpoint;
+pub mod node_key;
 pub mod rest;
+pub mod storage;
 pub mod transaction;
 pub mod vote;
 
@@ -37,6 +41,12 @@ pub enum JCliCommand {
     /// Key Generation
     #[clap(subcommand)]
     Key(key::Key),
+    /// Password protected storage of wallet keys
+    #[clap(subcommand)]
+    Keystore(keystore::KeystoreCommand),
+    /// Node key generation and inspection
+    #[clap(subcommand)]
+    NodeKey(node_key::NodeKey),
     /// Address tooling and helper
     #[clap(subcommand)]
     Address(address::Address),
@@ -61,6 +71,12 @@ pub enum JCliCommand {
     /// Vote related operations
     #[clap(subcommand)]
     Votes(vote::Vote),
+    /// Ledger checkpoint export and import
+    #[clap(subcommand)]
+    LedgerCheckpoint(ledger_checkpoint::LedgerCheckpointCommand),
+    /// Block storage maintenance
+    #[clap(subcommand)]
+    Storage(storage::Storage),
 }
 
 impl JCli {
@@ -84,6 +100,8 @@ impl JCliCommand {
         use self::JCliCommand::*;
         match self {
             Key(key) => key.exec()?,
+            Keystore(keystore) => keystore.exec()?,
+            NodeKey(node_key) => node_key.exec()?,
             Address(address) => address.exec()?,
             Genesis(genesis) => genesis.exec()?,
             Rest(rest) => rest.exec()?,
@@ -92,6 +110,8 @@ impl JCliCommand {
             Certificate(certificate) => certificate.exec()?,
             Utils(utils) => utils.exec()?,
             Votes(vote) => vote.exec()?,
+            LedgerCheckpoint(checkpoint) => checkpoint.exec()?,
+            Storage(storage) => storage.exec()?,
         };
         Ok(())
     }
@@ -0,0 +1,86 @@
+use crate::jcli_lib::utils::{
+    io,
+    output_file::{self, OutputFile},
+};
+use chain_crypto::Ed25519;
+use clap::Parser;
+use jormungandr_lib::{crypto::key::SigningKey, interfaces::tls_server_name};
+use rand::rngs::OsRng;
+use std::{io::Write, path::PathBuf};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("I/O error")]
+    Io(#[from] std::io::Error),
+    #[error("invalid node key")]
+    InvalidKey(#[from] chain_crypto::bech32::Error),
+    #[error(transparent)]
+    InvalidOutput(#[from] output_file::Error),
+}
+
+#[derive(Parser, Debug)]
+#[clap(rename_all = "kebab-case")]
+pub enum NodeKey {
+    /// generate a key identifying the node in the P2P network, to be used as
+    /// the node's `p2p.bootstrap.node_key_file`
+    Generate(Generate),
+    /// print the node ID of a node key, to be pinned as the `id`
+    /// of the node in the `trusted_peers` of other nodes
+    Inspect(Inspect),
+}
+
+#[derive(Parser, Debug)]
+pub struct Generate {
+    #[clap(flatten)]
+    output_file: OutputFile,
+}
+
+#[derive(Parser, Debug)]
+pub struct Inspect {
+    /// the node key file, or the `node_key` file in the storage directory
+    /// of a node started without a node key file
+    ///
+    /// if no value passed, the key will be read from the standard input
+    #[clap(long = "input")]
+    input_key: Option<PathBuf>,
+
+    /// print the DNS name the P2P TLS certificate of the node
+    /// must be issued for, instead of the node ID
+    #[clap(long = "tls-server-name")]
+    tls_server_name: bool,
+
+    #[clap(flatten)]
+    output_file: OutputFile,
+}
+
+impl NodeKey {
+    pub fn exec(self) -> Result<(), Error> {
+        match self {
+            NodeKey::Generate(args) => args.exec(),
+            NodeKey::Inspect(args) => args.exec(),
+        }
+    }
+}
+
+impl Generate {
+    fn exec(self) -> Result<(), Error> {
+        let key = <SigningKey<Ed25519>>::generate(OsRng);
+        let mut output = self.output_file.open()?;
+        writeln!(output, "{}", key.to_bech32_str())?;
+        Ok(())
+    }
+}
+
+impl Inspect {
+    fn exec(self) -> Result<(), Error> {
+        let key = <SigningKey<Ed25519>>::from_bech32_str(&io::read_line(&self.input_key)?)?;
+        let mut output = self.output_file.open()?;
+        if self.tls_server_name {
+            writeln!(output, "{}", tls_server_name(&key.identifier()))?;
+        } else {
+            writeln!(output, "{}", key.identifier())?;
+        }
+        Ok(())
+    }
+}
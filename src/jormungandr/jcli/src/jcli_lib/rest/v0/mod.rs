@@ -3,6 +3,7 @@ mod block;
 mod diagnostic;
 mod leaders;
 pub mod message;
+mod multisig;
 mod network;
 mod node;
 mod rewards;
@@ -32,6 +33,9 @@ pub enum V0 {
     /// Message sending
     #[clap(subcommand)]
     Message(message::Message),
+    /// Multisig account operations
+    #[clap(subcommand)]
+    Multisig(multisig::Multisig),
     /// Network information
     #[clap(subcommand)]
     Network(network::Network),
@@ -76,6 +80,7 @@ impl V0 {
             V0::Block(block) => block.exec(),
             V0::Leaders(leaders) => leaders.exec(),
             V0::Message(message) => message.exec(),
+            V0::Multisig(multisig) => multisig.exec(),
             V0::Network(network) => network.exec(),
             V0::Node(node) => node.exec(),
             V0::Settings(settings) => settings.exec(),
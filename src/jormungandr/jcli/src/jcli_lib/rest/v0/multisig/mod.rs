@@ -0,0 +1,38 @@
+use crate::jcli_lib::{
+    rest::{Error, RestArgs},
+    utils::OutputFormat,
+};
+use clap::Parser;
+use jormungandr_lib::interfaces::AccountState;
+
+#[derive(Parser)]
+#[clap(rename_all = "kebab-case")]
+pub enum Multisig {
+    /// Get multisig account state
+    Get {
+        #[clap(flatten)]
+        args: RestArgs,
+        #[clap(flatten)]
+        output_format: OutputFormat,
+        /// hex-encoded multisig account identifier
+        multisig_id: String,
+    },
+}
+
+impl Multisig {
+    pub fn exec(self) -> Result<(), Error> {
+        let Multisig::Get {
+            args,
+            output_format,
+            multisig_id,
+        } = self;
+        let state: AccountState = args
+            .client()?
+            .get(&["v0", "multisig", &multisig_id])
+            .execute()?
+            .json()?;
+        let formatted = output_format.format_json(serde_json::to_value(state)?)?;
+        println!("{}", formatted);
+        Ok(())
+    }
+}
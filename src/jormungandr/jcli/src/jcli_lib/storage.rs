@@ -0,0 +1,269 @@
+use crate::jcli_lib::utils::io;
+use chain_core::{packer::Codec, property::Deserialize};
+use chain_impl_mockchain::block::{Block, HeaderId};
+use chain_storage::{BlockInfo, BlockStore, StorageEngine};
+use clap::Parser;
+use std::{path::PathBuf, str::FromStr};
+use thiserror::Error;
+
+/// the tag of the node's main branch tip
+const MAIN_BRANCH_TAG: &str = "HEAD";
+
+/// the default limit of the size of an imported block, above the size of
+/// any block0 or block produced on a running chain
+const DEFAULT_MAX_BLOCK_SIZE: u32 = 32 * 1024 * 1024;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("I/O error")]
+    Io(#[from] std::io::Error),
+    #[error("block storage error")]
+    Storage(#[from] chain_storage::Error),
+    #[error("the block storage has {0} integrity issue(s), run `jcli storage repair` to rebuild its indexes")]
+    IntegrityCheckFailed(usize),
+    #[error("no block to export, the storage has no `HEAD` tag")]
+    NoTip,
+    #[error("block {to} is not a descendant of chain length {from}")]
+    InvalidRange { from: u32, to: HeaderId },
+    #[error("the imported blocks do not extend the main branch, its tip was not moved")]
+    ImportNotOnMainBranch,
+}
+
+/// Maintenance of a node's block storage, the node must not be running
+#[derive(Parser, Debug)]
+#[clap(rename_all = "kebab-case")]
+pub enum Storage {
+    /// check the blocks and the indexes of the storage
+    Check(StorageDirectory),
+    /// rebuild the indexes of the storage from the stored blocks, the blocks
+    /// that cannot be decoded or are not connected to the chain anymore
+    /// are removed
+    Repair(StorageDirectory),
+    /// reclaim the space unused by the volatile part of the storage
+    Compact(StorageDirectory),
+    /// move the volatile part of the storage to another storage engine
+    Convert(Convert),
+    /// write a range of blocks of the main branch to a single file
+    Export(Export),
+    /// add the blocks of a file written by `export` to the storage
+    Import(Import),
+}
+
+#[derive(Parser, Debug)]
+pub struct StorageDirectory {
+    /// the storage directory of the node
+    #[clap(long)]
+    storage: PathBuf,
+
+    /// the storage engine of the volatile part of the storage: `sled` or
+    /// `rocksdb`
+    #[clap(long, default_value = "sled", value_parser = StorageEngine::from_str)]
+    engine: StorageEngine,
+}
+
+#[derive(Parser, Debug)]
+pub struct Convert {
+    /// the storage directory of the node
+    #[clap(long)]
+    storage: PathBuf,
+
+    /// the current storage engine
+    #[clap(long, default_value = "sled", value_parser = StorageEngine::from_str)]
+    from: StorageEngine,
+
+    /// the new storage engine
+    #[clap(long, value_parser = StorageEngine::from_str)]
+    to: StorageEngine,
+}
+
+#[derive(Parser, Debug)]
+#[clap(rename_all = "kebab-case")]
+pub struct Export {
+    #[clap(flatten)]
+    storage: StorageDirectory,
+
+    /// the chain length of the first exported block
+    #[clap(long, default_value = "0")]
+    from: u32,
+
+    /// the last exported block, the tip of the main branch if not provided
+    #[clap(long, value_parser = HeaderId::from_str)]
+    to: Option<HeaderId>,
+
+    /// write the blocks to the given file or to stdout if not provided
+    #[clap(long)]
+    output: Option<PathBuf>,
+}
+
+/// The blocks of the archive are checked to be well formed and to extend the
+/// stored chain, but they are not validated by the ledger nor the consensus:
+/// the node would only notice an invalid block when it is applied. The node
+/// keeps using its own main branch unless `--set-tip` is given, which should
+/// only be done with an archive from a trusted source, e.g. one exported from
+/// a node you operate.
+#[derive(Parser, Debug)]
+#[clap(rename_all = "kebab-case")]
+pub struct Import {
+    #[clap(flatten)]
+    storage: StorageDirectory,
+
+    /// move the tip of the node's main branch to the last imported block if
+    /// it extends the current main branch, the node then continues from the
+    /// imported blocks without validating them
+    #[clap(long)]
+    set_tip: bool,
+
+    /// the maximum size in bytes of an imported block
+    #[clap(long, default_value_t = DEFAULT_MAX_BLOCK_SIZE)]
+    max_block_size: u32,
+
+    /// the file written by `export`, read from the standard input if not provided
+    input: Option<PathBuf>,
+}
+
+impl Storage {
+    pub fn exec(self) -> Result<(), Error> {
+        match self {
+            Storage::Check(args) => check(args),
+            Storage::Repair(args) => repair(args),
+            Storage::Compact(args) => compact(args),
+            Storage::Convert(args) => convert(args),
+            Storage::Export(args) => args.exec(),
+            Storage::Import(args) => args.exec(),
+        }
+    }
+}
+
+impl StorageDirectory {
+    fn open(&self) -> Result<BlockStore, Error> {
+        BlockStore::file_with_engine(
+            &self.storage,
+            HeaderId::zero_hash().as_bytes().to_vec(),
+            self.engine,
+        )
+        .map_err(Into::into)
+    }
+}
+
+fn check(args: StorageDirectory) -> Result<(), Error> {
+    let report = args.open()?.check_integrity(decode_block_info)?;
+    for issue in report.issues.iter() {
+        println!("{}", issue);
+    }
+    println!(
+        "checked {} permanent and {} volatile blocks",
+        report.permanent_blocks, report.volatile_blocks
+    );
+    if report.is_ok() {
+        Ok(())
+    } else {
+        Err(Error::IntegrityCheckFailed(report.issues.len()))
+    }
+}
+
+fn repair(args: StorageDirectory) -> Result<(), Error> {
+    let report = args.open()?.rebuild_indexes(decode_block_info)?;
+    for issue in report.unrecoverable.iter() {
+        println!("cannot repair: {}", issue);
+    }
+    for tag in report.removed_tags.iter() {
+        println!("removed tag '{}'", tag);
+    }
+    println!(
+        "indexed {} permanent and {} volatile blocks, removed {} blocks",
+        report.permanent_blocks,
+        report.volatile_blocks,
+        report.removed_blocks.len()
+    );
+    Ok(())
+}
+
+fn compact(args: StorageDirectory) -> Result<(), Error> {
+    let report = BlockStore::compact(&args.storage, args.engine)?;
+    println!(
+        "compacted the volatile storage from {} to {} bytes",
+        report.size_before, report.size_after
+    );
+    Ok(())
+}
+
+fn convert(args: Convert) -> Result<(), Error> {
+    let entries = BlockStore::convert(&args.storage, args.from, args.to)?;
+    println!(
+        "copied {} entries from {} to {}, the {} storage in '{}' can be removed",
+        entries,
+        args.from,
+        args.to,
+        args.from,
+        args.storage.join(args.from.directory_name()).display()
+    );
+    Ok(())
+}
+
+impl Export {
+    fn exec(self) -> Result<(), Error> {
+        let store = self.storage.open()?;
+        let to = match self.to {
+            Some(to) => to,
+            None => {
+                let tip = store.get_tag(MAIN_BRANCH_TAG)?.ok_or(Error::NoTip)?;
+                HeaderId::deserialize(&mut Codec::new(tip.as_ref())).map_err(|_| Error::NoTip)?
+            }
+        };
+        let to_chain_length = store.get_block_info(to.as_bytes())?.chain_length();
+        if to_chain_length < self.from {
+            return Err(Error::InvalidRange {
+                from: self.from,
+                to,
+            });
+        }
+        let distance = to_chain_length - self.from + 1;
+        store.export_blocks(to.as_bytes(), distance, io::open_file_write(&self.output)?)?;
+        Ok(())
+    }
+}
+
+impl Import {
+    fn exec(self) -> Result<(), Error> {
+        let store = self.storage.open()?;
+        let report = store.import_blocks(
+            io::open_file_read(&self.input)?,
+            self.max_block_size,
+            decode_block_info,
+        )?;
+        println!(
+            "imported {} blocks, {} were already stored",
+            report.imported, report.skipped
+        );
+
+        let last = match report.last_block_id {
+            Some(last) if self.set_tip => last,
+            _ => return Ok(()),
+        };
+        let extends_tip = match store.get_tag(MAIN_BRANCH_TAG)? {
+            Some(tip) => matches!(store.is_ancestor(tip.as_ref(), &last), Ok(Some(_))),
+            None => true,
+        };
+        if !extends_tip {
+            return Err(Error::ImportNotOnMainBranch);
+        }
+        store.put_tag(MAIN_BRANCH_TAG, &last)?;
+        println!("moved the tip of the main branch to the last imported block");
+        Ok(())
+    }
+}
+
+/// compute the storage metadata of a serialized block, checking that the
+/// content of the block matches its header
+fn decode_block_info(bytes: &[u8]) -> Option<BlockInfo> {
+    let block = Block::deserialize(&mut Codec::new(bytes)).ok()?;
+    let header = block.header();
+    if header.block_content_hash() != block.contents().compute_hash() {
+        return None;
+    }
+    Some(BlockInfo::new(
+        header.hash().as_bytes().to_vec(),
+        header.block_parent_hash().as_bytes().to_vec(),
+        header.chain_length().into(),
+    ))
+}
@@ -0,0 +1,51 @@
+use crate::jcli_lib::transaction::{add_witness::read_witness, mk_witness::write_witness, Error};
+use chain_impl_mockchain::{multisig::WitnessBuilder, transaction::Witness};
+use clap::Parser;
+use std::path::PathBuf;
+
+/// combine the multisig witnesses made by different owners of the
+/// same multisig account into a single witness
+#[derive(Parser)]
+#[clap(rename_all = "kebab-case")]
+pub struct MergeMultisigWitness {
+    /// the file path to the file to write the merged witness in.
+    /// If omitted it will be printed to the standard output.
+    #[clap(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// the multisig witness files to merge
+    #[clap(required = true)]
+    pub witnesses: Vec<PathBuf>,
+}
+
+impl MergeMultisigWitness {
+    pub fn exec(self) -> Result<(), Error> {
+        let mut spending_counter = None;
+        let mut builder = WitnessBuilder::new();
+
+        for path in &self.witnesses {
+            let (counter, witness) = match read_witness(path)? {
+                Witness::Multisig(counter, witness) => (counter, witness),
+                _ => return Err(Error::MergeMultisigWitnessNotMultisig { path: path.clone() }),
+            };
+            match spending_counter {
+                None => spending_counter = Some(counter),
+                Some(expected) if expected != counter => {
+                    return Err(Error::MergeMultisigWitnessCounterMismatch { path: path.clone() })
+                }
+                Some(_) => (),
+            }
+            for (index, pk, sig) in witness.iter() {
+                if builder.contains(index) {
+                    return Err(Error::MergeMultisigWitnessDuplicateIndex { path: path.clone() });
+                }
+                builder.append(*index, pk.clone(), sig.clone());
+            }
+        }
+
+        // `witnesses` is required to be non empty, so a counter has been read
+        let spending_counter = spending_counter.expect("at least one witness file");
+        let witness = Witness::Multisig(spending_counter, builder.finalize());
+        write_witness(self.output.as_deref(), &witness)
+    }
+}
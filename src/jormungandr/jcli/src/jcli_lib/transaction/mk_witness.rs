@@ -9,10 +9,15 @@ use chain_impl_mockchain::{
     accounting::account::spending::SpendingCounterIncreasing,
     header::HeaderId,
     key::EitherEd25519SecretKey,
-    transaction::{TransactionSignDataHash, Witness},
+    multisig::{self, Index, TreeIndex},
+    transaction::{TransactionSignDataHash, Witness, WitnessMultisigData},
 };
 use clap::Parser;
-use std::{io::Write, path::PathBuf, str::FromStr};
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
 #[derive(Parser)]
 #[clap(rename_all = "kebab-case")]
@@ -25,7 +30,7 @@ pub struct MkWitness {
     /// If omitted it will be printed to the standard output.
     pub output: Option<PathBuf>,
 
-    /// the type of witness to build: account, UTxO or Legacy UtxO
+    /// the type of witness to build: account, multisig, UTxO or Legacy UtxO
     #[clap(long = "type", value_parser = WitnessType::from_str)]
     pub witness_type: WitnessType,
 
@@ -33,8 +38,8 @@ pub struct MkWitness {
     #[clap(long = "genesis-block-hash", value_parser = HeaderId::from_str)]
     pub genesis_block_hash: HeaderId,
 
-    /// value is mandatory if `--type=account`. It is the counter value for
-    /// every time the account is being utilized.
+    /// value is mandatory if `--type=account` or `--type=multisig`. It is the
+    /// counter value for every time the account is being utilized.
     #[clap(long = "account-spending-counter")]
     pub account_spending_counter: Option<u32>,
 
@@ -44,6 +49,19 @@ pub struct MkWitness {
     #[clap(long)]
     pub account_spending_counter_lane: Option<usize>,
 
+    /// value is mandatory if `--type=multisig`. It is the index of the signing
+    /// key's owner in the multisig declaration (starting from 0).
+    ///
+    /// The resulting witness only holds this owner's signature, use
+    /// `merge-multisig-witness` to combine the witnesses of enough owners.
+    #[clap(long)]
+    pub multisig_index: Option<u8>,
+
+    /// if the owner at `--multisig-index` is a sub declaration, the index of
+    /// the signing key's owner in that sub declaration (starting from 0).
+    #[clap(long)]
+    pub multisig_sub_index: Option<u8>,
+
     /// the file path to the file to read the signing key from.
     /// If omitted it will be read from the standard input.
     pub secret: Option<PathBuf>,
@@ -54,6 +72,7 @@ pub enum WitnessType {
     UTxO,
     OldUTxO,
     Account,
+    Multisig,
 }
 
 impl std::str::FromStr for WitnessType {
@@ -64,7 +83,10 @@ impl std::str::FromStr for WitnessType {
             "utxo" => Ok(WitnessType::UTxO),
             "legacy-utxo" => Ok(WitnessType::OldUTxO),
             "account" => Ok(WitnessType::Account),
-            _ => Err("Invalid witness type, expected `utxo', `legacy-utxo' or `account'"),
+            "multisig" => Ok(WitnessType::Multisig),
+            _ => {
+                Err("Invalid witness type, expected `utxo', `legacy-utxo', `account' or `multisig'")
+            }
         }
     }
 }
@@ -90,27 +112,44 @@ impl MkWitness {
             &self.genesis_block_hash,
             &self.sign_data_hash,
             sc,
+            multisig_tree_index(self.multisig_index, self.multisig_sub_index)?,
             &secret_key,
         )?;
-        self.write_witness(&witness)
+        write_witness(self.output.as_deref(), &witness)
     }
+}
 
-    fn write_witness(&self, witness: &Witness) -> Result<(), Error> {
-        let mut writer =
-            io::open_file_write(&self.output).map_err(|source| Error::WitnessFileWriteFailed {
-                source,
-                path: self.output.clone().unwrap_or_default(),
-            })?;
-        let bytes = witness
-            .serialize_as_vec()
-            .map_err(Error::WitnessFileSerializationFailed)?;
-
-        let base32 = bytes.to_base32();
-        let bech32 = bech32::encode("witness", &base32, bech32::Variant::Bech32)?;
-        writeln!(writer, "{}", bech32).map_err(|source| Error::WitnessFileWriteFailed {
+pub(crate) fn write_witness(output: Option<&Path>, witness: &Witness) -> Result<(), Error> {
+    let mut writer =
+        io::open_file_write(&output).map_err(|source| Error::WitnessFileWriteFailed {
             source,
-            path: self.output.clone().unwrap_or_default(),
-        })
+            path: output.map(Path::to_path_buf).unwrap_or_default(),
+        })?;
+    let bytes = witness
+        .serialize_as_vec()
+        .map_err(Error::WitnessFileSerializationFailed)?;
+
+    let base32 = bytes.to_base32();
+    let bech32 = bech32::encode("witness", &base32, bech32::Variant::Bech32)?;
+    writeln!(writer, "{}", bech32).map_err(|source| Error::WitnessFileWriteFailed {
+        source,
+        path: output.map(Path::to_path_buf).unwrap_or_default(),
+    })
+}
+
+/// Position of a signing key in a multisig declaration, from the index of the
+/// owner and, if the owner is a sub declaration, the index in it.
+pub fn multisig_tree_index(
+    index: Option<u8>,
+    sub_index: Option<u8>,
+) -> Result<Option<TreeIndex>, Error> {
+    let parse =
+        |index| Index::from_u8(index).ok_or(Error::MakeWitnessMultisigIndexInvalid { index });
+    match (index, sub_index) {
+        (None, None) => Ok(None),
+        (None, Some(_)) => Err(Error::MakeWitnessMultisigIndexMissing),
+        (Some(index), None) => Ok(Some(TreeIndex::D1(parse(index)?))),
+        (Some(index), Some(sub_index)) => Ok(Some(TreeIndex::D2(parse(index)?, parse(sub_index)?))),
     }
 }
 
@@ -119,6 +158,7 @@ pub fn make_witness(
     genesis_block_hash: &HeaderId,
     sign_data_hash: &TransactionSignDataHash,
     account_spending_counter: Option<SpendingCounter>,
+    multisig_index: Option<TreeIndex>,
     secret_key: &EitherEd25519SecretKey,
 ) -> Result<Witness, Error> {
     let witness = match witness_type {
@@ -137,6 +177,16 @@ pub fn make_witness(
             account_spending_counter.ok_or(Error::MakeWitnessAccountCounterMissing)?,
             |d| secret_key.sign(d),
         ),
+        WitnessType::Multisig => {
+            let spending_counter =
+                account_spending_counter.ok_or(Error::MakeWitnessAccountCounterMissing)?;
+            let index = multisig_index.ok_or(Error::MakeWitnessMultisigIndexMissing)?;
+            let data =
+                WitnessMultisigData::new(genesis_block_hash, sign_data_hash, spending_counter);
+            let mut builder = multisig::WitnessBuilder::new();
+            builder.append(index, secret_key.to_public(), secret_key.sign(&data));
+            Witness::Multisig(spending_counter, builder.finalize())
+        }
     };
     Ok(witness)
 }
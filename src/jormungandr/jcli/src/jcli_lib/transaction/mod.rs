@@ -7,8 +7,10 @@ mod auth;
 mod common;
 pub mod finalize;
 mod info;
+mod merge_multisig_witness;
 mod mk_witness;
 pub mod new;
+mod partial;
 mod seal;
 mod set_expiry_date;
 mod simplified;
@@ -66,6 +68,12 @@ pub enum Transaction {
     Info(info::Info),
     /// create witnesses
     MakeWitness(mk_witness::MkWitness),
+    /// merge the multisig witnesses of several owners into one witness
+    MergeMultisigWitness(merge_multisig_witness::MergeMultisigWitness),
+    /// sign a transaction with several parties through a portable partially
+    /// signed transaction
+    #[clap(subcommand)]
+    Partial(partial::Partial),
     /// make auth
     Auth(auth::Auth),
     /// get the message format out of a sealed transaction
@@ -188,6 +196,60 @@ pub enum Error {
     MakeWitnessAccountCounterMissing,
     #[error("invalid account spending counter lane: max {max}, actual {actual}")]
     MakeWitnessAccountInvalidCounterLane { max: usize, actual: usize },
+    #[error("making multisig witness requires passing the owner's index")]
+    MakeWitnessMultisigIndexMissing,
+    #[error("invalid multisig owner index: {index}")]
+    MakeWitnessMultisigIndexInvalid { index: u8 },
+    #[error("witness file '{path}' is not a multisig witness")]
+    MergeMultisigWitnessNotMultisig { path: PathBuf },
+    #[error("witness file '{path}' has a different spending counter than the other witnesses")]
+    MergeMultisigWitnessCounterMismatch { path: PathBuf },
+    #[error("witness file '{path}' has a signature for an owner already present")]
+    MergeMultisigWitnessDuplicateIndex { path: PathBuf },
+    #[error("exporting a partially signed transaction from a {kind} transaction is not valid")]
+    TxKindToExportPartialInvalid { kind: StagingKind },
+    #[error("could not read partially signed transaction file '{path}'")]
+    PartialFileReadFailed {
+        #[source]
+        source: std::io::Error,
+        path: PathBuf,
+    },
+    #[error("could not parse partially signed transaction file '{path}'")]
+    PartialFileMalformed {
+        #[source]
+        source: serde_json::Error,
+        path: PathBuf,
+    },
+    #[error("could not write partially signed transaction file '{path}'")]
+    PartialFileWriteFailed {
+        #[source]
+        source: std::io::Error,
+        path: PathBuf,
+    },
+    #[error("unsupported version {version} of partially signed transaction")]
+    PartialVersionUnsupported { version: u8 },
+    #[error("partially signed transaction file '{path}' does not hold a finalized transaction with a witness slot per input")]
+    PartialContentInvalid { path: PathBuf },
+    #[error(
+        "the summary of partially signed transaction file '{path}' does not match its transaction"
+    )]
+    PartialSummaryMismatch { path: PathBuf },
+    #[error("partially signed transaction file '{path}' is for another transaction")]
+    PartialTransactionMismatch { path: PathBuf },
+    #[error("invalid input index {index}, the transaction has {inputs} inputs")]
+    PartialInputIndexInvalid { index: usize, inputs: usize },
+    #[error("the witness does not match the type of input {index}")]
+    PartialWitnessTypeInvalid { index: usize },
+    #[error("input {index} already has a different witness")]
+    PartialWitnessConflict { index: usize },
+    #[error("the multisig witness has a different spending counter than the one of input {index}")]
+    PartialWitnessCounterMismatch { index: usize },
+    #[error("the witness of input {index} is not signed by its owner for this transaction")]
+    PartialWitnessSignatureInvalid { index: usize },
+    #[error("input {index} is not an account input, it cannot be spent from a multisig account")]
+    PartialMultisigInputInvalid { index: usize },
+    #[error("the witnesses of the inputs {missing:?} are missing")]
+    PartialWitnessesMissing { missing: Vec<usize> },
     #[error("transaction type doesn't need payload authentification")]
     TxDoesntNeedPayloadAuth,
     #[error("transaction type need payload authentification")]
@@ -253,6 +315,8 @@ impl Transaction {
             Transaction::DataForWitness(common) => display_data_for_witness(common),
             Transaction::Info(info) => info.exec(),
             Transaction::MakeWitness(mk_witness) => mk_witness.exec(),
+            Transaction::MergeMultisigWitness(merge) => merge.exec(),
+            Transaction::Partial(partial) => partial.exec(),
             Transaction::Auth(auth) => auth.exec(),
             Transaction::ToMessage(common) => display_message(common),
             Transaction::MakeTransaction(send) => send.exec(),
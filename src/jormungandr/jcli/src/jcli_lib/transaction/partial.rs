@@ -0,0 +1,1136 @@
+//! Partially signed transactions: a portable document holding a finalized
+//! transaction and the witnesses collected so far, passed between the
+//! signers of the transaction, for example on separate air-gapped machines.
+//!
+//! The document carries a summary of the transaction for the reviewers. It
+//! is regenerated every time the document is written and checked against
+//! the content of the transaction every time the document is read.
+
+use crate::jcli_lib::{
+    transaction::{
+        add_witness::read_witness,
+        common,
+        staging::{Staging, StagingKind},
+        Error,
+    },
+    utils::{io, OutputFormat},
+};
+use chain_addr::AddressReadable;
+use chain_crypto::{Ed25519, PublicKey, Verification};
+use chain_impl_mockchain::{
+    certificate::Certificate,
+    header::HeaderId,
+    multisig::{self, TreeIndex},
+    transaction::{
+        Witness, WitnessAccountData, WitnessMultisigData, WitnessUtxoData, WitnessUtxoVersion,
+    },
+};
+use clap::Parser;
+use jormungandr_lib::{
+    crypto::hash::Hash,
+    interfaces::{BlockDate, TransactionInputType, TransactionWitness},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::Write as _,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// the version of the partially signed transaction format
+const PARTIAL_TRANSACTION_VERSION: u8 = 1;
+
+#[derive(Parser)]
+#[clap(rename_all = "kebab-case")]
+pub enum Partial {
+    /// export a finalized staging transaction to a partially signed
+    /// transaction, keeping the witnesses already added to it
+    Export(Export),
+    /// display the summary of a partially signed transaction and the
+    /// witnesses it is missing
+    Info(Info),
+    /// add a witness for one of the inputs of a partially signed transaction.
+    /// A multisig witness is merged with the signatures of the other owners
+    /// already present for the input
+    AddWitness(AddWitness),
+    /// merge the witnesses of several copies of the same partially signed
+    /// transaction, signed separately
+    Merge(Merge),
+    /// import a fully signed partially signed transaction in a staging file,
+    /// ready to be sealed
+    Import(Import),
+}
+
+#[derive(Parser)]
+#[clap(rename_all = "kebab-case")]
+pub struct PartialFile {
+    /// the partially signed transaction. If a file is given, it is read
+    /// from and the modifications are written to this same file. If no file
+    /// is given, it is read from the standard input and written to the
+    /// standard output
+    #[clap(long = "partial")]
+    pub partial_file: Option<PathBuf>,
+}
+
+#[derive(Parser)]
+#[clap(rename_all = "kebab-case")]
+pub struct Export {
+    #[clap(flatten)]
+    common: common::CommonTransaction,
+
+    /// the hash of the block0, the first block of the blockchain. The
+    /// witnesses added to the partially signed transaction are checked
+    /// against it
+    #[clap(long = "genesis-block-hash", value_parser = HeaderId::from_str)]
+    genesis_block_hash: HeaderId,
+
+    /// the multisig policy of an account input, as
+    /// `<input>:<threshold>:<owners>[:<owner>=<threshold>/<owners>,...]`: the
+    /// index of the input, the number of owners whose signatures are required
+    /// and the number of owners of the multisig declaration, followed by the
+    /// threshold and the number of owners of each owner which is itself a
+    /// multisig declaration. Required for every input spent from a multisig
+    /// account
+    #[clap(long = "multisig", value_parser = MultisigInput::from_str)]
+    multisig: Vec<MultisigInput>,
+
+    /// set the address prefix to use when displaying the addresses in the
+    /// summary
+    #[clap(long = "prefix", default_value = "ca")]
+    address_prefix: String,
+
+    /// write the partially signed transaction in the given file or print it
+    /// to the standard output
+    #[clap(long = "output")]
+    output: Option<PathBuf>,
+}
+
+#[derive(Parser)]
+#[clap(rename_all = "kebab-case")]
+pub struct Info {
+    #[clap(flatten)]
+    partial: PartialFile,
+
+    #[clap(flatten)]
+    output_format: OutputFormat,
+}
+
+#[derive(Parser)]
+#[clap(rename_all = "kebab-case")]
+pub struct AddWitness {
+    #[clap(flatten)]
+    partial: PartialFile,
+
+    /// the index of the input the witness is for, starting from 0
+    #[clap(long)]
+    input: usize,
+
+    /// the witness file, as made by `make-witness`
+    witness: PathBuf,
+}
+
+#[derive(Parser)]
+#[clap(rename_all = "kebab-case")]
+pub struct Merge {
+    /// write the merged partially signed transaction in the given file or
+    /// print it to the standard output
+    #[clap(long)]
+    output: Option<PathBuf>,
+
+    /// the partially signed transactions to merge
+    #[clap(required = true)]
+    partials: Vec<PathBuf>,
+}
+
+#[derive(Parser)]
+#[clap(rename_all = "kebab-case")]
+pub struct Import {
+    #[clap(flatten)]
+    common: common::CommonTransaction,
+
+    /// the fully signed partially signed transaction
+    partial: PathBuf,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultisigInput {
+    input: usize,
+    policy: MultisigPolicy,
+}
+
+/// The part of a multisig declaration needed to tell whether the witness of
+/// an input has enough signatures.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MultisigPolicy {
+    threshold: usize,
+    owners: usize,
+    /// the owners which are themselves multisig declarations, by index
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    sub_declarations: BTreeMap<usize, SubDeclarationPolicy>,
+}
+
+/// The threshold of an owner of a multisig declaration which is itself a
+/// multisig declaration: the signatures of its owners count as its own once
+/// they meet its threshold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubDeclarationPolicy {
+    threshold: usize,
+    owners: usize,
+}
+
+/// A finalized transaction and one witness slot per input.
+#[derive(Serialize, Deserialize)]
+pub struct PartialTransaction {
+    version: u8,
+    address_prefix: String,
+    genesis_block_hash: Hash,
+    multisig: BTreeMap<usize, MultisigPolicy>,
+    summary: Summary,
+    staging: Staging,
+    witnesses: Vec<Option<TransactionWitness>>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct Summary {
+    data_for_witness: String,
+    valid_until: Option<BlockDate>,
+    certificate: Option<String>,
+    inputs: Vec<InputSummary>,
+    outputs: Vec<OutputSummary>,
+    total_input: u64,
+    total_output: u64,
+    fee: u64,
+    missing_witnesses: Vec<usize>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct InputSummary {
+    kind: String,
+    source: String,
+    value: u64,
+    witness: String,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct OutputSummary {
+    address: String,
+    value: u64,
+}
+
+impl Partial {
+    pub fn exec(self) -> Result<(), Error> {
+        match self {
+            Partial::Export(export) => export.exec(),
+            Partial::Info(info) => info.exec(),
+            Partial::AddWitness(add_witness) => add_witness.exec(),
+            Partial::Merge(merge) => merge.exec(),
+            Partial::Import(import) => import.exec(),
+        }
+    }
+}
+
+impl Export {
+    fn exec(self) -> Result<(), Error> {
+        let staging = self.common.load()?;
+        let multisig = self
+            .multisig
+            .into_iter()
+            .map(|multisig| (multisig.input, multisig.policy))
+            .collect();
+        let partial = PartialTransaction::new(
+            staging,
+            self.genesis_block_hash,
+            multisig,
+            self.address_prefix,
+        )?;
+        partial.store(&self.output)
+    }
+}
+
+impl Info {
+    fn exec(self) -> Result<(), Error> {
+        let partial = PartialTransaction::load(&self.partial.partial_file)?;
+        let summary = serde_json::to_value(&partial.summary).expect("summary is serializable");
+        println!("{}", self.output_format.format_json(summary)?);
+        Ok(())
+    }
+}
+
+impl AddWitness {
+    fn exec(self) -> Result<(), Error> {
+        let mut partial = PartialTransaction::load(&self.partial.partial_file)?;
+        let witness = read_witness(&self.witness)?;
+        partial.add_witness(self.input, witness)?;
+        partial.store(&self.partial.partial_file)
+    }
+}
+
+impl Merge {
+    fn exec(self) -> Result<(), Error> {
+        let mut paths = self.partials.iter();
+        // `partials` is required to be non empty
+        let first = paths
+            .next()
+            .expect("at least one partially signed transaction");
+        let mut merged = PartialTransaction::load(&Some(first))?;
+
+        for path in paths {
+            let partial = PartialTransaction::load(&Some(path))?;
+            if partial.summary.data_for_witness != merged.summary.data_for_witness
+                || partial.genesis_block_hash != merged.genesis_block_hash
+                || partial.multisig != merged.multisig
+            {
+                return Err(Error::PartialTransactionMismatch { path: path.clone() });
+            }
+            for (index, witness) in partial.witnesses.into_iter().enumerate() {
+                if let Some(witness) = witness {
+                    merged.add_witness(index, witness.into())?;
+                }
+            }
+        }
+
+        merged.store(&self.output)
+    }
+}
+
+impl Import {
+    fn exec(self) -> Result<(), Error> {
+        let partial = PartialTransaction::load(&Some(&self.partial))?;
+        let staging = partial.into_staging()?;
+        self.common.store(&staging)
+    }
+}
+
+impl FromStr for MultisigInput {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<_> = s.split(':').collect();
+        let (input, threshold, owners, sub_declarations) = match fields.as_slice() {
+            [input, threshold, owners] => (input, threshold, owners, None),
+            [input, threshold, owners, sub_declarations] => {
+                (input, threshold, owners, Some(sub_declarations))
+            }
+            _ => {
+                return Err(format!(
+                    "expected <input>:<threshold>:<owners>[:<owner>=<threshold>/<owners>,...], got '{}'",
+                    s
+                ))
+            }
+        };
+        let parse = |name: &str, value: &str| {
+            value
+                .parse::<usize>()
+                .map_err(|e| format!("invalid {} '{}': {}", name, value, e))
+        };
+        let threshold = parse("threshold", threshold)?;
+        let owners = parse("owners", owners)?;
+        check_multisig_declaration(threshold, owners)?;
+
+        let mut policy = MultisigPolicy {
+            threshold,
+            owners,
+            sub_declarations: BTreeMap::new(),
+        };
+        for sub_declaration in sub_declarations.iter().flat_map(|s| s.split(',')) {
+            let (owner, threshold, owners) = match sub_declaration.split_once('=') {
+                Some((owner, rest)) => match rest.split_once('/') {
+                    Some((threshold, owners)) => (owner, threshold, owners),
+                    None => (owner, rest, ""),
+                },
+                None => {
+                    return Err(format!(
+                        "expected <owner>=<threshold>/<owners>, got '{}'",
+                        sub_declaration
+                    ))
+                }
+            };
+            let owner = parse("owner", owner)?;
+            let sub = SubDeclarationPolicy {
+                threshold: parse("threshold", threshold)?,
+                owners: parse("owners", owners)?,
+            };
+            if owner >= policy.owners {
+                return Err(format!(
+                    "the multisig declaration has {} owners, got owner {}",
+                    policy.owners, owner
+                ));
+            }
+            check_multisig_declaration(sub.threshold, sub.owners)?;
+            if policy.sub_declarations.insert(owner, sub).is_some() {
+                return Err(format!(
+                    "the owner {} is given more than one multisig declaration",
+                    owner
+                ));
+            }
+        }
+
+        Ok(MultisigInput {
+            input: parse("input", input)?,
+            policy,
+        })
+    }
+}
+
+fn check_multisig_declaration(threshold: usize, owners: usize) -> Result<(), String> {
+    if owners == 0 || owners > multisig::LEVEL_MAXLIMIT {
+        return Err(format!(
+            "a multisig declaration has between 1 and {} owners, got {}",
+            multisig::LEVEL_MAXLIMIT,
+            owners
+        ));
+    }
+    if threshold == 0 || threshold > owners {
+        return Err(format!(
+            "the threshold has to be between 1 and the number of owners, got {}",
+            threshold
+        ));
+    }
+    Ok(())
+}
+
+impl MultisigPolicy {
+    /// whether a signature at this index can be part of the witness: the
+    /// owners which are multisig declarations sign through their own owners
+    fn contains(&self, index: &TreeIndex) -> bool {
+        match index {
+            TreeIndex::D1(owner) => {
+                owner.to_usize() < self.owners
+                    && !self.sub_declarations.contains_key(&owner.to_usize())
+            }
+            TreeIndex::D2(owner, sub_owner) => self
+                .sub_declarations
+                .get(&owner.to_usize())
+                .map_or(false, |sub| sub_owner.to_usize() < sub.owners),
+        }
+    }
+
+    /// the owners of the declaration who signed, and the owners which are
+    /// multisig declarations signed by fewer of their own owners than their
+    /// threshold, which the ledger rejects
+    fn signers(&self, witness: &multisig::Witness) -> (BTreeSet<usize>, BTreeSet<usize>) {
+        let mut signed = BTreeSet::new();
+        let mut sub_signers = BTreeMap::<usize, BTreeSet<usize>>::new();
+        for (index, _, _) in witness.iter() {
+            match index {
+                TreeIndex::D1(owner) => {
+                    signed.insert(owner.to_usize());
+                }
+                TreeIndex::D2(owner, sub_owner) => {
+                    sub_signers
+                        .entry(owner.to_usize())
+                        .or_default()
+                        .insert(sub_owner.to_usize());
+                }
+            }
+        }
+
+        let mut incomplete = BTreeSet::new();
+        for (owner, sub_owners) in sub_signers {
+            match self.sub_declarations.get(&owner) {
+                Some(sub) if sub_owners.len() >= sub.threshold => signed.insert(owner),
+                _ => incomplete.insert(owner),
+            };
+        }
+        (signed, incomplete)
+    }
+}
+
+impl PartialTransaction {
+    pub fn new(
+        mut staging: Staging,
+        genesis_block_hash: HeaderId,
+        multisig: BTreeMap<usize, MultisigPolicy>,
+        address_prefix: String,
+    ) -> Result<Self, Error> {
+        if staging.kind() != StagingKind::Finalizing {
+            return Err(Error::TxKindToExportPartialInvalid {
+                kind: staging.kind(),
+            });
+        }
+        for index in multisig.keys() {
+            match staging.inputs().get(*index).map(|input| &input.input) {
+                Some(TransactionInputType::Account(_)) => {}
+                _ => return Err(Error::PartialMultisigInputInvalid { index: *index }),
+            }
+        }
+
+        let witnesses = staging.take_witnesses();
+        let slots = vec![None; staging.inputs().len()];
+        let summary = summarize(&staging, &slots, &multisig, &address_prefix)?;
+        let mut partial = Self {
+            version: PARTIAL_TRANSACTION_VERSION,
+            address_prefix,
+            genesis_block_hash: genesis_block_hash.into(),
+            multisig,
+            summary,
+            staging,
+            witnesses: slots,
+        };
+        // the witnesses already in the staging transaction go through the
+        // same checks as the ones added later
+        for (index, witness) in witnesses.into_iter().enumerate() {
+            partial.add_witness(index, witness.into())?;
+        }
+        Ok(partial)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: &Option<P>) -> Result<Self, Error> {
+        let file = io::open_file_read(path).map_err(|source| Error::PartialFileReadFailed {
+            source,
+            path: io::path_to_path_buf(path),
+        })?;
+        let partial: Self =
+            serde_json::from_reader(file).map_err(|source| Error::PartialFileMalformed {
+                source,
+                path: io::path_to_path_buf(path),
+            })?;
+
+        if partial.version != PARTIAL_TRANSACTION_VERSION {
+            return Err(Error::PartialVersionUnsupported {
+                version: partial.version,
+            });
+        }
+        let multisig_inputs_valid = partial.multisig.keys().all(|index| {
+            matches!(
+                partial
+                    .staging
+                    .inputs()
+                    .get(*index)
+                    .map(|input| &input.input),
+                Some(TransactionInputType::Account(_))
+            )
+        });
+        if partial.staging.kind() != StagingKind::Finalizing
+            || partial.witnesses.len() != partial.staging.inputs().len()
+            || !multisig_inputs_valid
+        {
+            return Err(Error::PartialContentInvalid {
+                path: io::path_to_path_buf(path),
+            });
+        }
+        for (index, witness) in partial.witnesses.iter().enumerate() {
+            if let Some(witness) = witness {
+                partial.verify_witness(index, witness.as_ref())?;
+            }
+        }
+        // the summary is what the reviewers read, it has to describe the
+        // transaction that is signed
+        let summary = partial.summarize()?;
+        if summary != partial.summary {
+            return Err(Error::PartialSummaryMismatch {
+                path: io::path_to_path_buf(path),
+            });
+        }
+
+        Ok(partial)
+    }
+
+    pub fn store<P: AsRef<Path>>(&self, path: &Option<P>) -> Result<(), Error> {
+        let mut file =
+            io::open_file_write(path).map_err(|source| Error::PartialFileWriteFailed {
+                source,
+                path: io::path_to_path_buf(path),
+            })?;
+        serde_json::to_writer_pretty(&mut file, self).map_err(|source| {
+            Error::PartialFileWriteFailed {
+                source: source.into(),
+                path: io::path_to_path_buf(path),
+            }
+        })?;
+        writeln!(file).map_err(|source| Error::PartialFileWriteFailed {
+            source,
+            path: io::path_to_path_buf(path),
+        })
+    }
+
+    /// Set the witness of the input at `index`, merging the signatures of
+    /// multisig witnesses. The witness is checked against the transaction
+    /// before it is added.
+    pub fn add_witness(&mut self, index: usize, witness: Witness) -> Result<(), Error> {
+        self.verify_witness(index, &witness)?;
+
+        let present: Option<&Witness> = self.witnesses[index].as_ref().map(AsRef::as_ref);
+        let witness = match (present, witness) {
+            (None, witness) => witness,
+            (
+                Some(Witness::Multisig(counter, present)),
+                Witness::Multisig(other_counter, other),
+            ) => {
+                if *counter != other_counter {
+                    return Err(Error::PartialWitnessCounterMismatch { index });
+                }
+                let mut builder = multisig::WitnessBuilder::new();
+                for (owner, pk, sig) in present.iter().chain(other.iter()) {
+                    if !builder.contains(owner) {
+                        builder.append(*owner, pk.clone(), sig.clone());
+                    }
+                }
+                Witness::Multisig(*counter, builder.finalize())
+            }
+            (Some(present), witness) if *present == witness => return Ok(()),
+            (Some(_), _) => return Err(Error::PartialWitnessConflict { index }),
+        };
+
+        self.witnesses[index] = Some(witness.into());
+        self.summary = self.summarize()?;
+        Ok(())
+    }
+
+    /// Check that the witness matches the type of the input at `index` and
+    /// that its signatures are of the data to sign.
+    ///
+    /// The key of an account input is the account identifier, so the
+    /// signature of an account witness is checked against it. The keys of
+    /// the owners of a multisig account and of the output spent by a utxo
+    /// input are not known to the partially signed transaction: their
+    /// signatures are checked against the keys carried by the witness and
+    /// the ledger checks the keys themselves when the transaction is sent.
+    fn verify_witness(&self, index: usize, witness: &Witness) -> Result<(), Error> {
+        let input = self
+            .staging
+            .inputs()
+            .get(index)
+            .ok_or(Error::PartialInputIndexInvalid {
+                index,
+                inputs: self.witnesses.len(),
+            })?;
+        let multisig = self.multisig.get(&index);
+
+        let expected = match (&input.input, witness) {
+            (TransactionInputType::Account(_), Witness::Account(..)) => multisig.is_none(),
+            (TransactionInputType::Account(_), Witness::Multisig(..)) => multisig.is_some(),
+            (TransactionInputType::Utxo(..), Witness::Utxo(_))
+            | (TransactionInputType::Utxo(..), Witness::OldUtxo(..))
+            | (TransactionInputType::Utxo(..), Witness::Script(_)) => true,
+            _ => false,
+        };
+        if !expected {
+            return Err(Error::PartialWitnessTypeInvalid { index });
+        }
+
+        let block0 = self.genesis_block_hash.into_hash();
+        let sign_data_hash = self.staging.transaction_sign_data_hash()?;
+        let valid = match (&input.input, witness) {
+            (TransactionInputType::Account(account), Witness::Account(counter, signature)) => {
+                let data = WitnessAccountData::new(&block0, &sign_data_hash, *counter);
+                PublicKey::<Ed25519>::from_binary(account)
+                    .map(|key| signature.verify(&key, &data) == Verification::Success)
+                    .unwrap_or(false)
+            }
+            (_, Witness::Multisig(counter, witness)) => {
+                let data = WitnessMultisigData::new(&block0, &sign_data_hash, *counter);
+                witness.iter().all(|(owner, key, signature)| {
+                    multisig.map_or(false, |policy| policy.contains(owner))
+                        && signature.verify(key, &data) == Verification::Success
+                })
+            }
+            (_, Witness::OldUtxo(key, _, signature)) => {
+                let data =
+                    WitnessUtxoData::new(&block0, &sign_data_hash, WitnessUtxoVersion::Legacy);
+                signature.verify(key, &data) == Verification::Success
+            }
+            // neither the key of the spent output nor the script are known
+            _ => true,
+        };
+        if !valid {
+            return Err(Error::PartialWitnessSignatureInvalid { index });
+        }
+        Ok(())
+    }
+
+    fn summarize(&self) -> Result<Summary, Error> {
+        summarize(
+            &self.staging,
+            &self.witnesses,
+            &self.multisig,
+            &self.address_prefix,
+        )
+    }
+
+    /// the staging transaction with all its witnesses, ready to be sealed
+    pub fn into_staging(self) -> Result<Staging, Error> {
+        if !self.summary.missing_witnesses.is_empty() {
+            return Err(Error::PartialWitnessesMissing {
+                missing: self.summary.missing_witnesses,
+            });
+        }
+
+        let mut staging = self.staging;
+        for witness in self.witnesses.into_iter().flatten() {
+            staging.add_witness(witness.into())?;
+        }
+        Ok(staging)
+    }
+}
+
+fn summarize(
+    staging: &Staging,
+    witnesses: &[Option<TransactionWitness>],
+    multisig: &BTreeMap<usize, MultisigPolicy>,
+    address_prefix: &str,
+) -> Result<Summary, Error> {
+    let mut missing_witnesses = Vec::new();
+    let inputs = staging
+        .inputs()
+        .iter()
+        .zip(witnesses)
+        .enumerate()
+        .map(|(index, (input, witness))| {
+            let (kind, source) = match input.input {
+                TransactionInputType::Utxo(utxo_ptr, index) => {
+                    ("utxo", format!("{}:{}", Hash::from(utxo_ptr), index))
+                }
+                TransactionInputType::Account(account) => ("account", hex::encode(account)),
+            };
+            let witness: Option<&Witness> = witness.as_ref().map(AsRef::as_ref);
+            let (signers, incomplete) = match (witness, multisig.get(&index)) {
+                (Some(Witness::Multisig(_, witness)), Some(policy)) => policy.signers(witness),
+                _ => Default::default(),
+            };
+            let complete = match (witness, multisig.get(&index)) {
+                (None, _) => false,
+                (Some(_), Some(policy)) => {
+                    signers.len() >= policy.threshold && incomplete.is_empty()
+                }
+                (Some(_), None) => true,
+            };
+            if !complete {
+                missing_witnesses.push(index);
+            }
+            let witness = match (witness, multisig.get(&index)) {
+                (None, _) => "missing".to_owned(),
+                (Some(_), Some(policy)) if !incomplete.is_empty() => format!(
+                    "multisig, signed by {} of the {} required owners out of {}, owners {} signed below their own threshold",
+                    signers.len(),
+                    policy.threshold,
+                    policy.owners,
+                    incomplete
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                (Some(_), Some(policy)) => format!(
+                    "multisig, signed by {} of the {} required owners out of {}",
+                    signers.len(),
+                    policy.threshold,
+                    policy.owners
+                ),
+                (Some(_), None) => "signed".to_owned(),
+            };
+            InputSummary {
+                kind: kind.to_owned(),
+                source,
+                value: input.value.into(),
+                witness,
+            }
+        })
+        .collect();
+
+    let outputs = staging
+        .outputs()
+        .iter()
+        .map(|output| OutputSummary {
+            address: AddressReadable::from_address(address_prefix, output.address().as_ref())
+                .to_string(),
+            value: (*output.value()).into(),
+        })
+        .collect();
+
+    let total_input = staging.total_input()?;
+    let total_output = staging.total_output()?;
+
+    Ok(Summary {
+        data_for_witness: staging.transaction_sign_data_hash()?.to_string(),
+        valid_until: staging.valid_until(),
+        certificate: staging
+            .extra()
+            .map(|certificate| certificate_kind(&certificate.0).to_owned()),
+        inputs,
+        outputs,
+        total_input: total_input.0,
+        total_output: total_output.0,
+        fee: (total_input - total_output)?.0,
+        missing_witnesses,
+    })
+}
+
+fn certificate_kind(certificate: &Certificate) -> &'static str {
+    match certificate {
+        Certificate::StakeDelegation(_) => "stake-delegation",
+        Certificate::OwnerStakeDelegation(_) => "owner-stake-delegation",
+        Certificate::PoolRegistration(_) => "pool-registration",
+        Certificate::PoolRetirement(_) => "pool-retirement",
+        Certificate::PoolUpdate(_) => "pool-update",
+        Certificate::VotePlan(_) => "vote-plan",
+        Certificate::VoteCast(_) => "vote-cast",
+        Certificate::VoteTally(_) => "vote-tally",
+        Certificate::UpdateProposal(_) => "update-proposal",
+        Certificate::UpdateVote(_) => "update-vote",
+        Certificate::MintToken(_) => "mint-token",
+        Certificate::EvmMapping(_) => "evm-mapping",
+        Certificate::MultisigRegistration(_) => "multisig-registration",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jcli_lib::transaction::mk_witness::{
+        make_witness, multisig_tree_index, WitnessType,
+    };
+    use chain_impl_mockchain::{
+        account::SpendingCounter,
+        fee::LinearFee,
+        header::HeaderId,
+        key::EitherEd25519SecretKey,
+        transaction::{Input, OutputPolicy},
+        value::Value,
+    };
+    use rand_chacha::{rand_core::SeedableRng, ChaChaRng};
+
+    fn key(seed: u8) -> EitherEd25519SecretKey {
+        EitherEd25519SecretKey::generate(ChaChaRng::from_seed([seed; 32]))
+    }
+
+    /// a transaction spending a utxo and, if `account` is given, from this
+    /// account, else from a multisig account needing 2 of its 3 owners
+    fn partial_from(account: Option<[u8; 32]>) -> PartialTransaction {
+        let policy = MultisigPolicy {
+            threshold: 2,
+            owners: 3,
+            sub_declarations: BTreeMap::new(),
+        };
+        partial_with(account, policy)
+    }
+
+    /// a transaction spending a utxo and, if `account` is given, from this
+    /// account, else from a multisig account with the given policy
+    fn partial_with(account: Option<[u8; 32]>, policy: MultisigPolicy) -> PartialTransaction {
+        let mut staging = Staging::new();
+        staging
+            .add_input(Input::new(0, Value(100), [1; 32]).into())
+            .unwrap();
+        // the account index marks an account input
+        staging
+            .add_input(Input::new(0xff, Value(100), account.unwrap_or([2; 32])).into())
+            .unwrap();
+        staging.set_expiry_date(BlockDate::new(1, 0)).unwrap();
+        staging
+            .balance_inputs_outputs(&LinearFee::new(0, 0, 0), OutputPolicy::Forget)
+            .unwrap();
+        let mut multisig = BTreeMap::new();
+        if account.is_none() {
+            multisig.insert(1, policy);
+        }
+        PartialTransaction::new(staging, HeaderId::zero_hash(), multisig, "ca".to_owned()).unwrap()
+    }
+
+    fn partial() -> PartialTransaction {
+        partial_from(None)
+    }
+
+    fn witness(
+        partial: &PartialTransaction,
+        witness_type: WitnessType,
+        multisig_index: Option<u8>,
+        seed: u8,
+    ) -> Witness {
+        witness_for_block0(
+            partial,
+            &HeaderId::zero_hash(),
+            witness_type,
+            multisig_index,
+            seed,
+        )
+    }
+
+    /// the multisig witness of the owner `sub_index` of the owner `index`,
+    /// itself a multisig declaration
+    fn sub_witness(partial: &PartialTransaction, index: u8, sub_index: u8, seed: u8) -> Witness {
+        make_witness(
+            &WitnessType::Multisig,
+            &HeaderId::zero_hash(),
+            &partial.staging.transaction_sign_data_hash().unwrap(),
+            Some(SpendingCounter::zero()),
+            multisig_tree_index(Some(index), Some(sub_index)).unwrap(),
+            &key(seed),
+        )
+        .unwrap()
+    }
+
+    fn witness_for_block0(
+        partial: &PartialTransaction,
+        block0: &HeaderId,
+        witness_type: WitnessType,
+        multisig_index: Option<u8>,
+        seed: u8,
+    ) -> Witness {
+        make_witness(
+            &witness_type,
+            block0,
+            &partial.staging.transaction_sign_data_hash().unwrap(),
+            Some(SpendingCounter::zero()),
+            multisig_tree_index(multisig_index, None).unwrap(),
+            &key(seed),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn merge_witnesses_signed_separately() {
+        let mut first = partial();
+        let mut second = partial();
+        assert_eq!(first.summary.missing_witnesses, vec![0, 1]);
+
+        let utxo = witness(&first, WitnessType::UTxO, None, 1);
+        first.add_witness(0, utxo.clone()).unwrap();
+        first
+            .add_witness(1, witness(&first, WitnessType::Multisig, Some(0), 2))
+            .unwrap();
+        // the same owner signing twice counts once
+        first
+            .add_witness(1, witness(&first, WitnessType::Multisig, Some(0), 2))
+            .unwrap();
+        assert_eq!(first.summary.missing_witnesses, vec![1]);
+        assert_eq!(
+            first.summary.inputs[1].witness,
+            "multisig, signed by 1 of the 2 required owners out of 3"
+        );
+        second
+            .add_witness(1, witness(&second, WitnessType::Multisig, Some(1), 3))
+            .unwrap();
+
+        // the partially signed transactions are carried as JSON documents
+        let json = serde_json::to_vec(&second).unwrap();
+        let second: PartialTransaction = serde_json::from_slice(&json).unwrap();
+
+        for (index, witness) in second.witnesses.into_iter().enumerate() {
+            if let Some(witness) = witness {
+                first.add_witness(index, witness.into()).unwrap();
+            }
+        }
+        assert!(first.summary.missing_witnesses.is_empty());
+        assert_eq!(
+            first.summary.inputs[1].witness,
+            "multisig, signed by 2 of the 2 required owners out of 3"
+        );
+
+        // adding the same witness again is harmless
+        first.add_witness(0, utxo).unwrap();
+
+        let mut staging = first.into_staging().unwrap();
+        staging.seal().unwrap();
+    }
+
+    #[test]
+    fn reject_invalid_witnesses() {
+        let mut partial = partial();
+
+        let account = witness(&partial, WitnessType::Account, None, 1);
+        assert!(matches!(
+            partial.add_witness(0, account),
+            Err(Error::PartialWitnessTypeInvalid { index: 0 })
+        ));
+
+        partial
+            .add_witness(0, witness(&partial, WitnessType::UTxO, None, 1))
+            .unwrap();
+        let other = witness(&partial, WitnessType::UTxO, None, 2);
+        assert!(matches!(
+            partial.add_witness(0, other),
+            Err(Error::PartialWitnessConflict { index: 0 })
+        ));
+
+        let account = witness(&partial, WitnessType::Account, None, 1);
+        assert!(matches!(
+            partial.add_witness(1, account),
+            Err(Error::PartialWitnessTypeInvalid { index: 1 })
+        ));
+
+        assert!(matches!(
+            partial.into_staging(),
+            Err(Error::PartialWitnessesMissing { .. })
+        ));
+    }
+
+    #[test]
+    fn reject_witnesses_with_invalid_signatures() {
+        let mut partial = partial();
+
+        let other_block0 = HeaderId::hash_bytes(b"another blockchain");
+        let utxo = witness_for_block0(&partial, &other_block0, WitnessType::OldUTxO, None, 1);
+        assert!(matches!(
+            partial.add_witness(0, utxo),
+            Err(Error::PartialWitnessSignatureInvalid { index: 0 })
+        ));
+        partial
+            .add_witness(0, witness(&partial, WitnessType::OldUTxO, None, 1))
+            .unwrap();
+
+        let multisig =
+            witness_for_block0(&partial, &other_block0, WitnessType::Multisig, Some(0), 2);
+        assert!(matches!(
+            partial.add_witness(1, multisig),
+            Err(Error::PartialWitnessSignatureInvalid { index: 1 })
+        ));
+        // the multisig declaration has 3 owners
+        let multisig = witness(&partial, WitnessType::Multisig, Some(3), 2);
+        assert!(matches!(
+            partial.add_witness(1, multisig),
+            Err(Error::PartialWitnessSignatureInvalid { index: 1 })
+        ));
+        assert_eq!(partial.summary.missing_witnesses, vec![1]);
+    }
+
+    #[test]
+    fn check_account_witnesses_against_the_account() {
+        let account: [u8; 32] = key(1).to_public().as_ref().try_into().unwrap();
+        let mut partial = partial_from(Some(account));
+
+        let multisig = witness(&partial, WitnessType::Multisig, Some(0), 1);
+        assert!(matches!(
+            partial.add_witness(1, multisig),
+            Err(Error::PartialWitnessTypeInvalid { index: 1 })
+        ));
+        let other = witness(&partial, WitnessType::Account, None, 2);
+        assert!(matches!(
+            partial.add_witness(1, other),
+            Err(Error::PartialWitnessSignatureInvalid { index: 1 })
+        ));
+        partial
+            .add_witness(1, witness(&partial, WitnessType::Account, None, 1))
+            .unwrap();
+        assert_eq!(partial.summary.missing_witnesses, vec![0]);
+    }
+
+    #[test]
+    fn parse_multisig_inputs() {
+        assert_eq!(
+            "1:2:3".parse::<MultisigInput>().unwrap(),
+            MultisigInput {
+                input: 1,
+                policy: MultisigPolicy {
+                    threshold: 2,
+                    owners: 3,
+                    sub_declarations: BTreeMap::new(),
+                }
+            }
+        );
+        assert!("1:2".parse::<MultisigInput>().is_err());
+        assert!("1:0:3".parse::<MultisigInput>().is_err());
+        assert!("1:4:3".parse::<MultisigInput>().is_err());
+        assert!("1:2:9".parse::<MultisigInput>().is_err());
+
+        assert_eq!(
+            "1:2:3:0=1/2,2=2/3".parse::<MultisigInput>().unwrap(),
+            MultisigInput {
+                input: 1,
+                policy: MultisigPolicy {
+                    threshold: 2,
+                    owners: 3,
+                    sub_declarations: [
+                        (
+                            0,
+                            SubDeclarationPolicy {
+                                threshold: 1,
+                                owners: 2
+                            }
+                        ),
+                        (
+                            2,
+                            SubDeclarationPolicy {
+                                threshold: 2,
+                                owners: 3
+                            }
+                        ),
+                    ]
+                    .into_iter()
+                    .collect(),
+                }
+            }
+        );
+        assert!("1:2:3:".parse::<MultisigInput>().is_err());
+        assert!("1:2:3:0=2".parse::<MultisigInput>().is_err());
+        assert!("1:2:3:3=1/2".parse::<MultisigInput>().is_err());
+        assert!("1:2:3:0=3/2".parse::<MultisigInput>().is_err());
+        assert!("1:2:3:0=1/2,0=2/2".parse::<MultisigInput>().is_err());
+        assert!("1:2:3:0=1/2:1=1/2".parse::<MultisigInput>().is_err());
+    }
+
+    #[test]
+    fn nested_declarations_count_once_their_threshold_is_met() {
+        // the owner 1 is a multisig declaration needing both its owners
+        let policy = MultisigPolicy {
+            threshold: 2,
+            owners: 3,
+            sub_declarations: [(
+                1,
+                SubDeclarationPolicy {
+                    threshold: 2,
+                    owners: 2,
+                },
+            )]
+            .into_iter()
+            .collect(),
+        };
+        let mut partial = partial_with(None, policy);
+        partial
+            .add_witness(0, witness(&partial, WitnessType::UTxO, None, 1))
+            .unwrap();
+
+        // the nested declaration signs through its own owners only
+        let multisig = witness(&partial, WitnessType::Multisig, Some(1), 2);
+        assert!(matches!(
+            partial.add_witness(1, multisig),
+            Err(Error::PartialWitnessSignatureInvalid { index: 1 })
+        ));
+        let multisig = sub_witness(&partial, 0, 0, 2);
+        assert!(matches!(
+            partial.add_witness(1, multisig),
+            Err(Error::PartialWitnessSignatureInvalid { index: 1 })
+        ));
+        let multisig = sub_witness(&partial, 1, 2, 2);
+        assert!(matches!(
+            partial.add_witness(1, multisig),
+            Err(Error::PartialWitnessSignatureInvalid { index: 1 })
+        ));
+
+        partial
+            .add_witness(1, witness(&partial, WitnessType::Multisig, Some(0), 2))
+            .unwrap();
+        partial
+            .add_witness(1, sub_witness(&partial, 1, 0, 3))
+            .unwrap();
+        assert_eq!(partial.summary.missing_witnesses, vec![1]);
+        assert_eq!(
+            partial.summary.inputs[1].witness,
+            "multisig, signed by 1 of the 2 required owners out of 3, owners 1 signed below their own threshold"
+        );
+
+        // the ledger rejects a nested declaration below its threshold even
+        // when enough other owners signed
+        partial
+            .add_witness(1, witness(&partial, WitnessType::Multisig, Some(2), 4))
+            .unwrap();
+        assert_eq!(partial.summary.missing_witnesses, vec![1]);
+        assert_eq!(
+            partial.summary.inputs[1].witness,
+            "multisig, signed by 2 of the 2 required owners out of 3, owners 1 signed below their own threshold"
+        );
+
+        partial
+            .add_witness(1, sub_witness(&partial, 1, 1, 5))
+            .unwrap();
+        assert!(partial.summary.missing_witnesses.is_empty());
+        assert_eq!(
+            partial.summary.inputs[1].witness,
+            "multisig, signed by 3 of the 2 required owners out of 3"
+        );
+
+        let mut staging = partial.into_staging().unwrap();
+        staging.seal().unwrap();
+    }
+}
@@ -212,6 +212,7 @@ pub fn make_transaction(
         &block0_hash,
         &transaction_sign_data_hash,
         Some(SpendingCounter::from(account_state.counters()[0])),
+        None,
         &secret_key,
     )?;
 
@@ -288,6 +288,7 @@ impl Staging {
                     self.extra_authed = Some(sc.into())
                 }
                 Certificate::MintToken(_) => unreachable!(),
+                Certificate::MultisigRegistration(_) => unreachable!(),
             },
         };
         self.kind = StagingKind::Authed;
@@ -323,6 +324,23 @@ impl Staging {
         self.witnesses.len()
     }
 
+    /// remove the witnesses added so far, in the order of the inputs
+    pub fn take_witnesses(&mut self) -> Vec<interfaces::TransactionWitness> {
+        std::mem::take(&mut self.witnesses)
+    }
+
+    pub fn kind(&self) -> StagingKind {
+        self.kind
+    }
+
+    pub fn valid_until(&self) -> Option<interfaces::BlockDate> {
+        self.valid_until
+    }
+
+    pub fn extra(&self) -> Option<&interfaces::Certificate> {
+        self.extra.as_ref()
+    }
+
     pub fn staging_kind_name(&self) -> String {
         self.kind.to_string()
     }
@@ -411,6 +429,9 @@ impl Staging {
                 Certificate::EvmMapping(vt) => {
                     self.finalize_payload(&vt, fee_algorithm, output_policy)
                 }
+                Certificate::MultisigRegistration(vt) => {
+                    self.finalize_payload(&vt, fee_algorithm, output_policy)
+                }
 
                 Certificate::OwnerStakeDelegation(c) => {
                     let balance = self.finalize_payload(&c, fee_algorithm, output_policy)?;
@@ -521,6 +542,9 @@ impl Staging {
                         Certificate::MintToken(mint_token) => {
                             self.make_fragment(&mint_token, &(), Fragment::MintToken)
                         }
+                        Certificate::MultisigRegistration(registration) => {
+                            self.make_fragment(&registration, &(), Fragment::MultisigRegistration)
+                        }
                         _ => unreachable!(),
                     },
                 }
@@ -627,6 +651,9 @@ impl Staging {
                 Certificate::EvmMapping(vt) => {
                     self.transaction_sign_data_hash_on(TxBuilder::new().set_payload(&vt))
                 }
+                Certificate::MultisigRegistration(vt) => {
+                    self.transaction_sign_data_hash_on(TxBuilder::new().set_payload(&vt))
+                }
             },
         };
 
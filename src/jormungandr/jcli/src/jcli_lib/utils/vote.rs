@@ -71,6 +71,10 @@ pub enum SharesError {
     InsufficientShares,
     #[error("invalid binary share data")]
     InvalidBinaryShare,
+    #[error("the decryption key does not belong to a member of the vote plan committee")]
+    MemberNotInCommittee,
+    #[error("more than one set of shares for the committee member {0}")]
+    DuplicateMember(u8),
     #[error("decryption share is not valid")]
     ValidationFailed(#[from] chain_vote::tally::DecryptionError),
 }
@@ -80,12 +84,21 @@ pub struct TallyDecryptShare(#[serde(with = "serde_base64_bytes")] Vec<u8>);
 
 // Set of shares (belonging to a single committee member) for the decryption of a vote plan
 #[derive(Debug, Serialize, Deserialize)]
-pub struct MemberVotePlanShares(Vec<TallyDecryptShare>);
+pub struct MemberVotePlanShares {
+    // index of the member in the vote plan committee
+    member_index: u8,
+    shares: Vec<TallyDecryptShare>,
+}
 
 // Set of decrypt shares (belonging to different committee members)
 // that decrypts a vote plan
 #[derive(Debug, Serialize, Deserialize)]
-pub struct VotePlanDecryptShares(Vec<Vec<TallyDecryptShare>>);
+pub struct VotePlanDecryptShares {
+    // index in the vote plan committee of the member who produced each of
+    // the shares of a proposal
+    member_indices: Vec<u8>,
+    shares: Vec<Vec<TallyDecryptShare>>,
+}
 
 impl TryFrom<TallyDecryptShare> for chain_vote::TallyDecryptShare {
     type Error = SharesError;
@@ -95,35 +108,52 @@ impl TryFrom<TallyDecryptShare> for chain_vote::TallyDecryptShare {
     }
 }
 
-impl From<Vec<chain_vote::TallyDecryptShare>> for MemberVotePlanShares {
-    fn from(shares: Vec<chain_vote::TallyDecryptShare>) -> Self {
-        Self(
-            shares
+impl MemberVotePlanShares {
+    pub fn new(member_index: u8, shares: Vec<chain_vote::TallyDecryptShare>) -> Self {
+        Self {
+            member_index,
+            shares: shares
                 .into_iter()
                 .map(|s| TallyDecryptShare(s.to_bytes()))
                 .collect::<Vec<_>>(),
-        )
+        }
     }
 }
 
 impl TryFrom<Vec<MemberVotePlanShares>> for VotePlanDecryptShares {
     type Error = SharesError;
-    fn try_from(shares: Vec<MemberVotePlanShares>) -> Result<Self, Self::Error> {
-        let shares = shares.into_iter().map(|s| s.0).collect::<Vec<_>>();
+    fn try_from(mut shares: Vec<MemberVotePlanShares>) -> Result<Self, Self::Error> {
         if shares.is_empty() {
             return Err(SharesError::Empty);
         }
-        let mut res = vec![Vec::new(); shares[0].len()];
+        // without a threshold the shares of all the members are needed in
+        // the committee order
+        shares.sort_by_key(|member_shares| member_shares.member_index);
+        let mut member_indices = Vec::with_capacity(shares.len());
+        let mut res = vec![Vec::new(); shares[0].shares.len()];
         // transponse 2d array
         for member_shares in shares {
-            if member_shares.len() != res.len() {
+            if member_indices.last() == Some(&member_shares.member_index) {
+                return Err(SharesError::DuplicateMember(member_shares.member_index));
+            }
+            member_indices.push(member_shares.member_index);
+            if member_shares.shares.len() != res.len() {
                 return Err(SharesError::ProposalSharesNotBalanced);
             }
-            for (i, share) in member_shares.into_iter().enumerate() {
+            for (i, share) in member_shares.shares.into_iter().enumerate() {
                 res[i].push(share);
             }
         }
-        Ok(VotePlanDecryptShares(res))
+        Ok(VotePlanDecryptShares {
+            member_indices,
+            shares: res,
+        })
+    }
+}
+
+impl VotePlanDecryptShares {
+    pub fn member_indices(&self) -> &[u8] {
+        &self.member_indices
     }
 }
 
@@ -131,7 +161,7 @@ impl TryFrom<VotePlanDecryptShares> for Vec<Vec<chain_vote::TallyDecryptShare>>
     type Error = SharesError;
     fn try_from(vote_plan: VotePlanDecryptShares) -> Result<Self, Self::Error> {
         vote_plan
-            .0
+            .shares
             .into_iter()
             .map(|v| {
                 v.into_iter()
@@ -149,7 +179,8 @@ pub fn read_vote_plan_shares_from_file<P: AsRef<Path>>(
 ) -> Result<VotePlanDecryptShares, SharesError> {
     let vote_plan_shares: VotePlanDecryptShares =
         serde_json::from_reader(io::open_file_read(&share_path)?)?;
-    if vote_plan_shares.0.len() != proposals || vote_plan_shares.0[0].len() < threshold.unwrap_or(1)
+    if vote_plan_shares.shares.len() != proposals
+        || vote_plan_shares.member_indices.len() < threshold.unwrap_or(1)
     {
         return Err(SharesError::InsufficientShares);
     }
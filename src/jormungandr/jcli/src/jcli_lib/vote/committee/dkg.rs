@@ -0,0 +1,382 @@
+use crate::jcli_lib::{
+    utils::{io, OutputFormat},
+    vote::{Error, OutputFile, Seed},
+};
+use chain_crypto::bech32::Bech32;
+use chain_vote::{
+    committee::{MemberCommunicationKey, MemberCommunicationPublicKey},
+    dkg::{
+        self, BroadcastPhase1, BroadcastPhase2, BroadcastPhase3, BroadcastPhase4, BroadcastPhase5,
+        Environment, MemberPhase1, MemberPhase3, MemberPhase4, MemberPhase5,
+    },
+};
+use clap::Parser;
+use rand::rngs::OsRng;
+use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+use serde::Serialize;
+use std::{io::Write, path::PathBuf, str::FromStr};
+
+/// Parameters of the key generation, identical for all the committee members
+#[derive(Parser)]
+pub struct Parameters {
+    /// threshold number of the committee members sufficient for
+    /// decrypting the tally
+    #[clap(long, short, name = "THRESHOLD", value_parser = usize::from_str)]
+    threshold: usize,
+
+    /// the common reference string
+    #[clap(long, name = "Crs")]
+    crs: String,
+
+    /// communication keys of all committee members, in the order of their
+    /// member index
+    #[clap(long, short, name = "COMMUNICATION_KEYS",
+        value_parser = MemberCommunicationPublicKey::try_from_bech32_str,
+        required = true,
+    )]
+    keys: Vec<MemberCommunicationPublicKey>,
+}
+
+/// Secret state of the committee member running the key generation
+#[derive(Parser)]
+pub struct Member {
+    /// the file with the secret state written by `round1`
+    #[clap(long)]
+    secret: PathBuf,
+
+    /// the file with the member's bech32-encoded communication key
+    #[clap(long)]
+    communication_key: PathBuf,
+}
+
+/// Files with the hex-encoded messages broadcasted by all the committee
+/// members at each round
+#[derive(Parser)]
+#[clap(rename_all = "kebab-case")]
+pub struct Broadcasts {
+    /// the messages of the first round
+    #[clap(long = "round1")]
+    round1: Vec<PathBuf>,
+    /// the messages of the second round
+    #[clap(long = "round2")]
+    round2: Vec<PathBuf>,
+    /// the messages of the third round
+    #[clap(long = "round3")]
+    round3: Vec<PathBuf>,
+    /// the messages of the fourth round
+    #[clap(long = "round4")]
+    round4: Vec<PathBuf>,
+    /// the messages of the fifth round
+    #[clap(long = "round5")]
+    round5: Vec<PathBuf>,
+}
+
+/// Deal the shares of the member's secret to the committee. The secret
+/// state of the member is written to the `--secret` file, and must be kept
+/// until the end of the key generation; the message to broadcast is written
+/// to the output, in hexadecimal.
+#[derive(Parser)]
+pub struct Round1 {
+    #[clap(flatten)]
+    parameters: Parameters,
+
+    /// index of the committee member running the key generation
+    #[clap(long, short, name = "INDEX", value_parser = usize::from_str)]
+    index: usize,
+
+    /// optional seed to generate the secret, for the same entropy the same
+    /// secret will be generated (32 bytes in hexadecimal). This seed will be
+    /// fed to ChaChaRNG and allow pseudo random key generation. Do not use
+    /// if you are not sure.
+    #[clap(long = "seed", short = 's', name = "SEED", value_parser = Seed::from_str)]
+    seed: Option<Seed>,
+
+    /// the file to write the secret state of the member to
+    #[clap(long)]
+    secret: PathBuf,
+
+    #[clap(flatten)]
+    output_file: OutputFile,
+}
+
+/// Run a round of the key generation from the secret state of the member
+/// and the messages of the previous rounds. The message to broadcast is
+/// written to the output, in hexadecimal.
+#[derive(Parser)]
+pub struct Round {
+    #[clap(flatten)]
+    parameters: Parameters,
+
+    #[clap(flatten)]
+    member: Member,
+
+    #[clap(flatten)]
+    broadcasts: Broadcasts,
+
+    #[clap(flatten)]
+    output_file: OutputFile,
+}
+
+/// Compute the member's bech32-encoded secret key once all the rounds
+/// are completed.
+#[derive(Parser)]
+pub struct Finalize {
+    #[clap(flatten)]
+    parameters: Parameters,
+
+    #[clap(flatten)]
+    member: Member,
+
+    #[clap(flatten)]
+    broadcasts: Broadcasts,
+
+    #[clap(flatten)]
+    output_file: OutputFile,
+}
+
+/// Compute the public outcome of the key generation from the messages of
+/// all the rounds: the election public key, and the public keys of the
+/// members used to verify their tally decryption shares.
+#[derive(Parser)]
+pub struct Outcome {
+    #[clap(flatten)]
+    parameters: Parameters,
+
+    #[clap(flatten)]
+    broadcasts: Broadcasts,
+
+    #[clap(flatten)]
+    output_format: OutputFormat,
+}
+
+#[derive(Parser)]
+#[clap(rename_all = "kebab-case")]
+pub enum Dkg {
+    /// deal the shares of the member's secret (first round)
+    Round1(Round1),
+    /// complain against the invalid shares received (second round)
+    Round2(Round),
+    /// publish the commitments to the dealt secret (third round)
+    Round3(Round),
+    /// reveal the shares not matching the commitments (fourth round)
+    Round4(Round),
+    /// reveal the shares of the dealers to reconstruct (fifth round)
+    Round5(Round),
+    /// compute the member's secret key
+    Finalize(Finalize),
+    /// compute the election public key and the members' public keys
+    Outcome(Outcome),
+}
+
+#[derive(Serialize)]
+struct CommitteeOutcome {
+    threshold: usize,
+    qualified_members: Vec<usize>,
+    election_public_key: String,
+    member_public_keys: Vec<String>,
+}
+
+impl Parameters {
+    fn environment(&self) -> Result<Environment, Error> {
+        let crs = chain_vote::Crs::from_hash(self.crs.as_bytes());
+        Environment::new(self.threshold, self.keys.len(), &crs).map_err(Into::into)
+    }
+}
+
+fn read_broadcasts<T>(
+    paths: &[PathBuf],
+    from_bytes: impl Fn(&[u8]) -> Option<T>,
+) -> Result<Vec<T>, Error> {
+    paths
+        .iter()
+        .map(|path| {
+            let bytes = hex::decode(io::read_line(&Some(path))?)?;
+            from_bytes(&bytes).ok_or_else(|| Error::InvalidDkgBroadcast { path: path.clone() })
+        })
+        .collect()
+}
+
+struct Messages {
+    round1: Vec<BroadcastPhase1>,
+    round2: Vec<BroadcastPhase2>,
+    round3: Vec<BroadcastPhase3>,
+    round4: Vec<BroadcastPhase4>,
+    round5: Vec<BroadcastPhase5>,
+}
+
+impl Broadcasts {
+    fn read(&self) -> Result<Messages, Error> {
+        Ok(Messages {
+            round1: read_broadcasts(&self.round1, BroadcastPhase1::from_bytes)?,
+            round2: read_broadcasts(&self.round2, BroadcastPhase2::from_bytes)?,
+            round3: read_broadcasts(&self.round3, BroadcastPhase3::from_bytes)?,
+            round4: read_broadcasts(&self.round4, BroadcastPhase4::from_bytes)?,
+            round5: read_broadcasts(&self.round5, BroadcastPhase5::from_bytes)?,
+        })
+    }
+}
+
+impl Member {
+    fn read(
+        &self,
+        parameters: &Parameters,
+    ) -> Result<(MemberPhase1, MemberCommunicationKey), Error> {
+        let bytes = hex::decode(io::read_line(&Some(&self.secret))?)?;
+        let state = MemberPhase1::from_bytes(&bytes).ok_or_else(|| Error::InvalidDkgState {
+            path: self.secret.clone(),
+        })?;
+        if state.environment() != &parameters.environment()? {
+            return Err(Error::InvalidDkgState {
+                path: self.secret.clone(),
+            });
+        }
+        let line = io::read_line(&Some(&self.communication_key))?;
+        let communication_key = MemberCommunicationKey::try_from_bech32_str(&line)?;
+        Ok((state, communication_key))
+    }
+
+    // replay the rounds up to the third one. The complaints of the second
+    // round were already published, so the randomness of their proofs does
+    // not matter here.
+    fn replay_to_phase_3(
+        &self,
+        parameters: &Parameters,
+        messages: &Messages,
+    ) -> Result<(MemberPhase3, BroadcastPhase3), Error> {
+        let (state, communication_key) = self.read(parameters)?;
+        let (state, _) = state.to_phase_2(&mut OsRng, &communication_key, &messages.round1);
+        state
+            .to_phase_3(&parameters.keys, &messages.round1, &messages.round2)
+            .map_err(Into::into)
+    }
+
+    fn replay_to_phase_4(
+        &self,
+        parameters: &Parameters,
+        messages: &Messages,
+    ) -> Result<(MemberPhase4, BroadcastPhase4), Error> {
+        let (state, _) = self.replay_to_phase_3(parameters, messages)?;
+        Ok(state.to_phase_4(&messages.round3))
+    }
+
+    fn replay_to_phase_5(
+        &self,
+        parameters: &Parameters,
+        messages: &Messages,
+    ) -> Result<(MemberPhase5, BroadcastPhase5), Error> {
+        let (state, _) = self.replay_to_phase_4(parameters, messages)?;
+        Ok(state.to_phase_5(&messages.round1, &messages.round3, &messages.round4))
+    }
+}
+
+impl Round1 {
+    fn exec(self) -> Result<(), Error> {
+        let mut rng = match self.seed {
+            Some(seed) => ChaCha20Rng::from_seed(seed.0),
+            None => ChaCha20Rng::from_rng(OsRng)?,
+        };
+        let environment = self.parameters.environment()?;
+        let (state, broadcast) =
+            MemberPhase1::init(&mut rng, &environment, &self.parameters.keys, self.index)?;
+
+        let mut secret = io::open_file_write(&Some(&self.secret))?;
+        writeln!(secret, "{}", hex::encode(state.to_bytes()))?;
+
+        let mut output = self.output_file.open()?;
+        writeln!(output, "{}", hex::encode(broadcast.to_bytes()))?;
+        Ok(())
+    }
+}
+
+impl Round {
+    fn exec(self, round: usize) -> Result<(), Error> {
+        let messages = self.broadcasts.read()?;
+        let parameters = &self.parameters;
+        let bytes = match round {
+            2 => {
+                let (state, communication_key) = self.member.read(parameters)?;
+                let (_, broadcast) =
+                    state.to_phase_2(&mut OsRng, &communication_key, &messages.round1);
+                broadcast.to_bytes()
+            }
+            3 => self
+                .member
+                .replay_to_phase_3(parameters, &messages)?
+                .1
+                .to_bytes(),
+            4 => self
+                .member
+                .replay_to_phase_4(parameters, &messages)?
+                .1
+                .to_bytes(),
+            _ => self
+                .member
+                .replay_to_phase_5(parameters, &messages)?
+                .1
+                .to_bytes(),
+        };
+
+        let mut output = self.output_file.open()?;
+        writeln!(output, "{}", hex::encode(bytes))?;
+        Ok(())
+    }
+}
+
+impl Finalize {
+    fn exec(self) -> Result<(), Error> {
+        let messages = self.broadcasts.read()?;
+        let (state, _) = self.member.replay_to_phase_5(&self.parameters, &messages)?;
+        let (secret_key, _) =
+            state.finalize(&messages.round1, &messages.round3, &messages.round5)?;
+
+        let mut output = self.output_file.open()?;
+        writeln!(output, "{}", secret_key.to_bech32_str())?;
+        Ok(())
+    }
+}
+
+impl Outcome {
+    fn exec(self) -> Result<(), Error> {
+        let messages = self.broadcasts.read()?;
+        let environment = self.parameters.environment()?;
+        let keys = dkg::committee_keys(
+            &environment,
+            &self.parameters.keys,
+            &messages.round1,
+            &messages.round2,
+            &messages.round3,
+            &messages.round4,
+            &messages.round5,
+        )?;
+
+        let outcome = CommitteeOutcome {
+            threshold: keys.threshold(),
+            qualified_members: keys.qualified_members().to_vec(),
+            election_public_key: keys.election_public_key().to_bech32_str(),
+            member_public_keys: keys
+                .member_public_keys()
+                .iter()
+                .map(Bech32::to_bech32_str)
+                .collect(),
+        };
+        let output = self
+            .output_format
+            .format_json(serde_json::to_value(outcome)?)?;
+        println!("{}", output);
+        Ok(())
+    }
+}
+
+impl Dkg {
+    pub fn exec(self) -> Result<(), Error> {
+        match self {
+            Dkg::Round1(args) => args.exec(),
+            Dkg::Round2(args) => args.exec(2),
+            Dkg::Round3(args) => args.exec(3),
+            Dkg::Round4(args) => args.exec(4),
+            Dkg::Round5(args) => args.exec(5),
+            Dkg::Finalize(args) => args.exec(),
+            Dkg::Outcome(args) => args.exec(),
+        }
+    }
+}
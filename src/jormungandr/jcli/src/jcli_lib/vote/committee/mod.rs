@@ -1,4 +1,5 @@
 mod communication_key;
+mod dkg;
 mod member_key;
 
 use super::Error;
@@ -13,6 +14,10 @@ pub enum Committee {
     /// commands for managing committee member stake keys
     #[clap(subcommand)]
     MemberKey(member_key::MemberKey),
+    /// commands for generating the committee keys with the distributed
+    /// key generation protocol
+    #[clap(subcommand)]
+    Dkg(dkg::Dkg),
 }
 
 impl Committee {
@@ -20,6 +25,7 @@ impl Committee {
         match self {
             Committee::CommunicationKey(args) => args.exec(),
             Committee::MemberKey(args) => args.exec(),
+            Committee::Dkg(args) => args.exec(),
         }
     }
 }
@@ -15,14 +15,33 @@ pub struct ElectionPublicKey {
     )]
     member_keys: Vec<chain_vote::committee::MemberPublicKey>,
 
+    /// Number of committee members needed to decrypt the tally, if the keys
+    /// were generated with the distributed key generation. The member keys
+    /// must then be given in the order of the members' indices.
+    #[clap(long)]
+    threshold: Option<usize>,
+
     #[clap(flatten)]
     output_file: OutputFile,
 }
 
 impl ElectionPublicKey {
     pub fn exec(&self) -> Result<(), Error> {
-        let election_public_key =
-            chain_vote::ElectionPublicKey::from_participants(&self.member_keys);
+        let election_public_key = match self.threshold {
+            Some(threshold) => {
+                if threshold == 0 || threshold > self.member_keys.len() {
+                    return Err(Error::InvalidThreshold {
+                        threshold,
+                        committee_members: self.member_keys.len(),
+                    });
+                }
+                chain_vote::ElectionPublicKey::from_threshold_participants(
+                    &self.member_keys,
+                    threshold,
+                )
+            }
+            None => chain_vote::ElectionPublicKey::from_participants(&self.member_keys),
+        };
 
         let mut output = self.output_file.open()?;
         writeln!(output, "{}", election_public_key.to_bech32_str()).map_err(Error::from)
@@ -87,6 +87,12 @@ pub enum Error {
     },
     #[error(transparent)]
     MergeError(#[from] tally::merge_results::Error),
+    #[error(transparent)]
+    Dkg(#[from] chain_vote::dkg::DkgError),
+    #[error("invalid key generation state in '{path}'")]
+    InvalidDkgState { path: PathBuf },
+    #[error("invalid key generation message in '{path}'")]
+    InvalidDkgBroadcast { path: PathBuf },
 }
 
 #[derive(Parser)]
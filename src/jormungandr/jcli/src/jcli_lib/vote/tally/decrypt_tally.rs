@@ -46,14 +46,19 @@ impl TallyVotePlanWithAllShares {
     pub fn exec(&self) -> Result<(), Error> {
         let mut vote_plan =
             vote::get_vote_plan_by_id(self.vote_plan.as_ref(), self.vote_plan_id.as_ref())?;
-        let shares: Vec<Vec<chain_vote::TallyDecryptShare>> =
-            vote::read_vote_plan_shares_from_file(
-                self.shares.as_ref(),
-                vote_plan.proposals.len(),
-                Some(self.threshold),
-            )?
-            .try_into()?;
+        let shares = vote::read_vote_plan_shares_from_file(
+            self.shares.as_ref(),
+            vote_plan.proposals.len(),
+            Some(self.threshold),
+        )?;
+        let member_indices = shares
+            .member_indices()
+            .iter()
+            .map(|index| *index as usize)
+            .collect::<Vec<_>>();
+        let shares: Vec<Vec<chain_vote::TallyDecryptShare>> = shares.try_into()?;
         let committee_member_keys = vote_plan.committee_member_keys.clone();
+        let committee_threshold = vote_plan.committee_threshold;
 
         let validated_tallies = (&vote_plan.proposals)
             .into_par_iter()
@@ -72,10 +77,21 @@ impl TallyVotePlanWithAllShares {
                 let encrypted_tally = EncryptedTally::from_bytes(encrypted_tally.as_ref())
                     .ok_or(Error::EncryptedTallyRead)?;
 
-                encrypted_tally
-                    .validate_partial_decryptions(&committee_member_keys, &shares)
-                    .map_err(SharesError::ValidationFailed)
-                    .map_err(Error::SharesError)
+                match committee_threshold {
+                    Some(threshold) => encrypted_tally.validate_threshold_partial_decryptions(
+                        &committee_member_keys,
+                        threshold as usize,
+                        &member_indices
+                            .iter()
+                            .copied()
+                            .zip(shares)
+                            .collect::<Vec<_>>(),
+                    ),
+                    None => encrypted_tally
+                        .validate_partial_decryptions(&committee_member_keys, &shares),
+                }
+                .map_err(SharesError::ValidationFailed)
+                .map_err(Error::SharesError)
             })
             .collect::<Result<Vec<_>, _>>()?;
 
@@ -1,7 +1,7 @@
 use super::Error;
 use crate::jcli_lib::utils::{
     io,
-    vote::{self, MemberVotePlanShares, VotePlanDecryptShares},
+    vote::{self, MemberVotePlanShares, SharesError, VotePlanDecryptShares},
 };
 use chain_crypto::bech32::Bech32;
 use chain_vote::tally::{EncryptedTally, OpeningVoteKey};
@@ -50,6 +50,11 @@ impl TallyGenerateVotePlanDecryptionShares {
             vote::get_vote_plan_by_id(self.vote_plan.as_ref(), self.vote_plan_id.as_ref())?;
         let line = io::read_line(&Some(&self.key))?;
         let decryption_key = OpeningVoteKey::try_from_bech32_str(&line)?;
+        let member_index = vote_plan
+            .committee_member_keys
+            .iter()
+            .position(|pk| *pk == decryption_key.to_public())
+            .ok_or(SharesError::MemberNotInCommittee)?;
 
         let shares = vote_plan
             .proposals
@@ -70,7 +75,7 @@ impl TallyGenerateVotePlanDecryptionShares {
             .collect::<Vec<_>>();
         println!(
             "{}",
-            serde_json::to_value(MemberVotePlanShares::from(shares))?
+            serde_json::to_value(MemberVotePlanShares::new(member_index as u8, shares))?
         );
         Ok(())
     }
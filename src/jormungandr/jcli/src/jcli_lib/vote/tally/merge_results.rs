@@ -125,13 +125,30 @@ fn merge_voteplans(voteplans: Vec<VotePlanStatus>) -> Result<Vec<MergedVotePlan>
                                 result: result1.merge(result2),
                             },
                         },
-                        (Tally::Public { result: _ }, Tally::Private { state: _ })
-                        | (Tally::Private { state: _ }, Tally::Public { result: _ }) => {
-                            return Err(Error::PrivacyMismatch);
-                        }
+                        (
+                            Tally::Approval { result: result1 },
+                            Tally::Approval { result: result2 },
+                        ) => Tally::Approval {
+                            result: result1.merge(result2),
+                        },
+                        (
+                            Tally::Quadratic { result: result1 },
+                            Tally::Quadratic { result: result2 },
+                        ) => Tally::Quadratic {
+                            result: result1.merge(result2),
+                        },
+                        (
+                            Tally::RankedChoice { result: result1 },
+                            Tally::RankedChoice { result: result2 },
+                        ) => Tally::RankedChoice {
+                            result: result1.merge(result2),
+                        },
                         (Tally::Private { state: _ }, Tally::Private { state: _ }) => {
                             return Err(Error::VotePlanEncrypted)
                         }
+                        _ => {
+                            return Err(Error::PrivacyMismatch);
+                        }
                     };
                 }
             }
@@ -165,6 +182,7 @@ mod tests {
             vote_end: BlockDate::new(0, 1),
             committee_end: BlockDate::new(0, 2),
             committee_member_keys: vec![],
+            committee_threshold: None,
             proposals: vec![VoteProposalStatus {
                 index: 0,
                 proposal_id,
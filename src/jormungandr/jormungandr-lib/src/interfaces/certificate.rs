@@ -116,6 +116,10 @@ impl property::Serialize for Certificate {
                 codec.put_bytes(&[12])?;
                 codec.put_bytes(c.serialize().as_slice())?;
             }
+            certificate::Certificate::MultisigRegistration(c) => {
+                codec.put_bytes(&[13])?;
+                codec.put_bytes(c.serialize().as_slice())?;
+            }
         };
         Ok(())
     }
@@ -178,6 +182,12 @@ impl property::DeserializeFromSlice for Certificate {
                 let cert = certificate::EvmMapping::deserialize_from_slice(codec)?;
                 Ok(Certificate(certificate::Certificate::EvmMapping(cert)))
             }
+            13 => {
+                let cert = certificate::MultisigRegistration::deserialize_from_slice(codec)?;
+                Ok(Certificate(certificate::Certificate::MultisigRegistration(
+                    cert,
+                )))
+            }
             t => Err(property::ReadError::UnknownTag(t as u32)),
         }
     }
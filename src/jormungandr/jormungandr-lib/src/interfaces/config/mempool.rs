@@ -1,5 +1,7 @@
+use crate::time::Duration;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::{convert::TryFrom, path::PathBuf};
+use thiserror::Error;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
 pub struct PoolMaxEntries(usize);
@@ -7,9 +9,50 @@ pub struct PoolMaxEntries(usize);
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
 pub struct LogMaxEntries(usize);
 
+/// percentage of the block content size, between 0 and 100
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[serde(try_from = "u8", into = "u8")]
+pub struct BlockShare(u8);
+
+#[derive(Debug, Error)]
+#[error("block share must be a percentage between 0 and 100, got {0}")]
+pub struct InvalidBlockShare(u8);
+
+/// strategy used by a leader node to pick the mempool fragments
+/// to include in a new block
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum FragmentSelection {
+    /// fragments are included in the order they were received
+    OldestFirst,
+    /// fragments paying the highest fee per byte are included first
+    HighestFeeFirst,
+    /// senders take turns to have one of their fragments included
+    SenderRoundRobin,
+    /// vote casts are guaranteed the given share of the block content and
+    /// the other fragments the rest of it, the space left unused by one
+    /// kind being given to the other one
+    ReservedSpace { vote_cast_share: BlockShare },
+}
+
+/// fragments to evict when the mempool is full
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PoolEviction {
+    /// the oldest fragment is evicted
+    Oldest,
+    /// the fragment paying the lowest fee per byte is evicted, incoming
+    /// fragments paying less than any fragment in the pool are rejected
+    LowestFee,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct PersistentLog {
     pub dir: PathBuf,
+    /// on startup, put back in the mempool the fragments logged within
+    /// this duration that are still pending
+    #[serde(default)]
+    pub replay_window: Option<Duration>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
@@ -24,6 +67,16 @@ pub struct Mempool {
     /// path to the persistent log of all incoming fragments
     #[serde(default)]
     pub persistent_log: Option<PersistentLog>,
+    /// strategy used to select the fragments to include in a block
+    #[serde(default)]
+    pub fragment_selection: FragmentSelection,
+    /// maximum number of pending fragments of an account in each of its
+    /// spending counter lanes, not limited if not set
+    #[serde(default)]
+    pub account_lane_max_entries: Option<usize>,
+    /// fragments to evict when the mempool is full
+    #[serde(default)]
+    pub eviction: PoolEviction,
 }
 
 impl Default for PoolMaxEntries {
@@ -61,3 +114,66 @@ impl From<LogMaxEntries> for usize {
         s.0
     }
 }
+
+impl Default for FragmentSelection {
+    fn default() -> Self {
+        FragmentSelection::OldestFirst
+    }
+}
+
+impl Default for PoolEviction {
+    fn default() -> Self {
+        PoolEviction::Oldest
+    }
+}
+
+impl TryFrom<u8> for BlockShare {
+    type Error = InvalidBlockShare;
+
+    fn try_from(percent: u8) -> Result<Self, Self::Error> {
+        if percent <= 100 {
+            Ok(BlockShare(percent))
+        } else {
+            Err(InvalidBlockShare(percent))
+        }
+    }
+}
+
+impl From<BlockShare> for u8 {
+    fn from(s: BlockShare) -> Self {
+        s.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fragment_selection_from_yaml() {
+        let mempool: Mempool = serde_yaml::from_str("pool_max_entries: 10").unwrap();
+        assert_eq!(mempool.fragment_selection, FragmentSelection::OldestFirst);
+
+        let mempool: Mempool =
+            serde_yaml::from_str("fragment_selection: highest_fee_first").unwrap();
+        assert_eq!(
+            mempool.fragment_selection,
+            FragmentSelection::HighestFeeFirst
+        );
+
+        let mempool: Mempool =
+            serde_yaml::from_str("fragment_selection:\n  reserved_space:\n    vote_cast_share: 60")
+                .unwrap();
+        assert_eq!(
+            mempool.fragment_selection,
+            FragmentSelection::ReservedSpace {
+                vote_cast_share: BlockShare::try_from(60).unwrap()
+            }
+        );
+
+        assert!(serde_yaml::from_str::<Mempool>(
+            "fragment_selection:\n  reserved_space:\n    vote_cast_share: 101",
+        )
+        .is_err());
+    }
+}
@@ -4,9 +4,13 @@ mod node;
 mod secret;
 
 pub use log::{Log, LogEntry, LogOutput};
-pub use mempool::{LogMaxEntries, Mempool, PersistentLog, PoolMaxEntries};
+pub use mempool::{
+    BlockShare, FragmentSelection, InvalidBlockShare, LogMaxEntries, Mempool, PersistentLog,
+    PoolEviction, PoolMaxEntries,
+};
 pub use node::{
-    Bootstrap, Connection, Cors, CorsOrigin, JRpc, LayersConfig, NodeConfig, NodeId, P2p, Policy,
-    PreferredListConfig, Rest, Tls, TopicsOfInterest, TrustedPeer,
+    tls_server_name, Bootstrap, Connection, Cors, CorsOrigin, JRpc, LayersConfig, NodeConfig,
+    NodeId, P2p, P2pProtocol, P2pTls, Policy, PreferredListConfig, Rest, Tls, TopicsOfInterest,
+    TrustedPeer, TLS_SERVER_NAME_SUFFIX,
 };
 pub use secret::{Bft, GenesisPraos, NodeSecret};
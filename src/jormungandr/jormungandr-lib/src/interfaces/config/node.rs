@@ -245,6 +245,61 @@ pub struct Connection {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub dns_server_address: Option<SocketAddr>,
+
+    /// Protocol served on the listen address and used to connect to peers
+    /// that do not specify one. The default is gRPC.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protocol: Option<P2pProtocol>,
+
+    /// Additional listen address accepting connections with the NTT protocol.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ntt_listen: Option<SocketAddr>,
+
+    /// TLS settings of the gRPC connections with peers. If set, the node
+    /// only accepts and makes gRPC connections secured with TLS.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls: Option<P2pTls>,
+}
+
+/// TLS settings of the gRPC connections between nodes.
+///
+/// Connections are mutually authenticated: both nodes present a certificate
+/// issued by one of the trusted authorities. The certificate of a node
+/// must be issued for the DNS name returned by [`tls_server_name`] for its
+/// node ID, which is checked by the nodes connecting to it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct P2pTls {
+    /// Path to the node's X.509 certificate chain file, must be PEM-encoded
+    pub cert_file: PathBuf,
+    /// Path to the node's private key file, must be a single PEM-encoded, unencrypted key
+    pub priv_key_file: PathBuf,
+    /// Path to the PEM-encoded certificates of the authorities trusted
+    /// to issue the certificates of peer nodes
+    pub ca_file: PathBuf,
+}
+
+/// Suffix of the DNS names the TLS certificates of nodes are issued for.
+pub const TLS_SERVER_NAME_SUFFIX: &str = "node.jormungandr";
+
+/// Returns the DNS name the TLS certificate of the node with the given ID
+/// must be issued for. The hexadecimal node ID is split in two labels
+/// to fit within the length limit of DNS labels.
+pub fn tls_server_name(id: &NodeId) -> String {
+    let hex = id.to_hex();
+    let (high, low) = hex.split_at(hex.len() / 2);
+    format!("{}.{}.{}", high, low, TLS_SERVER_NAME_SUFFIX)
+}
+
+/// Network protocol used for P2P connections.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum P2pProtocol {
+    /// The gRPC protocol over HTTP/2.
+    #[default]
+    Grpc,
+    /// The compact framed binary protocol over raw TCP.
+    Ntt,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -312,6 +367,10 @@ pub struct TrustedPeer {
     /// Node identifier as a bech32-encoded ed25519 public key.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub id: Option<NodeId>,
+    /// Protocol to connect to the peer with, if different from the one
+    /// configured for the node.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protocol: Option<P2pProtocol>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+/// Outcome of the reload of the node configuration file, listing the changed
+/// fields by their dotted path, e.g. `mempool.pool_max_entries`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigReloadReport {
+    /// changed fields now in use by the node
+    pub applied: Vec<String>,
+    /// changed fields only taken into account after a restart of the node
+    pub restart_required: Vec<String>,
+    /// changed fields ignored because they are set on the command line
+    pub overridden: Vec<String>,
+}
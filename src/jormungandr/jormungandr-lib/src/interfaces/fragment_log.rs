@@ -20,6 +20,9 @@ pub enum FragmentOrigin {
     /// This marks the fragment is coming from the JRpc interface
     /// (a client wallet or another service).
     JRpc,
+    /// This marks the fragment was recovered from the persistent
+    /// fragment log when the node restarted.
+    PersistentLog,
 }
 
 /// status of the fragment within the blockchain or the pool
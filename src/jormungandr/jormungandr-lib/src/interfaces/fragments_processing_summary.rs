@@ -12,6 +12,8 @@ pub enum FragmentRejectionReason {
     FragmentInvalid,
     PreviousFragmentInvalid,
     PoolOverflow,
+    FragmentExpired,
+    AccountLimitReached,
 }
 
 /// Information about a fragment rejected by the mempool. This is different from being rejected by
@@ -42,6 +44,8 @@ impl FragmentRejectionReason {
             FragmentRejectionReason::FragmentInvalid
                 | FragmentRejectionReason::PreviousFragmentInvalid
                 | FragmentRejectionReason::PoolOverflow
+                | FragmentRejectionReason::FragmentExpired
+                | FragmentRejectionReason::AccountLimitReached
         )
     }
 }
@@ -70,11 +74,13 @@ mod tests {
 
     impl Arbitrary for FragmentRejectionReason {
         fn arbitrary<G: Gen>(g: &mut G) -> Self {
-            match g.next_u32() % 4 {
+            match g.next_u32() % 6 {
                 0 => FragmentRejectionReason::FragmentAlreadyInLog,
                 1 => FragmentRejectionReason::FragmentInvalid,
                 2 => FragmentRejectionReason::PreviousFragmentInvalid,
                 3 => FragmentRejectionReason::PoolOverflow,
+                4 => FragmentRejectionReason::FragmentExpired,
+                5 => FragmentRejectionReason::AccountLimitReached,
                 _ => unreachable!(),
             }
         }
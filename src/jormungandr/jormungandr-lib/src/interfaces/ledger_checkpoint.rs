@@ -0,0 +1,251 @@
+use chain_core::{
+    packer::Codec,
+    property::{Deserialize, DeserializeFromSlice, ReadError, Serialize, WriteError},
+};
+use chain_crypto::Blake2b256;
+use chain_impl_mockchain::{block::HeaderId, chaintypes::ChainLength, ledger::Ledger};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+/// Name of the directory, in the node's storage directory, the ledger
+/// checkpoints are kept in.
+pub const LEDGER_CHECKPOINTS_DIRECTORY: &str = "ledger-checkpoints";
+
+const LEDGER_CHECKPOINT_EXTENSION: &str = "checkpoint";
+const LEDGER_CHECKPOINT_MAGIC: &[u8; 4] = b"JCKP";
+const LEDGER_CHECKPOINT_VERSION: u16 = 1;
+
+#[derive(Debug, Error)]
+pub enum LedgerCheckpointError {
+    #[error("I/O error")]
+    Io(#[from] io::Error),
+    #[error("not a ledger checkpoint")]
+    InvalidMagic,
+    #[error("unsupported ledger checkpoint version {0}")]
+    UnsupportedVersion(u16),
+    #[error("the ledger checkpoint is corrupted, its checksum does not match its content")]
+    ChecksumMismatch,
+    #[error("invalid ledger checkpoint")]
+    Read(#[from] ReadError),
+    #[error("cannot serialize the ledger checkpoint")]
+    Write(#[from] WriteError),
+}
+
+/// The ledger states a node needs to restart from a stable block without
+/// replaying all the blocks since the block0.
+///
+/// A checkpoint is taken at the last block of an epoch, it also keeps the
+/// states the leadership schedule of the block's epoch and of the next
+/// epoch are computed from.
+#[derive(Clone)]
+pub struct LedgerCheckpoint {
+    /// the block the checkpoint was taken at
+    pub block: HeaderId,
+    /// the ledger state after the block
+    pub ledger: Ledger,
+    /// the ledger state the leadership schedule of the block's epoch
+    /// was computed from
+    pub epoch_leadership_ledger: Ledger,
+    /// the last block of the previous epoch
+    pub previous_epoch_block: HeaderId,
+    /// the ledger state after the last block of the previous epoch
+    pub previous_epoch_ledger: Ledger,
+}
+
+impl LedgerCheckpoint {
+    pub fn chain_length(&self) -> ChainLength {
+        self.ledger.chain_length()
+    }
+
+    /// name of the checkpoint's file, the names of the checkpoints of the
+    /// same chain sort by chain length
+    pub fn file_name(&self) -> String {
+        format!(
+            "{:010}-{}.{}",
+            u32::from(self.chain_length()),
+            self.block,
+            LEDGER_CHECKPOINT_EXTENSION
+        )
+    }
+
+    /// serialize the checkpoint, followed by the checksum of its content
+    pub fn to_bytes(&self) -> Result<Vec<u8>, LedgerCheckpointError> {
+        let mut codec = Codec::new(Vec::new());
+        codec.put_bytes(LEDGER_CHECKPOINT_MAGIC)?;
+        codec.put_be_u16(LEDGER_CHECKPOINT_VERSION)?;
+        self.block.serialize(&mut codec)?;
+        self.ledger.serialize(&mut codec)?;
+        self.epoch_leadership_ledger.serialize(&mut codec)?;
+        self.previous_epoch_block.serialize(&mut codec)?;
+        self.previous_epoch_ledger.serialize(&mut codec)?;
+        let mut bytes = codec.into_inner();
+        let checksum = Blake2b256::new(&bytes);
+        bytes.extend_from_slice(checksum.as_hash_bytes());
+        Ok(bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, LedgerCheckpointError> {
+        if bytes.len() < Blake2b256::HASH_SIZE {
+            return Err(LedgerCheckpointError::ChecksumMismatch);
+        }
+        let (content, checksum) = bytes.split_at(bytes.len() - Blake2b256::HASH_SIZE);
+        if Blake2b256::new(content).as_hash_bytes()[..] != *checksum {
+            return Err(LedgerCheckpointError::ChecksumMismatch);
+        }
+
+        let mut codec = Codec::new(content);
+        if codec.get_slice(LEDGER_CHECKPOINT_MAGIC.len())? != LEDGER_CHECKPOINT_MAGIC {
+            return Err(LedgerCheckpointError::InvalidMagic);
+        }
+        let version = codec.get_be_u16()?;
+        if version != LEDGER_CHECKPOINT_VERSION {
+            return Err(LedgerCheckpointError::UnsupportedVersion(version));
+        }
+        Ok(LedgerCheckpoint {
+            block: HeaderId::deserialize(&mut codec)?,
+            ledger: Ledger::deserialize_from_slice(&mut codec)?,
+            epoch_leadership_ledger: Ledger::deserialize_from_slice(&mut codec)?,
+            previous_epoch_block: HeaderId::deserialize(&mut codec)?,
+            previous_epoch_ledger: Ledger::deserialize_from_slice(&mut codec)?,
+        })
+    }
+
+    pub fn read_from_file<P: AsRef<Path>>(path: P) -> Result<Self, LedgerCheckpointError> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+
+    /// write the checkpoint in the given directory under its
+    /// [`file_name`](Self::file_name), returns the path of the file
+    ///
+    /// the file is written under a temporary name first so a partially
+    /// written checkpoint is never picked up.
+    pub fn write_to_directory<P: AsRef<Path>>(
+        &self,
+        directory: P,
+    ) -> Result<PathBuf, LedgerCheckpointError> {
+        let directory = directory.as_ref();
+        fs::create_dir_all(directory)?;
+        let path = directory.join(self.file_name());
+        let tmp_path = directory.join(format!("tmp.{}", self.file_name()));
+        fs::write(&tmp_path, self.to_bytes()?)?;
+        fs::rename(tmp_path, &path)?;
+        Ok(path)
+    }
+}
+
+/// list the checkpoint files of the given directory, the most recent first
+pub fn list_ledger_checkpoints(directory: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut entries: Vec<_> = fs::read_dir(directory)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .map_or(false, |extension| extension == LEDGER_CHECKPOINT_EXTENSION)
+                && path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .map_or(false, |name| !name.starts_with("tmp."))
+        })
+        .collect();
+    entries.sort();
+    entries.reverse();
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chain_impl_mockchain::{
+        testing::{ConfigBuilder, LedgerBuilder},
+        value::Value,
+    };
+
+    fn ledger(value: u64) -> Ledger {
+        LedgerBuilder::from_config(ConfigBuilder::new())
+            .faucet_value(Value(value))
+            .build()
+            .expect("cannot build test ledger")
+            .into()
+    }
+
+    fn checkpoint() -> LedgerCheckpoint {
+        LedgerCheckpoint {
+            block: HeaderId::hash_bytes(&[1]),
+            ledger: ledger(3),
+            epoch_leadership_ledger: ledger(2),
+            previous_epoch_block: HeaderId::hash_bytes(&[0]),
+            previous_epoch_ledger: ledger(1),
+        }
+    }
+
+    /// replace the checksum of the altered content of a checkpoint
+    fn with_checksum(mut bytes: Vec<u8>) -> Vec<u8> {
+        bytes.truncate(bytes.len() - Blake2b256::HASH_SIZE);
+        let checksum = Blake2b256::new(&bytes);
+        bytes.extend_from_slice(checksum.as_hash_bytes());
+        bytes
+    }
+
+    #[test]
+    fn checkpoint_round_trip() {
+        let checkpoint = checkpoint();
+        let decoded = LedgerCheckpoint::from_bytes(&checkpoint.to_bytes().unwrap()).unwrap();
+
+        assert_eq!(decoded.block, checkpoint.block);
+        assert_eq!(decoded.ledger, checkpoint.ledger);
+        assert_eq!(
+            decoded.epoch_leadership_ledger,
+            checkpoint.epoch_leadership_ledger
+        );
+        assert_eq!(
+            decoded.previous_epoch_block,
+            checkpoint.previous_epoch_block
+        );
+        assert_eq!(
+            decoded.previous_epoch_ledger,
+            checkpoint.previous_epoch_ledger
+        );
+    }
+
+    #[test]
+    fn wrong_magic_is_rejected() {
+        let mut bytes = checkpoint().to_bytes().unwrap();
+        bytes[0] ^= 1;
+        assert!(matches!(
+            LedgerCheckpoint::from_bytes(&with_checksum(bytes)),
+            Err(LedgerCheckpointError::InvalidMagic)
+        ));
+    }
+
+    #[test]
+    fn other_version_is_rejected() {
+        let mut bytes = checkpoint().to_bytes().unwrap();
+        let version_position = LEDGER_CHECKPOINT_MAGIC.len();
+        bytes[version_position..version_position + 2]
+            .copy_from_slice(&(LEDGER_CHECKPOINT_VERSION + 1).to_be_bytes());
+        assert!(matches!(
+            LedgerCheckpoint::from_bytes(&with_checksum(bytes)),
+            Err(LedgerCheckpointError::UnsupportedVersion(version))
+                if version == LEDGER_CHECKPOINT_VERSION + 1
+        ));
+    }
+
+    #[test]
+    fn corrupted_content_is_rejected() {
+        let bytes = checkpoint().to_bytes().unwrap();
+        for position in [0, bytes.len() / 2, bytes.len() - 1] {
+            let mut corrupted = bytes.clone();
+            corrupted[position] ^= 1;
+            assert!(matches!(
+                LedgerCheckpoint::from_bytes(&corrupted),
+                Err(LedgerCheckpointError::ChecksumMismatch)
+            ));
+        }
+        assert!(matches!(
+            LedgerCheckpoint::from_bytes(&bytes[..Blake2b256::HASH_SIZE - 1]),
+            Err(LedgerCheckpointError::ChecksumMismatch)
+        ));
+    }
+}
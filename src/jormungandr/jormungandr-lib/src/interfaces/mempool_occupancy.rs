@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Pending fragments of an account in the mempool.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountPoolOccupancy {
+    /// hex-encoded identifier of the account
+    pub account: String,
+    /// number of pending fragments spending from the account
+    pub fragments: usize,
+    /// number of pending fragments in each spending counter lane
+    pub lanes: BTreeMap<usize, usize>,
+}
+
+/// Pending fragments of a given type in the mempool.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FragmentTypePoolOccupancy {
+    /// name of the fragment type, e.g. `transaction` or `vote_cast`
+    pub fragment_type: String,
+    /// number of pending fragments of this type
+    pub fragments: usize,
+    /// total size in bytes of the pending fragments of this type
+    pub size: usize,
+}
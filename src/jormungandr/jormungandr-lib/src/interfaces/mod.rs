@@ -8,6 +8,7 @@ mod certificate;
 mod committee;
 mod config;
 mod config_params;
+mod config_reload;
 mod evm_transaction;
 mod fragment;
 mod fragment_log;
@@ -15,7 +16,9 @@ mod fragment_log_persistent;
 mod fragments_batch;
 mod fragments_processing_summary;
 mod leadership_log;
+mod ledger_checkpoint;
 mod linear_fee;
+mod mempool_occupancy;
 mod mint_token;
 mod old_address;
 mod peer_stats;
@@ -53,10 +56,12 @@ pub use self::{
     config_params::{
         config_params_documented_example, ConfigParam, ConfigParams, FromConfigParamError,
     },
+    config_reload::ConfigReloadReport,
     evm_transaction::EvmTransaction,
     fragment::FragmentDef,
     fragment_log::{FragmentLog, FragmentOrigin, FragmentStatus},
     fragment_log_persistent::{
+        list_persistent_fragment_log_files_from_folder_path,
         load_persistent_fragments_logs_from_folder_path,
         read_persistent_fragment_logs_from_file_path,
         DeserializeError as FragmentLogDeserializeError, FileFragments, PersistentFragmentLog,
@@ -66,7 +71,12 @@ pub use self::{
         FragmentRejectionReason, FragmentsProcessingSummary, RejectedFragmentInfo,
     },
     leadership_log::{LeadershipLog, LeadershipLogId, LeadershipLogStatus},
+    ledger_checkpoint::{
+        list_ledger_checkpoints, LedgerCheckpoint, LedgerCheckpointError,
+        LEDGER_CHECKPOINTS_DIRECTORY,
+    },
     linear_fee::{LinearFeeDef, PerCertificateFeeDef, PerVoteCertificateFeeDef},
+    mempool_occupancy::{AccountPoolOccupancy, FragmentTypePoolOccupancy},
     mint_token::TokenIdentifier,
     old_address::OldAddress,
     peer_stats::{PeerRecord, PeerStats, Subscription},
@@ -27,10 +27,16 @@ pub struct VotePrivacy(#[serde(with = "PayloadTypeDef")] pub vote::PayloadType);
 enum PayloadTypeDef {
     Public,
     Private,
+    RankedChoice,
+    Approval,
+    Quadratic,
+    PrivateQuadratic,
 }
 
 #[derive(Debug, thiserror::Error)]
-#[error("Invalid vote privacy, expected \"public\" or \"private\".")]
+#[error(
+    "Invalid vote privacy, expected \"public\", \"private\", \"ranked_choice\", \"approval\", \"quadratic\" or \"private_quadratic\"."
+)]
 pub struct VotePrivacyFromStrError;
 
 impl FromStr for VotePrivacy {
@@ -39,6 +45,10 @@ impl FromStr for VotePrivacy {
         match s {
             "public" => Ok(VotePrivacy(vote::PayloadType::Public)),
             "private" => Ok(VotePrivacy(vote::PayloadType::Private)),
+            "ranked_choice" => Ok(VotePrivacy(vote::PayloadType::RankedChoice)),
+            "approval" => Ok(VotePrivacy(vote::PayloadType::Approval)),
+            "quadratic" => Ok(VotePrivacy(vote::PayloadType::Quadratic)),
+            "private_quadratic" => Ok(VotePrivacy(vote::PayloadType::PrivateQuadratic)),
             _ => Err(VotePrivacyFromStrError),
         }
     }
@@ -49,6 +59,10 @@ impl fmt::Display for VotePrivacy {
         let s = match self.0 {
             vote::PayloadType::Public => "public",
             vote::PayloadType::Private => "private",
+            vote::PayloadType::RankedChoice => "ranked_choice",
+            vote::PayloadType::Approval => "approval",
+            vote::PayloadType::Quadratic => "quadratic",
+            vote::PayloadType::PrivateQuadratic => "private_quadratic",
         };
         s.fmt(f)
     }
@@ -157,6 +171,10 @@ pub struct VotePlan {
     pub proposals: Proposals,
     #[serde(with = "serde_committee_member_public_keys", default = "Vec::new")]
     pub committee_member_public_keys: Vec<chain_vote::MemberPublicKey>,
+    /// number of committee members needed to decrypt the tally, for committee
+    /// keys generated with the distributed key generation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub committee_threshold: Option<u8>,
     pub voting_token: TokenIdentifier,
 }
 
@@ -214,6 +232,7 @@ impl From<certificate::VotePlan> for VotePlan {
             proposals: vp.proposals().clone(),
             payload_type: vp.payload_type().into(),
             committee_member_public_keys: vp.committee_public_keys().to_vec(),
+            committee_threshold: vp.committee_threshold(),
             voting_token: vp.voting_token().clone().into(),
         }
     }
@@ -221,7 +240,7 @@ impl From<certificate::VotePlan> for VotePlan {
 
 impl From<VotePlan> for certificate::VotePlan {
     fn from(vpd: VotePlan) -> Self {
-        certificate::VotePlan::new(
+        let vote_plan = certificate::VotePlan::new(
             vpd.vote_start.into(),
             vpd.vote_end.into(),
             vpd.committee_end.into(),
@@ -229,7 +248,11 @@ impl From<VotePlan> for certificate::VotePlan {
             vpd.payload_type.into(),
             vpd.committee_member_public_keys,
             vpd.voting_token.into(),
-        )
+        );
+        match vpd.committee_threshold {
+            Some(threshold) => vote_plan.with_committee_threshold(threshold),
+            None => vote_plan,
+        }
     }
 }
 
@@ -449,6 +472,8 @@ pub struct VotePlanStatus {
     pub committee_end: BlockDate,
     #[serde(with = "serde_committee_member_public_keys")]
     pub committee_member_keys: Vec<MemberPublicKey>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub committee_threshold: Option<u8>,
     pub proposals: Vec<VoteProposalStatus>,
     pub voting_token: TokenIdentifier,
 }
@@ -457,6 +482,9 @@ pub struct VotePlanStatus {
 pub enum Tally {
     Public { result: TallyResult },
     Private { state: PrivateTallyState },
+    RankedChoice { result: RankedChoiceResult },
+    Approval { result: TallyResult },
+    Quadratic { result: TallyResult },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Hash)]
@@ -574,6 +602,36 @@ impl From<EncryptedTally> for chain_vote::EncryptedTally {
     }
 }
 
+/// the ranked choice ballots and the rounds of the instant-runoff computed
+/// from them
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RankedChoiceResult {
+    pub options: Range<u8>,
+    pub ballots: Vec<RankedBallots>,
+    pub rounds: Vec<Vec<u64>>,
+    pub winner: Option<u8>,
+}
+
+impl RankedChoiceResult {
+    pub fn merge(&self, other: &Self) -> Self {
+        assert_eq!(self.options, other.options);
+
+        let mut result: vote::RankedChoiceResult = self.clone().into();
+        for ballots in other.ballots.iter() {
+            let ranking: Vec<Choice> = ballots.ranking.iter().copied().map(Choice::new).collect();
+            result.add_vote(&ranking, ballots.weight).unwrap();
+        }
+        result.into()
+    }
+}
+
+/// the total weight of the ballots casting the same ranking
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RankedBallots {
+    pub ranking: Vec<u8>,
+    pub weight: u64,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PrivateTallyState {
     Encrypted { encrypted_tally: EncryptedTally },
@@ -591,6 +649,24 @@ pub enum VotePayload {
         #[serde(with = "serde_base64_bytes")]
         proof: Vec<u8>,
     },
+    RankedChoice {
+        ranking: Vec<u8>,
+    },
+    Approval {
+        approvals: Vec<u8>,
+    },
+    Quadratic {
+        choice: u8,
+        credits: u64,
+    },
+    PrivateQuadratic {
+        #[serde(with = "serde_base64_bytes")]
+        encrypted_vote: Vec<u8>,
+        #[serde(with = "serde_base64_bytes")]
+        proof: Vec<u8>,
+        #[serde(with = "serde_base64_bytes")]
+        allocation: Vec<u8>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
@@ -615,6 +691,25 @@ impl From<vote::Payload> for VotePayload {
                 encrypted_vote: encrypted_vote.serialize().into(),
                 proof: proof.serialize().into(),
             },
+            vote::Payload::RankedChoice { ranking } => Self::RankedChoice {
+                ranking: ranking.iter().map(|choice| choice.as_byte()).collect(),
+            },
+            vote::Payload::Approval { approvals } => Self::Approval {
+                approvals: approvals.iter().map(|choice| choice.as_byte()).collect(),
+            },
+            vote::Payload::Quadratic { choice, credits } => Self::Quadratic {
+                choice: choice.as_byte(),
+                credits,
+            },
+            vote::Payload::PrivateQuadratic {
+                encrypted_vote,
+                proof,
+                allocation,
+            } => Self::PrivateQuadratic {
+                encrypted_vote: encrypted_vote.serialize().into(),
+                proof: proof.serialize().into(),
+                allocation: allocation.serialize().into(),
+            },
         }
     }
 }
@@ -622,12 +717,51 @@ impl From<vote::Payload> for VotePayload {
 impl VotePayload {
     pub fn choice(&self) -> Option<u8> {
         match self {
-            VotePayload::Public { choice } => Some(*choice),
-            VotePayload::Private { .. } => None,
+            VotePayload::Public { choice } | VotePayload::Quadratic { choice, .. } => Some(*choice),
+            VotePayload::Private { .. }
+            | VotePayload::RankedChoice { .. }
+            | VotePayload::Approval { .. }
+            | VotePayload::PrivateQuadratic { .. } => None,
+        }
+    }
+}
+
+impl From<vote::RankedChoiceResult> for RankedChoiceResult {
+    fn from(this: vote::RankedChoiceResult) -> Self {
+        let runoff = this.instant_runoff();
+        Self {
+            options: this.options().choice_range().clone(),
+            ballots: this
+                .ballots()
+                .map(|(ranking, weight)| RankedBallots {
+                    ranking: ranking.iter().map(|choice| choice.as_byte()).collect(),
+                    weight: weight.into(),
+                })
+                .collect(),
+            rounds: runoff
+                .rounds()
+                .iter()
+                .map(|round| round.iter().map(|weight| (*weight).into()).collect())
+                .collect(),
+            winner: runoff.winner().map(Choice::as_byte),
         }
     }
 }
 
+impl From<RankedChoiceResult> for vote::RankedChoiceResult {
+    fn from(this: RankedChoiceResult) -> vote::RankedChoiceResult {
+        // the rounds and the winner are computed again from the ballots
+        let mut result = vote::RankedChoiceResult::new(
+            Options::new_length(this.options.end - this.options.start).unwrap(),
+        );
+        for ballots in this.ballots {
+            let ranking: Vec<Choice> = ballots.ranking.into_iter().map(Choice::new).collect();
+            result.add_vote(&ranking, ballots.weight).unwrap();
+        }
+        result
+    }
+}
+
 impl From<vote::TallyResult> for TallyResult {
     fn from(this: vote::TallyResult) -> Self {
         Self {
@@ -678,6 +812,15 @@ impl From<vote::Tally> for Tally {
                     },
                 },
             },
+            vote::Tally::RankedChoice { result } => Tally::RankedChoice {
+                result: result.into(),
+            },
+            vote::Tally::Approval { result } => Tally::Approval {
+                result: result.into(),
+            },
+            vote::Tally::Quadratic { result } => Tally::Quadratic {
+                result: result.into(),
+            },
         }
     }
 }
@@ -700,6 +843,15 @@ impl From<Tally> for vote::Tally {
                     },
                 },
             },
+            Tally::RankedChoice { result } => vote::Tally::RankedChoice {
+                result: result.into(),
+            },
+            Tally::Approval { result } => vote::Tally::Approval {
+                result: result.into(),
+            },
+            Tally::Quadratic { result } => vote::Tally::Quadratic {
+                result: result.into(),
+            },
         }
     }
 }
@@ -740,6 +892,7 @@ impl From<vote::VotePlanStatus> for VotePlanStatus {
             committee_end: this.committee_end.into(),
             payload: this.payload,
             committee_member_keys: this.committee_public_keys,
+            committee_threshold: this.committee_threshold,
             proposals: this.proposals.into_iter().map(|p| p.into()).collect(),
             voting_token: this.voting_token.into(),
         }
@@ -755,6 +908,7 @@ impl From<VotePlanStatus> for vote::VotePlanStatus {
             committee_end: vote_plan_status.committee_end.into(),
             payload: vote_plan_status.payload,
             committee_public_keys: vote_plan_status.committee_member_keys,
+            committee_threshold: vote_plan_status.committee_threshold,
             proposals: vote_plan_status
                 .proposals
                 .into_iter()
@@ -0,0 +1,91 @@
+//! Remote signer holding the leader keys of a node in a separate process.
+//!
+//! The node is configured with the socket of the signer in
+//! `leadership.signer`, instead of loading the secret file itself.
+
+#[cfg(unix)]
+mod unix {
+    use clap::Parser;
+    use jormungandr::{
+        blockcfg::Leader,
+        secure::{
+            signer::{Signer, SigningGuard},
+            NodeSecret,
+        },
+    };
+    use std::{
+        error::Error,
+        fs,
+        os::unix::fs::{FileTypeExt, PermissionsExt},
+        path::PathBuf,
+    };
+    use tokio::net::UnixListener;
+
+    #[derive(Parser, Debug)]
+    #[clap(name = "jormungandr-signer")]
+    pub struct Arguments {
+        /// Set the secret node config (in YAML format) holding the leader keys.
+        #[clap(long = "secret")]
+        secret: PathBuf,
+
+        /// Path of the Unix socket to listen on for the requests of the node.
+        #[clap(long = "socket")]
+        socket: PathBuf,
+
+        /// File keeping the last signed block header, to refuse signing
+        /// another block for the same slot after a restart.
+        #[clap(long = "state")]
+        state: PathBuf,
+    }
+
+    pub fn run(args: Arguments) -> Result<(), Box<dyn Error + Send + Sync>> {
+        tracing_subscriber::fmt::init();
+
+        let secret = NodeSecret::load_from_file(&args.secret)?;
+        let leader = Leader {
+            bft_leader: secret.bft(),
+            genesis_leader: secret.genesis(),
+        };
+        if leader.bft_leader.is_none() && leader.genesis_leader.is_none() {
+            return Err("the secret file does not hold any leader key".into());
+        }
+        let guard = SigningGuard::open(&args.state)?;
+
+        let runtime = tokio::runtime::Runtime::new()?;
+        runtime.block_on(async {
+            // the socket of a previous run prevents binding the address
+            if let Ok(metadata) = fs::symlink_metadata(&args.socket) {
+                if metadata.file_type().is_socket() {
+                    fs::remove_file(&args.socket)?;
+                }
+            }
+            let listener = UnixListener::bind(&args.socket)?;
+            // the node has to run with the same user or group to connect
+            fs::set_permissions(&args.socket, fs::Permissions::from_mode(0o660))?;
+            tracing::info!("listening on {}", args.socket.display());
+            Signer::new(leader, guard).serve(listener).await
+        })?;
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+fn main() {
+    use clap::Parser;
+
+    if let Err(error) = unix::run(unix::Arguments::parse()) {
+        eprintln!("{}", error);
+        let mut source = error.source();
+        while let Some(err) = source {
+            eprintln!(" |-> {}", err);
+            source = err.source();
+        }
+        std::process::exit(1);
+    }
+}
+
+#[cfg(not(unix))]
+fn main() {
+    eprintln!("jormungandr-signer is only supported on Unix platforms");
+    std::process::exit(1);
+}
@@ -1,5 +1,5 @@
 pub use chain_impl_mockchain::{
-    block::{builder as block_builder, Block},
+    block::{builder as block_builder, builder_async as block_builder_async, Block},
     chaineval::HeaderContentEvalContext,
     chaintypes::ConsensusVersion,
     config::{self, Block0Date, ConfigParam},
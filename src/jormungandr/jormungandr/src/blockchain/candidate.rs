@@ -2,10 +2,7 @@ use super::{
     chain::{self, Blockchain, HeaderChainVerifyError, PreCheckedHeader},
     chunk_sizes,
 };
-use crate::{
-    blockcfg::{Header, HeaderHash},
-    utils::async_msg::MessageQueue,
-};
+use crate::blockcfg::{Header, HeaderHash};
 use futures::{
     future::poll_fn,
     prelude::*,
@@ -16,8 +13,6 @@ use std::{marker::Unpin, pin::Pin};
 // derive
 use thiserror::Error;
 
-type HeaderStream = MessageQueue<Header>;
-
 #[allow(clippy::large_enum_variant)]
 #[derive(Error, Debug)]
 pub enum Error {
@@ -215,10 +210,13 @@ where
 /// and the stream if the process terminated early due to reaching
 /// a limit on the number of blocks or (TODO: implement) needing
 /// block data to validate more blocks with newer leadership information.
-pub async fn advance_branch(
+pub async fn advance_branch<S>(
     blockchain: Blockchain,
-    header_stream: HeaderStream,
-) -> Result<(Vec<HeaderHash>, Option<impl Stream<Item = Header>>), Error> {
+    header_stream: S,
+) -> Result<(Vec<HeaderHash>, Option<S>), Error>
+where
+    S: Stream<Item = Header> + Unpin,
+{
     let mut advance = land_header_chain(blockchain, header_stream).await?;
 
     if advance.is_some() {
@@ -60,7 +60,11 @@ use crate::{
 use chain_impl_mockchain::{leadership::Verification, ledger};
 use chain_time::TimeFrame;
 use futures::{StreamExt, TryStreamExt};
-use std::sync::Arc;
+use jormungandr_lib::interfaces::{
+    list_ledger_checkpoints, LedgerCheckpoint, LedgerCheckpointError,
+};
+use std::{path::PathBuf, sync::Arc, time::Duration};
+use tokio::sync::{futures::Notified, Notify};
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -105,6 +109,12 @@ pub enum Error {
 
     #[error("block cannot be applied on top of the previous block's ledger state")]
     CannotApplyBlock(#[source] ledger::Error),
+
+    #[error(transparent)]
+    LedgerCheckpoint(#[from] LedgerCheckpointError),
+
+    #[error("ledger checkpoint does not match block `{0}`")]
+    LedgerCheckpointMismatch(HeaderHash),
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -124,6 +134,9 @@ pub type Result<T> = core::result::Result<T, Error>;
 
 pub const MAIN_BRANCH_TAG: &str = "HEAD";
 
+/// number of ledger checkpoints kept on disk, the older ones are removed
+const LEDGER_CHECKPOINTS_TO_KEEP: usize = 2;
+
 /// Performs lightweight sanity checks on information fields of a block header
 /// against those in the header of the block's parent.
 /// The `parent` header must have been retrieved based on, or otherwise
@@ -165,6 +178,10 @@ pub fn pre_verify_link(
 /// * `RefCache`: a cache of blocks headers and associated states;
 /// * `Multiverse`: of ledger. It is a cache of different ledger states.
 ///
+/// If a directory is given for the ledger checkpoints, the ledger states at the
+/// end of the stable epochs are saved there to restart without replaying the
+/// whole chain.
+///
 #[derive(Clone)]
 pub struct Blockchain {
     ref_cache: RefCache,
@@ -176,6 +193,11 @@ pub struct Blockchain {
     block0: HeaderHash,
 
     rewards_report_all: bool,
+
+    ledger_checkpoints: Option<PathBuf>,
+
+    /// woken up each time a new block is added to the storage
+    block_stored: Arc<Notify>,
 }
 
 pub enum PreCheckedHeader {
@@ -268,6 +290,7 @@ impl Blockchain {
         storage: Storage,
         cache_capacity: usize,
         rewards_report_all: bool,
+        ledger_checkpoints: Option<PathBuf>,
     ) -> Self {
         Blockchain {
             ref_cache: RefCache::new(cache_capacity),
@@ -275,6 +298,8 @@ impl Blockchain {
             storage,
             block0,
             rewards_report_all,
+            ledger_checkpoints,
+            block_stored: Arc::new(Notify::new()),
         }
     }
 
@@ -286,24 +311,208 @@ impl Blockchain {
         &self.storage
     }
 
+    /// Resolves the next time a new block is added to the storage.
+    ///
+    /// The blocks stored after this call wake up the returned future even if
+    /// it is not polled yet, so the storage can be checked between the call
+    /// and the wait without missing a block.
+    pub fn block_stored(&self) -> Notified<'_> {
+        self.block_stored.notified()
+    }
+
     pub async fn branches(&self) -> Result<Vec<Branch>> {
         futures::stream::iter(self.storage().get_branches()?)
-            // FIXME: this should always return a valid ref, as the branches
-            // are directly fetched from the node storage, but account for
-            // misses in the ref cache which are not handled currently by the node
+            // the branches are directly fetched from the node storage, so their
+            // state can be rebuilt unless no ancestor is left in the ref cache
             .filter_map(|branch| async move { self.get_ref(branch).await.transpose() })
             .map(|try_ref| try_ref.map(Branch::new))
             .try_collect()
             .await
     }
 
-    pub async fn gc(&self, tip: Arc<Ref>) -> Result<()> {
+    /// Prunes the stale branches and flushes the stable blocks to the
+    /// permanent storage. Returns the time spent flushing the blocks, if any.
+    pub async fn gc(&self, tip: Arc<Ref>) -> Result<Option<Duration>> {
         let depth = tip.ledger().settings().epoch_stability_depth;
         self.ledgers.gc(depth).await;
-        self.storage.gc(depth, tip.hash().as_ref())?;
+        let flush_time = self.storage.gc(depth, tip.hash().as_ref())?;
+        // the node keeps working without checkpoints, it is only slower to restart
+        if let Err(error) = self.save_ledger_checkpoint(&tip, depth).await {
+            tracing::warn!(%error, "failed to save the ledger checkpoint");
+        }
+        Ok(flush_time)
+    }
+
+    /// save a checkpoint of the ledger at the last block of the epoch before
+    /// the `tip`'s epoch, once that block is stable.
+    async fn save_ledger_checkpoint(&self, tip: &Ref, depth: u32) -> Result<()> {
+        let directory = match &self.ledger_checkpoints {
+            Some(directory) => directory.clone(),
+            None => return Ok(()),
+        };
+        let anchor = match tip.last_ref_previous_epoch() {
+            Some(anchor) => anchor,
+            None => return Ok(()),
+        };
+        if u32::from(tip.chain_length()) - u32::from(anchor.chain_length()) < depth {
+            return Ok(());
+        }
+        let previous = match anchor.last_ref_previous_epoch() {
+            Some(previous) => previous,
+            None => return Ok(()),
+        };
+
+        let transition_state = Arc::new(previous.ledger().apply_protocol_changes()?);
+        let checkpoint = LedgerCheckpoint {
+            block: anchor.hash(),
+            ledger: anchor.ledger().as_ref().clone(),
+            epoch_leadership_ledger: epoch_leadership_state(previous, &transition_state)
+                .as_ref()
+                .clone(),
+            previous_epoch_block: previous.hash(),
+            previous_epoch_ledger: previous.ledger().as_ref().clone(),
+        };
+        if directory.join(checkpoint.file_name()).exists() {
+            return Ok(());
+        }
+
+        let path = tokio::task::spawn_blocking(move || -> Result<PathBuf> {
+            let path = checkpoint.write_to_directory(&directory)?;
+            for outdated in list_ledger_checkpoints(&directory)
+                .map_err(LedgerCheckpointError::Io)?
+                .into_iter()
+                .skip(LEDGER_CHECKPOINTS_TO_KEEP)
+            {
+                std::fs::remove_file(outdated).map_err(LedgerCheckpointError::Io)?;
+            }
+            Ok(path)
+        })
+        .await
+        .expect("ledger checkpoint writer panicked")?;
+        tracing::info!(block = %anchor.hash(), path = %path.display(), "saved a ledger checkpoint");
         Ok(())
     }
 
+    /// restore the `Ref` of the most recent valid ledger checkpoint on the
+    /// main branch ending at `head_hash`, if any.
+    ///
+    /// Invalid checkpoints are skipped, the blocks are then replayed from an
+    /// older checkpoint or from the block0.
+    async fn load_ledger_checkpoint(
+        &self,
+        block0_ref: &Ref,
+        head_hash: HeaderHash,
+    ) -> Result<Option<Arc<Ref>>> {
+        let directory = match &self.ledger_checkpoints {
+            Some(directory) if directory.exists() => directory,
+            _ => return Ok(None),
+        };
+        for path in list_ledger_checkpoints(directory).map_err(LedgerCheckpointError::Io)? {
+            let read_path = path.clone();
+            let checkpoint =
+                tokio::task::spawn_blocking(move || LedgerCheckpoint::read_from_file(read_path))
+                    .await
+                    .expect("ledger checkpoint reader panicked");
+            let restored = match checkpoint {
+                Ok(checkpoint) => {
+                    self.restore_ledger_checkpoint(block0_ref, checkpoint, head_hash)
+                        .await
+                }
+                Err(error) => Err(error.into()),
+            };
+            match restored {
+                Ok(checkpoint_ref) => {
+                    tracing::info!(
+                        block = %checkpoint_ref.hash(),
+                        path = %path.display(),
+                        "restored the ledger from a checkpoint"
+                    );
+                    return Ok(Some(checkpoint_ref));
+                }
+                Err(error) => {
+                    tracing::warn!(%error, path = %path.display(), "ignoring ledger checkpoint");
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    async fn restore_ledger_checkpoint(
+        &self,
+        block0_ref: &Ref,
+        checkpoint: LedgerCheckpoint,
+        head_hash: HeaderHash,
+    ) -> Result<Arc<Ref>> {
+        let block_id = checkpoint.block;
+        if checkpoint
+            .ledger
+            .get_static_parameters()
+            .block0_initial_hash
+            != self.block0
+            || !self.storage.is_ancestor(block_id, head_hash)
+            || !self
+                .storage
+                .is_ancestor(checkpoint.previous_epoch_block, block_id)
+        {
+            return Err(Error::LedgerCheckpointMismatch(block_id));
+        }
+        let header = self
+            .storage
+            .get(block_id)?
+            .ok_or(Error::MissingParentBlock(block_id))?
+            .header()
+            .clone();
+        let previous_header = self
+            .storage
+            .get(checkpoint.previous_epoch_block)?
+            .ok_or(Error::MissingParentBlock(checkpoint.previous_epoch_block))?
+            .header()
+            .clone();
+        if checkpoint.ledger.chain_length() != header.chain_length()
+            || checkpoint.previous_epoch_ledger.chain_length() != previous_header.chain_length()
+        {
+            return Err(Error::LedgerCheckpointMismatch(block_id));
+        }
+
+        // the state of the previous epoch is only used to compute the
+        // leadership of the next epoch, it is not put in the ref cache so the
+        // blocks before the checkpoint are rebuilt from the block0 if needed
+        let previous_ledger = self
+            .ledgers
+            .insert(
+                previous_header.chain_length(),
+                checkpoint.previous_epoch_block,
+                checkpoint.previous_epoch_ledger,
+            )
+            .await;
+        let previous_leadership =
+            Leadership::new(previous_header.block_date().epoch, previous_ledger.state());
+        let previous_ref = Arc::new(Ref::new(
+            previous_ledger,
+            block0_ref.time_frame().clone(),
+            Arc::new(previous_leadership),
+            None,
+            previous_header,
+            None,
+        ));
+
+        let leadership = Leadership::new(
+            header.block_date().epoch,
+            &checkpoint.epoch_leadership_ledger,
+        );
+        Ok(self
+            .create_and_store_reference(
+                block_id,
+                header,
+                checkpoint.ledger,
+                block0_ref.time_frame().clone(),
+                Arc::new(leadership),
+                None,
+                Some(previous_ref),
+            )
+            .await)
+    }
+
     /// create and store a reference of this leader to the new
     #[allow(clippy::too_many_arguments)]
     async fn create_and_store_reference(
@@ -340,29 +549,105 @@ impl Blockchain {
     /// once the `Ref` is in hand, it means we have the Leadership schedule associated
     /// to this block and the `Ledger` state after this block.
     ///
+    /// If the block is in the storage but its `Ref` is not in the cache, the
+    /// state is rebuilt by applying the stored blocks on top of the closest
+    /// ancestor still in the cache.
+    ///
     /// If the future returns `None` it means we don't know about this block locally
     /// and it might be necessary to contacts the network to retrieve a missing
     /// branch
-    ///
-    /// TODO: the case where the block is in storage but not yet in the cache
-    ///       is not implemented
     pub async fn get_ref(&self, header_hash: HeaderHash) -> Result<Option<Arc<Ref>>> {
-        let maybe_ref = self.ref_cache.get(header_hash).await;
-        let block_exists = self.storage.block_exists(header_hash)?;
-
-        if maybe_ref.is_none() {
-            if block_exists {
-                // TODO: we have the block in the storage but it is missing
-                // from the state management. Force the node to fall through
-                // reloading the blocks from the storage to allow fast
-                // from storage reload
-            }
-            Ok(None)
+        if let Some(cached) = self.ref_cache.get(header_hash).await {
+            return Ok(Some(cached));
+        }
+
+        if self.storage.block_exists(header_hash)? {
+            self.load_ref_from_storage(header_hash).await
         } else {
-            Ok(maybe_ref)
+            Ok(None)
         }
     }
 
+    /// rebuild the `Ref` of a block that is in the storage but has been
+    /// evicted from the cache (or was never loaded, e.g. after a restart).
+    ///
+    /// The blocks are not verified again, they were checked before being
+    /// stored. Returns `None` if no ancestor of the block is in the cache.
+    ///
+    /// Only the blocks at most `epoch_stability_depth` below the tip are
+    /// rebuilt, so that a peer cannot make the node replay the whole chain
+    /// by announcing a header on top of an old block. `None` is returned
+    /// for the deeper blocks, they are handled as a missing parent.
+    async fn load_ref_from_storage(&self, header_hash: HeaderHash) -> Result<Option<Arc<Ref>>> {
+        let min_chain_length = match self.min_rebuild_chain_length().await? {
+            Some(min_chain_length) => min_chain_length,
+            None => return Ok(None),
+        };
+        let is_too_deep = |block_id| {
+            self.storage
+                .get_chain_length(block_id)
+                .map_or(true, |chain_length| chain_length < min_chain_length)
+        };
+        if is_too_deep(header_hash) {
+            return Ok(None);
+        }
+
+        let mut missing = vec![header_hash];
+        let mut parent_ref = loop {
+            let current = *missing.last().unwrap();
+            let parent_hash = match self.storage.get_parent(current)? {
+                Some(parent_hash) => parent_hash,
+                None => return Ok(None),
+            };
+            if let Some(parent_ref) = self.ref_cache.get(parent_hash).await {
+                break parent_ref;
+            }
+            if !self.storage.block_exists(parent_hash)? || is_too_deep(parent_hash) {
+                return Ok(None);
+            }
+            missing.push(parent_hash);
+        };
+
+        tracing::debug!(
+            block = %header_hash,
+            from = %parent_ref.hash(),
+            count = missing.len(),
+            "rebuilding block state from the storage"
+        );
+
+        while let Some(block_id) = missing.pop() {
+            let block = self
+                .storage
+                .get(block_id)?
+                .ok_or(Error::MissingParentBlock(block_id))?;
+            let post_checked = self
+                .post_check_header(
+                    block.header().clone(),
+                    parent_ref,
+                    CheckHeaderProof::SkipFromStorage,
+                )
+                .await?;
+            let new_ledger = self.apply_block_dry_run(&post_checked, &block)?;
+            parent_ref = self.apply_block_finalize(post_checked, new_ledger).await;
+        }
+
+        Ok(Some(parent_ref))
+    }
+
+    /// the chain length of the deepest block whose state can be rebuilt from
+    /// the storage: `epoch_stability_depth` blocks below the tip of the main
+    /// branch. `None` if the tip is not in the cache.
+    async fn min_rebuild_chain_length(&self) -> Result<Option<u32>> {
+        let tip_hash = match self.storage.get_tag(MAIN_BRANCH_TAG)? {
+            Some(tip_hash) => tip_hash,
+            None => return Ok(None),
+        };
+        Ok(self.ref_cache.get(tip_hash).await.map(|tip| {
+            let depth = tip.ledger().settings().epoch_stability_depth;
+            u32::from(tip.chain_length()).saturating_sub(depth)
+        }))
+    }
+
     /// load the header's parent `Ref`.
     async fn load_header_parent(&self, header: Header, force: bool) -> Result<PreCheckedHeader> {
         let block_id = header.hash();
@@ -537,7 +822,10 @@ impl Blockchain {
                     .await;
 
                 match res {
-                    Ok(()) => Ok(AppliedBlock::New(block_ref)),
+                    Ok(()) => {
+                        self.block_stored.notify_waiters();
+                        Ok(AppliedBlock::New(block_ref))
+                    }
                     Err(StorageError::BlockAlreadyPresent) => Ok(AppliedBlock::Existing(block_ref)),
                     _ => unreachable!(),
                 }
@@ -738,6 +1026,9 @@ impl Blockchain {
         };
 
         let mut last_ref = self.apply_block0(&block0).await?.get_ref();
+        if let Some(checkpoint_ref) = self.load_ledger_checkpoint(&last_ref, head_hash).await? {
+            last_ref = checkpoint_ref;
+        }
         let mut reporter = StreamReporter::new(|stream_info| {
             let elapsed = stream_info
                 .last_reported
@@ -753,7 +1044,7 @@ impl Blockchain {
 
         let mut block_stream = self
             .storage
-            .stream_from_to(block0_id, head_hash)
+            .stream_from_to(last_ref.hash(), head_hash)
             .map(Box::pin)?;
 
         while let Some(block) = block_stream.next().await.transpose()? {
@@ -842,8 +1133,6 @@ pub fn new_epoch_leadership_from(
         // TODO: the time frame may change in the future, we will need to handle this
         //       special case but it is not actually clear how to modify the time frame
         //       for the blockchain
-        use chain_impl_mockchain::chaintypes::ConsensusVersion;
-
         let ledger = parent_ledger_state
             .apply_protocol_changes()
             .expect("protocol update should not fail");
@@ -870,17 +1159,7 @@ pub fn new_epoch_leadership_from(
 
         // 2. now that the rewards have been distributed, prepare the schedule
         //    for the next leader
-        let epoch_state = if transition_state.consensus_version() == ConsensusVersion::GenesisPraos
-        {
-            // if there is no parent state available this might be because it is not
-            // available in memory or it is the epoch0 or epoch1
-            parent
-                .last_ref_previous_epoch()
-                .map(|r| r.ledger())
-                .unwrap_or(parent_ledger_state)
-        } else {
-            transition_state.clone()
-        };
+        let epoch_state = epoch_leadership_state(&parent, &transition_state);
 
         let leadership = Arc::new(Leadership::new(epoch, &epoch_state));
         let previous_epoch_state = Some(parent);
@@ -902,6 +1181,24 @@ pub fn new_epoch_leadership_from(
     }
 }
 
+/// the ledger state the leadership schedule of a new epoch is computed from,
+/// `parent` being the last block of the previous epoch and `transition_state`
+/// the ledger state after the epoch transition.
+fn epoch_leadership_state(parent: &Ref, transition_state: &Arc<Ledger>) -> Arc<Ledger> {
+    use chain_impl_mockchain::chaintypes::ConsensusVersion;
+
+    if transition_state.consensus_version() == ConsensusVersion::GenesisPraos {
+        // if there is no parent state available this might be because it is not
+        // available in memory or it is the epoch0 or epoch1
+        parent
+            .last_ref_previous_epoch()
+            .map(|r| r.ledger())
+            .unwrap_or_else(|| parent.ledger())
+    } else {
+        transition_state.clone()
+    }
+}
+
 pub struct StreamReporter<R> {
     stream_info: StreamInfo,
     report: R,
@@ -948,3 +1245,250 @@ impl<R: Fn(&StreamInfo)> StreamReporter<R> {
         }
     }
 }
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+    use chain_crypto::SecretKey;
+    use chain_impl_mockchain::{
+        block::{self, BlockVersion},
+        config::ConfigParam,
+        fragment::{ContentsBuilder, Fragment},
+        testing::{builders::BftBlockBuilder, data::LeaderPair, ConfigBuilder},
+    };
+    use std::fs;
+    use tempfile::TempDir;
+    use tracing::Span;
+
+    const SLOTS_PER_EPOCH: u32 = 4;
+    const EPOCH_STABILITY_DEPTH: u32 = 2;
+    const CACHE_CAPACITY: usize = 100;
+
+    struct TestChain {
+        block0: Block,
+        storage: Storage,
+        checkpoints: TempDir,
+        /// the refs of the blocks after the block0, by chain length
+        refs: Vec<Arc<Ref>>,
+    }
+
+    impl TestChain {
+        fn blockchain(&self, ledger_checkpoints: bool) -> Blockchain {
+            Blockchain::new(
+                self.block0.header().hash(),
+                self.storage.clone(),
+                CACHE_CAPACITY,
+                false,
+                ledger_checkpoints.then(|| self.checkpoints.path().to_path_buf()),
+            )
+        }
+
+        fn block_ref(&self, chain_length: usize) -> &Arc<Ref> {
+            &self.refs[chain_length - 1]
+        }
+    }
+
+    pub(in crate::blockchain) fn block0(leader: &LeaderPair) -> Block {
+        let mut config = ConfigBuilder::new()
+            .with_leaders(&[leader.id()])
+            .with_slots_per_epoch(SLOTS_PER_EPOCH)
+            .build();
+        config.push(ConfigParam::EpochStabilityDepth(EPOCH_STABILITY_DEPTH));
+        let mut contents = ContentsBuilder::new();
+        contents.push(Fragment::Initial(config));
+        block::builder(BlockVersion::Genesis, contents.into(), |header| {
+            Ok::<_, ()>(
+                header
+                    .set_genesis()
+                    .set_date(BlockDate {
+                        epoch: 0,
+                        slot_id: 0,
+                    })
+                    .into_unsigned_header()
+                    .unwrap()
+                    .generalize(),
+            )
+        })
+        .unwrap()
+    }
+
+    /// extend the main branch with one block per slot after `parent`
+    pub(in crate::blockchain) async fn extend_main_branch(
+        blockchain: &Blockchain,
+        leader: &LeaderPair,
+        mut parent: Arc<Ref>,
+        count: usize,
+    ) -> Vec<Arc<Ref>> {
+        let mut refs = Vec::with_capacity(count);
+        for _ in 0..count {
+            let block = BftBlockBuilder::new()
+                .with_parent_id(parent.hash())
+                .with_date(parent.block_date())
+                .with_chain_length(parent.chain_length().increase())
+                .build(leader, parent.ledger().era());
+            parent = blockchain
+                .handle_bootstrap_block(block, CheckHeaderProof::Enabled)
+                .await
+                .unwrap();
+            blockchain
+                .storage()
+                .put_tag(MAIN_BRANCH_TAG, parent.hash())
+                .unwrap();
+            refs.push(parent.clone());
+        }
+        refs
+    }
+
+    /// a chain of 14 blocks, 4 per epoch, with the checkpoints taken by the
+    /// garbage collection at the 10th block and at the 14th block
+    async fn chain_with_checkpoints() -> TestChain {
+        let leader = LeaderPair::new(SecretKey::generate(rand::thread_rng()));
+        let mut chain = TestChain {
+            block0: block0(&leader),
+            storage: Storage::memory(Span::none()).unwrap(),
+            checkpoints: tempfile::tempdir().unwrap(),
+            refs: Vec::new(),
+        };
+        let blockchain = chain.blockchain(true);
+        let block0_ref = blockchain
+            .load_from_block0(chain.block0.clone())
+            .await
+            .unwrap()
+            .get_ref()
+            .await;
+
+        chain.refs = extend_main_branch(&blockchain, &leader, block0_ref, 10).await;
+        blockchain.gc(chain.block_ref(10).clone()).await.unwrap();
+        let more_refs =
+            extend_main_branch(&blockchain, &leader, chain.block_ref(10).clone(), 4).await;
+        chain.refs.extend(more_refs);
+        blockchain.gc(chain.block_ref(14).clone()).await.unwrap();
+        chain
+    }
+
+    async fn load_from_storage(chain: &TestChain, blockchain: &Blockchain) -> Arc<Ref> {
+        blockchain
+            .load_from_storage(chain.block0.clone())
+            .await
+            .unwrap()
+            .get_ref()
+            .await
+    }
+
+    #[tokio::test]
+    async fn checkpoints_are_taken_at_the_last_stable_block_of_the_previous_epoch() {
+        let chain = chain_with_checkpoints().await;
+
+        // the 7th and 11th blocks are the last ones of the epochs 1 and 2
+        let saved = list_ledger_checkpoints(chain.checkpoints.path()).unwrap();
+        let blocks: Vec<_> = saved
+            .iter()
+            .map(|path| LedgerCheckpoint::read_from_file(path).unwrap().block)
+            .collect();
+        assert_eq!(
+            blocks,
+            vec![chain.block_ref(11).hash(), chain.block_ref(7).hash()]
+        );
+    }
+
+    #[tokio::test]
+    async fn restoring_the_latest_checkpoint_gives_the_ledger_of_the_replayed_blocks() {
+        let chain = chain_with_checkpoints().await;
+        let replayed = load_from_storage(&chain, &chain.blockchain(false)).await;
+
+        let blockchain = chain.blockchain(true);
+        let restored = load_from_storage(&chain, &blockchain).await;
+
+        assert_eq!(restored.hash(), chain.block_ref(14).hash());
+        assert_eq!(restored.hash(), replayed.hash());
+        assert_eq!(restored.ledger(), replayed.ledger());
+        // the blocks before the latest checkpoint were not replayed
+        assert!(blockchain
+            .ref_cache
+            .get(chain.block_ref(11).hash())
+            .await
+            .is_some());
+        assert!(blockchain
+            .ref_cache
+            .get(chain.block_ref(10).hash())
+            .await
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn refs_not_in_the_cache_are_rebuilt_from_the_storage() {
+        let chain = chain_with_checkpoints().await;
+        let blockchain = chain.blockchain(true);
+        load_from_storage(&chain, &blockchain).await;
+
+        // restored from the checkpoint at the 11th block, the blocks after
+        // it are evicted from the cache
+        for chain_length in [12, 13] {
+            let block_id = chain.block_ref(chain_length).hash();
+            assert!(blockchain.ref_cache.remove(block_id).await.is_some());
+        }
+
+        let block_id = chain.block_ref(13).hash();
+        let rebuilt = blockchain
+            .get_ref(block_id)
+            .await
+            .unwrap()
+            .expect("the block is in the storage");
+        assert_eq!(rebuilt.hash(), block_id);
+        assert_eq!(rebuilt.chain_length(), chain.block_ref(13).chain_length());
+        assert_eq!(rebuilt.ledger(), chain.block_ref(13).ledger());
+        assert!(blockchain.ref_cache.get(block_id).await.is_some());
+        assert!(blockchain
+            .ref_cache
+            .get(chain.block_ref(12).hash())
+            .await
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn refs_deeper_than_the_epoch_stability_depth_are_not_rebuilt() {
+        let chain = chain_with_checkpoints().await;
+        let blockchain = chain.blockchain(true);
+        load_from_storage(&chain, &blockchain).await;
+
+        // the 10th block is in the storage but more than 2 blocks below the
+        // tip, its state would be rebuilt from the block0
+        let block_id = chain.block_ref(10).hash();
+        assert!(blockchain.storage().block_exists(block_id).unwrap());
+        assert!(blockchain.get_ref(block_id).await.unwrap().is_none());
+        assert!(blockchain
+            .ref_cache
+            .get(chain.block_ref(1).hash())
+            .await
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn corrupted_checkpoints_are_skipped() {
+        let chain = chain_with_checkpoints().await;
+        let replayed = load_from_storage(&chain, &chain.blockchain(false)).await;
+
+        let latest = &list_ledger_checkpoints(chain.checkpoints.path()).unwrap()[0];
+        let mut bytes = fs::read(latest).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 1;
+        fs::write(latest, bytes).unwrap();
+
+        let blockchain = chain.blockchain(true);
+        let restored = load_from_storage(&chain, &blockchain).await;
+
+        assert_eq!(restored.hash(), replayed.hash());
+        assert_eq!(restored.ledger(), replayed.ledger());
+        // the blocks were replayed from the previous checkpoint
+        assert!(blockchain
+            .ref_cache
+            .get(chain.block_ref(7).hash())
+            .await
+            .is_some());
+        assert!(blockchain
+            .ref_cache
+            .get(chain.block_ref(6).hash())
+            .await
+            .is_none());
+    }
+}
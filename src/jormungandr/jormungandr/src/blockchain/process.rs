@@ -20,7 +20,10 @@ use crate::{
 };
 use chain_core::property::{Block as _, Header as _};
 use futures::prelude::*;
-use std::{sync::Arc, time::Duration};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tracing::{span, Level};
 use tracing_futures::Instrument;
 
@@ -34,6 +37,10 @@ const DEFAULT_TIMEOUT_PROCESS_ANNOUNCEMENT: u64 = 5;
 const DEFAULT_TIMEOUT_PROCESS_BLOCKS: u64 = 60;
 const DEFAULT_TIMEOUT_PROCESS_HEADERS: u64 = 60;
 
+// How long to wait for a batch of requested blocks before resuming
+// the processing of a header stream that has more headers.
+const BLOCK_BATCH_ARRIVAL_TIMEOUT: Duration = Duration::from_secs(15);
+
 const PULL_HEADERS_SCHEDULER_CONFIG: FireForgetSchedulerConfig = FireForgetSchedulerConfig {
     max_running: 16,
     max_running_same_task: 2,
@@ -282,9 +289,10 @@ impl Process {
                 let _enter = span.enter();
                 tracing::debug!("receiving header stream from network");
 
-                self.service_info.timeout_spawn(
+                // the stream is processed in batches that may wait for the
+                // blocks of the previous batch, each of them has its own timeout
+                self.service_info.spawn(
                     "process network headers",
-                    Duration::from_secs(DEFAULT_TIMEOUT_PROCESS_HEADERS),
                     process_chain_headers(
                         blockchain,
                         handle,
@@ -301,15 +309,23 @@ impl Process {
     fn start_garbage_collector(&self, info: &TokioServiceInfo) {
         let blockchain = self.blockchain.clone();
         let tip = self.blockchain_tip.clone();
+        let stats_counter = self.stats_counter.clone();
 
-        async fn blockchain_gc(blockchain: Blockchain, tip: Tip) -> chain::Result<()> {
-            blockchain.gc(tip.get_ref().await).await
+        async fn blockchain_gc(
+            blockchain: Blockchain,
+            tip: Tip,
+            stats_counter: Metrics,
+        ) -> chain::Result<()> {
+            if let Some(flush_time) = blockchain.gc(tip.get_ref().await).await? {
+                stats_counter.add_storage_flush_time(flush_time);
+            }
+            Ok(())
         }
 
         info.run_periodic_fallible(
             "collect stale branches",
             self.garbage_collection_interval,
-            move || blockchain_gc(blockchain.clone(), tip.clone()),
+            move || blockchain_gc(blockchain.clone(), tip.clone(), stats_counter.clone()),
         )
     }
 }
@@ -447,6 +463,7 @@ async fn process_network_blocks(
         let (maybe_block, stream_tail) = stream.into_future().await;
         match maybe_block {
             Some(block) => {
+                let started = Instant::now();
                 let res = process_network_block(
                     &blockchain,
                     block.clone(),
@@ -456,6 +473,7 @@ async fn process_network_blocks(
                 .await;
                 match res {
                     Ok(Some(r)) => {
+                        stats_counter.add_block_validation_time(started.elapsed());
                         stats_counter.add_block_recv_cnt(1);
                         stream = stream_tail;
                         candidate = Some(r);
@@ -578,24 +596,46 @@ async fn process_chain_headers(
     mut pull_headers_scheduler: PullHeadersScheduler,
     mut network_msg_box: MessageBox<NetworkMsg>,
 ) {
-    let (stream, reply) = handle.into_stream_and_reply();
-    match candidate::advance_branch(blockchain, stream).await {
-        Err(e) => {
-            tracing::info!(
-                reason = %e,
-                "error processing an incoming header stream"
-            );
-            reply.reply_error(chain_header_error_into_reply(e));
-        }
-        Ok((header_ids, _maybe_remainder)) => {
-            header_ids
-                .iter()
-                .try_for_each(|header_id| pull_headers_scheduler.declare_completed(*header_id))
-                .unwrap_or_else(
-                    |e| tracing::error!(reason = ?e, "get blocks schedule completion failed"),
+    let (mut stream, reply) = handle.into_stream_and_reply();
+    let mut reply = Some(reply);
+    loop {
+        let advance = tokio::time::timeout(
+            Duration::from_secs(DEFAULT_TIMEOUT_PROCESS_HEADERS),
+            candidate::advance_branch(blockchain.clone(), stream),
+        );
+        match advance.await {
+            Err(_) => {
+                tracing::info!("timed out processing a batch of an incoming header stream");
+                if let Some(reply) = reply.take() {
+                    reply.reply_error(intercom::Error::aborted(
+                        "timed out processing the header stream",
+                    ));
+                }
+                break;
+            }
+            Ok(Err(e)) => {
+                tracing::info!(
+                    reason = %e,
+                    "error processing an incoming header stream"
                 );
+                if let Some(reply) = reply.take() {
+                    reply.reply_error(chain_header_error_into_reply(e));
+                }
+                break;
+            }
+            Ok(Ok((header_ids, maybe_remainder))) => {
+                header_ids
+                    .iter()
+                    .try_for_each(|header_id| pull_headers_scheduler.declare_completed(*header_id))
+                    .unwrap_or_else(
+                        |e| tracing::error!(reason = ?e, "get blocks schedule completion failed"),
+                    );
+
+                let last_header_id = match header_ids.last() {
+                    Some(header_id) => *header_id,
+                    None => break,
+                };
 
-            if !header_ids.is_empty() {
                 network_msg_box
                     .send(NetworkMsg::GetBlocks(header_ids))
                     .await
@@ -603,14 +643,56 @@ async fn process_chain_headers(
                     .map(|_| ())
                     .unwrap();
 
-                reply.reply_ok(())
-                // TODO: if the stream is not ended, resume processing
-                // after more blocks arrive
+                if let Some(reply) = reply.take() {
+                    reply.reply_ok(())
+                }
+
+                // The stream was cut at the batch size limit. The remaining
+                // headers can only land on the chain once the blocks of
+                // this batch are stored, so resume from there.
+                match maybe_remainder {
+                    Some(remainder) => {
+                        if !wait_for_block(&blockchain, last_header_id).await {
+                            tracing::info!(
+                                block = %last_header_id,
+                                "requested blocks did not arrive, dropping the rest of the header stream"
+                            );
+                            break;
+                        }
+                        stream = remainder;
+                    }
+                    None => break,
+                }
             }
         }
     }
 }
 
+/// Waits until the block is in the storage, giving up after
+/// `BLOCK_BATCH_ARRIVAL_TIMEOUT`. The storage is checked again each time
+/// the block processing stores a new block.
+async fn wait_for_block(blockchain: &Blockchain, block_id: HeaderHash) -> bool {
+    let arrival = async {
+        loop {
+            // taken before checking the storage so that a block stored in
+            // between is not missed
+            let block_stored = blockchain.block_stored();
+            match blockchain.storage().block_exists(block_id) {
+                Ok(true) => return true,
+                Ok(false) => {}
+                Err(e) => {
+                    tracing::error!(reason = %e, "cannot check the storage for a block");
+                    return false;
+                }
+            }
+            block_stored.await;
+        }
+    };
+    tokio::time::timeout(BLOCK_BATCH_ARRIVAL_TIMEOUT, arrival)
+        .await
+        .unwrap_or(false)
+}
+
 fn network_block_error_into_reply(err: chain::Error) -> intercom::Error {
     use super::chain::Error::*;
 
@@ -636,3 +718,110 @@ fn chain_header_error_into_reply(err: candidate::Error) -> intercom::Error {
         HeaderChainVerificationFailed(e) => intercom::Error::invalid_argument(e),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blockchain::{
+        chain::tests::{block0, extend_main_branch},
+        Storage,
+    };
+    use chain_crypto::SecretKey;
+    use chain_impl_mockchain::testing::data::LeaderPair;
+    use tracing::Span;
+
+    const CACHE_CAPACITY: usize = 100;
+
+    /// Applies the blocks requested by the header processing, taking them
+    /// from the `source` chain. Returns the number of requests once the
+    /// header processing is over.
+    async fn serve_blocks(
+        node: Blockchain,
+        source: Blockchain,
+        mut requests: MessageQueue<NetworkMsg>,
+        delay: Duration,
+    ) -> usize {
+        let mut count = 0;
+        while let Some(msg) = requests.next().await {
+            let block_ids = match msg {
+                NetworkMsg::GetBlocks(block_ids) => block_ids,
+                _ => continue,
+            };
+            count += 1;
+            tokio::time::sleep(delay).await;
+            for block_id in block_ids {
+                let block = source.storage().get(block_id).unwrap().unwrap();
+                let parent_ref = match node
+                    .pre_check_header(block.header().clone(), false)
+                    .await
+                    .unwrap()
+                {
+                    PreCheckedHeader::HeaderWithCache { parent_ref, .. } => parent_ref,
+                    _ => panic!("the blocks are requested in order"),
+                };
+                let post_checked = node
+                    .post_check_header(
+                        block.header().clone(),
+                        parent_ref,
+                        CheckHeaderProof::Enabled,
+                    )
+                    .await
+                    .unwrap();
+                node.apply_and_store_block(post_checked, block)
+                    .await
+                    .unwrap();
+            }
+        }
+        count
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn header_streams_are_processed_batch_by_batch_past_the_timeout() {
+        let leader = LeaderPair::new(SecretKey::generate(rand::thread_rng()));
+        let block0 = block0(&leader);
+        let blockchain =
+            |storage| Blockchain::new(block0.header().hash(), storage, CACHE_CAPACITY, false, None);
+
+        let source = blockchain(Storage::memory(Span::none()).unwrap());
+        let block0_ref = source
+            .load_from_block0(block0.clone())
+            .await
+            .unwrap()
+            .get_ref()
+            .await;
+        // 7 batches, the blocks of each of them arriving after 10 seconds:
+        // the whole stream takes longer than the processing timeout
+        let refs = extend_main_branch(&source, &leader, block0_ref, 6 * 32 + 8).await;
+        let node = blockchain(Storage::memory(Span::none()).unwrap());
+        node.load_from_block0(block0.clone()).await.unwrap();
+
+        let (handle, mut sink, reply_future) = intercom::stream_request(32);
+        let headers: Vec<_> = refs
+            .iter()
+            .map(|block_ref| Ok::<_, intercom::Error>(block_ref.header().clone()))
+            .collect();
+        let feed = tokio::spawn(async move {
+            sink.send_all(&mut stream::iter(headers)).await.unwrap();
+        });
+        let (network_msg_box, requests) = async_msg::channel(32);
+        let served = tokio::spawn(serve_blocks(
+            node.clone(),
+            source,
+            requests,
+            Duration::from_secs(10),
+        ));
+        let scheduler = FireForgetSchedulerFuture::new(
+            &PULL_HEADERS_SCHEDULER_CONFIG,
+            |_: HeaderHash, _: NodeId, _: Checkpoints| {},
+        );
+
+        process_chain_headers(node.clone(), handle, scheduler.scheduler(), network_msg_box).await;
+
+        feed.await.unwrap();
+        reply_future.await.unwrap();
+        assert_eq!(served.await.unwrap(), 7);
+        for block_ref in &refs {
+            assert!(node.storage().block_exists(block_ref.hash()).unwrap());
+        }
+    }
+}
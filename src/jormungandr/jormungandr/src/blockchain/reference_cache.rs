@@ -53,4 +53,11 @@ impl RefCache {
         let mut guard = self.inner.lock().await;
         guard.get(&key).map(Arc::clone)
     }
+
+    /// remove the [`Ref`] from the cache, as if it was evicted
+    #[cfg(test)]
+    pub async fn remove(&self, key: HeaderHash) -> Option<Arc<Ref>> {
+        let mut guard = self.inner.lock().await;
+        guard.pop(&key)
+    }
 }
@@ -6,9 +6,13 @@ use chain_core::{
     packer::Codec,
     property::{Deserialize, ReadError, Serialize, WriteError},
 };
-use chain_storage::{BlockInfo, BlockStore, Error as StorageError};
+use chain_storage::{BlockInfo, BlockStore, Error as StorageError, StorageEngine};
 use futures::prelude::*;
-use std::{convert::identity, path::Path};
+use std::{
+    convert::identity,
+    path::Path,
+    time::{Duration, Instant},
+};
 use thiserror::Error;
 use tracing::Span;
 
@@ -63,8 +67,12 @@ enum StreamingError {
 }
 
 impl Storage {
-    pub fn file<P: AsRef<Path>>(path: P, span: Span) -> Result<Self, Error> {
-        let storage = BlockStore::file(path, HeaderHash::zero_hash().as_bytes().to_vec())?;
+    pub fn file<P: AsRef<Path>>(path: P, engine: StorageEngine, span: Span) -> Result<Self, Error> {
+        let storage = BlockStore::file_with_engine(
+            path,
+            HeaderHash::zero_hash().as_bytes().to_vec(),
+            engine,
+        )?;
         Ok(Storage { storage, span })
     }
 
@@ -342,12 +350,18 @@ impl Storage {
         .map_err(Error::Deserialize)
     }
 
-    pub fn gc(&self, threshold_depth: u32, main_branch_tip: &[u8]) -> Result<(), Error> {
+    /// Returns the time spent flushing blocks to the permanent store, if the
+    /// chain is long enough for blocks to be flushed.
+    pub fn gc(
+        &self,
+        threshold_depth: u32,
+        main_branch_tip: &[u8],
+    ) -> Result<Option<Duration>, Error> {
         let _enter = self.span.enter();
         let main_info = self.storage.get_block_info(main_branch_tip)?;
         let threshold_length = match main_info.chain_length().checked_sub(threshold_depth) {
             Some(result) => result,
-            None => return Ok(()),
+            None => return Ok(None),
         };
 
         tracing::debug!(
@@ -376,16 +390,19 @@ impl Storage {
         let to_block_info = self
             .storage
             .get_nth_ancestor(main_branch_tip, threshold_depth)?;
+        let started = Instant::now();
         let blocks_flushed = self
             .storage
             .flush_to_permanent_store(to_block_info.id().as_ref(), MINIMUM_BLOCKS_TO_FLUSH)?;
+        let flush_time = started.elapsed();
 
         tracing::debug!(
-            "flushed all blocks ({}) up to {} to the permanent store",
+            "flushed all blocks ({}) up to {} to the permanent store in {:?}",
             blocks_flushed,
-            HeaderHash::hash_bytes(to_block_info.id().as_ref())
+            HeaderHash::hash_bytes(to_block_info.id().as_ref()),
+            flush_time
         );
 
-        Ok(())
+        Ok(Some(flush_time))
     }
 }
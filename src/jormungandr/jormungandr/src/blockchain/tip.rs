@@ -9,7 +9,7 @@ use crate::{
     utils::async_msg::{self, MessageBox, MessageQueue},
 };
 use chain_core::property::{Block as _, Fragment as _};
-use chain_impl_mockchain::block::Block;
+use chain_impl_mockchain::{block::Block, fee::LinearFee};
 use futures::prelude::*;
 use jormungandr_lib::interfaces::FragmentStatus;
 use std::{sync::Arc, time::Duration};
@@ -167,6 +167,11 @@ impl TipUpdater {
 
                 self.stats_counter.set_tip_block(&block, &candidate);
 
+                let linear_fees = &candidate.ledger().settings().linear_fees;
+                if *linear_fees != tip_ref.ledger().settings().linear_fees {
+                    self.try_request_fee_update(linear_fees.clone())?;
+                }
+
                 if let Some(ref mut msg_box) = self.watch_mbox {
                     tracing::debug!("sending new tip to watch subscribers {}", candidate_hash);
 
@@ -201,6 +206,19 @@ impl TipUpdater {
         Ok(())
     }
 
+    /// the mempool computes the fees of the fragments with the fee settings of
+    /// the ledger of the tip
+    fn try_request_fee_update(
+        &mut self,
+        linear_fees: LinearFee,
+    ) -> Result<(), async_msg::TrySendError<TransactionMsg>> {
+        if let Some(ref mut mbox) = self.fragment_mbox {
+            mbox.try_send(TransactionMsg::SetLinearFees(linear_fees))?;
+        }
+
+        Ok(())
+    }
+
     /// this function will re-process the tip against the different branches.
     /// this is because a branch may have become more interesting with time
     /// moving forward and branches may have been dismissed
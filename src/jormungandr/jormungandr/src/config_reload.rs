@@ -0,0 +1,577 @@
+//! Reloading of the node configuration file while the node is running.
+//!
+//! Only some of the settings can change without restarting the node: the
+//! reload applies the changes to those and reports the other changes as
+//! requiring a restart.
+
+use crate::{
+    intercom::{TopologyMsg, TransactionMsg},
+    network,
+    settings::{
+        logging::{LogLevelHandle, LogSettings},
+        start::{
+            self,
+            config::Config,
+            network::{Configuration as NetworkConfig, PeerResolveError, Protocol, TrustedPeer},
+            ConfigFile, DEFAULT_NO_BLOCKCHAIN_UPDATES_WARNING_INTERVAL,
+        },
+    },
+    topology::{layers, Gossips},
+    utils::async_msg::{MessageBox, SendError},
+};
+use futures::SinkExt;
+use jormungandr_lib::interfaces::ConfigReloadReport;
+use serde_yaml::{Mapping, Value};
+use std::{borrow::Cow, net::SocketAddr, path::PathBuf, sync::Arc, time::Duration};
+use thiserror::Error;
+use tokio::sync::{watch, Mutex};
+use tracing::Span;
+use tracing_futures::Instrument;
+
+const LOG_LEVEL: &str = "log.level";
+const MEMPOOL: &str = "mempool";
+const TRUSTED_PEERS: &str = "p2p.bootstrap.trusted_peers";
+const LAYERS: &str = "p2p.layers";
+const NO_BLOCKCHAIN_UPDATES_WARNING_INTERVAL: &str = "no_blockchain_updates_warning_interval";
+
+/// Fields of the configuration applied without restarting the node, along
+/// with the fields of the sections listed here.
+///
+/// The `rest.cors` settings are not in the list: the CORS filter wraps the
+/// REST API when the server starts listening. Trusted peers can only be
+/// added: removing or changing one requires a restart.
+const RELOADABLE_FIELDS: &[&str] = &[
+    LOG_LEVEL,
+    "mempool.pool_max_entries",
+    "mempool.account_lane_max_entries",
+    "mempool.eviction",
+    TRUSTED_PEERS,
+    LAYERS,
+    NO_BLOCKCHAIN_UPDATES_WARNING_INTERVAL,
+];
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Cannot read the node configuration file: {0}")]
+    ConfigIo(#[from] std::io::Error),
+    #[error("Error while parsing the node configuration file: {0}")]
+    Config(#[from] serde_yaml::Error),
+    #[error("Invalid node configuration: {0}")]
+    Invalid(#[from] InvalidConfig),
+    #[error("cannot change the log level")]
+    LogLevel(#[from] tracing_subscriber::reload::Error),
+    #[error("cannot send the new limits to the mempool")]
+    MsgSend(#[from] SendError),
+    #[error("cannot send the new layers to the topology")]
+    TopologyMsgSend(#[source] SendError),
+}
+
+/// Values of the reloaded settings that the node cannot run with.
+#[derive(Debug, Error)]
+pub enum InvalidConfig {
+    #[error("`mempool.pool_max_entries` must be greater than 0")]
+    PoolMaxEntries,
+    #[error("`mempool.account_lane_max_entries` must be greater than 0")]
+    AccountLaneMaxEntries,
+    #[error("`no_blockchain_updates_warning_interval` must be greater than 0")]
+    WarningInterval,
+    #[error("cannot resolve the address of the trusted peer {address}")]
+    TrustedPeer {
+        address: String,
+        #[source]
+        source: PeerResolveError,
+    },
+    #[error("trusted peer {addr} needs an `id` to verify its TLS certificate")]
+    TlsTrustedPeerWithoutId { addr: SocketAddr },
+    #[error("trusted peer {addr} uses the NTT protocol, which p2p.tls cannot secure")]
+    TlsNttTrustedPeer { addr: SocketAddr },
+    #[error(transparent)]
+    Layers(#[from] layers::ParseError),
+}
+
+#[derive(Clone)]
+pub struct ConfigReloader {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    path: PathBuf,
+    log_level_overridden: bool,
+    log_level: LogLevelHandle,
+    transaction_task: MessageBox<TransactionMsg>,
+    topology_task: MessageBox<TopologyMsg>,
+    no_blockchain_updates_warning_interval: watch::Sender<Duration>,
+    running: Mutex<Running>,
+}
+
+struct Running {
+    // content of the configuration file the node runs with, only the
+    // applied changes are merged into it
+    content: Value,
+    // the trusted peers already known to the node are not contacted again
+    network: NetworkConfig,
+}
+
+impl ConfigReloader {
+    pub fn new(
+        config_file: ConfigFile,
+        log_level: LogLevelHandle,
+        transaction_task: MessageBox<TransactionMsg>,
+        topology_task: MessageBox<TopologyMsg>,
+        network: NetworkConfig,
+        no_blockchain_updates_warning_interval: watch::Sender<Duration>,
+    ) -> Self {
+        ConfigReloader {
+            inner: Arc::new(Inner {
+                path: config_file.path,
+                log_level_overridden: config_file.log_level_overridden,
+                log_level,
+                transaction_task,
+                topology_task,
+                no_blockchain_updates_warning_interval,
+                running: Mutex::new(Running {
+                    content: config_file.content,
+                    network,
+                }),
+            }),
+        }
+    }
+
+    /// Reads the configuration file again and applies the changes that do
+    /// not require a restart of the node. Nothing is applied if the file is
+    /// not a valid configuration.
+    pub async fn reload(&self) -> Result<ConfigReloadReport, Error> {
+        let contents = tokio::fs::read_to_string(&self.inner.path).await?;
+        let config: Config = serde_yaml::from_str(&contents)?;
+        let content: Value = serde_yaml::from_str(&contents)?;
+
+        // concurrent reloads would race to update the running configuration
+        let mut running = self.inner.running.lock().await;
+
+        let mut report = ConfigReloadReport::default();
+        for field in changed_fields(&running.content, &content) {
+            if !RELOADABLE_FIELDS
+                .iter()
+                .any(|reloadable| is_in(&field, reloadable))
+            {
+                report.restart_required.push(field);
+            } else if field == LOG_LEVEL && self.inner.log_level_overridden {
+                report.overridden.push(field);
+            } else if is_in(&field, TRUSTED_PEERS)
+                && trusted_peers_removed(&running.content, &content)
+            {
+                report.restart_required.push(field);
+            } else {
+                report.applied.push(field);
+            }
+        }
+        let applied = |section| report.applied.iter().any(|field| is_in(field, section));
+
+        // nothing is applied unless all the changes are valid
+        validate(&config)?;
+        let new_trusted_peers = if applied(TRUSTED_PEERS) {
+            let trusted_peers = config.p2p.bootstrap.trusted_peers.as_deref().unwrap_or(&[]);
+            new_trusted_peers(&running.network, trusted_peers)?
+        } else {
+            Vec::new()
+        };
+        let layers = if applied(LAYERS) {
+            Some(start::layers_config(config.p2p.layers.clone()).map_err(InvalidConfig::from)?)
+        } else {
+            None
+        };
+
+        if applied(LOG_LEVEL) {
+            let level = config
+                .log
+                .and_then(|log| log.level)
+                .unwrap_or_else(|| LogSettings::default().level);
+            self.inner.log_level.reload(level)?;
+        }
+        if applied(MEMPOOL) {
+            let mempool = config.mempool;
+            self.inner
+                .transaction_task
+                .clone()
+                .send(TransactionMsg::SetLimits {
+                    pool_max_entries: mempool.pool_max_entries.into(),
+                    account_lane_max_entries: mempool.account_lane_max_entries,
+                    eviction: mempool.eviction,
+                })
+                .await?;
+        }
+        if applied(NO_BLOCKCHAIN_UPDATES_WARNING_INTERVAL) {
+            let interval = config
+                .no_blockchain_updates_warning_interval
+                .map(Into::into)
+                .unwrap_or(DEFAULT_NO_BLOCKCHAIN_UPDATES_WARNING_INTERVAL);
+            // fails only if the stuck notifier is no longer running
+            let _ = self
+                .inner
+                .no_blockchain_updates_warning_interval
+                .send(interval);
+        }
+        if let Some(layers) = layers {
+            self.inner
+                .topology_task
+                .clone()
+                .send(TopologyMsg::SetLayers(layers))
+                .await
+                .map_err(Error::TopologyMsgSend)?;
+        }
+        if !new_trusted_peers.is_empty() {
+            running
+                .network
+                .trusted_peers
+                .extend(new_trusted_peers.iter().cloned());
+            self.add_peers_of(running.network.clone(), new_trusted_peers);
+        }
+
+        for field in &report.applied {
+            set_field(
+                &mut running.content,
+                field,
+                field_value(&content, field).cloned(),
+            );
+        }
+
+        tracing::info!(
+            applied = ?report.applied,
+            restart_required = ?report.restart_required,
+            overridden = ?report.overridden,
+            "reloaded the node configuration"
+        );
+        Ok(report)
+    }
+
+    /// Retrieves in the background the peers known to the new trusted peers
+    /// and passes them to the topology, as when bootstrapping.
+    fn add_peers_of(&self, config: NetworkConfig, trusted_peers: Vec<TrustedPeer>) {
+        let mut topology_task = self.inner.topology_task.clone();
+        let span = Span::current();
+        tokio::spawn(
+            async move {
+                let peers =
+                    network::peers_from_trusted_peers(&config, &trusted_peers, &span).await;
+                let gossips = peers.into_iter().collect::<Vec<_>>();
+                if let Err(e) = topology_task
+                    .send(TopologyMsg::AcceptGossip(Gossips::from(gossips)))
+                    .await
+                {
+                    tracing::error!(reason = %e, "cannot send the peers of the new trusted peers to the topology");
+                }
+            }
+            .in_current_span(),
+        );
+    }
+}
+
+/// Checks the values of the reloaded settings that deserialize but that the
+/// node cannot run with.
+fn validate(config: &Config) -> Result<(), InvalidConfig> {
+    let mempool = &config.mempool;
+    if usize::from(mempool.pool_max_entries) == 0 {
+        return Err(InvalidConfig::PoolMaxEntries);
+    }
+    if mempool.account_lane_max_entries == Some(0) {
+        return Err(InvalidConfig::AccountLaneMaxEntries);
+    }
+    if let Some(interval) = config.no_blockchain_updates_warning_interval {
+        if Duration::from(interval).is_zero() {
+            return Err(InvalidConfig::WarningInterval);
+        }
+    }
+    Ok(())
+}
+
+/// Resolves the trusted peers of the configuration, returning the ones the
+/// node does not know yet.
+fn new_trusted_peers(
+    network: &NetworkConfig,
+    trusted_peers: &[jormungandr_lib::interfaces::TrustedPeer],
+) -> Result<Vec<TrustedPeer>, InvalidConfig> {
+    let mut new_peers = Vec::new();
+    for config_peer in trusted_peers {
+        let peer =
+            TrustedPeer::resolve(config_peer).map_err(|source| InvalidConfig::TrustedPeer {
+                address: config_peer.address.to_string(),
+                source,
+            })?;
+        if network.tls.is_some() && peer.id.is_none() {
+            return Err(InvalidConfig::TlsTrustedPeerWithoutId { addr: peer.addr });
+        }
+        if network.tls.is_some() && peer.protocol == Some(Protocol::Ntt) {
+            return Err(InvalidConfig::TlsNttTrustedPeer { addr: peer.addr });
+        }
+        let known = network
+            .trusted_peers
+            .iter()
+            .chain(&new_peers)
+            .any(|known| known.addr == peer.addr);
+        if !known {
+            new_peers.push(peer);
+        }
+    }
+    Ok(new_peers)
+}
+
+/// Whether trusted peers of the running configuration file are missing from
+/// the new one. The node contacts the trusted peers which are added, but
+/// keeps running with the ones it started with.
+fn trusted_peers_removed(running: &Value, new: &Value) -> bool {
+    fn peers(content: &Value) -> &[Value] {
+        field_value(content, TRUSTED_PEERS)
+            .and_then(Value::as_sequence)
+            .map_or(&[][..], Vec::as_slice)
+    }
+    let new_peers = peers(new);
+    peers(running).iter().any(|peer| !new_peers.contains(peer))
+}
+
+/// Whether the field at the dotted path `field` is `section` or one of its
+/// fields.
+fn is_in(field: &str, section: &str) -> bool {
+    field
+        .strip_prefix(section)
+        .map_or(false, |rest| rest.is_empty() || rest.starts_with('.'))
+}
+
+/// Reloads the node configuration each time the node receives SIGHUP.
+#[cfg(unix)]
+pub async fn reload_on_sighup(reloader: ConfigReloader) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    match signal(SignalKind::hangup()) {
+        Ok(mut hangup) => {
+            while hangup.recv().await.is_some() {
+                if let Err(e) = reloader.reload().await {
+                    tracing::error!(reason = %e, "failed to reload the node configuration");
+                }
+            }
+        }
+        Err(e) => tracing::warn!(reason = %e, "failed to install handler for SIGHUP"),
+    }
+    // the node must not stop along with this task
+    futures::future::pending().await
+}
+
+/// Lists the dotted paths of the fields that differ between two contents of
+/// the configuration file, descending into the sections. A missing section is
+/// the same as an empty one.
+fn changed_fields(running: &Value, new: &Value) -> Vec<String> {
+    let mut changed = Vec::new();
+    diff(running, new, None, &mut changed);
+    changed
+}
+
+fn diff(running: &Value, new: &Value, path: Option<&str>, changed: &mut Vec<String>) {
+    match (as_section(running), as_section(new)) {
+        (Some(running), Some(new)) => {
+            let added = new
+                .iter()
+                .map(|(key, _)| key)
+                .filter(|&key| !running.contains_key(key));
+            for key in running.iter().map(|(key, _)| key).chain(added) {
+                let name = key
+                    .as_str()
+                    .map(Cow::Borrowed)
+                    .unwrap_or_else(|| Cow::Owned(format!("{:?}", key)));
+                let field = match path {
+                    Some(path) => format!("{}.{}", path, name),
+                    None => name.into_owned(),
+                };
+                diff(
+                    running.get(key).unwrap_or(&Value::Null),
+                    new.get(key).unwrap_or(&Value::Null),
+                    Some(&field),
+                    changed,
+                );
+            }
+        }
+        _ => {
+            if running != new {
+                changed.push(path.unwrap_or_default().to_owned());
+            }
+        }
+    }
+}
+
+fn as_section(value: &Value) -> Option<Cow<'_, Mapping>> {
+    match value {
+        Value::Mapping(mapping) => Some(Cow::Borrowed(mapping)),
+        Value::Null => Some(Cow::Owned(Mapping::new())),
+        _ => None,
+    }
+}
+
+fn field_value<'a>(content: &'a Value, field: &str) -> Option<&'a Value> {
+    field
+        .split('.')
+        .try_fold(content, |section, name| section.get(name))
+}
+
+fn set_field(content: &mut Value, field: &str, value: Option<Value>) {
+    let mut names: Vec<&str> = field.split('.').collect();
+    let name = names.pop().expect("field paths are not empty");
+    let mut section = content;
+    for section_name in names {
+        let mapping = section_mut(section);
+        let key = Value::from(section_name);
+        if !mapping.contains_key(&key) {
+            mapping.insert(key.clone(), Value::Null);
+        }
+        section = mapping.get_mut(&key).expect("key was just inserted");
+    }
+    let mapping = section_mut(section);
+    match value {
+        Some(value) => {
+            mapping.insert(Value::from(name), value);
+        }
+        None => {
+            mapping.remove(&Value::from(name));
+        }
+    }
+}
+
+fn section_mut(value: &mut Value) -> &mut Mapping {
+    if !value.is_mapping() {
+        *value = Value::Mapping(Mapping::new());
+    }
+    match value {
+        Value::Mapping(mapping) => mapping,
+        _ => unreachable!(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changed_fields_are_listed_by_path() {
+        let running: Value = serde_yaml::from_str(
+            "
+log:
+  level: info
+mempool:
+  pool_max_entries: 100
+p2p:
+  bootstrap:
+    trusted_peers:
+      - address: /ip4/127.0.0.1/tcp/3000
+",
+        )
+        .unwrap();
+        let new: Value = serde_yaml::from_str(
+            "
+log:
+  level: debug
+mempool:
+  pool_max_entries: 100
+  eviction: lowest_fee
+p2p:
+  bootstrap:
+    trusted_peers: []
+no_blockchain_updates_warning_interval: 10m
+",
+        )
+        .unwrap();
+
+        assert_eq!(
+            changed_fields(&running, &new),
+            vec![
+                "log.level",
+                "mempool.eviction",
+                "p2p.bootstrap.trusted_peers",
+                "no_blockchain_updates_warning_interval",
+            ]
+        );
+        assert!(changed_fields(&new, &new).is_empty());
+    }
+
+    #[test]
+    fn applied_fields_are_set_in_the_running_content() {
+        let mut running: Value = serde_yaml::from_str("rest:\n  listen: 127.0.0.1:8443\n").unwrap();
+        let new: Value = serde_yaml::from_str("log:\n  level: warn\n").unwrap();
+
+        set_field(
+            &mut running,
+            LOG_LEVEL,
+            field_value(&new, LOG_LEVEL).cloned(),
+        );
+        set_field(
+            &mut running,
+            "rest.listen",
+            field_value(&new, "rest.listen").cloned(),
+        );
+
+        // an emptied section is the same as a missing one
+        assert!(changed_fields(&running, &new).is_empty());
+    }
+
+    #[test]
+    fn reloadable_sections_include_their_fields() {
+        assert!(is_in("p2p.layers", LAYERS));
+        assert!(is_in("p2p.layers.preferred_list.view_max", LAYERS));
+        assert!(is_in("mempool.eviction", MEMPOOL));
+        assert!(!is_in("p2p.layers_extra", LAYERS));
+        assert!(!is_in("p2p", LAYERS));
+    }
+
+    #[test]
+    fn removed_trusted_peers_are_detected() {
+        let content = |yaml| serde_yaml::from_str::<Value>(yaml).unwrap();
+        let one = content(
+            "
+p2p:
+  bootstrap:
+    trusted_peers:
+      - address: /ip4/127.0.0.1/tcp/3000
+",
+        );
+        let two = content(
+            "
+p2p:
+  bootstrap:
+    trusted_peers:
+      - address: /ip4/127.0.0.1/tcp/3000
+      - address: /ip4/127.0.0.2/tcp/3000
+",
+        );
+        let changed = content(
+            "
+p2p:
+  bootstrap:
+    trusted_peers:
+      - address: /ip4/127.0.0.1/tcp/3000
+        protocol: ntt
+",
+        );
+        let none = content("p2p:\n  bootstrap: {}\n");
+
+        assert!(!trusted_peers_removed(&one, &two));
+        assert!(!trusted_peers_removed(&none, &one));
+        assert!(trusted_peers_removed(&two, &one));
+        assert!(trusted_peers_removed(&one, &none));
+        assert!(trusted_peers_removed(&one, &changed));
+    }
+
+    #[test]
+    fn limits_the_node_cannot_run_with_are_rejected() {
+        let validate_yaml = |yaml| validate(&serde_yaml::from_str(yaml).unwrap());
+
+        assert!(validate_yaml("mempool:\n  pool_max_entries: 10\n").is_ok());
+        assert!(matches!(
+            validate_yaml("mempool:\n  pool_max_entries: 0\n"),
+            Err(InvalidConfig::PoolMaxEntries)
+        ));
+        assert!(matches!(
+            validate_yaml("mempool:\n  account_lane_max_entries: 0\n"),
+            Err(InvalidConfig::AccountLaneMaxEntries)
+        ));
+        assert!(matches!(
+            validate_yaml("no_blockchain_updates_warning_interval: 0s\n"),
+            Err(InvalidConfig::WarningInterval)
+        ));
+    }
+}
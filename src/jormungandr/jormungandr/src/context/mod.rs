@@ -1,5 +1,6 @@
 use crate::{
     blockchain::{Blockchain, Tip},
+    config_reload::ConfigReloader,
     diagnostic::Diagnostic,
     intercom::{NetworkMsg, TopologyMsg, TransactionMsg},
     leadership::Logs as LeadershipLogs,
@@ -162,4 +163,6 @@ pub struct FullContext {
     pub network_state: NetworkStateR,
     #[cfg(feature = "prometheus-metrics")]
     pub prometheus: Option<Arc<crate::metrics::backends::Prometheus>>,
+    /// not available if the node was started without a configuration file
+    pub config_reloader: Option<ConfigReloader>,
 }
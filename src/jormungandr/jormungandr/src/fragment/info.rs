@@ -0,0 +1,101 @@
+use crate::fragment::Fragment;
+use chain_core::property::Serialize;
+use chain_impl_mockchain::{
+    block::BlockDate,
+    fee::{FeeAlgorithm, LinearFee},
+    transaction::{InputEnum, Payload, Transaction, UnspecifiedAccountIdentifier, Witness},
+};
+
+/// Properties of a fragment used by the mempool policies and by the
+/// fragment selection algorithms.
+#[derive(Debug, Clone)]
+pub struct FragmentInfo {
+    /// name of the type of the fragment
+    pub kind: &'static str,
+    /// the first account the fragment spends from, along with the
+    /// spending counter lane used by its witness
+    pub account: Option<(UnspecifiedAccountIdentifier, usize)>,
+    /// the fee the ledger charges for the fragment. The difference between
+    /// the inputs and the outputs is not used: a transaction claiming to pay
+    /// more is not balanced and the ledger rejects it
+    pub fee: u64,
+    /// size of the serialized fragment
+    pub size: usize,
+    /// the last block date the fragment can be included at
+    pub valid_until: Option<BlockDate>,
+}
+
+impl FragmentInfo {
+    pub fn new(fragment: &Fragment, fees: &LinearFee) -> Self {
+        let size = fragment.serialized_size();
+        match fragment {
+            Fragment::Initial(_) => Self::without_transaction("initial", size),
+            Fragment::OldUtxoDeclaration(_) => {
+                Self::without_transaction("old_utxo_declaration", size)
+            }
+            Fragment::Evm(_) => Self::without_transaction("evm", size),
+            Fragment::Transaction(ref tx) => Self::from_transaction(fees, "transaction", tx, size),
+            Fragment::OwnerStakeDelegation(ref tx) => {
+                Self::from_transaction(fees, "owner_stake_delegation", tx, size)
+            }
+            Fragment::StakeDelegation(ref tx) => {
+                Self::from_transaction(fees, "stake_delegation", tx, size)
+            }
+            Fragment::PoolRegistration(ref tx) => {
+                Self::from_transaction(fees, "pool_registration", tx, size)
+            }
+            Fragment::PoolRetirement(ref tx) => {
+                Self::from_transaction(fees, "pool_retirement", tx, size)
+            }
+            Fragment::PoolUpdate(ref tx) => Self::from_transaction(fees, "pool_update", tx, size),
+            Fragment::UpdateProposal(ref tx) => {
+                Self::from_transaction(fees, "update_proposal", tx, size)
+            }
+            Fragment::UpdateVote(ref tx) => Self::from_transaction(fees, "update_vote", tx, size),
+            Fragment::VotePlan(ref tx) => Self::from_transaction(fees, "vote_plan", tx, size),
+            Fragment::VoteCast(ref tx) => Self::from_transaction(fees, "vote_cast", tx, size),
+            Fragment::VoteTally(ref tx) => Self::from_transaction(fees, "vote_tally", tx, size),
+            Fragment::MintToken(ref tx) => Self::from_transaction(fees, "mint_token", tx, size),
+            Fragment::EvmMapping(ref tx) => Self::from_transaction(fees, "evm_mapping", tx, size),
+            Fragment::MultisigRegistration(ref tx) => {
+                Self::from_transaction(fees, "multisig_registration", tx, size)
+            }
+        }
+    }
+
+    fn without_transaction(kind: &'static str, size: usize) -> Self {
+        FragmentInfo {
+            kind,
+            account: None,
+            fee: 0,
+            size,
+            valid_until: None,
+        }
+    }
+
+    fn from_transaction<P: Payload>(
+        fees: &LinearFee,
+        kind: &'static str,
+        tx: &Transaction<P>,
+        size: usize,
+    ) -> Self {
+        let slice = tx.as_slice();
+        let account = slice
+            .inputs_and_witnesses()
+            .iter()
+            .find_map(|(input, witness)| match (input.to_enum(), witness) {
+                (InputEnum::AccountInput(account, _), Witness::Account(counter, _))
+                | (InputEnum::AccountInput(account, _), Witness::Multisig(counter, _)) => {
+                    Some((account, counter.lane()))
+                }
+                _ => None,
+            });
+        FragmentInfo {
+            kind,
+            account,
+            fee: fees.calculate_tx(&slice).0,
+            size,
+            valid_until: Some(slice.valid_until()),
+        }
+    }
+}
@@ -1,8 +1,13 @@
 mod entry;
+mod info;
 mod logs;
 mod pool;
 mod process;
+mod replay;
 pub mod selection;
 
-pub use self::{entry::PoolEntry, logs::Logs, pool::Pool, process::Process};
+pub use self::{
+    entry::PoolEntry, logs::Logs, pool::Pool, process::Process,
+    replay::pending_fragments_from_persistent_log,
+};
 pub use crate::blockcfg::{Fragment, FragmentId};
@@ -3,7 +3,7 @@ use crate::{
     fragment::{
         selection::{
             FragmentSelectionAlgorithm, FragmentSelectionAlgorithmParams, FragmentSelectionResult,
-            OldestFirst,
+            HighestFeeFirst, OldestFirst, ReservedSpace, SenderRoundRobin,
         },
         Fragment, FragmentId, Logs,
     },
@@ -12,16 +12,22 @@ use crate::{
     utils::async_msg::MessageBox,
 };
 use chain_core::{packer::Codec, property::Serialize};
-use chain_impl_mockchain::{block::BlockDate, fragment::Contents, transaction::Transaction};
+use chain_impl_mockchain::{
+    block::BlockDate,
+    fee::{FeeAlgorithm, LinearFee},
+    fragment::Contents,
+    transaction::{Payload, Transaction},
+};
 use futures::{channel::mpsc::SendError, sink::SinkExt};
 use jormungandr_lib::{
     interfaces::{
-        BlockDate as BlockDateDto, FragmentLog, FragmentOrigin, FragmentRejectionReason,
-        FragmentStatus, FragmentsProcessingSummary, PersistentFragmentLog, RejectedFragmentInfo,
+        AccountPoolOccupancy, BlockDate as BlockDateDto, FragmentLog, FragmentOrigin,
+        FragmentRejectionReason, FragmentStatus, FragmentTypePoolOccupancy,
+        FragmentsProcessingSummary, PersistentFragmentLog, PoolEviction, RejectedFragmentInfo,
     },
     time::SecondsSinceUnixEpoch,
 };
-use std::mem;
+use std::{collections::HashSet, mem};
 use thiserror::Error;
 use tokio::{
     fs::File,
@@ -52,6 +58,9 @@ pub enum Error {
 impl Pool {
     pub fn new(
         max_entries: usize,
+        account_lane_max_entries: Option<usize>,
+        eviction: PoolEviction,
+        linear_fees: LinearFee,
         logs: Logs,
         network_msg_box: MessageBox<NetworkMsg>,
         persistent_log: Option<File>,
@@ -59,7 +68,12 @@ impl Pool {
     ) -> Self {
         Pool {
             logs,
-            pool: internal::Pool::new(max_entries),
+            pool: internal::Pool::with_policies(
+                max_entries,
+                account_lane_max_entries,
+                eviction,
+                linear_fees,
+            ),
             network_msg_box,
             persistent_log: persistent_log
                 .map(|file| BufWriter::with_capacity(DEFAULT_BUF_SIZE, file)),
@@ -92,6 +106,7 @@ impl Pool {
 
     async fn filter_fragment(
         &mut self,
+        origin: FragmentOrigin,
         fragment: &Fragment,
         id: FragmentId,
     ) -> Result<(), FragmentRejectionReason> {
@@ -100,12 +115,18 @@ impl Pool {
             return Err(FragmentRejectionReason::FragmentAlreadyInLog);
         }
 
-        if !is_fragment_valid(fragment) {
+        if !is_fragment_valid(fragment, self.pool.linear_fees()) {
             tracing::debug!("fragment is invalid, not including to the pool");
             return Err(FragmentRejectionReason::FragmentInvalid);
         }
 
-        if let Some(persistent_log) = self.persistent_log.as_mut() {
+        // a replayed fragment is already in the persistent log with the time
+        // it was first received, which its replay window starts from
+        let persistent_log = match origin {
+            FragmentOrigin::PersistentLog => None,
+            _ => self.persistent_log.as_mut(),
+        };
+        if let Some(persistent_log) = persistent_log {
             let entry = PersistentFragmentLog {
                 time: SecondsSinceUnixEpoch::now(),
                 fragment: fragment.clone(),
@@ -147,7 +168,11 @@ impl Pool {
         for (fragment, id) in fragments.by_ref() {
             let span = tracing::debug_span!("pool_incoming_fragment", fragment_id=?id);
 
-            match self.filter_fragment(&fragment, id).instrument(span).await {
+            match self
+                .filter_fragment(origin, &fragment, id)
+                .instrument(span)
+                .await
+            {
                 Err(reason @ FragmentRejectionReason::FragmentInvalid) => {
                     rejected.push(RejectedFragmentInfo { id, reason });
                     if fail_fast {
@@ -182,8 +207,45 @@ impl Pool {
         let span = tracing::trace_span!("pool_insert_fragment");
         let _enter = span.enter();
 
-        let mut fragments = filtered_fragments.into_iter();
-        let new_fragments = self.pool.insert_all(fragments.by_ref());
+        let mut new_fragments = Vec::new();
+        let mut evicted = HashSet::new();
+        for (fragment, id) in filtered_fragments {
+            if new_fragments.len() >= self.pool.max_entries() {
+                tracing::debug!(fragment_id=?id, "rejecting fragment due to pool overflow");
+                rejected.push(RejectedFragmentInfo {
+                    id,
+                    reason: FragmentRejectionReason::PoolOverflow,
+                });
+                continue;
+            }
+            match self.pool.insert(fragment.clone(), id) {
+                Ok(evicted_ids) => {
+                    evicted.extend(evicted_ids);
+                    new_fragments.push((fragment, id));
+                }
+                Err(reason) => {
+                    tracing::debug!(fragment_id=?id, ?reason, "fragment not accepted in the pool");
+                    rejected.push(RejectedFragmentInfo { id, reason });
+                }
+            }
+        }
+
+        // fragments of this batch may have been evicted by the next ones
+        let mut evicted_from_batch = Vec::new();
+        new_fragments.retain(|(_, id)| {
+            if evicted.remove(id) {
+                evicted_from_batch.push(*id);
+                false
+            } else {
+                true
+            }
+        });
+        for id in evicted_from_batch {
+            rejected.push(RejectedFragmentInfo {
+                id,
+                reason: FragmentRejectionReason::PoolOverflow,
+            });
+        }
         tracing::debug!(
             count = %new_fragments.len(),
             "received fragments were added to the pool"
@@ -194,6 +256,17 @@ impl Pool {
             .collect();
         self.logs.insert_all_pending(fragment_logs);
 
+        if !evicted.is_empty() {
+            tracing::debug!(count = %evicted.len(), "fragments were evicted from the full pool");
+            self.logs.modify_all(
+                evicted,
+                FragmentStatus::Rejected {
+                    reason: "evicted from the full mempool".to_owned(),
+                },
+                self.pool.date().unwrap_or_else(BlockDate::first).into(),
+            );
+        }
+
         self.update_metrics();
 
         let mut accepted = Vec::new();
@@ -208,14 +281,6 @@ impl Pool {
                 .map_err(Error::CannotPropagate)?;
         }
 
-        for (_, id) in fragments {
-            tracing::debug!(fragment_id=?id, "rejecting fragment due to pool overflow");
-            rejected.push(RejectedFragmentInfo {
-                id,
-                reason: FragmentRejectionReason::PoolOverflow,
-            });
-        }
-
         Ok(FragmentsProcessingSummary { accepted, rejected })
     }
 
@@ -227,9 +292,59 @@ impl Pool {
         };
         self.pool.remove_all(fragment_ids.iter());
         self.logs.modify_all(fragment_ids, status, date);
+
+        let expired = self.pool.remove_expired(date.into());
+        if !expired.is_empty() {
+            tracing::debug!(count = %expired.len(), "removing expired fragments from the pool");
+            self.logs.modify_all(
+                expired,
+                FragmentStatus::Rejected {
+                    reason: "fragment expired".to_owned(),
+                },
+                date,
+            );
+        }
         self.update_metrics();
     }
 
+    /// Changes the limits and the eviction policy of the pool, rejecting the
+    /// fragments evicted if the pool holds more than the new maximum.
+    pub fn set_limits(
+        &mut self,
+        max_entries: usize,
+        account_lane_max_entries: Option<usize>,
+        eviction: PoolEviction,
+    ) {
+        let evicted = self
+            .pool
+            .set_policies(max_entries, account_lane_max_entries, eviction);
+        if !evicted.is_empty() {
+            tracing::debug!(count = %evicted.len(), "fragments were evicted from the shrunk pool");
+            self.logs.modify_all(
+                evicted,
+                FragmentStatus::Rejected {
+                    reason: "evicted from the full mempool".to_owned(),
+                },
+                self.pool.date().unwrap_or_else(BlockDate::first).into(),
+            );
+        }
+        self.update_metrics();
+    }
+
+    /// Changes the fee settings the fragments are checked against, after
+    /// the ledger of the tip changed them.
+    pub fn set_linear_fees(&mut self, linear_fees: LinearFee) {
+        self.pool.set_linear_fees(linear_fees);
+    }
+
+    pub fn accounts_occupancy(&self) -> Vec<AccountPoolOccupancy> {
+        self.pool.accounts_occupancy()
+    }
+
+    pub fn fragment_types_occupancy(&self) -> Vec<FragmentTypePoolOccupancy> {
+        self.pool.fragment_types_occupancy()
+    }
+
     pub async fn select(
         &mut self,
         ledger: ApplyBlockLedger,
@@ -242,21 +357,36 @@ impl Pool {
             contents,
             ledger,
             rejected_fragments_cnt,
-        } = match selection_alg {
-            FragmentSelectionAlgorithmParams::OldestFirst => {
-                let mut selection_alg = OldestFirst::new();
-                selection_alg
-                    .select(
-                        ledger,
-                        logs,
-                        pool,
-                        soft_deadline_future,
-                        hard_deadline_future,
-                    )
-                    .await
-            }
+            apply_times,
+        } = {
+            let mut selection_alg: Box<dyn FragmentSelectionAlgorithm + Send> = match selection_alg
+            {
+                FragmentSelectionAlgorithmParams::OldestFirst => Box::new(OldestFirst::new()),
+                FragmentSelectionAlgorithmParams::HighestFeeFirst => {
+                    Box::new(HighestFeeFirst::new())
+                }
+                FragmentSelectionAlgorithmParams::SenderRoundRobin => {
+                    Box::new(SenderRoundRobin::new())
+                }
+                FragmentSelectionAlgorithmParams::ReservedSpace { vote_cast_share } => {
+                    Box::new(ReservedSpace::new(vote_cast_share))
+                }
+            };
+            selection_alg
+                .select(
+                    ledger,
+                    logs,
+                    pool,
+                    soft_deadline_future,
+                    hard_deadline_future,
+                )
+                .await
         };
         self.metrics.add_tx_rejected_cnt(rejected_fragments_cnt);
+        for (fragment_type, apply_time) in apply_times {
+            self.metrics
+                .add_fragment_apply_time(fragment_type, apply_time);
+        }
         self.update_metrics();
         (contents, ledger)
     }
@@ -279,41 +409,48 @@ impl Pool {
     }
 }
 
-fn is_fragment_valid(fragment: &Fragment) -> bool {
+/// Checks what can be checked without the ledger state. The transactions have
+/// to pay exactly the fee of the ledger: the ledger rejects the ones paying
+/// more, which would otherwise rank above the valid ones in the pool.
+fn is_fragment_valid(fragment: &Fragment, fees: &LinearFee) -> bool {
     match fragment {
         // never valid in the pool, only acceptable in genesis
         Fragment::Initial(_) => false,
         Fragment::OldUtxoDeclaration(_) => false,
         // general transactions stuff
-        Fragment::Transaction(ref tx) => is_transaction_valid(tx),
-        Fragment::StakeDelegation(ref tx) => is_transaction_valid(tx),
-        Fragment::OwnerStakeDelegation(ref tx) => is_transaction_valid(tx),
-        Fragment::PoolRegistration(ref tx) => is_transaction_valid(tx),
-        Fragment::PoolRetirement(ref tx) => is_transaction_valid(tx),
-        Fragment::PoolUpdate(ref tx) => is_transaction_valid(tx),
+        Fragment::Transaction(ref tx) => is_transaction_valid(tx, fees),
+        Fragment::StakeDelegation(ref tx) => is_transaction_valid(tx, fees),
+        Fragment::OwnerStakeDelegation(ref tx) => is_transaction_valid(tx, fees),
+        Fragment::PoolRegistration(ref tx) => is_transaction_valid(tx, fees),
+        Fragment::PoolRetirement(ref tx) => is_transaction_valid(tx, fees),
+        Fragment::PoolUpdate(ref tx) => is_transaction_valid(tx, fees),
         // vote stuff
-        Fragment::UpdateProposal(ref tx) => is_transaction_valid(tx),
-        Fragment::UpdateVote(ref tx) => is_transaction_valid(tx),
-        Fragment::VotePlan(ref tx) => is_transaction_valid(tx),
-        Fragment::VoteCast(ref tx) => is_transaction_valid(tx),
-        Fragment::VoteTally(ref tx) => is_transaction_valid(tx),
-        Fragment::MintToken(ref tx) => is_transaction_valid(tx),
+        Fragment::UpdateProposal(ref tx) => is_transaction_valid(tx, fees),
+        Fragment::UpdateVote(ref tx) => is_transaction_valid(tx, fees),
+        Fragment::VotePlan(ref tx) => is_transaction_valid(tx, fees),
+        Fragment::VoteCast(ref tx) => is_transaction_valid(tx, fees),
+        Fragment::VoteTally(ref tx) => is_transaction_valid(tx, fees),
+        Fragment::MintToken(ref tx) => is_transaction_valid(tx, fees),
+        Fragment::MultisigRegistration(ref tx) => is_transaction_valid(tx, fees),
         // evm stuff
         // TODO, maybe we need to develop some evm specific stateless validation in this place
         Fragment::Evm(_) => true,
-        Fragment::EvmMapping(ref tx) => is_transaction_valid(tx),
+        Fragment::EvmMapping(ref tx) => is_transaction_valid(tx, fees),
     }
 }
 
-fn is_transaction_valid<E>(tx: &Transaction<E>) -> bool {
-    tx.verify_possibly_balanced().is_ok()
+fn is_transaction_valid<P: Payload>(tx: &Transaction<P>, fees: &LinearFee) -> bool {
+    let fee = fees.calculate_tx(&tx.as_slice());
+    tx.verify_strictly_balanced(fee).is_ok()
 }
 
 pub(super) mod internal {
     use super::*;
+    use crate::fragment::info::FragmentInfo;
+    use chain_impl_mockchain::transaction::UnspecifiedAccountIdentifier;
     use std::{
         cmp::Ordering,
-        collections::HashMap,
+        collections::{hash_map, BTreeMap, BTreeSet, HashMap},
         hash::{Hash, Hasher},
         ptr,
     };
@@ -409,6 +546,20 @@ pub(super) mod internal {
         fn contains(&self, key: &K) -> bool {
             self.index.contains_key(&IndexedQueueKeyRef(key))
         }
+
+        fn values(&self) -> impl Iterator<Item = &V> {
+            self.index.values().map(|entry| &entry.value)
+        }
+
+        /// Iterates over the elements from the back of the queue to the front.
+        fn iter_back(&self) -> impl Iterator<Item = (&K, &V)> + '_ {
+            let mut entry = self.tail as *const IndexedQueueEntry<K, V>;
+            std::iter::from_fn(move || {
+                let current = unsafe { entry.as_ref() }?;
+                entry = current.prev;
+                Some((&current.key, &current.value))
+            })
+        }
     }
 
     unsafe impl<K: Send, V: Send> Send for IndexedQueue<K, V> {}
@@ -453,19 +604,172 @@ pub(super) mod internal {
         }
     }
 
+    /// Fee per byte of a fragment, ties being broken by the fragment identifier.
+    #[derive(Clone, PartialEq, Eq)]
+    struct FeeQueueItem {
+        fee: u64,
+        size: u64,
+        id: FragmentId,
+    }
+
+    impl FeeQueueItem {
+        fn new(id: FragmentId, info: &FragmentInfo) -> Self {
+            FeeQueueItem {
+                fee: info.fee,
+                size: (info.size as u64).max(1),
+                id,
+            }
+        }
+
+        fn cmp_fee(&self, other: &Self) -> Ordering {
+            (self.fee as u128 * other.size as u128).cmp(&(other.fee as u128 * self.size as u128))
+        }
+    }
+
+    impl Ord for FeeQueueItem {
+        fn cmp(&self, other: &Self) -> Ordering {
+            self.cmp_fee(other).then_with(|| self.id.cmp(&other.id))
+        }
+    }
+
+    impl PartialOrd for FeeQueueItem {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
     pub struct Pool {
-        entries: IndexedQueue<FragmentId, Fragment>,
+        entries: IndexedQueue<FragmentId, (Fragment, FragmentInfo)>,
+        timeouts: BTreeSet<TimeoutQueueItem>,
+        fees: BTreeSet<FeeQueueItem>,
+        account_lanes: HashMap<(UnspecifiedAccountIdentifier, usize), usize>,
         max_entries: usize,
+        account_lane_max_entries: Option<usize>,
+        eviction: PoolEviction,
+        // fee settings of the ledger of the tip, the fees of the fragments
+        // are computed with them
+        linear_fees: LinearFee,
+        // date of the last block, the fragments expiring before it are not accepted
+        date: Option<BlockDate>,
     }
 
     impl Pool {
-        pub fn new(max_entries: usize) -> Self {
+        pub fn new(max_entries: usize, linear_fees: LinearFee) -> Self {
+            Self::with_policies(max_entries, None, PoolEviction::Oldest, linear_fees)
+        }
+
+        pub fn with_policies(
+            max_entries: usize,
+            account_lane_max_entries: Option<usize>,
+            eviction: PoolEviction,
+            linear_fees: LinearFee,
+        ) -> Self {
             Pool {
                 entries: IndexedQueue::new(),
+                timeouts: BTreeSet::new(),
+                fees: BTreeSet::new(),
+                account_lanes: HashMap::new(),
                 max_entries,
+                account_lane_max_entries,
+                eviction,
+                linear_fees,
+                date: None,
             }
         }
 
+        pub fn linear_fees(&self) -> &LinearFee {
+            &self.linear_fees
+        }
+
+        /// Changes the fee settings used for the fragments inserted
+        /// afterwards.
+        pub fn set_linear_fees(&mut self, linear_fees: LinearFee) {
+            self.linear_fees = linear_fees;
+        }
+
+        /// Inserts a fragment in the pool, evicting fragments according to the
+        /// eviction policy if the pool is full. Returns the identifiers of the
+        /// evicted fragments.
+        pub fn insert(
+            &mut self,
+            fragment: Fragment,
+            id: FragmentId,
+        ) -> Result<Vec<FragmentId>, FragmentRejectionReason> {
+            if self.entries.contains(&id) {
+                return Err(FragmentRejectionReason::FragmentAlreadyInLog);
+            }
+            let info = FragmentInfo::new(&fragment, &self.linear_fees);
+            if let (Some(valid_until), Some(date)) = (info.valid_until, self.date) {
+                if valid_until <= date {
+                    return Err(FragmentRejectionReason::FragmentExpired);
+                }
+            }
+            if let (Some(max), Some(account)) = (self.account_lane_max_entries, &info.account) {
+                if self.account_lanes.get(account).copied().unwrap_or(0) >= max {
+                    return Err(FragmentRejectionReason::AccountLimitReached);
+                }
+            }
+
+            let mut evicted = Vec::new();
+            if self.max_entries == 0 {
+                return Err(FragmentRejectionReason::PoolOverflow);
+            }
+            while self.entries.len() >= self.max_entries {
+                let evicted_id = match self.eviction {
+                    PoolEviction::Oldest => self.remove_oldest().map(|(_, id)| id),
+                    PoolEviction::LowestFee => {
+                        let lowest = self.fees.iter().next().expect("entry must exist");
+                        if FeeQueueItem::new(id, &info).cmp_fee(lowest) != Ordering::Greater {
+                            return Err(FragmentRejectionReason::PoolOverflow);
+                        }
+                        let lowest_id = lowest.id;
+                        self.remove(&lowest_id).map(|_| lowest_id)
+                    }
+                };
+                evicted.push(evicted_id.expect("entry must exist"));
+            }
+
+            if let Some(valid_until) = info.valid_until {
+                self.timeouts.insert(TimeoutQueueItem { valid_until, id });
+            }
+            self.fees.insert(FeeQueueItem::new(id, &info));
+            if let Some(account) = &info.account {
+                *self.account_lanes.entry(account.clone()).or_insert(0) += 1;
+            }
+            self.entries.push_front(id, (fragment, info));
+            Ok(evicted)
+        }
+
+        /// Changes the limits and the eviction policy of the pool, evicting
+        /// fragments according to the new policy if the pool holds more than
+        /// `max_entries`. Returns the identifiers of the evicted fragments.
+        ///
+        /// Lowering the account lane limit does not evict fragments, it only
+        /// applies to the fragments inserted afterwards.
+        pub fn set_policies(
+            &mut self,
+            max_entries: usize,
+            account_lane_max_entries: Option<usize>,
+            eviction: PoolEviction,
+        ) -> Vec<FragmentId> {
+            self.max_entries = max_entries;
+            self.account_lane_max_entries = account_lane_max_entries;
+            self.eviction = eviction;
+
+            let mut evicted = Vec::new();
+            while self.entries.len() > self.max_entries {
+                let evicted_id = match self.eviction {
+                    PoolEviction::Oldest => self.remove_oldest().map(|(_, id)| id),
+                    PoolEviction::LowestFee => {
+                        let lowest_id = self.fees.iter().next().expect("entry must exist").id;
+                        self.remove(&lowest_id).map(|_| lowest_id)
+                    }
+                };
+                evicted.push(evicted_id.expect("entry must exist"));
+            }
+            evicted
+        }
+
         /// Returns clones of registered fragments
         pub fn insert_all(
             &mut self,
@@ -473,18 +777,7 @@ pub(super) mod internal {
         ) -> Vec<(Fragment, FragmentId)> {
             fragments
                 .into_iter()
-                .filter(|(fragment, id)| {
-                    if self.entries.contains(id) {
-                        false
-                    } else {
-                        if self.entries.len() >= self.max_entries && self.entries.len() != 0 {
-                            // Remove an oldest entry from the pool
-                            self.entries.pop_back().expect("entry must exist");
-                        }
-                        self.entries.push_front(*id, fragment.clone());
-                        true
-                    }
-                })
+                .filter(|(fragment, id)| self.insert(fragment.clone(), *id).is_ok())
                 // Truncate overflowing fragments
                 .take(self.max_entries)
                 .collect()
@@ -492,12 +785,103 @@ pub(super) mod internal {
 
         pub fn remove_all<'a>(&mut self, fragment_ids: impl IntoIterator<Item = &'a FragmentId>) {
             for fragment_id in fragment_ids {
-                self.entries.remove(fragment_id);
+                self.remove(fragment_id);
             }
         }
 
+        /// Iterates over the fragments of the pool, the oldest first.
+        pub fn iter(&self) -> impl Iterator<Item = (FragmentId, &Fragment, &FragmentInfo)> + '_ {
+            self.entries
+                .iter_back()
+                .map(|(id, (fragment, info))| (*id, fragment, info))
+        }
+
         pub fn remove_oldest(&mut self) -> Option<(Fragment, FragmentId)> {
-            self.entries.pop_back().map(|(id, value)| (value, id))
+            let (id, (fragment, info)) = self.entries.pop_back()?;
+            self.unindex(id, &info);
+            Some((fragment, id))
+        }
+
+        /// Removes the fragments that cannot be included in a block after the given date.
+        pub fn remove_expired(&mut self, date: BlockDate) -> Vec<FragmentId> {
+            self.date = Some(date);
+            let mut expired = Vec::new();
+            while let Some(item) = self.timeouts.iter().next() {
+                if item.valid_until > date {
+                    break;
+                }
+                let id = item.id;
+                self.remove(&id);
+                expired.push(id);
+            }
+            expired
+        }
+
+        fn remove(&mut self, id: &FragmentId) -> Option<Fragment> {
+            let (fragment, info) = self.entries.remove(id)?;
+            self.unindex(*id, &info);
+            Some(fragment)
+        }
+
+        fn unindex(&mut self, id: FragmentId, info: &FragmentInfo) {
+            if let Some(valid_until) = info.valid_until {
+                self.timeouts.remove(&TimeoutQueueItem { valid_until, id });
+            }
+            self.fees.remove(&FeeQueueItem::new(id, info));
+            if let Some(account) = &info.account {
+                if let hash_map::Entry::Occupied(mut entry) =
+                    self.account_lanes.entry(account.clone())
+                {
+                    *entry.get_mut() -= 1;
+                    if *entry.get() == 0 {
+                        entry.remove();
+                    }
+                }
+            }
+        }
+
+        pub fn date(&self) -> Option<BlockDate> {
+            self.date
+        }
+
+        pub fn accounts_occupancy(&self) -> Vec<AccountPoolOccupancy> {
+            let mut accounts: HashMap<&UnspecifiedAccountIdentifier, AccountPoolOccupancy> =
+                HashMap::new();
+            for ((account, lane), count) in &self.account_lanes {
+                let occupancy = accounts
+                    .entry(account)
+                    .or_insert_with(|| AccountPoolOccupancy {
+                        account: hex::encode(account.as_ref()),
+                        fragments: 0,
+                        lanes: BTreeMap::new(),
+                    });
+                occupancy.fragments += count;
+                occupancy.lanes.insert(*lane, *count);
+            }
+            let mut accounts: Vec<_> = accounts.into_values().collect();
+            accounts.sort_unstable_by(|a, b| {
+                b.fragments
+                    .cmp(&a.fragments)
+                    .then_with(|| a.account.cmp(&b.account))
+            });
+            accounts
+        }
+
+        pub fn fragment_types_occupancy(&self) -> Vec<FragmentTypePoolOccupancy> {
+            let mut types: BTreeMap<&'static str, FragmentTypePoolOccupancy> = BTreeMap::new();
+            for (_, info) in self.entries.values() {
+                let occupancy =
+                    types
+                        .entry(info.kind)
+                        .or_insert_with(|| FragmentTypePoolOccupancy {
+                            fragment_type: info.kind.to_owned(),
+                            fragments: 0,
+                            size: 0,
+                        });
+                occupancy.fragments += 1;
+                occupancy.size += info.size;
+            }
+            types.into_values().collect()
         }
 
         pub fn len(&self) -> usize {
@@ -512,7 +896,11 @@ pub(super) mod internal {
     #[cfg(test)]
     mod tests {
         use super::*;
+        use chain_addr::Discrimination;
         use chain_core::property::Fragment as _;
+        use chain_impl_mockchain::{
+            key::Hash as HeaderId, testing::data::AddressData, transaction::TxBuilder, value::Value,
+        };
         use quickcheck::TestResult;
         use quickcheck_macros::quickcheck;
         use std::collections::HashSet;
@@ -560,7 +948,7 @@ pub(super) mod internal {
                 (fragments2_in.2.clone(), fragments2_in.2.id()),
                 (fragments2_in.3.clone(), fragments2_in.3.id()),
             ];
-            let mut pool = Pool::new(4);
+            let mut pool = Pool::new(4, LinearFee::new(0, 0, 0));
             assert_eq!(fragments1, pool.insert_all(fragments1.clone()));
 
             assert_eq!(fragments2_expected, pool.insert_all(fragments2));
@@ -569,5 +957,228 @@ pub(super) mod internal {
             }
             TestResult::from_bool(pool.remove_oldest().is_none())
         }
+
+        fn fees() -> LinearFee {
+            LinearFee::new(0, 100, 0)
+        }
+
+        /// a transaction paying the fee of the ledger and the same amount to
+        /// each of its `outputs`: the fee per byte grows with the number of
+        /// outputs
+        fn account_transaction(sender: &mut AddressData, outputs: usize) -> Fragment {
+            let receiver = AddressData::account(Discrimination::Test);
+            let fee = fees().calculate(None, 1, outputs as u8);
+            let builder = TxBuilder::new()
+                .set_nopayload()
+                .set_expiry_date(BlockDate::first().next_epoch())
+                .set_ios(
+                    &[sender.make_input(Value(10 * outputs as u64 + fee.0), None)],
+                    &vec![receiver.make_output(Value(10)); outputs],
+                );
+            let witness =
+                sender.make_witness(&HeaderId::zero_hash(), builder.get_auth_data_for_witness());
+            Fragment::Transaction(builder.set_witnesses(&[witness]).set_payload_auth(&()))
+        }
+
+        #[test]
+        fn full_pool_should_evict_lowest_fee_fragments() {
+            let mut sender = AddressData::account(Discrimination::Test);
+            let cheap = account_transaction(&mut sender, 2);
+            let expensive = account_transaction(&mut sender, 3);
+            let cheaper = account_transaction(&mut sender, 1);
+            let mut pool = Pool::with_policies(1, None, PoolEviction::LowestFee, fees());
+
+            assert_eq!(pool.insert(cheap.clone(), cheap.id()), Ok(vec![]));
+            assert_eq!(
+                pool.insert(expensive.clone(), expensive.id()),
+                Ok(vec![cheap.id()])
+            );
+            assert_eq!(
+                pool.insert(cheaper.clone(), cheaper.id()),
+                Err(FragmentRejectionReason::PoolOverflow)
+            );
+            assert_eq!(
+                pool.remove_oldest(),
+                Some((expensive.clone(), expensive.id()))
+            );
+        }
+
+        #[test]
+        fn shrinking_pool_should_evict_fragments() {
+            let mut sender = AddressData::account(Discrimination::Test);
+            let cheap = account_transaction(&mut sender, 1);
+            let expensive = account_transaction(&mut sender, 3);
+            let newest = account_transaction(&mut sender, 2);
+            let mut pool = Pool::new(3, fees());
+
+            assert_eq!(pool.insert(cheap.clone(), cheap.id()), Ok(vec![]));
+            assert_eq!(pool.insert(expensive.clone(), expensive.id()), Ok(vec![]));
+            assert_eq!(pool.insert(newest.clone(), newest.id()), Ok(vec![]));
+
+            assert_eq!(
+                pool.set_policies(2, None, PoolEviction::LowestFee),
+                vec![cheap.id()]
+            );
+            assert_eq!(
+                pool.set_policies(1, None, PoolEviction::Oldest),
+                vec![expensive.id()]
+            );
+            assert!(pool.set_policies(5, None, PoolEviction::Oldest).is_empty());
+            assert_eq!(pool.len(), 1);
+            assert_eq!(pool.max_entries(), 5);
+        }
+
+        #[test]
+        fn account_lane_limit_should_reject_fragments() {
+            let mut sender = AddressData::account(Discrimination::Test);
+            let first = account_transaction(&mut sender, 1);
+            let second = account_transaction(&mut sender, 1);
+            let mut pool = Pool::with_policies(10, Some(1), PoolEviction::Oldest, fees());
+
+            assert_eq!(pool.insert(first.clone(), first.id()), Ok(vec![]));
+            assert_eq!(
+                pool.insert(second.clone(), second.id()),
+                Err(FragmentRejectionReason::AccountLimitReached)
+            );
+            assert_eq!(pool.accounts_occupancy()[0].fragments, 1);
+
+            pool.remove_all(&[first.id()]);
+            assert_eq!(pool.insert(second.clone(), second.id()), Ok(vec![]));
+        }
+
+        #[test]
+        fn expired_fragments_should_be_removed() {
+            let mut sender = AddressData::account(Discrimination::Test);
+            let fragment = account_transaction(&mut sender, 1);
+            let mut pool = Pool::new(10, fees());
+
+            assert_eq!(pool.insert(fragment.clone(), fragment.id()), Ok(vec![]));
+            assert!(pool.remove_expired(BlockDate::first()).is_empty());
+            assert_eq!(
+                pool.remove_expired(BlockDate::first().next_epoch()),
+                vec![fragment.id()]
+            );
+            assert_eq!(pool.len(), 0);
+            assert_eq!(
+                pool.insert(fragment.clone(), fragment.id()),
+                Err(FragmentRejectionReason::FragmentExpired)
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::async_msg::{self, MessageQueue};
+    use chain_addr::Discrimination;
+    use chain_impl_mockchain::{
+        key::Hash as HeaderId, testing::data::AddressData, transaction::TxBuilder, value::Value,
+    };
+
+    fn fees() -> LinearFee {
+        LinearFee::new(0, 100, 0)
+    }
+
+    /// a pool along with the queue of the fragments it propagates, which
+    /// has to stay open
+    fn pool(
+        max_entries: usize,
+        eviction: PoolEviction,
+        persistent_log: Option<File>,
+    ) -> (Pool, MessageQueue<NetworkMsg>) {
+        let (network_msg_box, network_queue) = async_msg::channel(16);
+        let pool = Pool::new(
+            max_entries,
+            None,
+            eviction,
+            fees(),
+            Logs::new(max_entries),
+            network_msg_box,
+            persistent_log,
+            Metrics::builder().build(),
+        );
+        (pool, network_queue)
+    }
+
+    /// a transaction from a new account to `outputs` receivers, claiming to
+    /// pay `overpaid` on top of the fee of the ledger
+    fn transaction(outputs: usize, overpaid: u64) -> Fragment {
+        let sender = AddressData::account(Discrimination::Test);
+        let receiver = AddressData::account(Discrimination::Test);
+        let fee = fees().calculate(None, 1, outputs as u8);
+        let builder = TxBuilder::new()
+            .set_nopayload()
+            .set_expiry_date(BlockDate::first().next_epoch())
+            .set_ios(
+                &[sender.make_input(Value(10 * outputs as u64 + fee.0 + overpaid), None)],
+                &vec![receiver.make_output(Value(10)); outputs],
+            );
+        let witness =
+            sender.make_witness(&HeaderId::zero_hash(), builder.get_auth_data_for_witness());
+        Fragment::Transaction(builder.set_witnesses(&[witness]).set_payload_auth(&()))
+    }
+
+    #[tokio::test]
+    async fn overpaying_fragments_do_not_evict_valid_ones() {
+        let (mut pool, _network_queue) = pool(1, PoolEviction::LowestFee, None);
+        let valid = transaction(1, 0);
+        let overpaying = transaction(1, 1_000_000);
+
+        let summary = pool
+            .insert_and_propagate_all(FragmentOrigin::Rest, vec![valid.clone()], false)
+            .await
+            .unwrap();
+        assert_eq!(summary.accepted, vec![valid.hash()]);
+
+        let summary = pool
+            .insert_and_propagate_all(FragmentOrigin::Rest, vec![overpaying.clone()], false)
+            .await
+            .unwrap();
+        assert!(summary.accepted.is_empty());
+        assert_eq!(
+            summary.rejected[0].reason,
+            FragmentRejectionReason::FragmentInvalid
+        );
+        assert_eq!(pool.pool.len(), 1);
+
+        // a fragment paying more per byte to the ledger still evicts it
+        let better = transaction(3, 0);
+        let summary = pool
+            .insert_and_propagate_all(FragmentOrigin::Rest, vec![better.clone()], false)
+            .await
+            .unwrap();
+        assert_eq!(summary.accepted, vec![better.hash()]);
+        assert_eq!(
+            pool.pool.remove_oldest(),
+            Some((better.clone(), better.hash()))
+        );
+    }
+
+    #[tokio::test]
+    async fn replayed_fragments_are_not_logged_again() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fragments.log");
+        let file = File::create(&path).await.unwrap();
+        let (mut pool, _network_queue) = pool(10, PoolEviction::Oldest, Some(file));
+        let log_len = || std::fs::metadata(&path).unwrap().len();
+
+        let summary = pool
+            .insert_and_propagate_all(
+                FragmentOrigin::PersistentLog,
+                vec![transaction(1, 0)],
+                false,
+            )
+            .await
+            .unwrap();
+        assert_eq!(summary.accepted.len(), 1);
+        assert_eq!(log_len(), 0);
+
+        let summary = pool
+            .insert_and_propagate_all(FragmentOrigin::Rest, vec![transaction(1, 0)], false)
+            .await
+            .unwrap();
+        assert_eq!(summary.accepted.len(), 1);
+        assert!(log_len() > 0);
     }
 }
@@ -1,5 +1,5 @@
 use crate::{
-    fragment::{Logs, Pool},
+    fragment::{Fragment, Logs, Pool},
     intercom::{NetworkMsg, TransactionMsg},
     metrics::{Metrics, MetricsBackend},
     utils::{
@@ -7,7 +7,9 @@ use crate::{
         task::TokioServiceInfo,
     },
 };
+use chain_impl_mockchain::fee::LinearFee;
 use futures::{future, TryFutureExt};
+use jormungandr_lib::interfaces::{FragmentOrigin, PoolEviction};
 use std::{
     collections::HashMap,
     convert::TryInto,
@@ -23,6 +25,9 @@ use tracing_futures::Instrument;
 
 pub struct Process {
     pool_max_entries: usize,
+    account_lane_max_entries: Option<usize>,
+    eviction: PoolEviction,
+    linear_fees: LinearFee,
     logs_max_entries: usize,
     network_msg_box: MessageBox<NetworkMsg>,
 }
@@ -38,11 +43,17 @@ pub enum Error {
 impl Process {
     pub fn new(
         pool_max_entries: usize,
+        account_lane_max_entries: Option<usize>,
+        eviction: PoolEviction,
+        linear_fees: LinearFee,
         logs_max_entries: usize,
         network_msg_box: MessageBox<NetworkMsg>,
     ) -> Self {
         Process {
             pool_max_entries,
+            account_lane_max_entries,
+            eviction,
+            linear_fees,
             logs_max_entries,
             network_msg_box,
         }
@@ -54,6 +65,7 @@ impl Process {
         stats_counter: Metrics,
         mut input: MessageQueue<TransactionMsg>,
         persistent_log_dir: Option<P>,
+        replayed_fragments: Vec<Fragment>,
     ) -> Result<(), Error> {
         async fn hourly_wakeup(enabled: bool) {
             if enabled {
@@ -106,11 +118,28 @@ impl Process {
 
             let mut pool = Pool::new(
                 self.pool_max_entries,
+                self.account_lane_max_entries,
+                self.eviction,
+                self.linear_fees,
                 logs,
                 self.network_msg_box,
                 persistent_log,
                 stats_counter.clone()
             );
+            if !replayed_fragments.is_empty() {
+                let summary = pool
+                    .insert_and_propagate_all(
+                        FragmentOrigin::PersistentLog,
+                        replayed_fragments,
+                        false,
+                    )
+                    .instrument(debug_span!("persistent_log_replay"))
+                    .await?;
+                tracing::info!(
+                    "{} fragments of the persistent log are back in the mempool",
+                    summary.accepted.len()
+                );
+            }
             loop {
                 tokio::select! {
                     maybe_msg = input.next() => {
@@ -159,6 +188,33 @@ impl Process {
                                     let logs = pool.logs().logs().cloned().collect();
                                     reply_handle.reply_ok(logs);
                                 }
+                                TransactionMsg::GetAccountsOccupancy(reply_handle) => {
+                                    reply_handle.reply_ok(pool.accounts_occupancy());
+                                }
+                                TransactionMsg::GetFragmentTypesOccupancy(reply_handle) => {
+                                    reply_handle.reply_ok(pool.fragment_types_occupancy());
+                                }
+                                TransactionMsg::SetLimits {
+                                    pool_max_entries,
+                                    account_lane_max_entries,
+                                    eviction,
+                                } => {
+                                    tracing::info!(
+                                        pool_max_entries,
+                                        ?account_lane_max_entries,
+                                        ?eviction,
+                                        "changing the mempool limits"
+                                    );
+                                    pool.set_limits(
+                                        pool_max_entries,
+                                        account_lane_max_entries,
+                                        eviction,
+                                    );
+                                }
+                                TransactionMsg::SetLinearFees(linear_fees) => {
+                                    tracing::debug!(?linear_fees, "changing the fee settings of the mempool");
+                                    pool.set_linear_fees(linear_fees);
+                                }
                                 TransactionMsg::GetStatuses(fragment_ids, reply_handle) => {
                                     let mut statuses = HashMap::new();
                                     pool.logs().logs_by_ids(fragment_ids).into_iter().for_each(
@@ -182,7 +238,7 @@ impl Process {
                                     let span = span!(
                                         Level::DEBUG,
                                         "fragment_selection",
-                                        kind = "older_first",
+                                        algorithm = ?selection_alg,
                                     );
                                     async {
                                         let contents = pool
@@ -0,0 +1,306 @@
+use crate::{
+    blockcfg::{BlockDate, Fragment, FragmentId, Ledger},
+    blockchain::{Ref, Storage},
+};
+use chain_time::{
+    era::{EpochPosition, EpochSlotOffset},
+    Epoch,
+};
+use jormungandr_lib::{
+    interfaces::{list_persistent_fragment_log_files_from_folder_path, FileFragments},
+    time::SecondsSinceUnixEpoch,
+};
+use std::{
+    collections::HashSet,
+    fs,
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
+/// Reads back the fragments written to the persistent fragment log within
+/// the given window and returns the ones that are still pending, in the order
+/// they were received.
+///
+/// The fragments included in the blocks of the window are dropped and the
+/// others are checked against the ledger of the current tip, one after the
+/// other, so that the ones that cannot be applied anymore are dropped too.
+pub fn pending_fragments_from_persistent_log(
+    dir: &Path,
+    window: Duration,
+    tip: &Ref,
+    storage: &Storage,
+) -> Vec<Fragment> {
+    let since = SystemTime::now()
+        .checked_sub(window)
+        .unwrap_or(SystemTime::UNIX_EPOCH);
+
+    let logged = read_logged_fragments(dir, since);
+    if logged.is_empty() {
+        tracing::info!("no fragment to replay from the persistent log");
+        return Vec::new();
+    }
+
+    let in_chain = fragments_in_chain_since(tip, storage, since);
+    pending_fragments(
+        logged,
+        &in_chain,
+        tip.ledger().as_ref().clone(),
+        tip.block_date(),
+    )
+}
+
+/// Drops the logged fragments included in a block and the ones that cannot
+/// be applied to the ledger anymore, e.g. because they expired.
+fn pending_fragments(
+    logged: Vec<Fragment>,
+    in_chain: &HashSet<FragmentId>,
+    mut ledger: Ledger,
+    block_date: BlockDate,
+) -> Vec<Fragment> {
+    let mut pending = Vec::new();
+    let mut in_chain_cnt = 0;
+    let mut invalid_cnt = 0;
+
+    for fragment in logged {
+        let id = fragment.hash();
+        if in_chain.contains(&id) {
+            in_chain_cnt += 1;
+            continue;
+        }
+        match ledger.apply_fragment(&fragment, block_date) {
+            Ok(new_ledger) => {
+                ledger = new_ledger;
+                pending.push(fragment);
+            }
+            Err(error) => {
+                tracing::debug!(%id, %error, "dropping fragment of the persistent log");
+                invalid_cnt += 1;
+            }
+        }
+    }
+
+    tracing::info!(
+        "replaying {} fragments from the persistent log, {} already in a block, {} no longer valid",
+        pending.len(),
+        in_chain_cnt,
+        invalid_cnt
+    );
+    pending
+}
+
+fn read_logged_fragments(dir: &Path, since: SystemTime) -> Vec<Fragment> {
+    let files = match list_persistent_fragment_log_files_from_folder_path(dir) {
+        Ok(files) => files,
+        Err(error) => {
+            tracing::warn!(%error, "cannot list the persistent fragment log files");
+            return Vec::new();
+        }
+    };
+    let since_secs = SecondsSinceUnixEpoch::from_secs(
+        since
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs()),
+    );
+
+    let mut seen = HashSet::new();
+    let mut fragments = Vec::new();
+    for path in files {
+        // the files were last written to after all the entries they contain
+        let recent = fs::metadata(&path)
+            .and_then(|metadata| metadata.modified())
+            .map_or(true, |modified| modified >= since);
+        if !recent {
+            continue;
+        }
+
+        let entries = match FileFragments::from_path(path.clone()) {
+            Ok(entries) => entries,
+            Err(error) => {
+                tracing::warn!(%error, "cannot open persistent fragment log file {:?}", path);
+                continue;
+            }
+        };
+        for entry in entries {
+            match entry {
+                Ok(entry) => {
+                    if entry.time >= since_secs && seen.insert(entry.fragment.hash()) {
+                        fragments.push(entry.fragment);
+                    }
+                }
+                Err(error) => {
+                    // the last entry may have been partially written when the node stopped
+                    tracing::warn!(%error, "skipping the rest of persistent fragment log file");
+                    break;
+                }
+            }
+        }
+    }
+    fragments
+}
+
+fn fragments_in_chain_since(
+    tip: &Ref,
+    storage: &Storage,
+    since: SystemTime,
+) -> HashSet<FragmentId> {
+    let era = tip.epoch_leadership_schedule().era();
+    let time_frame = tip.time_frame();
+    let mut fragment_ids = HashSet::new();
+    let mut block_id = tip.hash();
+
+    loop {
+        let block = match storage.get(block_id) {
+            Ok(Some(block)) => block,
+            Ok(None) => break,
+            Err(error) => {
+                tracing::warn!(%error, "cannot read the blocks of the persistent log window");
+                break;
+            }
+        };
+        fragment_ids.extend(block.fragments().map(|fragment| fragment.hash()));
+
+        let date = block.header().block_date();
+        let slot = era.from_era_to_slot(EpochPosition {
+            epoch: Epoch(date.epoch),
+            slot: EpochSlotOffset(date.slot_id),
+        });
+        match time_frame.slot_to_systemtime(slot) {
+            Some(time) if time >= since => block_id = block.header().block_parent_hash(),
+            _ => break,
+        }
+    }
+    fragment_ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chain_core::{packer::Codec, property::Serialize as _};
+    use chain_impl_mockchain::{
+        fee::LinearFee,
+        testing::{data::Wallet, ConfigBuilder, LedgerBuilder, TestLedger},
+        transaction::TxBuilder,
+        value::Value,
+    };
+    use jormungandr_lib::interfaces::PersistentFragmentLog;
+    use std::io::Write as _;
+
+    fn ledger(senders: &[&Wallet]) -> TestLedger {
+        LedgerBuilder::from_config(ConfigBuilder::new().with_fee(LinearFee::new(0, 0, 0)))
+            .faucets_wallets(senders.to_vec())
+            .build()
+            .unwrap()
+    }
+
+    fn transaction(ledger: &TestLedger, sender: &mut Wallet, valid_until: BlockDate) -> Fragment {
+        let receiver = Wallet::from_value(Value(0));
+        let builder = TxBuilder::new()
+            .set_nopayload()
+            .set_expiry_date(valid_until)
+            .set_ios(
+                &[sender.make_input_with_value(Value(10))],
+                &[receiver.make_output_with_value(Value(10))],
+            );
+        let witness = sender.make_witness(&ledger.block0_hash, builder.get_auth_data_for_witness());
+        sender.confirm_transaction();
+        Fragment::Transaction(builder.set_witnesses(&[witness]).set_payload_auth(&()))
+    }
+
+    fn log_entry(fragment: &Fragment, time: SecondsSinceUnixEpoch) -> Vec<u8> {
+        let entry = PersistentFragmentLog {
+            time,
+            fragment: fragment.clone(),
+        };
+        let mut codec = Codec::new(Vec::new());
+        entry.serialize(&mut codec).unwrap();
+        codec.into_inner()
+    }
+
+    #[test]
+    fn fragments_in_a_block_are_dropped() {
+        let mut sender = Wallet::from_value(Value(100));
+        let mut ledger = ledger(&[&sender]);
+        let date = BlockDate {
+            epoch: 0,
+            slot_id: 1,
+        };
+        let included = transaction(&ledger, &mut sender, date.next_epoch());
+        let pending = transaction(&ledger, &mut sender, date.next_epoch());
+        ledger.apply_fragment(&included, date).unwrap();
+
+        let in_chain = [included.hash()].into_iter().collect();
+        let replayed = pending_fragments(
+            vec![included, pending.clone()],
+            &in_chain,
+            ledger.ledger,
+            date,
+        );
+        assert_eq!(
+            replayed.iter().map(Fragment::hash).collect::<Vec<_>>(),
+            vec![pending.hash()]
+        );
+    }
+
+    #[test]
+    fn expired_fragments_are_dropped() {
+        let mut alice = Wallet::from_value(Value(100));
+        let mut bob = Wallet::from_value(Value(100));
+        let ledger = ledger(&[&alice, &bob]);
+        let date = BlockDate {
+            epoch: 0,
+            slot_id: 10,
+        };
+        let expired = transaction(
+            &ledger,
+            &mut alice,
+            BlockDate {
+                epoch: 0,
+                slot_id: 5,
+            },
+        );
+        let valid = transaction(&ledger, &mut bob, date.next_epoch());
+
+        let replayed = pending_fragments(
+            vec![expired, valid.clone()],
+            &HashSet::new(),
+            ledger.ledger,
+            date,
+        );
+        assert_eq!(
+            replayed.iter().map(Fragment::hash).collect::<Vec<_>>(),
+            vec![valid.hash()]
+        );
+    }
+
+    #[test]
+    fn truncated_log_entries_stop_the_file() {
+        let mut sender = Wallet::from_value(Value(100));
+        let ledger = ledger(&[&sender]);
+        let valid_until = BlockDate::first().next_epoch();
+        let fragments: Vec<_> = (0..4)
+            .map(|_| transaction(&ledger, &mut sender, valid_until))
+            .collect();
+        let now = SecondsSinceUnixEpoch::now();
+        let dir = tempfile::tempdir().unwrap();
+
+        // the node stopped while writing the third entry of the first file
+        let mut first = fs::File::create(dir.path().join("1.log")).unwrap();
+        first.write_all(&log_entry(&fragments[0], now)).unwrap();
+        first.write_all(&log_entry(&fragments[1], now)).unwrap();
+        let entry = log_entry(&fragments[2], now);
+        first.write_all(&entry[..entry.len() / 2]).unwrap();
+        let mut second = fs::File::create(dir.path().join("2.log")).unwrap();
+        second.write_all(&log_entry(&fragments[3], now)).unwrap();
+        drop((first, second));
+
+        let since = SystemTime::now() - Duration::from_secs(3600);
+        let logged = read_logged_fragments(dir.path(), since);
+        assert_eq!(
+            logged.iter().map(Fragment::hash).collect::<Vec<_>>(),
+            [&fragments[0], &fragments[1], &fragments[3]]
+                .iter()
+                .map(|fragment| fragment.hash())
+                .collect::<Vec<_>>()
+        );
+    }
+}
@@ -1,13 +1,20 @@
-use super::{logs::Logs, pool::internal::Pool};
+use super::{info::FragmentInfo, logs::Logs, pool::internal::Pool};
 use crate::{
     blockcfg::{ApplyBlockLedger, Contents, ContentsBuilder},
     fragment::{Fragment, FragmentId},
 };
 use async_trait::async_trait;
 use chain_core::property::Serialize;
+use chain_impl_mockchain::transaction::UnspecifiedAccountIdentifier;
 use futures::{channel::oneshot::Receiver, future::Shared, prelude::*};
-use jormungandr_lib::interfaces::{BlockDate, FragmentStatus};
-use std::{error::Error, iter};
+use jormungandr_lib::interfaces::{BlockDate, FragmentSelection, FragmentStatus};
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    error::Error,
+    iter,
+    time::{Duration, Instant},
+};
 use tracing::{debug_span, Instrument};
 
 pub enum SelectionOutput {
@@ -33,11 +40,33 @@ pub struct FragmentSelectionResult {
     pub contents: Contents,
     pub ledger: ApplyBlockLedger,
     pub rejected_fragments_cnt: usize,
+    /// time spent applying each of the selected fragments to the ledger,
+    /// along with the type of the fragment
+    pub apply_times: Vec<(&'static str, Duration)>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum FragmentSelectionAlgorithmParams {
     OldestFirst,
+    HighestFeeFirst,
+    SenderRoundRobin,
+    /// percentage of the block content size guaranteed to vote casts
+    ReservedSpace {
+        vote_cast_share: u8,
+    },
+}
+
+impl From<FragmentSelection> for FragmentSelectionAlgorithmParams {
+    fn from(selection: FragmentSelection) -> Self {
+        match selection {
+            FragmentSelection::OldestFirst => Self::OldestFirst,
+            FragmentSelection::HighestFeeFirst => Self::HighestFeeFirst,
+            FragmentSelection::SenderRoundRobin => Self::SenderRoundRobin,
+            FragmentSelection::ReservedSpace { vote_cast_share } => Self::ReservedSpace {
+                vote_cast_share: vote_cast_share.into(),
+            },
+        }
+    }
 }
 
 pub struct OldestFirst;
@@ -54,6 +83,55 @@ impl Default for OldestFirst {
     }
 }
 
+/// Selects the fragments paying the highest fee per byte first. The fragments
+/// spending from the same account are still selected in the order they were
+/// received, so that their spending counters are applied in sequence.
+pub struct HighestFeeFirst;
+
+impl HighestFeeFirst {
+    pub fn new() -> Self {
+        HighestFeeFirst
+    }
+}
+
+impl Default for HighestFeeFirst {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Selects one fragment from each sender in turn, so that a single account
+/// flooding the mempool cannot starve the others.
+pub struct SenderRoundRobin;
+
+impl SenderRoundRobin {
+    pub fn new() -> Self {
+        SenderRoundRobin
+    }
+}
+
+impl Default for SenderRoundRobin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Selects the fragments in the order they were received, but guarantees
+/// a share of the block content to vote casts and the rest to the other
+/// fragments. Space left unused by one kind is then filled with the
+/// fragments of the other kind.
+pub struct ReservedSpace {
+    vote_cast_share: u8,
+}
+
+impl ReservedSpace {
+    pub fn new(vote_cast_share: u8) -> Self {
+        ReservedSpace {
+            vote_cast_share: vote_cast_share.min(100),
+        }
+    }
+}
+
 enum ApplyFragmentError {
     DoesNotFit,
     SoftDeadlineReached,
@@ -63,6 +141,7 @@ enum ApplyFragmentError {
 struct NewLedgerState {
     ledger: ApplyBlockLedger,
     space_left: u32,
+    apply_time: Duration,
 }
 
 async fn try_apply_fragment(
@@ -97,7 +176,11 @@ async fn try_apply_fragment(
 
     tracing::debug!("applying fragment in simulation");
 
-    let fragment_future = tokio::task::spawn_blocking(move || ledger.apply_fragment(&fragment));
+    let fragment_future = tokio::task::spawn_blocking(move || {
+        let started = Instant::now();
+        let ledger_res = ledger.apply_fragment(&fragment);
+        (ledger_res, started.elapsed())
+    });
 
     let ledger_res = match select(fragment_future, soft_deadline_future.clone()).await {
         Either::Left((join_result, _)) => join_result.unwrap(),
@@ -124,8 +207,12 @@ async fn try_apply_fragment(
     };
 
     match ledger_res {
-        Ok(ledger) => Ok(NewLedgerState { ledger, space_left }),
-        Err(err) => {
+        (Ok(ledger), apply_time) => Ok(NewLedgerState {
+            ledger,
+            space_left,
+            apply_time,
+        }),
+        (Err(err), _) => {
             let mut msg = err.to_string();
             for e in iter::successors(err.source(), |&e| e.source()) {
                 msg.push_str(": ");
@@ -136,76 +223,594 @@ async fn try_apply_fragment(
     }
 }
 
+/// Block contents under construction, with the ledger state resulting
+/// from the application of the fragments selected so far.
+struct BlockBuilder {
+    ledger: ApplyBlockLedger,
+    date: BlockDate,
+    space_left: u32,
+    contents_builder: ContentsBuilder,
+    // fragments included in the block or rejected, to be removed from the pool
+    taken: Vec<FragmentId>,
+    rejected_fragments_cnt: usize,
+    apply_times: Vec<(&'static str, Duration)>,
+    soft_deadline_future: Shared<Receiver<()>>,
+    hard_deadline_future: Shared<Receiver<()>>,
+}
+
+impl BlockBuilder {
+    fn new(
+        ledger: ApplyBlockLedger,
+        soft_deadline_future: Receiver<()>,
+        hard_deadline_future: Receiver<()>,
+    ) -> Self {
+        BlockBuilder {
+            date: ledger.block_date().into(),
+            space_left: ledger.settings().block_content_max_size,
+            ledger,
+            contents_builder: ContentsBuilder::new(),
+            taken: Vec::new(),
+            rejected_fragments_cnt: 0,
+            apply_times: Vec::new(),
+            soft_deadline_future: soft_deadline_future.shared(),
+            hard_deadline_future: hard_deadline_future.shared(),
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.space_left == 0
+    }
+
+    /// Tries to add the fragment to the block. Returns `false` if the
+    /// fragment cannot be included at this time and stays in the pool.
+    async fn push(&mut self, entry: &PoolEntry<'_>, logs: &mut Logs) -> bool {
+        let span = debug_span!("fragment", hash=%entry.id.to_string());
+
+        async {
+            let result = try_apply_fragment(
+                entry.fragment.clone(),
+                self.ledger.clone(),
+                self.soft_deadline_future.clone(),
+                self.hard_deadline_future.clone(),
+                self.space_left,
+            )
+            .await;
+            match result {
+                Ok(NewLedgerState {
+                    ledger,
+                    space_left,
+                    apply_time,
+                }) => {
+                    self.apply_times.push((entry.info.kind, apply_time));
+                    self.contents_builder.push(entry.fragment.clone());
+                    self.ledger = ledger;
+                    tracing::debug!("successfully applied and committed the fragment");
+                    self.space_left = space_left;
+                    self.taken.push(entry.id);
+                    true
+                }
+                Err(ApplyFragmentError::DoesNotFit)
+                | Err(ApplyFragmentError::SoftDeadlineReached) => false,
+                Err(ApplyFragmentError::Rejected(reason)) => {
+                    tracing::debug!(%reason, "fragment is rejected");
+                    logs.modify(entry.id, FragmentStatus::Rejected { reason }, self.date);
+                    self.rejected_fragments_cnt += 1;
+                    self.taken.push(entry.id);
+                    true
+                }
+            }
+        }
+        .instrument(span)
+        .await
+    }
+
+    /// Removes the fragments included in the block or rejected from the
+    /// pool, the others stay where they are.
+    fn finish(self, pool: &mut Pool) -> FragmentSelectionResult {
+        pool.remove_all(&self.taken);
+        tracing::debug!(
+            "finished block creation with {} fragments left in the pool",
+            pool.len()
+        );
+        FragmentSelectionResult {
+            contents: self.contents_builder.into(),
+            ledger: self.ledger,
+            rejected_fragments_cnt: self.rejected_fragments_cnt,
+            apply_times: self.apply_times,
+        }
+    }
+}
+
+/// A fragment of the pool, along with its position in the pool. The pool
+/// is not changed while the fragments of the block are selected.
+struct PoolEntry<'a> {
+    age: usize,
+    id: FragmentId,
+    fragment: &'a Fragment,
+    info: &'a FragmentInfo,
+}
+
+fn pool_entries(pool: &Pool) -> Vec<PoolEntry<'_>> {
+    pool.iter()
+        .enumerate()
+        .map(|(age, (id, fragment, info))| PoolEntry {
+            age,
+            id,
+            fragment,
+            info,
+        })
+        .collect()
+}
+
+#[derive(PartialEq, Eq, Hash)]
+enum Sender {
+    Account(UnspecifiedAccountIdentifier),
+    // fragments not spending from an account cannot be linked to each other
+    Unlinked(FragmentId),
+}
+
+impl Sender {
+    fn new(entry: &PoolEntry) -> Self {
+        match &entry.info.account {
+            Some((account, _)) => Sender::Account(account.clone()),
+            None => Sender::Unlinked(entry.id),
+        }
+    }
+}
+
+/// Groups the entries by sender, keeping the order in which they were received.
+/// The queues are ordered by the age of the oldest fragment of each sender.
+fn sender_queues(entries: Vec<PoolEntry>) -> Vec<VecDeque<PoolEntry>> {
+    let mut index = HashMap::new();
+    let mut queues: Vec<VecDeque<PoolEntry>> = Vec::new();
+    for entry in entries {
+        let i = *index.entry(Sender::new(&entry)).or_insert_with(|| {
+            queues.push(VecDeque::new());
+            queues.len() - 1
+        });
+        queues[i].push_back(entry);
+    }
+    queues
+}
+
+/// Fee per byte the ledger charges for a fragment, ties being broken in favour
+/// of the oldest one. The pool computes the fees with the fee settings of the
+/// ledger of the tip, which the block is built on.
+#[derive(PartialEq, Eq)]
+struct FeePriority {
+    fee: u64,
+    size: u64,
+    age: usize,
+}
+
+impl FeePriority {
+    fn new(entry: &PoolEntry) -> Self {
+        FeePriority {
+            fee: entry.info.fee,
+            size: (entry.info.size as u64).max(1),
+            age: entry.age,
+        }
+    }
+}
+
+impl Ord for FeePriority {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let lhs = self.fee as u128 * other.size as u128;
+        let rhs = other.fee as u128 * self.size as u128;
+        lhs.cmp(&rhs).then_with(|| other.age.cmp(&self.age))
+    }
+}
+
+impl PartialOrd for FeePriority {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 #[async_trait]
 impl FragmentSelectionAlgorithm for OldestFirst {
     async fn select(
         &mut self,
-        mut ledger: ApplyBlockLedger,
+        ledger: ApplyBlockLedger,
         logs: &mut Logs,
         pool: &mut Pool,
         soft_deadline_future: futures::channel::oneshot::Receiver<()>,
         hard_deadline_future: futures::channel::oneshot::Receiver<()>,
     ) -> FragmentSelectionResult {
-        let date: BlockDate = ledger.block_date().into();
-        let mut space_left = ledger.settings().block_content_max_size;
-        let mut contents_builder = ContentsBuilder::new();
-        let mut return_to_pool = Vec::new();
-        let mut rejected_fragments_cnt = 0;
-
-        let soft_deadline_future = soft_deadline_future.shared();
-        let hard_deadline_future = hard_deadline_future.shared();
-        while let Some((fragment, id)) = pool.remove_oldest() {
-            let span = debug_span!("fragment", hash=%id.to_string());
-
-            async {
-                let result = try_apply_fragment(
-                    fragment.clone(),
-                    ledger.clone(),
-                    soft_deadline_future.clone(),
-                    hard_deadline_future.clone(),
-                    space_left,
-                )
-                .await;
-                match result {
-                    Ok(NewLedgerState {
-                        ledger: ledger_new,
-                        space_left: space_left_new,
-                    }) => {
-                        contents_builder.push(fragment);
-                        ledger = ledger_new;
-                        tracing::debug!("successfully applied and committed the fragment");
-                        space_left = space_left_new;
-                    }
-                    Err(ApplyFragmentError::DoesNotFit)
-                    | Err(ApplyFragmentError::SoftDeadlineReached) => {
-                        return_to_pool.push((fragment, id));
-                    }
-                    Err(ApplyFragmentError::Rejected(reason)) => {
-                        tracing::debug!(%reason, "fragment is rejected");
-                        logs.modify(id, FragmentStatus::Rejected { reason }, date);
-                        rejected_fragments_cnt += 1;
-                    }
+        let mut block = BlockBuilder::new(ledger, soft_deadline_future, hard_deadline_future);
+
+        for entry in pool_entries(pool) {
+            block.push(&entry, logs).await;
+
+            if block.is_full() {
+                tracing::debug!("block has reached max total size, exiting");
+                break;
+            }
+        }
+
+        block.finish(pool)
+    }
+}
+
+#[async_trait]
+impl FragmentSelectionAlgorithm for HighestFeeFirst {
+    async fn select(
+        &mut self,
+        ledger: ApplyBlockLedger,
+        logs: &mut Logs,
+        pool: &mut Pool,
+        soft_deadline_future: futures::channel::oneshot::Receiver<()>,
+        hard_deadline_future: futures::channel::oneshot::Receiver<()>,
+    ) -> FragmentSelectionResult {
+        let mut block = BlockBuilder::new(ledger, soft_deadline_future, hard_deadline_future);
+        let mut queues = sender_queues(pool_entries(pool));
+
+        // only the oldest fragment of each sender competes for the next spot
+        let mut heads: BinaryHeap<_> = queues
+            .iter()
+            .enumerate()
+            .filter_map(|(i, queue)| queue.front().map(|entry| (FeePriority::new(entry), i)))
+            .collect();
+
+        while let Some((_, i)) = heads.pop() {
+            let entry = queues[i].pop_front().unwrap();
+            // the next fragments of this sender depend on this one, they stay
+            // in the pool with it if it is not taken
+            if block.push(&entry, logs).await {
+                if let Some(entry) = queues[i].front() {
+                    heads.push((FeePriority::new(entry), i));
                 }
             }
-            .instrument(span)
-            .await;
 
-            if space_left == 0 {
+            if block.is_full() {
                 tracing::debug!("block has reached max total size, exiting");
                 break;
             }
         }
 
-        tracing::debug!(
-            "finished block creation with {} fragments left in the pool",
-            pool.len()
-        );
-        pool.insert_all(return_to_pool);
+        block.finish(pool)
+    }
+}
 
-        FragmentSelectionResult {
-            contents: contents_builder.into(),
-            ledger,
-            rejected_fragments_cnt,
+#[async_trait]
+impl FragmentSelectionAlgorithm for SenderRoundRobin {
+    async fn select(
+        &mut self,
+        ledger: ApplyBlockLedger,
+        logs: &mut Logs,
+        pool: &mut Pool,
+        soft_deadline_future: futures::channel::oneshot::Receiver<()>,
+        hard_deadline_future: futures::channel::oneshot::Receiver<()>,
+    ) -> FragmentSelectionResult {
+        let mut block = BlockBuilder::new(ledger, soft_deadline_future, hard_deadline_future);
+        let mut queues: VecDeque<_> = sender_queues(pool_entries(pool)).into();
+
+        while let Some(mut queue) = queues.pop_front() {
+            let entry = queue.pop_front().unwrap();
+            // the next fragments of this sender depend on this one, they stay
+            // in the pool with it if it is not taken
+            if block.push(&entry, logs).await && !queue.is_empty() {
+                queues.push_back(queue);
+            }
+
+            if block.is_full() {
+                tracing::debug!("block has reached max total size, exiting");
+                break;
+            }
+        }
+
+        block.finish(pool)
+    }
+}
+
+#[async_trait]
+impl FragmentSelectionAlgorithm for ReservedSpace {
+    async fn select(
+        &mut self,
+        ledger: ApplyBlockLedger,
+        logs: &mut Logs,
+        pool: &mut Pool,
+        soft_deadline_future: futures::channel::oneshot::Receiver<()>,
+        hard_deadline_future: futures::channel::oneshot::Receiver<()>,
+    ) -> FragmentSelectionResult {
+        let block_content_max_size = ledger.settings().block_content_max_size;
+        let vote_cast_space =
+            (u64::from(block_content_max_size) * u64::from(self.vote_cast_share) / 100) as u32;
+        // space still reserved for vote casts and for the other fragments
+        let mut reserved = [vote_cast_space, block_content_max_size - vote_cast_space];
+
+        let mut block = BlockBuilder::new(ledger, soft_deadline_future, hard_deadline_future);
+        let mut deferred = Vec::new();
+
+        // first fill the space reserved to each kind of fragment, then give
+        // the remaining space to the fragments that did not fit their reservation
+        for entry in pool_entries(pool) {
+            let kind = match entry.fragment {
+                Fragment::VoteCast(_) => 0,
+                _ => 1,
+            };
+            let size = u32::try_from(entry.info.size).unwrap_or(u32::MAX);
+            if size > reserved[kind] {
+                // the fragments of this kind must stay in order
+                reserved[kind] = 0;
+                deferred.push(entry);
+                continue;
+            }
+
+            let space_left = block.space_left;
+            if block.push(&entry, logs).await {
+                reserved[kind] -= space_left - block.space_left;
+            }
+
+            if block.is_full() {
+                break;
+            }
         }
+
+        if !block.is_full() {
+            for entry in deferred {
+                block.push(&entry, logs).await;
+
+                if block.is_full() {
+                    break;
+                }
+            }
+        }
+
+        if block.is_full() {
+            tracing::debug!("block has reached max total size");
+        }
+        block.finish(pool)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chain_core::property::Fragment as _;
+    use chain_impl_mockchain::{
+        certificate::{VoteCast, VotePlan},
+        fee::{FeeAlgorithm, LinearFee},
+        header::BlockDate as ChainBlockDate,
+        testing::{
+            data::Wallet,
+            scenario::{prepare_scenario, proposal, vote_plan, wallet, Controller},
+            ConfigBuilder, TestLedger, VoteTestGen,
+        },
+        transaction::TxBuilder,
+        value::Value,
+        vote::{Choice, Payload},
+    };
+    use futures::channel::oneshot;
+
+    const ALICE: &str = "Alice";
+    const BOB: &str = "Bob";
+    const CLARA: &str = "Clara";
+    const DAVID: &str = "David";
+    const STAKE_POOL: &str = "stake_pool";
+    const VOTE_PLAN: &str = "fund1";
+
+    const VALID_UNTIL: ChainBlockDate = ChainBlockDate {
+        epoch: 0,
+        slot_id: 10,
+    };
+
+    fn fees() -> LinearFee {
+        LinearFee::new(0, 100, 0)
+    }
+
+    fn scenario(block_content_max_size: u32) -> (TestLedger, Controller) {
+        prepare_scenario()
+            .with_config(
+                ConfigBuilder::new()
+                    .with_fee(fees())
+                    .with_block_content_max_size(block_content_max_size),
+            )
+            .with_initials(vec![
+                wallet(ALICE)
+                    .with(1_000_000)
+                    .owns(STAKE_POOL)
+                    .committee_member(),
+                wallet(BOB).with(1_000_000),
+                wallet(CLARA).with(1_000_000),
+                wallet(DAVID).with(1_000_000),
+            ])
+            .with_vote_plans(vec![vote_plan(VOTE_PLAN)
+                .owner(ALICE)
+                .consecutive_epoch_dates()
+                .with_proposal(proposal(VoteTestGen::external_proposal_id()).options(3))])
+            .build()
+            .unwrap()
+    }
+
+    /// a transaction paying the same amount to each of its `outputs`: the
+    /// fee per byte grows with the number of outputs
+    fn transaction(ledger: &TestLedger, sender: &mut Wallet, outputs: usize) -> Fragment {
+        let fee = ledger.fee().calculate(None, 1, outputs as u8);
+        let input = sender.make_input_with_value(Value(10 * outputs as u64 + fee.0));
+        let outputs = vec![sender.make_output_with_value(Value(10)); outputs];
+        let builder = TxBuilder::new()
+            .set_nopayload()
+            .set_expiry_date(VALID_UNTIL)
+            .set_ios(&[input], &outputs);
+        let witness = sender.make_witness(&ledger.block0_hash, builder.get_auth_data_for_witness());
+        sender.confirm_transaction();
+        Fragment::Transaction(builder.set_witnesses(&[witness]).set_payload_auth(&()))
+    }
+
+    fn vote_cast(controller: &Controller, voter: &Wallet) -> Fragment {
+        let vote_plan: VotePlan = controller.vote_plan(VOTE_PLAN).unwrap().into();
+        let payload = Payload::Public {
+            choice: Choice::new(1),
+        };
+        controller.fragment_factory().vote_cast(
+            VALID_UNTIL,
+            voter,
+            VoteCast::new(vote_plan.to_id(), 0, payload),
+        )
+    }
+
+    fn pool<'a>(fragments: impl IntoIterator<Item = &'a Fragment>) -> Pool {
+        let mut pool = Pool::new(100, fees());
+        for fragment in fragments {
+            pool.insert(fragment.clone(), fragment.id()).unwrap();
+        }
+        pool
+    }
+
+    fn ids<'a>(fragments: impl IntoIterator<Item = &'a Fragment>) -> Vec<FragmentId> {
+        fragments
+            .into_iter()
+            .map(|fragment| fragment.id())
+            .collect()
+    }
+
+    /// the fragments selected for the next block, in order
+    async fn select(
+        mut algorithm: impl FragmentSelectionAlgorithm,
+        ledger: &TestLedger,
+        pool: &mut Pool,
+    ) -> Vec<FragmentId> {
+        let ledger = ledger
+            .ledger
+            .begin_block(
+                ledger.chain_length().increase(),
+                ChainBlockDate {
+                    epoch: 0,
+                    slot_id: 1,
+                },
+            )
+            .unwrap();
+        // the deadlines are not reached as long as the senders are alive
+        let (_soft_deadline, soft_deadline_future) = oneshot::channel();
+        let (_hard_deadline, hard_deadline_future) = oneshot::channel();
+        let result = algorithm
+            .select(
+                ledger,
+                &mut Logs::new(100),
+                pool,
+                soft_deadline_future,
+                hard_deadline_future,
+            )
+            .await;
+        assert_eq!(result.rejected_fragments_cnt, 0);
+        result
+            .contents
+            .iter()
+            .map(|fragment| fragment.id())
+            .collect()
+    }
+
+    fn remaining(pool: &mut Pool) -> Vec<FragmentId> {
+        iter::from_fn(|| pool.remove_oldest())
+            .map(|(_, id)| id)
+            .collect()
+    }
+
+    fn size(fragment: &Fragment) -> u32 {
+        fragment.serialized_size() as u32
+    }
+
+    #[tokio::test]
+    async fn highest_fee_first_keeps_the_order_of_each_account() {
+        let (ledger, controller) = scenario(u32::MAX);
+        let mut alice = controller.wallet(ALICE).unwrap();
+        let mut bob = controller.wallet(BOB).unwrap();
+        let mut clara = controller.wallet(CLARA).unwrap();
+
+        let a1 = transaction(&ledger, &mut alice, 1);
+        let a2 = transaction(&ledger, &mut alice, 3);
+        let b1 = transaction(&ledger, &mut bob, 2);
+        let c1 = transaction(&ledger, &mut clara, 3);
+        let mut pool = pool([&a1, &a2, &b1, &c1]);
+
+        // a2 pays the most per byte but has to wait for a1 to be selected
+        let selected = select(HighestFeeFirst::new(), &ledger, &mut pool).await;
+        assert_eq!(selected, ids([&c1, &b1, &a1, &a2]));
+        assert!(remaining(&mut pool).is_empty());
+    }
+
+    #[tokio::test]
+    async fn sender_round_robin_does_not_starve_the_other_senders() {
+        let (ledger, controller) = scenario(u32::MAX);
+        let alice = controller.wallet(ALICE).unwrap();
+        let size = size(&transaction(&ledger, &mut alice.clone(), 1));
+
+        let (ledger, controller) = scenario(4 * size);
+        let mut alice = controller.wallet(ALICE).unwrap();
+        let mut bob = controller.wallet(BOB).unwrap();
+        let mut clara = controller.wallet(CLARA).unwrap();
+
+        // Alice floods the pool before the others
+        let a: Vec<_> = (0..5)
+            .map(|_| transaction(&ledger, &mut alice, 1))
+            .collect();
+        let b1 = transaction(&ledger, &mut bob, 1);
+        let b2 = transaction(&ledger, &mut bob, 1);
+        let c1 = transaction(&ledger, &mut clara, 1);
+        let mut pool = pool(a.iter().chain([&b1, &b2, &c1]));
+
+        let selected = select(SenderRoundRobin::new(), &ledger, &mut pool).await;
+        assert_eq!(selected, ids([&a[0], &b1, &c1, &a[1]]));
+        // the fragments left are returned in the order they were received
+        assert_eq!(remaining(&mut pool), ids([&a[2], &a[3], &a[4], &b2]));
+    }
+
+    #[tokio::test]
+    async fn reserved_space_guarantees_the_vote_cast_share() {
+        let (ledger, controller) = scenario(u32::MAX);
+        let bob = controller.wallet(BOB).unwrap();
+        let vote_size = size(&vote_cast(&controller, &bob));
+        let transaction_size = size(&transaction(&ledger, &mut bob.clone(), 1));
+
+        // room for the 3 vote casts and 4 of the 10 transactions, the share
+        // of the vote casts being rounded up
+        let block_content_max_size = 3 * vote_size + 4 * transaction_size;
+        let vote_cast_share =
+            (300 * vote_size + block_content_max_size - 1) / block_content_max_size;
+        let (ledger, controller) = scenario(block_content_max_size);
+        let mut alice = controller.wallet(ALICE).unwrap();
+
+        // the transactions are received first and would fill the block
+        let transactions: Vec<_> = (0..10)
+            .map(|_| transaction(&ledger, &mut alice, 1))
+            .collect();
+        let votes: Vec<_> = [BOB, CLARA, DAVID]
+            .iter()
+            .map(|voter| vote_cast(&controller, &controller.wallet(voter).unwrap()))
+            .collect();
+        let mut pool = pool(transactions.iter().chain(&votes));
+
+        let selected = select(
+            ReservedSpace::new(vote_cast_share as u8),
+            &ledger,
+            &mut pool,
+        )
+        .await;
+        let (selected_votes, selected_transactions): (Vec<_>, Vec<_>) = selected
+            .into_iter()
+            .partition(|id| ids(&votes).contains(id));
+        assert_eq!(selected_votes, ids(&votes));
+        assert_eq!(selected_transactions, ids(&transactions[..4]));
+        assert_eq!(remaining(&mut pool), ids(&transactions[4..]));
+    }
+
+    #[tokio::test]
+    async fn reserved_space_gives_unused_space_to_the_other_fragments() {
+        let (ledger, controller) = scenario(u32::MAX);
+        let alice = controller.wallet(ALICE).unwrap();
+        let size = size(&transaction(&ledger, &mut alice.clone(), 1));
+
+        let (ledger, controller) = scenario(4 * size);
+        let mut alice = controller.wallet(ALICE).unwrap();
+        let transactions: Vec<_> = (0..6)
+            .map(|_| transaction(&ledger, &mut alice, 1))
+            .collect();
+        let mut pool = pool(&transactions);
+
+        // no vote casts are waiting: the transactions take the whole block
+        let selected = select(ReservedSpace::new(50), &ledger, &mut pool).await;
+        assert_eq!(selected, ids(&transactions[..4]));
+        assert_eq!(remaining(&mut pool), ids(&transactions[4..]));
     }
 }
@@ -3,10 +3,10 @@ use crate::{
     blockchain::{Checkpoints, LeadershipBlock, StorageError},
     fragment::selection::FragmentSelectionAlgorithmParams,
     network::p2p::comm::PeerInfo,
-    topology::{Gossips, NodeId, Peer, PeerInfo as TopologyPeerInfo, View},
+    topology::{layers::LayersConfig, Gossips, NodeId, Peer, PeerInfo as TopologyPeerInfo, View},
     utils::async_msg::{self, MessageBox, MessageQueue},
 };
-use chain_impl_mockchain::fragment::Contents as FragmentContents;
+use chain_impl_mockchain::{fee::LinearFee, fragment::Contents as FragmentContents};
 use chain_network::error as net_error;
 use futures::{
     channel::{mpsc, oneshot},
@@ -14,7 +14,8 @@ use futures::{
     ready,
 };
 use jormungandr_lib::interfaces::{
-    BlockDate, FragmentLog, FragmentOrigin, FragmentStatus, FragmentsProcessingSummary,
+    AccountPoolOccupancy, BlockDate, FragmentLog, FragmentOrigin, FragmentStatus,
+    FragmentTypePoolOccupancy, FragmentsProcessingSummary, PoolEviction,
 };
 use poldercast::layer::Selection;
 use std::{
@@ -511,6 +512,15 @@ pub enum TransactionMsg {
     RemoveTransactions(Vec<FragmentId>, FragmentStatus),
     BranchSwitch(BlockDate),
     GetLogs(ReplyHandle<Vec<FragmentLog>>),
+    GetAccountsOccupancy(ReplyHandle<Vec<AccountPoolOccupancy>>),
+    GetFragmentTypesOccupancy(ReplyHandle<Vec<FragmentTypePoolOccupancy>>),
+    SetLimits {
+        pool_max_entries: usize,
+        account_lane_max_entries: Option<usize>,
+        eviction: PoolEviction,
+    },
+    /// the fee settings of the ledger of the new tip, when they changed
+    SetLinearFees(LinearFee),
     GetStatuses(
         Vec<FragmentId>,
         ReplyHandle<HashMap<FragmentId, FragmentStatus>>,
@@ -614,6 +624,7 @@ pub enum TopologyMsg {
     AcceptGossip(Gossips),
     DemotePeer(NodeId),
     PromotePeer(NodeId),
+    SetLayers(LayersConfig),
     View(Selection, ReplyHandle<View>),
     ListAvailable(ReplyHandle<Vec<TopologyPeerInfo>>),
     ListNonPublic(ReplyHandle<Vec<TopologyPeerInfo>>),
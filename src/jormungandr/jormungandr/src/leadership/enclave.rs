@@ -1,4 +1,6 @@
 pub use crate::secure::enclave::{LeaderEvent, Schedule};
+#[cfg(unix)]
+use crate::secure::signer;
 use crate::{
     blockcfg::{
         HeaderBft, HeaderBftBuilder, HeaderGenesisPraos, HeaderGenesisPraosBuilder,
@@ -6,13 +8,26 @@ use crate::{
     },
     secure::enclave::Enclave as SecureEnclave,
 };
+#[cfg(unix)]
+use chain_crypto::Signature;
+#[cfg(unix)]
+use chain_impl_mockchain::{
+    key::BftLeaderId,
+    leadership::{LeaderOutput, LeadershipConsensus},
+};
 use std::sync::Arc;
 use thiserror::Error;
 
-#[derive(Debug, Clone, Error)]
+#[derive(Debug, Error)]
 pub enum EnclaveError {
     #[error("Enclave does not have a leader set")]
     EmptyEnclave,
+    #[cfg(unix)]
+    #[error("Request to the remote signer failed")]
+    Signer(#[from] signer::Error),
+    #[cfg(unix)]
+    #[error("The remote signer returned an invalid signature")]
+    InvalidSignature,
 }
 
 /// represent the client side of an enclave. From there we will query the
@@ -20,19 +35,32 @@ pub enum EnclaveError {
 ///
 #[derive(Clone)]
 pub struct Enclave {
-    /// TODO: we will need to remove this to instead query data to an
-    /// external service (running in the secure enclave). For now we
-    /// hold on the `SecureEnclave` . But it will need to be separated
-    /// when we have the necessary crypto done.
-    inner: Arc<SecureEnclave>,
+    inner: Backend,
+}
+
+#[derive(Clone)]
+enum Backend {
+    /// the leader keys are held in the node process
+    Local(Arc<SecureEnclave>),
+    /// the leader keys are held by a separate signer process
+    #[cfg(unix)]
+    Remote(signer::Client),
 }
 
 impl Enclave {
-    /// create a new enclave structure. This will need to change in the future
-    /// with a parameter to contact the secure enclave instead of the dummy type.
+    /// create a new enclave structure holding the leader keys in process.
     pub fn new(secure_enclave: SecureEnclave) -> Self {
         Enclave {
-            inner: Arc::new(secure_enclave),
+            inner: Backend::Local(Arc::new(secure_enclave)),
+        }
+    }
+
+    /// create a new enclave structure querying a remote signer which holds
+    /// the leader keys.
+    #[cfg(unix)]
+    pub fn remote(signer: signer::Client) -> Self {
+        Enclave {
+            inner: Backend::Remote(signer),
         }
     }
 
@@ -47,12 +75,19 @@ impl Enclave {
         slot_start: u32,
         nb_slots: u32,
     ) -> Result<Schedule, EnclaveError> {
-        Ok(Schedule::new(
-            self.inner.clone(),
-            leadership,
-            slot_start,
-            nb_slots,
-        ))
+        match &self.inner {
+            Backend::Local(enclave) => Ok(Schedule::new(
+                enclave.clone(),
+                leadership,
+                slot_start,
+                nb_slots,
+            )),
+            #[cfg(unix)]
+            Backend::Remote(signer) => {
+                let events = remote_schedule(signer, &leadership, slot_start, nb_slots).await?;
+                Ok(Schedule::from_events(leadership, events))
+            }
+        }
     }
 
     /// ask the leader associated to the `LeaderEvent` to finalize the given
@@ -60,14 +95,24 @@ impl Enclave {
     ///
     /// TODO: for now we are querying the whole with the block builder but on the long
     ///       run we will only need the block signing data.
-    pub fn query_header_bft_finalize(
+    pub async fn query_header_bft_finalize(
         &self,
         block_builder: HeaderBftBuilder<HeaderSetConsensusSignature>,
     ) -> Result<HeaderBft, EnclaveError> {
-        if let Some(block) = self.inner.create_header_bft(block_builder) {
-            Ok(block)
-        } else {
-            Err(EnclaveError::EmptyEnclave)
+        match &self.inner {
+            Backend::Local(enclave) => enclave
+                .create_header_bft(block_builder)
+                .ok_or(EnclaveError::EmptyEnclave),
+            #[cfg(unix)]
+            Backend::Remote(signer) => {
+                let signature = signer
+                    .sign_header(block_builder.get_authenticated_data())
+                    .await?;
+                let signature: Signature<_, chain_crypto::Ed25519> =
+                    Signature::from_binary(&signature)
+                        .map_err(|_| EnclaveError::InvalidSignature)?;
+                Ok(block_builder.set_signature(signature.into()))
+            }
         }
     }
 
@@ -76,14 +121,75 @@ impl Enclave {
     ///
     /// TODO: for now we are querying the whole with the block builder but on the long
     ///       run we will only need the block signing data.
-    pub fn query_header_genesis_praos_finalize(
+    pub async fn query_header_genesis_praos_finalize(
         &self,
         block_builder: HeaderGenesisPraosBuilder<HeaderSetConsensusSignature>,
     ) -> Result<HeaderGenesisPraos, EnclaveError> {
-        if let Some(block) = self.inner.create_header_genesis_praos(block_builder) {
-            Ok(block)
-        } else {
-            Err(EnclaveError::EmptyEnclave)
+        match &self.inner {
+            Backend::Local(enclave) => enclave
+                .create_header_genesis_praos(block_builder)
+                .ok_or(EnclaveError::EmptyEnclave),
+            #[cfg(unix)]
+            Backend::Remote(signer) => {
+                let signature = signer
+                    .sign_header(block_builder.get_authenticated_data())
+                    .await?;
+                let signature: Signature<_, chain_crypto::SumEd25519_12> =
+                    Signature::from_binary(&signature)
+                        .map_err(|_| EnclaveError::InvalidSignature)?;
+                Ok(block_builder.set_signature(signature.into()))
+            }
         }
     }
 }
+
+/// compute the leader events of the slots with the public identifiers of the
+/// leaders held by the remote signer, asking the signer to evaluate the Genesis
+/// Praos leader election which needs the VRF secret key.
+#[cfg(unix)]
+async fn remote_schedule(
+    signer: &signer::Client,
+    leadership: &Leadership,
+    slot_start: u32,
+    nb_slots: u32,
+) -> Result<Vec<LeaderEvent>, EnclaveError> {
+    let leaders = signer.leaders().await?;
+    let events = match leadership.consensus() {
+        LeadershipConsensus::Bft(bft) => {
+            let leader_id = match leaders.bft {
+                Some(key) => BftLeaderId::from(key),
+                None => return Ok(Vec::new()),
+            };
+            (slot_start..slot_start + nb_slots)
+                .map(|slot| leadership.date_at_slot(slot))
+                .filter(|date| bft.get_leader_at(*date) == leader_id)
+                .map(|date| LeaderEvent {
+                    date,
+                    output: LeaderOutput::Bft(leader_id.clone()),
+                })
+                .collect()
+        }
+        LeadershipConsensus::GenesisPraos(genesis) => {
+            let pool_id = match leaders.genesis {
+                Some(pool_id) => pool_id,
+                None => return Ok(Vec::new()),
+            };
+            // as with the local enclave, a pool without stake is not elected
+            let election = match genesis.leader_election(&pool_id) {
+                Ok(Some(election)) => election,
+                _ => return Ok(Vec::new()),
+            };
+            signer
+                .elect(&election, slot_start, nb_slots)
+                .await?
+                .into_iter()
+                .filter(|(slot, _)| (slot_start..slot_start + nb_slots).contains(slot))
+                .map(|(slot, witness)| LeaderEvent {
+                    date: leadership.date_at_slot(slot),
+                    output: LeaderOutput::GenesisPraos(pool_id.clone(), witness),
+                })
+                .collect()
+        }
+    };
+    Ok(events)
+}
@@ -23,8 +23,8 @@
 //!       the schedule to know if the scheduled block as been accepted in the
 //!       branch;
 //!
-//! The enclave is not yet implemented, but we will need to separate the crypto
-//! from the representation here.
+//! The enclave either holds the leader keys in the node process, or queries
+//! a remote signer process holding them (see `secure::signer`).
 //!
 //! ## workflow
 //!
@@ -1,6 +1,10 @@
 use crate::{
-    blockcfg::{block_builder, ApplyBlockLedger, BlockVersion, Contents, LeaderOutput, Leadership},
+    blockcfg::{
+        block_builder, block_builder_async, ApplyBlockLedger, BlockVersion, Contents, LeaderOutput,
+        Leadership,
+    },
     blockchain::{new_epoch_leadership_from, EpochLeadership, LeadershipBlock, Ref, Tip},
+    fragment::selection::FragmentSelectionAlgorithmParams,
     intercom::{unary_reply, BlockMsg, Error as IntercomError, TransactionMsg},
     leadership::{
         enclave::{Enclave, EnclaveError, LeaderEvent, Schedule},
@@ -68,6 +72,7 @@ pub struct Module {
     enclave: Enclave,
     block_message: MessageBox<BlockMsg>,
     rewards_report_all: bool,
+    selection_alg: FragmentSelectionAlgorithmParams,
     // the maximum number of slots we can allow the leader event to run for
     block_hard_deadline: u32,
 }
@@ -80,6 +85,7 @@ pub struct ModuleConfig {
     pub enclave: Enclave,
     pub block_message: MessageBox<BlockMsg>,
     pub rewards_report_all: bool,
+    pub selection_alg: FragmentSelectionAlgorithmParams,
     pub block_hard_deadline: u32,
 }
 
@@ -97,6 +103,7 @@ impl Module {
             enclave: config.enclave,
             block_message: config.block_message,
             rewards_report_all: config.rewards_report_all,
+            selection_alg: config.selection_alg,
             block_hard_deadline: config.block_hard_deadline,
         })
     }
@@ -486,8 +493,14 @@ impl Module {
             .begin_block(chain_length, event.date)
             .map_err(Box::new)?;
 
-        let (contents, ledger) =
-            prepare_block(pool, ledger, soft_deadline_future, hard_deadline_future).await?;
+        let (contents, ledger) = prepare_block(
+            pool,
+            ledger,
+            self.selection_alg,
+            soft_deadline_future,
+            hard_deadline_future,
+        )
+        .await?;
 
         let event_logs_error = event_logs.clone();
         let signing = {
@@ -510,7 +523,7 @@ impl Module {
                 })
                 .map(Some),
                 LeaderOutput::Bft(leader_id) => {
-                    let block = block_builder(ver, contents, |hdr_builder| {
+                    let block = block_builder_async(ver, contents, |hdr_builder| async move {
                         let final_builder = hdr_builder
                             .set_parent(&parent_id, chain_length)
                             .set_date(date)
@@ -520,8 +533,10 @@ impl Module {
 
                         enclave
                             .query_header_bft_finalize(final_builder)
+                            .await
                             .map(|h| h.generalize())
-                    });
+                    })
+                    .await;
 
                     match block {
                         Ok(block) => Ok(Some(block)),
@@ -536,7 +551,7 @@ impl Module {
                     }
                 }
                 LeaderOutput::GenesisPraos(node_id, vrfproof) => {
-                    let block = block_builder(ver, contents, |hdr_builder| {
+                    let block = block_builder_async(ver, contents, |hdr_builder| async move {
                         let final_builder = hdr_builder
                             .set_parent(&parent_id, chain_length)
                             .set_date(date)
@@ -546,8 +561,10 @@ impl Module {
 
                         enclave
                             .query_header_genesis_praos_finalize(final_builder)
+                            .await
                             .map(|h| h.generalize())
-                    });
+                    })
+                    .await;
 
                     match block {
                         Ok(block) => Ok(Some(block)),
@@ -692,16 +709,15 @@ impl Entry {
 async fn prepare_block(
     mut fragment_pool: MessageBox<TransactionMsg>,
     ledger: ApplyBlockLedger,
+    selection_alg: FragmentSelectionAlgorithmParams,
     soft_deadline_future: futures::channel::oneshot::Receiver<()>,
     hard_deadline_future: futures::channel::oneshot::Receiver<()>,
 ) -> Result<(Contents, ApplyBlockLedger), LeadershipError> {
-    use crate::fragment::selection::FragmentSelectionAlgorithmParams;
-
     let (reply_handle, reply_future) = unary_reply();
 
     let msg = TransactionMsg::SelectTransactions {
         ledger,
-        selection_alg: FragmentSelectionAlgorithmParams::OldestFirst,
+        selection_alg,
         reply_handle,
         soft_deadline_future,
         hard_deadline_future,
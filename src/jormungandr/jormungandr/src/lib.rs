@@ -18,16 +18,17 @@ use crate::{
 };
 use chain_impl_mockchain::leadership::LeadershipConsensus;
 use futures::{executor::block_on, prelude::*};
-use jormungandr_lib::interfaces::NodeState;
+use jormungandr_lib::interfaces::{NodeState, PersistentLog, LEDGER_CHECKPOINTS_DIRECTORY};
 use settings::{logging::LogGuard, start::RawSettings, CommandLine};
 use std::{sync::Arc, time::Duration};
-use tokio::signal;
+use tokio::{signal, sync::watch};
 use tokio_util::sync::CancellationToken;
 use tracing::{span, Level, Span};
 
 pub mod blockcfg;
 pub mod blockchain;
 pub mod client;
+pub mod config_reload;
 pub mod context;
 pub mod diagnostic;
 pub mod fragment;
@@ -79,6 +80,7 @@ const CLIENT_TASK_QUEUE_LEN: usize = 32;
 const TOPOLOGY_TASK_QUEUE_LEN: usize = 32;
 const WATCH_CLIENT_TASK_QUEUE_LEN: usize = 32;
 const BOOTSTRAP_RETRY_WAIT: Duration = Duration::from_secs(5);
+const OTLP_METRICS_EXPORT_INTERVAL: Duration = Duration::from_secs(10);
 
 fn start_services(bootstrapped_node: BootstrappedNode) -> Result<(), start_up::Error> {
     if let Some(context) = bootstrapped_node.context.as_ref() {
@@ -119,6 +121,25 @@ fn start_services(bootstrapped_node: BootstrappedNode) -> Result<(), start_up::E
         (None, metrics_builder)
     };
 
+    let metrics_builder = match bootstrapped_node.settings.otlp_metrics.clone() {
+        Some(otlp_metrics) => {
+            let export_interval = otlp_metrics
+                .export_interval
+                .map_or(OTLP_METRICS_EXPORT_INTERVAL, Into::into);
+            // the exporter sends the metrics from a task of the Tokio runtime
+            let otlp = services
+                .block_on_task("otlp_metrics", |_| async {
+                    crate::metrics::backends::Otlp::new(
+                        otlp_metrics.collector_endpoint,
+                        export_interval,
+                    )
+                })
+                .map_err(start_up::Error::OtlpMetrics)?;
+            metrics_builder.add_backend(Arc::new(otlp))
+        }
+        None => metrics_builder,
+    };
+
     let stats_counter = metrics_builder.build();
 
     {
@@ -269,10 +290,21 @@ fn start_services(bootstrapped_node: BootstrappedNode) -> Result<(), start_up::E
         let logs = leadership_logs.clone();
         let block_message = block_msgbox;
         let tip = blockchain_tip.clone();
-        let enclave = leadership::Enclave::new(enclave.clone());
+        let enclave = match bootstrapped_node.settings.leadership.signer.clone() {
+            #[cfg(unix)]
+            Some(socket_path) => {
+                tracing::info!(
+                    "using the remote signer at {} for leadership",
+                    socket_path.display()
+                );
+                leadership::Enclave::remote(secure::signer::Client::new(socket_path))
+            }
+            _ => leadership::Enclave::new(enclave.clone()),
+        };
         let pool = fragment_msgbox.clone();
         let rewards_report_all = bootstrapped_node.settings.rewards_report_all;
         let block_hard_deadline = bootstrapped_node.settings.block_hard_deadline;
+        let selection_alg = bootstrapped_node.settings.mempool.fragment_selection.into();
 
         services.spawn_try_future("leadership", move |service_info| {
             leadership::Module::new(leadership::ModuleConfig {
@@ -283,6 +315,7 @@ fn start_services(bootstrapped_node: BootstrappedNode) -> Result<(), start_up::E
                 enclave,
                 block_message,
                 rewards_report_all,
+                selection_alg,
                 block_hard_deadline,
             })
             .and_then(|module| module.run())
@@ -290,22 +323,66 @@ fn start_services(bootstrapped_node: BootstrappedNode) -> Result<(), start_up::E
     }
 
     {
+        let tip = services.block_on_task("get_tip_block", |_| blockchain_tip.get_ref());
         let process = fragment::Process::new(
             bootstrapped_node.settings.mempool.pool_max_entries.into(),
+            bootstrapped_node.settings.mempool.account_lane_max_entries,
+            bootstrapped_node.settings.mempool.eviction,
+            tip.ledger().settings().linear_fees.clone(),
             bootstrapped_node.settings.mempool.log_max_entries.into(),
             network_msgbox.clone(),
         );
-        let fragment_log_dir = bootstrapped_node
-            .settings
-            .mempool
-            .persistent_log
-            .map(|s| s.dir);
+        let persistent_log = bootstrapped_node.settings.mempool.persistent_log;
+        let replayed_fragments = match &persistent_log {
+            Some(PersistentLog {
+                dir,
+                replay_window: Some(window),
+            }) => fragment::pending_fragments_from_persistent_log(
+                dir,
+                (*window).into(),
+                &tip,
+                blockchain.storage(),
+            ),
+            _ => Vec::new(),
+        };
+        let fragment_log_dir = persistent_log.map(|s| s.dir);
 
         services.spawn_try_future("fragment", move |info| {
-            process.start(info, stats_counter, fragment_queue, fragment_log_dir)
+            process.start(
+                info,
+                stats_counter,
+                fragment_queue,
+                fragment_log_dir,
+                replayed_fragments,
+            )
         });
     };
 
+    let (warning_interval_sender, warning_interval) = watch::channel(
+        bootstrapped_node
+            .settings
+            .no_blockchain_updates_warning_interval,
+    );
+    let config_reloader = bootstrapped_node.settings.config_file.map(|config_file| {
+        config_reload::ConfigReloader::new(
+            config_file,
+            bootstrapped_node._logger_guard.level_handle(),
+            fragment_msgbox.clone(),
+            topology_msgbox.clone(),
+            bootstrapped_node.settings.network.clone(),
+            warning_interval_sender,
+        )
+    });
+
+    #[cfg(unix)]
+    {
+        if let Some(reloader) = config_reloader.clone() {
+            services.spawn_future("sighup_watcher", move |_| {
+                config_reload::reload_on_sighup(reloader)
+            });
+        }
+    }
+
     if let Some(context) = bootstrapped_node.context {
         let full_context = context::FullContext {
             stats_counter: simple_metrics_counter,
@@ -317,6 +394,7 @@ fn start_services(bootstrapped_node: BootstrappedNode) -> Result<(), start_up::E
             network_state,
             #[cfg(feature = "prometheus-metrics")]
             prometheus: prometheus_metric,
+            config_reloader,
         };
         block_on(async {
             let mut context = context.write().await;
@@ -327,15 +405,9 @@ fn start_services(bootstrapped_node: BootstrappedNode) -> Result<(), start_up::E
 
     {
         let blockchain_tip = blockchain_tip;
-        let no_blockchain_updates_warning_interval = bootstrapped_node
-            .settings
-            .no_blockchain_updates_warning_interval;
 
         services.spawn_future("stuck_notifier", move |_| {
-            stuck_notifier::check_last_block_time(
-                blockchain_tip,
-                no_blockchain_updates_warning_interval,
-            )
+            stuck_notifier::check_last_block_time(blockchain_tip, warning_interval)
         });
     }
 
@@ -436,10 +508,19 @@ async fn bootstrap_internal(
     let block0_hash = block0.header().hash();
 
     let cache_capacity = 102_400;
+    let ledger_checkpoints = settings
+        .storage
+        .as_ref()
+        .map(|storage| storage.join(LEDGER_CHECKPOINTS_DIRECTORY));
 
-    let (blockchain, blockchain_tip) =
-        start_up::load_blockchain(block0, storage, cache_capacity, settings.rewards_report_all)
-            .await?;
+    let (blockchain, blockchain_tip) = start_up::load_blockchain(
+        block0,
+        storage,
+        cache_capacity,
+        settings.rewards_report_all,
+        ledger_checkpoints,
+    )
+    .await?;
 
     if let Some(context) = &context {
         let mut context = context.write().await;
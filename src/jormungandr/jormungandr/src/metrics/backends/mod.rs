@@ -1,7 +1,9 @@
+mod otlp_exporter;
 #[cfg(feature = "prometheus-metrics")]
 mod prometheus_exporter;
 mod simple_counter;
 
+pub use otlp_exporter::Otlp;
 #[cfg(feature = "prometheus-metrics")]
 pub use prometheus_exporter::Prometheus;
 pub use simple_counter::SimpleCounter;
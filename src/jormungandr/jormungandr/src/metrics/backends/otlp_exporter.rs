@@ -0,0 +1,635 @@
+use crate::{blockchain::Ref, metrics::MetricsBackend, topology::NodeId};
+use chain_impl_mockchain::{
+    block::Block,
+    fragment::Fragment,
+    transaction::Transaction,
+    value::{Value, ValueError},
+};
+use jormungandr_lib::time::SecondsSinceUnixEpoch;
+use opentelemetry::{
+    metrics::{Counter, Histogram, MeterProvider as _, MetricsError, Unit, UpDownCounter},
+    runtime,
+    sdk::{
+        export::metrics::aggregation::cumulative_temporality_selector,
+        metrics::{controllers::BasicController, selectors},
+        Resource,
+    },
+    Context, KeyValue,
+};
+use opentelemetry_otlp::WithExportConfig;
+use std::{
+    collections::{HashMap, HashSet},
+    convert::TryInto,
+    sync::{
+        atomic::{AtomicU32, AtomicU64, Ordering},
+        Arc, Mutex, RwLock,
+    },
+    time::{Duration, SystemTime},
+};
+
+/// Upper bounds, in seconds, of the buckets of the duration histograms.
+const DURATION_BOUNDARIES: [f64; 13] = [
+    0.0001, 0.0005, 0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 10.0,
+];
+
+/// Maximum number of distinct values of the `vote_plan` and `peer`
+/// attributes, each value is a series the collector keeps for the lifetime of
+/// the node.
+const MAX_ATTRIBUTE_VALUES: usize = 64;
+
+/// Value of an attribute standing for the values past `MAX_ATTRIBUTE_VALUES`.
+const OTHER_ATTRIBUTE_VALUE: &str = "other";
+
+/// Exports the node metrics to an OpenTelemetry collector over OTLP/gRPC.
+pub struct Otlp {
+    // drives the periodic collection and export of the metrics
+    _controller: BasicController,
+
+    tx_recv_cnt: Counter<u64>,
+    tx_rejected_cnt: Counter<u64>,
+    votes_cast_cnt: Counter<u64>,
+    // chain length of the last tip block whose vote casts were counted
+    votes_cast_chain_length: AtomicU32,
+    vote_plans: BoundedAttribute,
+    block_recv_cnt: Counter<u64>,
+    peer_connected_cnt: UpDownCounter<i64>,
+    peer_quarantined_cnt: UpDownCounter<i64>,
+    block_validation_time: Histogram<f64>,
+    fragment_apply_time: Histogram<f64>,
+    peer_sync_blocks: Counter<u64>,
+    peer_sync_bytes: Counter<u64>,
+    peer_sync_time: Histogram<f64>,
+    // the first peers blocks are received from, usually the connected ones
+    peers: BoundedAttribute,
+    storage_flush_time: Histogram<f64>,
+
+    gauges: Arc<Gauges>,
+}
+
+/// Last values of the gauges, read by the collection callback.
+#[derive(Default)]
+struct Gauges {
+    mempool_usage_ratio: RwLock<f64>,
+    mempool_tx_count: AtomicU64,
+    peer_available_cnt: AtomicU64,
+    slot_start_time: AtomicU64,
+    block_tx_count: AtomicU64,
+    block_input_sum: AtomicU64,
+    block_fee_sum: AtomicU64,
+    block_content_size: AtomicU64,
+    block_epoch: AtomicU64,
+    block_slot: AtomicU64,
+    block_chain_length: AtomicU64,
+    block_time: AtomicU64,
+}
+
+/// Values of an attribute, the first `limit` distinct values are exported as
+/// is and the following ones as `OTHER_ATTRIBUTE_VALUE`.
+struct BoundedAttribute {
+    key: &'static str,
+    limit: usize,
+    values: Mutex<HashSet<String>>,
+}
+
+impl BoundedAttribute {
+    fn new(key: &'static str, limit: usize) -> Self {
+        Self {
+            key,
+            limit,
+            values: Mutex::new(HashSet::new()),
+        }
+    }
+
+    fn key_value(&self, value: String) -> KeyValue {
+        let mut values = self.values.lock().unwrap();
+        if values.contains(&value) || values.len() < self.limit {
+            values.insert(value.clone());
+            KeyValue::new(self.key, value)
+        } else {
+            KeyValue::new(self.key, OTHER_ATTRIBUTE_VALUE)
+        }
+    }
+}
+
+impl Otlp {
+    /// Starts exporting the metrics to the collector at `endpoint` every
+    /// `export_interval`. This must be called from within a Tokio runtime.
+    pub fn new(endpoint: url::Url, export_interval: Duration) -> Result<Self, MetricsError> {
+        let controller = opentelemetry_otlp::new_pipeline()
+            .metrics(
+                selectors::simple::histogram(DURATION_BOUNDARIES),
+                cumulative_temporality_selector(),
+                runtime::Tokio,
+            )
+            .with_exporter(
+                opentelemetry_otlp::new_exporter()
+                    .tonic()
+                    .with_endpoint(endpoint),
+            )
+            .with_period(export_interval)
+            .with_resource(Resource::new(vec![KeyValue::new(
+                opentelemetry_semantic_conventions::resource::SERVICE_NAME,
+                "jormungandr",
+            )]))
+            .build()?;
+        let meter =
+            controller.versioned_meter("jormungandr", Some(env!("CARGO_PKG_VERSION")), None);
+
+        let seconds = || Unit::new("s");
+
+        let gauges = Arc::new(Gauges::default());
+        let mempool_usage_ratio = meter
+            .f64_observable_gauge("jormungandr.mempool.usage_ratio")
+            .with_description("ratio of the mempool capacity in use")
+            .init();
+        let mempool_tx_count = meter
+            .u64_observable_gauge("jormungandr.mempool.fragments")
+            .with_description("number of fragments in the mempool")
+            .init();
+        let peer_available_cnt = meter
+            .u64_observable_gauge("jormungandr.peer.available")
+            .with_description("number of peers available in the topology")
+            .init();
+        let slot_start_time = meter
+            .u64_observable_gauge("jormungandr.block.last_received_time")
+            .with_description("start time of the slot of the last block received")
+            .with_unit(seconds())
+            .init();
+        let block_tx_count = meter
+            .u64_observable_gauge("jormungandr.tip.transactions")
+            .with_description("number of transactions in the tip block")
+            .init();
+        let block_input_sum = meter
+            .u64_observable_gauge("jormungandr.tip.input_sum")
+            .with_description("sum of the transaction inputs of the tip block")
+            .init();
+        let block_fee_sum = meter
+            .u64_observable_gauge("jormungandr.tip.fee_sum")
+            .with_description("sum of the fees of the tip block")
+            .init();
+        let block_content_size = meter
+            .u64_observable_gauge("jormungandr.tip.content_size")
+            .with_description("size of the contents of the tip block")
+            .with_unit(Unit::new("By"))
+            .init();
+        let block_epoch = meter
+            .u64_observable_gauge("jormungandr.tip.epoch")
+            .with_description("epoch of the tip block")
+            .init();
+        let block_slot = meter
+            .u64_observable_gauge("jormungandr.tip.slot")
+            .with_description("slot of the tip block in its epoch")
+            .init();
+        let block_chain_length = meter
+            .u64_observable_gauge("jormungandr.tip.chain_length")
+            .with_description("chain length of the tip block")
+            .init();
+        let block_time = meter
+            .u64_observable_gauge("jormungandr.tip.time")
+            .with_description("time of the slot of the tip block")
+            .with_unit(seconds())
+            .init();
+        {
+            let gauges = Arc::clone(&gauges);
+            meter.register_callback(move |cx| {
+                let load = |value: &AtomicU64| value.load(Ordering::Relaxed);
+                mempool_usage_ratio.observe(cx, *gauges.mempool_usage_ratio.read().unwrap(), &[]);
+                mempool_tx_count.observe(cx, load(&gauges.mempool_tx_count), &[]);
+                peer_available_cnt.observe(cx, load(&gauges.peer_available_cnt), &[]);
+                slot_start_time.observe(cx, load(&gauges.slot_start_time), &[]);
+                block_tx_count.observe(cx, load(&gauges.block_tx_count), &[]);
+                block_input_sum.observe(cx, load(&gauges.block_input_sum), &[]);
+                block_fee_sum.observe(cx, load(&gauges.block_fee_sum), &[]);
+                block_content_size.observe(cx, load(&gauges.block_content_size), &[]);
+                block_epoch.observe(cx, load(&gauges.block_epoch), &[]);
+                block_slot.observe(cx, load(&gauges.block_slot), &[]);
+                block_chain_length.observe(cx, load(&gauges.block_chain_length), &[]);
+                block_time.observe(cx, load(&gauges.block_time), &[]);
+            })?;
+        }
+
+        Ok(Self {
+            _controller: controller,
+            tx_recv_cnt: meter
+                .u64_counter("jormungandr.tx.received")
+                .with_description("number of fragments accepted in the mempool")
+                .init(),
+            tx_rejected_cnt: meter
+                .u64_counter("jormungandr.tx.rejected")
+                .with_description("number of fragments rejected from the mempool")
+                .init(),
+            votes_cast_cnt: meter
+                .u64_counter("jormungandr.vote.cast")
+                .with_description("number of vote casts in the blocks of the chain, per vote plan")
+                .init(),
+            votes_cast_chain_length: AtomicU32::new(0),
+            vote_plans: BoundedAttribute::new("vote_plan", MAX_ATTRIBUTE_VALUES),
+            block_recv_cnt: meter
+                .u64_counter("jormungandr.block.received")
+                .with_description("number of blocks received from the network")
+                .init(),
+            peer_connected_cnt: meter
+                .i64_up_down_counter("jormungandr.peer.connected")
+                .with_description("number of connected peers")
+                .init(),
+            peer_quarantined_cnt: meter
+                .i64_up_down_counter("jormungandr.peer.quarantined")
+                .with_description("number of quarantined peers")
+                .init(),
+            block_validation_time: meter
+                .f64_histogram("jormungandr.block.validation_time")
+                .with_description("time spent checking and applying a block from the network")
+                .with_unit(seconds())
+                .init(),
+            fragment_apply_time: meter
+                .f64_histogram("jormungandr.fragment.apply_time")
+                .with_description("time spent applying a fragment to the ledger, per fragment type")
+                .with_unit(seconds())
+                .init(),
+            peer_sync_blocks: meter
+                .u64_counter("jormungandr.peer.sync.blocks")
+                .with_description("number of blocks received from the peers")
+                .init(),
+            peer_sync_bytes: meter
+                .u64_counter("jormungandr.peer.sync.bytes")
+                .with_description("size of the blocks received from the peers")
+                .with_unit(Unit::new("By"))
+                .init(),
+            peer_sync_time: meter
+                .f64_histogram("jormungandr.peer.sync.time")
+                .with_description("time spent receiving the blocks solicited from the peers")
+                .with_unit(seconds())
+                .init(),
+            peers: BoundedAttribute::new("peer", MAX_ATTRIBUTE_VALUES),
+            storage_flush_time: meter
+                .f64_histogram("jormungandr.storage.flush_time")
+                .with_description("time spent flushing blocks to the permanent storage")
+                .with_unit(seconds())
+                .init(),
+            gauges,
+        })
+    }
+
+    /// Counts the vote casts of the tip block at `chain_length`, unless a
+    /// block at that chain length was already counted: when switching
+    /// branches, the new tip replaces blocks whose votes are in the counter.
+    fn add_votes_cast(&self, chain_length: u32, votes_cast: HashMap<String, u64>) {
+        let counted = self
+            .votes_cast_chain_length
+            .fetch_max(chain_length, Ordering::Relaxed);
+        if chain_length <= counted {
+            return;
+        }
+        let cx = Context::current();
+        for (vote_plan, count) in votes_cast {
+            self.votes_cast_cnt
+                .add(&cx, count, &[self.vote_plans.key_value(vote_plan)]);
+        }
+    }
+}
+
+impl MetricsBackend for Otlp {
+    fn add_tx_recv_cnt(&self, count: usize) {
+        self.tx_recv_cnt.add(&Context::current(), count as u64, &[]);
+    }
+
+    fn add_tx_rejected_cnt(&self, count: usize) {
+        self.tx_rejected_cnt
+            .add(&Context::current(), count as u64, &[]);
+    }
+
+    fn set_mempool_usage_ratio(&self, ratio: f64) {
+        *self.gauges.mempool_usage_ratio.write().unwrap() = ratio;
+    }
+
+    fn set_mempool_tx_count(&self, size: usize) {
+        self.gauges
+            .mempool_tx_count
+            .store(size as u64, Ordering::Relaxed);
+    }
+
+    fn add_block_recv_cnt(&self, count: usize) {
+        self.block_recv_cnt
+            .add(&Context::current(), count as u64, &[]);
+    }
+
+    fn add_peer_connected_cnt(&self, count: usize) {
+        self.peer_connected_cnt
+            .add(&Context::current(), count as i64, &[]);
+    }
+
+    fn sub_peer_connected_cnt(&self, count: usize) {
+        self.peer_connected_cnt
+            .add(&Context::current(), -(count as i64), &[]);
+    }
+
+    fn add_peer_quarantined_cnt(&self, count: usize) {
+        self.peer_quarantined_cnt
+            .add(&Context::current(), count as i64, &[]);
+    }
+
+    fn sub_peer_quarantined_cnt(&self, count: usize) {
+        self.peer_quarantined_cnt
+            .add(&Context::current(), -(count as i64), &[]);
+    }
+
+    fn set_peer_available_cnt(&self, count: usize) {
+        self.gauges
+            .peer_available_cnt
+            .store(count as u64, Ordering::Relaxed);
+    }
+
+    fn set_slot_start_time(&self, time: SecondsSinceUnixEpoch) {
+        self.gauges
+            .slot_start_time
+            .store(time.to_secs(), Ordering::Relaxed);
+    }
+
+    fn set_tip_block(&self, block: &Block, block_ref: &Ref) {
+        let mut block_tx_count = 0;
+        let mut block_input_sum = Value::zero();
+        let mut block_fee_sum = Value::zero();
+        let mut votes_cast = HashMap::new();
+
+        block
+            .contents()
+            .iter()
+            .try_for_each::<_, Result<(), ValueError>>(|fragment| {
+                fn totals<T>(t: &Transaction<T>) -> Result<(Value, Value), ValueError> {
+                    Ok((t.total_input()?, t.total_output()?))
+                }
+
+                let (total_input, total_output) = match &fragment {
+                    Fragment::Transaction(tx) => totals(tx),
+                    Fragment::OwnerStakeDelegation(tx) => totals(tx),
+                    Fragment::StakeDelegation(tx) => totals(tx),
+                    Fragment::PoolRegistration(tx) => totals(tx),
+                    Fragment::PoolRetirement(tx) => totals(tx),
+                    Fragment::PoolUpdate(tx) => totals(tx),
+                    Fragment::VotePlan(tx) => totals(tx),
+                    Fragment::VoteCast(tx) => {
+                        let vote_cast = tx.as_slice().payload().into_payload();
+                        *votes_cast
+                            .entry(vote_cast.vote_plan().to_string())
+                            .or_insert(0) += 1;
+                        totals(tx)
+                    }
+                    Fragment::VoteTally(tx) => totals(tx),
+                    Fragment::MintToken(tx) => totals(tx),
+                    Fragment::UpdateProposal(tx) => totals(tx),
+                    Fragment::UpdateVote(tx) => totals(tx),
+                    Fragment::EvmMapping(tx) => totals(tx),
+                    Fragment::MultisigRegistration(tx) => totals(tx),
+                    Fragment::Initial(_) | Fragment::OldUtxoDeclaration(_) | Fragment::Evm(_) => {
+                        return Ok(())
+                    }
+                }?;
+                block_tx_count += 1;
+                block_input_sum = (block_input_sum + total_input)?;
+                let fee = (total_input - total_output).unwrap_or_else(|_| Value::zero());
+                block_fee_sum = (block_fee_sum + fee)?;
+                Ok(())
+            })
+            .expect("should be good");
+
+        let header = block.header();
+        let chain_length: u32 = header.chain_length().try_into().unwrap();
+        self.add_votes_cast(chain_length, votes_cast);
+        let block_time = block_ref
+            .time()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let gauges = &self.gauges;
+        gauges
+            .block_tx_count
+            .store(block_tx_count, Ordering::Relaxed);
+        gauges
+            .block_input_sum
+            .store(block_input_sum.0, Ordering::Relaxed);
+        gauges
+            .block_fee_sum
+            .store(block_fee_sum.0, Ordering::Relaxed);
+        gauges
+            .block_content_size
+            .store(header.block_content_size().into(), Ordering::Relaxed);
+        gauges
+            .block_epoch
+            .store(header.block_date().epoch.into(), Ordering::Relaxed);
+        gauges
+            .block_slot
+            .store(header.block_date().slot_id.into(), Ordering::Relaxed);
+        gauges
+            .block_chain_length
+            .store(chain_length.into(), Ordering::Relaxed);
+        gauges.block_time.store(block_time, Ordering::Relaxed);
+    }
+
+    fn add_block_validation_time(&self, duration: Duration) {
+        self.block_validation_time
+            .record(&Context::current(), duration.as_secs_f64(), &[]);
+    }
+
+    fn add_fragment_apply_time(&self, fragment_type: &'static str, duration: Duration) {
+        self.fragment_apply_time.record(
+            &Context::current(),
+            duration.as_secs_f64(),
+            &[KeyValue::new("fragment_type", fragment_type)],
+        );
+    }
+
+    fn add_peer_blocks_synced(
+        &self,
+        peer: NodeId,
+        blocks: usize,
+        bytes: usize,
+        duration: Duration,
+    ) {
+        let cx = Context::current();
+        let attributes = [self.peers.key_value(peer.to_string())];
+        self.peer_sync_blocks.add(&cx, blocks as u64, &attributes);
+        self.peer_sync_bytes.add(&cx, bytes as u64, &attributes);
+        self.peer_sync_time
+            .record(&cx, duration.as_secs_f64(), &attributes);
+    }
+
+    fn add_storage_flush_time(&self, duration: Duration) {
+        self.storage_flush_time
+            .record(&Context::current(), duration.as_secs_f64(), &[]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry_proto::tonic::{
+        collector::metrics::v1::{
+            metrics_service_server::{MetricsService, MetricsServiceServer},
+            ExportMetricsServiceRequest, ExportMetricsServiceResponse,
+        },
+        common::v1::{any_value, KeyValue as ProtoKeyValue},
+        metrics::v1::{metric::Data, number_data_point, Metric, NumberDataPoint},
+    };
+    use otlp_tonic::{transport::Server, Request, Response, Status};
+    use tokio::{net::TcpListener, sync::mpsc};
+    use tokio_stream::wrappers::TcpListenerStream;
+
+    /// Collector forwarding the exported metrics to the test.
+    struct Collector(mpsc::UnboundedSender<ExportMetricsServiceRequest>);
+
+    #[async_trait::async_trait]
+    impl MetricsService for Collector {
+        async fn export(
+            &self,
+            request: Request<ExportMetricsServiceRequest>,
+        ) -> Result<Response<ExportMetricsServiceResponse>, Status> {
+            let _ = self.0.send(request.into_inner());
+            Ok(Response::new(ExportMetricsServiceResponse::default()))
+        }
+    }
+
+    fn metrics(request: &ExportMetricsServiceRequest) -> impl Iterator<Item = &Metric> {
+        request
+            .resource_metrics
+            .iter()
+            .flat_map(|resource| &resource.scope_metrics)
+            .flat_map(|scope| &scope.metrics)
+    }
+
+    fn metric<'a>(request: &'a ExportMetricsServiceRequest, name: &str) -> &'a Metric {
+        metrics(request)
+            .find(|metric| metric.name == name)
+            .unwrap_or_else(|| panic!("metric {} not exported", name))
+    }
+
+    fn attributes(attributes: &[ProtoKeyValue]) -> Vec<(String, String)> {
+        attributes
+            .iter()
+            .map(|attribute| {
+                let value = match attribute.value.as_ref().and_then(|v| v.value.as_ref()) {
+                    Some(any_value::Value::StringValue(value)) => value.clone(),
+                    value => panic!("unexpected attribute value {:?}", value),
+                };
+                (attribute.key.clone(), value)
+            })
+            .collect()
+    }
+
+    /// Attributes and values of the data points of a sum.
+    fn sum(metric: &Metric) -> Vec<(Vec<(String, String)>, f64)> {
+        let value = |point: &NumberDataPoint| match point.value {
+            Some(number_data_point::Value::AsInt(value)) => value as f64,
+            Some(number_data_point::Value::AsDouble(value)) => value,
+            None => panic!("no value in a data point of {}", metric.name),
+        };
+        match &metric.data {
+            Some(Data::Sum(sum)) => sum
+                .data_points
+                .iter()
+                .map(|point| (attributes(&point.attributes), value(point)))
+                .collect(),
+            data => panic!("{} is not a sum: {:?}", metric.name, data),
+        }
+    }
+
+    /// Attributes of the data points of a histogram.
+    fn histogram(metric: &Metric) -> Vec<Vec<(String, String)>> {
+        match &metric.data {
+            Some(Data::Histogram(histogram)) => histogram
+                .data_points
+                .iter()
+                .map(|point| attributes(&point.attributes))
+                .collect(),
+            data => panic!("{} is not a histogram: {:?}", metric.name, data),
+        }
+    }
+
+    #[tokio::test]
+    async fn export_metric_names_and_attributes() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap())
+            .parse()
+            .unwrap();
+        let (sender, mut requests) = mpsc::unbounded_channel();
+        tokio::spawn(
+            Server::builder()
+                .add_service(MetricsServiceServer::new(Collector(sender)))
+                .serve_with_incoming(TcpListenerStream::new(listener)),
+        );
+
+        let otlp = Otlp::new(endpoint, Duration::from_millis(100)).unwrap();
+        otlp.add_tx_recv_cnt(3);
+        otlp.set_mempool_tx_count(2);
+        otlp.add_fragment_apply_time("transaction", Duration::from_millis(1));
+        let peer = NodeId::from_seed(0);
+        otlp.add_peer_blocks_synced(peer, 2, 1024, Duration::from_millis(10));
+        let votes = |count| HashMap::from([("plan".to_string(), count)]);
+        otlp.add_votes_cast(1, votes(2));
+        // a branch switch replacing the block at chain length 1
+        otlp.add_votes_cast(1, votes(2));
+        otlp.add_votes_cast(2, votes(1));
+
+        let request = loop {
+            let request = tokio::time::timeout(Duration::from_secs(10), requests.recv())
+                .await
+                .expect("no metrics exported")
+                .unwrap();
+            if metrics(&request).any(|metric| metric.name == "jormungandr.vote.cast") {
+                break request;
+            }
+        };
+
+        let no_attributes = Vec::new();
+        assert_eq!(
+            sum(metric(&request, "jormungandr.tx.received")),
+            vec![(no_attributes.clone(), 3.0)]
+        );
+        match &metric(&request, "jormungandr.mempool.fragments").data {
+            Some(Data::Gauge(gauge)) => assert_eq!(gauge.data_points.len(), 1),
+            data => panic!("jormungandr.mempool.fragments is not a gauge: {:?}", data),
+        }
+        assert_eq!(
+            histogram(metric(&request, "jormungandr.fragment.apply_time")),
+            vec![vec![(
+                "fragment_type".to_string(),
+                "transaction".to_string()
+            )]]
+        );
+        let peer_attributes = vec![("peer".to_string(), peer.to_string())];
+        assert_eq!(
+            sum(metric(&request, "jormungandr.peer.sync.blocks")),
+            vec![(peer_attributes.clone(), 2.0)]
+        );
+        assert_eq!(
+            sum(metric(&request, "jormungandr.peer.sync.bytes")),
+            vec![(peer_attributes.clone(), 1024.0)]
+        );
+        assert_eq!(
+            histogram(metric(&request, "jormungandr.peer.sync.time")),
+            vec![peer_attributes]
+        );
+        assert_eq!(
+            sum(metric(&request, "jormungandr.vote.cast")),
+            vec![(vec![("vote_plan".to_string(), "plan".to_string())], 3.0)]
+        );
+    }
+
+    #[test]
+    fn attribute_values_past_the_limit_are_grouped() {
+        let attribute = BoundedAttribute::new("peer", 2);
+        let value = |v: &str| {
+            attribute
+                .key_value(v.to_string())
+                .value
+                .as_str()
+                .into_owned()
+        };
+
+        assert_eq!(value("a"), "a");
+        assert_eq!(value("b"), "b");
+        assert_eq!(value("c"), OTHER_ATTRIBUTE_VALUE);
+        // the values already exported keep their series
+        assert_eq!(value("a"), "a");
+        assert_eq!(value("b"), "b");
+    }
+}
@@ -206,6 +206,7 @@ impl MetricsBackend for SimpleCounter {
                     Fragment::UpdateProposal(tx) => totals(tx),
                     Fragment::UpdateVote(tx) => totals(tx),
                     Fragment::EvmMapping(tx) => totals(tx),
+                    Fragment::MultisigRegistration(tx) => totals(tx),
                     Fragment::Initial(_) | Fragment::OldUtxoDeclaration(_) | Fragment::Evm(_) => {
                         return Ok(())
                     }
@@ -1,7 +1,7 @@
-use crate::blockchain::Ref;
+use crate::{blockchain::Ref, topology::NodeId};
 use chain_impl_mockchain::block::Block;
 use jormungandr_lib::time::SecondsSinceUnixEpoch;
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 pub mod backends;
 
@@ -18,6 +18,27 @@ pub trait MetricsBackend {
     fn set_peer_available_cnt(&self, count: usize);
     fn set_slot_start_time(&self, time: SecondsSinceUnixEpoch);
     fn set_tip_block(&self, block: &Block, block_ref: &Ref);
+
+    // The timings below are only recorded by the backends exporting
+    // distributions, the other backends ignore them.
+
+    /// time spent checking and applying a block received from the network
+    fn add_block_validation_time(&self, _duration: Duration) {}
+    /// time spent applying a fragment to the ledger when selecting the
+    /// contents of a block
+    fn add_fragment_apply_time(&self, _fragment_type: &'static str, _duration: Duration) {}
+    /// blocks, and their size in bytes, received from a peer in response to
+    /// one block solicitation
+    fn add_peer_blocks_synced(
+        &self,
+        _peer: NodeId,
+        _blocks: usize,
+        _bytes: usize,
+        _duration: Duration,
+    ) {
+    }
+    /// time spent flushing blocks to the permanent storage
+    fn add_storage_flush_time(&self, _duration: Duration) {}
 }
 
 #[derive(Clone)]
@@ -77,10 +98,30 @@ impl MetricsBackend for Metrics {
     metrics_count_method!(sub_peer_quarantined_cnt);
     metrics_count_method!(set_peer_available_cnt);
     metrics_method!(set_slot_start_time, SecondsSinceUnixEpoch);
+    metrics_method!(add_block_validation_time, Duration);
+    metrics_method!(add_storage_flush_time, Duration);
 
     fn set_tip_block(&self, block: &Block, block_ref: &Ref) {
         for backend in &self.backends {
             backend.set_tip_block(block, block_ref);
         }
     }
+
+    fn add_fragment_apply_time(&self, fragment_type: &'static str, duration: Duration) {
+        for backend in &self.backends {
+            backend.add_fragment_apply_time(fragment_type, duration);
+        }
+    }
+
+    fn add_peer_blocks_synced(
+        &self,
+        peer: NodeId,
+        blocks: usize,
+        bytes: usize,
+        duration: Duration,
+    ) {
+        for backend in &self.backends {
+            backend.add_peer_blocks_synced(peer, blocks, bytes, duration);
+        }
+    }
 }
@@ -1,9 +1,9 @@
-use super::grpc;
+use super::transport;
 use crate::{
     blockchain::{self, Blockchain, BootstrapError, Error as BlockchainError, Tip},
     network::convert::Decode,
-    settings::start::network::Peer,
-    topology,
+    settings::start::network::{Peer, Tls},
+    topology::{self, NodeId},
 };
 use chain_core::property::ReadError;
 use chain_network::{data as net_data, error::Error as NetworkError};
@@ -15,7 +15,9 @@ use tokio_util::sync::CancellationToken;
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error("failed to connect to bootstrap peer")]
-    Connect(#[source] grpc::ConnectError),
+    Connect(#[source] transport::ConnectError),
+    #[error("failed to authenticate the peer")]
+    Authenticate(#[source] transport::AuthenticateError),
     #[error("connection broken")]
     ClientNotReady(#[source] NetworkError),
     #[error("peers not available")]
@@ -42,10 +44,22 @@ pub enum Error {
 
 const MAX_BOOTSTRAP_PEERS: u32 = 32;
 
-pub async fn peers_from_trusted_peer(peer: &Peer) -> Result<Vec<topology::Peer>, Error> {
+pub async fn peers_from_trusted_peer(
+    peer: &Peer,
+    tls: Option<&Tls>,
+    expected_id: Option<NodeId>,
+) -> Result<Vec<topology::Peer>, Error> {
     tracing::info!("getting peers from bootstrap peer {}", peer.connection);
 
-    let mut client = grpc::connect(peer).await.map_err(Error::Connect)?;
+    let mut client = transport::connect(peer, tls, expected_id)
+        .await
+        .map_err(Error::Connect)?;
+    if let Some(expected_id) = expected_id {
+        client
+            .authenticate(expected_id)
+            .await
+            .map_err(Error::Authenticate)?;
+    }
     let gossip = client
         .peers(MAX_BOOTSTRAP_PEERS)
         .await
@@ -64,6 +78,8 @@ pub async fn peers_from_trusted_peer(peer: &Peer) -> Result<Vec<topology::Peer>,
 
 pub async fn bootstrap_from_peer(
     peer: &Peer,
+    tls: Option<&Tls>,
+    expected_id: Option<NodeId>,
     blockchain: Blockchain,
     tip: Tip,
     cancellation_token: CancellationToken,
@@ -84,9 +100,21 @@ pub async fn bootstrap_from_peer(
 
     tracing::debug!("connecting to bootstrap peer {}", peer.connection);
 
-    let mut client = with_cancellation_token(grpc::connect(peer).boxed(), &cancellation_token)
+    let mut client = with_cancellation_token(
+        transport::connect(peer, tls, expected_id).boxed(),
+        &cancellation_token,
+    )
+    .await?
+    .map_err(Error::Connect)?;
+
+    if let Some(expected_id) = expected_id {
+        with_cancellation_token(
+            client.authenticate(expected_id).boxed(),
+            &cancellation_token,
+        )
         .await?
-        .map_err(Error::Connect)?;
+        .map_err(Error::Authenticate)?;
+    }
 
     loop {
         let remote_tip = with_cancellation_token(client.tip().boxed(), &cancellation_token)
@@ -95,7 +123,8 @@ pub async fn bootstrap_from_peer(
             .map_err(Error::TipFailed)?
             .id();
 
-        if remote_tip == tip.get_ref().await.hash() {
+        let local_tip = tip.get_ref().await.hash();
+        if remote_tip == local_tip {
             break Ok(());
         }
 
@@ -117,13 +146,25 @@ pub async fn bootstrap_from_peer(
         .await?
         .map_err(Error::PullRequestFailed)?;
 
-        blockchain::bootstrap_from_stream(
+        let res = blockchain::bootstrap_from_stream(
             blockchain.clone(),
             tip.clone(),
             stream,
             cancellation_token.clone(),
         )
-        .await
-        .map_err(Box::new)?;
+        .await;
+
+        // the tip has been moved to the last good block received, so as long
+        // as the stream makes progress pull again from there
+        if let Err(BootstrapError::PullStreamFailed(e)) = &res {
+            if tip.get_ref().await.hash() != local_tip {
+                tracing::info!(
+                    reason = %e,
+                    "bootstrap pull stream failed, resuming from the last received block"
+                );
+                continue;
+            }
+        }
+        res.map_err(Box::new)?;
     }
 }
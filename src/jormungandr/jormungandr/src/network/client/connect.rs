@@ -1,7 +1,9 @@
 use super::{Client, ClientBuilder, InboundSubscriptions};
 use crate::{
     blockcfg::HeaderHash,
-    network::{grpc, p2p::comm::PeerComms, security_params::NONCE_LEN, Channels, ConnectionState},
+    network::{
+        p2p::comm::PeerComms, security_params::NONCE_LEN, transport, Channels, ConnectionState,
+    },
     topology::NodeId,
 };
 use chain_core::{
@@ -23,10 +25,8 @@ use tracing_futures::Instrument;
 /// Initiates a client connection, returning a connection handle and
 /// the connection future that must be polled to complete the connection.
 ///
-/// Note that this is the only function in this module that is tied to the
-/// gRPC protocol, all other code is generic in terms of network-core traits.
-/// This is intentional, to facilitate extension to different protocols
-/// in the future.
+/// The connection is made with the protocol configured for the peer,
+/// all other code is generic in terms of network-core traits.
 pub fn connect(
     state: ConnectionState,
     channels: Channels,
@@ -39,16 +39,17 @@ pub fn connect(
     let async_span = span.clone();
     let _enter = span.enter();
     let cf = async move {
-        let mut grpc_client = {
-            tracing::debug!("connecting");
-            grpc::connect(&peer).await
+        let mut net_client = {
+            tracing::debug!(protocol = ?peer.protocol, "connecting");
+            let tls = state.global.config.tls.as_ref();
+            transport::connect(&peer, tls, Some(expected_server_id)).await
         }
         .map_err(ConnectError::Transport)?;
 
         let mut nonce = [0u8; NONCE_LEN];
         rand::thread_rng().fill(&mut nonce);
 
-        let hr = grpc_client
+        let hr = net_client
             .handshake(&nonce[..])
             .await
             .map_err(ConnectError::Handshake)?;
@@ -60,7 +61,8 @@ pub fn connect(
 
         // Validate the server's node ID
         let peer_id = validate_peer_auth(hr.auth, &nonce)?;
-        // TODO: this should be better done by adding a network level authenticated / encrypted connection.
+        // With TLS, the certificate of the server has already been verified
+        // for the expected ID; plaintext connections rely on this check.
         if peer_id != expected_server_id {
             tracing::warn!(
                 "server id ({}) is different from the expected one ({}), aborting handshake",
@@ -79,20 +81,20 @@ pub fn connect(
 
         // Send client authentication
         let auth = keypair.sign(&hr.nonce);
-        grpc_client
+        net_client
             .client_auth(auth)
             .await
             .map_err(ConnectError::ClientAuth)?;
 
         let mut comms = PeerComms::new(peer.address());
         let (block_sub, fragment_sub, gossip_sub) = future::try_join3(
-            grpc_client
+            net_client
                 .clone()
                 .block_subscription(comms.subscribe_to_block_announcements()),
-            grpc_client
+            net_client
                 .clone()
                 .fragment_subscription(comms.subscribe_to_fragments()),
-            grpc_client
+            net_client
                 .clone()
                 .gossip_subscription(comms.subscribe_to_gossip()),
         )
@@ -109,7 +111,7 @@ pub fn connect(
             span: async_span,
         };
         let client = Client::new(
-            grpc_client,
+            net_client,
             builder,
             state.global.clone(),
             inbound,
@@ -127,7 +129,10 @@ pub fn connect(
 }
 
 // Validate the server peer's node ID
-fn validate_peer_auth(auth: AuthenticatedNodeId, nonce: &[u8]) -> Result<NodeId, ConnectError> {
+pub(in crate::network) fn validate_peer_auth(
+    auth: AuthenticatedNodeId,
+    nonce: &[u8],
+) -> Result<NodeId, ConnectError> {
     use super::super::convert::Decode;
     auth.verify(nonce)
         .map_err(ConnectError::PeerSignatureVerificationFailed)?;
@@ -173,7 +178,7 @@ pub enum ConnectError {
     #[error("connection has been canceled")]
     Canceled,
     #[error(transparent)]
-    Transport(tonic::transport::Error),
+    Transport(transport::ConnectError),
     #[error("protocol handshake failed: {0}")]
     Handshake(#[source] HandshakeError),
     #[error("failed to decode genesis block in response")]
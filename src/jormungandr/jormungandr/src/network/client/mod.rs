@@ -1,22 +1,22 @@
 mod connect;
 
+pub(super) use self::connect::validate_peer_auth;
 pub use self::connect::{connect, ConnectError, ConnectFuture, ConnectHandle};
 use super::{
     buffer_sizes,
     convert::{Decode, Encode},
-    grpc::{
-        self,
-        client::{BlockSubscription, FragmentSubscription, GossipSubscription},
-    },
     p2p::comm::{OutboundSubscription, PeerComms},
     subscription::{BlockAnnouncementProcessor, Direction, FragmentProcessor, GossipProcessor},
+    transport::{self, BlockSubscription, FragmentSubscription, GossipSubscription},
     Channels, GlobalStateR,
 };
 use crate::{
     intercom::{self, BlockMsg, ClientMsg},
+    metrics::MetricsBackend,
     topology::NodeId,
     utils::async_msg::MessageBox,
 };
+use chain_core::property::Serialize;
 use chain_network::{
     data as net_data,
     data::block::{BlockEvent, BlockIds, ChainPullRequest},
@@ -25,13 +25,14 @@ use futures::{prelude::*, ready};
 use std::{
     pin::Pin,
     task::{Context, Poll},
+    time::Instant,
 };
 use tracing::{instrument, Span};
 use tracing_futures::Instrument;
 
 #[must_use = "Client must be polled"]
 pub struct Client {
-    inner: grpc::Client,
+    inner: transport::Client,
     global_state: GlobalStateR,
     inbound: InboundSubscriptions,
     block_solicitations: OutboundSubscription<BlockIds>,
@@ -59,7 +60,7 @@ impl Client {
 
 impl Client {
     fn new(
-        inner: grpc::Client,
+        inner: transport::Client,
         builder: ClientBuilder,
         global_state: GlobalStateR,
         inbound: InboundSubscriptions,
@@ -409,17 +410,32 @@ impl Client {
             .in_current_span(),
         );
         let mut client = self.inner.clone();
+        let global_state = self.global_state.clone();
+        let peer_id = self.inbound.peer_id;
         self.global_state.spawn(
             async move {
-                match client.get_blocks(block_ids).await {
+                let started = Instant::now();
+                let mut received = Vec::new();
+                let mut received_bytes = 0;
+                // set when the peer fails to serve the blocks, as opposed to
+                // the processing of the blocks ending the stream
+                let mut peer_failed = false;
+                match client.get_blocks(block_ids.clone()).await {
                     Err(e) => {
                         tracing::info!(
                             reason = %e,
                             "request failed"
                         );
+                        peer_failed = true;
                     }
                     Ok(stream) => {
-                        let stream = stream.and_then(|item| async { item.decode() });
+                        let stream = stream
+                            .inspect_err(|_| peer_failed = true)
+                            .and_then(|item| async { item.decode() })
+                            .inspect_ok(|block| {
+                                received.push(block.header().hash().encode());
+                                received_bytes += block.serialized_size();
+                            });
                         let res = stream.forward(sink.sink_err_into()).await;
                         if let Err(e) = res {
                             tracing::info!(
@@ -429,6 +445,32 @@ impl Client {
                         }
                     }
                 }
+                if !received.is_empty() {
+                    global_state.stats_counter.add_peer_blocks_synced(
+                        peer_id,
+                        received.len(),
+                        received_bytes,
+                        started.elapsed(),
+                    );
+                }
+                if peer_failed {
+                    // resume with the blocks not received yet from another peer
+                    let remaining: BlockIds = block_ids
+                        .iter()
+                        .filter(|id| !received.contains(id))
+                        .copied()
+                        .collect();
+                    if !remaining.is_empty() {
+                        tracing::debug!(
+                            "soliciting {} remaining blocks from another peer",
+                            remaining.len()
+                        );
+                        global_state
+                            .peers
+                            .solicit_blocks_except(&[peer_id], remaining)
+                            .await;
+                    }
+                }
             }
             .in_current_span(),
         );
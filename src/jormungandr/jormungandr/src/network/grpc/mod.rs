@@ -1,7 +1,8 @@
 pub(super) mod client;
 mod server;
+mod tls;
 
 pub use self::{
-    client::{connect, fetch_block, Client, ConnectError, FetchBlockError},
+    client::{connect, Client, ConnectError},
     server::run_listen_socket,
 };
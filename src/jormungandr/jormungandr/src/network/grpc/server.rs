@@ -1,6 +1,9 @@
-use super::super::{
-    concurrency_limits, keepalive_durations, service::NodeService, Channels, GlobalStateR,
-    ListenError,
+use super::{
+    super::{
+        concurrency_limits, keepalive_durations, service::NodeService, Channels, GlobalStateR,
+        ListenError,
+    },
+    tls,
 };
 use crate::settings::start::network::Listen;
 use chain_network::grpc::{self, watch::server::Server as WatchServer};
@@ -18,16 +21,24 @@ pub async fn run_listen_socket(
     let span = span!(parent: &state.span, Level::TRACE, "listen_socket", local_addr = %sockaddr.to_string());
     async {
         tracing::info!("listening and accepting gRPC connections");
+        let tls_config = state.config.tls.as_ref().map(tls::server_config);
         let service = grpc::server::Builder::new().build(NodeService::new(channels, state));
 
-        Server::builder()
+        let mut server = Server::builder();
+        if let Some(tls_config) = tls_config {
+            tracing::info!("securing gRPC connections with TLS");
+            server = server
+                .tls_config(tls_config)
+                .map_err(|cause| ListenError::new(cause, sockaddr))?;
+        }
+        server
             .concurrency_limit_per_connection(concurrency_limits::SERVER_REQUESTS)
             .tcp_keepalive(Some(keepalive_durations::TCP))
             .add_service(service)
             .add_service(watch_service)
             .serve(sockaddr)
             .await
-            .map_err(|cause| ListenError { cause, sockaddr })
+            .map_err(|cause| ListenError::new(cause, sockaddr))
     }
     .instrument(span)
     .await
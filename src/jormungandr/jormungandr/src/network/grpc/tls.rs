@@ -0,0 +1,21 @@
+use crate::{settings::start::network::Tls, topology::NodeId};
+use jormungandr_lib::interfaces::tls_server_name;
+use tonic::transport::{Certificate, ClientTlsConfig, Identity, ServerTlsConfig};
+
+/// Client configuration verifying that the server certificate is issued
+/// by a trusted authority for the expected node ID.
+pub fn client_config(tls: &Tls, peer_id: NodeId) -> ClientTlsConfig {
+    ClientTlsConfig::new()
+        .ca_certificate(Certificate::from_pem(&tls.ca))
+        .identity(Identity::from_pem(&tls.cert, &tls.priv_key))
+        .domain_name(tls_server_name(&peer_id.into()))
+}
+
+/// Server configuration requiring clients to present a certificate
+/// issued by a trusted authority. The node ID of the client is then
+/// authenticated with the client auth request.
+pub fn server_config(tls: &Tls) -> ServerTlsConfig {
+    ServerTlsConfig::new()
+        .identity(Identity::from_pem(&tls.cert, &tls.priv_key))
+        .client_ca_root(Certificate::from_pem(&tls.ca))
+}
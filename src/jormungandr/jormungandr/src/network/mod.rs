@@ -9,9 +9,11 @@ pub mod bootstrap;
 mod client;
 mod convert;
 mod grpc;
+mod ntt;
 pub mod p2p;
 mod service;
 mod subscription;
+mod transport;
 
 use self::convert::Encode;
 use futures::{future, prelude::*};
@@ -80,7 +82,7 @@ use crate::{
     blockchain::{Blockchain as NewBlockchain, Tip},
     intercom::{BlockMsg, ClientMsg, NetworkMsg, PropagateMsg, TopologyMsg, TransactionMsg},
     metrics::Metrics,
-    settings::start::network::{Configuration, Peer, Protocol},
+    settings::start::network::{Configuration, Peer, Protocol, TrustedPeer},
     topology::{self, NodeId},
     utils::async_msg::{MessageBox, MessageQueue},
 };
@@ -97,16 +99,27 @@ use std::{
     },
     time::Duration,
 };
-use tonic::transport;
 use tracing::{instrument, span, Level, Span};
 use tracing_futures::Instrument;
 
 #[derive(Debug)]
 pub struct ListenError {
-    cause: transport::Error,
+    cause: Box<dyn error::Error + Send + Sync>,
     sockaddr: SocketAddr,
 }
 
+impl ListenError {
+    fn new<E>(cause: E, sockaddr: SocketAddr) -> Self
+    where
+        E: Into<Box<dyn error::Error + Send + Sync>>,
+    {
+        ListenError {
+            cause: cause.into(),
+            sockaddr,
+        }
+    }
+}
+
 impl fmt::Display for ListenError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
@@ -119,7 +132,7 @@ impl fmt::Display for ListenError {
 
 impl error::Error for ListenError {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
-        Some(&self.cause)
+        Some(&*self.cause)
     }
 }
 
@@ -164,6 +177,7 @@ pub struct GlobalState {
     config: Configuration,
     peers: Peers,
     keypair: NodeKeyPair,
+    stats_counter: Metrics,
     span: Span,
 
     connected_count: AtomicUsize,
@@ -180,7 +194,7 @@ impl GlobalState {
         stats_counter: Metrics,
         span: Span,
     ) -> Result<Self, NewGlobalStateError> {
-        let peers = Peers::new(config.max_connections, stats_counter);
+        let peers = Peers::new(config.max_connections, stats_counter.clone());
 
         //TODO: move this to a secure enclave
         let keypair =
@@ -194,6 +208,7 @@ impl GlobalState {
             config,
             peers,
             keypair,
+            stats_counter,
             span,
             connected_count: AtomicUsize::new(0),
             dns_resolver,
@@ -275,6 +290,9 @@ pub struct ConnectionState {
     /// the local (to the task) connection details
     pub connection: Connection,
 
+    /// the protocol used for the connection
+    pub protocol: Protocol,
+
     pub span: Span,
 }
 
@@ -283,13 +301,14 @@ impl ConnectionState {
         ConnectionState {
             timeout: peer.timeout,
             connection: peer.connection,
+            protocol: peer.protocol,
             span,
             global,
         }
     }
 
     fn peer(&self) -> Peer {
-        Peer::with_timeout(self.connection, self.timeout)
+        Peer::with_timeout(self.connection, self.protocol, self.timeout)
     }
 
     fn span(&self) -> &Span {
@@ -305,39 +324,44 @@ pub struct TaskParams {
 }
 
 pub async fn start(params: TaskParams) {
-    // TODO: the node needs to be saved/loaded
-    //
-    // * the ID needs to be consistent between restart;
     let input = params.input;
     let channels = params.channels;
     let global_state = params.global_state;
     let watch = params.watch;
 
-    // open the port for listening/accepting other peers to connect too
-    let listen_state = global_state.clone();
-    let listen_channels = channels.clone();
-    let listener = async move {
-        if let Some(listen) = listen_state.config.listen() {
-            match listen.protocol {
-                Protocol::Grpc => {
-                    grpc::run_listen_socket(
-                        &listen,
-                        listen_state,
-                        listen_channels,
-                        watch.into_server(),
-                    )
-                    .await
-                    .unwrap_or_else(|e| {
-                        tracing::error!(
-                            reason = %e,
-                            "failed to listen for P2P connections at {}", listen.connection
-                        );
-                    });
-                }
-                Protocol::Ntt => unimplemented!(),
+    // open the ports for listening/accepting other peers to connect too
+    let mut watch_server = Some(watch.into_server());
+    let listeners = global_state
+        .config
+        .listeners()
+        .into_iter()
+        .map(|listen| {
+            let state = global_state.clone();
+            let channels = channels.clone();
+            // The watch service is only served over gRPC, which
+            // is used by at most one listener.
+            let watch_server = match listen.protocol {
+                Protocol::Grpc => watch_server.take(),
+                Protocol::Ntt => None,
+            };
+            async move {
+                let res = match listen.protocol {
+                    Protocol::Grpc => {
+                        let watch_server = watch_server.expect("only one gRPC listener is set up");
+                        grpc::run_listen_socket(&listen, state, channels, watch_server).await
+                    }
+                    Protocol::Ntt => ntt::run_listen_socket(&listen, state, channels).await,
+                };
+                res.unwrap_or_else(|e| {
+                    tracing::error!(
+                        reason = %e,
+                        "failed to listen for P2P connections at {}", listen.connection
+                    );
+                });
             }
-        }
-    };
+        })
+        .collect::<Vec<_>>();
+    let listener = future::join_all(listeners);
 
     let handle_cmds = handle_network_input(input, global_state.clone(), channels.clone());
     future::join(listener, handle_cmds).await;
@@ -509,8 +533,19 @@ fn connect_and_propagate(
             return;
         }
     }
+    if let Some(pinned_id) = state.config.trusted_peer_id(addr) {
+        if pinned_id != id {
+            tracing::warn!(
+                peer = %addr,
+                expected = %pinned_id,
+                advertised = %id,
+                "node ID of the peer differs from the one pinned for the trusted peer, ignoring"
+            );
+            return;
+        }
+    }
     drop(_enter);
-    let peer = Peer::new(addr);
+    let peer = Peer::new(addr, state.config.peer_protocol(addr));
     let conn_span = span!(parent: &state.span, Level::DEBUG, "client", %addr, %id);
     let spawn_state = state.clone();
     let cf = async move {
@@ -522,7 +557,7 @@ fn connect_and_propagate(
             Err(e) => {
                 let benign = match e {
                     ConnectError::Transport(e) => {
-                        tracing::info!(reason = %e, "gRPC connection to peer failed");
+                        tracing::info!(reason = %e, "connection to peer failed");
                         false
                     }
                     ConnectError::Handshake(e) => {
@@ -600,16 +635,39 @@ async fn netboot_peers(config: &Configuration, parent_span: &Span) -> BootstrapP
         .iter()
         .map(|peer| peer.addr)
         .collect::<Vec<_>>();
+    let peers = peers_from_trusted_peers(config, &config.trusted_peers, parent_span).await;
+
+    let (bootstrap_peers, topology_peers) = if config.bootstrap_from_trusted_peers {
+        peers
+            .into_iter()
+            .partition(|peer| trusted_peers_addrs.contains(&peer.address()))
+    } else {
+        (Vec::from_iter(peers), Vec::new())
+    };
+
+    BootstrapPeers {
+        bootstrap_peers,
+        topology_peers,
+    }
+}
+
+/// Retrieves the peers known to each of the given trusted peers.
+pub async fn peers_from_trusted_peers(
+    config: &Configuration,
+    trusted_peers: &[TrustedPeer],
+    parent_span: &Span,
+) -> HashSet<topology::Peer> {
     let mut peers = HashSet::new();
-    for tpeer in &trusted_peers_addrs {
+    for tpeer in trusted_peers {
         let span = span!(
             parent: parent_span,
             Level::DEBUG,
             "netboot_peers",
-            peer_addr = %tpeer.to_string()
+            peer_addr = %tpeer.addr.to_string()
         );
+        let peer = Peer::new(tpeer.addr, tpeer.protocol.unwrap_or(config.protocol));
         let received_peers = async move {
-            let res = bootstrap::peers_from_trusted_peer(&Peer::new(*tpeer))
+            let res = bootstrap::peers_from_trusted_peer(&peer, config.tls.as_ref(), tpeer.id)
                 .await
                 .unwrap_or_else(|e| {
                     tracing::warn!(
@@ -625,19 +683,7 @@ async fn netboot_peers(config: &Configuration, parent_span: &Span) -> BootstrapP
         .await;
         peers.extend(received_peers);
     }
-
-    let (bootstrap_peers, topology_peers) = if config.bootstrap_from_trusted_peers {
-        peers
-            .into_iter()
-            .partition(|peer| trusted_peers_addrs.contains(&peer.address()))
-    } else {
-        (Vec::from_iter(peers), Vec::new())
-    };
-
-    BootstrapPeers {
-        bootstrap_peers,
-        topology_peers,
-    }
+    peers
 }
 
 pub struct NetworkBootstrapResult {
@@ -654,10 +700,6 @@ pub async fn bootstrap(
 ) -> Result<NetworkBootstrapResult, bootstrap::Error> {
     use futures::future::{select, Either, FutureExt};
 
-    if config.protocol != Protocol::Grpc {
-        unimplemented!()
-    }
-
     if config.skip_bootstrap {
         return Ok(NetworkBootstrapResult {
             initial_peers: Vec::new(),
@@ -692,7 +734,14 @@ pub async fn bootstrap(
         let span =
             span!(parent: span, Level::DEBUG, "bootstrap", peer_addr = %peer.address().to_string());
         let res = bootstrap::bootstrap_from_peer(
-            &Peer::new(peer.address()),
+            &Peer::new(peer.address(), config.peer_protocol(peer.address())),
+            config.tls.as_ref(),
+            // the ID gossiped by the trusted peers, unless one is pinned
+            Some(
+                config
+                    .trusted_peer_id(peer.address())
+                    .unwrap_or_else(|| peer.id()),
+            ),
             blockchain.clone(),
             branch.clone(),
             cancellation_token.clone(),
@@ -758,10 +807,6 @@ pub async fn fetch_block(
     config: &Configuration,
     hash: HeaderHash,
 ) -> Result<Block, FetchBlockError> {
-    if config.protocol != Protocol::Grpc {
-        unimplemented!()
-    }
-
     if config.trusted_peers.is_empty() {
         return Err(FetchBlockError::NoTrustedPeers);
     }
@@ -772,12 +817,17 @@ pub async fn fetch_block(
     async {
         for address in trusted_peers_shuffled(config) {
             let peer_span = span!(Level::TRACE, "peer_address", address = %address.to_string());
-            let peer = Peer::new(address);
-            match grpc::fetch_block(&peer, hash)
-                .instrument(peer_span.clone())
-                .await
+            let peer = Peer::new(address, config.peer_protocol(address));
+            match transport::fetch_block(
+                &peer,
+                config.tls.as_ref(),
+                config.trusted_peer_id(address),
+                hash,
+            )
+            .instrument(peer_span.clone())
+            .await
             {
-                Err(grpc::FetchBlockError::Connect { source: e }) => {
+                Err(transport::FetchBlockError::Connect { source: e }) => {
                     async {
                         tracing::warn!(reason = %e, "unable to reach peer for block download");
                     }
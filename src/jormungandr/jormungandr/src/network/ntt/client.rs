@@ -0,0 +1,23 @@
+use crate::settings::start::network::{Peer, Protocol};
+use chain_network::error::{Code, Error};
+use tokio::net::TcpStream;
+
+pub type ConnectError = Error;
+
+pub type Client = chain_network::ntt::Client;
+
+pub async fn connect(peer: &Peer) -> Result<Client, ConnectError> {
+    assert!(peer.protocol == Protocol::Ntt);
+    let connect = async {
+        let socket = TcpStream::connect(peer.connection)
+            .await
+            .map_err(|e| Error::new(Code::Unavailable, e))?;
+        socket
+            .set_nodelay(true)
+            .map_err(|e| Error::new(Code::Unavailable, e))?;
+        Client::new(socket).await
+    };
+    tokio::time::timeout(peer.timeout, connect)
+        .await
+        .map_err(|_| Error::new(Code::Unavailable, "connection timed out"))?
+}
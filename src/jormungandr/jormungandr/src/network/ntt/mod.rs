@@ -0,0 +1,7 @@
+mod client;
+mod server;
+
+pub use self::{
+    client::{connect, Client, ConnectError},
+    server::run_listen_socket,
+};
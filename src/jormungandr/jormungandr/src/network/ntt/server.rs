@@ -0,0 +1,31 @@
+use super::super::{concurrency_limits, service::NodeService, Channels, GlobalStateR, ListenError};
+use crate::settings::start::network::Listen;
+use chain_network::ntt;
+use tokio::net::TcpListener;
+use tracing::{span, Level};
+use tracing_futures::Instrument;
+
+pub async fn run_listen_socket(
+    listen: &Listen,
+    state: GlobalStateR,
+    channels: Channels,
+) -> Result<(), ListenError> {
+    let sockaddr = listen.address();
+    let span = span!(parent: &state.span, Level::TRACE, "listen_socket", local_addr = %sockaddr.to_string());
+    async {
+        tracing::info!("listening and accepting NTT connections");
+        let server = ntt::server::Builder::new()
+            .max_streams_per_connection(concurrency_limits::SERVER_REQUESTS)
+            .build(NodeService::new(channels, state));
+
+        let listener = TcpListener::bind(sockaddr)
+            .await
+            .map_err(|cause| ListenError::new(cause, sockaddr))?;
+        server
+            .serve(listener)
+            .await
+            .map_err(|cause| ListenError::new(cause, sockaddr))
+    }
+    .instrument(span)
+    .await
+}
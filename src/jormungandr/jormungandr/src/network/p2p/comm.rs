@@ -708,12 +708,34 @@ impl Peers {
     }
 
     pub async fn solicit_blocks_any(&self, hashes: BlockIds) {
+        self.solicit_blocks_except(&[], hashes).await
+    }
+
+    /// Solicits the blocks from an established peer other than the ones
+    /// in `exclude`, moving on to the next peer if sending fails.
+    pub async fn solicit_blocks_except(&self, exclude: &[NodeId], mut hashes: BlockIds) {
         let mut map = self.inner().await;
-        if let Some((peer, _)) = map.next_peer_for_block_fetch() {
-            drop(map); // do not hold the lock
-            self.solicit_blocks_peer(&peer, hashes).await;
-        } else {
-            tracing::warn!("no peers to fetch blocks from");
+        loop {
+            let (peer, comms) = match map.next_peer_for_block_fetch(exclude) {
+                Some(next) => next,
+                None => {
+                    tracing::warn!("no peers to fetch blocks from");
+                    return;
+                }
+            };
+            tracing::debug!(%peer, "sending block solicitation");
+            match comms.block_solicitations.try_send(hashes) {
+                Ok(()) => return,
+                Err(e) => {
+                    tracing::debug!(
+                        %peer,
+                        reason = %e.kind(),
+                        "sending block solicitation failed, unsubscribing"
+                    );
+                    hashes = e.into_item();
+                    map.remove_peer(&peer);
+                }
+            }
         }
     }
 
@@ -726,25 +748,31 @@ impl Peers {
         );
         async move {
             let mut map = self.inner().await;
-            match map.peer_comms(peer) {
+            let unsent = match map.peer_comms(peer) {
                 Some(comms) => {
                     Span::current().record("peer_addr", format_args!("{}", comms.remote_addr));
                     tracing::debug!("sending block solicitation");
-                    comms
-                        .block_solicitations
-                        .try_send(hashes)
-                        .unwrap_or_else(|e| {
+                    match comms.block_solicitations.try_send(hashes) {
+                        Ok(()) => None,
+                        Err(e) => {
                             tracing::debug!(
-                                error = ?e,
+                                reason = %e.kind(),
                                 "sending block solicitation failed, unsubscribing"
                             );
                             map.remove_peer(peer);
-                        });
+                            Some(e.into_item())
+                        }
+                    }
                 }
                 None => {
-                    // TODO: connect and request on demand, or select another peer?
-                    tracing::warn!("peer not available to solicit blocks from");
+                    tracing::info!("peer not available to solicit blocks from");
+                    Some(hashes)
                 }
+            };
+            drop(map); // do not hold the lock
+            if let Some(hashes) = unsent {
+                tracing::debug!("soliciting the blocks from another peer");
+                self.solicit_blocks_except(&[*peer], hashes).await;
             }
         }
         .instrument(span)
@@ -761,29 +789,44 @@ impl Peers {
         );
         async {
             let mut map = self.inner().await;
+            let mut req = ChainPullRequest { from, to };
             match map.peer_comms(peer) {
                 Some(comms) => {
                     Span::current().record("peer_addr", format_args!("{}", comms.remote_addr));
                     tracing::debug!("sending header pull request");
-                    comms
-                        .chain_pulls
-                        .try_send(ChainPullRequest { from, to })
-                        .unwrap_or_else(|e| {
+                    match comms.chain_pulls.try_send(req) {
+                        Ok(()) => return,
+                        Err(e) => {
                             tracing::debug!(
-                                error = ?e,
+                                reason = %e.kind(),
                                 "sending header pull solicitation failed, unsubscribing"
                             );
                             map.remove_peer(peer);
-                        });
+                            req = e.into_item();
+                        }
+                    }
                 }
                 None => {
-                    // TODO: connect and request on demand, or select another peer?
-                    tracing::info!(
-                        peer = %peer,
-                        "peer not available to pull headers from"
-                    );
+                    tracing::info!("peer not available to pull headers from");
+                }
+            }
+            // other peers are likely to have the chain announced by the peer
+            while let Some((other, comms)) = map.next_peer_for_block_fetch(&[*peer]) {
+                tracing::debug!(peer = %other, "sending header pull request to another peer");
+                match comms.chain_pulls.try_send(req) {
+                    Ok(()) => return,
+                    Err(e) => {
+                        tracing::debug!(
+                            peer = %other,
+                            reason = %e.kind(),
+                            "sending header pull solicitation failed, unsubscribing"
+                        );
+                        map.remove_peer(&other);
+                        req = e.into_item();
+                    }
                 }
             }
+            tracing::warn!("no peers to pull headers from");
         }
         .instrument(span)
         .await
@@ -794,3 +837,33 @@ impl Peers {
         map.infos()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn block_solicitation_fails_over_to_the_next_peer() {
+        let peers = Peers::new(16, Metrics::builder().build());
+        let (first, second) = (NodeId::from_seed(1), NodeId::from_seed(2));
+        let (mut first_solicitations, second_solicitations) = {
+            let mut map = peers.inner().await;
+            let first_solicitations = map
+                .add_client(first, ([127, 0, 0, 1], 9001).into())
+                .subscribe_to_block_solicitations();
+            let second_solicitations = map
+                .add_client(second, ([127, 0, 0, 1], 9002).into())
+                .subscribe_to_block_solicitations();
+            (first_solicitations, second_solicitations)
+        };
+        // the most recent peer went away
+        drop(second_solicitations);
+
+        let hashes: BlockIds = vec![BlockId::try_from(&[1; 32][..]).unwrap()].into();
+        peers.solicit_blocks_any(hashes.clone()).await;
+
+        assert_eq!(first_solicitations.next().await, Some(hashes));
+        assert!(peers.inner().await.peer_comms(&second).is_none());
+        assert!(peers.inner().await.peer_comms(&first).is_some());
+    }
+}
@@ -253,9 +253,17 @@ impl PeerMap {
         })
     }
 
-    pub fn next_peer_for_block_fetch(&mut self) -> Option<(NodeId, &mut PeerComms)> {
+    /// Returns the most recently active established peer,
+    /// skipping the peers in `exclude`.
+    pub fn next_peer_for_block_fetch(
+        &mut self,
+        exclude: &[NodeId],
+    ) -> Option<(NodeId, &mut PeerComms)> {
         let mut iter = self.map.iter_mut();
         while let Some((id, data)) = iter.next_back() {
+            if exclude.contains(id) {
+                continue;
+            }
             match data.update_comm_status() {
                 CommStatus::Established(comms) => return Some((*id, comms)),
                 CommStatus::Connecting(_) => {}
@@ -316,3 +324,35 @@ impl<'a> Entry<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peer_map_with(peers: &[NodeId]) -> PeerMap {
+        let mut map = PeerMap::new(16, Metrics::builder().build());
+        for (port, id) in (9000..).zip(peers) {
+            map.add_client(*id, ([127, 0, 0, 1], port).into());
+        }
+        map
+    }
+
+    #[test]
+    fn block_fetch_picks_the_most_recent_peer_not_excluded() {
+        let peers = [
+            NodeId::from_seed(1),
+            NodeId::from_seed(2),
+            NodeId::from_seed(3),
+        ];
+        let mut map = peer_map_with(&peers);
+        map.refresh_peer(&peers[0]);
+
+        let next = |map: &mut PeerMap, exclude: &[NodeId]| {
+            map.next_peer_for_block_fetch(exclude).map(|(id, _)| id)
+        };
+        assert_eq!(next(&mut map, &[]), Some(peers[0]));
+        assert_eq!(next(&mut map, &[peers[0]]), Some(peers[2]));
+        assert_eq!(next(&mut map, &[peers[0], peers[2]]), Some(peers[1]));
+        assert_eq!(next(&mut map, &peers), None);
+    }
+}
@@ -0,0 +1,295 @@
+//! Client connections over the protocol transport chosen for the peer.
+
+use super::{client, convert::Decode, grpc, ntt, security_params::NONCE_LEN};
+use crate::{
+    blockcfg::{Block, HeaderHash},
+    settings::start::network::{Peer, Protocol, Tls},
+    topology::NodeId,
+};
+use chain_network::{
+    data::{
+        block::{Block as NetBlock, BlockEvent, BlockId, BlockIds, Header},
+        fragment::Fragment,
+        AuthenticatedNodeId, Gossip, HandshakeResponse,
+    },
+    error::{Error, HandshakeError},
+};
+use futures::{prelude::*, stream::BoxStream};
+use rand::Rng;
+use std::convert::TryFrom;
+use thiserror::Error;
+
+pub type BlockSubscription = BoxStream<'static, Result<BlockEvent, Error>>;
+pub type FragmentSubscription = BoxStream<'static, Result<Fragment, Error>>;
+pub type GossipSubscription = BoxStream<'static, Result<Gossip, Error>>;
+
+#[derive(Error, Debug)]
+pub enum ConnectError {
+    #[error(transparent)]
+    Grpc(grpc::ConnectError),
+    #[error(transparent)]
+    Ntt(ntt::ConnectError),
+    #[error("the node ID of the peer must be known to verify its TLS certificate")]
+    TlsPeerIdUnknown,
+    #[error("TLS is not supported over the NTT protocol")]
+    TlsOverNtt,
+}
+
+#[derive(Error, Debug)]
+pub enum AuthenticateError {
+    #[error("protocol handshake failed")]
+    Handshake(#[source] HandshakeError),
+    #[error("peer node ID validation failed")]
+    PeerAuth(#[source] client::ConnectError),
+    #[error("peer node ID {received} is not the expected {expected}")]
+    UnexpectedNodeId { expected: NodeId, received: NodeId },
+}
+
+#[derive(Error, Debug)]
+pub enum FetchBlockError {
+    #[error("connection to peer failed")]
+    Connect { source: ConnectError },
+    #[error("peer authentication failed")]
+    Authenticate { source: AuthenticateError },
+    #[error("block request failed")]
+    GetBlocks { source: Error },
+    #[error("block response stream failed")]
+    GetBlocksStream { source: Error },
+    #[error("no blocks received")]
+    NoBlocks,
+    #[error("Unexpected block hash: requested {requested} received {received}")]
+    UnexpectedBlock {
+        requested: HeaderHash,
+        received: HeaderHash,
+    },
+}
+
+/// Client of a peer node, connected with the protocol configured for it.
+#[derive(Clone)]
+pub enum Client {
+    Grpc(grpc::Client),
+    Ntt(ntt::Client),
+}
+
+/// Connects to the peer with its protocol. If TLS credentials are given,
+/// gRPC connections are secured with TLS, verifying that the peer has
+/// the expected node ID, and NTT connections are refused rather than
+/// made in plaintext.
+pub async fn connect(
+    peer: &Peer,
+    tls: Option<&Tls>,
+    peer_id: Option<NodeId>,
+) -> Result<Client, ConnectError> {
+    match peer.protocol {
+        Protocol::Grpc => {
+            let tls = match tls {
+                Some(tls) => Some((tls, peer_id.ok_or(ConnectError::TlsPeerIdUnknown)?)),
+                None => None,
+            };
+            grpc::connect(peer, tls)
+                .await
+                .map(Client::Grpc)
+                .map_err(ConnectError::Grpc)
+        }
+        Protocol::Ntt if tls.is_some() => Err(ConnectError::TlsOverNtt),
+        Protocol::Ntt => ntt::connect(peer)
+            .await
+            .map(Client::Ntt)
+            .map_err(ConnectError::Ntt),
+    }
+}
+
+impl Client {
+    /// Checks with a protocol handshake that the peer holds the key
+    /// of the expected node ID.
+    pub async fn authenticate(&mut self, expected: NodeId) -> Result<(), AuthenticateError> {
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::thread_rng().fill(&mut nonce);
+        let hr = self
+            .handshake(&nonce)
+            .await
+            .map_err(AuthenticateError::Handshake)?;
+        let received =
+            client::validate_peer_auth(hr.auth, &nonce).map_err(AuthenticateError::PeerAuth)?;
+        if received != expected {
+            return Err(AuthenticateError::UnexpectedNodeId { expected, received });
+        }
+        Ok(())
+    }
+
+    pub async fn handshake(&mut self, nonce: &[u8]) -> Result<HandshakeResponse, HandshakeError> {
+        match self {
+            Client::Grpc(client) => client.handshake(nonce).await,
+            Client::Ntt(client) => client.handshake(nonce).await,
+        }
+    }
+
+    pub async fn client_auth(&mut self, auth: AuthenticatedNodeId) -> Result<(), Error> {
+        match self {
+            Client::Grpc(client) => client.client_auth(auth).await,
+            Client::Ntt(client) => client.client_auth(auth).await,
+        }
+    }
+
+    pub async fn peers(&mut self, limit: u32) -> Result<Gossip, Error> {
+        match self {
+            Client::Grpc(client) => client.peers(limit).await,
+            Client::Ntt(client) => client.peers(limit).await,
+        }
+    }
+
+    pub async fn tip(&mut self) -> Result<Header, Error> {
+        match self {
+            Client::Grpc(client) => client.tip().await,
+            Client::Ntt(client) => client.tip().await,
+        }
+    }
+
+    pub async fn get_blocks(
+        &mut self,
+        ids: BlockIds,
+    ) -> Result<BoxStream<'static, Result<NetBlock, Error>>, Error> {
+        match self {
+            Client::Grpc(client) => client.get_blocks(ids).await.map(StreamExt::boxed),
+            Client::Ntt(client) => client.get_blocks(ids).await.map(StreamExt::boxed),
+        }
+    }
+
+    pub async fn pull_blocks(
+        &mut self,
+        from: BlockIds,
+        to: BlockId,
+    ) -> Result<BoxStream<'static, Result<NetBlock, Error>>, Error> {
+        match self {
+            Client::Grpc(client) => client.pull_blocks(from, to).await.map(StreamExt::boxed),
+            Client::Ntt(client) => client.pull_blocks(from, to).await.map(StreamExt::boxed),
+        }
+    }
+
+    pub async fn pull_headers(
+        &mut self,
+        from: BlockIds,
+        to: BlockId,
+    ) -> Result<BoxStream<'static, Result<Header, Error>>, Error> {
+        match self {
+            Client::Grpc(client) => client.pull_headers(from, to).await.map(StreamExt::boxed),
+            Client::Ntt(client) => client.pull_headers(from, to).await.map(StreamExt::boxed),
+        }
+    }
+
+    pub async fn push_headers<S>(&mut self, headers: S) -> Result<(), Error>
+    where
+        S: Stream<Item = Header> + Send + Sync + 'static,
+    {
+        match self {
+            Client::Grpc(client) => client.push_headers(headers).await,
+            Client::Ntt(client) => client.push_headers(headers).await,
+        }
+    }
+
+    pub async fn upload_blocks<S>(&mut self, blocks: S) -> Result<(), Error>
+    where
+        S: Stream<Item = NetBlock> + Send + Sync + 'static,
+    {
+        match self {
+            Client::Grpc(client) => client.upload_blocks(blocks).await,
+            Client::Ntt(client) => client.upload_blocks(blocks).await,
+        }
+    }
+
+    pub async fn block_subscription<S>(&mut self, outbound: S) -> Result<BlockSubscription, Error>
+    where
+        S: Stream<Item = Header> + Send + Sync + 'static,
+    {
+        match self {
+            Client::Grpc(client) => client
+                .block_subscription(outbound)
+                .await
+                .map(StreamExt::boxed),
+            Client::Ntt(client) => client
+                .block_subscription(outbound)
+                .await
+                .map(StreamExt::boxed),
+        }
+    }
+
+    pub async fn fragment_subscription<S>(
+        &mut self,
+        outbound: S,
+    ) -> Result<FragmentSubscription, Error>
+    where
+        S: Stream<Item = Fragment> + Send + Sync + 'static,
+    {
+        match self {
+            Client::Grpc(client) => client
+                .fragment_subscription(outbound)
+                .await
+                .map(StreamExt::boxed),
+            Client::Ntt(client) => client
+                .fragment_subscription(outbound)
+                .await
+                .map(StreamExt::boxed),
+        }
+    }
+
+    pub async fn gossip_subscription<S>(&mut self, outbound: S) -> Result<GossipSubscription, Error>
+    where
+        S: Stream<Item = Gossip> + Send + Sync + 'static,
+    {
+        match self {
+            Client::Grpc(client) => client
+                .gossip_subscription(outbound)
+                .await
+                .map(StreamExt::boxed),
+            Client::Ntt(client) => client
+                .gossip_subscription(outbound)
+                .await
+                .map(StreamExt::boxed),
+        }
+    }
+}
+
+// Fetches a block from a network peer, authenticating the peer first
+// if its node ID is known.
+// This function is used during node bootstrap to fetch the genesis block.
+pub async fn fetch_block(
+    peer: &Peer,
+    tls: Option<&Tls>,
+    expected_id: Option<NodeId>,
+    hash: HeaderHash,
+) -> Result<Block, FetchBlockError> {
+    tracing::info!("fetching block {}", hash);
+    let mut client = connect(peer, tls, expected_id)
+        .await
+        .map_err(|err| FetchBlockError::Connect { source: err })?;
+    if let Some(expected_id) = expected_id {
+        client
+            .authenticate(expected_id)
+            .await
+            .map_err(|err| FetchBlockError::Authenticate { source: err })?;
+    }
+    let block_id = BlockId::try_from(hash.as_bytes()).unwrap();
+    let stream = client
+        .get_blocks(vec![block_id].into())
+        .await
+        .map_err(|err| FetchBlockError::GetBlocks { source: err })?;
+    let (next_block, _) = stream.into_future().await;
+    match next_block {
+        Some(Ok(block)) => {
+            let block = block
+                .decode()
+                .map_err(|e| FetchBlockError::GetBlocksStream { source: e })?;
+
+            if block.header().id() == hash {
+                Ok(block)
+            } else {
+                Err(FetchBlockError::UnexpectedBlock {
+                    requested: hash.to_owned(),
+                    received: block.header().id(),
+                })
+            }
+        }
+        None => Err(FetchBlockError::NoBlocks),
+        Some(Err(e)) => Err(FetchBlockError::GetBlocksStream { source: e }),
+    }
+}
@@ -15,6 +15,18 @@ pub async fn get_account_state(
         .ok_or_else(warp::reject::not_found)
 }
 
+pub async fn get_multisig_account_state(
+    multisig_id_hex: String,
+    context: ContextLock,
+) -> Result<impl Reply, Rejection> {
+    let context = context.read().await;
+    logic::get_multisig_account_state(&context, &multisig_id_hex)
+        .await
+        .map_err(warp::reject::custom)?
+        .map(|r| warp::reply::json(&r))
+        .ok_or_else(warp::reject::not_found)
+}
+
 pub async fn get_update_proposals(context: ContextLock) -> Result<impl Reply, Rejection> {
     let context = context.read().await;
     logic::get_update_proposals(&context)
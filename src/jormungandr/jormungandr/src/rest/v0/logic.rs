@@ -26,6 +26,7 @@ use chain_impl_mockchain::{
     fragment::{Fragment, FragmentId},
     key::Hash,
     leadership::LeadershipConsensus,
+    multisig,
     value::ValueError,
 };
 use futures::{
@@ -88,6 +89,12 @@ fn parse_account_id(id_hex: &str) -> Result<Identifier, Error> {
         .map_err(Into::into)
 }
 
+fn parse_multisig_id(id_hex: &str) -> Result<multisig::Identifier, Error> {
+    Blake2b256::from_str(id_hex)
+        .map(|hash| <[u8; 32]>::from(hash).into())
+        .map_err(Into::into)
+}
+
 fn parse_block_hash(hex: &str) -> Result<Hash, Error> {
     Blake2b256::from_str(hex)
         .map_err(Into::into)
@@ -134,6 +141,21 @@ pub async fn get_account_state(
         .map(Into::into))
 }
 
+pub async fn get_multisig_account_state(
+    context: &Context,
+    multisig_id_hex: &str,
+) -> Result<Option<AccountState>, Error> {
+    Ok(context
+        .blockchain_tip()?
+        .get_ref()
+        .await
+        .ledger()
+        .multisig()
+        .get_state(&parse_multisig_id(multisig_id_hex)?)
+        .ok()
+        .map(Into::into))
+}
+
 pub async fn get_message_logs(context: &Context) -> Result<Vec<FragmentLog>, Error> {
     let span = span!(parent: context.span()?, Level::TRACE, "message_logs");
     async move {
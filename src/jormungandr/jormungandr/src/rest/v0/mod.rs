@@ -22,6 +22,12 @@ pub fn filter(
         .and_then(handlers::get_account_state)
         .boxed();
 
+    let multisig = warp::path!("multisig" / String)
+        .and(warp::get())
+        .and(with_context.clone())
+        .and_then(handlers::get_multisig_account_state)
+        .boxed();
+
     let block = {
         let root = warp::path!("block" / ..);
 
@@ -235,6 +241,7 @@ pub fn filter(
 
     let routes = shutdown
         .or(account)
+        .or(multisig)
         .or(block)
         .or(fragment)
         .or(leaders)
@@ -40,6 +40,22 @@ pub async fn get_fragment_logs(context: ContextLock) -> Result<impl Reply, Rejec
         .map(|r| warp::reply::json(&r))
 }
 
+pub async fn get_pool_accounts(context: ContextLock) -> Result<impl Reply, Rejection> {
+    let context = context.read().await;
+    logic::get_pool_accounts(&context)
+        .await
+        .map_err(warp::reject::custom)
+        .map(|r| warp::reply::json(&r))
+}
+
+pub async fn get_pool_fragment_types(context: ContextLock) -> Result<impl Reply, Rejection> {
+    let context = context.read().await;
+    logic::get_pool_fragment_types(&context)
+        .await
+        .map_err(warp::reject::custom)
+        .map(|r| warp::reply::json(&r))
+}
+
 pub async fn get_account_votes_with_plan(
     vote_plan_id: VotePlanId,
     account_id_hex: String,
@@ -72,3 +88,11 @@ pub async fn get_accounts_votes_all(context: ContextLock) -> Result<impl Reply,
         .map_err(warp::reject::custom)
         .map(|r| warp::reply::json(&r))
 }
+
+pub async fn reload_config(context: ContextLock) -> Result<impl Reply, Rejection> {
+    let context = context.read().await;
+    logic::reload_config(&context)
+        .await
+        .map_err(warp::reject::custom)
+        .map(|r| warp::reply::json(&r))
+}
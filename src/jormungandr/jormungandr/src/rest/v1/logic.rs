@@ -1,5 +1,6 @@
 use crate::{
     blockchain::StorageError,
+    config_reload,
     intercom::{self, TransactionMsg},
     rest::Context,
 };
@@ -18,8 +19,9 @@ use futures::{
 };
 use hex::ToHex;
 use jormungandr_lib::interfaces::{
-    AccountVotes, FragmentLog, FragmentOrigin, FragmentStatus, FragmentsBatch,
-    FragmentsProcessingSummary, VotePlanId,
+    AccountPoolOccupancy, AccountVotes, ConfigReloadReport, FragmentLog, FragmentOrigin,
+    FragmentStatus, FragmentTypePoolOccupancy, FragmentsBatch, FragmentsProcessingSummary,
+    VotePlanId,
 };
 use std::{collections::HashMap, convert::TryInto, str::FromStr};
 use tracing::{span, Level};
@@ -50,6 +52,10 @@ pub enum Error {
     Hex(#[from] hex::FromHexError),
     #[error("Could not process all fragments")]
     Fragments(FragmentsProcessingSummary),
+    #[error("The node was started without a configuration file")]
+    NoConfigFile,
+    #[error(transparent)]
+    ConfigReload(#[from] config_reload::Error),
 }
 
 fn parse_account_id(id_hex: &str) -> Result<Identifier, Error> {
@@ -130,6 +136,43 @@ pub async fn get_fragment_logs(context: &Context) -> Result<Vec<FragmentLog>, Er
     .await
 }
 
+pub async fn get_pool_accounts(context: &Context) -> Result<Vec<AccountPoolOccupancy>, Error> {
+    let span =
+        span!(parent: context.span()?, Level::TRACE, "pool_accounts", request = "pool_accounts");
+    async move {
+        let (reply_handle, reply_future) = intercom::unary_reply();
+        let mut mbox = context.try_full()?.transaction_task.clone();
+        mbox.send(TransactionMsg::GetAccountsOccupancy(reply_handle))
+            .await
+            .map_err(|e| {
+                tracing::debug!(reason = %e, "error getting mempool occupancy by account");
+                Error::MsgSend(e)
+            })?;
+        reply_future.await.map_err(Into::into)
+    }
+    .instrument(span)
+    .await
+}
+
+pub async fn get_pool_fragment_types(
+    context: &Context,
+) -> Result<Vec<FragmentTypePoolOccupancy>, Error> {
+    let span = span!(parent: context.span()?, Level::TRACE, "pool_fragment_types", request = "pool_fragment_types");
+    async move {
+        let (reply_handle, reply_future) = intercom::unary_reply();
+        let mut mbox = context.try_full()?.transaction_task.clone();
+        mbox.send(TransactionMsg::GetFragmentTypesOccupancy(reply_handle))
+            .await
+            .map_err(|e| {
+                tracing::debug!(reason = %e, "error getting mempool occupancy by fragment type");
+                Error::MsgSend(e)
+            })?;
+        reply_future.await.map_err(Into::into)
+    }
+    .instrument(span)
+    .await
+}
+
 pub async fn get_account_votes_with_plan(
     context: &Context,
     vote_plan_id: VotePlanId,
@@ -252,3 +295,18 @@ pub async fn get_accounts_votes_all(
     .instrument(span)
     .await
 }
+
+pub async fn reload_config(context: &Context) -> Result<ConfigReloadReport, Error> {
+    let span =
+        span!(parent: context.span()?, Level::TRACE, "reload_config", request = "reload_config");
+    async move {
+        let reloader = context
+            .try_full()?
+            .config_reloader
+            .clone()
+            .ok_or(Error::NoConfigFile)?;
+        reloader.reload().await.map_err(Into::into)
+    }
+    .instrument(span)
+    .await
+}
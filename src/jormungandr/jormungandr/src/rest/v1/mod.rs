@@ -1,7 +1,10 @@
 mod handlers;
 mod logic;
 
-use crate::rest::{display_internal_server_error, ContextLock};
+use crate::{
+    config_reload,
+    rest::{display_internal_server_error, ContextLock},
+};
 use jormungandr_lib::interfaces::VotePlanId;
 use warp::{http::StatusCode, Filter, Rejection, Reply};
 
@@ -34,7 +37,20 @@ pub fn filter(
             .and_then(handlers::get_fragment_logs)
             .boxed();
 
-        root.and(post.or(status).or(logs)).boxed()
+        let pool_accounts = warp::path!("pool" / "accounts")
+            .and(warp::get())
+            .and(with_context.clone())
+            .and_then(handlers::get_pool_accounts)
+            .boxed();
+
+        let pool_types = warp::path!("pool" / "types")
+            .and(warp::get())
+            .and(with_context.clone())
+            .and_then(handlers::get_pool_fragment_types)
+            .boxed();
+
+        root.and(post.or(status).or(logs).or(pool_accounts).or(pool_types))
+            .boxed()
     };
 
     let votes_with_plan = warp::path!("votes" / "plan" / VotePlanId / "account-votes" / String)
@@ -49,10 +65,19 @@ pub fn filter(
 
     let votes_count = warp::path!("votes" / "plan" / "accounts-votes-all")
         .and(warp::get())
-        .and(with_context)
+        .and(with_context.clone())
         .and_then(handlers::get_accounts_votes_all);
 
-    let routes = fragments.or(votes_with_plan).or(votes).or(votes_count);
+    let config_reload = warp::path!("node" / "config" / "reload")
+        .and(warp::post())
+        .and(with_context)
+        .and_then(handlers::reload_config);
+
+    let routes = fragments
+        .or(votes_with_plan)
+        .or(votes)
+        .or(votes_count)
+        .or(config_reload);
 
     root.and(routes).recover(handle_rejection).boxed()
 }
@@ -61,9 +86,13 @@ pub fn filter(
 async fn handle_rejection(err: Rejection) -> Result<impl Reply, Rejection> {
     if let Some(err) = err.find::<logic::Error>() {
         let (body, code) = match err {
-            logic::Error::PublicKey(_) | logic::Error::Hash(_) | logic::Error::Hex(_) => {
-                (err.to_string(), StatusCode::BAD_REQUEST)
-            }
+            logic::Error::PublicKey(_)
+            | logic::Error::Hash(_)
+            | logic::Error::Hex(_)
+            | logic::Error::NoConfigFile
+            | logic::Error::ConfigReload(
+                config_reload::Error::Config(_) | config_reload::Error::Invalid(_),
+            ) => (err.to_string(), StatusCode::BAD_REQUEST),
             logic::Error::Fragments(summary) => (
                 serde_json::to_string(&summary).unwrap(),
                 StatusCode::BAD_REQUEST,
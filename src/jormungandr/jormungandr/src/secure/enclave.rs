@@ -17,7 +17,9 @@ pub struct LeaderEvent {
 }
 
 pub struct Schedule {
-    enclave: Arc<Enclave>,
+    /// the enclave to evaluate the leader election with, `None` if the
+    /// events were computed beforehand
+    enclave: Option<Arc<Enclave>>,
     leadership: Arc<Leadership>,
     current_slot: u32,
     stop_at_slot: u32,
@@ -67,7 +69,7 @@ impl Schedule {
         let stop_at_slot = slot_start + nb_slots;
 
         Self {
-            enclave,
+            enclave: Some(enclave),
             leadership,
             current_slot: slot_start,
             stop_at_slot,
@@ -75,8 +77,26 @@ impl Schedule {
         }
     }
 
+    /// Create a schedule of leader events already computed, e.g. by
+    /// a remote signer. The events must be in the order of their dates.
+    pub fn from_events(leadership: Arc<Leadership>, mut events: Vec<LeaderEvent>) -> Self {
+        // events are taken from the end
+        events.reverse();
+        Self {
+            enclave: None,
+            leadership,
+            current_slot: 0,
+            stop_at_slot: 0,
+            current_slot_data: events,
+        }
+    }
+
     fn fill(&mut self) {
-        let leader = if let Some(leader) = self.enclave.leader_data.as_ref() {
+        let leader = if let Some(leader) = self
+            .enclave
+            .as_ref()
+            .and_then(|enclave| enclave.leader_data.as_ref().as_ref())
+        {
             leader
         } else {
             return;
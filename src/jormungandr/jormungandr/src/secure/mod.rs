@@ -9,6 +9,8 @@ use std::path::Path;
 use thiserror::Error;
 
 pub mod enclave;
+#[cfg(unix)]
+pub mod signer;
 
 /// hold the node's bft secret setting
 #[derive(Clone, Deserialize)]
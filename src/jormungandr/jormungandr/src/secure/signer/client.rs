@@ -0,0 +1,116 @@
+use super::{Error, Request, Response};
+use chain_crypto::{algorithms::vrf::ProvenOutputSeed, Ed25519, PublicKey};
+use chain_impl_mockchain::{
+    certificate::PoolId,
+    header::SlotId,
+    leadership::genesis::{LeaderElection, Witness},
+    milli::Milli,
+};
+use std::path::PathBuf;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::UnixStream,
+};
+
+/// Public identifiers of the leaders the signer holds keys for.
+pub struct Leaders {
+    pub bft: Option<PublicKey<Ed25519>>,
+    pub genesis: Option<PoolId>,
+}
+
+/// Client of a remote signer listening on a Unix socket.
+///
+/// A new connection is made for every request, so that the node does not
+/// depend on the signer being started first or staying up.
+#[derive(Clone)]
+pub struct Client {
+    socket_path: PathBuf,
+}
+
+impl Client {
+    pub fn new(socket_path: PathBuf) -> Self {
+        Client { socket_path }
+    }
+
+    async fn call(&self, request: &Request) -> Result<Response, Error> {
+        let mut stream = UnixStream::connect(&self.socket_path)
+            .await
+            .map_err(|source| Error::Connect {
+                path: self.socket_path.clone(),
+                source,
+            })?;
+        let mut request = serde_json::to_vec(request)?;
+        request.push(b'\n');
+        stream.write_all(&request).await.map_err(Error::Io)?;
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).await.map_err(Error::Io)?;
+        match serde_json::from_slice(&response)? {
+            Response::Refused { reason } => Err(Error::Refused(reason)),
+            response => Ok(response),
+        }
+    }
+
+    pub async fn leaders(&self) -> Result<Leaders, Error> {
+        match self.call(&Request::Leaders).await? {
+            Response::Leaders { bft, genesis } => Ok(Leaders {
+                bft: bft
+                    .map(|key| {
+                        hex::decode(key)
+                            .ok()
+                            .and_then(|key| PublicKey::from_binary(&key).ok())
+                            .ok_or(Error::InvalidResponse("BFT leader key"))
+                    })
+                    .transpose()?,
+                genesis: genesis
+                    .map(|id| id.parse().map_err(|_| Error::InvalidResponse("pool ID")))
+                    .transpose()?,
+            }),
+            _ => Err(Error::UnexpectedResponse),
+        }
+    }
+
+    /// Evaluate the leader election of the signer's stake pool for the slots
+    /// starting at `slot_start`, returning the slots the pool is elected for
+    /// with the witness of the election.
+    pub async fn elect(
+        &self,
+        election: &LeaderElection,
+        slot_start: SlotId,
+        nb_slots: u32,
+    ) -> Result<Vec<(SlotId, Witness)>, Error> {
+        let request = Request::Elect {
+            epoch_nonce: hex::encode(&election.epoch_nonce),
+            stake: election.stake.stake.0,
+            total_stake: election.stake.total.0,
+            active_slots_coeff: Milli::from(election.active_slots_coeff).to_millis(),
+            slot_start,
+            nb_slots,
+        };
+        match self.call(&request).await? {
+            Response::Elected { slots } => slots
+                .into_iter()
+                .map(|slot| {
+                    hex::decode(slot.witness)
+                        .ok()
+                        .and_then(|witness| ProvenOutputSeed::from_bytes_unverified(&witness))
+                        .map(|witness| (slot.slot_id, witness))
+                        .ok_or(Error::InvalidResponse("election witness"))
+                })
+                .collect(),
+            _ => Err(Error::UnexpectedResponse),
+        }
+    }
+
+    /// Get the signature of the authenticated data of a block header.
+    pub async fn sign_header(&self, header: &[u8]) -> Result<Vec<u8>, Error> {
+        let request = Request::SignHeader {
+            header: hex::encode(header),
+        };
+        match self.call(&request).await? {
+            Response::Signature { signature } => {
+                hex::decode(signature).map_err(|_| Error::InvalidResponse("signature"))
+            }
+            _ => Err(Error::UnexpectedResponse),
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use crate::blockcfg::BlockDate;
+use chain_crypto::hash::Blake2b256;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum GuardError {
+    #[error("a different block header was already signed for {date}")]
+    DoubleSigning { date: BlockDate },
+    #[error("refusing to sign a block header for {date}, a header was already signed for the later {last}")]
+    DateInPast { date: BlockDate, last: BlockDate },
+    #[error("cannot access the signing state file {path}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("malformed signing state file {path}")]
+    Format {
+        path: PathBuf,
+        #[source]
+        source: serde_json::Error,
+    },
+}
+
+/// The last block header signed, kept as a high watermark.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct LastSigned {
+    epoch: u32,
+    slot_id: u32,
+    /// hash of the authenticated data of the header
+    header_hash: String,
+}
+
+impl LastSigned {
+    fn date(&self) -> BlockDate {
+        BlockDate {
+            epoch: self.epoch,
+            slot_id: self.slot_id,
+        }
+    }
+}
+
+/// Protection against double signing.
+///
+/// The guard only allows signing headers for dates after the last signed
+/// one, or the very same header again. The last signed header is written to
+/// the state file before the signature is released, so that the protection
+/// holds across restarts of the signer.
+pub struct SigningGuard {
+    path: Option<PathBuf>,
+    last: Option<LastSigned>,
+}
+
+impl SigningGuard {
+    /// Create a guard keeping its state in memory only.
+    pub fn in_memory() -> Self {
+        SigningGuard {
+            path: None,
+            last: None,
+        }
+    }
+
+    /// Open the guard with the state kept in the given file,
+    /// which is created on the first signature.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, GuardError> {
+        let path = path.as_ref().to_path_buf();
+        let last = match fs::read(&path) {
+            Ok(content) => {
+                Some(
+                    serde_json::from_slice(&content).map_err(|source| GuardError::Format {
+                        path: path.clone(),
+                        source,
+                    })?,
+                )
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(source) => return Err(GuardError::Io { path, source }),
+        };
+        Ok(SigningGuard {
+            path: Some(path),
+            last,
+        })
+    }
+
+    /// Check that the header with the given authenticated data can be signed
+    /// for the date, and record it as the last signed header.
+    pub fn check_and_record(&mut self, date: BlockDate, header: &[u8]) -> Result<(), GuardError> {
+        let header_hash = Blake2b256::new(header).to_string();
+        if let Some(last) = &self.last {
+            let last_date = last.date();
+            if date < last_date {
+                return Err(GuardError::DateInPast {
+                    date,
+                    last: last_date,
+                });
+            }
+            if date == last_date {
+                return if last.header_hash == header_hash {
+                    Ok(())
+                } else {
+                    Err(GuardError::DoubleSigning { date })
+                };
+            }
+        }
+        let last = LastSigned {
+            epoch: date.epoch,
+            slot_id: date.slot_id,
+            header_hash,
+        };
+        if let Some(path) = &self.path {
+            write_state(path, &last).map_err(|source| GuardError::Io {
+                path: path.clone(),
+                source,
+            })?;
+        }
+        self.last = Some(last);
+        Ok(())
+    }
+}
+
+fn write_state(path: &Path, last: &LastSigned) -> std::io::Result<()> {
+    // write to a temporary file first so that a crash does not leave
+    // a truncated state file behind
+    let tmp_path = path.with_extension("tmp");
+    let mut file = fs::File::create(&tmp_path)?;
+    file.write_all(&serde_json::to_vec(last)?)?;
+    file.sync_all()?;
+    fs::rename(&tmp_path, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(epoch: u32, slot_id: u32) -> BlockDate {
+        BlockDate { epoch, slot_id }
+    }
+
+    #[test]
+    fn refuses_a_different_header_for_the_same_slot() {
+        let mut guard = SigningGuard::in_memory();
+        guard.check_and_record(date(1, 10), b"header A").unwrap();
+        guard.check_and_record(date(1, 10), b"header A").unwrap();
+        assert!(matches!(
+            guard.check_and_record(date(1, 10), b"header B"),
+            Err(GuardError::DoubleSigning { .. })
+        ));
+        assert!(matches!(
+            guard.check_and_record(date(1, 9), b"header C"),
+            Err(GuardError::DateInPast { .. })
+        ));
+        guard.check_and_record(date(2, 0), b"header D").unwrap();
+    }
+
+    #[test]
+    fn last_signed_header_is_kept_across_restarts() {
+        let path = std::env::temp_dir().join(format!(
+            "jormungandr-signer-state-{}.json",
+            std::process::id()
+        ));
+        let mut guard = SigningGuard::open(&path).unwrap();
+        guard.check_and_record(date(3, 7), b"header A").unwrap();
+        let mut reopened = SigningGuard::open(&path).unwrap();
+        let res = reopened.check_and_record(date(3, 7), b"header B");
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(res, Err(GuardError::DoubleSigning { .. })));
+    }
+}
@@ -0,0 +1,90 @@
+//! Protocol of the remote signer, a separate process holding the leader
+//! secret keys of the node.
+//!
+//! The node connects to the signer over a Unix socket, sending one request
+//! per connection as a line of JSON and reading the response the same way.
+//! The signer evaluates the Genesis Praos leader election with its VRF key
+//! and signs block headers, refusing to sign two different headers for the
+//! same slot.
+
+mod client;
+mod guard;
+mod server;
+
+pub use self::{
+    client::{Client, Leaders},
+    guard::{GuardError, SigningGuard},
+    server::Signer,
+};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("cannot connect to the signer at {path}")]
+    Connect {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("communication with the signer failed")]
+    Io(#[source] std::io::Error),
+    #[error("malformed signer message")]
+    Format(#[from] serde_json::Error),
+    #[error("the signer refused the request: {0}")]
+    Refused(String),
+    #[error("unexpected response from the signer")]
+    UnexpectedResponse,
+    #[error("invalid {0} in the signer response")]
+    InvalidResponse(&'static str),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "request", rename_all = "snake_case")]
+enum Request {
+    /// Get the public identifiers of the leaders the signer holds keys for.
+    Leaders,
+    /// Evaluate the Genesis Praos leader election of the pool for a range of
+    /// slots, returning the slots the pool is elected for.
+    Elect {
+        /// hex-encoded epoch nonce
+        epoch_nonce: String,
+        stake: u64,
+        total_stake: u64,
+        /// active slots coefficient in thousandths
+        active_slots_coeff: u64,
+        slot_start: u32,
+        nb_slots: u32,
+    },
+    /// Sign the hex-encoded authenticated data of a block header.
+    SignHeader { header: String },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "response", rename_all = "snake_case")]
+enum Response {
+    Leaders {
+        /// hex-encoded public key of the BFT leader
+        bft: Option<String>,
+        /// hex-encoded ID of the stake pool
+        genesis: Option<String>,
+    },
+    Elected {
+        slots: Vec<ElectedSlot>,
+    },
+    Signature {
+        /// hex-encoded signature
+        signature: String,
+    },
+    Refused {
+        reason: String,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ElectedSlot {
+    slot_id: u32,
+    /// hex-encoded VRF proof
+    witness: String,
+}
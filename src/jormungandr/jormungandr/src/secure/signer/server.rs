@@ -0,0 +1,230 @@
+use super::{ElectedSlot, Error, GuardError, Request, Response, SigningGuard};
+use crate::blockcfg::{BlockVersion, Header, Leader};
+use chain_crypto::{Ed25519, Signature, SumEd25519_12};
+use chain_impl_mockchain::{
+    chaineval::PraosNonce,
+    leadership::genesis::LeaderElection,
+    milli::Milli,
+    setting::ActiveSlotsCoeff,
+    stake::{PercentStake, Stake},
+};
+use std::{
+    convert::TryFrom,
+    sync::{Arc, Mutex},
+};
+use thiserror::Error;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{UnixListener, UnixStream},
+};
+
+#[derive(Debug, Error)]
+enum RequestError {
+    #[error("the signer does not hold a {0} leader key")]
+    NoLeaderKey(&'static str),
+    #[error("invalid {0}")]
+    Invalid(&'static str),
+    #[error("unsigned block headers cannot be signed")]
+    UnsignedHeader,
+    #[error("the block header is not for the leader held by the signer")]
+    ForeignHeader,
+    #[error(transparent)]
+    Guard(#[from] GuardError),
+}
+
+/// Server side of the remote signer, holding the leader secret keys.
+pub struct Signer {
+    leader: Leader,
+    guard: Mutex<SigningGuard>,
+}
+
+impl Signer {
+    pub fn new(leader: Leader, guard: SigningGuard) -> Self {
+        Signer {
+            leader,
+            guard: Mutex::new(guard),
+        }
+    }
+
+    /// Serve the requests of the connections accepted on the listener.
+    ///
+    /// Resolves only if accepting a connection fails.
+    pub async fn serve(self, listener: UnixListener) -> std::io::Result<()> {
+        let signer = Arc::new(self);
+        loop {
+            let (stream, _) = listener.accept().await?;
+            let signer = Arc::clone(&signer);
+            tokio::spawn(async move {
+                if let Err(e) = signer.serve_connection(stream).await {
+                    tracing::warn!(reason = %e, "failed to serve a signer request");
+                }
+            });
+        }
+    }
+
+    async fn serve_connection(self: Arc<Self>, stream: UnixStream) -> Result<(), Error> {
+        let (reader, mut writer) = stream.into_split();
+        let mut line = String::new();
+        BufReader::new(reader)
+            .read_line(&mut line)
+            .await
+            .map_err(Error::Io)?;
+        let request: Request = serde_json::from_str(&line)?;
+        // the leader election is computationally heavy and signing
+        // writes the guard state to disk
+        let response = tokio::task::spawn_blocking(move || self.handle(request))
+            .await
+            .expect("signer request handler panicked");
+        let mut response = serde_json::to_vec(&response)?;
+        response.push(b'\n');
+        writer.write_all(&response).await.map_err(Error::Io)?;
+        writer.shutdown().await.map_err(Error::Io)
+    }
+
+    fn handle(&self, request: Request) -> Response {
+        let res = match request {
+            Request::Leaders => Ok(self.leaders()),
+            Request::Elect {
+                epoch_nonce,
+                stake,
+                total_stake,
+                active_slots_coeff,
+                slot_start,
+                nb_slots,
+            } => self.elect(
+                &epoch_nonce,
+                PercentStake {
+                    stake: Stake(stake),
+                    total: Stake(total_stake),
+                },
+                active_slots_coeff,
+                slot_start,
+                nb_slots,
+            ),
+            Request::SignHeader { header } => self.sign_header(&header),
+        };
+        res.unwrap_or_else(|e| {
+            tracing::warn!(reason = %e, "refusing signer request");
+            Response::Refused {
+                reason: e.to_string(),
+            }
+        })
+    }
+
+    fn leaders(&self) -> Response {
+        Response::Leaders {
+            bft: self
+                .leader
+                .bft_leader
+                .as_ref()
+                .map(|leader| hex::encode(leader.sig_key.to_public())),
+            genesis: self
+                .leader
+                .genesis_leader
+                .as_ref()
+                .map(|leader| leader.node_id.to_string()),
+        }
+    }
+
+    fn elect(
+        &self,
+        epoch_nonce: &str,
+        stake: PercentStake,
+        active_slots_coeff: u64,
+        slot_start: u32,
+        nb_slots: u32,
+    ) -> Result<Response, RequestError> {
+        let leader = self
+            .leader
+            .genesis_leader
+            .as_ref()
+            .ok_or(RequestError::NoLeaderKey("Genesis Praos"))?;
+        let epoch_nonce = hex::decode(epoch_nonce)
+            .ok()
+            .and_then(|nonce| <[u8; 32]>::try_from(nonce.as_slice()).ok())
+            .ok_or(RequestError::Invalid("epoch nonce"))?;
+        let active_slots_coeff = ActiveSlotsCoeff::try_from(Milli::from_millis(active_slots_coeff))
+            .map_err(|_| RequestError::Invalid("active slots coefficient"))?;
+        let election = LeaderElection {
+            epoch_nonce: PraosNonce::from_output_array(epoch_nonce),
+            stake,
+            active_slots_coeff,
+        };
+        let slot_end = slot_start
+            .checked_add(nb_slots)
+            .ok_or(RequestError::Invalid("slot range"))?;
+        let slots = (slot_start..slot_end)
+            .filter_map(|slot_id| {
+                election
+                    .evaluate(slot_id, &leader.vrf_key)
+                    .map(|witness| ElectedSlot {
+                        slot_id,
+                        witness: hex::encode(witness.bytes()),
+                    })
+            })
+            .collect();
+        Ok(Response::Elected { slots })
+    }
+
+    fn sign_header(&self, header: &str) -> Result<Response, RequestError> {
+        let data = hex::decode(header).map_err(|_| RequestError::Invalid("block header"))?;
+        let header = parse_header(&data)?;
+        let signature = match header.block_version() {
+            BlockVersion::Genesis => return Err(RequestError::UnsignedHeader),
+            BlockVersion::Ed25519Signed => {
+                let leader = self
+                    .leader
+                    .bft_leader
+                    .as_ref()
+                    .ok_or(RequestError::NoLeaderKey("BFT"))?;
+                let leader_id = header
+                    .get_bft_leader_id()
+                    .ok_or(RequestError::Invalid("block header"))?;
+                if leader_id.as_public_key() != &leader.sig_key.to_public() {
+                    return Err(RequestError::ForeignHeader);
+                }
+                self.guard_header(&header, &data)?;
+                let signature: Signature<[u8], Ed25519> = leader.sig_key.sign_slice(&data);
+                hex::encode(signature)
+            }
+            BlockVersion::KesVrfproof => {
+                let leader = self
+                    .leader
+                    .genesis_leader
+                    .as_ref()
+                    .ok_or(RequestError::NoLeaderKey("Genesis Praos"))?;
+                if header.get_stakepool_id().as_ref() != Some(&leader.node_id) {
+                    return Err(RequestError::ForeignHeader);
+                }
+                self.guard_header(&header, &data)?;
+                let signature: Signature<[u8], SumEd25519_12> = leader.sig_key.sign_slice(&data);
+                hex::encode(signature)
+            }
+        };
+        Ok(Response::Signature { signature })
+    }
+
+    fn guard_header(&self, header: &Header, data: &[u8]) -> Result<(), RequestError> {
+        self.guard
+            .lock()
+            .unwrap()
+            .check_and_record(header.block_date(), data)
+            .map_err(Into::into)
+    }
+}
+
+/// Parse the authenticated data of a block header, which is the header
+/// without its signature, to inspect what the signer is asked to sign.
+fn parse_header(data: &[u8]) -> Result<Header, RequestError> {
+    let version = data
+        .first()
+        .and_then(|version| BlockVersion::from_u8(*version))
+        .ok_or(RequestError::Invalid("block header version"))?;
+    if data.len() != version.get_auth_size().get() {
+        return Err(RequestError::Invalid("block header size"));
+    }
+    // pad with an empty signature to get a well-formed header
+    let mut header = data.to_vec();
+    header.resize(version.get_size().get(), 0);
+    Header::from_slice(&header).map_err(|_| RequestError::Invalid("block header"))
+}
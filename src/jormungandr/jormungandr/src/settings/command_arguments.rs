@@ -51,6 +51,10 @@ pub struct StartArguments {
     #[clap(long = "enable-prometheus")]
     pub prometheus_enabled: bool,
 
+    /// Enable the OTLP metrics exporter and set the collector's GRPC endpoint.
+    #[clap(long = "metrics-collector-endpoint")]
+    pub metrics_collector_endpoint: Option<url::Url>,
+
     /// The address to listen from and accept connection from. This is the
     /// public address that will be distributed to other peers of the network.
     #[clap(long = "public-address")]
@@ -8,6 +8,7 @@ use std::{
     str::FromStr,
 };
 use tracing::level_filters::LevelFilter;
+use tracing_subscriber::{reload, Registry};
 
 #[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
@@ -85,8 +86,18 @@ impl Default for LogOutput {
     }
 }
 
+/// Handle to change the level of the logs while the node is running.
+pub type LogLevelHandle = reload::Handle<LevelFilter, Registry>;
+
 pub struct LogGuard {
     _nonblocking_worker_guard: Option<tracing_appender::non_blocking::WorkerGuard>,
+    level_handle: LogLevelHandle,
+}
+
+impl LogGuard {
+    pub fn level_handle(&self) -> LogLevelHandle {
+        self.level_handle.clone()
+    }
 }
 
 impl Drop for LogGuard {
@@ -141,8 +152,9 @@ impl LogSettings {
             None
         };
 
+        let (level_layer, level_handle) = reload::Layer::new(self.level);
         let subscriber = tracing_subscriber::registry()
-            .with(self.level)
+            .with(level_layer)
             .with(otel_layer);
 
         // configure the registry subscriber as the global default,
@@ -292,6 +304,7 @@ impl LogSettings {
 
         Ok(LogGuard {
             _nonblocking_worker_guard: nonblocking_worker_guard,
+            level_handle,
         })
     }
 }
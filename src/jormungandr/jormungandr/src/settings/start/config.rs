@@ -6,7 +6,10 @@ use crate::{
     },
     topology::QuarantineConfig,
 };
-pub use jormungandr_lib::interfaces::{Cors, JRpc, LayersConfig, Rest, Tls, TrustedPeer};
+use chain_storage::StorageEngine;
+pub use jormungandr_lib::interfaces::{
+    Cors, JRpc, LayersConfig, P2pProtocol, P2pTls, Rest, Tls, TrustedPeer,
+};
 use jormungandr_lib::{interfaces::Mempool, time::Duration};
 use multiaddr::Multiaddr;
 use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
@@ -18,6 +21,12 @@ use tracing::level_filters::LevelFilter;
 pub struct Config {
     pub secret_file: Option<PathBuf>,
     pub storage: Option<PathBuf>,
+
+    /// the engine of the volatile part of the block storage, `sled` if not
+    /// provided
+    #[serde(default, deserialize_with = "storage_engine_opt_serde::deserialize")]
+    pub storage_engine: Option<StorageEngine>,
+
     pub log: Option<ConfigLogSettings>,
 
     /// setting of the mempool, fragment logs and related data
@@ -40,6 +49,9 @@ pub struct Config {
     #[cfg(feature = "prometheus-metrics")]
     pub prometheus: Option<Prometheus>,
 
+    /// export of the metrics to an OpenTelemetry collector
+    pub otlp_metrics: Option<OtlpMetrics>,
+
     /// the time interval with no blockchain updates after which alerts are thrown
     #[serde(default)]
     pub no_blockchain_updates_warning_interval: Option<Duration>,
@@ -154,6 +166,25 @@ pub struct Connection {
     /// for executing DNS lookups.
     #[serde(default)]
     pub dns_server_address: Option<Address>,
+
+    /// The protocol served on the listen address, also used to connect to
+    /// peers for which no protocol is configured.
+    ///
+    /// The default value is grpc.
+    #[serde(default)]
+    pub protocol: Option<P2pProtocol>,
+
+    /// If given, the node also accepts connections with the NTT protocol
+    /// on this socket address, so that it can serve both protocols at once.
+    #[serde(default)]
+    pub ntt_listen: Option<Address>,
+
+    /// TLS settings of the gRPC connections with peers.
+    ///
+    /// If set, the node only accepts and makes gRPC connections
+    /// secured with TLS.
+    #[serde(default)]
+    pub tls: Option<P2pTls>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
@@ -163,6 +194,11 @@ pub struct Leadership {
     /// the least recently used log will be erased from the logs for a new one
     /// to be inserted.
     pub logs_capacity: usize,
+
+    /// Unix socket of a `jormungandr-signer` process holding the leader keys,
+    /// to use instead of a secret file loaded into the node.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signer: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -171,10 +207,21 @@ pub struct Prometheus {
     pub enabled: bool,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct OtlpMetrics {
+    /// GRPC endpoint of the collector
+    pub collector_endpoint: url::Url,
+    /// interval between two exports of the metrics, 10 seconds if not provided
+    #[serde(default)]
+    pub export_interval: Option<Duration>,
+}
+
 impl Default for Leadership {
     fn default() -> Self {
         Leadership {
             logs_capacity: 1_024,
+            signer: None,
         }
     }
 }
@@ -201,3 +248,19 @@ mod filter_level_opt_serde {
         data.map(|level| level.to_string()).serialize(serializer)
     }
 }
+
+mod storage_engine_opt_serde {
+    use super::*;
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<StorageEngine>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|variant| {
+                variant
+                    .parse()
+                    .map_err(|_| D::Error::unknown_variant(&variant, &["sled", "rocksdb"]))
+            })
+            .transpose()
+    }
+}
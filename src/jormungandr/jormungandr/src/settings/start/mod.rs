@@ -2,22 +2,32 @@ pub mod config;
 pub mod network;
 
 use self::{
-    config::{Config, Leadership},
-    network::{Protocol, TrustedPeer},
+    config::{Config, Leadership, OtlpMetrics, P2pTls},
+    network::{Protocol, Tls, TrustedPeer},
 };
 use crate::{
     settings::{command_arguments::*, logging::LogSettings, Block0Info},
     topology::layers::{self, LayersConfig, PreferredListConfig, RingsConfig},
 };
 use chain_crypto::Ed25519;
+use chain_storage::StorageEngine;
 pub use jormungandr_lib::interfaces::{Cors, JRpc, Mempool, Rest, Tls};
 use jormungandr_lib::{crypto::key::SigningKey, multiaddr};
-use std::{convert::TryFrom, fs::File, path::PathBuf};
+use std::{
+    convert::TryFrom,
+    io::Write,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 use thiserror::Error;
 
-const DEFAULT_NO_BLOCKCHAIN_UPDATES_WARNING_INTERVAL: u64 = 1800; // 30 min
+pub const DEFAULT_NO_BLOCKCHAIN_UPDATES_WARNING_INTERVAL: Duration = Duration::from_secs(30 * 60);
 const DEFAULT_BLOCK_HARD_DEADLINE: u32 = 50;
 
+/// Name of the file in the storage directory that keeps the node key
+/// when no node key file is configured.
+pub const NODE_KEY_FILE_NAME: &str = "node_key";
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("Cannot read the node configuration file: {0}")]
@@ -30,14 +40,37 @@ pub enum Error {
     InvalidMultiaddr(#[from] multiaddr::Error),
     #[error("cannot deserialize node key from file")]
     InvalidKey(#[from] chain_crypto::bech32::Error),
+    #[error("cannot access the node key file {path}")]
+    NodeKeyIo {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("cannot read the P2P TLS file {path}")]
+    TlsIo {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("trusted peer {addr} needs an `id` to verify its TLS certificate")]
+    TlsTrustedPeerWithoutId { addr: std::net::SocketAddr },
+    #[error("p2p.tls only secures gRPC connections and cannot be used with an NTT listener")]
+    TlsWithNttListener,
+    #[error("trusted peer {addr} uses the NTT protocol, which p2p.tls cannot secure")]
+    TlsNttTrustedPeer { addr: std::net::SocketAddr },
     #[error(transparent)]
     InvalidLayersConfig(#[from] layers::ParseError),
+    #[error("the leader secret file cannot be used together with a remote signer")]
+    SecretWithSigner,
+    #[error("remote signers are only supported on Unix platforms")]
+    SignerNotSupported,
 }
 
 /// Overall Settings for node
 pub struct Settings {
     pub network: network::Configuration,
     pub storage: Option<PathBuf>,
+    pub storage_engine: StorageEngine,
     pub block_0: Block0Info,
     pub secret: Option<PathBuf>,
     pub rest: Option<Rest>,
@@ -47,25 +80,42 @@ pub struct Settings {
     pub leadership: Leadership,
     #[cfg(feature = "prometheus-metrics")]
     pub prometheus: bool,
-    pub no_blockchain_updates_warning_interval: std::time::Duration,
+    pub otlp_metrics: Option<OtlpMetrics>,
+    pub no_blockchain_updates_warning_interval: Duration,
     pub block_hard_deadline: u32,
+    pub config_file: Option<ConfigFile>,
+}
+
+/// The node configuration file, as read when the node started.
+pub struct ConfigFile {
+    pub path: PathBuf,
+    pub content: serde_yaml::Value,
+    /// the log level set on the command line takes precedence over the file
+    pub log_level_overridden: bool,
 }
 
 pub struct RawSettings {
     command_line: CommandLine,
     config: Option<Config>,
+    config_content: Option<serde_yaml::Value>,
 }
 
 impl RawSettings {
     pub fn load(command_line: CommandLine) -> Result<Self, Error> {
-        let config = if let Some(node_config) = &command_line.start_arguments.node_config {
-            Some(serde_yaml::from_reader(File::open(node_config)?)?)
-        } else {
-            None
-        };
+        let (config, config_content) =
+            if let Some(node_config) = &command_line.start_arguments.node_config {
+                let contents = std::fs::read_to_string(node_config)?;
+                (
+                    Some(serde_yaml::from_str(&contents)?),
+                    Some(serde_yaml::from_str(&contents)?),
+                )
+            } else {
+                (None, None)
+            };
         Ok(Self {
             command_line,
             config,
+            config_content,
         })
     }
 
@@ -147,9 +197,9 @@ impl RawSettings {
         let RawSettings {
             command_line,
             config,
+            config_content,
         } = self;
         let command_arguments = &command_line.start_arguments;
-        let network = generate_network(command_arguments, &config)?;
 
         let storage = match (
             command_arguments.storage.as_ref(),
@@ -160,11 +210,23 @@ impl RawSettings {
             (None, None) => None,
         };
 
+        let network = generate_network(command_arguments, &config, storage.as_deref())?;
+
         let secret = command_arguments
             .secret
             .clone()
             .or_else(|| config.as_ref().and_then(|cfg| cfg.secret_file.clone()));
-        if secret.is_none() {
+        let leadership = config
+            .as_ref()
+            .map_or(Leadership::default(), |cfg| cfg.leadership.clone());
+        if leadership.signer.is_some() {
+            if cfg!(not(unix)) {
+                return Err(Error::SignerNotSupported);
+            }
+            if secret.is_some() {
+                return Err(Error::SecretWithSigner);
+            }
+        } else if secret.is_none() {
             tracing::warn!(
                 "Node started without path to the stored secret keys (not a stake pool or a BFT leader)"
             );
@@ -188,8 +250,31 @@ impl RawSettings {
                     .map_or(false, |settings| settings.enabled)
             });
 
+        let otlp_metrics_config = config.as_ref().and_then(|cfg| cfg.otlp_metrics.clone());
+        let otlp_metrics = match command_arguments.metrics_collector_endpoint.clone() {
+            Some(collector_endpoint) => Some(OtlpMetrics {
+                collector_endpoint,
+                export_interval: otlp_metrics_config.and_then(|cfg| cfg.export_interval),
+            }),
+            None => otlp_metrics_config,
+        };
+
+        let config_file = command_arguments
+            .node_config
+            .clone()
+            .zip(config_content)
+            .map(|(path, content)| ConfigFile {
+                path,
+                content,
+                log_level_overridden: command_line.log_level.is_some(),
+            });
+
         Ok(Settings {
             storage,
+            storage_engine: config
+                .as_ref()
+                .and_then(|cfg| cfg.storage_engine)
+                .unwrap_or_default(),
             block_0,
             network,
             secret,
@@ -199,22 +284,20 @@ impl RawSettings {
             mempool: config
                 .as_ref()
                 .map_or(Mempool::default(), |cfg| cfg.mempool.clone()),
-            leadership: config
-                .as_ref()
-                .map_or(Leadership::default(), |cfg| cfg.leadership.clone()),
+            leadership,
             #[cfg(feature = "prometheus-metrics")]
             prometheus,
+            otlp_metrics,
             no_blockchain_updates_warning_interval: config
                 .as_ref()
                 .and_then(|config| config.no_blockchain_updates_warning_interval)
                 .map(|d| d.into())
-                .unwrap_or_else(|| {
-                    std::time::Duration::from_secs(DEFAULT_NO_BLOCKCHAIN_UPDATES_WARNING_INTERVAL)
-                }),
+                .unwrap_or(DEFAULT_NO_BLOCKCHAIN_UPDATES_WARNING_INTERVAL),
             block_hard_deadline: config
                 .as_ref()
                 .and_then(|config| config.block_hard_deadline)
                 .unwrap_or(DEFAULT_BLOCK_HARD_DEADLINE),
+            config_file,
         })
     }
 }
@@ -247,6 +330,7 @@ fn resolve_trusted_peers(peers: &[jormungandr_lib::interfaces::TrustedPeer]) ->
 fn generate_network(
     command_arguments: &StartArguments,
     config: &Option<Config>,
+    storage: Option<&Path>,
 ) -> Result<network::Configuration, Error> {
     let (mut p2p, http_fetch_block0_service, skip_bootstrap, bootstrap_from_trusted_peers) =
         if let Some(cfg) = config {
@@ -274,18 +358,7 @@ fn generate_network(
         .as_ref()
         .map_or_else(Vec::new, |peers| resolve_trusted_peers(peers));
 
-    // Layers config
-    let preferred_list_config = p2p.layers.preferred_list.unwrap_or_default();
-    let preferred_list = PreferredListConfig {
-        view_max: preferred_list_config.view_max.into(),
-        peers: resolve_trusted_peers(&preferred_list_config.peers),
-    };
-    let rings = p2p
-        .layers
-        .topics_of_interest
-        .map(RingsConfig::try_from)
-        .transpose()?
-        .unwrap_or_default();
+    let layers = layers_config(p2p.layers)?;
 
     let config_addr = p2p.connection.public_address;
     let public_address = command_arguments
@@ -294,12 +367,7 @@ fn generate_network(
         .or(config_addr)
         .and_then(|addr| multiaddr::to_tcp_socket_addr(&addr));
 
-    let node_key = match p2p.bootstrap.node_key_file {
-        Some(node_key_file) => {
-            <SigningKey<Ed25519>>::from_bech32_str(&std::fs::read_to_string(&node_key_file)?)?
-        }
-        None => SigningKey::generate(rand::thread_rng()),
-    };
+    let node_key = load_node_key(p2p.bootstrap.node_key_file.as_deref(), storage)?;
 
     let p2p_listen_address = p2p.connection.listen.as_ref();
     let listen_address = command_arguments
@@ -308,17 +376,23 @@ fn generate_network(
         .or(p2p_listen_address)
         .cloned();
 
+    let tls = p2p.connection.tls.as_ref().map(load_tls).transpose()?;
+    if tls.is_some() {
+        if let Some(peer) = trusted_peers.iter().find(|peer| peer.id.is_none()) {
+            return Err(Error::TlsTrustedPeerWithoutId { addr: peer.addr });
+        }
+    }
+
     let mut network = network::Configuration {
         listen_address,
         public_address,
         trusted_peers,
         node_key,
         policy: p2p.policy.clone(),
-        protocol: Protocol::Grpc,
-        layers: LayersConfig {
-            preferred_list,
-            rings,
-        },
+        protocol: p2p.connection.protocol.unwrap_or_default(),
+        ntt_listen_address: p2p.connection.ntt_listen,
+        tls,
+        layers,
         max_connections: p2p
             .connection
             .max_connections
@@ -347,6 +421,14 @@ fn generate_network(
         skip_bootstrap,
     };
 
+    if network.tls.is_some() {
+        check_tls_transport(
+            network.protocol,
+            network.ntt_listen_address,
+            &network.trusted_peers,
+        )?;
+    }
+
     if network.max_client_connections > network.max_connections {
         tracing::warn!(
             "p2p.max_client_connections is larger than p2p.max_connections, decreasing from {} to {}",
@@ -358,3 +440,161 @@ fn generate_network(
 
     Ok(network)
 }
+
+/// TLS only secures gRPC connections, so rejects the NTT listeners and peers
+/// that would be connected to in plaintext despite TLS being configured.
+fn check_tls_transport(
+    protocol: Protocol,
+    ntt_listen_address: Option<std::net::SocketAddr>,
+    trusted_peers: &[TrustedPeer],
+) -> Result<(), Error> {
+    if protocol == Protocol::Ntt || ntt_listen_address.is_some() {
+        return Err(Error::TlsWithNttListener);
+    }
+    if let Some(peer) = trusted_peers
+        .iter()
+        .find(|peer| peer.protocol == Some(Protocol::Ntt))
+    {
+        return Err(Error::TlsNttTrustedPeer { addr: peer.addr });
+    }
+    Ok(())
+}
+
+/// Builds the configuration of the topology layers, resolving the addresses of
+/// the peers in the preferred list.
+pub fn layers_config(
+    config: jormungandr_lib::interfaces::LayersConfig,
+) -> Result<LayersConfig, layers::ParseError> {
+    let preferred_list_config = config.preferred_list.unwrap_or_default();
+    let preferred_list = PreferredListConfig {
+        view_max: preferred_list_config.view_max.into(),
+        peers: resolve_trusted_peers(&preferred_list_config.peers),
+    };
+    let rings = config
+        .topics_of_interest
+        .map(RingsConfig::try_from)
+        .transpose()?
+        .unwrap_or_default();
+    Ok(LayersConfig {
+        preferred_list,
+        rings,
+    })
+}
+
+/// Loads the key the node is identified with in the network.
+///
+/// The key is read from the configured node key file if there is one.
+/// Otherwise, the key is kept in the storage directory so that the node
+/// keeps its identity across restarts, generating it on the first start.
+fn load_node_key(
+    node_key_file: Option<&Path>,
+    storage: Option<&Path>,
+) -> Result<SigningKey<Ed25519>, Error> {
+    let path = match (node_key_file, storage) {
+        (Some(path), _) => return read_node_key(path),
+        (None, Some(storage)) => storage.join(NODE_KEY_FILE_NAME),
+        (None, None) => {
+            tracing::warn!(
+                "Node started without storage or node key file, the node ID will change on restart"
+            );
+            return Ok(SigningKey::generate(rand::thread_rng()));
+        }
+    };
+    if path.exists() {
+        return read_node_key(&path);
+    }
+
+    let node_key = SigningKey::generate(rand::thread_rng());
+    write_node_key(&path, &node_key).map_err(|source| Error::NodeKeyIo {
+        path: path.clone(),
+        source,
+    })?;
+    tracing::info!(
+        path = %path.display(),
+        node_id = %node_key.identifier(),
+        "generated a new node key"
+    );
+    Ok(node_key)
+}
+
+fn load_tls(config: &P2pTls) -> Result<Tls, Error> {
+    let read = |path: &PathBuf| {
+        std::fs::read(path).map_err(|source| Error::TlsIo {
+            path: path.clone(),
+            source,
+        })
+    };
+    Ok(Tls {
+        cert: read(&config.cert_file)?,
+        priv_key: read(&config.priv_key_file)?,
+        ca: read(&config.ca_file)?,
+    })
+}
+
+fn read_node_key(path: &Path) -> Result<SigningKey<Ed25519>, Error> {
+    let bech32_str = std::fs::read_to_string(path).map_err(|source| Error::NodeKeyIo {
+        path: path.to_owned(),
+        source,
+    })?;
+    Ok(SigningKey::from_bech32_str(bech32_str.trim())?)
+}
+
+fn write_node_key(path: &Path, node_key: &SigningKey<Ed25519>) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt as _;
+        // the key is secret, only the node's user may read it
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    writeln!(file, "{}", node_key.to_bech32_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_node_key_is_kept_in_storage() {
+        let storage =
+            std::env::temp_dir().join(format!("jormungandr-node-key-{}", std::process::id()));
+        let key = load_node_key(None, Some(&storage)).unwrap();
+        let reloaded = load_node_key(None, Some(&storage)).unwrap();
+        std::fs::remove_dir_all(&storage).unwrap();
+        assert_eq!(key.identifier(), reloaded.identifier());
+    }
+
+    #[test]
+    fn tls_is_not_downgraded_on_ntt() {
+        let addr = "127.0.0.1:3000".parse().unwrap();
+        let peer = |protocol| TrustedPeer {
+            addr,
+            id: None,
+            protocol,
+        };
+
+        check_tls_transport(
+            Protocol::Grpc,
+            None,
+            &[peer(None), peer(Some(Protocol::Grpc))],
+        )
+        .unwrap();
+        assert!(matches!(
+            check_tls_transport(Protocol::Ntt, None, &[]),
+            Err(Error::TlsWithNttListener)
+        ));
+        assert!(matches!(
+            check_tls_transport(Protocol::Grpc, Some(addr), &[]),
+            Err(Error::TlsWithNttListener)
+        ));
+        assert!(matches!(
+            check_tls_transport(Protocol::Grpc, None, &[peer(Some(Protocol::Ntt))]),
+            Err(Error::TlsNttTrustedPeer { .. })
+        ));
+    }
+}
@@ -9,11 +9,7 @@ use jormungandr_lib::{crypto::key::SigningKey, multiaddr};
 use std::{net::SocketAddr, str, time::Duration};
 
 /// Protocol to use for a connection.
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub enum Protocol {
-    Ntt,
-    Grpc,
-}
+pub use jormungandr_lib::interfaces::P2pProtocol as Protocol;
 
 /// represent a connection peer
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -67,9 +63,17 @@ pub struct Configuration {
     /// list of trusted addresses
     pub trusted_peers: Vec<TrustedPeer>,
 
-    /// the protocol to utilise for the p2p network
+    /// the protocol served on the listen address, and used to connect
+    /// to peers for which no protocol is configured
     pub protocol: Protocol,
 
+    /// Local socket address to accept NTT connections on in addition
+    /// to the main listener.
+    pub ntt_listen_address: Option<SocketAddr>,
+
+    /// TLS credentials securing the gRPC connections, if enabled.
+    pub tls: Option<Tls>,
+
     /// Maximum allowed number of peer connections.
     pub max_connections: usize,
 
@@ -105,12 +109,22 @@ pub struct Configuration {
     pub dns_server_address: Option<Address>,
 }
 
+/// PEM-encoded TLS credentials of the node, loaded from the files
+/// given in the configuration.
+#[derive(Clone)]
+pub struct Tls {
+    pub cert: Vec<u8>,
+    pub priv_key: Vec<u8>,
+    pub ca: Vec<u8>,
+}
+
 /// Trusted peer with DNS address resolved.
 #[derive(Clone, Hash)]
 pub struct TrustedPeer {
     pub addr: SocketAddr,
     // This will need to become compulsory if we want to check validity of keys/ids
     pub id: Option<NodeId>,
+    pub protocol: Option<Protocol>,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -128,19 +142,20 @@ impl TrustedPeer {
         Ok(TrustedPeer {
             addr,
             id: peer.id.clone().map(Into::into),
+            protocol: peer.protocol,
         })
     }
 }
 
 impl Peer {
-    pub fn new(connection: SocketAddr) -> Self {
-        Peer::with_timeout(connection, DEFAULT_TIMEOUT)
+    pub fn new(connection: SocketAddr, protocol: Protocol) -> Self {
+        Peer::with_timeout(connection, protocol, DEFAULT_TIMEOUT)
     }
 
-    pub fn with_timeout(connection: SocketAddr, timeout: Duration) -> Self {
+    pub fn with_timeout(connection: SocketAddr, protocol: Protocol, timeout: Duration) -> Self {
         Peer {
             connection,
-            protocol: Protocol::Grpc,
+            protocol,
             timeout,
         }
     }
@@ -151,10 +166,10 @@ impl Peer {
 }
 
 impl Listen {
-    pub fn new(connection: SocketAddr) -> Self {
+    pub fn new(connection: SocketAddr, protocol: Protocol) -> Self {
         Listen {
             connection,
-            protocol: Protocol::Grpc,
+            protocol,
             timeout: DEFAULT_TIMEOUT,
         }
     }
@@ -169,9 +184,35 @@ impl Configuration {
         self.public_address
     }
 
-    /// Returns the listener configuration, if the options defining it
-    /// were set.
-    pub fn listen(&self) -> Option<Listen> {
-        self.listen_address.or(self.public_address).map(Listen::new)
+    /// Returns the configuration of the listeners for which
+    /// the options defining them were set.
+    pub fn listeners(&self) -> Vec<Listen> {
+        let main = self
+            .listen_address
+            .or(self.public_address)
+            .map(|addr| Listen::new(addr, self.protocol));
+        let ntt = self
+            .ntt_listen_address
+            .map(|addr| Listen::new(addr, Protocol::Ntt));
+        main.into_iter().chain(ntt).collect()
+    }
+
+    /// Returns the protocol to connect to the peer at the given address:
+    /// the one configured for the trusted peer, if any, or the default.
+    pub fn peer_protocol(&self, addr: SocketAddr) -> Protocol {
+        self.trusted_peers
+            .iter()
+            .find(|peer| peer.addr == addr)
+            .and_then(|peer| peer.protocol)
+            .unwrap_or(self.protocol)
+    }
+
+    /// Returns the node ID pinned in the trusted peer configuration
+    /// for the peer at the given address, if any.
+    pub fn trusted_peer_id(&self, addr: SocketAddr) -> Option<NodeId> {
+        self.trusted_peers
+            .iter()
+            .find(|peer| peer.addr == addr)
+            .and_then(|peer| peer.id)
     }
 }
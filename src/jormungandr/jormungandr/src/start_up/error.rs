@@ -62,6 +62,8 @@ pub enum Error {
     Interrupted,
     #[error("Failed to create global state: {0}")]
     GlobalState(crate::network::NewGlobalStateError),
+    #[error("Failed to start the OTLP metrics exporter")]
+    OtlpMetrics(#[source] opentelemetry::metrics::MetricsError),
 }
 
 impl From<network::BootstrapError> for Error {
@@ -93,6 +95,7 @@ impl Error {
             Error::ServiceTerminatedWithError { .. } => 12,
             Error::DiagnosticError { .. } => 13,
             Error::GlobalState(..) => 14,
+            Error::OtlpMetrics(..) => 15,
         }
     }
 }
@@ -8,6 +8,7 @@ use crate::{
     settings::start::Settings,
 };
 use chain_core::packer::Codec;
+use std::path::PathBuf;
 use tracing::{span, Level};
 
 /// prepare the block storage from the given settings
@@ -21,9 +22,13 @@ pub fn prepare_storage(setting: &Settings) -> Result<Storage, Error> {
             reason: ErrorKind::BlockStorage,
         })?;
 
-        tracing::info!("storing blockchain in '{:?}'", dir);
+        tracing::info!(
+            "storing blockchain in '{:?}' with {}",
+            dir,
+            setting.storage_engine
+        );
 
-        Storage::file(dir, storage_span).map_err(Into::into)
+        Storage::file(dir, setting.storage_engine, storage_span).map_err(Into::into)
     } else {
         Storage::memory(storage_span).map_err(Into::into)
     }
@@ -152,12 +157,14 @@ pub async fn load_blockchain(
     storage: Storage,
     cache_capacity: usize,
     rewards_report_all: bool,
+    ledger_checkpoints: Option<PathBuf>,
 ) -> Result<(Blockchain, Tip), Error> {
     let blockchain = Blockchain::new(
         block0.header().hash(),
         storage,
         cache_capacity,
         rewards_report_all,
+        ledger_checkpoints,
     );
 
     let tip = match blockchain.load_from_block0(block0.clone()).await {
@@ -4,18 +4,23 @@ use chain_time::{
     Epoch,
 };
 use std::time::{Duration, SystemTime};
-use tokio::time::interval;
+use tokio::{sync::watch, time::interval};
 
-pub async fn check_last_block_time(blockchain_tip: blockchain::Tip, check_interval: Duration) {
+/// Warns periodically when no block was added to the chain for longer than
+/// the current value of `check_interval`, which can change while running.
+pub async fn check_last_block_time(
+    blockchain_tip: blockchain::Tip,
+    check_interval: watch::Receiver<Duration>,
+) {
     // those are different values, because check_interval can be big
     // (30 minutes) and the notification may remain unseen
-    let check_period = check_interval;
     let notification_period = Duration::from_secs(60);
 
     let mut interval = interval(notification_period);
 
     loop {
         interval.tick().await;
+        let check_period = *check_interval.borrow();
         let tip = blockchain_tip.get_ref().await;
         let era = tip.epoch_leadership_schedule().era();
 
@@ -78,6 +78,18 @@ impl TryFrom<&[u8]> for NodeId {
     }
 }
 
+#[cfg(test)]
+impl NodeId {
+    /// the id of a node whose key is generated from `seed`
+    pub(crate) fn from_seed(seed: u8) -> Self {
+        use chain_crypto::{Ed25519, SecretKey};
+        use rand::SeedableRng;
+
+        let key = SecretKey::<Ed25519>::generate(rand_chacha::ChaChaRng::from_seed([seed; 32]));
+        Self::from(jormungandr_lib::interfaces::NodeId::from(key.to_public()))
+    }
+}
+
 impl fmt::Display for NodeId {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.0)
@@ -76,6 +76,7 @@ impl Process {
                         },
                         TopologyMsg::DemotePeer(id) => self.topology.report_node(&id),
                         TopologyMsg::PromotePeer(id) => self.topology.promote_node(&id),
+                        TopologyMsg::SetLayers(config) => self.topology.set_layers(config),
                         TopologyMsg::View(selection, handle) => {
                             handle.reply_ok(self.topology.view(selection))
                         }
@@ -228,6 +228,38 @@ impl P2pTopology {
         }
     }
 
+    /// Replaces the layers selecting the peers of the views. The peers known
+    /// to the node are kept, except the quarantined ones which come back
+    /// with their next gossip.
+    pub fn set_layers(&mut self, config: LayersConfig) {
+        let addr = self.topology.self_profile().address();
+        let mut topology = Topology::new_with(addr, &self.key, CustomLayerBuilder::from(config));
+        topology.subscribe_topic(topic::MESSAGES);
+        topology.subscribe_topic(topic::BLOCKS);
+
+        let peers = self.topology.peers();
+        let trusted: Vec<_> = peers
+            .trusted()
+            .iter()
+            .map(|(_, profile)| profile.gossip().clone())
+            .collect();
+        let pool = peers
+            .pool()
+            .iter()
+            .map(|(_, profile)| profile.gossip().clone());
+        for gossip in pool.chain(trusted.iter().cloned()) {
+            topology.add_peer(Profile::from_gossip(gossip));
+        }
+        // the peers the node could connect to remain trusted
+        for gossip in trusted {
+            topology.promote_peer(&Profile::from_gossip(gossip).id());
+        }
+
+        self.topology = topology;
+        self.stats_counter
+            .set_peer_available_cnt(self.peer_available_cnt());
+    }
+
     /// update our gossip so that other nodes can see that we are updating
     /// it and are alive
     pub fn update_gossip(&mut self) {
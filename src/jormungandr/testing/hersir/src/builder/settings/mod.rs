@@ -180,6 +180,7 @@ impl Settings {
                 trusted_peers.push(TrustedPeer {
                     address: trusted_peer.config.p2p.connection.public_address.clone(),
                     id: Some(id),
+                    protocol: None,
                 })
             }
 
@@ -363,6 +363,7 @@ impl SpawnParams {
             .as_ref()
             .map(|dir| PersistentLog {
                 dir: dir.to_path_buf(),
+                replay_window: None,
             });
     }
 }
@@ -238,5 +238,9 @@ fn payload_type_to_string(payload_type: PayloadType) -> &'static str {
     match payload_type {
         PayloadType::Public => "public",
         PayloadType::Private => "private",
+        PayloadType::RankedChoice => "ranked_choice",
+        PayloadType::Approval => "approval",
+        PayloadType::Quadratic => "quadratic",
+        PayloadType::PrivateQuadratic => "private_quadratic",
     }
 }
@@ -76,6 +76,9 @@ impl Default for NodeConfigBuilder {
                     network_stuck_check: None,
                     whitelist: None,
                     dns_server_address: None,
+                    protocol: None,
+                    ntt_listen: None,
+                    tls: None,
                 },
 
                 policy: Some(Policy {
@@ -264,6 +264,9 @@ impl ExplorerVerifier {
                                             //Not implemented because of the bug EAS-238
                                            return Err(VerifierError::Unimplemented);
                                         }
+                                        AllBlocksTipBlocksEdgesNodeTransactionsEdgesNodeCertificate::MultisigRegistration(_) => {
+                                           return Err(VerifierError::Unimplemented);
+                                        }
                                     }
                             }
                         }
@@ -836,6 +839,22 @@ impl ExplorerVerifier {
                 explorer_cert.payload_type,
                 expPayloadType::PRIVATE
             )),
+            PayloadType::RankedChoice => assert!(matches!(
+                explorer_cert.payload_type,
+                expPayloadType::RANKED_CHOICE
+            )),
+            PayloadType::Approval => assert!(matches!(
+                explorer_cert.payload_type,
+                expPayloadType::APPROVAL
+            )),
+            PayloadType::Quadratic => assert!(matches!(
+                explorer_cert.payload_type,
+                expPayloadType::QUADRATIC
+            )),
+            PayloadType::PrivateQuadratic => assert!(matches!(
+                explorer_cert.payload_type,
+                expPayloadType::PRIVATE_QUADRATIC
+            )),
         }
 
         assert_eq!(
@@ -53,6 +53,22 @@ impl ExplorerVerifier {
                             vote_plan_status.payload,
                             vote::PayloadType::Private
                         )),
+                        all_vote_plans::PayloadType::RANKED_CHOICE => assert!(matches!(
+                            vote_plan_status.payload,
+                            vote::PayloadType::RankedChoice
+                        )),
+                        all_vote_plans::PayloadType::APPROVAL => assert!(matches!(
+                            vote_plan_status.payload,
+                            vote::PayloadType::Approval
+                        )),
+                        all_vote_plans::PayloadType::QUADRATIC => assert!(matches!(
+                            vote_plan_status.payload,
+                            vote::PayloadType::Quadratic
+                        )),
+                        all_vote_plans::PayloadType::PRIVATE_QUADRATIC => assert!(matches!(
+                            vote_plan_status.payload,
+                            vote::PayloadType::PrivateQuadratic
+                        )),
                         all_vote_plans::PayloadType::Other(_) => panic!("Wrong payload type"),
                     }
 
@@ -77,7 +93,9 @@ impl ExplorerVerifier {
                             explorer_proposal.options.end as u8
                         );
                         match &vote_proposal_status.tally {
-                            Tally::Public { result } => {
+                            Tally::Public { result }
+                            | Tally::Approval { result }
+                            | Tally::Quadratic { result } => {
                                 assert!(explorer_proposal.tally.is_some());
                                 if let TallyPublicStatus(explorer_tally_status) =
                                     explorer_proposal.tally.as_ref().unwrap()
@@ -109,6 +127,11 @@ impl ExplorerVerifier {
                                     panic!("Wrong tally status. Expected Public")
                                 }
                             }
+                            // the explorer only counts the first preferences of the ballots
+                            Tally::RankedChoice { .. } => assert!(matches!(
+                                explorer_proposal.tally,
+                                Some(TallyPublicStatus(_))
+                            )),
                             Tally::Private { state } => {
                                 assert!(explorer_proposal.tally.is_some());
                                 if let TallyPrivateStatus(explorer_tally_status) =
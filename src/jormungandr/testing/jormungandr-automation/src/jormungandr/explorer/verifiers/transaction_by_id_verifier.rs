@@ -203,6 +203,9 @@ impl ExplorerVerifier {
                     //Not implemented because of the bug EAS-238
                     Err(VerifierError::Unimplemented)
                 }
+                TransactionByIdCertificatesTransactionCertificate::MultisigRegistration(_) => {
+                    Err(VerifierError::Unimplemented)
+                }
             }
         }
     }
@@ -489,6 +492,22 @@ impl ExplorerVerifier {
                 explorer_cert.payload_type,
                 expPayloadType::PRIVATE
             )),
+            PayloadType::RankedChoice => assert!(matches!(
+                explorer_cert.payload_type,
+                expPayloadType::RANKED_CHOICE
+            )),
+            PayloadType::Approval => assert!(matches!(
+                explorer_cert.payload_type,
+                expPayloadType::APPROVAL
+            )),
+            PayloadType::Quadratic => assert!(matches!(
+                explorer_cert.payload_type,
+                expPayloadType::QUADRATIC
+            )),
+            PayloadType::PrivateQuadratic => assert!(matches!(
+                explorer_cert.payload_type,
+                expPayloadType::PRIVATE_QUADRATIC
+            )),
         }
 
         assert_eq!(
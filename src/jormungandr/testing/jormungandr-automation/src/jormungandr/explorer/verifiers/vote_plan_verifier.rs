@@ -47,6 +47,22 @@ impl ExplorerVerifier {
                 vote_plan_status.payload,
                 vote::PayloadType::Private
             )),
+            vote_plan_by_id::PayloadType::RANKED_CHOICE => assert!(matches!(
+                vote_plan_status.payload,
+                vote::PayloadType::RankedChoice
+            )),
+            vote_plan_by_id::PayloadType::APPROVAL => assert!(matches!(
+                vote_plan_status.payload,
+                vote::PayloadType::Approval
+            )),
+            vote_plan_by_id::PayloadType::QUADRATIC => assert!(matches!(
+                vote_plan_status.payload,
+                vote::PayloadType::Quadratic
+            )),
+            vote_plan_by_id::PayloadType::PRIVATE_QUADRATIC => assert!(matches!(
+                vote_plan_status.payload,
+                vote::PayloadType::PrivateQuadratic
+            )),
             vote_plan_by_id::PayloadType::Other(_) => panic!("Wrong payload type"),
         }
 
@@ -71,7 +87,9 @@ impl ExplorerVerifier {
                 explorer_proposal.options.end as u8
             );
             match &vote_proposal_status.tally {
-                Tally::Public { result } => {
+                Tally::Public { result }
+                | Tally::Approval { result }
+                | Tally::Quadratic { result } => {
                     assert!(explorer_proposal.tally.is_some());
                     if let TallyPublicStatus(explorer_tally_status) =
                         explorer_proposal.tally.unwrap()
@@ -94,6 +112,11 @@ impl ExplorerVerifier {
                         panic!("Wrong tally status. Expected Public")
                     }
                 }
+                // the explorer only counts the first preferences of the ballots
+                Tally::RankedChoice { .. } => assert!(matches!(
+                    explorer_proposal.tally,
+                    Some(TallyPublicStatus(_))
+                )),
                 Tally::Private { state } => {
                     assert!(explorer_proposal.tally.is_some());
                     if let TallyPrivateStatus(explorer_tally_status) =
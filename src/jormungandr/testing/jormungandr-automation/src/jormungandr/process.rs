@@ -297,6 +297,7 @@ impl JormungandrProcess {
         TrustedPeer {
             address: self.p2p_public_address.clone(),
             id: None,
+            protocol: None,
         }
     }
 
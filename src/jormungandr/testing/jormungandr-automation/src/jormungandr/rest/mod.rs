@@ -10,9 +10,9 @@ use chain_impl_mockchain::{
 use jormungandr_lib::{
     crypto::{account::Identifier, hash::Hash},
     interfaces::{
-        AccountState, AccountVotes, Address, EpochRewardsInfo, FragmentLog, FragmentStatus,
-        FragmentsProcessingSummary, LeadershipLog, NodeStatsDto, PeerRecord, PeerStats,
-        SettingsDto, StakeDistributionDto, UpdateProposalStateDef, Value, VotePlanId,
+        AccountState, AccountVotes, Address, ConfigReloadReport, EpochRewardsInfo, FragmentLog,
+        FragmentStatus, FragmentsProcessingSummary, LeadershipLog, NodeStatsDto, PeerRecord,
+        PeerStats, SettingsDto, StakeDistributionDto, UpdateProposalStateDef, Value, VotePlanId,
         VotePlanStatus,
     },
 };
@@ -269,6 +269,11 @@ impl JormungandrRest {
             .map_err(RestError::CannotDeserialize)
     }
 
+    pub fn reload_config(&self) -> Result<ConfigReloadReport, RestError> {
+        serde_json::from_str(&self.raw().reload_config()?.text()?)
+            .map_err(RestError::CannotDeserialize)
+    }
+
     pub fn set_origin<S: Into<String>>(&mut self, origin: S) {
         self.inner.set_origin(origin);
     }
@@ -318,6 +318,12 @@ impl RawRest {
             .send()
     }
 
+    pub fn reload_config(&self) -> Result<Response, reqwest::Error> {
+        self.client
+            .post(self.path(ApiVersion::V1, "node/config/reload"))
+            .send()
+    }
+
     pub fn vote_plan_statuses(&self) -> Result<Response, reqwest::Error> {
         self.get("vote/active/plans")
     }
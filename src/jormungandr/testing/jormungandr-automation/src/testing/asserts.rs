@@ -22,7 +22,12 @@ impl VotePlanStatusAssert for Vec<VotePlanStatus> {
             .tally;
 
         match tally {
-            Tally::Public { result } => assert_eq!(expected, result.results()),
+            Tally::Public { result } | Tally::Approval { result } | Tally::Quadratic { result } => {
+                assert_eq!(expected, result.results())
+            }
+            Tally::RankedChoice { .. } => {
+                panic!("expected single choice, approval or quadratic tally")
+            }
             Tally::Private { state } => match state {
                 PrivateTallyState::Encrypted { .. } => {
                     panic!("expected decrypted private tally state")
@@ -18,6 +18,10 @@ impl VotePlanExtension for VotePlan {
         let payload = match self.payload_type() {
             PayloadType::Public => "public",
             PayloadType::Private => "private",
+            PayloadType::RankedChoice => "ranked_choice",
+            PayloadType::Approval => "approval",
+            PayloadType::Quadratic => "quadratic",
+            PayloadType::PrivateQuadratic => "private_quadratic",
         };
 
         data["payload_type"] = json::JsonValue::String(payload.to_owned());
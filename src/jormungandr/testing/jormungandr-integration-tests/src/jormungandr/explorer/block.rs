@@ -65,6 +65,9 @@ pub fn explorer_block_test() {
             pool_max_entries: 1_000_000usize.into(),
             log_max_entries: 1_000_000usize.into(),
             persistent_log: None,
+            fragment_selection: Default::default(),
+            account_lane_max_entries: None,
+            eviction: Default::default(),
         }))
         .build()
         .start_node(temp_dir)
@@ -245,6 +248,9 @@ pub fn explorer_last_block_test() {
             pool_max_entries: 1_000_000usize.into(),
             log_max_entries: 1_000_000usize.into(),
             persistent_log: None,
+            fragment_selection: Default::default(),
+            account_lane_max_entries: None,
+            eviction: Default::default(),
         }))
         .build()
         .start_node(temp_dir)
@@ -349,6 +355,9 @@ pub fn explorer_all_blocks_test() {
             pool_max_entries: 1_000_000usize.into(),
             log_max_entries: 1_000_000usize.into(),
             persistent_log: None,
+            fragment_selection: Default::default(),
+            account_lane_max_entries: None,
+            eviction: Default::default(),
         }))
         .build();
 
@@ -463,6 +472,9 @@ pub fn explorer_block_by_chain_length_test() {
             pool_max_entries: 1_000_000usize.into(),
             log_max_entries: 1_000_000usize.into(),
             persistent_log: None,
+            fragment_selection: Default::default(),
+            account_lane_max_entries: None,
+            eviction: Default::default(),
         }))
         .build()
         .start_node(temp_dir)
@@ -97,6 +97,7 @@ pub mod server {
                 .parse()
                 .unwrap(),
             id: None,
+            protocol: None,
         };
         let node_config = NodeConfigBuilder::default().with_trusted_peers(vec![trusted_peer]);
         let test_context = SingleNodeTestBootstrapper::default()
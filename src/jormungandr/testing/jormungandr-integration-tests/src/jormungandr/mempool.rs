@@ -1,12 +1,13 @@
 use crate::startup::SingleNodeTestBootstrapper;
 use assert_fs::{fixture::PathChild, TempDir};
-use chain_core::property::FromStr;
+use chain_core::property::{BlockDate as _, FromStr};
 use chain_crypto::Ed25519;
 use chain_impl_mockchain::{
     block::BlockDate,
     chaintypes::ConsensusVersion,
     fee::LinearFee,
     tokens::{identifier::TokenIdentifier, minting_policy::MintingPolicy},
+    vote::Choice,
 };
 use hersir::{
     builder::{NetworkBuilder, Node, Topology},
@@ -14,17 +15,18 @@ use hersir::{
 };
 use jormungandr_automation::{
     jormungandr::{Block0ConfigurationBuilder, FragmentNode, MemPoolCheck, NodeConfigBuilder},
-    testing::{keys::create_new_key_pair, time},
+    testing::{keys::create_new_key_pair, time, VotePlanBuilder},
 };
 use jormungandr_lib::interfaces::{
-    BlockDate as BlockDateDto, InitialToken, InitialUTxO, Mempool, PersistentLog, SlotDuration,
+    BlockDate as BlockDateDto, Initial, InitialToken, InitialUTxO, Mempool, PersistentLog,
+    SlotDuration,
 };
 use loki::{AdversaryFragmentSender, AdversaryFragmentSenderSetup};
 use mjolnir::generators::FragmentGenerator;
 use std::{fs::metadata, path::Path, thread::sleep, time::Duration};
 use thor::{
-    Block0ConfigurationBuilderExtension, BlockDateGenerator, FragmentBuilder, FragmentExporter,
-    FragmentSender, FragmentSenderSetup, FragmentVerifier, PersistentLogViewer,
+    vote_plan_cert, Block0ConfigurationBuilderExtension, BlockDateGenerator, FragmentBuilder,
+    FragmentExporter, FragmentSender, FragmentSenderSetup, FragmentVerifier, PersistentLogViewer,
 };
 
 #[test]
@@ -60,7 +62,11 @@ pub fn dump_send_correct_fragments() {
             log_max_entries: 1_000_000usize.into(),
             persistent_log: Some(PersistentLog {
                 dir: persistent_log_path.path().to_path_buf(),
+                replay_window: None,
             }),
+            fragment_selection: Default::default(),
+            account_lane_max_entries: None,
+            eviction: Default::default(),
         }))
         .build()
         .start_node(temp_dir)
@@ -124,7 +130,11 @@ pub fn dump_send_invalid_fragments() {
             log_max_entries: 1_000_000usize.into(),
             persistent_log: Some(PersistentLog {
                 dir: persistent_log_path.path().to_path_buf(),
+                replay_window: None,
             }),
+            fragment_selection: Default::default(),
+            account_lane_max_entries: None,
+            eviction: Default::default(),
         }))
         .build()
         .start_node(temp_dir)
@@ -176,7 +186,11 @@ pub fn non_existing_folder() {
             log_max_entries: 1_000_000usize.into(),
             persistent_log: Some(PersistentLog {
                 dir: persistent_log_path.path().to_path_buf(),
+                replay_window: None,
             }),
+            fragment_selection: Default::default(),
+            account_lane_max_entries: None,
+            eviction: Default::default(),
         }))
         .build()
         .start_node(temp_dir)
@@ -202,7 +216,11 @@ pub fn invalid_folder() {
         log_max_entries: 1_000_000usize.into(),
         persistent_log: Some(PersistentLog {
             dir: persistent_log_path.path().to_path_buf(),
+            replay_window: None,
         }),
+        fragment_selection: Default::default(),
+        account_lane_max_entries: None,
+        eviction: Default::default(),
     });
 
     SingleNodeTestBootstrapper::default()
@@ -236,7 +254,11 @@ pub fn fragment_which_reached_mempool_should_be_persisted() {
             log_max_entries: 1000usize.into(),
             persistent_log: Some(PersistentLog {
                 dir: persistent_log_path.path().to_path_buf(),
+                replay_window: None,
             }),
+            fragment_selection: Default::default(),
+            account_lane_max_entries: None,
+            eviction: Default::default(),
         }))
         .build()
         .start_node(temp_dir)
@@ -279,7 +301,11 @@ pub fn fragment_which_is_not_in_fragment_log_should_be_persisted() {
             log_max_entries: 1usize.into(),
             persistent_log: Some(PersistentLog {
                 dir: persistent_log_path.path().to_path_buf(),
+                replay_window: None,
             }),
+            fragment_selection: Default::default(),
+            account_lane_max_entries: None,
+            eviction: Default::default(),
         }))
         .build()
         .start_node(temp_dir)
@@ -322,7 +348,11 @@ pub fn pending_fragment_should_be_persisted() {
             log_max_entries: 10usize.into(),
             persistent_log: Some(PersistentLog {
                 dir: persistent_log_path.path().to_path_buf(),
+                replay_window: None,
             }),
+            fragment_selection: Default::default(),
+            account_lane_max_entries: None,
+            eviction: Default::default(),
         }))
         .build()
         .start_node(temp_dir)
@@ -381,7 +411,11 @@ pub fn node_should_pickup_log_after_restart() {
             log_max_entries: 1000usize.into(),
             persistent_log: Some(PersistentLog {
                 dir: persistent_log_path.path().to_path_buf(),
+                replay_window: None,
             }),
+            fragment_selection: Default::default(),
+            account_lane_max_entries: None,
+            eviction: Default::default(),
         }))
         .as_bft_leader()
         .build();
@@ -424,6 +458,81 @@ pub fn node_should_pickup_log_after_restart() {
     assert_eq!(20, persistent_log_viewer.get_all().len());
 }
 
+#[test]
+pub fn pending_vote_cast_should_be_replayed_after_restart() {
+    let mut temp_dir = TempDir::new().unwrap();
+    let persistent_log_path = temp_dir.child("persistent_log");
+    let mut alice = thor::Wallet::default();
+
+    let vote_plan = VotePlanBuilder::new()
+        .proposals_count(1)
+        .vote_start(BlockDate::from_epoch_slot_id(0, 0))
+        .tally_start(BlockDate::from_epoch_slot_id(1, 0))
+        .tally_end(BlockDate::from_epoch_slot_id(2, 0))
+        .public()
+        .build();
+    let vote_plan_cert =
+        Initial::Cert(vote_plan_cert(&alice, BlockDate::first().next_epoch(), &vote_plan).into());
+
+    // the leader creates a block every minute: the vote cast stays pending
+    let config = Block0ConfigurationBuilder::default()
+        .with_utxos(vec![alice.to_initial_fund(1_000_000)])
+        .with_token(InitialToken {
+            token_id: vote_plan.voting_token().clone().into(),
+            policy: MintingPolicy::new().into(),
+            to: vec![alice.to_initial_token(1_000)],
+        })
+        .with_committees(&[alice.to_committee_id()])
+        .with_slots_per_epoch(5.try_into().unwrap())
+        .with_slot_duration(60.try_into().unwrap())
+        .with_certs(vec![vote_plan_cert]);
+
+    let test_context = SingleNodeTestBootstrapper::default()
+        .as_bft_leader()
+        .with_block0_config(config)
+        .with_node_config(NodeConfigBuilder::default().with_mempool(Mempool {
+            pool_max_entries: 10usize.into(),
+            log_max_entries: 10usize.into(),
+            persistent_log: Some(PersistentLog {
+                dir: persistent_log_path.path().to_path_buf(),
+                replay_window: Some(Duration::from_secs(3600).into()),
+            }),
+            fragment_selection: Default::default(),
+            account_lane_max_entries: None,
+            eviction: Default::default(),
+        }))
+        .build();
+
+    let mut jormungandr = test_context.start_node(temp_dir).unwrap();
+
+    let fragment_sender = FragmentSender::try_from_with_setup(
+        &jormungandr,
+        BlockDate::first().next_epoch(),
+        FragmentSenderSetup::no_verify(),
+    )
+    .unwrap();
+    let check = fragment_sender
+        .send_vote_cast(&mut alice, &vote_plan, 0, &Choice::new(1), &jormungandr)
+        .unwrap();
+
+    sleep(Duration::from_secs(1));
+    assert!(jormungandr.rest().fragment_logs().unwrap()[check.fragment_id()].is_pending());
+
+    temp_dir = jormungandr.steal_temp_dir().unwrap().try_into().unwrap();
+    jormungandr.stop();
+    let jormungandr = test_context.start_node(temp_dir).unwrap();
+
+    sleep(Duration::from_secs(1));
+    let fragment_logs = jormungandr.rest().fragment_logs().unwrap();
+    assert!(
+        fragment_logs
+            .get(check.fragment_id())
+            .map_or(false, |log| log.is_pending()),
+        "the pending vote cast was not replayed: {:?}",
+        fragment_logs
+    );
+}
+
 #[test]
 /// Verifies `tx_pending` and `mempool_total_size` metrics reported by the node
 fn pending_transaction_stats() {
@@ -446,6 +555,9 @@ fn pending_transaction_stats() {
                     pool_max_entries: mempool_max_entries.into(),
                     log_max_entries: mempool_max_entries.into(),
                     persistent_log: None,
+                    fragment_selection: Default::default(),
+                    account_lane_max_entries: None,
+                    eviction: Default::default(),
                 })
                 .with_log_level("debug".to_string()),
         )
@@ -0,0 +1,80 @@
+use crate::startup::SingleNodeTestBootstrapper;
+use assert_fs::{fixture::PathChild, TempDir};
+use jormungandr_automation::jormungandr::{Block0ConfigurationBuilder, JormungandrProcess};
+use reqwest::StatusCode;
+use serde_yaml::{Mapping, Value};
+use std::path::{Path, PathBuf};
+
+fn start_node() -> (JormungandrProcess, PathBuf) {
+    let temp_dir = TempDir::new().unwrap();
+    let node_config_path = temp_dir.child("node_config.yaml").path().to_path_buf();
+    let jormungandr = SingleNodeTestBootstrapper::default()
+        .with_block0_config(Block0ConfigurationBuilder::default())
+        .as_bft_leader()
+        .build()
+        .start_node(temp_dir)
+        .unwrap();
+    (jormungandr, node_config_path)
+}
+
+/// Sets `value` at the dotted `field` path of the node configuration file.
+fn set_node_config_field<T: serde::Serialize>(path: &Path, field: &str, value: T) {
+    let mut config: Value = serde_yaml::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+    let mut names: Vec<&str> = field.split('.').collect();
+    let name = names.pop().unwrap();
+    let section = names.into_iter().fold(&mut config, |section, name| {
+        let section = section_mut(section);
+        let key = Value::from(name);
+        if !section.contains_key(&key) {
+            section.insert(key.clone(), Value::Null);
+        }
+        section.get_mut(&key).unwrap()
+    });
+    section_mut(section).insert(Value::from(name), serde_yaml::to_value(value).unwrap());
+    std::fs::write(path, serde_yaml::to_string(&config).unwrap()).unwrap();
+}
+
+fn section_mut(value: &mut Value) -> &mut Mapping {
+    if !value.is_mapping() {
+        *value = Value::Mapping(Mapping::new());
+    }
+    value.as_mapping_mut().unwrap()
+}
+
+#[test]
+pub fn reload_applies_the_changed_mempool_limits() {
+    let (jormungandr, node_config_path) = start_node();
+
+    set_node_config_field(&node_config_path, "mempool.pool_max_entries", 5);
+    set_node_config_field(&node_config_path, "block_hard_deadline", 123);
+
+    let report = jormungandr.rest().reload_config().unwrap();
+    assert_eq!(report.applied, vec!["mempool.pool_max_entries"]);
+    assert_eq!(report.restart_required, vec!["block_hard_deadline"]);
+    assert!(report.overridden.is_empty());
+
+    // the applied changes are now part of the running configuration
+    let report = jormungandr.rest().reload_config().unwrap();
+    assert!(report.applied.is_empty());
+    assert_eq!(report.restart_required, vec!["block_hard_deadline"]);
+}
+
+#[test]
+pub fn reload_rejects_a_config_the_node_cannot_run_with() {
+    let (jormungandr, node_config_path) = start_node();
+    let running_config = std::fs::read_to_string(&node_config_path).unwrap();
+
+    set_node_config_field(&node_config_path, "mempool.pool_max_entries", 0);
+    let response = jormungandr.rest().raw().reload_config().unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    std::fs::write(&node_config_path, "mempool: [").unwrap();
+    let response = jormungandr.rest().raw().reload_config().unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // nothing was applied: the node still runs with the original file
+    std::fs::write(&node_config_path, running_config).unwrap();
+    let report = jormungandr.rest().reload_config().unwrap();
+    assert!(report.applied.is_empty());
+    assert!(report.restart_required.is_empty());
+}
@@ -1,3 +1,4 @@
+pub mod config_reload;
 pub mod fragments;
 pub mod utils;
 pub mod votes;
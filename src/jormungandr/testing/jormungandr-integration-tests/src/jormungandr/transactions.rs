@@ -27,6 +27,9 @@ pub fn accounts_funds_are_updated_after_transaction() {
             pool_max_entries: 1_000_000usize.into(),
             log_max_entries: 1_000_000usize.into(),
             persistent_log: None,
+            fragment_selection: Default::default(),
+            account_lane_max_entries: None,
+            eviction: Default::default(),
         }),
     )
     .unwrap();
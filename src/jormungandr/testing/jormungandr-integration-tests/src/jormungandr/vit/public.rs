@@ -319,7 +319,12 @@ fn assert_first_proposal_has_votes(stake: u64, vote_plan_statuses: Vec<VotePlanS
             assert_eq!(*results.get(1).unwrap(), stake);
             assert_eq!(*results.get(2).unwrap(), 0);
         }
-        Tally::Private { .. } => unimplemented!("Private tally testing is not implemented"),
+        Tally::Private { .. }
+        | Tally::RankedChoice { .. }
+        | Tally::Approval { .. }
+        | Tally::Quadratic { .. } => {
+            unimplemented!("Only public tally testing is implemented")
+        }
     }
 }
 
@@ -99,6 +99,7 @@ pub fn node_trust_itself() {
     let peer = TrustedPeer {
         address: config.connection.public_address,
         id: None,
+        protocol: None,
     };
     network_controller
         .expect_spawn_failed(
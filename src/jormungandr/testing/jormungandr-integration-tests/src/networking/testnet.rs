@@ -90,6 +90,7 @@ impl TestnetConfig {
                         .parse()
                         .expect("cannot parse trusted peer id"),
                 ),
+                protocol: None,
             });
         }
         trusted_peers
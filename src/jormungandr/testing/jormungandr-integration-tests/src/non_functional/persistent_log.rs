@@ -33,7 +33,11 @@ pub fn persistent_log_load_test() {
             log_max_entries: 1_000_000usize.into(),
             persistent_log: Some(PersistentLog {
                 dir: persistent_log_path.path().to_path_buf(),
+                replay_window: None,
             }),
+            fragment_selection: Default::default(),
+            account_lane_max_entries: None,
+            eviction: Default::default(),
         }))
         .as_bft_leader()
         .build()
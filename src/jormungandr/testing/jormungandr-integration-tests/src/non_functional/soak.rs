@@ -29,6 +29,9 @@ pub fn test_blocks_are_being_created_for_7_hours() {
             pool_max_entries: 1_000_000usize.into(),
             log_max_entries: 1_000_000usize.into(),
             persistent_log: None,
+            fragment_selection: Default::default(),
+            account_lane_max_entries: None,
+            eviction: Default::default(),
         }),
     )
     .unwrap();
@@ -323,6 +323,9 @@ pub fn test_transactions_with_long_ttl_processing_speed() {
             pool_max_entries: N_TRANSACTIONS.into(),
             log_max_entries: N_TRANSACTIONS.into(),
             persistent_log: None,
+            fragment_selection: Default::default(),
+            account_lane_max_entries: None,
+            eviction: Default::default(),
         }),
     )
     .unwrap();
@@ -108,7 +108,11 @@ impl AdversaryNode {
             IpAddr::V6(ip) => address.push(Protocol::Ip6(ip)),
         }
         address.push(Protocol::Tcp(self.p2p_public_addr().port()));
-        TrustedPeer { address, id: None }
+        TrustedPeer {
+            address,
+            id: None,
+            protocol: None,
+        }
     }
 
     pub fn steal_temp_dir(&mut self) -> Option<TestingDirectory> {
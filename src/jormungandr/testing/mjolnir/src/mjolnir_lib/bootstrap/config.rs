@@ -70,6 +70,7 @@ impl ClientLoadConfig {
         TrustedPeer {
             address: self.address.parse().unwrap(),
             id: None,
+            protocol: None,
         }
     }
 
@@ -68,11 +68,14 @@ impl<'a, S: SyncNode + Send> AdversaryVoteCastsGenerator<'a, S> {
         let fees = self.fragment_sender.fees();
 
         match self.voting_privacy {
-            PayloadType::Public => {
+            PayloadType::Public
+            | PayloadType::RankedChoice
+            | PayloadType::Approval
+            | PayloadType::Quadratic => {
                 FragmentBuilder::new(&block0_hash, &fees, self.expiry_generator.block_date())
                     .public_vote_cast(&self.voter, &vote_plan, 0, &Choice::new(0))
             }
-            PayloadType::Private => {
+            PayloadType::Private | PayloadType::PrivateQuadratic => {
                 FragmentBuilder::new(&block0_hash, &fees, self.expiry_generator.block_date())
                     .private_vote_cast(&self.voter, &vote_plan, 0, &Choice::new(0))
             }
@@ -89,11 +92,14 @@ impl<'a, S: SyncNode + Send> AdversaryVoteCastsGenerator<'a, S> {
         let fees = self.fragment_sender.fees();
 
         match self.voting_privacy {
-            PayloadType::Public => {
+            PayloadType::Public
+            | PayloadType::RankedChoice
+            | PayloadType::Approval
+            | PayloadType::Quadratic => {
                 FragmentBuilder::new(&block0_hash, &fees, self.expiry_generator.block_date())
                     .public_vote_cast(&self.voter, vote_plan, 255, &Choice::new(0))
             }
-            PayloadType::Private => {
+            PayloadType::Private | PayloadType::PrivateQuadratic => {
                 FragmentBuilder::new(&block0_hash, &fees, self.expiry_generator.block_date())
                     .private_vote_cast(&self.voter, vote_plan, 255, &Choice::new(0))
             }
@@ -106,11 +112,14 @@ impl<'a, S: SyncNode + Send> AdversaryVoteCastsGenerator<'a, S> {
         let fees = self.fragment_sender.fees();
 
         match self.voting_privacy {
-            PayloadType::Public => {
+            PayloadType::Public
+            | PayloadType::RankedChoice
+            | PayloadType::Approval
+            | PayloadType::Quadratic => {
                 FragmentBuilder::new(&block0_hash, &fees, self.expiry_generator.block_date())
                     .private_vote_cast(&self.voter, vote_plan, 0, &Choice::new(0))
             }
-            PayloadType::Private => {
+            PayloadType::Private | PayloadType::PrivateQuadratic => {
                 FragmentBuilder::new(&block0_hash, &fees, self.expiry_generator.block_date())
                     .public_vote_cast(&self.voter, vote_plan, 0, &Choice::new(0))
             }
@@ -124,11 +133,14 @@ impl<'a, S: SyncNode + Send> AdversaryVoteCastsGenerator<'a, S> {
         let fees = self.fragment_sender.fees();
 
         match self.voting_privacy {
-            PayloadType::Public => {
+            PayloadType::Public
+            | PayloadType::RankedChoice
+            | PayloadType::Approval
+            | PayloadType::Quadratic => {
                 FragmentBuilder::new(&block0_hash, &fees, self.expiry_generator.block_date())
                     .public_vote_cast(&self.voter, vote_plan, 0, &Choice::new(options))
             }
-            PayloadType::Private => {
+            PayloadType::Private | PayloadType::PrivateQuadratic => {
                 FragmentBuilder::new(&block0_hash, &fees, self.expiry_generator.block_date())
                     .private_vote_cast(&self.voter, vote_plan, 0, &Choice::new(options))
             }
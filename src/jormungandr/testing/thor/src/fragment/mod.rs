@@ -276,9 +276,63 @@ impl FragmentBuilder {
             PayloadType::Private => {
                 self.private_vote_cast(wallet, vote_plan, proposal_index, choice)
             }
+            PayloadType::RankedChoice | PayloadType::Approval => {
+                self.choices_vote_cast(wallet, vote_plan, proposal_index, &[*choice])
+            }
+            // a single credit, i.e. a vote of weight one
+            PayloadType::Quadratic => {
+                self.quadratic_vote_cast(wallet, vote_plan, proposal_index, choice, 1)
+            }
+            PayloadType::PrivateQuadratic => {
+                panic!("private quadratic votes need the credits already spent by the voter")
+            }
         }
     }
 
+    /// allocate `credits` to the `choice` in a public quadratic vote plan
+    pub fn quadratic_vote_cast(
+        &self,
+        wallet: &Wallet,
+        vote_plan: &VotePlan,
+        proposal_index: u8,
+        choice: &Choice,
+        credits: u64,
+    ) -> Fragment {
+        let inner_wallet = wallet.clone().into();
+        let vote_cast = VoteCast::new(
+            vote_plan.to_id(),
+            proposal_index,
+            Payload::quadratic(*choice, credits),
+        );
+        self.fragment_factory
+            .vote_cast(self.valid_until, &inner_wallet, vote_cast)
+    }
+
+    /// cast a ranked choice (most preferred first) or an approval ballot,
+    /// depending on the payload type of the vote plan
+    pub fn choices_vote_cast(
+        &self,
+        wallet: &Wallet,
+        vote_plan: &VotePlan,
+        proposal_index: u8,
+        choices: &[Choice],
+    ) -> Fragment {
+        let payload = match vote_plan.payload_type() {
+            PayloadType::RankedChoice => Payload::ranked_choice(choices.to_vec()),
+            PayloadType::Approval => Payload::approval(choices.to_vec()),
+            PayloadType::Public
+            | PayloadType::Private
+            | PayloadType::Quadratic
+            | PayloadType::PrivateQuadratic => {
+                panic!("this vote plan expects a single choice")
+            }
+        };
+        let inner_wallet = wallet.clone().into();
+        let vote_cast = VoteCast::new(vote_plan.to_id(), proposal_index, payload);
+        self.fragment_factory
+            .vote_cast(self.valid_until, &inner_wallet, vote_cast)
+    }
+
     pub fn public_vote_cast(
         &self,
         wallet: &Wallet,
@@ -301,8 +355,7 @@ impl FragmentBuilder {
     ) -> Fragment {
         let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
 
-        let election_key =
-            chain_vote::ElectionPublicKey::from_participants(vote_plan.committee_public_keys());
+        let election_key = vote_plan.election_public_key();
 
         let options = vote_plan
             .proposals()
@@ -424,6 +424,14 @@ impl<'a, S: SyncNode + Send> FragmentSender<'a, S> {
             chain_impl_mockchain::vote::PayloadType::Private => {
                 builder.private_vote_cast(from, vote_plan, proposal_index, choice)
             }
+            chain_impl_mockchain::vote::PayloadType::RankedChoice
+            | chain_impl_mockchain::vote::PayloadType::Approval => {
+                builder.choices_vote_cast(from, vote_plan, proposal_index, &[*choice])
+            }
+            chain_impl_mockchain::vote::PayloadType::Quadratic
+            | chain_impl_mockchain::vote::PayloadType::PrivateQuadratic => {
+                builder.vote_cast(from, vote_plan, proposal_index, choice)
+            }
         };
         self.dump_fragment_if_enabled(from, &fragment, via)?;
         self.send_fragment(from, fragment, via)
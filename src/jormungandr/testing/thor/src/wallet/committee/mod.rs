@@ -217,6 +217,7 @@ impl CommitteeDataManager {
                 .map(
                     |(tally_result, decrypt_shares)| DecryptedPrivateTallyProposal {
                         tally_result: tally_result.votes.into_boxed_slice(),
+                        member_indices: (0..decrypt_shares.len() as u8).collect(),
                         decrypt_shares: decrypt_shares.into_boxed_slice(),
                     },
                 )
@@ -47,6 +47,12 @@ impl ValidVotePlanGenerator {
                 let payload_type = match vote_plan.payload_type() {
                     chain_impl_mockchain::vote::PayloadType::Public => "public",
                     chain_impl_mockchain::vote::PayloadType::Private => "private",
+                    chain_impl_mockchain::vote::PayloadType::RankedChoice => "ranked_choice",
+                    chain_impl_mockchain::vote::PayloadType::Approval => "approval",
+                    chain_impl_mockchain::vote::PayloadType::Quadratic => "quadratic",
+                    chain_impl_mockchain::vote::PayloadType::PrivateQuadratic => {
+                        "private_quadratic"
+                    }
                 };
 
                 Voteplan {
@@ -1,6 +1,8 @@
 use crate::Vote;
 use chain_impl_mockchain::vote::Choice;
-use chain_impl_mockchain::vote::{PayloadType, TallyResult as TallyResultLib, Weight};
+use chain_impl_mockchain::vote::{
+    PayloadType, RankedChoiceResult, TallyResult as TallyResultLib, Weight,
+};
 use jormungandr_lib::crypto::hash::Hash;
 use jormungandr_lib::interfaces::Block0Configuration;
 use jormungandr_lib::interfaces::Initial;
@@ -45,6 +47,13 @@ impl VotePlanStatusProvider for Block0Configuration {
                                 result: chain_result.into(),
                             }
                         }
+                        Tally::Quadratic { result } => {
+                            let mut chain_result: TallyResultLib = result.clone().into();
+                            chain_result.add_vote(vote.choice(), vote.weight).unwrap();
+                            Tally::Quadratic {
+                                result: chain_result.into(),
+                            }
+                        }
                         Tally::Private { state } => {
                             if let PrivateTallyState::Decrypted { result } = state {
                                 let mut chain_result: TallyResultLib = result.clone().into();
@@ -58,6 +67,9 @@ impl VotePlanStatusProvider for Block0Configuration {
                                 panic!("private tally should be already decrypted")
                             }
                         }
+                        Tally::RankedChoice { .. } | Tally::Approval { .. } => {
+                            unimplemented!("multiple choices ballots are not supported")
+                        }
                     };
                     proposal.tally = new_tally;
                     proposal.votes_cast += 1;
@@ -82,6 +94,7 @@ impl VotePlanStatusProvider for Block0Configuration {
                         payload: v.payload_type(),
                         voting_token: v.voting_token().clone().into(),
                         committee_member_keys: v.committee_public_keys().into(),
+                        committee_threshold: v.committee_threshold(),
                         proposals: v
                             .proposals()
                             .iter()
@@ -94,11 +107,23 @@ impl VotePlanStatusProvider for Block0Configuration {
                                     PayloadType::Public => Tally::Public {
                                         result: TallyResultLib::new(p.options().clone()).into(),
                                     },
-                                    PayloadType::Private => Tally::Private {
-                                        state: PrivateTallyState::Decrypted {
-                                            result: TallyResultLib::new(p.options().clone()).into(),
-                                        },
+                                    PayloadType::Quadratic => Tally::Quadratic {
+                                        result: TallyResultLib::new(p.options().clone()).into(),
+                                    },
+                                    PayloadType::RankedChoice => Tally::RankedChoice {
+                                        result: RankedChoiceResult::new(p.options().clone()).into(),
+                                    },
+                                    PayloadType::Approval => Tally::Approval {
+                                        result: TallyResultLib::new(p.options().clone()).into(),
                                     },
+                                    PayloadType::Private | PayloadType::PrivateQuadratic => {
+                                        Tally::Private {
+                                            state: PrivateTallyState::Decrypted {
+                                                result: TallyResultLib::new(p.options().clone())
+                                                    .into(),
+                                            },
+                                        }
+                                    }
                                 },
                                 votes_cast: 0,
                             })